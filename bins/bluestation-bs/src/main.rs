@@ -7,15 +7,22 @@ use tetra_config::bluestation::{PhyBackend, SharedConfig, StackMode, parsing};
 use tetra_core::{TdmaTime, debug};
 use tetra_entities::MessageRouter;
 use tetra_entities::brew::entity::BrewEntity;
+use tetra_entities::capture::pdu_capture::PduCapture;
+use tetra_entities::mgmt::entity::MgmtEntity;
 use tetra_entities::{
-    cmce::cmce_bs::CmceBs,
+    cmce::{cmce_bs::CmceBs, cmce_ms::CmceMs},
     llc::llc_bs_ms::Llc,
-    lmac::lmac_bs::LmacBs,
-    mle::mle_bs::MleBs,
-    mm::mm_bs::MmBs,
-    phy::{components::soapy_dev::RxTxDevSoapySdr, phy_bs::PhyBs},
-    sndcp::sndcp_bs::Sndcp,
-    umac::umac_bs::UmacBs,
+    lmac::{lmac_bs::LmacBs, lmac_mon::LmacMon, lmac_ms::LmacMs},
+    mle::{mle_bs::MleBs, mle_ms::MleMs},
+    mm::{mm_bs::MmBs, mm_ms::MmMs},
+    phy::{
+        components::{file_io::RxTxDevFile, soapy_dev::RxTxDevSoapySdr},
+        phy_bs::PhyBs,
+        phy_mon::PhyMon,
+        phy_ms::PhyMs,
+    },
+    sndcp::{sndcp_bs::Sndcp, tun::TunDevice},
+    umac::{umac_bs::UmacBs, umac_mon::UmacMon, umac_ms::UmacMs},
 };
 
 /// Load configuration file
@@ -40,6 +47,11 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
             let phy = PhyBs::new(cfg.clone(), rxdev);
             router.register_entity(Box::new(phy));
         }
+        PhyBackend::File => {
+            let rxdev = RxTxDevFile::new(cfg);
+            let phy = PhyBs::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
         _ => {
             panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
         }
@@ -51,7 +63,12 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
     let llc = Llc::new(cfg.clone());
     let mle = MleBs::new(cfg.clone());
     let mm = MmBs::new(cfg.clone());
-    let sndcp = Sndcp::new(cfg.clone());
+    let mut sndcp = Sndcp::new(cfg.clone());
+    if let Some(sndcp_cfg) = &cfg.config().sndcp {
+        let tun = TunDevice::open(sndcp_cfg).unwrap_or_else(|e| panic!("Failed to open TUN device {}: {}", sndcp_cfg.tun_name, e));
+        sndcp.set_packet_io(Box::new(tun));
+        eprintln!(" -> SNDCP packet data enabled on {}", sndcp_cfg.tun_name);
+    }
     let cmce = CmceBs::new(cfg.clone());
     router.register_entity(Box::new(lmac));
     router.register_entity(Box::new(umac));
@@ -68,12 +85,90 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
         eprintln!(" -> Brew/TetraPack integration enabled");
     }
 
+    // Register management API entity if enabled
+    if let Some(mgmt_cfg) = &cfg.config().mgmt {
+        let mgmt_entity = MgmtEntity::new(cfg.clone());
+        router.register_entity(Box::new(mgmt_entity));
+        eprintln!(" -> Management API listening on {}", mgmt_cfg.socket_path);
+    }
+
     // Init network time
     router.set_dl_time(TdmaTime::default());
 
     router
 }
 
+/// Start passive monitor stack, which decodes the downlink and uplink of a cell without transmitting
+fn build_mon_stack(cfg: &mut SharedConfig) -> MessageRouter {
+    let mut router = MessageRouter::new(cfg.clone());
+
+    match cfg.config().phy_io.backend {
+        PhyBackend::SoapySdr => {
+            let rxdev = RxTxDevSoapySdr::new(cfg);
+            let phy = PhyMon::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        PhyBackend::File => {
+            let rxdev = RxTxDevFile::new(cfg);
+            let phy = PhyMon::new(cfg.clone(), rxdev);
+            router.register_entity(Box::new(phy));
+        }
+        _ => {
+            panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
+        }
+    }
+
+    // Higher layers are decoded statelessly by the Umac, so no further entities are needed
+    let lmac = LmacMon::new(cfg.clone());
+    let umac = UmacMon::new(cfg.clone());
+    router.register_entity(Box::new(lmac));
+    router.register_entity(Box::new(umac));
+
+    // Timestamps are relative until the first SYNC burst is decoded
+    router.set_dl_time(TdmaTime::default());
+
+    router
+}
+
+/// Start mobile station stack, which syncs to a cell, registers and attaches to the configured groups
+fn build_ms_stack(cfg: &mut SharedConfig) -> MessageRouter {
+    let mut router = MessageRouter::new(cfg.clone());
+
+    match cfg.config().phy_io.backend {
+        PhyBackend::SoapySdr => {
+            let rxtxdev = RxTxDevSoapySdr::new(cfg);
+            let phy = PhyMs::new(cfg.clone(), rxtxdev);
+            router.register_entity(Box::new(phy));
+        }
+        PhyBackend::File => {
+            let rxtxdev = RxTxDevFile::new(cfg);
+            let phy = PhyMs::new(cfg.clone(), rxtxdev);
+            router.register_entity(Box::new(phy));
+        }
+        _ => {
+            panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
+        }
+    }
+
+    let lmac = LmacMs::new(cfg.clone());
+    let umac = UmacMs::new(cfg.clone());
+    let llc = Llc::new(cfg.clone());
+    let mle = MleMs::new(cfg.clone());
+    let mm = MmMs::new(cfg.clone());
+    let cmce = CmceMs::new(cfg.clone());
+    router.register_entity(Box::new(lmac));
+    router.register_entity(Box::new(umac));
+    router.register_entity(Box::new(llc));
+    router.register_entity(Box::new(mle));
+    router.register_entity(Box::new(mm));
+    router.register_entity(Box::new(cmce));
+
+    // Timestamps are relative until the first SYNC burst is decoded
+    router.set_dl_time(TdmaTime::default());
+
+    router
+}
+
 #[derive(Parser, Debug)]
 #[command(
     author,
@@ -101,15 +196,22 @@ fn main() {
     let _log_guard = debug::setup_logging_default(cfg.config().debug_log.clone());
 
     let mut router = match cfg.config().stack_mode {
-        StackMode::Mon => {
-            unimplemented!("Monitor mode is not implemented");
-        }
-        StackMode::Ms => {
-            unimplemented!("MS mode is not implemented");
-        }
+        StackMode::Mon => build_mon_stack(&mut cfg),
+        StackMode::Ms => build_ms_stack(&mut cfg),
         StackMode::Bs => build_bs_stack(&mut cfg),
     };
 
+    if let Some(path) = cfg.config().capture_file.clone() {
+        match PduCapture::create(&path, &cfg.config()) {
+            Ok(capture) => router.set_capture(capture),
+            Err(e) => {
+                eprintln!("Failed to create capture file {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        eprintln!(" -> Capturing to {}", path);
+    }
+
     // Set up Ctrl+C handler for graceful shutdown
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
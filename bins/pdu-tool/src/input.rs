@@ -0,0 +1,94 @@
+use tetra_core::{BitBuffer, Direction};
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+/// Parses a PDU given as bitstring, or as hex when prefixed by 0x or containing other digits than 0 and 1.
+/// Each hex digit is 4 bits, so hex input covers PDUs of any multiple of 4 bits.
+pub fn parse_pdu(s: &str, force_hex: bool) -> Result<BitBuffer, String> {
+    let (hex, force_hex) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, true),
+        None => (s, force_hex),
+    };
+    if hex.is_empty() {
+        return Err("empty pdu".to_string());
+    }
+    if !force_hex && hex.bytes().all(|c| c == b'0' || c == b'1') {
+        return Ok(BitBuffer::from_bitstr(hex));
+    }
+
+    let mut bits = String::with_capacity(hex.len() * 4);
+    for c in hex.chars() {
+        let Some(digit) = c.to_digit(16) else {
+            return Err(format!("invalid hex digit '{}'", c));
+        };
+        bits.push_str(&format!("{:04b}", digit));
+    }
+    Ok(BitBuffer::from_bitstr(&bits))
+}
+
+pub fn parse_direction(s: &str) -> Option<Direction> {
+    match s.to_lowercase().as_str() {
+        "ul" | "uplink" => Some(Direction::Ul),
+        "dl" | "downlink" => Some(Direction::Dl),
+        _ => None,
+    }
+}
+
+pub fn parse_channel(s: &str) -> Option<LogicalChannel> {
+    match s.to_lowercase().as_str() {
+        "schf" | "sch_f" | "sch/f" => Some(LogicalChannel::SchF),
+        "schhu" | "sch_hu" | "sch/hu" => Some(LogicalChannel::SchHu),
+        "schhd" | "sch_hd" | "sch/hd" => Some(LogicalChannel::SchHd),
+        "stch" => Some(LogicalChannel::Stch),
+        "bnch" => Some(LogicalChannel::Bnch),
+        "bsch" => Some(LogicalChannel::Bsch),
+        "aach" => Some(LogicalChannel::Aach),
+        _ => None,
+    }
+}
+
+/// A line of a batch file: the PDU, optionally preceded by a direction and/or logical channel
+/// overriding the ones given on the command line. Empty lines and comments starting with # yield None.
+pub fn parse_batch_line(line: &str) -> Option<Result<(Option<Direction>, Option<LogicalChannel>, &str), String>> {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+    let pdu = tokens.pop()?;
+
+    let mut direction = None;
+    let mut channel = None;
+    for token in tokens {
+        if let Some(d) = parse_direction(token) {
+            direction = Some(d);
+        } else if let Some(c) = parse_channel(token) {
+            channel = Some(c);
+        } else {
+            return Some(Err(format!("unknown direction or channel '{}'", token)));
+        }
+    }
+    Some(Ok((direction, channel, pdu)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pdu() {
+        assert_eq!(parse_pdu("0110", false).unwrap().to_bitstr(), "0110");
+        assert_eq!(parse_pdu("0110", true).unwrap().to_bitstr(), "0000000100010000");
+        assert_eq!(parse_pdu("0xA5", false).unwrap().to_bitstr(), "10100101");
+        assert_eq!(parse_pdu("2f1", false).unwrap().to_bitstr(), "001011110001");
+        assert!(parse_pdu("0x", false).is_err());
+        assert!(parse_pdu("12g", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_batch_line() {
+        assert!(parse_batch_line("  # comment").is_none());
+        assert_eq!(parse_batch_line("0x1f").unwrap().unwrap(), (None, None, "0x1f"));
+        assert_eq!(
+            parse_batch_line("dl sch/hd 0110 # trailing").unwrap().unwrap(),
+            (Some(Direction::Dl), Some(LogicalChannel::SchHd), "0110")
+        );
+        assert!(parse_batch_line("up 0110").unwrap().is_err());
+    }
+}
@@ -1,91 +1,191 @@
+use std::io::{BufRead, BufReader};
+
 use clap::Parser;
+use serde_json::{Value, json};
 
-use tetra_core::BitBuffer;
+use tetra_core::{BitBuffer, Direction};
+use tetra_pdus::decode::{self, DecodedPdu};
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 
-mod entities;
-use entities::umac::UmacParser;
+mod input;
 
 #[derive(Parser, Debug)]
 #[command(
     author,
     version,
     about = "TETRA Raw PDU Decoder",
-    long_about = "Decodes a raw bitstring as a PDU for the specified SAP and destination component"
+    long_about = "Decodes raw PDUs through all layers, from the UMAC, LLC or MLE up to MM, CMCE and SNDCP"
 )]
 struct Args {
     /// Direction: uplink or downlink
     #[arg(help = "Direction: [ ul | dl ]")]
     direction: String,
 
-    /// SAP (Service Access Point) name
-    #[arg(help = "SAP name: [ tmv ]")]
-    sap: String,
-
-    /// Destination component name
-    #[arg(help = "Destination component: [ umac ]")]
-    destination: String,
+    /// Layer receiving the PDU
+    #[arg(help = "Layer receiving the PDU: [ umac | llc | mle ]. umac takes a MAC block, llc a TM-SDU, mle a TL-SDU")]
+    layer: String,
 
-    /// Raw bitstring to decode
-    #[arg(help = "Raw bitstring (binary representation) to parse as PDU")]
-    bitstring: String,
+    /// PDU to decode
+    #[arg(help = "PDU as bitstring, or as hex (prefixed by 0x, if it only has digits 0 and 1)")]
+    pdu: Option<String>,
 
     #[arg(
         short = 'c',
         long = "channel",
         default_value = "schf",
-        help = "Logical channel (for tmv sap): [ schf | schhu | schhd | stch | bnch | bsch | aach ]"
+        help = "Logical channel (for umac): [ schf | schhu | schhd | stch | bnch | bsch | aach ]"
     )]
     channel: String,
+
+    #[arg(
+        short = 'f',
+        long = "file",
+        help = "Read PDUs from a file, or stdin if -, one per line. A line may start with a direction and logical channel overriding the defaults. # starts a comment."
+    )]
+    file: Option<String>,
+
+    #[arg(short = 'x', long = "hex", help = "Treat all PDUs as hex")]
+    hex: bool,
+
+    #[arg(short = 'j', long = "json", help = "Output one JSON object per PDU")]
+    json: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Layer {
+    Umac,
+    Llc,
+    Mle,
+}
+
+/// Decodes a PDU input to layer, through all layers above it
+fn decode_pdu(pdu: &mut BitBuffer, layer: Layer, direction: Direction, channel: LogicalChannel) -> Vec<DecodedPdu> {
+    match layer {
+        Layer::Umac => decode::decode_mac_block(pdu, channel, direction),
+        Layer::Llc => decode::decode_tm_sdu(pdu, direction),
+        Layer::Mle => decode::decode_tl_sdu(pdu, direction),
+    }
+}
+
+/// Prints the outcome of decoding the PDU given by input, or the reason it could not be decoded
+fn print_result(args: &Args, input: &str, direction: Direction, channel: LogicalChannel, result: Result<Vec<DecodedPdu>, String>) {
+    if args.json {
+        let mut obj = json!({
+            "input": input,
+            "direction": format!("{:?}", direction),
+            "layer": args.layer.to_lowercase(),
+        });
+        if args.layer.eq_ignore_ascii_case("umac") {
+            obj["channel"] = json!(format!("{:?}", channel));
+        }
+        match result {
+            Ok(pdus) => obj["pdus"] = Value::Array(pdus.iter().map(pdu_to_json).collect()),
+            Err(e) => obj["error"] = json!(e),
+        }
+        println!("{}", obj);
+        return;
+    }
+
+    if args.layer.eq_ignore_ascii_case("umac") {
+        println!("=== {:?} umac {:?}: {}", direction, channel, input);
+    } else {
+        println!("=== {:?} {}: {}", direction, args.layer.to_lowercase(), input);
+    }
+    match result {
+        Ok(pdus) => {
+            for pdu in pdus {
+                match pdu.contents {
+                    Ok(contents) => println!("{:?} {}: {}", pdu.layer, pdu.name, contents),
+                    Err(e) => println!("[!] {:?} {}: {}", pdu.layer, pdu.name, e),
+                }
+            }
+        }
+        Err(e) => println!("[!] {}", e),
+    }
+    println!();
+}
+
+fn pdu_to_json(pdu: &DecodedPdu) -> Value {
+    let mut obj = json!({
+        "layer": format!("{:?}", pdu.layer),
+        "name": pdu.name,
+        "ok": pdu.contents.is_ok(),
+    });
+    match &pdu.contents {
+        Ok(contents) => obj["contents"] = json!(contents),
+        Err(e) => obj["error"] = json!(e),
+    }
+    obj
 }
 
 fn main() {
     eprintln!("[+] TETRA PDU Decoding tool");
     eprintln!("    Wouter Bokslag / Midnight Blue");
-    eprintln!(" *  This tool is a MESS and is for testing only  *");
-    eprintln!(" *  There be bugs..                              *");
 
     let args = Args::parse();
 
-    let logical_channel = match args.channel.to_lowercase().as_str() {
-        "schf" | "sch_f" | "sch/f" => LogicalChannel::SchF,
-        "schhu" | "sch_hu" | "sch/hu" => LogicalChannel::SchHu,
-        "schhd" | "sch_hd" | "sch/hd" => LogicalChannel::SchHd,
-        "stch" => LogicalChannel::Stch,
-        "bnch" => LogicalChannel::Bnch,
-        "bsch" => LogicalChannel::Bsch,
-        "aach" => LogicalChannel::Aach,
-        _ => {
-            eprintln!(
-                "Error: Unsupported logical channel '{}'. Use: schf, schhu, schhd, stch, bnch, bsch, aach",
-                args.channel
-            );
-            std::process::exit(1);
-        }
+    let Some(channel) = input::parse_channel(&args.channel) else {
+        eprintln!(
+            "Error: Unsupported logical channel '{}'. Use: schf, schhu, schhd, stch, bnch, bsch, aach",
+            args.channel
+        );
+        std::process::exit(1);
+    };
+
+    let Some(direction) = input::parse_direction(&args.direction) else {
+        eprintln!("Error: Unsupported direction '{}'. Use: ul, dl", args.direction);
+        std::process::exit(1);
     };
 
-    let is_downlink = match args.direction.to_lowercase().as_str() {
-        "ul" | "uplink" => false,
-        "dl" | "downlink" => true,
+    let layer = match args.layer.to_lowercase().as_str() {
+        "umac" => Layer::Umac,
+        "llc" => Layer::Llc,
+        "mle" => Layer::Mle,
         _ => {
-            eprintln!("Error: Unsupported direction '{}'. Use: ul, dl", args.direction);
+            eprintln!("Error: Unsupported layer '{}'. Use: umac, llc, mle", args.layer);
             std::process::exit(1);
         }
     };
 
-    match (args.sap.to_lowercase().as_str(), args.destination.to_lowercase().as_str()) {
-        ("tmv", "umac") => {
-            let pdu = BitBuffer::from_bitstr(args.bitstring.as_str());
-            if is_downlink {
-                UmacParser::parse_dl(pdu, logical_channel);
+    let decode_input = |pdu: &str, direction: Direction, channel: LogicalChannel| {
+        let result = input::parse_pdu(pdu, args.hex).map(|mut buf| decode_pdu(&mut buf, layer, direction, channel));
+        print_result(&args, pdu, direction, channel, result);
+    };
+
+    match (&args.pdu, &args.file) {
+        (Some(pdu), None) => decode_input(pdu, direction, channel),
+        (None, Some(path)) => {
+            let reader: Box<dyn BufRead> = if path == "-" {
+                Box::new(BufReader::new(std::io::stdin()))
             } else {
-                UmacParser::parse_ul(pdu, logical_channel);
+                match std::fs::File::open(path) {
+                    Ok(f) => Box::new(BufReader::new(f)),
+                    Err(e) => {
+                        eprintln!("Error: Failed to open {}: {}", path, e);
+                        std::process::exit(1);
+                    }
+                }
+            };
+            for (num, line) in reader.lines().enumerate() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(e) => {
+                        eprintln!("Error: Failed to read {}: {}", path, e);
+                        std::process::exit(1);
+                    }
+                };
+                match input::parse_batch_line(&line) {
+                    None => {}
+                    Some(Ok((line_direction, line_channel, pdu))) => {
+                        decode_input(pdu, line_direction.unwrap_or(direction), line_channel.unwrap_or(channel))
+                    }
+                    Some(Err(e)) => print_result(&args, line.trim(), direction, channel, Err(format!("line {}: {}", num + 1, e))),
+                }
             }
         }
         _ => {
-            eprintln!("Error: Unsupported SAP '{}' or destination '{}'", args.sap, args.destination);
-            eprintln!("Supported: tmv umac");
+            eprintln!("Error: Give either a PDU or a file to read PDUs from");
             std::process::exit(1);
         }
-    };
+    }
 }
@@ -2,9 +2,13 @@ use serde::Deserialize;
 use std::sync::{Arc, RwLock};
 use tetra_core::freqs::FreqInfo;
 
-use crate::bluestation::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackState};
+use crate::bluestation::{
+    CfgAccessPolicy, CfgCallQueue, CfgCellInfo, CfgEmergency, CfgGroupAssignment, CfgMgmt, CfgMsInfo, CfgNeighbourCell, CfgNetInfo,
+    CfgPhyIo, CfgRegistry, CfgSecurity, CfgSndcp, PhyBackend, SecurityClass, StackState,
+};
 
 use super::sec_brew::CfgBrew;
+use super::sec_neighbour::MAX_NEIGHBOUR_CELLS;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -18,6 +22,8 @@ pub enum StackMode {
 pub struct StackConfig {
     pub stack_mode: StackMode,
     pub debug_log: Option<String>,
+    /// pcapng file receiving every MAC block and TM-SDU as GSMTAP, for analysis in Wireshark
+    pub capture_file: Option<String>,
 
     pub phy_io: CfgPhyIo,
     pub net: CfgNetInfo,
@@ -25,6 +31,36 @@ pub struct StackConfig {
 
     /// Brew protocol (TetraPack/BrandMeister) configuration
     pub brew: Option<CfgBrew>,
+
+    /// Terminal identity, required in MS stack mode
+    pub ms: Option<CfgMsInfo>,
+
+    /// Authentication of registering terminals and air interface encryption, BS stack mode only
+    pub security: Option<CfgSecurity>,
+
+    /// SNDCP packet data, BS stack mode only
+    pub sndcp: Option<CfgSndcp>,
+
+    /// Runtime management API on a local socket, BS stack mode only
+    pub mgmt: Option<CfgMgmt>,
+
+    /// On-disk subscriber registry surviving restarts, BS stack mode only
+    pub registry: Option<CfgRegistry>,
+
+    /// Queuing and pre-emption of group calls when no traffic timeslot is free, BS stack mode only
+    pub call_queue: Option<CfgCallQueue>,
+
+    /// Emergency call handling, defaults apply without it. BS stack mode only
+    pub emergency: Option<CfgEmergency>,
+
+    /// Registration and group attach restrictions, any radio is accepted without it. BS stack mode only
+    pub access_policy: Option<CfgAccessPolicy>,
+
+    /// Groups attached to terminals by the SwMI on registration, BS stack mode only
+    pub group_assignment: Option<CfgGroupAssignment>,
+
+    /// Neighbour cells advertised in D-NWRK-BROADCAST, BS stack mode only
+    pub neighbour_cells: Vec<CfgNeighbourCell>,
 }
 
 impl StackConfig {
@@ -37,6 +73,28 @@ impl StackConfig {
                     return Err("soapysdr configuration must be provided for Soapysdr backend");
                 };
             }
+            PhyBackend::File => {
+                // Filter bank bin spacing is 500 Hz and the FFT size has to be a multiple of 4
+                if self.phy_io.sample_rate.is_some_and(|fs| fs <= 0.0 || fs % 2000.0 != 0.0) {
+                    return Err("phy_io sample_rate must be a positive multiple of 2000 Hz");
+                }
+                if self.phy_io.rx_silence_tail_secs.is_some_and(|secs| secs < 0.0) {
+                    return Err("phy_io rx_silence_tail_secs must not be negative");
+                }
+                // Carrier frequencies are derived from the cell info
+                if FreqInfo::from_components(
+                    self.cell.freq_band,
+                    self.cell.main_carrier,
+                    self.cell.freq_offset_hz,
+                    self.cell.reverse_operation,
+                    self.cell.duplex_spacing_id,
+                    self.cell.custom_duplex_spacing,
+                )
+                .is_err()
+                {
+                    return Err("Invalid cell info frequency settings");
+                }
+            }
             PhyBackend::None => {} // For testing
             PhyBackend::Undefined => {
                 return Err("phy_io backend must be defined");
@@ -75,6 +133,71 @@ impl StackConfig {
             };
         }
 
+        if self.stack_mode == StackMode::Ms && self.ms.is_none() {
+            return Err("ms_info must be provided for Ms stack mode");
+        }
+
+        if let Some(ref security) = self.security
+            && security.security_class != SecurityClass::Class1
+            && !self.cell.aie_service
+        {
+            return Err("cell.aie_service must be enabled for security class 2 or 3");
+        }
+
+        if self.sndcp.is_some() && !self.cell.sndcp_service {
+            return Err("cell.sndcp_service must be enabled for packet data");
+        }
+
+        if !self.cell.secondary_carriers.is_empty() {
+            if self.stack_mode != StackMode::Bs {
+                return Err("cell.secondary_carriers is only supported in Bs stack mode");
+            }
+            for (i, &carrier) in self.cell.secondary_carriers.iter().enumerate() {
+                if carrier == self.cell.main_carrier || self.cell.secondary_carriers[..i].contains(&carrier) {
+                    return Err("cell.secondary_carriers must be distinct from each other and from the main carrier");
+                }
+                if self.cell.carrier_freq_info(carrier).is_err() {
+                    return Err("Invalid frequency settings for a secondary carrier");
+                }
+            }
+        }
+
+        if self.access_policy.is_some() && self.stack_mode != StackMode::Bs {
+            return Err("access_policy is only supported in Bs stack mode");
+        }
+        if self.group_assignment.is_some() && self.stack_mode != StackMode::Bs {
+            return Err("group_assignment is only supported in Bs stack mode");
+        }
+
+        if !self.neighbour_cells.is_empty() {
+            if self.stack_mode != StackMode::Bs {
+                return Err("neighbour_cells are only supported in Bs stack mode");
+            }
+            if self.neighbour_cells.len() > MAX_NEIGHBOUR_CELLS {
+                return Err("At most 7 neighbour_cells can be advertised");
+            }
+            for (i, neighbour) in self.neighbour_cells.iter().enumerate() {
+                if self.neighbour_cells[..i]
+                    .iter()
+                    .any(|n| n.cell_identifier == neighbour.cell_identifier)
+                {
+                    return Err("neighbour_cells cell_identifier values must be distinct");
+                }
+                if FreqInfo::from_components(
+                    neighbour.freq_band.unwrap_or(self.cell.freq_band),
+                    neighbour.main_carrier,
+                    neighbour.freq_offset_hz.unwrap_or(self.cell.freq_offset_hz),
+                    neighbour.reverse_operation.unwrap_or(self.cell.reverse_operation),
+                    neighbour.duplex_spacing_id.unwrap_or(self.cell.duplex_spacing_id),
+                    None,
+                )
+                .is_err()
+                {
+                    return Err("Invalid frequency settings for a neighbour cell");
+                }
+            }
+        }
+
         // Validate timezone if configured
         if let Some(ref tz) = self.cell.timezone {
             if tz.parse::<chrono_tz::Tz>().is_err() {
@@ -100,13 +223,15 @@ impl SharedConfig {
         Self::from_parts(cfg, StackState::default())
     }
 
-    pub fn from_parts(cfg: StackConfig, state: StackState) -> Self {
+    pub fn from_parts(cfg: StackConfig, mut state: StackState) -> Self {
         // Check config for validity before returning the SharedConfig object
         match cfg.validate() {
             Ok(_) => {}
             Err(e) => panic!("Invalid stack configuration: {}", e),
         }
 
+        state.timeslot_alloc.set_secondary_carriers(cfg.cell.secondary_carriers.len());
+
         Self {
             cfg: Arc::new(cfg),
             state: Arc::new(RwLock::new(state)),
@@ -0,0 +1,110 @@
+//! On-disk list of disabled terminals, keeping a disabled ISSI refused across restarts of the BS
+//!
+//! The list is a text file with one ISSI per line: `<issi> <temporary|permanent>`.
+//! Like the subscriber journal, it is rewritten as a whole through a temporary file.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use crate::bluestation::{DisableKind, SubscriberRegistry};
+
+const DISABLED_LIST_HEADER: &str = "# bluestation disabled terminals v1";
+
+pub fn render_disabled_list(registry: &SubscriberRegistry) -> String {
+    let mut entries: Vec<(u32, DisableKind)> = registry.iter_disabled().collect();
+    entries.sort_by_key(|&(issi, _)| issi);
+    let mut text = format!("{}\n", DISABLED_LIST_HEADER);
+    for (issi, kind) in entries {
+        let kind = match kind {
+            DisableKind::Temporary => "temporary",
+            DisableKind::Permanent => "permanent",
+        };
+        text.push_str(&format!("{} {}\n", issi, kind));
+    }
+    text
+}
+
+/// Parses a disabled list, failing on the first malformed line
+pub fn parse_disabled_list(text: &str) -> Result<Vec<(u32, DisableKind)>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(num, line)| {
+            let mut fields = line.split_whitespace();
+            let issi = fields.next().and_then(|f| f.parse().ok());
+            let kind = match fields.next() {
+                Some("temporary") => Some(DisableKind::Temporary),
+                Some("permanent") => Some(DisableKind::Permanent),
+                _ => None,
+            };
+            match (issi, kind, fields.next()) {
+                (Some(issi), Some(kind), None) => Ok((issi, kind)),
+                _ => Err(format!("malformed disabled list line {}: {}", num + 1, line)),
+            }
+        })
+        .collect()
+}
+
+/// Disabled terminals file, written whenever an ISSI is disabled or enabled and reloaded at startup
+pub struct DisabledList {
+    path: PathBuf,
+}
+
+impl DisabledList {
+    pub fn new(path: &str) -> Self {
+        Self { path: PathBuf::from(path) }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the list. A missing file is treated as empty.
+    pub fn load(&self) -> io::Result<Vec<(u32, DisableKind)>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        parse_disabled_list(&text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Writes the disabled ISSIs of the registry to the list
+    pub fn save(&self, registry: &SubscriberRegistry) -> io::Result<()> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, render_disabled_list(registry))?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_list_roundtrip() {
+        let path = std::env::temp_dir().join(format!("bluestation-disabled-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let list = DisabledList::new(&path.to_string_lossy());
+        assert!(list.load().unwrap().is_empty());
+
+        let mut reg = SubscriberRegistry::new();
+        reg.disable(1002, DisableKind::Permanent);
+        reg.disable(1001, DisableKind::Temporary);
+        list.save(&reg).unwrap();
+        assert_eq!(
+            list.load().unwrap(),
+            vec![(1001, DisableKind::Temporary), (1002, DisableKind::Permanent)]
+        );
+    }
+
+    #[test]
+    fn test_parse_disabled_list_malformed() {
+        assert_eq!(parse_disabled_list("# comment\n\n1001 permanent\n").unwrap().len(), 1);
+        for bad in ["1001", "1001 stunned", "x temporary", "1001 temporary extra"] {
+            assert!(parse_disabled_list(bad).is_err(), "accepted {}", bad);
+        }
+    }
+}
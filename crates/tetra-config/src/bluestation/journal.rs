@@ -0,0 +1,240 @@
+//! On-disk snapshot of the subscriber registry, letting registrations survive a restart of the BS
+//!
+//! The journal is a text file with one subscriber per line:
+//! `<issi> <last seen, seconds since the Unix epoch> <clear|sck> <comma separated GSSIs, or ->`.
+//! It is rewritten as a whole through a temporary file, so a crash never leaves a partial journal behind.
+
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::bluestation::{AieKeyClass, CfgRegistry, SubscriberRegistry};
+
+const JOURNAL_HEADER: &str = "# bluestation subscriber journal v1";
+
+/// A registered subscriber as stored in the journal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub issi: u32,
+    /// Seconds since the Unix epoch
+    pub last_seen: u64,
+    /// Air interface encryption with the static cipher key was agreed
+    pub sck: bool,
+    /// Attached GSSIs, sorted
+    pub groups: Vec<u32>,
+}
+
+impl JournalEntry {
+    /// Journal entries for all subscribers in the registry, sorted by ISSI.
+    /// Subscribers using a derived cipher key are left out: the DCK is not written to disk,
+    /// so such an MS has to authenticate again after a restart.
+    pub fn from_registry(registry: &SubscriberRegistry) -> Vec<Self> {
+        let mut entries: Vec<Self> = registry
+            .iter()
+            .filter(|s| !matches!(s.aie, Some(AieKeyClass::Dck(_))))
+            .map(|s| {
+                let mut groups: Vec<u32> = s.attached_groups.iter().copied().collect();
+                groups.sort_unstable();
+                Self {
+                    issi: s.issi,
+                    last_seen: s.last_seen.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+                    sck: s.aie == Some(AieKeyClass::Sck),
+                    groups,
+                }
+            })
+            .collect();
+        entries.sort_by_key(|e| e.issi);
+        entries
+    }
+
+    pub fn last_seen_time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.last_seen)
+    }
+
+    fn to_line(&self) -> String {
+        let groups = if self.groups.is_empty() {
+            "-".to_string()
+        } else {
+            self.groups.iter().map(|g| g.to_string()).collect::<Vec<_>>().join(",")
+        };
+        let aie = if self.sck { "sck" } else { "clear" };
+        format!("{} {} {} {}", self.issi, self.last_seen, aie, groups)
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let issi = fields.next()?.parse().ok()?;
+        let last_seen = fields.next()?.parse().ok()?;
+        let sck = match fields.next()? {
+            "clear" => false,
+            "sck" => true,
+            _ => return None,
+        };
+        let groups = match fields.next()? {
+            "-" => Vec::new(),
+            list => list.split(',').map(|g| g.parse().ok()).collect::<Option<Vec<u32>>>()?,
+        };
+        if fields.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            issi,
+            last_seen,
+            sck,
+            groups,
+        })
+    }
+}
+
+pub fn render_journal(entries: &[JournalEntry]) -> String {
+    let mut text = format!("{}\n", JOURNAL_HEADER);
+    for entry in entries {
+        text.push_str(&entry.to_line());
+        text.push('\n');
+    }
+    text
+}
+
+/// Parses a journal, failing on the first malformed line
+pub fn parse_journal(text: &str) -> Result<Vec<JournalEntry>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(num, line)| JournalEntry::from_line(line).ok_or_else(|| format!("malformed journal line {}: {}", num + 1, line)))
+        .collect()
+}
+
+/// Subscriber journal file, snapshotting the registry and reloading it at startup
+pub struct SubscriberJournal {
+    path: PathBuf,
+    ttl: Duration,
+    /// Contents last written, to skip rewriting an unchanged journal
+    last_written: Option<String>,
+}
+
+impl SubscriberJournal {
+    pub fn new(cfg: &CfgRegistry) -> Self {
+        Self {
+            path: PathBuf::from(&cfg.journal_path),
+            ttl: cfg.ttl,
+            last_written: None,
+        }
+    }
+
+    /// Reads the journal, dropping entries not seen within the TTL before now.
+    /// This is the only place the TTL applies: registrations are not expired while the BS runs.
+    /// A missing journal is treated as empty.
+    pub fn load(&self, now: SystemTime) -> io::Result<Vec<JournalEntry>> {
+        let text = match fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let entries = parse_journal(&text).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        Ok(entries
+            .into_iter()
+            .filter(|e| now.duration_since(e.last_seen_time()).map_or(true, |age| age <= self.ttl))
+            .collect())
+    }
+
+    /// Moves an unreadable journal out of the way, so the next snapshot does not overwrite it.
+    /// Returns where it was moved to.
+    pub fn set_aside(&self) -> io::Result<PathBuf> {
+        let mut bad_path = self.path.clone().into_os_string();
+        bad_path.push(".bad");
+        let bad_path = PathBuf::from(bad_path);
+        fs::rename(&self.path, &bad_path)?;
+        Ok(bad_path)
+    }
+
+    /// Writes the registry to the journal if it changed since the last snapshot.
+    /// Returns whether the journal was written.
+    pub fn snapshot(&mut self, registry: &SubscriberRegistry) -> io::Result<bool> {
+        let text = render_journal(&JournalEntry::from_registry(registry));
+        if self.last_written.as_ref() == Some(&text) {
+            return Ok(false);
+        }
+
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, &text)?;
+        fs::rename(&tmp_path, &self.path)?;
+        self.last_written = Some(text);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal(name: &str, ttl: Duration) -> SubscriberJournal {
+        let path = std::env::temp_dir().join(format!("bluestation-journal-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        SubscriberJournal::new(&CfgRegistry {
+            journal_path: path.to_string_lossy().into_owned(),
+            ttl,
+            disabled_path: None,
+        })
+    }
+
+    #[test]
+    fn test_journal_roundtrip() {
+        let mut reg = SubscriberRegistry::new();
+        reg.register(1002);
+        reg.affiliate(1002, 91);
+        reg.affiliate(1002, 9);
+        reg.register(1001);
+        reg.get_subscriber_mut(1001).aie = Some(AieKeyClass::Sck);
+        reg.register(1003);
+        reg.get_subscriber_mut(1003).aie = Some(AieKeyClass::Dck(0x1234));
+
+        let mut journal = journal("roundtrip", Duration::from_secs(3600));
+        assert!(journal.load(SystemTime::now()).unwrap().is_empty());
+        assert!(journal.snapshot(&reg).unwrap());
+        assert!(!journal.snapshot(&reg).unwrap());
+
+        let entries = journal.load(SystemTime::now()).unwrap();
+        assert_eq!(entries, JournalEntry::from_registry(&reg));
+        let summary: Vec<(u32, bool, Vec<u32>)> = entries.into_iter().map(|e| (e.issi, e.sck, e.groups)).collect();
+        assert_eq!(summary, vec![(1001, true, vec![]), (1002, false, vec![9, 91])]);
+    }
+
+    #[test]
+    fn test_journal_ttl() {
+        let mut reg = SubscriberRegistry::new();
+        reg.register(1001);
+        let mut journal = journal("ttl", Duration::from_secs(60));
+        journal.snapshot(&reg).unwrap();
+
+        assert_eq!(journal.load(SystemTime::now()).unwrap().len(), 1);
+        assert!(journal.load(SystemTime::now() + Duration::from_secs(120)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_journal_set_aside() {
+        let journal = journal("aside", Duration::from_secs(60));
+        fs::write(&journal.path, "garbage\n").unwrap();
+        assert!(journal.load(SystemTime::now()).is_err());
+
+        let bad_path = journal.set_aside().unwrap();
+        assert_eq!(fs::read_to_string(&bad_path).unwrap(), "garbage\n");
+        assert!(journal.load(SystemTime::now()).unwrap().is_empty());
+        let _ = fs::remove_file(bad_path);
+    }
+
+    #[test]
+    fn test_parse_journal_malformed() {
+        assert_eq!(parse_journal("# comment\n\n1001 5 clear -\n").unwrap().len(), 1);
+        for bad in [
+            "1001 5 clear",
+            "1001 5 dck -",
+            "1001 x clear -",
+            "1001 5 clear 1,x",
+            "1001 5 clear - extra",
+        ] {
+            assert!(parse_journal(bad).is_err(), "accepted {}", bad);
+        }
+    }
+}
@@ -19,5 +19,40 @@ pub use sec_phy_soapy::*;
 pub mod sec_brew;
 pub use sec_brew::*;
 
+pub mod sec_ms;
+pub use sec_ms::*;
+
+pub mod sec_security;
+pub use sec_security::*;
+
+pub mod sec_sndcp;
+pub use sec_sndcp::*;
+
+pub mod sec_mgmt;
+pub use sec_mgmt::*;
+
+pub mod sec_registry;
+pub use sec_registry::*;
+
+pub mod sec_call_queue;
+pub use sec_call_queue::*;
+
+pub mod sec_emergency;
+pub use sec_emergency::*;
+
+pub mod sec_access_policy;
+pub use sec_access_policy::*;
+
+pub mod sec_group_assignment;
+pub use sec_group_assignment::*;
+
+pub mod sec_neighbour;
+pub use sec_neighbour::*;
+
+pub mod disabled_list;
+pub use disabled_list::*;
+pub mod journal;
+pub use journal::*;
+
 pub mod state;
 pub use state::*;
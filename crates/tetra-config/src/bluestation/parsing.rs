@@ -6,7 +6,12 @@ use std::path::Path;
 use serde::Deserialize;
 use toml::Value;
 
-use crate::bluestation::{CellInfoDto, NetInfoDto, cell_dto_to_cfg, net_dto_to_cfg};
+use crate::bluestation::{
+    AccessPolicyDto, CallQueueDto, CellInfoDto, EmergencyDto, GroupAssignmentDto, MgmtDto, MsInfoDto, NeighbourCellDto, NetInfoDto,
+    RegistryDto, SecurityDto, SndcpDto, access_policy_dto_to_cfg, call_queue_dto_to_cfg, cell_dto_to_cfg, emergency_dto_to_cfg,
+    group_assignment_dto_to_cfg, mgmt_dto_to_cfg, ms_dto_to_cfg, neighbour_cell_dto_to_cfg, net_dto_to_cfg, registry_dto_to_cfg,
+    security_dto_to_cfg, sndcp_dto_to_cfg,
+};
 
 use super::config::{SharedConfig, StackConfig, StackMode};
 use super::sec_brew::{CfgBrewDto, apply_brew_patch};
@@ -56,14 +61,105 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
         }
     }
 
+    // Optional ms section, required in MS stack mode
+    if let Some(ref ms_info) = root.ms_info {
+        if !ms_info.extra.is_empty() {
+            return Err(format!("Unrecognized fields in ms_info: {:?}", sorted_keys(&ms_info.extra)).into());
+        }
+    } else if root.stack_mode == StackMode::Ms {
+        return Err("ms_info section is required in Ms stack mode".into());
+    }
+
+    // Optional security section
+    if let Some(ref security) = root.security
+        && !security.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in security: {:?}", sorted_keys(&security.extra)).into());
+    }
+
+    // Optional sndcp section
+    if let Some(ref sndcp) = root.sndcp
+        && !sndcp.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in sndcp: {:?}", sorted_keys(&sndcp.extra)).into());
+    }
+
+    // Optional mgmt section
+    if let Some(ref mgmt) = root.mgmt
+        && !mgmt.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in mgmt: {:?}", sorted_keys(&mgmt.extra)).into());
+    }
+
+    // Optional registry section
+    if let Some(ref registry) = root.registry
+        && !registry.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in registry: {:?}", sorted_keys(&registry.extra)).into());
+    }
+
+    // Optional call_queue section
+    if let Some(ref call_queue) = root.call_queue
+        && !call_queue.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in call_queue: {:?}", sorted_keys(&call_queue.extra)).into());
+    }
+
+    // Optional emergency section
+    if let Some(ref emergency) = root.emergency
+        && !emergency.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in emergency: {:?}", sorted_keys(&emergency.extra)).into());
+    }
+
+    // Optional access_policy section
+    if let Some(ref access_policy) = root.access_policy
+        && !access_policy.extra.is_empty()
+    {
+        return Err(format!("Unrecognized fields in access_policy: {:?}", sorted_keys(&access_policy.extra)).into());
+    }
+
+    // Optional group_assignment section
+    if let Some(ref group_assignment) = root.group_assignment
+        && !group_assignment.extra.is_empty()
+    {
+        return Err(format!(
+            "Unrecognized fields in group_assignment: {:?}",
+            sorted_keys(&group_assignment.extra)
+        )
+        .into());
+    }
+
+    // Optional neighbour_cells array
+    for neighbour in &root.neighbour_cells {
+        if !neighbour.extra.is_empty() {
+            return Err(format!("Unrecognized fields in neighbour_cells: {:?}", sorted_keys(&neighbour.extra)).into());
+        }
+    }
+
     // Build config from required and optional values
     let mut cfg = StackConfig {
         stack_mode: root.stack_mode,
         debug_log: root.debug_log,
+        capture_file: root.capture_file,
         phy_io: phy_dto_to_cfg(root.phy_io),
         net: net_dto_to_cfg(root.net_info),
         cell: cell_dto_to_cfg(root.cell_info),
         brew: None,
+        ms: root.ms_info.map(ms_dto_to_cfg),
+        security: root.security.map(security_dto_to_cfg).transpose()?,
+        sndcp: root.sndcp.map(sndcp_dto_to_cfg).transpose()?,
+        mgmt: root.mgmt.map(mgmt_dto_to_cfg).transpose()?,
+        registry: root.registry.map(registry_dto_to_cfg).transpose()?,
+        call_queue: root.call_queue.map(call_queue_dto_to_cfg).transpose()?,
+        emergency: root.emergency.map(emergency_dto_to_cfg).transpose()?,
+        access_policy: root.access_policy.map(access_policy_dto_to_cfg).transpose()?,
+        group_assignment: root.group_assignment.map(group_assignment_dto_to_cfg).transpose()?,
+        neighbour_cells: root
+            .neighbour_cells
+            .into_iter()
+            .map(neighbour_cell_dto_to_cfg)
+            .collect::<Result<_, _>>()?,
     };
 
     if let Some(brew) = root.brew {
@@ -105,6 +201,7 @@ struct TomlConfigRoot {
     config_version: String,
     stack_mode: StackMode,
     debug_log: Option<String>,
+    capture_file: Option<String>,
 
     phy_io: PhyIoDto,
     net_info: NetInfoDto,
@@ -112,6 +209,27 @@ struct TomlConfigRoot {
 
     brew: Option<CfgBrewDto>,
 
+    ms_info: Option<MsInfoDto>,
+
+    security: Option<SecurityDto>,
+
+    sndcp: Option<SndcpDto>,
+
+    mgmt: Option<MgmtDto>,
+
+    registry: Option<RegistryDto>,
+
+    call_queue: Option<CallQueueDto>,
+
+    emergency: Option<EmergencyDto>,
+
+    access_policy: Option<AccessPolicyDto>,
+
+    group_assignment: Option<GroupAssignmentDto>,
+
+    #[serde(default)]
+    neighbour_cells: Vec<NeighbourCellDto>,
+
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
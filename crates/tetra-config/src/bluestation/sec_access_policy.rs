@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Which radios may register on the cell and which groups they may attach to, BS stack mode only.
+/// The default policy lets any ISSI register and attach to any group.
+#[derive(Debug, Clone, Default)]
+pub struct CfgAccessPolicy {
+    /// ISSIs allowed to register, in addition to those in allowed_issi_ranges
+    pub allowed_issis: HashSet<u32>,
+    /// Inclusive ISSI ranges allowed to register. Any ISSI may register if neither this nor allowed_issis is set.
+    pub allowed_issi_ranges: Vec<RangeInclusive<u32>>,
+    /// Inclusive SSI ranges reserved for groups. If set, these may not register as ISSI and
+    /// attaching to an SSI outside them is refused.
+    pub group_ranges: Vec<RangeInclusive<u32>>,
+    /// Maximum number of groups a single MS may be attached to
+    pub max_groups_per_ms: Option<usize>,
+    /// Restrictions for individual subscribers, keyed by ISSI
+    pub subscribers: HashMap<u32, CfgSubscriberPolicy>,
+}
+
+/// Restrictions applying to a single subscriber
+#[derive(Debug, Clone, Default)]
+pub struct CfgSubscriberPolicy {
+    /// Groups the MS may attach to, any group if None
+    pub allowed_gssis: Option<HashSet<u32>>,
+    /// Subscriber class bitmap of the MS. It may only register if it shares a class with the cell.
+    pub subscriber_class: Option<u16>,
+}
+
+impl CfgAccessPolicy {
+    /// Returns true if the ISSI is allowed to register by the ISSI lists
+    pub fn issi_allowed(&self, issi: u32) -> bool {
+        (self.allowed_issis.is_empty() && self.allowed_issi_ranges.is_empty())
+            || self.allowed_issis.contains(&issi)
+            || self.allowed_issi_ranges.iter().any(|r| r.contains(&issi))
+    }
+
+    /// Returns true if the SSI is not reserved for groups
+    pub fn is_individual(&self, ssi: u32) -> bool {
+        !self.group_ranges.iter().any(|r| r.contains(&ssi))
+    }
+
+    /// Returns true if the SSI may be used as a group, which is any SSI if no group ranges are set
+    pub fn is_group(&self, ssi: u32) -> bool {
+        self.group_ranges.is_empty() || self.group_ranges.iter().any(|r| r.contains(&ssi))
+    }
+
+    /// Returns true if the per-subscriber restrictions let the ISSI attach to the GSSI
+    pub fn may_attach(&self, issi: u32, gssi: u32) -> bool {
+        self.subscribers
+            .get(&issi)
+            .and_then(|s| s.allowed_gssis.as_ref())
+            .is_none_or(|gssis| gssis.contains(&gssi))
+    }
+
+    pub fn subscriber_class(&self, issi: u32) -> Option<u16> {
+        self.subscribers.get(&issi).and_then(|s| s.subscriber_class)
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct AccessPolicyDto {
+    #[serde(default)]
+    pub allowed_issis: Vec<u32>,
+    #[serde(default)]
+    pub allowed_issi_ranges: Vec<[u32; 2]>,
+    #[serde(default)]
+    pub group_ranges: Vec<[u32; 2]>,
+    #[serde(default)]
+    pub max_groups_per_ms: Option<usize>,
+    #[serde(default)]
+    pub subscribers: HashMap<String, SubscriberPolicyDto>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct SubscriberPolicyDto {
+    #[serde(default)]
+    pub allowed_gssis: Option<Vec<u32>>,
+    #[serde(default)]
+    pub subscriber_class: Option<u16>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn check_ssi(name: &str, ssi: u32) -> Result<u32, String> {
+    if ssi == 0 || ssi >= 1 << 24 {
+        return Err(format!("Invalid SSI in access_policy.{}: {}", name, ssi));
+    }
+    Ok(ssi)
+}
+
+fn ranges_to_cfg(name: &str, ranges: Vec<[u32; 2]>) -> Result<Vec<RangeInclusive<u32>>, String> {
+    ranges
+        .into_iter()
+        .map(|[start, end]| {
+            check_ssi(name, start)?;
+            check_ssi(name, end)?;
+            if start > end {
+                return Err(format!("Invalid range in access_policy.{}: [{}, {}]", name, start, end));
+            }
+            Ok(start..=end)
+        })
+        .collect()
+}
+
+pub fn access_policy_dto_to_cfg(policy: AccessPolicyDto) -> Result<CfgAccessPolicy, String> {
+    let allowed_issis = policy
+        .allowed_issis
+        .into_iter()
+        .map(|issi| check_ssi("allowed_issis", issi))
+        .collect::<Result<_, _>>()?;
+    if policy.max_groups_per_ms == Some(0) {
+        return Err("access_policy.max_groups_per_ms must be positive".to_string());
+    }
+
+    let mut subscribers = HashMap::new();
+    for (issi_str, subscriber) in policy.subscribers {
+        let issi = issi_str
+            .parse::<u32>()
+            .ok()
+            .and_then(|issi| check_ssi("subscribers", issi).ok())
+            .ok_or_else(|| format!("Invalid ISSI in access_policy.subscribers: {}", issi_str))?;
+        if !subscriber.extra.is_empty() {
+            let mut keys: Vec<&String> = subscriber.extra.keys().collect();
+            keys.sort();
+            return Err(format!("Unrecognized fields in access_policy.subscribers.{}: {:?}", issi_str, keys));
+        }
+        let allowed_gssis = subscriber
+            .allowed_gssis
+            .map(|gssis| gssis.into_iter().map(|gssi| check_ssi("allowed_gssis", gssi)).collect())
+            .transpose()?;
+        if subscriber.subscriber_class == Some(0) {
+            return Err(format!("access_policy.subscribers.{}.subscriber_class must not be 0", issi_str));
+        }
+        subscribers.insert(
+            issi,
+            CfgSubscriberPolicy {
+                allowed_gssis,
+                subscriber_class: subscriber.subscriber_class,
+            },
+        );
+    }
+
+    Ok(CfgAccessPolicy {
+        allowed_issis,
+        allowed_issi_ranges: ranges_to_cfg("allowed_issi_ranges", policy.allowed_issi_ranges)?,
+        group_ranges: ranges_to_cfg("group_ranges", policy.group_ranges)?,
+        max_groups_per_ms: policy.max_groups_per_ms,
+        subscribers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_policy() {
+        let cfg = access_policy_dto_to_cfg(toml::from_str("").unwrap()).unwrap();
+        assert!(cfg.issi_allowed(2040814));
+        assert!(cfg.is_individual(91));
+        assert!(cfg.is_group(2040814));
+        assert!(cfg.may_attach(2040814, 91));
+        assert_eq!(cfg.subscriber_class(2040814), None);
+    }
+
+    #[test]
+    fn test_access_policy_config() {
+        let dto: AccessPolicyDto = toml::from_str(
+            r#"
+            allowed_issis = [2040814]
+            allowed_issi_ranges = [[1000000, 1000999]]
+            group_ranges = [[1, 9999]]
+            max_groups_per_ms = 4
+
+            [subscribers.1000001]
+            allowed_gssis = [91]
+            subscriber_class = 0x0003
+            "#,
+        )
+        .unwrap();
+        let cfg = access_policy_dto_to_cfg(dto).unwrap();
+        assert!(cfg.issi_allowed(2040814));
+        assert!(cfg.issi_allowed(1000999));
+        assert!(!cfg.issi_allowed(1001000));
+        assert!(!cfg.is_individual(91));
+        assert!(cfg.is_group(9999));
+        assert!(!cfg.is_group(10000));
+        assert!(cfg.may_attach(1000001, 91));
+        assert!(!cfg.may_attach(1000001, 92));
+        assert!(cfg.may_attach(1000002, 92));
+        assert_eq!(cfg.subscriber_class(1000001), Some(3));
+        assert_eq!(cfg.max_groups_per_ms, Some(4));
+
+        let dto: AccessPolicyDto = toml::from_str("allowed_issi_ranges = [[2000, 1000]]").unwrap();
+        assert!(access_policy_dto_to_cfg(dto).is_err());
+        let dto: AccessPolicyDto = toml::from_str("[subscribers.abc]\nsubscriber_class = 1").unwrap();
+        assert!(access_policy_dto_to_cfg(dto).is_err());
+        let dto: AccessPolicyDto = toml::from_str("[subscribers.1000001]\nallowed_groups = [91]").unwrap();
+        assert!(access_policy_dto_to_cfg(dto).is_err());
+    }
+}
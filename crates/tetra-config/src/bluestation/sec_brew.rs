@@ -23,6 +23,10 @@ pub struct CfgBrew {
 
     /// Set to true when SDS between local and Brew clients is enabled
     pub feature_sds_enabled: bool,
+    /// Set to true when DTMF within group calls is exchanged with Brew
+    pub feature_dtmf_enabled: bool,
+    /// Set to true when SNDCP packet data is bridged to Brew instead of the local packet interface
+    pub feature_packet_data_enabled: bool,
     /// If present, restrict Brew call to these remote SSIs
     pub whitelisted_ssis: Option<Vec<u32>>,
 }
@@ -54,6 +58,14 @@ pub struct CfgBrewDto {
     #[serde(default = "default_brew_feature_sds_enabled")]
     pub feature_sds_enabled: bool,
 
+    /// Set to true when DTMF within group calls is exchanged with Brew
+    #[serde(default = "default_brew_feature_dtmf_enabled")]
+    pub feature_dtmf_enabled: bool,
+
+    /// Set to true when SNDCP packet data is bridged to Brew instead of the local packet interface
+    #[serde(default)]
+    pub feature_packet_data_enabled: bool,
+
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -70,6 +82,10 @@ fn default_brew_feature_sds_enabled() -> bool {
     true
 }
 
+fn default_brew_feature_dtmf_enabled() -> bool {
+    true
+}
+
 /// Convert a CfgBrewDto (from TOML) into a CfgBrew (used in the stack config)
 pub fn apply_brew_patch(src: CfgBrewDto) -> CfgBrew {
     CfgBrew {
@@ -81,6 +97,8 @@ pub fn apply_brew_patch(src: CfgBrewDto) -> CfgBrew {
         reconnect_delay: Duration::from_secs(src.reconnect_delay_secs),
         jitter_initial_latency_frames: src.jitter_initial_latency_frames,
         feature_sds_enabled: src.feature_sds_enabled,
+        feature_dtmf_enabled: src.feature_dtmf_enabled,
+        feature_packet_data_enabled: src.feature_packet_data_enabled,
         whitelisted_ssis: src.whitelisted_ssis,
     }
 }
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Lowest of the pre-emptive call priorities (ETSI 14.8.9), 15 being the emergency priority
+pub const PREEMPTIVE_CALL_PRIORITY: u8 = 12;
+
+/// Queuing of group call set-ups when no traffic timeslot is free, BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgCallQueue {
+    /// Maximum number of call set-ups waiting for a traffic timeslot
+    pub max_length: usize,
+    /// Queued call set-ups not served within this time are rejected
+    pub timeout: Duration,
+    /// Queue time-out for emergency calls (call priority 15)
+    pub emergency_timeout: Duration,
+    /// Whether calls of a pre-emptive priority may release a lower-priority call to obtain a timeslot
+    pub preemption: bool,
+    /// Lowest call priority that may pre-empt other calls
+    pub preemption_priority: u8,
+}
+
+#[derive(Default, Deserialize)]
+pub struct CallQueueDto {
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub emergency_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub preemption: Option<bool>,
+    #[serde(default)]
+    pub preemption_priority: Option<u8>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn call_queue_dto_to_cfg(call_queue: CallQueueDto) -> Result<CfgCallQueue, String> {
+    let timeout_secs = call_queue.timeout_secs.unwrap_or(30);
+    let emergency_timeout_secs = call_queue.emergency_timeout_secs.unwrap_or(60);
+    if timeout_secs == 0 || emergency_timeout_secs == 0 {
+        return Err("call_queue time-outs must be positive".to_string());
+    }
+    let preemption_priority = call_queue.preemption_priority.unwrap_or(PREEMPTIVE_CALL_PRIORITY);
+    if !(1..=15).contains(&preemption_priority) {
+        return Err(format!("Invalid call_queue.preemption_priority: {}", preemption_priority));
+    }
+    Ok(CfgCallQueue {
+        max_length: call_queue.max_length.unwrap_or(8),
+        timeout: Duration::from_secs(timeout_secs),
+        emergency_timeout: Duration::from_secs(emergency_timeout_secs),
+        preemption: call_queue.preemption.unwrap_or(true),
+        preemption_priority,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_call_queue_defaults() {
+        let cfg = call_queue_dto_to_cfg(toml::from_str("").unwrap()).unwrap();
+        assert_eq!(cfg.max_length, 8);
+        assert_eq!(cfg.timeout, Duration::from_secs(30));
+        assert_eq!(cfg.emergency_timeout, Duration::from_secs(60));
+        assert!(cfg.preemption);
+        assert_eq!(cfg.preemption_priority, PREEMPTIVE_CALL_PRIORITY);
+
+        for bad in ["timeout_secs = 0", "preemption_priority = 0", "preemption_priority = 16"] {
+            let dto: CallQueueDto = toml::from_str(bad).unwrap();
+            assert!(call_queue_dto_to_cfg(dto).is_err(), "accepted {}", bad);
+        }
+    }
+}
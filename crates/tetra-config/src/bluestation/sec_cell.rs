@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use tetra_core::freqs::FreqInfo;
 use tetra_core::ranges::SortedDisjointSsiRanges;
 use toml::Value;
 
@@ -25,6 +26,9 @@ pub struct CfgCellInfo {
     pub custom_duplex_spacing: Option<u32>,
     /// 1 bits, from MAC SYSINFO
     pub reverse_operation: bool,
+    /// Carrier numbers of secondary carriers, in the same band, offset and duplex spacing as the main carrier.
+    /// These carry traffic channels only, assigned through MAC-RESOURCE channel allocation.
+    pub secondary_carriers: Vec<u16>,
 
     // 14 bits, from 18.4.2.2 D-MLE-SYSINFO
     pub location_area: u16,
@@ -44,6 +48,10 @@ pub struct CfgCellInfo {
     pub aie_service: bool,
     pub advanced_link: bool,
 
+    /// Address individual MSs on the MCCH and on the traffic channels of their calls by event label instead of
+    /// their SSI, once they hold one
+    pub event_labels: bool,
+
     // From SYNC
     pub system_code: u8,
     pub colour_code: u8,
@@ -59,6 +67,20 @@ pub struct CfgCellInfo {
     pub timezone: Option<String>,
 }
 
+impl CfgCellInfo {
+    /// Frequency info for a carrier of this cell, using the band, offset and duplex spacing of the main carrier
+    pub fn carrier_freq_info(&self, carrier_num: u16) -> Result<FreqInfo, String> {
+        FreqInfo::from_components(
+            self.freq_band,
+            carrier_num,
+            self.freq_offset_hz,
+            self.reverse_operation,
+            self.duplex_spacing_id,
+            self.custom_duplex_spacing,
+        )
+    }
+}
+
 #[derive(Default, Deserialize)]
 pub struct CellInfoDto {
     pub main_carrier: u16,
@@ -67,6 +89,7 @@ pub struct CellInfoDto {
     pub duplex_spacing: u8,
     pub reverse_operation: bool,
     pub custom_duplex_spacing: Option<u32>,
+    pub secondary_carriers: Option<Vec<u16>>,
 
     pub location_area: u16,
 
@@ -84,6 +107,7 @@ pub struct CellInfoDto {
     pub sndcp_service: Option<bool>,
     pub aie_service: Option<bool>,
     pub advanced_link: Option<bool>,
+    pub event_labels: Option<bool>,
 
     pub system_code: Option<u8>,
     pub colour_code: Option<u8>,
@@ -108,6 +132,7 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
         duplex_spacing_id: ci.duplex_spacing,
         reverse_operation: ci.reverse_operation,
         custom_duplex_spacing: ci.custom_duplex_spacing,
+        secondary_carriers: ci.secondary_carriers.unwrap_or_default(),
         location_area: ci.location_area,
         neighbor_cell_broadcast: ci.neighbor_cell_broadcast.unwrap_or(0),
         late_entry_supported: ci.late_entry_supported.unwrap_or(false),
@@ -123,6 +148,7 @@ pub fn cell_dto_to_cfg(ci: CellInfoDto) -> CfgCellInfo {
         sndcp_service: ci.sndcp_service.unwrap_or(false),
         aie_service: ci.aie_service.unwrap_or(false),
         advanced_link: ci.advanced_link.unwrap_or(false),
+        event_labels: ci.event_labels.unwrap_or(false),
         system_code: ci.system_code.unwrap_or(3), // 3 = ETSI EN 300 392-2 V3.1.1
         colour_code: ci.colour_code.unwrap_or(0),
         sharing_mode: ci.sharing_mode.unwrap_or(0),
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Handling of emergency calls (call priority 15), BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgEmergency {
+    /// Whether emergency calls may release a lower-priority call to obtain a traffic timeslot
+    pub preemption: bool,
+    /// Program run on every emergency call, with the call type, calling ISSI, called SSI and call identifier as arguments
+    pub alarm_command: Option<String>,
+}
+
+impl Default for CfgEmergency {
+    fn default() -> Self {
+        Self {
+            preemption: true,
+            alarm_command: None,
+        }
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct EmergencyDto {
+    #[serde(default)]
+    pub preemption: Option<bool>,
+    #[serde(default)]
+    pub alarm_command: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn emergency_dto_to_cfg(emergency: EmergencyDto) -> Result<CfgEmergency, String> {
+    if emergency.alarm_command.as_ref().is_some_and(|c| c.is_empty()) {
+        return Err("emergency.alarm_command must not be empty".to_string());
+    }
+    Ok(CfgEmergency {
+        preemption: emergency.preemption.unwrap_or(true),
+        alarm_command: emergency.alarm_command,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emergency_config() {
+        let cfg = emergency_dto_to_cfg(toml::from_str("").unwrap()).unwrap();
+        assert!(cfg.preemption);
+        assert!(cfg.alarm_command.is_none());
+
+        let dto: EmergencyDto = toml::from_str(r#"alarm_command = """#).unwrap();
+        assert!(emergency_dto_to_cfg(dto).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Groups the SwMI attaches terminals to with D-ATTACH/DETACH GROUP IDENTITY, BS stack mode only
+#[derive(Debug, Clone, Default)]
+pub struct CfgGroupAssignment {
+    /// Groups every MS is attached to when it registers
+    pub static_gssis: Vec<u32>,
+    /// Further groups attached to individual subscribers when they register, keyed by ISSI
+    pub subscribers: HashMap<u32, Vec<u32>>,
+    /// ISSIs of authorised users, such as dispatchers, that may assign groups to other terminals with SS-DGNA
+    pub dgna_authorised_issis: Vec<u32>,
+}
+
+impl CfgGroupAssignment {
+    /// All groups the ISSI is attached to on registration
+    pub fn static_gssis_for(&self, issi: u32) -> Vec<u32> {
+        let mut gssis = self.static_gssis.clone();
+        for &gssi in self.subscribers.get(&issi).into_iter().flatten() {
+            if !gssis.contains(&gssi) {
+                gssis.push(gssi);
+            }
+        }
+        gssis
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct GroupAssignmentDto {
+    #[serde(default)]
+    pub static_gssis: Vec<u32>,
+    #[serde(default)]
+    pub subscribers: HashMap<String, Vec<u32>>,
+    #[serde(default)]
+    pub dgna_authorised_issis: Vec<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+fn check_issi(issi: u32) -> Result<u32, String> {
+    if issi == 0 || issi >= 1 << 24 {
+        return Err(format!("Invalid ISSI in group_assignment: {}", issi));
+    }
+    Ok(issi)
+}
+
+fn check_gssi(gssi: u32) -> Result<u32, String> {
+    if gssi == 0 || gssi >= 1 << 24 {
+        return Err(format!("Invalid GSSI in group_assignment: {}", gssi));
+    }
+    Ok(gssi)
+}
+
+pub fn group_assignment_dto_to_cfg(group_assignment: GroupAssignmentDto) -> Result<CfgGroupAssignment, String> {
+    let static_gssis = group_assignment
+        .static_gssis
+        .into_iter()
+        .map(check_gssi)
+        .collect::<Result<_, _>>()?;
+
+    let mut subscribers = HashMap::new();
+    for (issi_str, gssis) in group_assignment.subscribers {
+        let issi = issi_str
+            .parse::<u32>()
+            .ok()
+            .filter(|&issi| issi != 0 && issi < 1 << 24)
+            .ok_or_else(|| format!("Invalid ISSI in group_assignment.subscribers: {}", issi_str))?;
+        let gssis = gssis.into_iter().map(check_gssi).collect::<Result<_, _>>()?;
+        subscribers.insert(issi, gssis);
+    }
+
+    let dgna_authorised_issis = group_assignment
+        .dgna_authorised_issis
+        .into_iter()
+        .map(check_issi)
+        .collect::<Result<_, _>>()?;
+
+    Ok(CfgGroupAssignment {
+        static_gssis,
+        subscribers,
+        dgna_authorised_issis,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_assignment_config() {
+        let dto: GroupAssignmentDto = toml::from_str(
+            r#"
+            static_gssis = [91]
+            dgna_authorised_issis = [1000009]
+
+            [subscribers]
+            1000001 = [91, 92]
+            "#,
+        )
+        .unwrap();
+        let cfg = group_assignment_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.static_gssis_for(1000001), vec![91, 92]);
+        assert_eq!(cfg.static_gssis_for(1000002), vec![91]);
+        assert_eq!(cfg.dgna_authorised_issis, vec![1000009]);
+
+        let dto: GroupAssignmentDto = toml::from_str("static_gssis = [16777216]").unwrap();
+        assert!(group_assignment_dto_to_cfg(dto).is_err());
+        let dto: GroupAssignmentDto = toml::from_str("[subscribers]\nabc = [91]").unwrap();
+        assert!(group_assignment_dto_to_cfg(dto).is_err());
+        let dto: GroupAssignmentDto = toml::from_str("dgna_authorised_issis = [0]").unwrap();
+        assert!(group_assignment_dto_to_cfg(dto).is_err());
+    }
+}
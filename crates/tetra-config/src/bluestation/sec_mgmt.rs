@@ -0,0 +1,28 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Runtime management API configuration, BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgMgmt {
+    /// Path of the Unix socket the management API listens on
+    pub socket_path: String,
+}
+
+#[derive(Default, Deserialize)]
+pub struct MgmtDto {
+    pub socket_path: String,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn mgmt_dto_to_cfg(mgmt: MgmtDto) -> Result<CfgMgmt, String> {
+    if mgmt.socket_path.is_empty() {
+        return Err("mgmt.socket_path must not be empty".to_string());
+    }
+    Ok(CfgMgmt {
+        socket_path: mgmt.socket_path,
+    })
+}
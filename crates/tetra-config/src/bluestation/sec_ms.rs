@@ -0,0 +1,29 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use toml::Value;
+
+/// Identity of the terminal when running in MS stack mode
+#[derive(Debug, Clone)]
+pub struct CfgMsInfo {
+    /// Individual subscriber identity, used to register with the cell
+    pub issi: u32,
+    /// Group identities (GSSIs) to attach to during registration
+    pub groups: Vec<u32>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct MsInfoDto {
+    pub issi: u32,
+    #[serde(default)]
+    pub groups: Vec<u32>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn ms_dto_to_cfg(mi: MsInfoDto) -> CfgMsInfo {
+    CfgMsInfo {
+        issi: mi.issi,
+        groups: mi.groups,
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tetra_core::freqs::FreqInfo;
+use toml::Value;
+
+/// Most CA neighbour cells a D-NWRK-BROADCAST can carry (3-bit count)
+pub const MAX_NEIGHBOUR_CELLS: usize = 7;
+
+/// Neighbour cell advertised in D-NWRK-BROADCAST for announced cell reselection, BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgNeighbourCell {
+    /// 5 bits, identifies the neighbour towards the MS in cell reselection signalling
+    pub cell_identifier: u8,
+    /// 12 bits, main carrier number of the neighbour
+    pub main_carrier: u16,
+    /// Frequency band, offset, duplex spacing and reverse operation of the neighbour's main carrier.
+    /// Default to those of this cell.
+    pub freq_band: Option<u8>,
+    pub freq_offset_hz: Option<i16>,
+    pub duplex_spacing_id: Option<u8>,
+    pub reverse_operation: Option<bool>,
+    /// 2 bits, 0 = no announced reselection, 1 = type 1, 2 = types 1 and 2, 3 = types 1, 2 and 3
+    pub reselection_types_supported: u8,
+    /// Whether the neighbour's TDMA timing is synchronized with this cell
+    pub synchronized: bool,
+    /// 2 bits, advertised load of the neighbour
+    pub cell_load: u8,
+    /// Network and location area of the neighbour, only advertised if configured
+    pub mcc: Option<u16>,
+    pub mnc: Option<u16>,
+    pub location_area: Option<u16>,
+    /// 3 bits, maximum MS transmit power on the neighbour
+    pub max_ms_tx_power: Option<u8>,
+    /// 4 bits, minimum RX access level on the neighbour
+    pub min_rx_access_level: Option<u8>,
+    /// 6 bits, TDMA frame offset of a synchronized neighbour
+    pub tdma_frame_offset: Option<u8>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct NeighbourCellDto {
+    pub cell_identifier: u8,
+    pub main_carrier: u16,
+    #[serde(default)]
+    pub freq_band: Option<u8>,
+    #[serde(default)]
+    pub freq_offset: Option<i16>,
+    #[serde(default)]
+    pub duplex_spacing: Option<u8>,
+    #[serde(default)]
+    pub reverse_operation: Option<bool>,
+    #[serde(default)]
+    pub reselection_types_supported: Option<u8>,
+    #[serde(default)]
+    pub synchronized: Option<bool>,
+    #[serde(default)]
+    pub cell_load: Option<u8>,
+    #[serde(default)]
+    pub mcc: Option<u16>,
+    #[serde(default)]
+    pub mnc: Option<u16>,
+    #[serde(default)]
+    pub location_area: Option<u16>,
+    #[serde(default)]
+    pub max_ms_tx_power: Option<u8>,
+    #[serde(default)]
+    pub min_rx_access_level: Option<u8>,
+    #[serde(default)]
+    pub tdma_frame_offset: Option<u8>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+/// Checks a value fits the given number of bits of its PDU element
+fn check_bits(name: &str, value: Option<u64>, bits: u32) -> Result<(), String> {
+    match value {
+        Some(v) if v >> bits != 0 => Err(format!("neighbour_cells.{} out of range: {}", name, v)),
+        _ => Ok(()),
+    }
+}
+
+pub fn neighbour_cell_dto_to_cfg(n: NeighbourCellDto) -> Result<CfgNeighbourCell, String> {
+    let cfg = CfgNeighbourCell {
+        cell_identifier: n.cell_identifier,
+        main_carrier: n.main_carrier,
+        freq_band: n.freq_band,
+        freq_offset_hz: n.freq_offset,
+        duplex_spacing_id: n.duplex_spacing,
+        reverse_operation: n.reverse_operation,
+        reselection_types_supported: n.reselection_types_supported.unwrap_or(1),
+        synchronized: n.synchronized.unwrap_or(false),
+        cell_load: n.cell_load.unwrap_or(0),
+        mcc: n.mcc,
+        mnc: n.mnc,
+        location_area: n.location_area,
+        max_ms_tx_power: n.max_ms_tx_power,
+        min_rx_access_level: n.min_rx_access_level,
+        tdma_frame_offset: n.tdma_frame_offset,
+    };
+
+    check_bits("cell_identifier", Some(cfg.cell_identifier as u64), 5)?;
+    check_bits("main_carrier", Some(cfg.main_carrier as u64), 12)?;
+    check_bits("freq_band", cfg.freq_band.map(u64::from), 4)?;
+    check_bits("duplex_spacing", cfg.duplex_spacing_id.map(u64::from), 3)?;
+    check_bits("reselection_types_supported", Some(cfg.reselection_types_supported as u64), 2)?;
+    check_bits("cell_load", Some(cfg.cell_load as u64), 2)?;
+    check_bits("mcc", cfg.mcc.map(u64::from), 10)?;
+    check_bits("mnc", cfg.mnc.map(u64::from), 14)?;
+    check_bits("location_area", cfg.location_area.map(u64::from), 14)?;
+    check_bits("max_ms_tx_power", cfg.max_ms_tx_power.map(u64::from), 3)?;
+    check_bits("min_rx_access_level", cfg.min_rx_access_level.map(u64::from), 4)?;
+    check_bits("tdma_frame_offset", cfg.tdma_frame_offset.map(u64::from), 6)?;
+    if let Some(offset) = cfg.freq_offset_hz
+        && FreqInfo::freq_offset_hz_to_id(offset).is_none()
+    {
+        return Err(format!("neighbour_cells.freq_offset invalid: {}", offset));
+    }
+    Ok(cfg)
+}
@@ -12,12 +12,14 @@ pub enum PhyBackend {
     Undefined,
     None,
     SoapySdr,
+    /// Baseband IQ samples read from and written to files, see CfgPhyIo
+    File,
 }
 
 /// PHY layer I/O configuration
 #[derive(Debug, Clone)]
 pub struct CfgPhyIo {
-    /// Backend type: Soapysdr, File, or None
+    /// Backend type: SoapySdr, File, or None
     pub backend: PhyBackend,
 
     pub dl_tx_file: Option<String>,
@@ -25,6 +27,13 @@ pub struct CfgPhyIo {
     pub ul_input_file: Option<String>,
     pub dl_input_file: Option<String>,
 
+    /// For File backend: sample rate of the IQ files.
+    /// Defaults to 512 kHz if None.
+    pub sample_rate: Option<f64>,
+    /// For File backend: seconds of silence received after the input file ends, before the stack stops.
+    /// Lets the last bursts of the file through the receive chain. Stops right away if None.
+    pub rx_silence_tail_secs: Option<f64>,
+
     /// For Soapysdr backend: SoapySDR configuration
     pub soapysdr: Option<CfgSoapySdr>,
 }
@@ -38,6 +47,9 @@ pub struct PhyIoDto {
     pub ul_input_file: Option<String>,
     pub dl_input_file: Option<String>,
 
+    pub sample_rate: Option<f64>,
+    pub rx_silence_tail_secs: Option<f64>,
+
     pub soapysdr: Option<SoapySdrDto>,
 
     #[serde(flatten)]
@@ -99,6 +111,8 @@ pub fn phy_dto_to_cfg(src: PhyIoDto) -> CfgPhyIo {
         ul_rx_file: src.ul_rx_file,
         ul_input_file: src.ul_input_file,
         dl_input_file: src.dl_input_file,
+        sample_rate: src.sample_rate,
+        rx_silence_tail_secs: src.rx_silence_tail_secs,
         soapysdr,
     }
 }
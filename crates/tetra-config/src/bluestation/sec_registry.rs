@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// Persistent subscriber registry configuration, BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgRegistry {
+    /// File the registrations and group affiliations are snapshotted to, and reloaded from at startup
+    pub journal_path: String,
+    /// Registrations not refreshed by the MS for this long are dropped when the journal is loaded.
+    /// It does not expire registrations while the BS runs.
+    pub ttl: Duration,
+    /// File the disabled terminals are kept in. Without it, disabling an MS only lasts until the BS restarts.
+    pub disabled_path: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+pub struct RegistryDto {
+    pub journal_path: String,
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    #[serde(default)]
+    pub disabled_path: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn registry_dto_to_cfg(registry: RegistryDto) -> Result<CfgRegistry, String> {
+    if registry.journal_path.is_empty() {
+        return Err("registry.journal_path must not be empty".to_string());
+    }
+    let ttl_secs = registry.ttl_secs.unwrap_or(24 * 3600);
+    if ttl_secs == 0 {
+        return Err("registry.ttl_secs must be positive".to_string());
+    }
+    if registry.disabled_path.as_ref().is_some_and(|p| p.is_empty()) {
+        return Err("registry.disabled_path must not be empty".to_string());
+    }
+    Ok(CfgRegistry {
+        journal_path: registry.journal_path,
+        ttl: Duration::from_secs(ttl_secs),
+        disabled_path: registry.disabled_path,
+    })
+}
@@ -0,0 +1,182 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use toml::Value;
+
+/// Air interface encryption security class of the cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SecurityClass {
+    /// No air interface encryption
+    #[default]
+    Class1,
+    /// Encryption with a static cipher key (SCK) shared by all terminals
+    Class2,
+    /// Encryption with a derived cipher key (DCK) from authentication, which is then mandatory
+    Class3,
+}
+
+/// Authentication and air interface encryption settings for a BS
+#[derive(Debug, Clone)]
+pub struct CfgSecurity {
+    /// If set, terminals without a provisioned key are refused registration.
+    /// Otherwise, they are registered without authentication.
+    pub authentication_required: bool,
+    /// Authentication key K (128 bits) for each provisioned ISSI
+    pub keys: HashMap<u32, u128>,
+
+    /// Air interface encryption used on the cell. Requires cell.aie_service for class 2 and 3.
+    pub security_class: SecurityClass,
+    /// Static cipher key (80 bits), required for class 2
+    pub sck: Option<u128>,
+    /// Number of the static cipher key, signalled to terminals (1-32)
+    pub sck_number: u8,
+    /// Keystream generator (TEA1-4 is 0-3) in use, signalled to terminals
+    pub ksg_number: u8,
+}
+
+impl Default for CfgSecurity {
+    fn default() -> Self {
+        Self {
+            authentication_required: false,
+            keys: HashMap::new(),
+            security_class: SecurityClass::Class1,
+            sck: None,
+            sck_number: 1,
+            ksg_number: 0,
+        }
+    }
+}
+
+impl CfgSecurity {
+    /// Authentication key K for the given ISSI, if provisioned
+    pub fn key(&self, issi: u32) -> Option<u128> {
+        self.keys.get(&issi).copied()
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct SecurityDto {
+    #[serde(default)]
+    pub authentication_required: bool,
+    /// ISSI (decimal) to K (32 hex digits)
+    #[serde(default)]
+    pub keys: HashMap<String, String>,
+    /// 1, 2 or 3
+    #[serde(default)]
+    pub security_class: Option<u8>,
+    /// 20 hex digits
+    #[serde(default)]
+    pub sck: Option<String>,
+    #[serde(default)]
+    pub sck_number: Option<u8>,
+    #[serde(default)]
+    pub ksg_number: Option<u8>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn security_dto_to_cfg(sec: SecurityDto) -> Result<CfgSecurity, String> {
+    let mut keys = HashMap::with_capacity(sec.keys.len());
+    for (issi_str, k_str) in sec.keys {
+        let issi = match issi_str.parse::<u32>() {
+            Ok(issi) if issi < (1 << 24) => issi,
+            _ => return Err(format!("Invalid ISSI in security.keys: {}", issi_str)),
+        };
+        let k = parse_hex_key(&k_str, 32).map_err(|e| format!("Key for ISSI {} {}", issi, e))?;
+        keys.insert(issi, k);
+    }
+
+    let security_class = match sec.security_class.unwrap_or(1) {
+        1 => SecurityClass::Class1,
+        2 => SecurityClass::Class2,
+        3 => SecurityClass::Class3,
+        other => return Err(format!("Invalid security.security_class: {}", other)),
+    };
+    let sck = sec
+        .sck
+        .map(|sck_str| parse_hex_key(&sck_str, 20).map_err(|e| format!("security.sck {}", e)))
+        .transpose()?;
+    if security_class == SecurityClass::Class2 && sck.is_none() {
+        return Err("security.sck must be provided for security class 2".to_string());
+    }
+    let sck_number = sec.sck_number.unwrap_or(1);
+    if !(1..=32).contains(&sck_number) {
+        return Err(format!("Invalid security.sck_number: {}", sck_number));
+    }
+    let ksg_number = sec.ksg_number.unwrap_or(0);
+    if ksg_number > 15 {
+        return Err(format!("Invalid security.ksg_number: {}", ksg_number));
+    }
+
+    Ok(CfgSecurity {
+        authentication_required: sec.authentication_required,
+        keys,
+        security_class,
+        sck,
+        sck_number,
+        ksg_number,
+    })
+}
+
+/// Parses a key of exactly the given number of hex digits, optionally prefixed with 0x
+fn parse_hex_key(s: &str, digits: usize) -> Result<u128, String> {
+    let s = s.trim_start_matches("0x");
+    if s.len() != digits {
+        return Err(format!("must be {} hex digits", digits));
+    }
+    u128::from_str_radix(s, 16).map_err(|_| "is not valid hex".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_keys() {
+        let dto: SecurityDto = toml::from_str(
+            r#"
+            authentication_required = true
+            [keys]
+            "2040814" = "000102030405060708090a0b0c0d0e0f"
+            "#,
+        )
+        .unwrap();
+        let cfg = security_dto_to_cfg(dto).unwrap();
+        assert!(cfg.authentication_required);
+        assert_eq!(cfg.key(2040814), Some(0x000102030405060708090a0b0c0d0e0f));
+        assert_eq!(cfg.key(2040815), None);
+
+        let dto: SecurityDto = toml::from_str(
+            r#"
+            [keys]
+            "2040814" = "0102"
+            "#,
+        )
+        .unwrap();
+        assert!(security_dto_to_cfg(dto).is_err());
+    }
+
+    #[test]
+    fn test_security_class() {
+        let dto: SecurityDto = toml::from_str("").unwrap();
+        let cfg = security_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.security_class, SecurityClass::Class1);
+
+        let dto: SecurityDto = toml::from_str(
+            r#"
+            security_class = 2
+            sck = "00112233445566778899"
+            sck_number = 3
+            "#,
+        )
+        .unwrap();
+        let cfg = security_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.security_class, SecurityClass::Class2);
+        assert_eq!(cfg.sck, Some(0x00112233445566778899));
+        assert_eq!(cfg.sck_number, 3);
+
+        // Class 2 needs an SCK
+        let dto: SecurityDto = toml::from_str("security_class = 2").unwrap();
+        assert!(security_dto_to_cfg(dto).is_err());
+    }
+}
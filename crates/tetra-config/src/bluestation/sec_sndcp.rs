@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use serde::Deserialize;
+use toml::Value;
+
+/// SNDCP packet data (TETRA IP) configuration, BS stack mode only
+#[derive(Debug, Clone)]
+pub struct CfgSndcp {
+    /// Name of the Linux TUN device that carries the IPv4 traffic of the terminals
+    pub tun_name: String,
+    /// Network address of the pool terminal addresses are taken from
+    pub network: Ipv4Addr,
+    /// Prefix length of the address pool
+    pub prefix_len: u8,
+    /// MTU of the TUN device, also the largest N-PDU accepted from terminals
+    pub mtu: u16,
+    /// Move terminals with an active PDP context onto a packet data channel on a free timeslot.
+    /// If false, or no timeslot is free, packet data is carried on the MCCH.
+    pub pdch: bool,
+}
+
+impl CfgSndcp {
+    /// Address of the BS side of the network, assigned to the TUN device
+    pub fn gateway(&self) -> Ipv4Addr {
+        Ipv4Addr::from(u32::from(self.network) + 1)
+    }
+
+    /// Addresses that may be handed out to terminals, excluding network, gateway and broadcast addresses
+    pub fn pool(&self) -> impl Iterator<Item = Ipv4Addr> {
+        let first = u32::from(self.network) + 2;
+        let broadcast = u32::from(self.network) | (u32::MAX >> self.prefix_len);
+        (first..broadcast).map(Ipv4Addr::from)
+    }
+
+    /// Whether the given address falls within the address pool network
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        let mask = u32::MAX << (32 - self.prefix_len as u32);
+        u32::from(addr) & mask == u32::from(self.network)
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct SndcpDto {
+    #[serde(default)]
+    pub tun_name: Option<String>,
+    /// IPv4 network in CIDR notation, such as "10.200.0.0/24"
+    pub address_pool: String,
+    #[serde(default)]
+    pub mtu: Option<u16>,
+    #[serde(default)]
+    pub pdch: Option<bool>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+pub fn sndcp_dto_to_cfg(sndcp: SndcpDto) -> Result<CfgSndcp, String> {
+    let Some((addr_str, len_str)) = sndcp.address_pool.split_once('/') else {
+        return Err(format!("sndcp.address_pool must be in CIDR notation: {}", sndcp.address_pool));
+    };
+    let addr = addr_str
+        .parse::<Ipv4Addr>()
+        .map_err(|_| format!("Invalid address in sndcp.address_pool: {}", addr_str))?;
+    let prefix_len = match len_str.parse::<u8>() {
+        // Need room for at least a gateway and one terminal
+        Ok(len) if (8..=30).contains(&len) => len,
+        _ => return Err(format!("Invalid prefix length in sndcp.address_pool: {}", len_str)),
+    };
+    let mask = u32::MAX << (32 - prefix_len as u32);
+    if u32::from(addr) & !mask != 0 {
+        return Err(format!("sndcp.address_pool has host bits set: {}", sndcp.address_pool));
+    }
+
+    let mtu = sndcp.mtu.unwrap_or(1500);
+    if !(576..=1500).contains(&mtu) {
+        return Err(format!("Invalid sndcp.mtu: {}", mtu));
+    }
+
+    Ok(CfgSndcp {
+        tun_name: sndcp.tun_name.unwrap_or_else(|| "tetra0".to_string()),
+        network: addr,
+        prefix_len,
+        mtu,
+        pdch: sndcp.pdch.unwrap_or(true),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sndcp_address_pool() {
+        let dto: SndcpDto = toml::from_str(r#"address_pool = "10.200.0.0/29""#).unwrap();
+        let cfg = sndcp_dto_to_cfg(dto).unwrap();
+        assert_eq!(cfg.tun_name, "tetra0");
+        assert_eq!(cfg.gateway(), Ipv4Addr::new(10, 200, 0, 1));
+        let pool: Vec<Ipv4Addr> = cfg.pool().collect();
+        assert_eq!(pool.first(), Some(&Ipv4Addr::new(10, 200, 0, 2)));
+        assert_eq!(pool.last(), Some(&Ipv4Addr::new(10, 200, 0, 6)));
+        assert!(cfg.contains(Ipv4Addr::new(10, 200, 0, 7)));
+        assert!(!cfg.contains(Ipv4Addr::new(10, 200, 0, 8)));
+
+        for bad in ["10.200.0.0", "10.200.0.1/24", "10.200.0.0/31", "10.200.0/24"] {
+            let dto: SndcpDto = toml::from_str(&format!(r#"address_pool = "{}""#, bad)).unwrap();
+            assert!(sndcp_dto_to_cfg(dto).is_err(), "accepted {}", bad);
+        }
+    }
+}
@@ -1,11 +1,45 @@
 use std::collections::{HashMap, HashSet};
-use tetra_core::TimeslotAllocator;
+use std::time::SystemTime;
+use tetra_core::{EnergyEconomySchedule, TimeslotAllocator};
+
+/// Air interface encryption agreed with a subscriber at registration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AieKeyClass {
+    /// Class 2, encrypted with the static cipher key (SCK)
+    Sck,
+    /// Class 3, encrypted with the derived cipher key (DCK) from authentication
+    Dck(u128),
+}
+
+/// Condition under which calls to a subscriber are forwarded (SS-CF)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallForwardingCondition {
+    Unconditional,
+    Busy,
+    NoReply,
+    NotReachable,
+}
+
+/// How an MS was disabled by the operator with D-DISABLE (EN 300 392-7 clause 5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisableKind {
+    /// Until enabled again with D-ENABLE
+    Temporary,
+    /// The MS cannot be enabled again over the air
+    Permanent,
+}
 
 #[derive(Debug, Clone)]
 pub struct Subscriber {
     pub issi: u32,
     // Set of attached GSSIs
     pub attached_groups: HashSet<u32>,
+    /// Key used for air interface encryption, None if traffic is sent in clear
+    pub aie: Option<AieKeyClass>,
+    /// Wake schedule agreed at registration, None if the MS stays alive
+    pub energy_economy: Option<EnergyEconomySchedule>,
+    /// Last time the MS registered or otherwise refreshed its registration
+    pub last_seen: SystemTime,
 }
 
 /// Centralized subscriber registry tracking locally registered ISSIs and their group affiliations.
@@ -15,6 +49,11 @@ pub struct SubscriberRegistry {
     subscribers: HashMap<u32, Subscriber>,
     /// Set of all GSSIs with at least one local affiliate
     all_attached_groups: HashSet<u32>,
+    /// Active call forwarding: (ISSI, condition) → forwarded-to SSI.
+    /// Kept across deregistration, so forwarding on not reachable keeps working while the MS is away.
+    call_forwarding: HashMap<(u32, CallForwardingCondition), u32>,
+    /// Disabled ISSIs, refused when they register again
+    disabled: HashMap<u32, DisableKind>,
 }
 
 impl SubscriberRegistry {
@@ -22,6 +61,8 @@ impl SubscriberRegistry {
         Self {
             subscribers: HashMap::new(),
             all_attached_groups: HashSet::new(),
+            call_forwarding: HashMap::new(),
+            disabled: HashMap::new(),
         }
     }
 
@@ -37,6 +78,9 @@ impl SubscriberRegistry {
             Subscriber {
                 issi,
                 attached_groups: HashSet::new(),
+                aie: None,
+                energy_economy: None,
+                last_seen: SystemTime::now(),
             },
         );
     }
@@ -46,9 +90,28 @@ impl SubscriberRegistry {
         self.subscribers.entry(issi).or_insert_with(|| Subscriber {
             issi,
             attached_groups: HashSet::new(),
+            aie: None,
+            energy_economy: None,
+            last_seen: SystemTime::now(),
         })
     }
 
+    pub fn get_subscriber(&self, issi: u32) -> Option<&Subscriber> {
+        self.subscribers.get(&issi)
+    }
+
+    /// Record that a registered MS refreshed its registration
+    pub fn touch(&mut self, issi: u32) {
+        if let Some(subscriber) = self.subscribers.get_mut(&issi) {
+            subscriber.last_seen = SystemTime::now();
+        }
+    }
+
+    /// All registered subscribers, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Subscriber> {
+        self.subscribers.values()
+    }
+
     /// Deregister an ISSI, removing it from the registry and cleaning up any group affiliations
     pub fn deregister(&mut self, issi: u32) {
         if let Some(subscriber) = self.subscribers.remove(&issi) {
@@ -86,6 +149,38 @@ impl SubscriberRegistry {
     pub fn has_group_members(&self, gssi: u32) -> bool {
         self.all_attached_groups.contains(&gssi)
     }
+
+    /// Activate call forwarding for issi under condition, or deactivate it if forwarded_to is None
+    pub fn set_call_forwarding(&mut self, issi: u32, condition: CallForwardingCondition, forwarded_to: Option<u32>) {
+        match forwarded_to {
+            Some(ssi) => self.call_forwarding.insert((issi, condition), ssi),
+            None => self.call_forwarding.remove(&(issi, condition)),
+        };
+    }
+
+    /// SSI that calls to issi are forwarded to under condition, if forwarding is active
+    pub fn call_forwarding(&self, issi: u32, condition: CallForwardingCondition) -> Option<u32> {
+        self.call_forwarding.get(&(issi, condition)).copied()
+    }
+
+    /// Mark an ISSI as disabled. It stays disabled across deregistration.
+    pub fn disable(&mut self, issi: u32, kind: DisableKind) {
+        self.disabled.insert(issi, kind);
+    }
+
+    /// Lift the disabling of an ISSI, returning how it was disabled
+    pub fn enable(&mut self, issi: u32) -> Option<DisableKind> {
+        self.disabled.remove(&issi)
+    }
+
+    pub fn disabled(&self, issi: u32) -> Option<DisableKind> {
+        self.disabled.get(&issi).copied()
+    }
+
+    /// All disabled ISSIs, in no particular order
+    pub fn iter_disabled(&self) -> impl Iterator<Item = (u32, DisableKind)> + '_ {
+        self.disabled.iter().map(|(&issi, &kind)| (issi, kind))
+    }
 }
 
 /// Mutable, stack-editable state (mutex-protected).
@@ -152,6 +247,31 @@ mod tests {
         assert!(!reg.has_group_members(999));
     }
 
+    #[test]
+    fn test_call_forwarding_survives_deregistration() {
+        let mut reg = SubscriberRegistry::new();
+        reg.register(1001);
+        reg.set_call_forwarding(1001, CallForwardingCondition::NotReachable, Some(1002));
+        reg.deregister(1001);
+        assert_eq!(reg.call_forwarding(1001, CallForwardingCondition::NotReachable), Some(1002));
+        assert_eq!(reg.call_forwarding(1001, CallForwardingCondition::Busy), None);
+
+        reg.set_call_forwarding(1001, CallForwardingCondition::NotReachable, None);
+        assert_eq!(reg.call_forwarding(1001, CallForwardingCondition::NotReachable), None);
+    }
+
+    #[test]
+    fn test_disabled_survives_deregistration() {
+        let mut reg = SubscriberRegistry::new();
+        reg.register(1001);
+        reg.disable(1001, DisableKind::Temporary);
+        reg.deregister(1001);
+        reg.register(1001);
+        assert_eq!(reg.disabled(1001), Some(DisableKind::Temporary));
+        assert_eq!(reg.enable(1001), Some(DisableKind::Temporary));
+        assert_eq!(reg.disabled(1001), None);
+    }
+
     #[test]
     fn test_register_overwrites_existing_subscriber() {
         let mut reg = SubscriberRegistry::new();
@@ -0,0 +1,88 @@
+use crate::TdmaTime;
+
+/// Frames in a hyperframe. All energy economy cycles divide it, so hyperframe numbers can be ignored.
+const FRAMES_PER_HYPERFRAME: u32 = 18 * 60;
+
+/// Wake schedule of an MS in energy economy mode, clause 23.7.6.
+/// The MS sleeps on the MCCH except for one TDMA frame per cycle, counted from the starting point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnergyEconomySchedule {
+    /// Energy economy group, 1 (EG1) to 7 (EG7)
+    pub group: u8,
+    /// Frame number of the starting point, 1 to 18
+    pub start_frame: u8,
+    /// Multiframe number of the starting point, 1 to 60
+    pub start_multiframe: u8,
+}
+
+impl EnergyEconomySchedule {
+    /// Length in frames of the cycle of an energy economy group: the awake frame followed by
+    /// 1, 2, 5, 8, 17, 71 or 359 frames asleep. None for stay alive (0) or invalid groups.
+    pub fn cycle_frames(group: u8) -> Option<u32> {
+        match group {
+            1 => Some(2),
+            2 => Some(3),
+            3 => Some(6),
+            4 => Some(9),
+            5 => Some(18),
+            6 => Some(72),
+            7 => Some(360),
+            _ => None,
+        }
+    }
+
+    /// Schedule for the given group, starting at the first frame after now that can carry MCCH signalling.
+    /// Frame 18 is skipped, as the longer cycles would otherwise only ever wake the MS on it.
+    pub fn starting_after(group: u8, now: TdmaTime) -> Option<Self> {
+        Self::cycle_frames(group)?;
+        let mut start = now.add_timeslots(4);
+        if start.f == 18 {
+            start = start.add_timeslots(4);
+        }
+        Some(Self {
+            group,
+            start_frame: start.f,
+            start_multiframe: start.m,
+        })
+    }
+
+    /// Returns true if the MS monitors the downlink in the frame of the given time
+    pub fn is_awake(&self, ts: TdmaTime) -> bool {
+        let Some(cycle) = Self::cycle_frames(self.group) else {
+            return true;
+        };
+        let frame = (ts.m as u32 - 1) * 18 + (ts.f as u32 - 1);
+        let start = (self.start_multiframe as u32 - 1) * 18 + (self.start_frame as u32 - 1);
+        (frame + FRAMES_PER_HYPERFRAME - start).is_multiple_of(cycle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_awake() {
+        let now = TdmaTime { t: 1, f: 3, m: 10, h: 0 };
+        let sched = EnergyEconomySchedule::starting_after(3, now).unwrap();
+        assert_eq!((sched.start_frame, sched.start_multiframe), (4, 10));
+
+        let awake: Vec<u8> = (1..=18).filter(|&f| sched.is_awake(TdmaTime { t: 1, f, m: 10, h: 0 })).collect();
+        assert_eq!(awake, vec![4, 10, 16]);
+
+        // The cycle carries over multiframe and hyperframe boundaries
+        assert!(sched.is_awake(TdmaTime { t: 1, f: 4, m: 1, h: 5 }));
+        assert!(sched.is_awake(TdmaTime { t: 3, f: 16, m: 60, h: 5 }));
+    }
+
+    #[test]
+    fn test_start_skips_frame_18() {
+        let now = TdmaTime { t: 2, f: 17, m: 60, h: 0 };
+        let sched = EnergyEconomySchedule::starting_after(7, now).unwrap();
+        assert_eq!((sched.start_frame, sched.start_multiframe), (1, 1));
+        assert!(sched.is_awake(TdmaTime { t: 1, f: 1, m: 21, h: 0 }));
+        assert!(!sched.is_awake(TdmaTime { t: 1, f: 1, m: 5, h: 0 }));
+
+        assert!(EnergyEconomySchedule::starting_after(0, now).is_none());
+    }
+}
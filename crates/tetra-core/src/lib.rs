@@ -11,6 +11,7 @@ pub mod address;
 pub mod bitbuffer;
 pub mod debug;
 pub mod direction;
+pub mod energy_economy;
 pub mod freqs;
 pub mod pdu_parse_error;
 pub mod phy_types;
@@ -27,6 +28,7 @@ pub mod typed_pdu_fields;
 pub use address::*;
 pub use bitbuffer::BitBuffer;
 pub use direction::Direction;
+pub use energy_economy::EnergyEconomySchedule;
 pub use pdu_parse_error::PduParseErr;
 pub use phy_types::*;
 pub use sap_fields::*;
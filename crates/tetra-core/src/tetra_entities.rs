@@ -23,4 +23,7 @@ pub enum TetraEntity {
 
     /// Brew protocol bridge (TetraPack/BrandMeister integration)
     Brew,
+
+    /// Runtime management API for the operator
+    Mgmt,
 }
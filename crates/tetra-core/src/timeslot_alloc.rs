@@ -2,11 +2,13 @@
 pub enum TimeslotOwner {
     Brew,
     Cmce,
+    Sndcp,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TimeslotAllocErr {
     InvalidTimeslot(u8),
+    InvalidCarrier(u8),
     InUse {
         ts: u8,
         owner: TimeslotOwner,
@@ -21,16 +23,22 @@ pub enum TimeslotAllocErr {
     },
 }
 
+/// Pool of traffic timeslots, per carrier.
+/// Carrier 0 is the main carrier, where TS1 carries the MCCH and only TS2..TS4 can be allocated.
+/// Carriers 1 and up are secondary carriers, on which all four timeslots can carry traffic.
 #[derive(Debug, Clone)]
 pub struct TimeslotAllocator {
     // Index 0 = TS2, 1 = TS3, 2 = TS4
     owners: [Option<TimeslotOwner>; 3],
+    // Index 0 = carrier 1, timeslot index 0 = TS1
+    secondary: Vec<[Option<TimeslotOwner>; 4]>,
 }
 
 impl Default for TimeslotAllocator {
     fn default() -> Self {
         Self {
             owners: [None, None, None],
+            secondary: Vec::new(),
         }
     }
 }
@@ -44,21 +52,90 @@ impl TimeslotAllocator {
         }
     }
 
+    fn slot(&self, carrier: u8, ts: u8) -> Result<&Option<TimeslotOwner>, TimeslotAllocErr> {
+        if carrier == 0 {
+            return Ok(&self.owners[Self::idx(ts)?]);
+        }
+        let slots = self
+            .secondary
+            .get(carrier as usize - 1)
+            .ok_or(TimeslotAllocErr::InvalidCarrier(carrier))?;
+        if !(1..=4).contains(&ts) {
+            return Err(TimeslotAllocErr::InvalidTimeslot(ts));
+        }
+        Ok(&slots[ts as usize - 1])
+    }
+
+    fn slot_mut(&mut self, carrier: u8, ts: u8) -> Result<&mut Option<TimeslotOwner>, TimeslotAllocErr> {
+        if carrier == 0 {
+            return Ok(&mut self.owners[Self::idx(ts)?]);
+        }
+        let slots = self
+            .secondary
+            .get_mut(carrier as usize - 1)
+            .ok_or(TimeslotAllocErr::InvalidCarrier(carrier))?;
+        if !(1..=4).contains(&ts) {
+            return Err(TimeslotAllocErr::InvalidTimeslot(ts));
+        }
+        Ok(&mut slots[ts as usize - 1])
+    }
+
+    /// Sets the number of secondary carriers. Allocations on removed carriers are dropped.
+    pub fn set_secondary_carriers(&mut self, count: usize) {
+        self.secondary.resize(count, [None; 4]);
+    }
+
+    /// Number of carriers, including the main carrier
+    pub fn num_carriers(&self) -> u8 {
+        1 + self.secondary.len() as u8
+    }
+
+    /// Timeslots of the given carrier that can carry traffic
+    pub fn traffic_timeslots(carrier: u8) -> std::ops::RangeInclusive<u8> {
+        if carrier == 0 { 2..=4 } else { 1..=4 }
+    }
+
+    /// Allocates a free timeslot on the main carrier
     pub fn allocate_any(&mut self, owner: TimeslotOwner) -> Option<u8> {
-        for (i, slot) in self.owners.iter_mut().enumerate() {
-            if slot.is_none() {
-                *slot = Some(owner);
-                return Some(i as u8 + 2);
+        self.allocate_on(owner, 0)
+    }
+
+    /// Allocates a free timeslot on the given carrier
+    pub fn allocate_on(&mut self, owner: TimeslotOwner, carrier: u8) -> Option<u8> {
+        let ts = Self::traffic_timeslots(carrier).find(|&ts| self.slot(carrier, ts) == Ok(&None))?;
+        *self.slot_mut(carrier, ts).ok()? = Some(owner);
+        Some(ts)
+    }
+
+    /// Allocates a free timeslot on any carrier, filling the main carrier first.
+    /// Returns (carrier, timeslot).
+    pub fn allocate_traffic(&mut self, owner: TimeslotOwner) -> Option<(u8, u8)> {
+        (0..self.num_carriers()).find_map(|carrier| self.allocate_on(owner, carrier).map(|ts| (carrier, ts)))
+    }
+
+    /// Allocates two free timeslots on the same carrier, filling the main carrier first.
+    /// Returns (carrier, first timeslot, second timeslot).
+    pub fn allocate_traffic_pair(&mut self, owner: TimeslotOwner) -> Option<(u8, u8, u8)> {
+        for carrier in 0..self.num_carriers() {
+            let mut free = Self::traffic_timeslots(carrier).filter(|&ts| self.slot(carrier, ts) == Ok(&None));
+            if let (Some(ts1), Some(ts2)) = (free.next(), free.next()) {
+                self.reserve_on(owner, carrier, ts1).ok()?;
+                self.reserve_on(owner, carrier, ts2).ok()?;
+                return Some((carrier, ts1, ts2));
             }
         }
         None
     }
 
     pub fn reserve(&mut self, owner: TimeslotOwner, ts: u8) -> Result<(), TimeslotAllocErr> {
-        let idx = Self::idx(ts)?;
-        match self.owners[idx] {
+        self.reserve_on(owner, 0, ts)
+    }
+
+    pub fn reserve_on(&mut self, owner: TimeslotOwner, carrier: u8, ts: u8) -> Result<(), TimeslotAllocErr> {
+        let slot = self.slot_mut(carrier, ts)?;
+        match *slot {
             None => {
-                self.owners[idx] = Some(owner);
+                *slot = Some(owner);
                 Ok(())
             }
             Some(existing) => Err(TimeslotAllocErr::InUse { ts, owner: existing }),
@@ -66,8 +143,12 @@ impl TimeslotAllocator {
     }
 
     pub fn release(&mut self, owner: TimeslotOwner, ts: u8) -> Result<(), TimeslotAllocErr> {
-        let idx = Self::idx(ts)?;
-        match self.owners[idx] {
+        self.release_on(owner, 0, ts)
+    }
+
+    pub fn release_on(&mut self, owner: TimeslotOwner, carrier: u8, ts: u8) -> Result<(), TimeslotAllocErr> {
+        let slot = self.slot_mut(carrier, ts)?;
+        match *slot {
             None => Err(TimeslotAllocErr::NotAllocated { ts }),
             Some(existing) if existing != owner => Err(TimeslotAllocErr::OwnerMismatch {
                 ts,
@@ -75,17 +156,77 @@ impl TimeslotAllocator {
                 actual: existing,
             }),
             Some(_) => {
-                self.owners[idx] = None;
+                *slot = None;
                 Ok(())
             }
         }
     }
 
     pub fn owner(&self, ts: u8) -> Option<TimeslotOwner> {
-        Self::idx(ts).ok().and_then(|idx| self.owners[idx])
+        self.owner_on(0, ts)
+    }
+
+    pub fn owner_on(&self, carrier: u8, ts: u8) -> Option<TimeslotOwner> {
+        self.slot(carrier, ts).ok().copied().flatten()
     }
 
     pub fn is_free(&self, ts: u8) -> bool {
         self.owner(ts).is_none()
     }
+
+    pub fn is_free_on(&self, carrier: u8, ts: u8) -> bool {
+        self.owner_on(carrier, ts).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_traffic_fills_main_carrier_first() {
+        let mut alloc = TimeslotAllocator::default();
+        alloc.set_secondary_carriers(1);
+        assert_eq!(alloc.num_carriers(), 2);
+
+        let allocated: Vec<(u8, u8)> = (0..7).map_while(|_| alloc.allocate_traffic(TimeslotOwner::Cmce)).collect();
+        assert_eq!(allocated, vec![(0, 2), (0, 3), (0, 4), (1, 1), (1, 2), (1, 3), (1, 4)]);
+        assert_eq!(alloc.allocate_traffic(TimeslotOwner::Cmce), None);
+
+        assert_eq!(alloc.release_on(TimeslotOwner::Cmce, 1, 2), Ok(()));
+        assert!(alloc.is_free_on(1, 2));
+        assert_eq!(alloc.allocate_traffic(TimeslotOwner::Brew), Some((1, 2)));
+        assert_eq!(alloc.owner_on(1, 2), Some(TimeslotOwner::Brew));
+    }
+
+    #[test]
+    fn test_allocate_traffic_pair_stays_on_one_carrier() {
+        let mut alloc = TimeslotAllocator::default();
+        alloc.set_secondary_carriers(1);
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Sndcp), Some(2));
+        assert_eq!(alloc.allocate_any(TimeslotOwner::Cmce), Some(3));
+
+        // Only TS4 is left on the main carrier, so the pair moves to the secondary carrier
+        assert_eq!(alloc.allocate_traffic_pair(TimeslotOwner::Cmce), Some((1, 1, 2)));
+        assert!(alloc.is_free(4));
+    }
+
+    #[test]
+    fn test_invalid_carrier_and_timeslot() {
+        let mut alloc = TimeslotAllocator::default();
+        assert_eq!(alloc.allocate_traffic(TimeslotOwner::Cmce), Some((0, 2)));
+        assert_eq!(alloc.reserve(TimeslotOwner::Cmce, 1), Err(TimeslotAllocErr::InvalidTimeslot(1)));
+        assert_eq!(
+            alloc.reserve_on(TimeslotOwner::Cmce, 1, 1),
+            Err(TimeslotAllocErr::InvalidCarrier(1))
+        );
+        assert_eq!(
+            alloc.release(TimeslotOwner::Brew, 2),
+            Err(TimeslotAllocErr::OwnerMismatch {
+                ts: 2,
+                owner: TimeslotOwner::Brew,
+                actual: TimeslotOwner::Cmce,
+            })
+        );
+    }
 }
@@ -236,6 +236,11 @@ pub mod typed {
         // Seek forward to end of element, if larger than 64 bits
         if len_bits > 64 {
             tracing::warn!("Type3 element {} length {} exceeds 64 bits, data truncated", id, len_bits);
+            if buffer.get_len_remaining() < len_bits - 64 {
+                return Err(PduParseErr::BufferEnded {
+                    field: Some("parse_type3_generic data"),
+                });
+            }
             buffer.seek_rel(len_bits as isize - 64);
         }
 
@@ -517,6 +522,11 @@ pub mod typed {
                 // Seek forward to end of element, if larger than 64 bits
                 if len_bits > 64 {
                     tracing::warn!("Type4 element {} length {} exceeds 64 bits, data truncated", id, len_bits);
+                    if buffer.get_len_remaining() < len_bits - 64 {
+                        return Err(PduParseErr::BufferEnded {
+                            field: Some("parse_type4_generic data"),
+                        });
+                    }
                     buffer.seek_rel(len_bits as isize - 64);
                 }
 
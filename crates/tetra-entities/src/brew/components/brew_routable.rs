@@ -12,6 +12,18 @@ pub fn feature_sds_enabled(config: &SharedConfig) -> bool {
     config.config().brew.as_ref().map_or(false, |brew| brew.feature_sds_enabled)
 }
 
+/// Returns true if the DTMF over Brew feature is enabled
+#[inline]
+pub fn feature_dtmf_enabled(config: &SharedConfig) -> bool {
+    config.config().brew.as_ref().is_some_and(|brew| brew.feature_dtmf_enabled)
+}
+
+/// Returns true if SNDCP packet data is bridged over Brew
+#[inline]
+pub fn feature_packet_data_enabled(config: &SharedConfig) -> bool {
+    config.config().brew.as_ref().is_some_and(|brew| brew.feature_packet_data_enabled)
+}
+
 /// Returns true if the configured Brew server is TetraPack (core.tetrapack.online)
 fn is_tetrapack(config: &SharedConfig) -> bool {
     let Some(brew_config) = &config.config().brew else {
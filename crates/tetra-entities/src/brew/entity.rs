@@ -6,6 +6,7 @@ use std::time::{Duration, Instant};
 
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::control::packet_data::SndcpPacketData;
 use tetra_saps::control::sds::CmceSdsData;
 use uuid::Uuid;
 
@@ -43,7 +44,9 @@ struct ActiveCall {
     uuid: Uuid,
     /// TETRA call identifier (14-bit) - None until NetworkCallReady received
     call_id: Option<u16>,
-    /// Allocated timeslot (2-4) - None until NetworkCallReady received
+    /// Carrier of the allocated timeslot, 0 for the main carrier
+    carrier: u8,
+    /// Allocated timeslot - None until NetworkCallReady received
     ts: Option<u8>,
     /// Usage number for the channel allocation - None until NetworkCallReady received
     usage: Option<u8>,
@@ -62,7 +65,9 @@ struct HangingCall {
     uuid: Uuid,
     /// TETRA call identifier (14-bit)
     call_id: u16,
-    /// Allocated timeslot (2-4)
+    /// Carrier of the allocated timeslot
+    carrier: u8,
+    /// Allocated timeslot
     ts: u8,
     /// Usage number for the channel allocation
     usage: u8,
@@ -248,12 +253,15 @@ pub struct BrewEntity {
     /// new speaker or timeout. Only one hanging call per GSSI.
     hanging_calls: HashMap<u32, HangingCall>,
 
-    /// UL calls being forwarded to TetraPack, keyed by timeslot
-    ul_forwarded: HashMap<u8, UlForwardedCall>,
+    /// UL calls being forwarded to TetraPack, keyed by (carrier, timeslot)
+    ul_forwarded: HashMap<(u8, u8), UlForwardedCall>,
 
     /// Registered subscriber groups (ISSI -> set of GSSIs)
     subscriber_groups: HashMap<u32, HashSet<u32>>,
 
+    /// Brew session UUIDs for packet data of local terminals, keyed by ISSI
+    packet_sessions: HashMap<u32, Uuid>,
+
     /// Whether the worker is connected
     connected: bool,
 
@@ -294,6 +302,7 @@ impl BrewEntity {
             hanging_calls: HashMap::new(),
             ul_forwarded: HashMap::new(),
             subscriber_groups: HashMap::new(),
+            packet_sessions: HashMap::new(),
             connected: false,
             worker_handle: Some(handle),
         }
@@ -343,6 +352,12 @@ impl BrewEntity {
                 BrewEvent::SdsReport { uuid, status } => {
                     tracing::debug!("BrewEntity: SDS report uuid={} status={}", uuid, status);
                 }
+                BrewEvent::DtmfData { uuid, digits } => {
+                    self.handle_dtmf(queue, uuid, digits);
+                }
+                BrewEvent::PacketData { uuid, data } => {
+                    self.handle_packet_data(queue, uuid, data);
+                }
                 BrewEvent::SubscriberEvent { msg_type, issi, groups } => {
                     tracing::debug!("BrewEntity: subscriber event type={} issi={} groups={:?}", msg_type, issi, groups);
                 }
@@ -513,6 +528,7 @@ impl BrewEntity {
             let call = ActiveCall {
                 uuid,
                 call_id: None, // Set by NetworkCallReady
+                carrier: 0,    // Set by NetworkCallReady
                 ts: None,      // Set by NetworkCallReady
                 usage: None,   // Set by NetworkCallReady
                 source_issi,
@@ -552,6 +568,7 @@ impl BrewEntity {
         let call = ActiveCall {
             uuid,
             call_id: None, // Set by NetworkCallReady
+            carrier: 0,    // Set by NetworkCallReady
             ts: None,      // Set by NetworkCallReady
             usage: None,   // Set by NetworkCallReady
             source_issi,
@@ -609,6 +626,7 @@ impl BrewEntity {
                 HangingCall {
                     uuid,
                     call_id,
+                    carrier: call.carrier,
                     ts,
                     usage,
                     source_issi: call.source_issi,
@@ -689,7 +707,7 @@ impl BrewEntity {
             return;
         }
 
-        let mut to_send: Vec<(u8, Uuid, usize, JitterFrame)> = Vec::new();
+        let mut to_send: Vec<((u8, u8), Uuid, usize, JitterFrame)> = Vec::new();
 
         for (uuid, call) in &self.active_calls {
             let Some(ts) = call.ts else {
@@ -703,14 +721,15 @@ impl BrewEntity {
             };
             jitter.maybe_warn_unhealthy(*uuid);
             if let Some(frame) = jitter.pop_ready() {
-                to_send.push((ts, *uuid, jitter.target_frames(), frame));
+                to_send.push(((call.carrier, ts), *uuid, jitter.target_frames(), frame));
             }
         }
 
-        for (ts, uuid, target_frames, frame) in to_send {
+        for ((carrier, ts), uuid, target_frames, frame) in to_send {
             tracing::trace!(
-                "BrewEntity: playout uuid={} ts={} rx_seq={} age_ms={} target_frames={}",
+                "BrewEntity: playout uuid={} carrier={} ts={} rx_seq={} age_ms={} target_frames={}",
                 uuid,
+                carrier,
                 ts,
                 frame.rx_seq,
                 frame.rx_at.elapsed().as_millis(),
@@ -722,6 +741,7 @@ impl BrewEntity {
                 dest: TetraEntity::Umac,
                 dltime: self.dltime,
                 msg: SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq {
+                    carrier,
                     ts,
                     data: frame.acelp_data,
                 }),
@@ -750,11 +770,12 @@ impl BrewEntity {
     }
 
     /// Handle NetworkCallReady response from CMCE
-    fn rx_network_call_ready(&mut self, brew_uuid: Uuid, call_id: u16, ts: u8, usage: u8) {
+    fn rx_network_call_ready(&mut self, brew_uuid: Uuid, call_id: u16, carrier: u8, ts: u8, usage: u8) {
         tracing::info!(
-            "BrewEntity: network call ready uuid={} call_id={} ts={} usage={}",
+            "BrewEntity: network call ready uuid={} call_id={} carrier={} ts={} usage={}",
             brew_uuid,
             call_id,
+            carrier,
             ts,
             usage
         );
@@ -762,6 +783,7 @@ impl BrewEntity {
         // Update active call with CMCE-allocated resources
         if let Some(call) = self.active_calls.get_mut(&brew_uuid) {
             call.call_id = Some(call_id);
+            call.carrier = carrier;
             call.ts = Some(ts);
             call.usage = Some(usage);
         } else {
@@ -819,22 +841,24 @@ impl TetraEntityTrait for BrewEntity {
         match message.msg {
             // UL voice from UMAC — forward to TetraPack if this timeslot is being forwarded
             SapMsgInner::TmdCircuitDataInd(prim) => {
-                self.handle_ul_voice(prim.ts, prim.data);
+                self.handle_ul_voice((prim.carrier, prim.ts), prim.data);
             }
             // Floor-control and call lifecycle notifications from CMCE
             SapMsgInner::CmceCallControl(CallControl::FloorGranted {
                 call_id,
                 source_issi,
                 dest_gssi,
+                carrier,
                 ts,
+                priority,
             }) => {
-                self.handle_local_call_start(call_id, source_issi, dest_gssi, ts);
+                self.handle_local_call_start(call_id, source_issi, dest_gssi, (carrier, ts), priority);
             }
-            SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }) => {
-                self.handle_local_call_tx_stopped(call_id, ts);
+            SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }) => {
+                self.handle_local_call_tx_stopped(call_id, (carrier, ts));
             }
-            SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }) => {
-                self.handle_local_call_end(call_id, ts);
+            SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, carrier, ts }) => {
+                self.handle_local_call_end(call_id, (carrier, ts));
             }
             SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }) => {
                 self.drop_network_call(brew_uuid);
@@ -842,10 +866,11 @@ impl TetraEntityTrait for BrewEntity {
             SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
                 brew_uuid,
                 call_id,
+                carrier,
                 ts,
                 usage,
             }) => {
-                self.rx_network_call_ready(brew_uuid, call_id, ts, usage);
+                self.rx_network_call_ready(brew_uuid, call_id, carrier, ts, usage);
             }
             // UlInactivityTimeout is UMAC→CMCE only; Brew handles FloorReleased instead
             SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { .. }) => {}
@@ -855,6 +880,12 @@ impl TetraEntityTrait for BrewEntity {
             SapMsgInner::CmceSdsData(sds) => {
                 self.handle_sds_send(sds);
             }
+            SapMsgInner::CmceCallControl(CallControl::Dtmf { call_id, digits }) => {
+                self.handle_dtmf_send(call_id, digits);
+            }
+            SapMsgInner::SndcpPacketData(data) => {
+                self.handle_packet_data_send(data);
+            }
             _ => {
                 tracing::debug!("BrewEntity: unexpected rx_prim from {:?} on {:?}", message.src, message.sap);
             }
@@ -867,7 +898,8 @@ impl TetraEntityTrait for BrewEntity {
 impl BrewEntity {
     /// Handle notification that a local UL group call has started.
     /// If the group is subscribed (in config.groups), start forwarding to TetraPack.
-    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8) {
+    fn handle_local_call_start(&mut self, call_id: u16, source_issi: u32, dest_gssi: u32, chan: (u8, u8), priority: u8) {
+        let (carrier, ts) = chan;
         if !self.connected {
             tracing::trace!("BrewEntity: not connected, ignoring local call start");
             return;
@@ -891,10 +923,11 @@ impl BrewEntity {
         // }
 
         // If we're already forwarding on this timeslot, treat as a talker change/update
-        if let Some(fwd) = self.ul_forwarded.get_mut(&ts) {
+        if let Some(fwd) = self.ul_forwarded.get_mut(&chan) {
             if fwd.call_id != call_id || fwd.dest_gssi != dest_gssi {
                 tracing::warn!(
-                    "BrewEntity: updating forwarded call on ts={} (was call_id={} gssi={}) -> (call_id={} gssi={})",
+                    "BrewEntity: updating forwarded call on carrier={} ts={} (was call_id={} gssi={}) -> (call_id={} gssi={})",
+                    carrier,
                     ts,
                     fwd.call_id,
                     fwd.dest_gssi,
@@ -913,7 +946,7 @@ impl BrewEntity {
                 uuid: fwd.uuid,
                 source_issi,
                 dest_gssi,
-                priority: 0,
+                priority,
                 service: 0, // TETRA encoded speech
             });
             return;
@@ -922,10 +955,11 @@ impl BrewEntity {
         // Generate a UUID for this Brew session
         let uuid = Uuid::new_v4();
         tracing::info!(
-            "BrewEntity: forwarding local call to TetraPack: call_id={} src={} gssi={} ts={} uuid={}",
+            "BrewEntity: forwarding local call to TetraPack: call_id={} src={} gssi={} carrier={} ts={} uuid={}",
             call_id,
             source_issi,
             dest_gssi,
+            carrier,
             ts,
             uuid
         );
@@ -935,13 +969,13 @@ impl BrewEntity {
             uuid,
             source_issi,
             dest_gssi,
-            priority: 0,
+            priority,
             service: 0, // TETRA encoded speech
         });
 
         // Track this forwarded call
         self.ul_forwarded.insert(
-            ts,
+            chan,
             UlForwardedCall {
                 uuid,
                 call_id,
@@ -953,12 +987,13 @@ impl BrewEntity {
     }
 
     /// Handle notification that a local UL call has ended.
-    fn handle_local_call_tx_stopped(&mut self, call_id: u16, ts: u8) {
-        if let Some(fwd) = self.ul_forwarded.remove(&ts) {
+    fn handle_local_call_tx_stopped(&mut self, call_id: u16, chan: (u8, u8)) {
+        if let Some(fwd) = self.ul_forwarded.remove(&chan) {
             if fwd.call_id != call_id {
                 tracing::warn!(
-                    "BrewEntity: call_id mismatch on ts={}: expected {} got {}",
-                    ts,
+                    "BrewEntity: call_id mismatch on carrier={} ts={}: expected {} got {}",
+                    chan.0,
+                    chan.1,
                     fwd.call_id,
                     call_id
                 );
@@ -975,13 +1010,14 @@ impl BrewEntity {
         }
     }
 
-    fn handle_local_call_end(&mut self, call_id: u16, ts: u8) {
+    fn handle_local_call_end(&mut self, call_id: u16, chan: (u8, u8)) {
         // Check if ul_forwarded entry still exists (might have been removed by handle_local_call_tx_stopped)
-        if let Some(fwd) = self.ul_forwarded.remove(&ts) {
+        if let Some(fwd) = self.ul_forwarded.remove(&chan) {
             if fwd.call_id != call_id {
                 tracing::warn!(
-                    "BrewEntity: call_id mismatch on ts={}: expected {} got {}",
-                    ts,
+                    "BrewEntity: call_id mismatch on carrier={} ts={}: expected {} got {}",
+                    chan.0,
+                    chan.1,
                     fwd.call_id,
                     call_id
                 );
@@ -992,14 +1028,18 @@ impl BrewEntity {
                 fwd.frame_count
             );
         } else {
-            tracing::debug!("BrewEntity: local call ended on ts={} (already cleaned up during tx_stopped)", ts);
+            tracing::debug!(
+                "BrewEntity: local call ended on carrier={} ts={} (already cleaned up during tx_stopped)",
+                chan.0,
+                chan.1
+            );
         }
     }
 
     /// Handle UL voice data from UMAC. If the timeslot is being forwarded to TetraPack,
     /// convert to STE format and send.
-    fn handle_ul_voice(&mut self, ts: u8, acelp_bits: Vec<u8>) {
-        let Some(fwd) = self.ul_forwarded.get_mut(&ts) else {
+    fn handle_ul_voice(&mut self, chan: (u8, u8), acelp_bits: Vec<u8>) {
+        let Some(fwd) = self.ul_forwarded.get_mut(&chan) else {
             return; // Not forwarded to TetraPack
         };
 
@@ -1138,6 +1178,109 @@ impl BrewEntity {
     }
 }
 
+// ─── DTMF and packet data handling ────────────────────────────────
+
+impl BrewEntity {
+    /// Find the TETRA call identifier of a Brew session, whether it is transmitting, in hangtime or forwarded from UL
+    fn call_id_for_uuid(&self, uuid: Uuid) -> Option<u16> {
+        if let Some(call) = self.active_calls.get(&uuid) {
+            return call.call_id;
+        }
+        self.hanging_calls
+            .values()
+            .find(|call| call.uuid == uuid)
+            .map(|call| call.call_id)
+            .or_else(|| self.ul_forwarded.values().find(|call| call.uuid == uuid).map(|call| call.call_id))
+    }
+
+    /// Find the Brew session UUID of a TETRA call, preferring the UL call currently forwarded to Brew
+    fn uuid_for_call_id(&self, call_id: u16) -> Option<Uuid> {
+        self.ul_forwarded
+            .values()
+            .find(|call| call.call_id == call_id)
+            .map(|call| call.uuid)
+            .or_else(|| {
+                self.active_calls
+                    .values()
+                    .find(|call| call.call_id == Some(call_id))
+                    .map(|call| call.uuid)
+            })
+            .or_else(|| {
+                self.hanging_calls
+                    .values()
+                    .find(|call| call.call_id == call_id)
+                    .map(|call| call.uuid)
+            })
+    }
+
+    /// Handle DTMF digits from Brew, delivered by CMCE to the group in D-INFO
+    fn handle_dtmf(&mut self, queue: &mut MessageQueue, uuid: Uuid, digits: String) {
+        let Some(call_id) = self.call_id_for_uuid(uuid) else {
+            tracing::debug!("BrewEntity: DTMF {:?} for unknown or unready call uuid={}, dropping", digits, uuid);
+            return;
+        };
+        tracing::info!("BrewEntity: DTMF {:?} uuid={} -> call_id={}", digits, uuid, call_id);
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Cmce,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::Dtmf { call_id, digits }),
+        });
+    }
+
+    /// Handle DTMF digits sent by a local MS within a group call
+    fn handle_dtmf_send(&self, call_id: u16, digits: String) {
+        if !self.connected {
+            tracing::debug!("BrewEntity: not connected, dropping DTMF for call_id={}", call_id);
+            return;
+        }
+        let Some(uuid) = self.uuid_for_call_id(call_id) else {
+            tracing::debug!("BrewEntity: call_id={} not bridged to Brew, dropping DTMF {:?}", call_id, digits);
+            return;
+        };
+        tracing::info!("BrewEntity: sending DTMF {:?} call_id={} uuid={}", digits, call_id, uuid);
+        let _ = self.command_sender.send(BrewCommand::SendDtmf { uuid, digits });
+    }
+
+    /// Handle an IP packet from Brew, delivered by SNDCP to the terminal owning its destination address
+    fn handle_packet_data(&mut self, queue: &mut MessageQueue, uuid: Uuid, data: Vec<u8>) {
+        let issi = self
+            .packet_sessions
+            .iter()
+            .find(|(_, session)| **session == uuid)
+            .map(|(issi, _)| *issi);
+        tracing::debug!("BrewEntity: packet data uuid={} issi={:?} {} bytes", uuid, issi, data.len());
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Sndcp,
+            dltime: self.dltime,
+            msg: SapMsgInner::SndcpPacketData(SndcpPacketData { issi, packet: data }),
+        });
+    }
+
+    /// Handle an IP packet sent by a local MS, forwarded in the packet data session of that MS
+    fn handle_packet_data_send(&mut self, data: SndcpPacketData) {
+        if !self.connected {
+            tracing::debug!("BrewEntity: not connected, dropping packet data from ISSI {:?}", data.issi);
+            return;
+        }
+        let Some(issi) = data.issi else {
+            tracing::warn!("BrewEntity: packet data without source ISSI, dropping");
+            return;
+        };
+        let uuid = *self.packet_sessions.entry(issi).or_insert_with(Uuid::new_v4);
+        tracing::debug!(
+            "BrewEntity: sending packet data issi={} uuid={} {} bytes",
+            issi,
+            uuid,
+            data.packet.len()
+        );
+        let _ = self.command_sender.send(BrewCommand::SendPacketData { uuid, data: data.packet });
+    }
+}
+
 impl Drop for BrewEntity {
     fn drop(&mut self) {
         tracing::debug!("BrewEntity: shutting down, sending graceful disconnect");
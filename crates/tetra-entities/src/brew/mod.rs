@@ -5,6 +5,8 @@ pub mod entity;
 pub mod protocol;
 pub mod worker;
 
+pub use components::brew_routable::feature_dtmf_enabled;
+pub use components::brew_routable::feature_packet_data_enabled;
 pub use components::brew_routable::feature_sds_enabled;
 /// Convenience re-export of commonly externally used functions
 pub use components::brew_routable::is_active;
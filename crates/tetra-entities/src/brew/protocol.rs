@@ -495,6 +495,30 @@ pub fn build_sds_report(session_uuid: &Uuid, status: u8) -> Vec<u8> {
     buf
 }
 
+/// Build a FRAME_TYPE_DTMF_DATA message, digits as ASCII characters
+pub fn build_dtmf_frame(session_uuid: &Uuid, digits: &str) -> Vec<u8> {
+    // kind(1) + type(1) + uuid(16) + length(2) + digits = 20 + digits.len()
+    let mut buf = Vec::with_capacity(20 + digits.len());
+    buf.push(BREW_CLASS_FRAME);
+    buf.push(FRAME_TYPE_DTMF_DATA);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    write_u16_le(&mut buf, (digits.len() * 8) as u16);
+    buf.extend_from_slice(digits.as_bytes());
+    buf
+}
+
+/// Build a FRAME_TYPE_PACKET_DATA message carrying a single IP packet
+pub fn build_packet_data_frame(session_uuid: &Uuid, data: &[u8]) -> Vec<u8> {
+    // kind(1) + type(1) + uuid(16) + length(2) + data = 20 + data.len()
+    let mut buf = Vec::with_capacity(20 + data.len());
+    buf.push(BREW_CLASS_FRAME);
+    buf.push(FRAME_TYPE_PACKET_DATA);
+    buf.extend_from_slice(session_uuid.as_bytes());
+    write_u16_le(&mut buf, (data.len() * 8) as u16);
+    buf.extend_from_slice(data);
+    buf
+}
+
 /// Build a service query (query subscriber profiles)
 pub fn build_query_subscribers(issis: &[u32]) -> Vec<u8> {
     let json = serde_json::to_string(issis).unwrap_or_else(|_| "[]".to_string());
@@ -662,4 +686,37 @@ mod tests {
             panic!("Expected Frame message");
         }
     }
+
+    #[test]
+    fn test_build_parse_dtmf_frame() {
+        let uuid = Uuid::new_v4();
+        let built = build_dtmf_frame(&uuid, "91#");
+
+        let msg = parse_brew_message(&built).unwrap();
+        if let BrewMessage::Frame(frame) = msg {
+            assert_eq!(frame.frame_type, FRAME_TYPE_DTMF_DATA);
+            assert_eq!(frame.identifier, uuid);
+            assert_eq!(frame.length_bits, 24);
+            assert_eq!(frame.data, b"91#".to_vec());
+        } else {
+            panic!("Expected Frame message");
+        }
+    }
+
+    #[test]
+    fn test_build_parse_packet_data_frame() {
+        let uuid = Uuid::new_v4();
+        let payload = vec![0x45, 0x00, 0x00, 0x14];
+        let built = build_packet_data_frame(&uuid, &payload);
+
+        let msg = parse_brew_message(&built).unwrap();
+        if let BrewMessage::Frame(frame) = msg {
+            assert_eq!(frame.frame_type, FRAME_TYPE_PACKET_DATA);
+            assert_eq!(frame.identifier, uuid);
+            assert_eq!(frame.length_bits, 32);
+            assert_eq!(frame.data, payload);
+        } else {
+            panic!("Expected Frame message");
+        }
+    }
 }
@@ -9,6 +9,7 @@ use std::time::{Duration, Instant};
 use crossbeam_channel::{Receiver, Sender};
 use tetra_config::bluestation::CfgBrew;
 use tetra_config::bluestation::SharedConfig;
+use tetra_pdus::cmce::fields::dtmf::DTMF_DIGITS;
 use tungstenite::{Message, WebSocket, stream::MaybeTlsStream};
 use uuid::Uuid;
 
@@ -60,6 +61,12 @@ pub enum BrewEvent {
     /// SDS report received
     SdsReport { uuid: Uuid, status: u8 },
 
+    /// DTMF digits received within a call
+    DtmfData { uuid: Uuid, digits: String },
+
+    /// IP packet received for the SNDCP side
+    PacketData { uuid: Uuid, data: Vec<u8> },
+
     /// Error from server
     ServerError { error_type: u8, data: Vec<u8> },
 }
@@ -106,6 +113,12 @@ pub enum BrewCommand {
     /// Send SDS report to Brew (delivery acknowledgement)
     SendSdsReport { uuid: Uuid, status: u8 },
 
+    /// Send DTMF digits within a call to Brew
+    SendDtmf { uuid: Uuid, digits: String },
+
+    /// Send an IP packet from a local MS to Brew
+    SendPacketData { uuid: Uuid, data: Vec<u8> },
+
     /// Disconnect gracefully
     Disconnect,
 }
@@ -742,6 +755,34 @@ impl BrewWorker {
                             tracing::debug!("BrewWorker: sent SDS_REPORT uuid={} status={}", uuid, status);
                         }
                     }
+                    BrewCommand::SendDtmf { uuid, digits } => {
+                        if !brew::feature_dtmf_enabled(&self.config) {
+                            tracing::warn!("BrewWorker: ignoring SendDtmf command because DTMF over Brew is disabled in config");
+                            continue;
+                        }
+
+                        let msg = build_dtmf_frame(&uuid, &digits);
+                        if let Err(e) = ws.send(Message::Binary(msg)) {
+                            tracing::error!("BrewWorker: failed to send DTMF_DATA: {}", e);
+                        } else {
+                            tracing::debug!("BrewWorker: sent DTMF_DATA uuid={} digits={}", uuid, digits);
+                        }
+                    }
+                    BrewCommand::SendPacketData { uuid, data } => {
+                        if !brew::feature_packet_data_enabled(&self.config) {
+                            tracing::warn!(
+                                "BrewWorker: ignoring SendPacketData command because packet data over Brew is disabled in config"
+                            );
+                            continue;
+                        }
+
+                        let msg = build_packet_data_frame(&uuid, &data);
+                        if let Err(e) = ws.send(Message::Binary(msg)) {
+                            tracing::error!("BrewWorker: failed to send PACKET_DATA: {}", e);
+                        } else {
+                            tracing::debug!("BrewWorker: sent PACKET_DATA uuid={} {} bytes", uuid, data.len());
+                        }
+                    }
                     BrewCommand::Disconnect => {
                         self.graceful_teardown(ws);
                         return Ok(());
@@ -913,6 +954,46 @@ impl BrewWorker {
                     status,
                 });
             }
+            FRAME_TYPE_DTMF_DATA => {
+                if !brew::feature_dtmf_enabled(&self.config) {
+                    tracing::debug!("BrewWorker: ignoring incoming DTMF_DATA because DTMF over Brew is disabled in config");
+                    return;
+                }
+
+                // Digits are sent as ASCII characters, drop anything that can't be signalled as DTMF
+                let digits: String = frame
+                    .data
+                    .iter()
+                    .map(|d| d.to_ascii_uppercase())
+                    .filter(|d| DTMF_DIGITS.contains(d))
+                    .map(char::from)
+                    .collect();
+                if digits.is_empty() {
+                    tracing::warn!(
+                        "BrewWorker: DTMF_DATA uuid={} without valid digits: {:?}",
+                        frame.identifier,
+                        frame.data
+                    );
+                    return;
+                }
+                tracing::debug!("BrewWorker: DTMF_DATA uuid={} digits={}", frame.identifier, digits);
+                let _ = self.event_sender.send(BrewEvent::DtmfData {
+                    uuid: frame.identifier,
+                    digits,
+                });
+            }
+            FRAME_TYPE_PACKET_DATA => {
+                if !brew::feature_packet_data_enabled(&self.config) {
+                    tracing::debug!("BrewWorker: ignoring incoming PACKET_DATA because packet data over Brew is disabled in config");
+                    return;
+                }
+
+                tracing::debug!("BrewWorker: PACKET_DATA uuid={} {} bytes", frame.identifier, frame.data.len());
+                let _ = self.event_sender.send(BrewEvent::PacketData {
+                    uuid: frame.identifier,
+                    data: frame.data,
+                });
+            }
             ft => {
                 tracing::debug!("BrewWorker: unhandled frame type {} uuid={}", ft, frame.identifier);
             }
@@ -0,0 +1,151 @@
+use tetra_core::{BitBuffer, TdmaTime};
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+/// UDP port Wireshark dissects as GSMTAP
+pub const GSMTAP_UDP_PORT: u16 = 4729;
+
+const GSMTAP_VERSION: u8 = 2;
+const GSMTAP_HDR_LEN: usize = 16;
+const GSMTAP_TYPE_TETRA_I1: u8 = 0x05;
+const GSMTAP_ARFCN_F_UPLINK: u16 = 0x4000;
+
+/// GSMTAP TETRA channel sub types, as understood by Wireshark's TETRA dissector
+const GSMTAP_TETRA_BSCH: u8 = 0x01;
+const GSMTAP_TETRA_AACH: u8 = 0x02;
+const GSMTAP_TETRA_SCH_HU: u8 = 0x03;
+const GSMTAP_TETRA_SCH_HD: u8 = 0x04;
+const GSMTAP_TETRA_SCH_F: u8 = 0x05;
+const GSMTAP_TETRA_BNCH: u8 = 0x06;
+const GSMTAP_TETRA_STCH: u8 = 0x07;
+const GSMTAP_TETRA_TCH_F: u8 = 0x08;
+
+/// Sub type for PDUs not carried on a single logical channel, like TM-SDUs. Wireshark shows their contents as data.
+pub const GSMTAP_TETRA_NONE: u8 = 0x00;
+
+/// Returns the GSMTAP sub type for a logical channel, or None for the linearization channels, which carry no data
+pub fn tetra_sub_type(lchan: LogicalChannel) -> Option<u8> {
+    match lchan {
+        LogicalChannel::Bsch => Some(GSMTAP_TETRA_BSCH),
+        LogicalChannel::Aach => Some(GSMTAP_TETRA_AACH),
+        LogicalChannel::SchHd => Some(GSMTAP_TETRA_SCH_HD),
+        LogicalChannel::SchHu => Some(GSMTAP_TETRA_SCH_HU),
+        LogicalChannel::SchF => Some(GSMTAP_TETRA_SCH_F),
+        LogicalChannel::Bnch => Some(GSMTAP_TETRA_BNCH),
+        LogicalChannel::Stch => Some(GSMTAP_TETRA_STCH),
+        LogicalChannel::TchS | LogicalChannel::Tch24 | LogicalChannel::Tch48 | LogicalChannel::Tch72 => Some(GSMTAP_TETRA_TCH_F),
+        LogicalChannel::Blch | LogicalChannel::Clch => None,
+    }
+}
+
+/// GSMTAP version 2 header for a TETRA PDU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GsmtapHeader {
+    /// Timeslot, from 1 to 4
+    pub timeslot: u8,
+    /// Carrier number, 14 bits
+    pub arfcn: u16,
+    pub uplink: bool,
+    pub frame_number: u32,
+    pub sub_type: u8,
+    /// 0 for the first half slot or a full slot, 1 for the second half slot
+    pub sub_slot: u8,
+}
+
+impl GsmtapHeader {
+    /// Frame number counting from frame 1 of multiframe 1 of hyperframe 0
+    pub fn frame_number(time: TdmaTime) -> u32 {
+        (time.h as u32 * 60 + (time.m as u32).saturating_sub(1)) * 18 + (time.f as u32).saturating_sub(1)
+    }
+
+    pub fn to_bytes(&self) -> [u8; GSMTAP_HDR_LEN] {
+        let arfcn = (self.arfcn & 0x3FFF) | if self.uplink { GSMTAP_ARFCN_F_UPLINK } else { 0 };
+        let mut hdr = [0u8; GSMTAP_HDR_LEN];
+        hdr[0] = GSMTAP_VERSION;
+        hdr[1] = (GSMTAP_HDR_LEN / 4) as u8;
+        hdr[2] = GSMTAP_TYPE_TETRA_I1;
+        hdr[3] = self.timeslot;
+        hdr[4..6].copy_from_slice(&arfcn.to_be_bytes());
+        // Signal level and SNR are left 0
+        hdr[8..12].copy_from_slice(&self.frame_number.to_be_bytes());
+        hdr[12] = self.sub_type;
+        hdr[14] = self.sub_slot;
+        hdr
+    }
+}
+
+/// Packs the bits in the window of buf into bytes, most significant bit first, zero padding the last byte
+pub fn pack_bits(buf: &BitBuffer) -> Vec<u8> {
+    let len = buf.get_len();
+    let mut ret = Vec::with_capacity(len.div_ceil(8));
+    let mut offset = 0;
+    while offset < len {
+        let n = (len - offset).min(8);
+        let bits = buf.peek_bits_startoffset(offset, n).unwrap(); // Never fails
+        ret.push((bits << (8 - n)) as u8);
+        offset += n;
+    }
+    ret
+}
+
+/// Builds a GSMTAP packet in an IPv4/UDP datagram from and to localhost, as Wireshark expects GSMTAP over UDP
+pub fn build_ipv4_packet(hdr: &GsmtapHeader, payload: &[u8]) -> Vec<u8> {
+    let udp_len = 8 + GSMTAP_HDR_LEN + payload.len();
+    let ip_len = 20 + udp_len;
+
+    let mut pkt = Vec::with_capacity(ip_len);
+    pkt.extend_from_slice(&[0x45, 0x00]); // IPv4, 20 byte header
+    pkt.extend_from_slice(&(ip_len as u16).to_be_bytes());
+    pkt.extend_from_slice(&[0x00, 0x00, 0x40, 0x00]); // Id 0, don't fragment
+    pkt.extend_from_slice(&[64, 17, 0x00, 0x00]); // TTL, UDP, checksum filled in below
+    pkt.extend_from_slice(&[127, 0, 0, 1]);
+    pkt.extend_from_slice(&[127, 0, 0, 1]);
+    let checksum = ipv4_checksum(&pkt);
+    pkt[10..12].copy_from_slice(&checksum.to_be_bytes());
+
+    pkt.extend_from_slice(&GSMTAP_UDP_PORT.to_be_bytes());
+    pkt.extend_from_slice(&GSMTAP_UDP_PORT.to_be_bytes());
+    pkt.extend_from_slice(&(udp_len as u16).to_be_bytes());
+    pkt.extend_from_slice(&[0x00, 0x00]); // UDP checksum is optional over IPv4
+    pkt.extend_from_slice(&hdr.to_bytes());
+    pkt.extend_from_slice(payload);
+    pkt
+}
+
+fn ipv4_checksum(hdr: &[u8]) -> u16 {
+    let mut sum: u32 = hdr.chunks(2).map(|w| u16::from_be_bytes([w[0], w[1]]) as u32).sum();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gsmtap_packet() {
+        let hdr = GsmtapHeader {
+            timeslot: 2,
+            arfcn: 1521,
+            uplink: true,
+            frame_number: GsmtapHeader::frame_number(TdmaTime { h: 1, m: 2, f: 3, t: 2 }),
+            sub_type: GSMTAP_TETRA_SCH_HU,
+            sub_slot: 1,
+        };
+        assert_eq!(hdr.frame_number, 60 * 18 + 18 + 2);
+
+        let payload = pack_bits(&BitBuffer::from_bitstr("1010000111"));
+        assert_eq!(payload, vec![0b1010_0001, 0b1100_0000]);
+
+        let pkt = build_ipv4_packet(&hdr, &payload);
+        assert_eq!(pkt.len(), 20 + 8 + 16 + 2);
+        assert_eq!(ipv4_checksum(&pkt[..20]), 0);
+        assert_eq!(u16::from_be_bytes([pkt[22], pkt[23]]), GSMTAP_UDP_PORT);
+        assert_eq!(&pkt[28..32], &[GSMTAP_VERSION, 4, GSMTAP_TYPE_TETRA_I1, 2]);
+        assert_eq!(u16::from_be_bytes([pkt[32], pkt[33]]), 0x4000 | 1521);
+        assert_eq!(pkt[40], 0x03);
+        assert_eq!(pkt[42], 1);
+        assert_eq!(&pkt[44..], &payload[..]);
+    }
+}
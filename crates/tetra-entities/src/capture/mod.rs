@@ -0,0 +1,5 @@
+//! Capture of air interface PDUs to a pcapng file, as GSMTAP, for analysis in Wireshark
+
+pub mod gsmtap;
+pub mod pcapng;
+pub mod pdu_capture;
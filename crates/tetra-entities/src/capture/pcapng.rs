@@ -0,0 +1,125 @@
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Raw IPv4 packets, without link layer header
+pub const LINKTYPE_IPV4: u16 = 228;
+
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const OPT_END_OF_OPT: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const OPT_IF_NAME: u16 = 2;
+const OPT_SHB_USERAPPL: u16 = 4;
+
+/// Minimal pcapng writer: a single section with a single interface, timestamps in microseconds
+pub struct PcapngWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Writes the section header and the description of the interface all packets are captured on
+    pub fn new(mut out: W, linktype: u16, if_name: &str, application: &str) -> io::Result<Self> {
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes()); // Major version
+        shb.extend_from_slice(&0u16.to_le_bytes()); // Minor version
+        shb.extend_from_slice(&(-1i64).to_le_bytes()); // Section length not specified
+        push_option(&mut shb, OPT_SHB_USERAPPL, application.as_bytes());
+        push_option(&mut shb, OPT_END_OF_OPT, &[]);
+        write_block(&mut out, BLOCK_SECTION_HEADER, &shb)?;
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&linktype.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+        idb.extend_from_slice(&0u32.to_le_bytes()); // No snapshot length limit
+        push_option(&mut idb, OPT_IF_NAME, if_name.as_bytes());
+        push_option(&mut idb, OPT_END_OF_OPT, &[]);
+        write_block(&mut out, BLOCK_INTERFACE_DESCRIPTION, &idb)?;
+
+        Ok(Self { out })
+    }
+
+    /// Writes a packet captured at the given time, with an optional comment shown alongside it
+    pub fn write_packet(&mut self, time: SystemTime, data: &[u8], comment: Option<&str>) -> io::Result<()> {
+        let micros = time.duration_since(UNIX_EPOCH).map(|d| d.as_micros() as u64).unwrap_or(0);
+
+        let mut epb = Vec::with_capacity(32 + data.len());
+        epb.extend_from_slice(&0u32.to_le_bytes()); // Interface id
+        epb.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(micros as u32).to_le_bytes());
+        epb.extend_from_slice(&(data.len() as u32).to_le_bytes()); // Captured length
+        epb.extend_from_slice(&(data.len() as u32).to_le_bytes()); // Original length
+        epb.extend_from_slice(data);
+        pad32(&mut epb);
+        if let Some(comment) = comment {
+            push_option(&mut epb, OPT_COMMENT, comment.as_bytes());
+            push_option(&mut epb, OPT_END_OF_OPT, &[]);
+        }
+        write_block(&mut self.out, BLOCK_ENHANCED_PACKET, &epb)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Writes a block around body, which must be padded to 32 bits already
+fn write_block(out: &mut impl Write, block_type: u32, body: &[u8]) -> io::Result<()> {
+    let total_len = (12 + body.len()) as u32;
+    out.write_all(&block_type.to_le_bytes())?;
+    out.write_all(&total_len.to_le_bytes())?;
+    out.write_all(body)?;
+    out.write_all(&total_len.to_le_bytes())
+}
+
+fn push_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_le_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+    pad32(buf);
+}
+
+fn pad32(buf: &mut Vec<u8>) {
+    buf.resize(buf.len().next_multiple_of(4), 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_block_structure() {
+        let mut out = Vec::new();
+        let mut writer = PcapngWriter::new(&mut out, LINKTYPE_IPV4, "tetra", "test").unwrap();
+        writer.write_packet(UNIX_EPOCH, &[1, 2, 3, 4, 5], Some("comment")).unwrap();
+        writer.write_packet(UNIX_EPOCH, &[6], None).unwrap();
+
+        // Walk the blocks, each starting and ending with its total length
+        let mut types = Vec::new();
+        let mut offset = 0;
+        while offset < out.len() {
+            let len = read_u32(&out, offset + 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(read_u32(&out, offset + len - 4) as usize, len);
+            types.push(read_u32(&out, offset));
+            offset += len;
+        }
+        assert_eq!(offset, out.len());
+        assert_eq!(
+            types,
+            vec![
+                BLOCK_SECTION_HEADER,
+                BLOCK_INTERFACE_DESCRIPTION,
+                BLOCK_ENHANCED_PACKET,
+                BLOCK_ENHANCED_PACKET
+            ]
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::SystemTime;
+
+use tetra_config::bluestation::{StackConfig, StackMode};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, TdmaTime};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::TmvUnitdataReq;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+use super::gsmtap::{self, GSMTAP_TETRA_NONE, GsmtapHeader};
+use super::pcapng::{LINKTYPE_IPV4, PcapngWriter};
+
+/// Writes the MAC blocks passing the TMV-SAP and the TM-SDUs passing the TMA-SAP to a pcapng file.
+/// Every packet carries a comment with the direction, TDMA time, logical channel and CRC status.
+pub struct PduCapture {
+    writer: PcapngWriter<Box<dyn Write>>,
+    stack_mode: StackMode,
+    main_carrier: u16,
+    secondary_carriers: Vec<u16>,
+}
+
+impl PduCapture {
+    /// Creates the capture file at path, replacing any existing file
+    pub fn create(path: &str, config: &StackConfig) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Self::new(Box::new(BufWriter::new(file)), config)
+    }
+
+    pub fn new(out: Box<dyn Write>, config: &StackConfig) -> io::Result<Self> {
+        let application = format!("tetra-bluestation {}", tetra_core::STACK_VERSION);
+        let writer = PcapngWriter::new(out, LINKTYPE_IPV4, "tetra", &application)?;
+        Ok(Self {
+            writer,
+            stack_mode: config.stack_mode,
+            main_carrier: config.cell.main_carrier,
+            secondary_carriers: config.cell.secondary_carriers.clone(),
+        })
+    }
+
+    /// Writes the PDUs carried by message, if it is a TMV-UNITDATA or TMA-UNITDATA primitive.
+    /// Must be called before the message is delivered, as the receiving entity moves the buffer position.
+    pub fn capture(&mut self, message: &SapMsg) -> io::Result<()> {
+        let is_bs = self.stack_mode == StackMode::Bs;
+        match &message.msg {
+            SapMsgInner::TmvUnitdataInd(prim) => {
+                let direction = match prim.direction {
+                    Direction::Ul | Direction::Dl => prim.direction,
+                    _ if is_bs => Direction::Ul,
+                    _ => Direction::Dl,
+                };
+                let sub_slot = match prim.block_num {
+                    PhyBlockNum::Block2 => 1,
+                    _ => 0,
+                };
+                self.write_mac_block(
+                    &prim.pdu,
+                    prim.logical_channel,
+                    message.dltime,
+                    prim.carrier,
+                    direction,
+                    sub_slot,
+                    Some(prim.crc_pass),
+                )
+            }
+            SapMsgInner::TmvUnitdataReq(slot) => {
+                let direction = if is_bs { Direction::Dl } else { Direction::Ul };
+                let blocks: [(&Option<TmvUnitdataReq>, u8); 3] = [(&slot.bbk, 0), (&slot.blk1, 0), (&slot.blk2, 1)];
+                for (blk, sub_slot) in blocks {
+                    if let Some(blk) = blk {
+                        self.write_mac_block(
+                            &blk.mac_block,
+                            blk.logical_channel,
+                            slot.ts,
+                            slot.carrier,
+                            direction,
+                            sub_slot,
+                            None,
+                        )?;
+                    }
+                }
+                Ok(())
+            }
+            SapMsgInner::TmaUnitdataInd(prim) => {
+                let Some(pdu) = &prim.pdu else {
+                    return Ok(());
+                };
+                let direction = if is_bs { Direction::Ul } else { Direction::Dl };
+                self.write_tm_sdu(pdu, message.dltime, direction, &format!("TMA-UNITDATA-IND {}", prim.main_address))
+            }
+            SapMsgInner::TmaUnitdataReq(prim) => {
+                let direction = if is_bs { Direction::Dl } else { Direction::Ul };
+                self.write_tm_sdu(
+                    &prim.pdu,
+                    message.dltime,
+                    direction,
+                    &format!("TMA-UNITDATA-REQ {}", prim.main_address),
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_mac_block(
+        &mut self,
+        block: &BitBuffer,
+        lchan: LogicalChannel,
+        time: TdmaTime,
+        carrier: u8,
+        direction: Direction,
+        sub_slot: u8,
+        crc_pass: Option<bool>,
+    ) -> io::Result<()> {
+        // Linearization blocks carry no data
+        let Some(sub_type) = gsmtap::tetra_sub_type(lchan) else {
+            return Ok(());
+        };
+        let hdr = GsmtapHeader {
+            timeslot: time.t,
+            arfcn: self.carrier_num(carrier),
+            uplink: direction == Direction::Ul,
+            frame_number: GsmtapHeader::frame_number(time),
+            sub_type,
+            sub_slot,
+        };
+        let crc = match crc_pass {
+            Some(true) => "CRC ok",
+            Some(false) => "CRC FAIL",
+            None => "tx",
+        };
+        let comment = format!(
+            "{:?} {} {:?} carrier {} blk {} {}",
+            direction,
+            format_time(time),
+            lchan,
+            carrier,
+            sub_slot + 1,
+            crc
+        );
+        self.write(&hdr, block, &comment)
+    }
+
+    fn write_tm_sdu(&mut self, sdu: &BitBuffer, time: TdmaTime, direction: Direction, what: &str) -> io::Result<()> {
+        let hdr = GsmtapHeader {
+            timeslot: time.t,
+            arfcn: self.main_carrier,
+            uplink: direction == Direction::Ul,
+            frame_number: GsmtapHeader::frame_number(time),
+            sub_type: GSMTAP_TETRA_NONE,
+            sub_slot: 0,
+        };
+        let comment = format!("{:?} {} {} len {}", direction, format_time(time), what, sdu.get_len());
+        self.write(&hdr, sdu, &comment)
+    }
+
+    fn write(&mut self, hdr: &GsmtapHeader, bits: &BitBuffer, comment: &str) -> io::Result<()> {
+        let pkt = gsmtap::build_ipv4_packet(hdr, &gsmtap::pack_bits(bits));
+        self.writer.write_packet(SystemTime::now(), &pkt, Some(comment))
+    }
+
+    /// Carrier number of carrier index, 0 being the main carrier
+    fn carrier_num(&self, carrier: u8) -> u16 {
+        match carrier {
+            0 => self.main_carrier,
+            n => self.secondary_carriers.get(n as usize - 1).copied().unwrap_or(self.main_carrier),
+        }
+    }
+}
+
+/// Same as the TdmaTime Display, without padding the hyperframe number
+fn format_time(time: TdmaTime) -> String {
+    format!("{}/{:02}/{:02}/{}", time.h, time.m, time.f, time.t)
+}
@@ -24,7 +24,7 @@ impl CmceBs {
             config: config.clone(),
             sds: SdsBsSubentity::new(config.clone()),
             cc: CcBsSubentity::new(config.clone()),
-            ss: SsBsSubentity::new(),
+            ss: SsBsSubentity::new(config.clone()),
         }
     }
 
@@ -63,8 +63,7 @@ impl CmceBs {
                 self.sds.route_rf_deliver(_queue, message);
             }
             CmcePduTypeUl::UFacility => {
-                unimplemented_log!("{:?}", pdu_type);
-                // self.ss.route_xx_deliver(_queue, message);
+                self.ss.route_re_deliver(_queue, message, &self.cc);
             }
             CmcePduTypeUl::CmceFunctionNotSupported => {
                 unimplemented_log!("{:?}", pdu_type);
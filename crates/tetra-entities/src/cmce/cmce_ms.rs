@@ -21,10 +21,10 @@ pub struct CmceMs {
 impl CmceMs {
     pub fn new(config: SharedConfig) -> Self {
         Self {
-            config,
-            sds: SdsMsSubentity::new(),
+            sds: SdsMsSubentity::new(config.clone()),
             cc: CcMsSubentity::new(),
             ss: SsMsSubentity::new(),
+            config,
         }
     }
 
@@ -68,7 +68,7 @@ impl CmceMs {
                 self.cc.route_rd_deliver(queue, message);
             }
             _ => {
-                panic!();
+                tracing::warn!("rx_unitdata_ind: unexpected {}", pdu_type);
             }
         }
     }
@@ -80,6 +80,7 @@ impl TetraEntityTrait for CmceMs {
     }
 
     fn set_config(&mut self, config: SharedConfig) {
+        self.sds.set_config(config.clone());
         self.config = config;
     }
 
@@ -87,15 +88,25 @@ impl TetraEntityTrait for CmceMs {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for CMCE
-        assert!(message.sap == Sap::LcmcSap);
-
-        match message.msg {
-            SapMsgInner::LcmcMleUnitdataInd(_) => {
-                self.rx_unitdata_ind(queue, message);
-            }
+        match message.sap {
+            Sap::LcmcSap => match message.msg {
+                SapMsgInner::LcmcMleUnitdataInd(_) => {
+                    self.rx_unitdata_ind(queue, message);
+                }
+                _ => {
+                    tracing::warn!("Unexpected message on LcmcSap: {:?}", message.msg);
+                }
+            },
+            Sap::Control => match message.msg {
+                SapMsgInner::CmceSdsData(_) => {
+                    self.sds.rx_sds_from_user(queue, message);
+                }
+                _ => {
+                    tracing::warn!("Unexpected control message: {:?}", message.msg);
+                }
+            },
             _ => {
-                panic!();
+                tracing::warn!("Unexpected SAP: {:?}", message.sap);
             }
         }
     }
@@ -0,0 +1,96 @@
+use tetra_core::TdmaTime;
+use tetra_saps::lcmc::CallId;
+
+/// Call set-up waiting for a free traffic timeslot
+pub struct QueuedCall<T> {
+    /// Call identifier, already announced to the calling party
+    pub call_id: CallId,
+    /// Call priority, 0 (lowest) to 15 (emergency)
+    pub priority: u8,
+    pub queued_at: TdmaTime,
+    /// When the calling party was last told the call is still queued
+    pub last_info: TdmaTime,
+    pub request: T,
+}
+
+/// Call set-ups waiting for a free traffic timeslot, served by priority and then in order of arrival
+pub struct CallQueue<T> {
+    /// Ordered by descending priority, oldest first within a priority
+    entries: Vec<QueuedCall<T>>,
+    max_length: usize,
+}
+
+impl<T> CallQueue<T> {
+    pub fn new(max_length: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            max_length,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Queues a call set-up. When the queue is full, the newest call of the lowest priority is
+    /// dropped to make room, unless that is the new call itself. The dropped call is returned.
+    pub fn push(&mut self, entry: QueuedCall<T>) -> Option<QueuedCall<T>> {
+        let dropped = if self.entries.len() >= self.max_length {
+            match self.entries.last() {
+                Some(last) if last.priority < entry.priority => self.entries.pop(),
+                _ => return Some(entry),
+            }
+        } else {
+            None
+        };
+        let pos = self.entries.partition_point(|e| e.priority >= entry.priority);
+        self.entries.insert(pos, entry);
+        dropped
+    }
+
+    /// The call set-up to serve first
+    pub fn peek(&self) -> Option<&QueuedCall<T>> {
+        self.entries.first()
+    }
+
+    pub fn pop(&mut self) -> Option<QueuedCall<T>> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.remove(0))
+        }
+    }
+
+    /// Whether a call of the given priority has to wait behind already queued calls
+    pub fn has_precedence_over(&self, priority: u8) -> bool {
+        self.entries.first().is_some_and(|e| e.priority >= priority)
+    }
+
+    /// Removes the first queued call matching the predicate
+    pub fn remove_where(&mut self, pred: impl Fn(&QueuedCall<T>) -> bool) -> Option<QueuedCall<T>> {
+        let pos = self.entries.iter().position(pred)?;
+        Some(self.entries.remove(pos))
+    }
+
+    /// Removes and returns all calls queued for longer than their time-out, in timeslots
+    pub fn take_expired(&mut self, now: TdmaTime, timeout: impl Fn(u8) -> i32) -> Vec<QueuedCall<T>> {
+        let mut expired = Vec::new();
+        let mut i = 0;
+        while i < self.entries.len() {
+            if self.entries[i].queued_at.age(now) > timeout(self.entries[i].priority) {
+                expired.push(self.entries.remove(i));
+            } else {
+                i += 1;
+            }
+        }
+        expired
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut QueuedCall<T>> {
+        self.entries.iter_mut()
+    }
+}
@@ -18,20 +18,20 @@ pub enum CircuitErr {
 }
 
 pub enum CircuitMgrCmd {
-    SendDSetup(CallId, u8, u8), // call id, usage number, timeslot
+    SendDSetup(CallId, u8, u8, u8), // call id, usage number, carrier, timeslot
     SendClose(CallId, CmceCircuit),
 }
 
 pub struct CircuitMgr {
     pub dltime: TdmaTime,
 
-    /// Holds any Dl and Dl+Ul circuits
-    pub dl: [Option<CmceCircuit>; 4],
-    /// Holds any Ul-only circuits, with no recipients on this cell
-    pub ul_only: [Option<CmceCircuit>; 4],
+    /// Holds any Dl and Dl+Ul circuits, per carrier
+    pub dl: Vec<[Option<CmceCircuit>; 4]>,
+    /// Holds any Ul-only circuits, with no recipients on this cell, per carrier
+    pub ul_only: Vec<[Option<CmceCircuit>; 4]>,
 
-    /// Data blocks queued to be transmitted, per timeslot
-    pub tx_data: [VecDeque<Vec<u8>>; 4],
+    /// Data blocks queued to be transmitted, per carrier and timeslot
+    pub tx_data: Vec<[VecDeque<Vec<u8>>; 4]>,
 
     /// 14-bit call identifier. Zero value is reserved.
     pub next_call_identifier: u16,
@@ -40,45 +40,50 @@ pub struct CircuitMgr {
 }
 
 impl CircuitMgr {
-    pub fn new() -> Self {
+    /// Creates a circuit manager for the main carrier plus the given number of secondary carriers
+    pub fn new(num_carriers: usize) -> Self {
         Self {
             dltime: TdmaTime::default(),
-            dl: [None, None, None, None],
-            ul_only: [None, None, None, None],
-            tx_data: [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            dl: (0..num_carriers).map(|_| [None, None, None, None]).collect(),
+            ul_only: (0..num_carriers).map(|_| [None, None, None, None]).collect(),
+            tx_data: (0..num_carriers)
+                .map(|_| [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()])
+                .collect(),
             next_call_identifier: 4,
             next_usage_number: 4,
         }
     }
 
-    /// Checks if a circuit is active on the given timeslot
+    /// Checks if a circuit is active on the given carrier and timeslot
     /// Returns (dl_active, ul_active)
-    pub fn is_active(&self, ts: u8) -> (bool, bool) {
-        match &self.dl[ts as usize - 1] {
+    pub fn is_active(&self, carrier: u8, ts: u8) -> (bool, bool) {
+        let (c, t) = (carrier as usize, ts as usize - 1);
+        match &self.dl[c][t] {
             Some(dl) => {
                 if dl.direction == Direction::Both {
                     (true, true)
                 } else {
-                    (true, self.ul_only[ts as usize - 1].is_some())
+                    (true, self.ul_only[c][t].is_some())
                 }
             }
-            None => (false, self.ul_only[ts as usize - 1].is_some()),
+            None => (false, self.ul_only[c][t].is_some()),
         }
     }
 
-    /// Checks if a circuit is active on the given timeslot and direction
+    /// Checks if a circuit is active on the given carrier, timeslot and direction
     /// Direction must be Dl or Ul
-    pub fn is_active_dir(&self, ts: u8, dir: Direction) -> bool {
+    pub fn is_active_dir(&self, carrier: u8, ts: u8, dir: Direction) -> bool {
+        let (c, t) = (carrier as usize, ts as usize - 1);
         match dir {
-            Direction::Dl => self.dl[ts as usize - 1].is_some(),
+            Direction::Dl => self.dl[c][t].is_some(),
             Direction::Ul => {
-                let dl_is_both = if let Some(dl) = &self.dl[ts as usize - 1] {
-                    assert!(self.ul_only[ts as usize - 1].is_none());
+                let dl_is_both = if let Some(dl) = &self.dl[c][t] {
+                    assert!(self.ul_only[c][t].is_none());
                     dl.direction == Direction::Both
                 } else {
                     false
                 };
-                self.ul_only[ts as usize - 1].is_some() || dl_is_both
+                self.ul_only[c][t].is_some() || dl_is_both
             }
 
             _ => panic!("can only use with specific ul/dl direction"),
@@ -86,19 +91,18 @@ impl CircuitMgr {
     }
 
     /// Gets the usage number of an active circuit, (Option<dl_usage>, Option<ul_usage>)
-    pub fn get_usage(&self, ts: u8) -> (Option<u8>, Option<u8>) {
-        let (dl_usage, dl_is_both) = if let Some(dl) = &self.dl[ts as usize - 1] {
+    pub fn get_usage(&self, carrier: u8, ts: u8) -> (Option<u8>, Option<u8>) {
+        let (c, t) = (carrier as usize, ts as usize - 1);
+        let (dl_usage, dl_is_both) = if let Some(dl) = &self.dl[c][t] {
             (Some(dl.usage), dl.direction == Direction::Both)
         } else {
             (None, false)
         };
         let ul_usage = if dl_is_both {
-            assert!(self.ul_only[ts as usize - 1].is_none());
+            assert!(self.ul_only[c][t].is_none());
             dl_usage
-        } else if let Some(ul) = &self.ul_only[ts as usize - 1] {
-            Some(ul.usage)
         } else {
-            None
+            self.ul_only[c][t].as_ref().map(|ul| ul.usage)
         };
         (dl_usage, ul_usage)
     }
@@ -121,17 +125,17 @@ impl CircuitMgr {
         usage
     }
 
-    /// Finds a free timeslot for the given direction (Ul, Dl or Both)
+    /// Finds a free timeslot on the main carrier for the given direction (Ul, Dl or Both)
     fn get_free_ts(&self, dir: Direction) -> Result<u8, CircuitErr> {
         // TODO FIXME we may do a bit smarter allocation here
         for ts in 2..=4 {
-            let (dl_active, ul_active) = self.is_active(ts);
+            let (dl_active, ul_active) = self.is_active(0, ts);
             match (dir, dl_active, ul_active) {
                 (Direction::Dl, false, _) => return Ok(ts),
                 (Direction::Ul, false, false) => return Ok(ts),
                 (Direction::Ul, true, false) => {
                     // Check if dl circuit covers Dl+Ul
-                    let dl = self.dl[ts as usize - 1].as_ref().unwrap();
+                    let dl = self.dl[0][ts as usize - 1].as_ref().unwrap();
                     if dl.direction != Direction::Both {
                         return Ok(ts);
                     }
@@ -153,6 +157,7 @@ impl CircuitMgr {
         let circuit = CmceCircuit {
             ts_created: self.dltime,
             direction: dir,
+            carrier: 0,
             ts,
             call_id,
             usage,
@@ -162,30 +167,32 @@ impl CircuitMgr {
             simplex_duplex: false,   // TODO, simplex only for now
             speech_service: Some(0), // TODO, only TETRA encoded speech for now
             etee_encrypted: false,   // TODO, no encryption for now
+            peer_ts: None,
         };
 
         // Register circuit and return
         Ok(self.open_circuit(dir, circuit)?)
     }
 
-    /// Allocate circuit using centralized timeslot allocator
+    /// Allocate circuit with an already assigned call id, using centralized timeslot allocator
     pub fn allocate_circuit_with_allocator(
         &mut self,
+        call_id: CallId,
         dir: Direction,
         comm_type: CommunicationType,
         timeslot_alloc: &mut TimeslotAllocator,
         owner: TimeslotOwner,
     ) -> Result<&CmceCircuit, CircuitErr> {
-        // Get timeslot from centralized allocator
-        let ts = timeslot_alloc.allocate_any(owner).ok_or(CircuitErr::NoCircuitFree)?;
+        // Get carrier and timeslot from centralized allocator
+        let (carrier, ts) = timeslot_alloc.allocate_traffic(owner).ok_or(CircuitErr::NoCircuitFree)?;
 
-        let call_id = self.get_next_call_id();
         let usage = self.get_next_usage_number();
 
         // Create circuit
         let circuit = CmceCircuit {
             ts_created: self.dltime,
             direction: dir,
+            carrier,
             ts,
             call_id,
             usage,
@@ -194,27 +201,79 @@ impl CircuitMgr {
             simplex_duplex: false,
             speech_service: Some(0),
             etee_encrypted: false,
+            peer_ts: None,
         };
 
         // Register circuit and return
         Ok(self.open_circuit(dir, circuit)?)
     }
 
-    /// Closes any active circuits for given timeslot and direction.
+    /// Allocate the traffic circuits for an individual call with an already assigned call id.
+    /// Simplex calls share a single DL+UL circuit, on which the floor is handed over.
+    /// Duplex calls get a DL+UL circuit per party on separate timeslots of the same carrier, with
+    /// the UL of each routed to the DL of the other. The calling party's circuit is returned first.
+    pub fn allocate_individual_circuits_with_allocator(
+        &mut self,
+        call_id: CallId,
+        duplex: bool,
+        comm_type: CommunicationType,
+        timeslot_alloc: &mut TimeslotAllocator,
+        owner: TimeslotOwner,
+    ) -> Result<Vec<CmceCircuit>, CircuitErr> {
+        let (carrier, ts_calling, ts_called) = if duplex {
+            let (carrier, ts_calling, ts_called) = timeslot_alloc.allocate_traffic_pair(owner).ok_or(CircuitErr::NoCircuitFree)?;
+            (carrier, ts_calling, Some(ts_called))
+        } else {
+            let (carrier, ts_calling) = timeslot_alloc.allocate_traffic(owner).ok_or(CircuitErr::NoCircuitFree)?;
+            (carrier, ts_calling, None)
+        };
+
+        let mut circuits = vec![(ts_calling, ts_called)];
+        if let Some(ts_called) = ts_called {
+            circuits.push((ts_called, Some(ts_calling)));
+        }
+
+        let mut opened = Vec::with_capacity(circuits.len());
+        for (ts, peer_ts) in circuits {
+            let circuit = CmceCircuit {
+                ts_created: self.dltime,
+                direction: Direction::Both,
+                carrier,
+                ts,
+                call_id,
+                usage: self.get_next_usage_number(),
+                circuit_mode: CircuitModeType::TchS,
+                comm_type,
+                simplex_duplex: duplex,
+                speech_service: Some(0),
+                etee_encrypted: false,
+                peer_ts,
+            };
+            opened.push(self.open_circuit(Direction::Both, circuit)?.clone());
+        }
+        Ok(opened)
+    }
+
+    /// Closes any active circuits for given carrier, timeslot and direction.
     /// Returns the CmceCircuit
     /// When direction is Both, closes both directions
-    pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Result<CmceCircuit, CircuitErr> {
+    pub fn close_circuit(&mut self, dir: Direction, carrier: u8, ts: u8) -> Result<CmceCircuit, CircuitErr> {
+        let (c, t) = (carrier as usize, ts as usize - 1);
         match dir {
             Direction::Dl | Direction::Both => {
-                self.tx_data[ts as usize - 1].clear();
-                if dir == Direction::Both && self.ul_only[ts as usize - 1].is_some() {
-                    tracing::warn!("Closing Dl+Ul circuit on ts {} while Ul-only circuit exists", ts);
+                self.tx_data[c][t].clear();
+                if dir == Direction::Both && self.ul_only[c][t].is_some() {
+                    tracing::warn!(
+                        "Closing Dl+Ul circuit on carrier {} ts {} while Ul-only circuit exists",
+                        carrier,
+                        ts
+                    );
                 }
-                let circuit = self.dl[ts as usize - 1].take();
+                let circuit = self.dl[c][t].take();
                 circuit.ok_or(CircuitErr::CircuitNotActive)
             }
             Direction::Ul => {
-                let circuit = self.ul_only[ts as usize - 1].take();
+                let circuit = self.ul_only[c][t].take();
                 circuit.ok_or(CircuitErr::CircuitNotActive)
             }
             _ => panic!(),
@@ -226,8 +285,9 @@ impl CircuitMgr {
     /// Consumes the circuit but returns a reference
     fn open_circuit(&mut self, dir: Direction, circuit: CmceCircuit) -> Result<&CmceCircuit, CircuitErr> {
         // Sanity check, close circuit and issue warning if exists
-        let ts = circuit.ts;
-        let (dl_active, ul_active) = self.is_active(ts);
+        let (carrier, ts) = (circuit.carrier, circuit.ts);
+        let (c, t) = (carrier as usize, ts as usize - 1);
+        let (dl_active, ul_active) = self.is_active(carrier, ts);
         if dir.includes_dl() && dl_active {
             return Err(CircuitErr::CircuitAlreadyInUse);
         }
@@ -237,37 +297,37 @@ impl CircuitMgr {
 
         match dir {
             Direction::Dl | Direction::Both => {
-                if !self.tx_data[ts as usize - 1].is_empty() {
-                    tracing::warn!("CircuitMgr::create had pending tx_data on Dl {}", ts);
-                    self.tx_data[ts as usize - 1].clear();
+                if !self.tx_data[c][t].is_empty() {
+                    tracing::warn!("CircuitMgr::create had pending tx_data on Dl carrier {} ts {}", carrier, ts);
+                    self.tx_data[c][t].clear();
                 }
-                self.dl[ts as usize - 1] = Some(circuit);
-                Ok(self.dl[ts as usize - 1].as_ref().unwrap())
+                self.dl[c][t] = Some(circuit);
+                Ok(self.dl[c][t].as_ref().unwrap())
             }
             Direction::Ul => {
-                self.ul_only[ts as usize - 1] = Some(circuit);
-                Ok(self.ul_only[ts as usize - 1].as_ref().unwrap())
+                self.ul_only[c][t] = Some(circuit);
+                Ok(self.ul_only[c][t].as_ref().unwrap())
             }
             _ => panic!(),
         }
     }
 
     /// Put a block in the queue for transmission on an associated channel
-    pub fn put_block(&mut self, ts: u8, block: Vec<u8>) -> Result<(), CircuitErr> {
-        if !self.is_active_dir(ts, Direction::Dl) {
+    pub fn put_block(&mut self, carrier: u8, ts: u8, block: Vec<u8>) -> Result<(), CircuitErr> {
+        if !self.is_active_dir(carrier, ts, Direction::Dl) {
             Err(CircuitErr::CircuitNotActive)
         } else {
-            self.tx_data[ts as usize - 1].push_back(block);
+            self.tx_data[carrier as usize][ts as usize - 1].push_back(block);
             Ok(())
         }
     }
 
     /// Take a to-be-transmitted block from the queue
-    pub fn take_block(&mut self, ts: u8) -> Result<Option<Vec<u8>>, CircuitErr> {
-        if !self.is_active_dir(ts, Direction::Dl) {
+    pub fn take_block(&mut self, carrier: u8, ts: u8) -> Result<Option<Vec<u8>>, CircuitErr> {
+        if !self.is_active_dir(carrier, ts, Direction::Dl) {
             return Err(CircuitErr::CircuitNotActive);
         } else {
-            Ok(self.tx_data[ts as usize - 1].pop_front())
+            Ok(self.tx_data[carrier as usize][ts as usize - 1].pop_front())
         }
     }
 
@@ -280,19 +340,21 @@ impl CircuitMgr {
         let mut to_close: Vec<_> = self
             .dl
             .iter()
+            .flatten()
             .filter_map(|circuit| circuit.as_ref())
             .filter(|circuit| circuit.ts_created.age(self.dltime) > CIRCUIT_EXPIRY_TIMESLOTS)
-            .map(|circuit| (circuit.direction, circuit.ts, circuit.call_id))
+            .map(|circuit| (circuit.direction, circuit.carrier, circuit.ts, circuit.call_id))
             .collect();
         to_close.extend(
             self.ul_only
                 .iter()
+                .flatten()
                 .filter_map(|circuit| circuit.as_ref())
                 .filter(|circuit| circuit.ts_created.age(self.dltime) > CIRCUIT_EXPIRY_TIMESLOTS)
-                .map(|circuit| (circuit.direction, circuit.ts, circuit.call_id)),
+                .map(|circuit| (circuit.direction, circuit.carrier, circuit.ts, circuit.call_id)),
         );
-        for (dir, ts, call_id) in to_close {
-            let circuit = self.close_circuit(dir, ts).unwrap(); // TODO FIXME not so sure about this one
+        for (dir, carrier, ts, call_id) in to_close {
+            let circuit = self.close_circuit(dir, carrier, ts).unwrap(); // TODO FIXME not so sure about this one
             tasks.get_or_insert_with(Vec::new).push(CircuitMgrCmd::SendClose(call_id, circuit));
         }
         tasks
@@ -308,25 +370,21 @@ impl CircuitMgr {
 
             // Next, go through channels, see if D-SETUPs need to be sent
             // Late entry: resend D-SETUP every 5 seconds
-            for circuit in self.dl.iter() {
-                if let Some(circuit) = circuit {
-                    let age = circuit.ts_created.age(dltime);
-
-                    // Send D-SETUP for the initial frame + 1 backup frame after circuit creation.
-                    // Matches ETSI Annex D Figure D.2: 1 initial + 1 back-up on MCCH.
-                    if age < frames!(D_SETUP_REPEATS) {
-                        tasks
-                            .get_or_insert_with(Vec::new)
-                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.usage, circuit.ts));
-                    }
-                    // Late entry: resend every 5 seconds.
-                    // Compare in frames (age/4) since tick_start only fires on t==1
-                    // but ts_created may have any timeslot value.
-                    else if (age / 4) % (LATE_ENTRY_INTERVAL_TIMESLOTS / 4) == 0 {
-                        tasks
-                            .get_or_insert_with(Vec::new)
-                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.usage, circuit.ts));
-                    }
+            for circuit in self.dl.iter().flatten().flatten() {
+                let age = circuit.ts_created.age(dltime);
+
+                // Send D-SETUP for the initial frame + 1 backup frame after circuit creation.
+                // Matches ETSI Annex D Figure D.2: 1 initial + 1 back-up on MCCH.
+                // Late entry: resend every 5 seconds.
+                // Compare in frames (age/4) since tick_start only fires on t==1
+                // but ts_created may have any timeslot value.
+                if age < frames!(D_SETUP_REPEATS) || (age / 4) % (LATE_ENTRY_INTERVAL_TIMESLOTS / 4) == 0 {
+                    tasks.get_or_insert_with(Vec::new).push(CircuitMgrCmd::SendDSetup(
+                        circuit.call_id,
+                        circuit.usage,
+                        circuit.carrier,
+                        circuit.ts,
+                    ));
                 }
             }
             return tasks;
@@ -0,0 +1,63 @@
+use std::process::Command;
+use std::thread;
+
+use tetra_config::bluestation::CfgEmergency;
+use tetra_saps::lcmc::CallId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmergencyCallType {
+    Group,
+    Individual,
+}
+
+impl EmergencyCallType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EmergencyCallType::Group => "group",
+            EmergencyCallType::Individual => "individual",
+        }
+    }
+}
+
+/// Log an emergency call and run the configured alarm command for it. The command runs in the
+/// background, its exit status is only logged.
+pub fn raise_emergency_alarm(
+    cfg: Option<&CfgEmergency>,
+    call_type: EmergencyCallType,
+    calling_issi: u32,
+    called_ssi: u32,
+    call_id: CallId,
+) {
+    tracing::warn!(
+        "EMERGENCY {} call from ISSI {} to SSI {} call_id={}",
+        call_type.as_str(),
+        calling_issi,
+        called_ssi,
+        call_id
+    );
+
+    let Some(command) = cfg.and_then(|c| c.alarm_command.clone()) else {
+        return;
+    };
+    let child = Command::new(&command)
+        .arg(call_type.as_str())
+        .arg(calling_issi.to_string())
+        .arg(called_ssi.to_string())
+        .arg(call_id.to_string())
+        .spawn();
+    match child {
+        Ok(mut child) => {
+            let spawned = thread::Builder::new()
+                .name("emergency-alarm".to_string())
+                .spawn(move || match child.wait() {
+                    Ok(status) if !status.success() => tracing::warn!("Emergency alarm command exited with {}", status),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed waiting for emergency alarm command: {}", e),
+                });
+            if let Err(e) = spawned {
+                tracing::warn!("Failed to spawn emergency alarm waiter: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("Failed to run emergency alarm command {}: {}", command, e),
+    }
+}
@@ -1 +1,7 @@
+pub mod call_queue;
 pub mod circuit_mgr;
+pub mod emergency_alarm;
+pub mod ss_cf;
+pub mod ss_dgna;
+pub mod ss_handler;
+pub mod ss_tpi;
@@ -0,0 +1,83 @@
+use tetra_config::bluestation::CallForwardingCondition;
+use tetra_pdus::cmce::enums::cf_condition::CfCondition;
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::fields::{ss_cf::SsCfPdu, ss_pdu::SsPdu};
+
+use super::ss_handler::{SsContext, SsHandler};
+
+/// Call Forwarding. The MS (de)activates and interrogates forwarding of calls addressed to it;
+/// the settings are kept in the SubscriberRegistry for call control to act on.
+#[derive(Default)]
+pub struct SsCfHandler;
+
+impl SsCfHandler {
+    fn to_registry_condition(condition: CfCondition) -> CallForwardingCondition {
+        match condition {
+            CfCondition::Unconditional => CallForwardingCondition::Unconditional,
+            CfCondition::Busy => CallForwardingCondition::Busy,
+            CfCondition::NoReply => CallForwardingCondition::NoReply,
+            CfCondition::NotReachable => CallForwardingCondition::NotReachable,
+        }
+    }
+}
+
+impl SsHandler for SsCfHandler {
+    fn ss_type(&self) -> SsType {
+        SsType::Cf
+    }
+
+    fn rx_ss_pdu(&mut self, ctx: &mut SsContext, issi: u32, ss_pdu: SsPdu) {
+        let SsPdu::Cf(pdu) = ss_pdu else {
+            panic!();
+        };
+
+        let (condition, accepted) = match pdu {
+            SsCfPdu::Activate {
+                condition,
+                forwarded_to_ssi,
+            } => {
+                // Forwarding to oneself would loop
+                let accepted = forwarded_to_ssi != issi;
+                if accepted {
+                    tracing::info!("SS-CF: ISSI {} forwards calls to {} on {}", issi, forwarded_to_ssi, condition);
+                    ctx.config.state_write().subscribers.set_call_forwarding(
+                        issi,
+                        Self::to_registry_condition(condition),
+                        Some(forwarded_to_ssi),
+                    );
+                } else {
+                    tracing::warn!("SS-CF: ISSI {} attempted to forward calls to itself", issi);
+                }
+                (condition, accepted)
+            }
+            SsCfPdu::Deactivate { condition } => {
+                tracing::info!("SS-CF: ISSI {} deactivates forwarding on {}", issi, condition);
+                ctx.config
+                    .state_write()
+                    .subscribers
+                    .set_call_forwarding(issi, Self::to_registry_condition(condition), None);
+                (condition, true)
+            }
+            SsCfPdu::Interrogate { condition } => (condition, true),
+            SsCfPdu::Result { .. } => {
+                tracing::warn!("SS-CF: unexpected {:?} from ISSI {}", pdu, issi);
+                return;
+            }
+        };
+
+        // Every request is answered with the resulting forwarding state
+        let forwarded_to_ssi = ctx
+            .config
+            .state_read()
+            .subscribers
+            .call_forwarding(issi, Self::to_registry_condition(condition));
+        ctx.send_d_facility(
+            issi,
+            SsPdu::Cf(SsCfPdu::Result {
+                condition,
+                accepted,
+                forwarded_to_ssi,
+            }),
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use tetra_core::{Sap, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::fields::{ss_dgna::SsDgnaPdu, ss_pdu::SsPdu};
+use tetra_saps::control::mm::MmControl;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use super::ss_handler::{SsContext, SsHandler};
+
+/// Dynamic Group Number Assignment requested by an authorised user, such as a dispatcher terminal.
+/// The assignment itself is handed to MM, which attaches the served user with D-ATTACH/DETACH GROUP
+/// IDENTITY, so it follows the access policy and is tracked along with all other group attachments.
+#[derive(Default)]
+pub struct SsDgnaHandler;
+
+impl SsHandler for SsDgnaHandler {
+    fn ss_type(&self) -> SsType {
+        SsType::Dgna
+    }
+
+    fn rx_ss_pdu(&mut self, ctx: &mut SsContext, issi: u32, ss_pdu: SsPdu) {
+        let SsPdu::Dgna(pdu) = ss_pdu else {
+            panic!();
+        };
+        let (gssi, served_issi, assign) = match pdu {
+            SsDgnaPdu::AssignRequest { gssi, issi } => (gssi, issi, true),
+            SsDgnaPdu::DeassignRequest { gssi, issi } => (gssi, issi, false),
+            _ => {
+                tracing::warn!("SS-DGNA: unexpected {:?} from ISSI {}", pdu, issi);
+                return;
+            }
+        };
+
+        let accepted = {
+            let config = ctx.config.config();
+            let state = ctx.config.state_read();
+            let authorised = config
+                .group_assignment
+                .as_ref()
+                .is_some_and(|g| g.dgna_authorised_issis.contains(&issi));
+            if !authorised {
+                tracing::warn!("SS-DGNA: ISSI {} is not authorised to assign groups", issi);
+                false
+            } else if gssi == 0 || gssi >= 1 << 24 || !state.subscribers.is_registered(served_issi) {
+                tracing::warn!("SS-DGNA: ISSI {} can't be assigned gssi={}", served_issi, gssi);
+                false
+            } else {
+                true
+            }
+        };
+
+        if accepted {
+            tracing::info!(
+                "SS-DGNA: ISSI {} {} gssi={} to ISSI {}",
+                issi,
+                if assign { "assigns" } else { "deassigns" },
+                gssi,
+                served_issi
+            );
+            let (attach, detach) = if assign {
+                (vec![gssi], Vec::new())
+            } else {
+                (Vec::new(), vec![gssi])
+            };
+            ctx.queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Mm,
+                dltime: ctx.dltime,
+                msg: SapMsgInner::MmControl(MmControl::AssignGroups {
+                    issi: served_issi,
+                    attach,
+                    detach,
+                }),
+            });
+        }
+        ctx.send_d_facility(
+            issi,
+            SsPdu::Dgna(SsDgnaPdu::RequestResult {
+                gssi,
+                issi: served_issi,
+                accepted,
+            }),
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::fields::ss_pdu::SsPdu;
+use tetra_pdus::cmce::pdus::cmce_function_not_supported::CmceFunctionNotSupported;
+use tetra_pdus::cmce::pdus::d_facility::DFacility;
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::MessageQueue;
+use crate::cmce::subentities::cc_bs::CcBsSubentity;
+
+/// What a supplementary service handler gets to work with while handling a message
+pub struct SsContext<'a> {
+    pub config: &'a SharedConfig,
+    pub queue: &'a mut MessageQueue,
+    pub dltime: TdmaTime,
+    /// Call control, for services relating to ongoing calls
+    pub cc: &'a CcBsSubentity,
+}
+
+impl SsContext<'_> {
+    /// Send an SS-PDU to an individual MS in a D-FACILITY
+    pub fn send_d_facility(&mut self, issi: u32, ss_pdu: SsPdu) {
+        let pdu = DFacility { ss_pdu };
+        tracing::debug!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        self.send(issi, sdu);
+    }
+
+    /// Clause 14.7.3.2 Tell an MS that the received PDU as a whole is not supported
+    pub fn send_function_not_supported(&mut self, issi: u32, pdu_type: CmcePduTypeUl) {
+        let pdu = CmceFunctionNotSupported {
+            not_supported_pdu_type: pdu_type.into_raw() as u8,
+            call_identifier_present: false,
+            call_identifier: None,
+            function_not_supported_pointer: 0,
+            length_of_received_pdu_extract: None,
+            received_pdu_extract: None,
+        };
+        tracing::debug!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        self.send(issi, sdu);
+    }
+
+    fn send(&mut self, issi: u32, sdu: BitBuffer) {
+        self.queue.push_back(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime: self.dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Todo,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: TetraAddress::new(issi, SsiType::Issi),
+                tx_reporter: None,
+            }),
+        });
+    }
+}
+
+/// A supplementary service, registered with the SS sub-entity for one SS type
+pub trait SsHandler: Send {
+    fn ss_type(&self) -> SsType;
+
+    /// Handle an SS-PDU of our SS type, received from issi in a U-FACILITY
+    fn rx_ss_pdu(&mut self, ctx: &mut SsContext, issi: u32, ss_pdu: SsPdu);
+}
@@ -0,0 +1,50 @@
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::fields::{ss_pdu::SsPdu, ss_tpi::SsTpiPdu};
+
+use super::ss_handler::{SsContext, SsHandler};
+
+/// Talking Party Identification. The talking party is already signalled to all call members
+/// in D-TX GRANTED; this lets an MS that missed it, e.g. after late entry, ask for it.
+#[derive(Default)]
+pub struct SsTpiHandler;
+
+impl SsHandler for SsTpiHandler {
+    fn ss_type(&self) -> SsType {
+        SsType::Tpi
+    }
+
+    fn rx_ss_pdu(&mut self, ctx: &mut SsContext, issi: u32, ss_pdu: SsPdu) {
+        let SsPdu::Tpi(pdu) = ss_pdu else {
+            panic!();
+        };
+        let SsTpiPdu::Interrogate { call_identifier } = pdu else {
+            tracing::warn!("SS-TPI: unexpected {:?} from ISSI {}", pdu, issi);
+            return;
+        };
+
+        let Some((gssi, talking_party_ssi)) = ctx.cc.talking_party(call_identifier) else {
+            tracing::warn!("SS-TPI: ISSI {} interrogated unknown call_id={}", issi, call_identifier);
+            return;
+        };
+
+        // Only reveal the talker to members of the group
+        let is_member = ctx
+            .config
+            .state_read()
+            .subscribers
+            .get_subscriber(issi)
+            .is_some_and(|s| s.attached_groups.contains(&gssi));
+        if !is_member {
+            tracing::warn!("SS-TPI: ISSI {} is not a member of gssi={}", issi, gssi);
+            return;
+        }
+
+        ctx.send_d_facility(
+            issi,
+            SsPdu::Tpi(SsTpiPdu::Result {
+                call_identifier,
+                talking_party_ssi,
+            }),
+        );
+    }
+}
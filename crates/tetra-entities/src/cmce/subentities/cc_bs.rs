@@ -1,19 +1,21 @@
 use std::collections::{HashMap, HashSet};
 
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{CallForwardingCondition, SharedConfig};
 use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
-use tetra_core::{Layer2Service, TimeslotOwner, TxReporter, TxState};
+use tetra_core::{Layer2Service, TimeslotOwner, TxReporter, TxState, multiframes};
 use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
 use tetra_pdus::cmce::{
     enums::{
-        call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase, cmce_pdu_type_ul::CmcePduTypeUl,
-        transmission_grant::TransmissionGrant,
+        call_status::CallStatus, call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase,
+        cmce_pdu_type_ul::CmcePduTypeUl, dtmf_type::DtmfType, transmission_grant::TransmissionGrant,
     },
     fields::basic_service_information::BasicServiceInformation,
+    fields::dtmf::{DTMF_MAX_DIGITS, Dtmf},
     pdus::{
-        d_call_proceeding::DCallProceeding, d_connect::DConnect, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased,
-        d_tx_granted::DTxGranted, u_disconnect::UDisconnect, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased,
-        u_tx_demand::UTxDemand,
+        d_alert::DAlert, d_call_proceeding::DCallProceeding, d_call_restore::DCallRestore, d_connect::DConnect,
+        d_connect_acknowledge::DConnectAcknowledge, d_info::DInfo, d_release::DRelease, d_setup::DSetup, d_tx_ceased::DTxCeased,
+        d_tx_granted::DTxGranted, u_alert::UAlert, u_call_restore::UCallRestore, u_connect::UConnect, u_disconnect::UDisconnect,
+        u_info::UInfo, u_release::URelease, u_setup::USetup, u_tx_ceased::UTxCeased, u_tx_demand::UTxDemand,
     },
     structs::cmce_circuit::CmceCircuit,
 };
@@ -21,7 +23,7 @@ use tetra_saps::{
     SapMsg, SapMsgInner,
     control::{
         brew::{BrewSubscriberAction, MmSubscriberUpdate},
-        call_control::{CallControl, Circuit},
+        call_control::{CallControl, CallInfo, Circuit},
         enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType},
     },
     lcmc::{
@@ -34,7 +36,9 @@ use tetra_saps::{
 use crate::brew;
 use crate::{
     MessageQueue,
-    cmce::components::circuit_mgr::{CircuitMgr, CircuitMgrCmd},
+    cmce::components::call_queue::{CallQueue, QueuedCall},
+    cmce::components::circuit_mgr::{CircuitErr, CircuitMgr, CircuitMgrCmd},
+    cmce::components::emergency_alarm::{EmergencyCallType, raise_emergency_alarm},
 };
 
 /// Clause 11 Call Control CMCE sub-entity
@@ -50,8 +54,21 @@ pub struct CcBsSubentity {
     subscriber_groups: HashMap<u32, HashSet<u32>>,
     /// Listener counts per GSSI
     group_listeners: HashMap<u32, usize>,
+    /// Active individual calls between two local ISSIs: call_id -> call info
+    individual_calls: HashMap<u16, IndividualCall>,
+    /// Group call set-ups waiting for a free traffic timeslot
+    call_queue: CallQueue<QueuedRequest>,
 }
 
+/// No-answer timer for hook signalling, matching the T60s set-up phase time-out sent to the MSs
+const INDIVIDUAL_SETUP_TIMEOUT: i32 = multiframes!(60);
+/// Call time-out for connected individual calls, matching the T5m sent in D-CONNECT
+const INDIVIDUAL_CALL_TIMEOUT: i32 = multiframes!(5 * 60);
+/// Queued callers are reminded their call is still queued at this interval, well within the T60s set-up phase time-out
+const CALL_QUEUE_INFO_INTERVAL: i32 = multiframes!(30);
+/// Call priority of emergency calls (ETSI 14.8.9)
+const EMERGENCY_CALL_PRIORITY: u8 = 15;
+
 /// Origin of a group call
 #[derive(Clone)]
 enum CallOrigin {
@@ -71,6 +88,7 @@ struct ActiveCall {
     origin: CallOrigin,
     dest_gssi: u32,   // Destination group
     source_issi: u32, // Current speaker
+    carrier: u8,
     ts: u8,
     usage: u8,
     /// True if someone is currently transmitting
@@ -80,18 +98,92 @@ struct ActiveCall {
     /// Brew session UUID — set when a network speaker is active on this call,
     /// regardless of call origin. Cleared when the network speaker ends.
     brew_uuid: Option<uuid::Uuid>,
+    /// Call priority, lower priority calls are pre-empted first
+    priority: u8,
+}
+
+/// Group call set-up waiting in the call queue
+enum QueuedRequest {
+    /// U-SETUP from a local MS, with the message it arrived in for addressing the responses
+    Local { message: Box<SapMsg>, pdu: USetup },
+    /// Network call start from Brew
+    Network {
+        brew_uuid: uuid::Uuid,
+        source_issi: u32,
+        dest_gssi: u32,
+    },
+}
+
+/// Set-up progress of an individual call
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IndividualCallState {
+    /// D-SETUP sent to the called party, awaiting U-ALERT or U-CONNECT
+    Setup,
+    /// Called party is being alerted, awaiting U-CONNECT
+    Alerting,
+    /// Both parties are through-connected
+    Connected,
+}
+
+/// Tracks an individual call between two local ISSIs
+#[derive(Clone)]
+struct IndividualCall {
+    calling_addr: TetraAddress,
+    /// Called party after applying call forwarding
+    called_issi: u32,
+    /// Hook on/off signalling: the called party is alerted and answers with U-CONNECT.
+    /// With direct signalling, the call is through-connected straight away.
+    hook: bool,
+    duplex: bool,
+    basic_service_information: BasicServiceInformation,
+    call_priority: u8,
+    state: IndividualCallState,
+    /// Start of the current set-up phase, or of the call once connected
+    timer_start: TdmaTime,
+    /// Traffic circuits once connected. The calling party's comes first,
+    /// duplex calls have a second one for the called party.
+    circuits: Vec<CmceCircuit>,
+    /// Simplex only: ISSI currently holding the floor
+    tx_issi: Option<u32>,
+}
+
+impl IndividualCall {
+    fn involves(&self, issi: u32) -> bool {
+        self.calling_addr.ssi == issi || self.called_issi == issi
+    }
+
+    fn other_party(&self, issi: u32) -> u32 {
+        if self.calling_addr.ssi == issi {
+            self.called_issi
+        } else {
+            self.calling_addr.ssi
+        }
+    }
+
+    /// Traffic circuit used by the given party
+    fn circuit_of(&self, issi: u32) -> Option<&CmceCircuit> {
+        if self.duplex && issi == self.called_issi {
+            self.circuits.get(1)
+        } else {
+            self.circuits.first()
+        }
+    }
 }
 
 impl CcBsSubentity {
     pub fn new(config: SharedConfig) -> Self {
+        let num_carriers = 1 + config.config().cell.secondary_carriers.len();
+        let max_queue_length = config.config().call_queue.as_ref().map_or(0, |q| q.max_length);
         CcBsSubentity {
             config,
             dltime: TdmaTime::default(),
             cached_setups: HashMap::new(),
-            circuits: CircuitMgr::new(),
+            circuits: CircuitMgr::new(num_carriers),
             active_calls: HashMap::new(),
             subscriber_groups: HashMap::new(),
             group_listeners: HashMap::new(),
+            individual_calls: HashMap::new(),
+            call_queue: CallQueue::new(max_queue_length),
         }
     }
 
@@ -99,7 +191,7 @@ impl CcBsSubentity {
         self.config = config;
     }
 
-    fn build_d_setup_prim(pdu: &DSetup, usage: u8, ts: u8, ul_dl: UlDlAssignment) -> (BitBuffer, CmceChanAllocReq) {
+    fn build_d_setup_prim(pdu: &DSetup, usage: u8, carrier: u8, ts: u8, ul_dl: UlDlAssignment) -> (BitBuffer, CmceChanAllocReq) {
         let mut sdu = BitBuffer::new_autoexpand(80);
         pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
         sdu.seek(0);
@@ -111,7 +203,7 @@ impl CcBsSubentity {
         let chan_alloc = CmceChanAllocReq {
             usage: Some(usage),
             alloc_type: ChanAllocType::Replace,
-            carrier: None,
+            carrier: Some(carrier),
             timeslots,
             ul_dl_assigned: ul_dl,
         };
@@ -149,13 +241,15 @@ impl CcBsSubentity {
         }
     }
 
-    fn build_sapmsg_stealing(sdu: BitBuffer, dltime: TdmaTime, address: TetraAddress, ts: u8) -> SapMsg {
-        // For FACCH stealing on traffic channel, must specify target timeslot
+    /// Builds a FACCH message for the traffic channel given as (carrier, timeslot)
+    fn build_sapmsg_stealing(sdu: BitBuffer, dltime: TdmaTime, address: TetraAddress, chan: (u8, u8)) -> SapMsg {
+        // For FACCH stealing on traffic channel, must specify target carrier and timeslot
+        let (carrier, ts) = chan;
         let mut timeslots = [false; 4];
         timeslots[(ts - 1) as usize] = true;
         let chan_alloc = CmceChanAllocReq {
             usage: None,
-            carrier: None,
+            carrier: Some(carrier),
             timeslots,
             alloc_type: ChanAllocType::Replace,
             ul_dl_assigned: UlDlAssignment::Both,
@@ -200,6 +294,12 @@ impl CcBsSubentity {
         sdu
     }
 
+    /// Group addressed by an active call and the ISSI currently holding its floor, if any
+    pub fn talking_party(&self, call_id: u16) -> Option<(u32, Option<u32>)> {
+        let call = self.active_calls.get(&call_id)?;
+        Some((call.dest_gssi, call.tx_active.then_some(call.source_issi)))
+    }
+
     fn has_listener(&self, gssi: u32) -> bool {
         self.group_listeners.get(&gssi).copied().unwrap_or(0) > 0
     }
@@ -227,7 +327,7 @@ impl CcBsSubentity {
         let to_drop: Vec<(u16, CallOrigin)> = self
             .active_calls
             .iter()
-            .filter(|(_, call)| call.dest_gssi == gssi)
+            .filter(|(_, call)| call.dest_gssi == gssi && call.priority < EMERGENCY_CALL_PRIORITY)
             .map(|(call_id, call)| (*call_id, call.origin.clone()))
             .collect();
 
@@ -265,6 +365,15 @@ impl CcBsSubentity {
                         self.drop_group_calls_if_unlistened(queue, gssi);
                     }
                 }
+                let individual: Vec<u16> = self
+                    .individual_calls
+                    .iter()
+                    .filter(|(_, call)| call.involves(issi))
+                    .map(|(call_id, _)| *call_id)
+                    .collect();
+                for call_id in individual {
+                    self.release_individual_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection, Some(issi));
+                }
                 tracing::info!("CMCE: subscriber deregister issi={}", issi);
             }
             BrewSubscriberAction::Affiliate => {
@@ -318,7 +427,14 @@ impl CcBsSubentity {
         }
     }
 
-    fn send_d_call_proceeding(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu_request: &USetup, call_id: u16) {
+    fn send_d_call_proceeding(
+        queue: &mut MessageQueue,
+        message: &SapMsg,
+        pdu_request: &USetup,
+        call_id: u16,
+        call_time_out_set_up_phase: CallTimeoutSetupPhase,
+        call_status: Option<CallStatus>,
+    ) {
         tracing::trace!("send_d_call_proceeding");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
@@ -327,11 +443,11 @@ impl CcBsSubentity {
 
         let pdu_response = DCallProceeding {
             call_identifier: call_id,
-            call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s,
+            call_time_out_set_up_phase,
             hook_method_selection: pdu_request.hook_method_selection,
             simplex_duplex_selection: pdu_request.simplex_duplex_selection,
             basic_service_information: None, // Only needed if different from requested
-            call_status: None,
+            call_status,
             notification_indicator: None,
             facility: None,
             proprietary: None,
@@ -369,11 +485,13 @@ impl CcBsSubentity {
     fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit, dltime: TdmaTime) {
         let circuit = Circuit {
             direction: call.direction,
+            carrier: call.carrier,
             ts: call.ts,
             usage: call.usage,
             circuit_mode: call.circuit_mode,
             speech_service: call.speech_service,
             etee_encrypted: call.etee_encrypted,
+            peer_ts: call.peer_ts,
         };
         let cmd = SapMsg {
             sap: Sap::Control,
@@ -391,7 +509,7 @@ impl CcBsSubentity {
             src: TetraEntity::Cmce,
             dest: TetraEntity::Umac,
             dltime,
-            msg: SapMsgInner::CmceCallControl(CallControl::Close(circuit.direction, circuit.ts)),
+            msg: SapMsgInner::CmceCallControl(CallControl::Close(circuit.direction, circuit.carrier, circuit.ts)),
         };
         queue.push_back(cmd);
     }
@@ -420,15 +538,25 @@ impl CcBsSubentity {
             return;
         }
 
+        if pdu.basic_service_information.communication_type == CommunicationType::P2p {
+            self.rx_u_setup_individual(queue, message, pdu);
+            return;
+        }
+        if pdu.hook_method_selection || pdu.simplex_duplex_selection {
+            unimplemented_log!("Only direct set-up simplex group calls supported");
+            return;
+        }
+
         // Get destination GSSI (called party)
         let Some(dest_gssi) = pdu.called_party_ssi else {
             tracing::warn!("U-SETUP without called_party_ssi, ignoring");
             return;
         };
         let dest_gssi = dest_gssi as u32;
-        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
+        let emergency = pdu.call_priority >= EMERGENCY_CALL_PRIORITY;
 
-        if !self.has_listener(dest_gssi) {
+        // Emergency calls are set up even if nobody is attached to the group, dispatch may still listen
+        if !emergency && !self.has_listener(dest_gssi) {
             tracing::info!(
                 "CMCE: rejecting U-SETUP from issi={} to gssi={} (no listeners)",
                 calling_party.ssi,
@@ -437,27 +565,63 @@ impl CcBsSubentity {
             return;
         }
 
-        // Allocate circuit (DL+UL for group call)
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                pdu.basic_service_information.communication_type,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(circuit) => circuit.clone(),
-            Err(e) => {
-                tracing::error!("Failed to allocate circuit for U-SETUP: {:?}", e);
-                return;
-            }
+        // Allocate circuit (DL+UL for group call), unless calls queued before this one go first
+        let call_id = self.circuits.get_next_call_id();
+        if emergency {
+            let cfg = self.config.config();
+            raise_emergency_alarm(
+                cfg.emergency.as_ref(),
+                EmergencyCallType::Group,
+                calling_party.ssi,
+                dest_gssi,
+                call_id,
+            );
+        }
+        let circuit = if self.call_queue.has_precedence_over(pdu.call_priority) {
+            None
+        } else {
+            self.allocate_group_circuit(queue, call_id, pdu.call_priority, pdu.basic_service_information.communication_type)
+        };
+        let Some(circuit) = circuit else {
+            tracing::info!(
+                "rx_u_setup: no circuit for call from ISSI {} to GSSI {}, call_id={} priority={}",
+                calling_party.ssi,
+                dest_gssi,
+                call_id,
+                pdu.call_priority
+            );
+            self.enqueue_call(
+                queue,
+                call_id,
+                pdu.call_priority,
+                QueuedRequest::Local {
+                    message: Box::new(message),
+                    pdu,
+                },
+            );
+            return;
+        };
+
+        // Acknowledge the U-SETUP, keeps the radio from timing out
+        Self::send_d_call_proceeding(queue, &message, &pdu, call_id, CallTimeoutSetupPhase::T10s, None);
+        self.start_local_group_call(queue, &message, &pdu, circuit);
+    }
+
+    /// Set up a group call from a local MS on the given circuit: D-CONNECT to the calling MS,
+    /// D-SETUP to the group. D-CALL PROCEEDING has already been sent.
+    fn start_local_group_call(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu: &USetup, circuit: CmceCircuit) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
         };
+        let calling_party = prim.received_tetra_address;
+        let dest_gssi = pdu.called_party_ssi.unwrap_or_default() as u32;
+        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
 
         tracing::info!(
-            "rx_u_setup: call from ISSI {} to GSSI {} → ts={} call_id={} usage={}",
+            "rx_u_setup: call from ISSI {} to GSSI {} → carrier={} ts={} call_id={} usage={}",
             calling_party.ssi,
             dest_gssi,
+            circuit.carrier,
             circuit.ts,
             circuit.call_id,
             circuit.usage
@@ -473,16 +637,11 @@ impl CcBsSubentity {
         // Extract UL message routing info (handle, link_id, endpoint_id) for
         // individually-addressed responses. These are needed so MLE can route
         // the response back to the correct radio via the established LLC link.
-        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
-            panic!()
-        };
         let ul_handle = prim.handle;
         let ul_link_id = prim.link_id;
         let ul_endpoint_id = prim.endpoint_id;
 
-        // === 1) Send D-CALL-PROCEEDING to the calling MS (individually addressed) ===
-        // This acknowledges the U-SETUP and keeps the radio from timing out.
-        self.send_d_call_proceeding(queue, &message, &pdu, circuit.call_id);
+        // === 1) D-CALL-PROCEEDING was sent to the calling MS when accepting or queuing the U-SETUP ===
 
         // === 2) Send D-CONNECT to the calling MS with Granted + channel allocation ===
         // This transitions the calling MS from "Call Setup" to "Active".
@@ -527,7 +686,7 @@ impl CcBsSubentity {
                 chan_alloc: Some(CmceChanAllocReq {
                     usage: Some(circuit.usage),
                     alloc_type: ChanAllocType::Replace,
-                    carrier: None,
+                    carrier: Some(circuit.carrier),
                     timeslots,
                     ul_dl_assigned: UlDlAssignment::Both,
                 }),
@@ -564,7 +723,8 @@ impl CcBsSubentity {
         self.cached_setups.insert(circuit.call_id, (d_setup, dest_addr, None));
         let (d_setup_ref, _, _) = self.cached_setups.get(&circuit.call_id).unwrap();
 
-        let (setup_sdu, setup_chan_alloc) = Self::build_d_setup_prim(d_setup_ref, circuit.usage, circuit.ts, UlDlAssignment::Both);
+        let (setup_sdu, setup_chan_alloc) =
+            Self::build_d_setup_prim(d_setup_ref, circuit.usage, circuit.carrier, circuit.ts, UlDlAssignment::Both);
         let setup_msg = Self::build_sapmsg(
             setup_sdu,
             Some(setup_chan_alloc),
@@ -584,11 +744,13 @@ impl CcBsSubentity {
                 },
                 dest_gssi,
                 source_issi: calling_party.ssi,
+                carrier: circuit.carrier,
                 ts: circuit.ts,
                 usage: circuit.usage,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: None,
+                priority: pdu.call_priority,
             },
         );
 
@@ -604,7 +766,9 @@ impl CcBsSubentity {
                     call_id: circuit.call_id,
                     source_issi: calling_party.ssi,
                     dest_gssi,
+                    carrier: circuit.carrier,
                     ts: circuit.ts,
+                    priority: pdu.call_priority,
                 }),
             };
             queue.push_back(msg);
@@ -633,11 +797,11 @@ impl CcBsSubentity {
             CmcePduTypeUl::UTxDemand => self.rx_u_tx_demand(_queue, message),
             CmcePduTypeUl::URelease => self.rx_u_release(_queue, message),
             CmcePduTypeUl::UDisconnect => self.rx_u_disconnect(_queue, message),
-            CmcePduTypeUl::UAlert
-            | CmcePduTypeUl::UConnect
-            | CmcePduTypeUl::UInfo
-            | CmcePduTypeUl::UStatus
-            | CmcePduTypeUl::UCallRestore => {
+            CmcePduTypeUl::UAlert => self.rx_u_alert(_queue, message),
+            CmcePduTypeUl::UConnect => self.rx_u_connect(_queue, message),
+            CmcePduTypeUl::UCallRestore => self.rx_u_call_restore(_queue, message),
+            CmcePduTypeUl::UInfo => self.rx_u_info(_queue, message),
+            CmcePduTypeUl::UStatus => {
                 unimplemented_log!("{}", pdu_type);
             }
             _ => {
@@ -651,11 +815,18 @@ impl CcBsSubentity {
 
         // Check hangtime expiry for active local calls
         self.check_hangtime_expiry(queue);
+        self.check_individual_call_timers(queue);
+        self.check_call_queue(queue);
 
         if let Some(tasks) = self.circuits.tick_start(dltime) {
             for task in tasks {
                 match task {
-                    CircuitMgrCmd::SendDSetup(call_id, usage, ts) => {
+                    CircuitMgrCmd::SendDSetup(call_id, usage, carrier, ts) => {
+                        // Individual calls are set up once, there is no late entry
+                        if self.individual_calls.contains_key(&call_id) {
+                            continue;
+                        }
+
                         // Skip late-entry D-SETUP during hangtime. The traffic channel is still
                         // allocated and sending D-SETUP with NotGranted can prevent floor requests.
                         if let Some(active) = self.active_calls.get(&call_id) {
@@ -697,7 +868,7 @@ impl CcBsSubentity {
                             };
                         }
                         let dest_addr = *dest_addr;
-                        let (sdu, chan_alloc) = Self::build_d_setup_prim(pdu, usage, ts, UlDlAssignment::Both);
+                        let (sdu, chan_alloc) = Self::build_d_setup_prim(pdu, usage, carrier, ts, UlDlAssignment::Both);
 
                         // Create a fresh txreporter for this re-send
                         let reporter = TxReporter::new();
@@ -713,9 +884,18 @@ impl CcBsSubentity {
                         queue.push_back(prim);
                     }
 
+                    CircuitMgrCmd::SendClose(call_id, circuit) if self.individual_calls.contains_key(&call_id) => {
+                        // CircuitMgr already dropped this circuit, release it here and let
+                        // release_individual_call take care of any other circuit of the call
+                        let (carrier, ts) = (circuit.carrier, circuit.ts);
+                        Self::signal_umac_circuit_close(queue, circuit, self.dltime);
+                        self.release_timeslot(carrier, ts);
+                        self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+                    }
+
                     CircuitMgrCmd::SendClose(call_id, circuit) => {
                         tracing::warn!("need to send CLOSE for call id {}", call_id);
-                        let ts = circuit.ts;
+                        let (carrier, ts) = (circuit.carrier, circuit.ts);
                         // Get our cached D-SETUP, build D-RELEASE and send
                         if let Some((pdu, dest_addr, _)) = self.cached_setups.get(&call_id) {
                             let dest_addr = *dest_addr;
@@ -732,7 +912,7 @@ impl CcBsSubentity {
 
                         // Signal UMAC to release the circuit
                         Self::signal_umac_circuit_close(queue, circuit, self.dltime);
-                        self.release_timeslot(ts);
+                        self.release_timeslot(carrier, ts);
                     }
                 }
             }
@@ -763,10 +943,15 @@ impl CcBsSubentity {
         }
     }
 
-    fn release_timeslot(&mut self, ts: u8) {
+    fn release_timeslot(&mut self, carrier: u8, ts: u8) {
         let mut state = self.config.state_write();
-        if let Err(err) = state.timeslot_alloc.release(TimeslotOwner::Cmce, ts) {
-            tracing::warn!("CcBsSubentity: failed to release timeslot ts={} err={:?}", ts, err);
+        if let Err(err) = state.timeslot_alloc.release_on(TimeslotOwner::Cmce, carrier, ts) {
+            tracing::warn!(
+                "CcBsSubentity: failed to release timeslot carrier={} ts={} err={:?}",
+                carrier,
+                ts,
+                err
+            );
         }
     }
 
@@ -785,11 +970,11 @@ impl CcBsSubentity {
 
         // Close the circuit in CircuitMgr and notify Brew
         if let Some(call) = self.active_calls.get(&call_id) {
-            let ts = call.ts;
+            let (carrier, ts) = (call.carrier, call.ts);
             let dest_ssi = call.dest_gssi;
             let is_local = matches!(call.origin, CallOrigin::Local { .. });
 
-            if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, ts) {
+            if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, carrier, ts) {
                 Self::signal_umac_circuit_close(queue, circuit, self.dltime);
             }
 
@@ -799,10 +984,10 @@ impl CcBsSubentity {
                 src: TetraEntity::Cmce,
                 dest: TetraEntity::Umac,
                 dltime: self.dltime,
-                msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
+                msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, carrier, ts }),
             });
 
-            self.release_timeslot(ts);
+            self.release_timeslot(carrier, ts);
 
             // Notify Brew only for local calls on SSIs that are cleared for Brew
             if brew::is_brew_gssi_routable(&self.config, dest_ssi) {
@@ -812,7 +997,7 @@ impl CcBsSubentity {
                         src: TetraEntity::Cmce,
                         dest: TetraEntity::Brew,
                         dltime: self.dltime,
-                        msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, ts }),
+                        msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, carrier, ts }),
                     };
                     queue.push_back(notify);
                 }
@@ -824,6 +1009,251 @@ impl CcBsSubentity {
         self.active_calls.remove(&call_id);
     }
 
+    /// Allocate a DL+UL circuit for a group call, pre-empting a lower-priority call if allowed
+    fn allocate_group_circuit(
+        &mut self,
+        queue: &mut MessageQueue,
+        call_id: u16,
+        priority: u8,
+        comm_type: CommunicationType,
+    ) -> Option<CmceCircuit> {
+        if let Some(circuit) = self.allocate_circuit(call_id, comm_type) {
+            return Some(circuit);
+        }
+        let victim = self.preemption_candidate(priority)?;
+        tracing::info!("CMCE: pre-empting call_id={} for call_id={} priority={}", victim, call_id, priority);
+        self.preempt_call(queue, victim);
+        self.allocate_circuit(call_id, comm_type)
+    }
+
+    fn allocate_circuit(&mut self, call_id: u16, comm_type: CommunicationType) -> Option<CmceCircuit> {
+        let mut state = self.config.state_write();
+        match self.circuits.allocate_circuit_with_allocator(
+            call_id,
+            Direction::Both,
+            comm_type,
+            &mut state.timeslot_alloc,
+            TimeslotOwner::Cmce,
+        ) {
+            Ok(circuit) => Some(circuit.clone()),
+            Err(err) => {
+                tracing::debug!("CMCE: no circuit for call_id={}: {:?}", call_id, err);
+                None
+            }
+        }
+    }
+
+    /// Call to release so a call of the given priority gets a timeslot: the lowest priority
+    /// call below it, idle calls first. None if the priority is not pre-emptive.
+    fn preemption_candidate(&self, priority: u8) -> Option<u16> {
+        let cfg = self.config.config();
+        let preemptive = if priority >= EMERGENCY_CALL_PRIORITY {
+            cfg.emergency.as_ref().is_none_or(|e| e.preemption)
+        } else {
+            cfg.call_queue
+                .as_ref()
+                .is_some_and(|q| q.preemption && priority >= q.preemption_priority)
+        };
+        if !preemptive {
+            return None;
+        }
+        let group = self
+            .active_calls
+            .iter()
+            .map(|(call_id, call)| (*call_id, call.priority, call.tx_active));
+        // Individual calls still waiting for the called party hold no timeslot
+        let individual = self
+            .individual_calls
+            .iter()
+            .filter(|(_, call)| !call.circuits.is_empty())
+            .map(|(call_id, call)| (*call_id, call.call_priority, call.tx_issi.is_some() || call.duplex));
+        group
+            .chain(individual)
+            .filter(|(_, call_priority, _)| *call_priority < priority)
+            .min_by_key(|(call_id, call_priority, tx_active)| (*call_priority, *tx_active, *call_id))
+            .map(|(call_id, _, _)| call_id)
+    }
+
+    /// Release a call to free its timeslots for a higher-priority call
+    fn preempt_call(&mut self, queue: &mut MessageQueue, call_id: u16) {
+        if self.individual_calls.contains_key(&call_id) {
+            self.release_individual_call(queue, call_id, DisconnectCause::PreEmptiveUseOfResource, None);
+        } else {
+            self.release_group_call(queue, call_id, DisconnectCause::PreEmptiveUseOfResource);
+        }
+    }
+
+    /// Release a group call, ending the Brew session of network-initiated calls
+    fn release_group_call(&mut self, queue: &mut MessageQueue, call_id: u16, disconnect_cause: DisconnectCause) {
+        let Some(call) = self.active_calls.get(&call_id) else {
+            return;
+        };
+        if let CallOrigin::Network { brew_uuid } = call.origin
+            && brew::is_brew_gssi_routable(&self.config, call.dest_gssi)
+        {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Brew,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+            });
+        }
+        self.release_call(queue, call_id, disconnect_cause);
+    }
+
+    /// Put a group call set-up in the call queue, or reject it if queuing is disabled or the queue is full
+    fn enqueue_call(&mut self, queue: &mut MessageQueue, call_id: u16, priority: u8, request: QueuedRequest) {
+        let entry = QueuedCall {
+            call_id,
+            priority,
+            queued_at: self.dltime,
+            last_info: self.dltime,
+            request,
+        };
+        if self.config.config().call_queue.is_none() {
+            self.reject_queued_call(queue, entry, DisconnectCause::CongestionInInfrastructure);
+            return;
+        }
+
+        // Tell a local caller its call is queued, it won't time out while waiting for a timeslot
+        if let QueuedRequest::Local { message, pdu } = &entry.request {
+            Self::send_d_call_proceeding(
+                queue,
+                message,
+                pdu,
+                call_id,
+                CallTimeoutSetupPhase::T60s,
+                Some(CallStatus::Callqueued),
+            );
+        }
+        if let Some(dropped) = self.call_queue.push(entry) {
+            tracing::info!("CMCE: call queue full, rejecting call_id={}", dropped.call_id);
+            self.reject_queued_call(queue, dropped, DisconnectCause::CongestionInInfrastructure);
+        }
+    }
+
+    fn reject_queued_call(&mut self, queue: &mut MessageQueue, entry: QueuedCall<QueuedRequest>, disconnect_cause: DisconnectCause) {
+        match entry.request {
+            QueuedRequest::Local { message, .. } => {
+                let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+                    panic!()
+                };
+                let issi = prim.received_tetra_address.ssi;
+                Self::send_d_release_to(queue, self.dltime, entry.call_id, issi, disconnect_cause);
+            }
+            QueuedRequest::Network { brew_uuid, dest_gssi, .. } => {
+                if brew::is_brew_gssi_routable(&self.config, dest_gssi) {
+                    queue.push_back(SapMsg {
+                        sap: Sap::Control,
+                        src: TetraEntity::Cmce,
+                        dest: TetraEntity::Brew,
+                        dltime: self.dltime,
+                        msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Serve queued call set-ups as timeslots become free, reject those queued for too long
+    /// and remind local callers that their call is still queued
+    fn check_call_queue(&mut self, queue: &mut MessageQueue) {
+        if self.call_queue.is_empty() {
+            return;
+        }
+        let Some(cfg) = self.config.config().call_queue.clone() else {
+            return;
+        };
+
+        let timeout = |priority: u8| {
+            let t = if priority >= EMERGENCY_CALL_PRIORITY {
+                cfg.emergency_timeout
+            } else {
+                cfg.timeout
+            };
+            multiframes!(t.as_secs() as i32)
+        };
+        for entry in self.call_queue.take_expired(self.dltime, timeout) {
+            tracing::info!("CMCE: queued call_id={} timed out", entry.call_id);
+            self.reject_queued_call(queue, entry, DisconnectCause::ExpiryOfTimer);
+        }
+
+        while let Some(head) = self.call_queue.peek() {
+            let comm_type = match &head.request {
+                QueuedRequest::Local { pdu, .. } => pdu.basic_service_information.communication_type,
+                QueuedRequest::Network { .. } => CommunicationType::P2Mp,
+            };
+            let Some(circuit) = self.allocate_circuit(head.call_id, comm_type) else {
+                break;
+            };
+            let entry = self.call_queue.pop().unwrap();
+            tracing::info!(
+                "CMCE: serving queued call_id={} after {} timeslots",
+                entry.call_id,
+                entry.queued_at.age(self.dltime)
+            );
+            match entry.request {
+                QueuedRequest::Local { mut message, pdu } => {
+                    message.dltime = self.dltime;
+                    self.start_local_group_call(queue, &message, &pdu, circuit);
+                }
+                QueuedRequest::Network {
+                    brew_uuid,
+                    source_issi,
+                    dest_gssi,
+                } => {
+                    self.start_network_group_call(queue, brew_uuid, source_issi, dest_gssi, entry.priority, circuit);
+                }
+            }
+        }
+
+        let dltime = self.dltime;
+        for entry in self.call_queue.iter_mut() {
+            if entry.last_info.age(dltime) < CALL_QUEUE_INFO_INTERVAL {
+                continue;
+            }
+            entry.last_info = dltime;
+            if let QueuedRequest::Local { message, .. } = &entry.request {
+                let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+                    panic!()
+                };
+                Self::send_d_info_queued(queue, dltime, entry.call_id, prim.received_tetra_address.ssi);
+            }
+        }
+    }
+
+    /// Send D-INFO telling a queued caller its call is still queued, restarting its set-up time-out
+    fn send_d_info_queued(queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16, issi: u32) {
+        let pdu = DInfo {
+            call_identifier: call_id,
+            reset_call_time_out_timer_t310_: false,
+            poll_request: false,
+            new_call_identifier: None,
+            call_time_out: None,
+            call_time_out_set_up_phase_t301_t302_: Some(CallTimeoutSetupPhase::T60s.into_raw()),
+            call_ownership: None,
+            modify: None,
+            call_status: Some(CallStatus::Callqueued.into_raw()),
+            temporary_address: None,
+            notification_indicator: None,
+            poll_response_percentage: None,
+            poll_response_number: None,
+            dtmf: None,
+            facility: None,
+            poll_response_addresses: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(40);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DInfo");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+        let address = TetraAddress::new(issi, SsiType::Issi);
+        queue.push_back(Self::build_sapmsg(sdu, None, dltime, address, Layer2Service::Unacknowledged, None));
+    }
+
     fn feature_check_u_setup(pdu: &USetup) -> bool {
         let mut supported = true;
 
@@ -831,14 +1261,6 @@ impl CcBsSubentity {
             unimplemented_log!("Area selection not supported: {}", pdu.area_selection);
             supported = false;
         };
-        if pdu.hook_method_selection == true {
-            unimplemented_log!("Hook method selection not supported: {}", pdu.hook_method_selection);
-            supported = false;
-        };
-        if pdu.simplex_duplex_selection != false {
-            unimplemented_log!("Only simplex calls supported: {}", pdu.simplex_duplex_selection);
-            supported = false;
-        };
         // if pdu.basic_service_information != 0xFC {
         //     // TODO FIXME implement parsing
         //     tracing::error!("Basic service information not supported: {}", pdu.basic_service_information);
@@ -888,6 +1310,10 @@ impl CcBsSubentity {
         };
 
         let call_id = pdu.call_identifier;
+        if self.individual_calls.contains_key(&call_id) {
+            self.individual_floor_released(queue, call_id, prim.received_tetra_address.ssi);
+            return;
+        }
 
         // Look up the active call
         let Some(call) = self.active_calls.get_mut(&call_id) else {
@@ -903,7 +1329,7 @@ impl CcBsSubentity {
 
         tracing::info!("U-TX CEASED: PTT released on call_id={}, entering hangtime", call_id);
 
-        let ts = call.ts;
+        let (carrier, ts) = (call.carrier, call.ts);
         let dest_ssi = call.dest_gssi;
         call.tx_active = false;
         call.hangtime_start = Some(self.dltime);
@@ -931,7 +1357,7 @@ impl CcBsSubentity {
         tracing::info!("-> {:?} sdu {}", d_tx_ceased, sdu.dump_bin());
 
         // Send via FACCH (stealing channel) so radios on the traffic channel hear the beep
-        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, ts);
+        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, (carrier, ts));
         queue.push_back(msg);
 
         // Notify UMAC to enter hangtime signalling mode on this traffic timeslot.
@@ -940,7 +1366,7 @@ impl CcBsSubentity {
             src: TetraEntity::Cmce,
             dest: TetraEntity::Umac,
             dltime: self.dltime,
-            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
         });
 
         // Notify Brew to stop forwarding audio, if this SSI is cleared for Br
@@ -950,7 +1376,7 @@ impl CcBsSubentity {
                 src: TetraEntity::Cmce,
                 dest: TetraEntity::Brew,
                 dltime: self.dltime,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
             });
         }
     }
@@ -975,6 +1401,10 @@ impl CcBsSubentity {
         };
 
         let call_id = pdu.call_identifier;
+        if self.individual_calls.contains_key(&call_id) {
+            self.individual_floor_demand(queue, call_id, requesting_party.ssi);
+            return;
+        }
 
         let Some(call) = self.active_calls.get_mut(&call_id) else {
             tracing::warn!("U-TX DEMAND for unknown call_id={}", call_id);
@@ -996,10 +1426,11 @@ impl CcBsSubentity {
         }
 
         // Grant the floor to the requesting MS
-        let ts = call.ts;
+        let (carrier, ts) = (call.carrier, call.ts);
         call.tx_active = true;
         call.hangtime_start = None;
         call.source_issi = requesting_party.ssi;
+        let priority = call.priority;
 
         // Update caller_addr for local calls
         if let CallOrigin::Local { caller_addr } = &mut call.origin {
@@ -1035,11 +1466,18 @@ impl CcBsSubentity {
         tracing::info!("-> {:?} sdu {}", d_tx_granted_individual, sdu.dump_bin());
 
         let requesting_addr = TetraAddress::new(requesting_party.ssi, SsiType::Issi);
-        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, requesting_addr, ts);
+        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, requesting_addr, (carrier, ts));
         queue.push_back(msg);
 
         // ETSI 14.5.2.2.1 b): Send group D-TX GRANTED (GrantedToOtherUser) to GSSI
-        self.send_d_tx_granted_facch(queue, call_id, requesting_party.ssi, dest_addr.ssi, ts);
+        self.send_d_tx_granted_facch(
+            queue,
+            call_id,
+            TransmissionGrant::GrantedToOtherUser,
+            requesting_party.ssi,
+            dest_addr,
+            (carrier, ts),
+        );
 
         // Notify UMAC to resume traffic mode (exit hangtime) for this timeslot.
         queue.push_back(SapMsg {
@@ -1051,7 +1489,9 @@ impl CcBsSubentity {
                 call_id,
                 source_issi: requesting_party.ssi,
                 dest_gssi: dest_addr.ssi,
+                carrier,
                 ts,
+                priority,
             }),
         });
 
@@ -1069,7 +1509,9 @@ impl CcBsSubentity {
                     call_id,
                     source_issi: requesting_party.ssi,
                     dest_gssi: dest_addr.ssi,
+                    carrier: call.carrier,
                     ts: call.ts,
+                    priority,
                 }),
             });
         }
@@ -1094,6 +1536,16 @@ impl CcBsSubentity {
 
         let call_id = pdu.call_identifier;
         tracing::info!("U-RELEASE: call_id={} cause={}", call_id, pdu.disconnect_cause);
+        if self.individual_calls.contains_key(&call_id) {
+            // The releasing party is done with the call, only the other party gets a D-RELEASE
+            let sender = prim.received_tetra_address.ssi;
+            self.release_individual_call(queue, call_id, pdu.disconnect_cause, Some(sender));
+            return;
+        }
+        if self.call_queue.remove_where(|e| e.call_id == call_id).is_some() {
+            tracing::info!("U-RELEASE: queued call_id={} abandoned", call_id);
+            return;
+        }
         self.release_call(queue, call_id, DisconnectCause::UserRequestedDisconnection);
     }
 
@@ -1123,6 +1575,28 @@ impl CcBsSubentity {
         let call_id = pdu.call_identifier;
         let disconnect_cause = pdu.disconnect_cause;
 
+        // Either party may disconnect an individual call, both then get a D-RELEASE
+        if let Some(call) = self.individual_calls.get(&call_id) {
+            if !call.involves(sender.ssi) {
+                tracing::warn!(
+                    "U-DISCONNECT from ISSI {} for individual call_id={} it is not part of",
+                    sender.ssi,
+                    call_id
+                );
+                return;
+            }
+            tracing::info!("U-DISCONNECT: ISSI {} disconnecting individual call_id={}", sender.ssi, call_id);
+            self.release_individual_call(queue, call_id, disconnect_cause, None);
+            return;
+        }
+
+        // A queued caller giving up, confirm with D-RELEASE
+        if let Some(entry) = self.call_queue.remove_where(|e| e.call_id == call_id) {
+            tracing::info!("U-DISCONNECT: ISSI {} abandoning queued call_id={}", sender.ssi, call_id);
+            self.reject_queued_call(queue, entry, DisconnectCause::UserRequestedDisconnection);
+            return;
+        }
+
         let Some(call) = self.active_calls.get(&call_id) else {
             tracing::debug!("U-DISCONNECT for unknown call_id={} (likely duplicate)", call_id);
             return;
@@ -1182,61 +1656,332 @@ impl CcBsSubentity {
         }
     }
 
-    /// Handle incoming CallControl messages from Brew
-    pub fn rx_call_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
-        let SapMsgInner::CmceCallControl(call_control) = message.msg else {
-            panic!("Expected CmceCallControl message");
+    /// Handle U-INFO. DTMF digits sent within a group call bridged to Brew are forwarded there.
+    fn rx_u_info(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
         };
+        let issi = prim.received_tetra_address.ssi;
 
-        match call_control {
-            CallControl::NetworkCallStart {
-                brew_uuid,
-                source_issi,
-                dest_gssi,
-                priority,
-            } => {
-                self.rx_network_call_start(queue, brew_uuid, source_issi, dest_gssi, priority);
-            }
-            CallControl::NetworkCallEnd { brew_uuid } => {
-                self.rx_network_call_end(queue, brew_uuid);
+        let pdu = match UInfo::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
             }
-            CallControl::UlInactivityTimeout { ts } => {
-                self.handle_ul_inactivity_timeout(queue, ts);
+            Err(e) => {
+                tracing::warn!("Failed parsing U-INFO: {:?}", e);
+                return;
             }
-            _ => {
-                tracing::warn!("Unexpected CallControl message: {:?}", call_control);
+        };
+        let Some(elem) = &pdu.dtmf else {
+            unimplemented_log!("U-INFO without DTMF from ISSI {}", issi);
+            return;
+        };
+        let dtmf = match Dtmf::from_type3(elem) {
+            Ok(dtmf) => dtmf,
+            Err(e) => {
+                tracing::warn!("Failed parsing DTMF in U-INFO from ISSI {}: {:?}", issi, e);
+                return;
             }
+        };
+        if dtmf.dtmf_type != DtmfType::DtmfToneStart || dtmf.digits.is_empty() {
+            tracing::debug!("U-INFO from ISSI {}: ignoring {}", issi, dtmf);
+            return;
         }
-    }
-
-    /// Handle network-initiated group call start
-    fn rx_network_call_start(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, source_issi: u32, dest_gssi: u32, _priority: u8) {
-        assert!(brew::is_brew_gssi_routable(&self.config, dest_gssi));
 
-        if !self.has_listener(dest_gssi) {
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.active_calls.get(&call_id) else {
             tracing::info!(
-                "CMCE: ignoring network call start uuid={} gssi={} (no listeners)",
-                brew_uuid,
-                dest_gssi
+                "U-INFO: DTMF {:?} from ISSI {} outside a group call, call_id={}",
+                dtmf.digits,
+                issi,
+                call_id
             );
-            self.drop_group_calls_if_unlistened(queue, dest_gssi);
-
-            // We already checked this is cleared for brew
-            queue.push_back(SapMsg {
-                sap: Sap::Control,
-                src: TetraEntity::Cmce,
-                dest: TetraEntity::Brew,
-                dltime: self.dltime,
-                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
-            });
+            return;
+        };
+        if !brew::feature_dtmf_enabled(&self.config) || !brew::is_brew_gssi_routable(&self.config, call.dest_gssi) {
+            tracing::debug!("U-INFO: DTMF {:?} on call_id={} not forwarded to Brew", dtmf.digits, call_id);
             return;
         }
-
-        // Check if there is an active call for this GSSI (speaker change scenario)
-        if let Some((call_id, call)) = self.active_calls.iter_mut().find(|(_, c)| c.dest_gssi == dest_gssi) {
-            // Reject speaker change if a local MS is already transmitting
-            if call.tx_active {
-                tracing::warn!(
+        tracing::info!(
+            "U-INFO: forwarding DTMF {:?} from ISSI {} on call_id={} to Brew",
+            dtmf.digits,
+            issi,
+            call_id
+        );
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Brew,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::Dtmf {
+                call_id,
+                digits: dtmf.digits,
+            }),
+        });
+    }
+
+    /// Deliver DTMF digits received from Brew to the members of a group call with D-INFO, on the traffic channel
+    fn rx_network_dtmf(&mut self, queue: &mut MessageQueue, call_id: u16, digits: &str) {
+        let Some(call) = self.active_calls.get(&call_id) else {
+            tracing::warn!("DTMF from Brew for unknown call_id={}", call_id);
+            return;
+        };
+        let Some((_, dest_addr, _)) = self.cached_setups.get(&call_id) else {
+            tracing::error!("No cached D-SETUP for call_id={}", call_id);
+            return;
+        };
+
+        let digits: Vec<char> = digits.chars().collect();
+        for chunk in digits.chunks(DTMF_MAX_DIGITS) {
+            let chunk: String = chunk.iter().collect();
+            let Some(dtmf) = Dtmf::tone_start(&chunk) else {
+                tracing::warn!("Invalid DTMF digits {:?} from Brew for call_id={}", chunk, call_id);
+                return;
+            };
+            let pdu = DInfo {
+                call_identifier: call_id,
+                reset_call_time_out_timer_t310_: false,
+                poll_request: false,
+                new_call_identifier: None,
+                call_time_out: None,
+                call_time_out_set_up_phase_t301_t302_: None,
+                call_ownership: None,
+                modify: None,
+                call_status: None,
+                temporary_address: None,
+                notification_indicator: None,
+                poll_response_percentage: None,
+                poll_response_number: None,
+                dtmf: Some(dtmf.to_type3()),
+                facility: None,
+                poll_response_addresses: None,
+                proprietary: None,
+            };
+
+            let mut sdu = BitBuffer::new_autoexpand(96);
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DInfo");
+            sdu.seek(0);
+            tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+            queue.push_back(Self::build_sapmsg_stealing(sdu, self.dltime, *dest_addr, (call.carrier, call.ts)));
+        }
+    }
+
+    /// Handle U-CALL RESTORE from an MS that reselected this cell during a group call (ETSI 14.5.2.4).
+    /// The call identifier was assigned by the old cell, so the call is found by its group. The MS is
+    /// told our call identifier in D-CALL RESTORE, together with the traffic channel of the call.
+    fn rx_u_call_restore(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let issi = prim.received_tetra_address.ssi;
+
+        let pdu = match UCallRestore::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-CALL RESTORE: {:?}", e);
+                return;
+            }
+        };
+
+        let gssi = pdu.other_party_ssi.map(|ssi| ssi as u32);
+        let found = gssi.and_then(|gssi| self.active_calls.iter().find(|(_, call)| call.dest_gssi == gssi));
+        let Some((&call_id, call)) = found else {
+            tracing::info!(
+                "U-CALL RESTORE from ISSI {}: no group call to {:?} on this cell, old call_id={}",
+                issi,
+                gssi,
+                pdu.call_identifier
+            );
+            Self::send_d_release_to(
+                queue,
+                message.dltime,
+                pdu.call_identifier,
+                issi,
+                DisconnectCause::InvalidCallIdentifier,
+            );
+            return;
+        };
+
+        // The floor is not handed over with the restoration, the MS asks for it again with U-TX DEMAND
+        let transmission_grant = if call.tx_active {
+            TransmissionGrant::GrantedToOtherUser
+        } else {
+            TransmissionGrant::NotGranted
+        };
+        tracing::info!(
+            "U-CALL RESTORE from ISSI {}: restoring call_id={} (old call_id={}) to GSSI {} on carrier={} ts={}",
+            issi,
+            call_id,
+            pdu.call_identifier,
+            call.dest_gssi,
+            call.carrier,
+            call.ts
+        );
+
+        let d_call_restore = DCallRestore {
+            call_identifier: pdu.call_identifier,
+            transmission_grant: transmission_grant.into_raw() as u8,
+            transmission_request_permission: false,
+            reset_call_time_out_timer_t310_: true,
+            new_call_identifier: (call_id != pdu.call_identifier).then_some(call_id as u64),
+            call_time_out: Some(CallTimeout::T5m.into_raw()),
+            call_status: None,
+            modify: None,
+            notification_indicator: None,
+            facility: None,
+            temporary_address: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(48);
+        d_call_restore.to_bitbuf(&mut sdu).expect("Failed to serialize DCallRestore");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_call_restore, sdu.dump_bin());
+
+        let mut timeslots = [false; 4];
+        timeslots[call.ts as usize - 1] = true;
+        let chan_alloc = CmceChanAllocReq {
+            usage: Some(call.usage),
+            alloc_type: ChanAllocType::Replace,
+            carrier: Some(call.carrier),
+            timeslots,
+            ul_dl_assigned: UlDlAssignment::Both,
+        };
+        let address = TetraAddress::new(issi, SsiType::Issi);
+        queue.push_back(Self::build_sapmsg(
+            sdu,
+            Some(chan_alloc),
+            message.dltime,
+            address,
+            Layer2Service::Unacknowledged,
+            None,
+        ));
+    }
+
+    /// Handle incoming CallControl messages from Brew
+    pub fn rx_call_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::CmceCallControl(call_control) = message.msg else {
+            panic!("Expected CmceCallControl message");
+        };
+
+        match call_control {
+            CallControl::NetworkCallStart {
+                brew_uuid,
+                source_issi,
+                dest_gssi,
+                priority,
+            } => {
+                self.rx_network_call_start(queue, brew_uuid, source_issi, dest_gssi, priority);
+            }
+            CallControl::NetworkCallEnd { brew_uuid } => {
+                self.rx_network_call_end(queue, brew_uuid);
+            }
+            CallControl::UlInactivityTimeout { carrier, ts } => {
+                self.handle_ul_inactivity_timeout(queue, carrier, ts);
+            }
+            CallControl::ListCalls { req_id } => {
+                queue.push_back(SapMsg {
+                    sap: Sap::Control,
+                    src: TetraEntity::Cmce,
+                    dest: message.src,
+                    dltime: self.dltime,
+                    msg: SapMsgInner::CmceCallControl(CallControl::CallList {
+                        req_id,
+                        calls: self.list_calls(),
+                    }),
+                });
+            }
+            CallControl::ReleaseCall { call_id } => {
+                self.rx_release_call_request(queue, call_id);
+            }
+            CallControl::Dtmf { call_id, digits } => {
+                self.rx_network_dtmf(queue, call_id, &digits);
+            }
+            _ => {
+                tracing::warn!("Unexpected CallControl message: {:?}", call_control);
+            }
+        }
+    }
+
+    /// Snapshot of all active group and individual calls, ordered by call identifier
+    fn list_calls(&self) -> Vec<CallInfo> {
+        let group = self.active_calls.iter().map(|(call_id, call)| CallInfo {
+            call_id: *call_id,
+            individual: false,
+            source_ssi: call.source_issi,
+            dest_ssi: call.dest_gssi,
+            carrier: call.carrier,
+            timeslots: vec![call.ts],
+            network: matches!(call.origin, CallOrigin::Network { .. }),
+            tx_active: call.tx_active,
+        });
+        let individual = self.individual_calls.iter().map(|(call_id, call)| CallInfo {
+            call_id: *call_id,
+            individual: true,
+            source_ssi: call.calling_addr.ssi,
+            dest_ssi: call.called_issi,
+            carrier: call.circuits.first().map_or(0, |c| c.carrier),
+            timeslots: call.circuits.iter().map(|c| c.ts).collect(),
+            network: false,
+            tx_active: call.tx_issi.is_some() || (call.duplex && call.state == IndividualCallState::Connected),
+        });
+        let mut calls: Vec<CallInfo> = group.chain(individual).collect();
+        calls.sort_by_key(|c| c.call_id);
+        calls
+    }
+
+    /// Release a call on operator request
+    fn rx_release_call_request(&mut self, queue: &mut MessageQueue, call_id: u16) {
+        if self.individual_calls.contains_key(&call_id) {
+            tracing::info!("CMCE: releasing individual call_id={} on request", call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection, None);
+            return;
+        }
+        if let Some(entry) = self.call_queue.remove_where(|e| e.call_id == call_id) {
+            tracing::info!("CMCE: removing queued call_id={} on request", call_id);
+            self.reject_queued_call(queue, entry, DisconnectCause::SwmiRequestedDisconnection);
+            return;
+        }
+        let Some(call) = self.active_calls.get(&call_id) else {
+            tracing::warn!("CMCE: release requested for unknown call_id={}", call_id);
+            return;
+        };
+        tracing::info!("CMCE: releasing call_id={} gssi={} on request", call_id, call.dest_gssi);
+        self.release_group_call(queue, call_id, DisconnectCause::SwmiRequestedDisconnection);
+    }
+
+    /// Handle network-initiated group call start
+    fn rx_network_call_start(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid, source_issi: u32, dest_gssi: u32, priority: u8) {
+        assert!(brew::is_brew_gssi_routable(&self.config, dest_gssi));
+
+        if priority < EMERGENCY_CALL_PRIORITY && !self.has_listener(dest_gssi) {
+            tracing::info!(
+                "CMCE: ignoring network call start uuid={} gssi={} (no listeners)",
+                brew_uuid,
+                dest_gssi
+            );
+            self.drop_group_calls_if_unlistened(queue, dest_gssi);
+
+            // We already checked this is cleared for brew
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Brew,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallEnd { brew_uuid }),
+            });
+            return;
+        }
+
+        // Check if there is an active call for this GSSI (speaker change scenario)
+        if let Some((call_id, call)) = self.active_calls.iter_mut().find(|(_, c)| c.dest_gssi == dest_gssi) {
+            // Reject speaker change if a local MS is already transmitting
+            if call.tx_active {
+                tracing::warn!(
                     "CMCE: network speaker change rejected, ISSI {} already transmitting on gssi={}",
                     call.source_issi,
                     dest_gssi
@@ -1274,14 +2019,22 @@ impl CcBsSubentity {
 
             // Extract values before mutable borrow ends
             let call_id_val = *call_id;
-            let ts = call.ts;
+            let (carrier, ts) = (call.carrier, call.ts);
             let usage = call.usage;
+            let call_priority = call.priority;
 
             // End the mutable borrow
             let _ = call;
 
             // Send D-TX GRANTED via FACCH to notify radios of new speaker
-            self.send_d_tx_granted_facch(queue, call_id_val, source_issi, dest_gssi, ts);
+            self.send_d_tx_granted_facch(
+                queue,
+                call_id_val,
+                TransmissionGrant::GrantedToOtherUser,
+                source_issi,
+                TetraAddress::new(dest_gssi, SsiType::Gssi),
+                (carrier, ts),
+            );
 
             // Notify UMAC to resume traffic mode (exit hangtime) for this timeslot.
             queue.push_back(SapMsg {
@@ -1293,7 +2046,9 @@ impl CcBsSubentity {
                     call_id: call_id_val,
                     source_issi,
                     dest_gssi,
+                    carrier,
                     ts,
+                    priority: call_priority,
                 }),
             });
 
@@ -1306,6 +2061,7 @@ impl CcBsSubentity {
                 msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
                     brew_uuid,
                     call_id: call_id_val,
+                    carrier,
                     ts,
                     usage,
                 }),
@@ -1313,32 +2069,52 @@ impl CcBsSubentity {
             return;
         }
 
-        // New network call - allocate circuit
-        let circuit = match {
-            let mut state = self.config.state_write();
-            self.circuits.allocate_circuit_with_allocator(
-                Direction::Both,
-                CommunicationType::P2Mp,
-                &mut state.timeslot_alloc,
-                TimeslotOwner::Cmce,
-            )
-        } {
-            Ok(c) => c.clone(),
-            Err(err) => {
-                tracing::warn!("CMCE: failed to allocate circuit for network call: {:?}", err);
-                return;
-            }
+        // New network call - allocate circuit, unless calls queued before this one go first
+        let call_id = self.circuits.get_next_call_id();
+        let circuit = if self.call_queue.has_precedence_over(priority) {
+            None
+        } else {
+            self.allocate_group_circuit(queue, call_id, priority, CommunicationType::P2Mp)
+        };
+        let Some(circuit) = circuit else {
+            tracing::info!(
+                "CMCE: no circuit for network call brew_uuid={} gssi={}, call_id={} priority={}",
+                brew_uuid,
+                dest_gssi,
+                call_id,
+                priority
+            );
+            let request = QueuedRequest::Network {
+                brew_uuid,
+                source_issi,
+                dest_gssi,
+            };
+            self.enqueue_call(queue, call_id, priority, request);
+            return;
         };
+        self.start_network_group_call(queue, brew_uuid, source_issi, dest_gssi, priority, circuit);
+    }
 
+    /// Set up a network-initiated group call on the given circuit and report the resources to Brew
+    fn start_network_group_call(
+        &mut self,
+        queue: &mut MessageQueue,
+        brew_uuid: uuid::Uuid,
+        source_issi: u32,
+        dest_gssi: u32,
+        priority: u8,
+        circuit: CmceCircuit,
+    ) {
         let call_id = circuit.call_id;
-        let ts = circuit.ts;
+        let (carrier, ts) = (circuit.carrier, circuit.ts);
         let usage = circuit.usage;
 
         tracing::info!(
-            "CMCE: starting NEW network call brew_uuid={} gssi={} speaker={} ts={} call_id={}",
+            "CMCE: starting NEW network call brew_uuid={} gssi={} speaker={} carrier={} ts={} call_id={}",
             brew_uuid,
             dest_gssi,
             source_issi,
+            carrier,
             ts,
             call_id
         );
@@ -1368,7 +2144,7 @@ impl CcBsSubentity {
             },
             transmission_grant: TransmissionGrant::GrantedToOtherUser,
             transmission_request_permission: false,
-            call_priority: 0,
+            call_priority: priority,
             notification_indicator: None,
             temporary_address: None,
             calling_party_address_ssi: Some(source_issi),
@@ -1385,7 +2161,7 @@ impl CcBsSubentity {
         self.cached_setups.insert(call_id, (d_setup, dest_addr, None));
         let (d_setup_ref, _, _) = self.cached_setups.get(&call_id).unwrap();
 
-        let (setup_sdu, setup_chan_alloc) = Self::build_d_setup_prim(d_setup_ref, usage, ts, UlDlAssignment::Both);
+        let (setup_sdu, setup_chan_alloc) = Self::build_d_setup_prim(d_setup_ref, usage, carrier, ts, UlDlAssignment::Both);
         let setup_msg = Self::build_sapmsg(
             setup_sdu,
             Some(setup_chan_alloc),
@@ -1446,11 +2222,13 @@ impl CcBsSubentity {
                 origin: CallOrigin::Network { brew_uuid },
                 dest_gssi,
                 source_issi,
+                carrier,
                 ts,
                 usage,
                 tx_active: true,
                 hangtime_start: None,
                 brew_uuid: Some(brew_uuid),
+                priority,
             },
         );
 
@@ -1463,6 +2241,7 @@ impl CcBsSubentity {
             msg: SapMsgInner::CmceCallControl(CallControl::NetworkCallReady {
                 brew_uuid,
                 call_id,
+                carrier,
                 ts,
                 usage,
             }),
@@ -1471,6 +2250,18 @@ impl CcBsSubentity {
 
     /// Handle network call end request
     fn rx_network_call_end(&mut self, queue: &mut MessageQueue, brew_uuid: uuid::Uuid) {
+        let queued = self
+            .call_queue
+            .remove_where(|e| matches!(e.request, QueuedRequest::Network { brew_uuid: uuid, .. } if uuid == brew_uuid));
+        if let Some(entry) = queued {
+            tracing::info!(
+                "CMCE: network call brew_uuid={} ended while queued, call_id={}",
+                brew_uuid,
+                entry.call_id
+            );
+            return;
+        }
+
         // Find the call by brew_uuid field (works for both Local and Network origin calls)
         let Some((call_id, call)) = self
             .active_calls
@@ -1492,7 +2283,7 @@ impl CcBsSubentity {
         // If currently transmitting, enter hangtime instead of immediate release
         let tx_active = call.tx_active;
         let dest_gssi = call.dest_gssi;
-        let ts = call.ts;
+        let (carrier, ts) = (call.carrier, call.ts);
 
         if tx_active {
             if let Some(active_call) = self.active_calls.get_mut(&call_id) {
@@ -1501,7 +2292,7 @@ impl CcBsSubentity {
                 active_call.brew_uuid = None;
             }
             // Send D-TX CEASED via FACCH
-            self.send_d_tx_ceased_facch(queue, call_id, dest_gssi, ts);
+            self.send_d_tx_ceased_facch(queue, call_id, TetraAddress::new(dest_gssi, SsiType::Gssi), (carrier, ts));
 
             // Notify UMAC to enter hangtime signalling mode on this traffic timeslot.
             queue.push_back(SapMsg {
@@ -1509,7 +2300,7 @@ impl CcBsSubentity {
                 src: TetraEntity::Cmce,
                 dest: TetraEntity::Umac,
                 dltime: self.dltime,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
             });
         } else {
             // Already in hangtime or idle, release immediately
@@ -1518,10 +2309,18 @@ impl CcBsSubentity {
     }
 
     /// Send D-TX GRANTED via FACCH stealing
-    fn send_d_tx_granted_facch(&mut self, queue: &mut MessageQueue, call_id: u16, source_issi: u32, dest_gssi: u32, ts: u8) {
+    fn send_d_tx_granted_facch(
+        &mut self,
+        queue: &mut MessageQueue,
+        call_id: u16,
+        transmission_grant: TransmissionGrant,
+        source_issi: u32,
+        dest_addr: TetraAddress,
+        chan: (u8, u8),
+    ) {
         let pdu = DTxGranted {
             call_identifier: call_id,
-            transmission_grant: TransmissionGrant::GrantedToOtherUser.into_raw() as u8,
+            transmission_grant: transmission_grant.into_raw() as u8,
             transmission_request_permission: false,
             encryption_control: false,
             reserved: false,
@@ -1540,22 +2339,38 @@ impl CcBsSubentity {
         sdu.seek(0);
         tracing::info!("-> FACCH {:?} sdu {}", pdu, sdu.dump_bin());
 
-        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
-        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, ts);
+        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, chan);
         queue.push_back(msg);
     }
 
     /// Handle UL inactivity timeout from UMAC: a radio disappeared mid-transmission.
     /// Treat identically to rx_u_tx_ceased — force TX ceased, enter hangtime.
-    fn handle_ul_inactivity_timeout(&mut self, queue: &mut MessageQueue, ts: u8) {
+    fn handle_ul_inactivity_timeout(&mut self, queue: &mut MessageQueue, carrier: u8, ts: u8) {
         // Find the active call on this timeslot with tx_active == true
         let call_entry = self
             .active_calls
             .iter()
-            .find(|(_, call)| call.ts == ts && call.tx_active)
+            .find(|(_, call)| call.carrier == carrier && call.ts == ts && call.tx_active)
             .map(|(id, _)| *id);
 
         let Some(call_id) = call_entry else {
+            // Simplex individual calls have floor control too
+            let individual = self
+                .individual_calls
+                .iter()
+                .find(|(_, call)| {
+                    !call.duplex && call.tx_issi.is_some() && call.circuits.first().is_some_and(|c| c.carrier == carrier && c.ts == ts)
+                })
+                .map(|(id, call)| (*id, call.tx_issi.unwrap()));
+            if let Some((call_id, tx_issi)) = individual {
+                tracing::warn!(
+                    "UL inactivity timeout on ts={}, forcing TX ceased for individual call_id={}",
+                    ts,
+                    call_id
+                );
+                self.individual_floor_released(queue, call_id, tx_issi);
+                return;
+            }
             tracing::debug!("UL inactivity timeout on ts={} but no active transmitting call found", ts);
             return;
         };
@@ -1568,7 +2383,7 @@ impl CcBsSubentity {
         call.hangtime_start = Some(self.dltime);
 
         // Send D-TX CEASED via FACCH to all group members
-        self.send_d_tx_ceased_facch(queue, call_id, dest_gssi, ts);
+        self.send_d_tx_ceased_facch(queue, call_id, TetraAddress::new(dest_gssi, SsiType::Gssi), (carrier, ts));
 
         // Notify UMAC to enter hangtime signalling mode
         queue.push_back(SapMsg {
@@ -1576,7 +2391,7 @@ impl CcBsSubentity {
             src: TetraEntity::Cmce,
             dest: TetraEntity::Umac,
             dltime: self.dltime,
-            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
         });
 
         // Notify Brew to stop forwarding audio
@@ -1586,13 +2401,13 @@ impl CcBsSubentity {
                 src: TetraEntity::Cmce,
                 dest: TetraEntity::Brew,
                 dltime: self.dltime,
-                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, ts }),
+                msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
             });
         }
     }
 
     /// Send D-TX CEASED via FACCH stealing
-    fn send_d_tx_ceased_facch(&mut self, queue: &mut MessageQueue, call_id: u16, dest_gssi: u32, ts: u8) {
+    fn send_d_tx_ceased_facch(&mut self, queue: &mut MessageQueue, call_id: u16, dest_addr: TetraAddress, chan: (u8, u8)) {
         let pdu = DTxCeased {
             call_identifier: call_id,
             transmission_request_permission: false, // ETSI 14.8.43: 0 = allowed to request transmission
@@ -1607,8 +2422,627 @@ impl CcBsSubentity {
         sdu.seek(0);
         tracing::info!("-> FACCH {:?} sdu {}", pdu, sdu.dump_bin());
 
-        let dest_addr = TetraAddress::new(dest_gssi, SsiType::Gssi);
-        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, ts);
+        let msg = Self::build_sapmsg_stealing(sdu, self.dltime, dest_addr, chan);
         queue.push_back(msg);
     }
+
+    fn is_in_individual_call(&self, issi: u32) -> bool {
+        self.individual_calls.values().any(|call| call.involves(issi))
+    }
+
+    /// Resolve the party an individual call to `requested` ends up at, applying call forwarding.
+    /// Forwarding is followed for a single hop only, so forwarding loops can't occur.
+    fn resolve_called_party(&self, calling_issi: u32, requested: u32) -> Result<u32, DisconnectCause> {
+        let state = self.config.state_read();
+        let subscribers = &state.subscribers;
+
+        let mut called = requested;
+        if let Some(forward_to) = subscribers.call_forwarding(called, CallForwardingCondition::Unconditional) {
+            called = forward_to;
+        } else if !subscribers.is_registered(called) {
+            called = subscribers
+                .call_forwarding(called, CallForwardingCondition::NotReachable)
+                .ok_or(DisconnectCause::CalledPartyNotReachable)?;
+        } else if self.is_in_individual_call(called) {
+            called = subscribers
+                .call_forwarding(called, CallForwardingCondition::Busy)
+                .ok_or(DisconnectCause::CalledPartyBusy)?;
+        }
+
+        if called == calling_issi {
+            return Err(DisconnectCause::NotAllowedTrafficCase);
+        }
+        if !subscribers.is_registered(called) {
+            return Err(DisconnectCause::CalledPartyNotReachable);
+        }
+        if self.is_in_individual_call(called) {
+            return Err(DisconnectCause::CalledPartyBusy);
+        }
+        Ok(called)
+    }
+
+    fn build_individual_chan_alloc(circuit: &CmceCircuit) -> CmceChanAllocReq {
+        let mut timeslots = [false; 4];
+        timeslots[circuit.ts as usize - 1] = true;
+        CmceChanAllocReq {
+            usage: Some(circuit.usage),
+            alloc_type: ChanAllocType::Replace,
+            carrier: Some(circuit.carrier),
+            timeslots,
+            ul_dl_assigned: UlDlAssignment::Both,
+        }
+    }
+
+    /// Send a PDU to one party of an individual call. PDUs carrying a channel allocation go out
+    /// unacknowledged, like the D-CONNECT and D-SETUP of group calls.
+    fn send_to_issi(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: BitBuffer, chan_alloc: Option<CmceChanAllocReq>) {
+        let layer2service = if chan_alloc.is_some() {
+            Layer2Service::Unacknowledged
+        } else {
+            Layer2Service::Acknowledged
+        };
+        let address = TetraAddress::new(issi, SsiType::Issi);
+        queue.push_back(Self::build_sapmsg(sdu, chan_alloc, dltime, address, layer2service, None));
+    }
+
+    fn send_d_release_to(queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16, issi: u32, disconnect_cause: DisconnectCause) {
+        let pdu = DRelease {
+            call_identifier: call_id,
+            disconnect_cause,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DRelease");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+        let address = TetraAddress::new(issi, SsiType::Issi);
+        queue.push_back(Self::build_sapmsg(sdu, None, dltime, address, Layer2Service::Unacknowledged, None));
+    }
+
+    /// Handle U-SETUP for an individual call to another local ISSI (ETSI 14.5.1)
+    fn rx_u_setup_individual(&mut self, queue: &mut MessageQueue, message: SapMsg, pdu: USetup) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+        let calling_party = prim.received_tetra_address;
+
+        let Some(requested) = pdu.called_party_ssi else {
+            tracing::warn!("U-SETUP without called_party_ssi, ignoring");
+            return;
+        };
+        let requested = requested as u32;
+        let call_id = self.circuits.get_next_call_id();
+
+        let resolved = if self.is_in_individual_call(calling_party.ssi) {
+            Err(DisconnectCause::ConcurrentSetUpNotSupported)
+        } else {
+            self.resolve_called_party(calling_party.ssi, requested)
+        };
+        let called_issi = match resolved {
+            Ok(issi) => issi,
+            Err(cause) => {
+                tracing::info!(
+                    "CMCE: rejecting individual call from issi={} to issi={}: {}",
+                    calling_party.ssi,
+                    requested,
+                    cause
+                );
+                Self::send_d_release_to(queue, message.dltime, call_id, calling_party.ssi, cause);
+                return;
+            }
+        };
+        if called_issi != requested {
+            tracing::info!(
+                "CMCE: individual call from issi={} to issi={} forwarded to issi={}",
+                calling_party.ssi,
+                requested,
+                called_issi
+            );
+        }
+
+        if pdu.call_priority >= EMERGENCY_CALL_PRIORITY {
+            let cfg = self.config.config();
+            raise_emergency_alarm(
+                cfg.emergency.as_ref(),
+                EmergencyCallType::Individual,
+                calling_party.ssi,
+                called_issi,
+                call_id,
+            );
+        }
+
+        tracing::info!(
+            "rx_u_setup: {} {} individual call from ISSI {} to ISSI {} call_id={}",
+            if pdu.hook_method_selection { "hook" } else { "direct" },
+            if pdu.simplex_duplex_selection { "duplex" } else { "simplex" },
+            calling_party.ssi,
+            called_issi,
+            call_id
+        );
+
+        // Hook signalling waits for the called user to answer, give it time to do so
+        let setup_phase = if pdu.hook_method_selection {
+            CallTimeoutSetupPhase::T60s
+        } else {
+            CallTimeoutSetupPhase::T10s
+        };
+        Self::send_d_call_proceeding(queue, &message, &pdu, call_id, setup_phase, None);
+
+        self.individual_calls.insert(
+            call_id,
+            IndividualCall {
+                calling_addr: calling_party,
+                called_issi,
+                hook: pdu.hook_method_selection,
+                duplex: pdu.simplex_duplex_selection,
+                basic_service_information: pdu.basic_service_information.clone(),
+                call_priority: pdu.call_priority,
+                state: IndividualCallState::Setup,
+                timer_start: message.dltime,
+                circuits: Vec::new(),
+                tx_issi: None,
+            },
+        );
+
+        if pdu.hook_method_selection {
+            // Circuits are only allocated once the called party answers
+            self.send_individual_d_setup(queue, message.dltime, call_id, TransmissionGrant::NotGranted);
+        } else {
+            self.connect_individual_call(queue, message.dltime, call_id);
+        }
+    }
+
+    fn allocate_individual_circuits(
+        &mut self,
+        call_id: u16,
+        duplex: bool,
+        comm_type: CommunicationType,
+    ) -> Result<Vec<CmceCircuit>, CircuitErr> {
+        let mut state = self.config.state_write();
+        self.circuits.allocate_individual_circuits_with_allocator(
+            call_id,
+            duplex,
+            comm_type,
+            &mut state.timeslot_alloc,
+            TimeslotOwner::Cmce,
+        )
+    }
+
+    /// Send D-SETUP to the called party of an individual call. Carries the channel allocation
+    /// of the called party once circuits exist, i.e. with direct signalling.
+    fn send_individual_d_setup(&self, queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16, transmission_grant: TransmissionGrant) {
+        let Some(call) = self.individual_calls.get(&call_id) else {
+            return;
+        };
+
+        let pdu = DSetup {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: call.hook,
+            simplex_duplex_selection: call.duplex,
+            basic_service_information: call.basic_service_information.clone(),
+            transmission_grant,
+            transmission_request_permission: false,
+            call_priority: call.call_priority,
+            notification_indicator: None,
+            temporary_address: None,
+            calling_party_address_ssi: Some(call.calling_addr.ssi),
+            calling_party_extension: None,
+            external_subscriber_number: None,
+            facility: None,
+            dm_ms_address: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(80);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+        let chan_alloc = call.circuit_of(call.called_issi).map(Self::build_individual_chan_alloc);
+        Self::send_to_issi(queue, dltime, call.called_issi, sdu, chan_alloc);
+    }
+
+    /// Allocate the traffic circuits of an individual call and through-connect both parties.
+    /// With direct signalling, the called party learns about the call from the D-SETUP sent here.
+    fn connect_individual_call(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, call_id: u16) {
+        let Some(call) = self.individual_calls.get(&call_id) else {
+            return;
+        };
+        let duplex = call.duplex;
+        let comm_type = call.basic_service_information.communication_type;
+        let priority = call.call_priority;
+
+        // A duplex call needs two timeslots, so up to two calls may have to be pre-empted
+        let mut circuits = self.allocate_individual_circuits(call_id, duplex, comm_type);
+        for _ in 0..2 {
+            if circuits.is_ok() {
+                break;
+            }
+            let Some(victim) = self.preemption_candidate(priority) else {
+                break;
+            };
+            tracing::info!(
+                "CMCE: pre-empting call_id={} for individual call_id={} priority={}",
+                victim,
+                call_id,
+                priority
+            );
+            self.preempt_call(queue, victim);
+            circuits = self.allocate_individual_circuits(call_id, duplex, comm_type);
+        }
+        let circuits = match circuits {
+            Ok(circuits) => circuits,
+            Err(e) => {
+                tracing::error!("Failed to allocate circuits for individual call_id={}: {:?}", call_id, e);
+                self.release_individual_call(queue, call_id, DisconnectCause::CongestionInInfrastructure, None);
+                return;
+            }
+        };
+        for circuit in &circuits {
+            Self::signal_umac_circuit_open(queue, circuit, dltime);
+        }
+
+        let call = self.individual_calls.get_mut(&call_id).unwrap();
+        call.circuits = circuits;
+        call.state = IndividualCallState::Connected;
+        call.timer_start = dltime;
+        // In simplex calls, the calling party starts out with the floor
+        call.tx_issi = (!duplex).then_some(call.calling_addr.ssi);
+        let call = call.clone();
+
+        tracing::info!(
+            "CMCE: individual call_id={} connected ISSI {} <-> ISSI {} on ts={:?}",
+            call_id,
+            call.calling_addr.ssi,
+            call.called_issi,
+            call.circuits.iter().map(|c| c.ts).collect::<Vec<_>>()
+        );
+
+        // === 1) D-CONNECT to the calling party with its channel allocation ===
+        let d_connect = DConnect {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m,
+            hook_method_selection: call.hook,
+            simplex_duplex_selection: duplex,
+            transmission_grant: TransmissionGrant::Granted,
+            transmission_request_permission: false,
+            call_ownership: false, // Call ownership only applies to group calls
+            call_priority: None,
+            basic_service_information: None,
+            temporary_address: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect.to_bitbuf(&mut sdu).expect("Failed to serialize DConnect");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect, sdu.dump_bin());
+        let chan_alloc = call.circuit_of(call.calling_addr.ssi).map(Self::build_individual_chan_alloc);
+        Self::send_to_issi(queue, dltime, call.calling_addr.ssi, sdu, chan_alloc);
+
+        // === 2) Through-connect the called party ===
+        // In duplex calls both parties may talk at once, in simplex calls the caller talks first.
+        let called_grant = if duplex {
+            TransmissionGrant::Granted
+        } else {
+            TransmissionGrant::GrantedToOtherUser
+        };
+        if !call.hook {
+            self.send_individual_d_setup(queue, dltime, call_id, called_grant);
+            return;
+        }
+
+        let d_connect_ack = DConnectAcknowledge {
+            call_identifier: call_id,
+            call_time_out: CallTimeout::T5m.into_raw() as u8,
+            transmission_grant: called_grant.into_raw() as u8,
+            transmission_request_permission: false,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_connect_ack.to_bitbuf(&mut sdu).expect("Failed to serialize DConnectAcknowledge");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_connect_ack, sdu.dump_bin());
+        let chan_alloc = call.circuit_of(call.called_issi).map(Self::build_individual_chan_alloc);
+        Self::send_to_issi(queue, dltime, call.called_issi, sdu, chan_alloc);
+    }
+
+    /// Handle U-ALERT: the called party of a hook signalling call is being alerted
+    fn rx_u_alert(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address.ssi;
+
+        let pdu = match UAlert::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-ALERT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            tracing::warn!("U-ALERT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_issi != sender || call.state != IndividualCallState::Setup {
+            tracing::warn!(
+                "U-ALERT from ISSI {} unexpected for call_id={} in state {:?}",
+                sender,
+                call_id,
+                call.state
+            );
+            return;
+        }
+
+        tracing::info!("U-ALERT: ISSI {} alerted for call_id={}", sender, call_id);
+        call.state = IndividualCallState::Alerting;
+        call.timer_start = message.dltime;
+
+        let d_alert = DAlert {
+            call_identifier: call_id,
+            call_time_out_set_up_phase: CallTimeoutSetupPhase::T60s.into_raw() as u8,
+            reserved: true, // ETSI 14.7.1.1 note 1: set to 1 for backwards compatibility
+            simplex_duplex_selection: call.duplex,
+            call_queued: false,
+            basic_service_information: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        d_alert.to_bitbuf(&mut sdu).expect("Failed to serialize DAlert");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", d_alert, sdu.dump_bin());
+        Self::send_to_issi(queue, message.dltime, call.calling_addr.ssi, sdu, None);
+    }
+
+    /// Handle U-CONNECT: the called party of a hook signalling call answers
+    fn rx_u_connect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let sender = prim.received_tetra_address.ssi;
+
+        let pdu = match UConnect::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-CONNECT: {:?}", e);
+                return;
+            }
+        };
+
+        let call_id = pdu.call_identifier;
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            tracing::warn!("U-CONNECT for unknown call_id={}", call_id);
+            return;
+        };
+        if call.called_issi != sender {
+            tracing::warn!(
+                "U-CONNECT from ISSI {} which is not the called party of call_id={}",
+                sender,
+                call_id
+            );
+            return;
+        }
+        if call.state == IndividualCallState::Connected {
+            tracing::debug!("U-CONNECT for already connected call_id={}, ignoring", call_id);
+            return;
+        }
+
+        tracing::info!("U-CONNECT: ISSI {} answered call_id={}", sender, call_id);
+        // The called party may accept a duplex call as simplex only
+        call.duplex &= pdu.simplex_duplex_selection;
+        self.connect_individual_call(queue, message.dltime, call_id);
+    }
+
+    /// Simplex individual call: the party holding the floor stopped transmitting
+    fn individual_floor_released(&mut self, queue: &mut MessageQueue, call_id: u16, issi: u32) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        if call.duplex || call.tx_issi != Some(issi) {
+            tracing::debug!(
+                "U-TX CEASED from ISSI {} not holding the floor on call_id={}, ignoring",
+                issi,
+                call_id
+            );
+            return;
+        }
+
+        tracing::info!("U-TX CEASED: ISSI {} released floor on individual call_id={}", issi, call_id);
+        call.tx_issi = None;
+        let (carrier, ts) = (call.circuits[0].carrier, call.circuits[0].ts);
+        let parties = [call.calling_addr.ssi, call.called_issi];
+
+        for party in parties {
+            self.send_d_tx_ceased_facch(queue, call_id, TetraAddress::new(party, SsiType::Issi), (carrier, ts));
+        }
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorReleased { call_id, carrier, ts }),
+        });
+    }
+
+    /// Simplex individual call: a party requests the floor
+    fn individual_floor_demand(&mut self, queue: &mut MessageQueue, call_id: u16, issi: u32) {
+        let Some(call) = self.individual_calls.get_mut(&call_id) else {
+            return;
+        };
+        if call.duplex || call.state != IndividualCallState::Connected || !call.involves(issi) {
+            tracing::warn!("U-TX DEMAND from ISSI {} not applicable to individual call_id={}", issi, call_id);
+            return;
+        }
+        if let Some(tx_issi) = call.tx_issi {
+            if tx_issi != issi {
+                tracing::warn!(
+                    "U-TX DEMAND from ISSI {} rejected, ISSI {} already transmitting on call_id={}",
+                    issi,
+                    tx_issi,
+                    call_id
+                );
+            }
+            return;
+        }
+
+        tracing::info!("U-TX DEMAND: ISSI {} takes floor on individual call_id={}", issi, call_id);
+        call.tx_issi = Some(issi);
+        let (carrier, ts) = (call.circuits[0].carrier, call.circuits[0].ts);
+        let other = call.other_party(issi);
+        let priority = call.call_priority;
+
+        let requesting_addr = TetraAddress::new(issi, SsiType::Issi);
+        self.send_d_tx_granted_facch(queue, call_id, TransmissionGrant::Granted, issi, requesting_addr, (carrier, ts));
+        let other_addr = TetraAddress::new(other, SsiType::Issi);
+        self.send_d_tx_granted_facch(
+            queue,
+            call_id,
+            TransmissionGrant::GrantedToOtherUser,
+            issi,
+            other_addr,
+            (carrier, ts),
+        );
+
+        // dest_gssi carries the listening party here, UMAC only acts on the timeslot
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(CallControl::FloorGranted {
+                call_id,
+                source_issi: issi,
+                dest_gssi: other,
+                carrier,
+                ts,
+                priority,
+            }),
+        });
+    }
+
+    /// Release an individual call: send D-RELEASE to the parties, close circuits, clean up state.
+    /// `skip_issi` is a party that already left the call and needs no D-RELEASE.
+    fn release_individual_call(
+        &mut self,
+        queue: &mut MessageQueue,
+        call_id: u16,
+        disconnect_cause: DisconnectCause,
+        skip_issi: Option<u32>,
+    ) {
+        let Some(call) = self.individual_calls.remove(&call_id) else {
+            tracing::debug!("Release for unknown individual call_id={}", call_id);
+            return;
+        };
+        tracing::info!("CMCE: releasing individual call_id={} cause={}", call_id, disconnect_cause);
+
+        for issi in [call.calling_addr.ssi, call.called_issi] {
+            if Some(issi) != skip_issi {
+                Self::send_d_release_to(queue, self.dltime, call_id, issi, disconnect_cause);
+            }
+        }
+
+        for circuit in &call.circuits {
+            let (carrier, ts) = (circuit.carrier, circuit.ts);
+            let owned = self.circuits.dl[carrier as usize][ts as usize - 1]
+                .as_ref()
+                .is_some_and(|c| c.call_id == call_id);
+            if owned {
+                if let Ok(circuit) = self.circuits.close_circuit(Direction::Both, carrier, ts) {
+                    Self::signal_umac_circuit_close(queue, circuit, self.dltime);
+                }
+                self.release_timeslot(carrier, ts);
+            }
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Cmce,
+                dest: TetraEntity::Umac,
+                dltime: self.dltime,
+                msg: SapMsgInner::CmceCallControl(CallControl::CallEnded { call_id, carrier, ts }),
+            });
+        }
+    }
+
+    /// Run the no-answer timer of individual calls being set up, and the call time-out of connected ones
+    fn check_individual_call_timers(&mut self, queue: &mut MessageQueue) {
+        let expired: Vec<(u16, IndividualCallState)> = self
+            .individual_calls
+            .iter()
+            .filter(|(_, call)| {
+                let timeout = if call.state == IndividualCallState::Connected {
+                    INDIVIDUAL_CALL_TIMEOUT
+                } else {
+                    INDIVIDUAL_SETUP_TIMEOUT
+                };
+                call.timer_start.age(self.dltime) > timeout
+            })
+            .map(|(&call_id, call)| (call_id, call.state))
+            .collect();
+
+        for (call_id, state) in expired {
+            if state == IndividualCallState::Connected {
+                tracing::info!("Call time-out expired for individual call_id={}, releasing", call_id);
+                self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+            } else {
+                self.individual_no_reply(queue, call_id);
+            }
+        }
+    }
+
+    /// The called party didn't answer in time: forward the call if it has call forwarding
+    /// on no reply active, otherwise release the call
+    fn individual_no_reply(&mut self, queue: &mut MessageQueue, call_id: u16) {
+        let Some(call) = self.individual_calls.get(&call_id) else {
+            return;
+        };
+        let (calling_issi, called_issi) = (call.calling_addr.ssi, call.called_issi);
+
+        let forward_to = {
+            let state = self.config.state_read();
+            state
+                .subscribers
+                .call_forwarding(called_issi, CallForwardingCondition::NoReply)
+                .filter(|&issi| issi != calling_issi && state.subscribers.is_registered(issi) && !self.is_in_individual_call(issi))
+        };
+        let Some(forward_to) = forward_to else {
+            tracing::info!("No answer from ISSI {} on call_id={}, releasing", called_issi, call_id);
+            self.release_individual_call(queue, call_id, DisconnectCause::ExpiryOfTimer, None);
+            return;
+        };
+
+        tracing::info!(
+            "No answer from ISSI {} on call_id={}, forwarding to ISSI {}",
+            called_issi,
+            call_id,
+            forward_to
+        );
+        Self::send_d_release_to(queue, self.dltime, call_id, called_issi, DisconnectCause::ExpiryOfTimer);
+
+        let call = self.individual_calls.get_mut(&call_id).unwrap();
+        call.called_issi = forward_to;
+        call.state = IndividualCallState::Setup;
+        call.timer_start = self.dltime;
+        self.send_individual_d_setup(queue, self.dltime, call_id, TransmissionGrant::NotGranted);
+    }
 }
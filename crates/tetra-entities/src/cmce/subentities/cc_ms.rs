@@ -73,7 +73,7 @@ impl CcMsSubentity {
                 unimplemented_log!("{}", pdu_type);
             }
             _ => {
-                panic!();
+                tracing::warn!("route_rd_deliver: unexpected {}", pdu_type);
             }
         }
     }
@@ -94,14 +94,15 @@ impl SdsBsSubentity {
         }
     }
 
-    /// Handle incoming SDS data from Brew entity (network-originated SDS)
+    /// Handle incoming SDS data from the Brew entity (network-originated SDS) or the management API
     pub fn rx_sds_from_brew(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         let SapMsgInner::CmceSdsData(sds) = message.msg else {
             panic!("Expected CmceSdsData message");
         };
 
         tracing::info!(
-            "SDS: received from Brew: {} -> {}, type={}, {} bits",
+            "SDS: received from {:?}: {} -> {}, type={}, {} bits",
+            message.src,
             sds.source_issi,
             sds.dest_issi,
             sds.user_defined_data.type_identifier(),
@@ -109,7 +110,11 @@ impl SdsBsSubentity {
         );
 
         if !self.config.state_read().subscribers.is_registered(sds.dest_issi) {
-            tracing::warn!("SDS: dest ISSI {} from Brew is not locally registered, dropping", sds.dest_issi);
+            tracing::warn!(
+                "SDS: dest ISSI {} from {:?} is not locally registered, dropping",
+                sds.dest_issi,
+                message.src
+            );
             return;
         }
 
@@ -1,16 +1,29 @@
-use tetra_core::unimplemented_log;
-use tetra_pdus::cmce::{enums::cmce_pdu_type_dl::CmcePduTypeDl, pdus::d_sds_data::DSdsData};
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::d_status::DStatus;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_saps::control::sds::CmceSdsData;
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::MessageQueue;
 
 /// Clause 13 Short Data Service CMCE sub-entity
-pub struct SdsMsSubentity {}
+pub struct SdsMsSubentity {
+    config: SharedConfig,
+}
 
 impl SdsMsSubentity {
     /// Create a new instance of the SdsSubentity
-    pub fn new() -> Self {
-        SdsMsSubentity {}
+    pub fn new(config: SharedConfig) -> Self {
+        SdsMsSubentity { config }
+    }
+
+    pub fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
     }
 
     pub fn rx_sds_data(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
@@ -19,9 +32,10 @@ impl SdsMsSubentity {
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
             panic!();
         };
-        let _pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
+        let dest_ssi = prim.received_tetra_address.ssi;
+        let pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
             Ok(pdu) => {
-                tracing::debug!("Received DSdsData: {:?}", pdu);
+                tracing::debug!("<- {:?}", pdu);
                 pdu
             }
             Err(e) => {
@@ -30,7 +44,39 @@ impl SdsMsSubentity {
             }
         };
 
-        unimplemented_log!("rx_sds_data not implemented");
+        tracing::info!(
+            "SDS: D-SDS-DATA from ISSI {:?} to SSI {}, type={}, {} bits",
+            pdu.calling_party_address_ssi,
+            dest_ssi,
+            pdu.user_defined_data.type_identifier(),
+            pdu.user_defined_data.length_bits()
+        );
+    }
+
+    pub fn rx_status(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_status");
+
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let dest_ssi = prim.received_tetra_address.ssi;
+        let pdu = match DStatus::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DStatus: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        tracing::info!(
+            "SDS: D-STATUS from ISSI {:?} to SSI {}, status={}",
+            pdu.calling_party_address_ssi,
+            dest_ssi,
+            pdu.pre_coded_status
+        );
     }
 
     /// Poor man's rx_prim, as this is a subcomponent and not governed by the MessageRouter
@@ -58,11 +104,75 @@ impl SdsMsSubentity {
                 self.rx_sds_data(queue, message);
             }
             CmcePduTypeDl::DStatus => {
-                unimplemented_log!("rx_prim not implemented for SDS DStatus PDU");
+                self.rx_status(queue, message);
             }
             _ => {
-                panic!();
+                tracing::warn!("route_rf_deliver: unexpected {}", pdu_type);
             }
         }
     }
+
+    /// Send an SDS originated by the user of this MS as U-SDS-DATA.
+    /// The source ISSI in the request is ignored, the SwMI derives it from the address we transmit with.
+    pub fn rx_sds_from_user(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::CmceSdsData(sds) = message.msg else {
+            panic!("Expected CmceSdsData message");
+        };
+        let Some(issi) = self.config.config().ms.as_ref().map(|ms| ms.issi) else {
+            tracing::warn!("SDS: no MS identity configured, dropping SDS to {}", sds.dest_issi);
+            return;
+        };
+
+        tracing::info!(
+            "SDS: sending U-SDS-DATA to SSI {}, type={}, {} bits",
+            sds.dest_issi,
+            sds.user_defined_data.type_identifier(),
+            sds.user_defined_data.length_bits()
+        );
+        self.send_u_sds_data(queue, message.dltime, issi, sds);
+    }
+
+    fn send_u_sds_data(&self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sds: CmceSdsData) {
+        let pdu = USdsData {
+            area_selection: 0,
+            called_party_type_identifier: PartyTypeIdentifier::Ssi,
+            called_party_short_number_address: None,
+            called_party_ssi: Some(sds.dest_issi as u64),
+            called_party_extension: None,
+            user_defined_data: sds.user_defined_data,
+            external_subscriber_number: None,
+            dm_ms_address: None,
+        };
+
+        tracing::debug!("-> U-SDS-DATA {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(128);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::error!("Failed to serialize U-SDS-DATA: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+
+        let msg = SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Todo,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: TetraAddress::new(issi, SsiType::Issi),
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
 }
@@ -1,18 +1,73 @@
+use std::collections::HashMap;
+
+use tetra_config::bluestation::SharedConfig;
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::enums::ss_type::SsType;
+use tetra_pdus::cmce::pdus::u_facility::UFacility;
+use tetra_saps::{SapMsg, SapMsgInner};
+
 use crate::MessageQueue;
-use tetra_saps::SapMsg;
+use crate::cmce::components::ss_cf::SsCfHandler;
+use crate::cmce::components::ss_dgna::SsDgnaHandler;
+use crate::cmce::components::ss_handler::{SsContext, SsHandler};
+use crate::cmce::components::ss_tpi::SsTpiHandler;
+use crate::cmce::subentities::cc_bs::CcBsSubentity;
 
 /// Clause 12 Supplementary Services CMCE sub-entity
-pub struct SsBsSubentity {}
+/// Dispatches SS-PDUs to the handler registered for their SS type.
+pub struct SsBsSubentity {
+    config: SharedConfig,
+    handlers: HashMap<SsType, Box<dyn SsHandler>>,
+}
 
 impl SsBsSubentity {
-    pub fn new() -> Self {
-        SsBsSubentity {}
+    pub fn new(config: SharedConfig) -> Self {
+        let mut ss = SsBsSubentity {
+            config,
+            handlers: HashMap::new(),
+        };
+        ss.register_handler(Box::new(SsCfHandler));
+        ss.register_handler(Box::new(SsDgnaHandler));
+        ss.register_handler(Box::new(SsTpiHandler));
+        ss
     }
 
-    pub fn route_re_deliver(&mut self, _queue: &mut MessageQueue, mut _message: SapMsg) {
+    /// Register a handler for its SS type, replacing any previous handler for that type
+    pub fn register_handler(&mut self, handler: Box<dyn SsHandler>) {
+        self.handlers.insert(handler.ss_type(), handler);
+    }
+
+    /// Handle U-FACILITY from an MS
+    pub fn route_re_deliver(&mut self, queue: &mut MessageQueue, mut message: SapMsg, cc: &CcBsSubentity) {
         tracing::trace!("route_re_deliver");
 
-        // Handle the incoming unit data indication
-        unimplemented!();
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let issi = prim.received_tetra_address.ssi;
+
+        let pdu = match UFacility::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-FACILITY: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let mut ctx = SsContext {
+            config: &self.config,
+            queue,
+            dltime: message.dltime,
+            cc,
+        };
+        let Some(handler) = pdu.ss_pdu.ss_type().and_then(|ss_type| self.handlers.get_mut(&ss_type)) else {
+            tracing::warn!("SS: no handler for {} from ISSI {}, not supported", pdu.ss_pdu, issi);
+            ctx.send_function_not_supported(issi, CmcePduTypeUl::UFacility);
+            return;
+        };
+        handler.rx_ss_pdu(&mut ctx, issi, pdu.ss_pdu);
     }
 }
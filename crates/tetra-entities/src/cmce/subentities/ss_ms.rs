@@ -1,4 +1,10 @@
-use tetra_saps::SapMsg;
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::fields::ss_dgna::SsDgnaPdu;
+use tetra_pdus::cmce::fields::ss_pdu::SsPdu;
+use tetra_pdus::cmce::pdus::d_facility::DFacility;
+use tetra_pdus::cmce::pdus::u_facility::UFacility;
+use tetra_saps::lcmc::LcmcMleUnitdataReq;
+use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::MessageQueue;
 
@@ -10,10 +16,70 @@ impl SsMsSubentity {
         SsMsSubentity {}
     }
 
-    pub fn route_re_deliver(&mut self, _queue: &mut MessageQueue, mut _message: SapMsg) {
+    /// Handle D-FACILITY from the SwMI
+    pub fn route_re_deliver(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("route_re_deliver");
 
-        // Handle the incoming unit data indication
-        unimplemented!();
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let issi = prim.received_tetra_address.ssi;
+
+        let pdu = match DFacility::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing D-FACILITY: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        match pdu.ss_pdu {
+            // Dynamically assigned groups are always accepted
+            SsPdu::Dgna(SsDgnaPdu::Assign { gssi }) => {
+                tracing::info!("SS-DGNA: assigned gssi={}", gssi);
+                let ack = SsDgnaPdu::AssignAck { gssi, accepted: true };
+                Self::send_u_facility(queue, message.dltime, issi, SsPdu::Dgna(ack));
+            }
+            SsPdu::Dgna(SsDgnaPdu::Deassign { gssi }) => {
+                tracing::info!("SS-DGNA: deassigned gssi={}", gssi);
+                Self::send_u_facility(queue, message.dltime, issi, SsPdu::Dgna(SsDgnaPdu::DeassignAck { gssi }));
+            }
+            ss_pdu => {
+                tracing::info!("SS: {}", ss_pdu);
+            }
+        }
+    }
+
+    fn send_u_facility(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, ss_pdu: SsPdu) {
+        let pdu = UFacility { ss_pdu };
+        tracing::debug!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+
+        queue.push_back(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: Layer2Service::Todo,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: TetraAddress::new(issi, SsiType::Issi),
+                tx_reporter: None,
+            }),
+        });
     }
 }
@@ -24,4 +24,10 @@ pub trait TetraEntityTrait: Send + AsAny {
     fn tick_end(&mut self, _queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
         false
     }
+
+    /// Returns true once the entity cannot make any further progress, for example when its
+    /// input has ended. The stack stops running at the end of the tick.
+    fn is_finished(&self) -> bool {
+        false
+    }
 }
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+pub mod capture;
 pub mod cmce;
 pub mod entity_trait;
 pub mod llc;
@@ -15,6 +16,7 @@ pub mod network;
 pub mod tnmm_net;
 
 pub mod brew;
+pub mod mgmt;
 
 // Re-export commonly used items from router
 pub use entity_trait::TetraEntityTrait;
@@ -0,0 +1,793 @@
+//! Clause 22.3.3 Advanced link, connection oriented (acknowledged) service
+//!
+//! State machine for one advanced link towards an individual address. The LLC feeds it received AL PDUs and
+//! TL-SDUs to send, and it returns the AL PDUs to transmit and the reassembled TL-SDUs to pass up to MLE.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use tetra_core::{BitBuffer, TdmaTime, TxReporter, TxState};
+use tetra_pdus::llc::consts::consts::{
+    N262_AL_MAX_CONNECTION_SETUP_RETRIES, N263_AL_MAX_DISCONNECTION_RETRIES, N272_AL_WINDOW_SIZE_TLSDU_ACKED,
+    N273_AL_MAX_TLSDU_RETRANSMISSIONS, N274_AL_MAX_SEGMENT_RETRANSMISSIONS,
+};
+use tetra_pdus::llc::consts::timers::{
+    T252_ACK_WAITING_TIMER, T261_SETUP_WAITING_TIMER, T263_DISCONNECT_WAITING_TIMER, T271_RECEIVER_NOT_READY_FOR_TX_TIMER,
+};
+use tetra_pdus::llc::enums::al_disc_report::AlDiscReport;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
+use tetra_pdus::llc::pdus::al_ack::{AlAck, AlSelectiveAck};
+use tetra_pdus::llc::pdus::al_data::AlData;
+use tetra_pdus::llc::pdus::al_disc::AlDisc;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
+
+use crate::llc::components::fcs;
+
+/// Segment length used for AL-DATA, chosen so that one segment with its AL-DATA header fits a single
+/// SCH/F MAC-RESOURCE addressed to an SSI
+pub const AL_SEGMENT_LEN_BITS: usize = 208;
+
+/// N(S) and N(R) are counted modulo 8
+const AL_SEQ_MODULO: u8 = 8;
+
+/// Most segments one TL-SDU can be split into, as S(S) is 8 bits
+const AL_MAX_SEGMENTS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlState {
+    /// We sent AL-SETUP and wait for the peer to accept it (T.261)
+    SetupPending,
+    Connected,
+    /// We sent AL-DISC and wait for the peer to confirm it (T.263)
+    DisconnectPending,
+    /// The link is gone and can be dropped
+    Released,
+}
+
+/// Parameters agreed on in AL-SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlParams {
+    /// N.271, coded as 32 << max_tlsdu_len octets
+    pub max_tlsdu_len: u8,
+    /// N.272
+    pub window_size: u8,
+    /// N.273
+    pub max_tlsdu_retransmissions: u8,
+    /// N.274
+    pub max_segment_retransmissions: u8,
+}
+
+impl Default for AlParams {
+    fn default() -> Self {
+        Self {
+            max_tlsdu_len: 7, // 4096 octets, N.271
+            window_size: N272_AL_WINDOW_SIZE_TLSDU_ACKED as u8,
+            max_tlsdu_retransmissions: N273_AL_MAX_TLSDU_RETRANSMISSIONS as u8,
+            max_segment_retransmissions: N274_AL_MAX_SEGMENT_RETRANSMISSIONS as u8,
+        }
+    }
+}
+
+impl AlParams {
+    /// Agree on the lesser of our own parameters and those proposed by the peer
+    pub fn negotiate(&self, setup: &AlSetup) -> Self {
+        Self {
+            max_tlsdu_len: self.max_tlsdu_len.min(setup.max_tlsdu_len),
+            window_size: self.window_size.min(setup.window_size).max(1),
+            max_tlsdu_retransmissions: self.max_tlsdu_retransmissions.min(setup.max_tlsdu_retransmissions),
+            max_segment_retransmissions: self.max_segment_retransmissions.min(setup.max_segment_retransmissions),
+        }
+    }
+
+    pub fn to_setup(&self, setup_report: AlSetupReport) -> AlSetup {
+        AlSetup {
+            unacknowledged: false,
+            setup_report,
+            max_tlsdu_len: self.max_tlsdu_len,
+            window_size: self.window_size,
+            max_tlsdu_retransmissions: self.max_tlsdu_retransmissions,
+            max_segment_retransmissions: self.max_segment_retransmissions,
+            num_timeslots: 0,
+        }
+    }
+
+    fn max_tlsdu_bits(&self) -> usize {
+        (32 << self.max_tlsdu_len) * 8
+    }
+}
+
+/// Output of the advanced link, for the LLC to act upon
+#[derive(Debug)]
+pub enum AlOutput {
+    /// LLC PDU to send to the peer, with the reporter to pass to the MAC, if any
+    Pdu(BitBuffer, Option<TxReporter>),
+    /// Reassembled TL-SDU (without FCS) to pass up to MLE
+    TlSdu(BitBuffer),
+}
+
+/// TL-SDU in the transmit window
+struct TxTlSdu {
+    ns: u8,
+    segments: Vec<BitBuffer>,
+    acked: Vec<bool>,
+    /// Segments still to be (re)transmitted
+    pending: Vec<bool>,
+    segment_retransmissions: Vec<u8>,
+    tlsdu_retransmissions: u8,
+    /// Reporter of the last transmitted AL-FINAL-AR, to start T.252 once it went out
+    final_reporter: Option<TxReporter>,
+    /// Time the last AL-FINAL-AR was transmitted or discarded by the MAC
+    t_final_done: Option<TdmaTime>,
+    tx_reporter: TxReporter,
+}
+
+/// TL-SDU being received
+#[derive(Default)]
+struct RxTlSdu {
+    segments: BTreeMap<u8, BitBuffer>,
+    final_ss: Option<u8>,
+}
+
+pub struct AdvancedLink {
+    state: AlState,
+    params: AlParams,
+    /// Start of T.261 or T.263, depending on state
+    t_timer: Option<TdmaTime>,
+    /// Number of AL-SETUP or AL-DISC retries so far
+    retries: u32,
+
+    /// Send sequence variable V(S)
+    vs: u8,
+    /// TL-SDUs waiting for room in the window
+    tx_queue: VecDeque<(BitBuffer, TxReporter)>,
+    tx_window: VecDeque<TxTlSdu>,
+    /// Time the peer first reported AL-RNR, cleared by the next AL-ACK
+    peer_not_ready_since: Option<TdmaTime>,
+
+    rx_partial: BTreeMap<u8, RxTlSdu>,
+    /// Recently completed N(S), to acknowledge retransmissions without delivering them again
+    rx_done: VecDeque<u8>,
+}
+
+impl AdvancedLink {
+    fn new(state: AlState, params: AlParams, now: TdmaTime) -> Self {
+        Self {
+            state,
+            params,
+            t_timer: Some(now),
+            retries: 0,
+            vs: 0,
+            tx_queue: VecDeque::new(),
+            tx_window: VecDeque::new(),
+            peer_not_ready_since: None,
+            rx_partial: BTreeMap::new(),
+            rx_done: VecDeque::new(),
+        }
+    }
+
+    /// Set up a new advanced link on our own initiative. TL-SDUs may be queued right away.
+    pub fn initiate(now: TdmaTime, out: &mut Vec<AlOutput>) -> Self {
+        let link = Self::new(AlState::SetupPending, AlParams::default(), now);
+        out.push(Self::build_setup(&link.params, AlSetupReport::SetupRequest));
+        link
+    }
+
+    /// Accept an advanced link requested by the peer
+    pub fn accept(setup: &AlSetup, now: TdmaTime, out: &mut Vec<AlOutput>) -> Self {
+        let mut link = Self::new(AlState::Connected, AlParams::default().negotiate(setup), now);
+        link.t_timer = None;
+        out.push(Self::build_setup(&link.params, AlSetupReport::SetupConfirm));
+        link
+    }
+
+    /// Turn down an AL-SETUP without setting up a link
+    pub fn reject(report: AlDiscReport, out: &mut Vec<AlOutput>) {
+        out.push(Self::build_disc(report));
+    }
+
+    pub fn state(&self) -> AlState {
+        self.state
+    }
+
+    pub fn params(&self) -> &AlParams {
+        &self.params
+    }
+
+    /// True if TL-SDUs are queued or awaiting acknowledgement
+    pub fn is_busy(&self) -> bool {
+        !self.tx_queue.is_empty() || !self.tx_window.is_empty()
+    }
+
+    /// Queue a TL-SDU for transmission. It is sent once the link is connected and the window has room.
+    pub fn enqueue(&mut self, mut tl_sdu: BitBuffer, tx_reporter: TxReporter) {
+        let len = tl_sdu.get_len_remaining();
+        if len + 32 > self.params.max_tlsdu_bits() || len + 32 > AL_MAX_SEGMENTS * AL_SEGMENT_LEN_BITS {
+            tracing::warn!("TL-SDU of {} bits exceeds the advanced link maximum, dropping", len);
+            report_lost(&tx_reporter);
+            return;
+        }
+        let mut sdu = BitBuffer::new_autoexpand(len);
+        sdu.copy_bits(&mut tl_sdu, len);
+        self.tx_queue.push_back((sdu, tx_reporter));
+    }
+
+    /// Handle a received AL-SETUP on an existing link
+    pub fn rx_setup(&mut self, setup: &AlSetup, out: &mut Vec<AlOutput>) {
+        match setup.setup_report {
+            AlSetupReport::SetupRequest => {
+                // The peer (re)starts the link. Sequence numbers start over, unacknowledged TL-SDUs are sent anew.
+                self.params = AlParams::default().negotiate(setup);
+                self.restart_window();
+                self.rx_partial.clear();
+                self.rx_done.clear();
+                self.state = AlState::Connected;
+                self.t_timer = None;
+                out.push(Self::build_setup(&self.params, AlSetupReport::SetupConfirm));
+            }
+            AlSetupReport::SetupConfirm if self.state == AlState::SetupPending => {
+                self.params = self.params.negotiate(setup);
+                self.state = AlState::Connected;
+                self.t_timer = None;
+                tracing::debug!("advanced link connected: {:?}", self.params);
+            }
+            _ => {
+                tracing::warn!("ignoring {} in state {:?}", setup, self.state);
+            }
+        }
+    }
+
+    /// Handle a received AL-DATA, AL-DATA-AR, AL-FINAL or AL-FINAL-AR. `segment` holds the bits after the header.
+    pub fn rx_data(&mut self, data: &AlData, mut segment: BitBuffer, out: &mut Vec<AlOutput>) {
+        if self.state != AlState::Connected {
+            tracing::warn!("ignoring {} in state {:?}", data, self.state);
+            return;
+        }
+
+        if self.rx_done.contains(&data.ns) {
+            // Retransmission of a TL-SDU we already delivered; our AL-ACK must have been lost
+            if data.ack_request || data.is_final {
+                out.push(Self::build_ack(data.ns, None));
+            }
+            return;
+        }
+
+        let len = segment.get_len_remaining();
+        let mut seg = BitBuffer::new_autoexpand(len);
+        seg.copy_bits(&mut segment, len);
+        seg.seek(0);
+        let rx = self.rx_partial.entry(data.ns).or_default();
+        rx.segments.insert(data.ss, seg);
+        if data.is_final {
+            rx.final_ss = Some(data.ss);
+        }
+
+        if !data.ack_request && !data.is_final {
+            return;
+        }
+
+        // Find the first missing segment, if any
+        let last_ss = rx.final_ss.unwrap_or(data.ss);
+        let first_missing = (0..=last_ss).find(|ss| !rx.segments.contains_key(ss));
+
+        match (first_missing, rx.final_ss) {
+            (None, Some(_)) => {
+                let rx = self.rx_partial.remove(&data.ns).unwrap(); // Never fails
+                let mut tl_sdu = BitBuffer::new_autoexpand(rx.segments.values().map(|s| s.get_len()).sum());
+                for mut s in rx.segments.into_values() {
+                    let len = s.get_len_remaining();
+                    tl_sdu.copy_bits(&mut s, len);
+                }
+                tl_sdu.seek(0);
+                if !fcs::check_fcs(&tl_sdu) {
+                    // Have the whole TL-SDU sent again
+                    tracing::warn!("advanced link N(S) {} FCS check failed", data.ns);
+                    out.push(Self::build_ack(data.ns, Some(AlSelectiveAck { sr: 0, mask: vec![] })));
+                    return;
+                }
+                let len = tl_sdu.get_len() - 32;
+                tl_sdu.set_raw_end(tl_sdu.get_raw_start() + len);
+
+                out.push(Self::build_ack(data.ns, None));
+                out.push(AlOutput::TlSdu(tl_sdu));
+                self.rx_done.push_back(data.ns);
+                while self.rx_done.len() > self.params.window_size as usize {
+                    self.rx_done.pop_front();
+                }
+            }
+            (None, None) => {
+                // All segments so far received, final one still to come
+                let sr = last_ss.wrapping_add(1);
+                out.push(Self::build_ack(data.ns, Some(AlSelectiveAck { sr, mask: vec![] })));
+            }
+            (Some(sr), _) => {
+                let mask = (sr as usize + 1..=last_ss as usize)
+                    .take(AlSelectiveAck::MAX_MASK_LEN)
+                    .map(|ss| rx.segments.contains_key(&(ss as u8)))
+                    .collect();
+                out.push(Self::build_ack(data.ns, Some(AlSelectiveAck { sr, mask })));
+            }
+        }
+    }
+
+    /// Handle a received AL-ACK or AL-RNR
+    pub fn rx_ack(&mut self, ack: &AlAck, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        if self.state != AlState::Connected {
+            tracing::warn!("ignoring {} in state {:?}", ack, self.state);
+            return;
+        }
+
+        if ack.not_ready {
+            self.peer_not_ready_since.get_or_insert(now);
+        } else {
+            self.peer_not_ready_since = None;
+        }
+
+        let Some(index) = self.tx_window.iter().position(|t| t.ns == ack.nr) else {
+            tracing::debug!("advanced link: ack for N(R) {} not in window", ack.nr);
+            return;
+        };
+
+        let Some(sel) = &ack.selective else {
+            let tlsdu = self.tx_window.remove(index).unwrap(); // Never fails
+            tracing::debug!("advanced link: N(S) {} acknowledged", tlsdu.ns);
+            report_acknowledged(&tlsdu.tx_reporter);
+            return;
+        };
+
+        // Selective acknowledgement: retransmit every segment the peer did not report as received
+        let max_segment_retransmissions = self.params.max_segment_retransmissions;
+        let tlsdu = &mut self.tx_window[index];
+        for ss in 0..tlsdu.segments.len() {
+            let received = match ss.checked_sub(sel.sr as usize + 1) {
+                None => ss < sel.sr as usize,
+                Some(i) => sel.mask.get(i).copied().unwrap_or(false),
+            };
+            if received {
+                tlsdu.acked[ss] = true;
+                tlsdu.pending[ss] = false;
+            } else if !tlsdu.acked[ss] && !tlsdu.pending[ss] {
+                if tlsdu.segment_retransmissions[ss] >= max_segment_retransmissions {
+                    tracing::warn!("advanced link: N(S) {} S(S) {} exhausted retransmissions", tlsdu.ns, ss);
+                    self.fail(now, out);
+                    return;
+                }
+                tlsdu.segment_retransmissions[ss] += 1;
+                tlsdu.pending[ss] = true;
+            }
+        }
+        tlsdu.final_reporter = None;
+        tlsdu.t_final_done = None;
+    }
+
+    /// Handle a received AL-DISC
+    pub fn rx_disc(&mut self, disc: &AlDisc, out: &mut Vec<AlOutput>) {
+        match disc.report {
+            AlDiscReport::DisconnectConfirm => {
+                if self.state != AlState::DisconnectPending {
+                    tracing::warn!("ignoring {} in state {:?}", disc, self.state);
+                    return;
+                }
+            }
+            AlDiscReport::DisconnectRequest | AlDiscReport::LinkFailure => {
+                out.push(Self::build_disc(AlDiscReport::DisconnectConfirm));
+            }
+            _ => {
+                // Peer turned down our AL-SETUP
+                tracing::info!("advanced link released by peer: {}", disc.report);
+            }
+        }
+        self.release();
+    }
+
+    /// Close the link on our own initiative
+    pub fn disconnect(&mut self, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        self.start_disconnect(AlDiscReport::DisconnectRequest, now, out);
+    }
+
+    /// Run timers and transmit pending segments. Call once per tick.
+    pub fn tick(&mut self, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        match self.state {
+            AlState::SetupPending => {
+                if self.timer_expired(now, T261_SETUP_WAITING_TIMER) {
+                    if self.retries < N262_AL_MAX_CONNECTION_SETUP_RETRIES {
+                        self.retries += 1;
+                        self.t_timer = Some(now);
+                        out.push(Self::build_setup(&self.params, AlSetupReport::SetupRequest));
+                    } else {
+                        tracing::warn!("advanced link setup exhausted retries");
+                        self.release();
+                    }
+                }
+            }
+            AlState::DisconnectPending => {
+                if self.timer_expired(now, T263_DISCONNECT_WAITING_TIMER) {
+                    if self.retries < N263_AL_MAX_DISCONNECTION_RETRIES {
+                        self.retries += 1;
+                        self.t_timer = Some(now);
+                        out.push(Self::build_disc(AlDiscReport::DisconnectRequest));
+                    } else {
+                        self.release();
+                    }
+                }
+            }
+            AlState::Connected => self.tick_connected(now, out),
+            AlState::Released => {}
+        }
+    }
+
+    fn tick_connected(&mut self, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        if let Some(since) = self.peer_not_ready_since {
+            if since.age(now) as u32 >= T271_RECEIVER_NOT_READY_FOR_TX_TIMER {
+                tracing::warn!("advanced link: peer not ready for too long");
+                self.fail(now, out);
+            }
+            return;
+        }
+
+        // Admit queued TL-SDUs into the window
+        while self.tx_window.len() < self.params.window_size as usize
+            && let Some((sdu, tx_reporter)) = self.tx_queue.pop_front()
+        {
+            let ns = self.vs;
+            self.vs = (self.vs + 1) % AL_SEQ_MODULO;
+            self.tx_window.push_back(Self::segment(ns, sdu, tx_reporter));
+        }
+
+        // T.252: retransmit TL-SDUs that were not acknowledged in time
+        let max_tlsdu_retransmissions = self.params.max_tlsdu_retransmissions;
+        let mut exhausted = false;
+        for tlsdu in self.tx_window.iter_mut() {
+            if tlsdu.t_final_done.is_none()
+                && let Some(reporter) = &tlsdu.final_reporter
+                && (reporter.is_transmitted() || reporter.is_discarded())
+            {
+                tlsdu.t_final_done = Some(now);
+                if reporter.is_transmitted() && tlsdu.tx_reporter.get_state() == TxState::Pending {
+                    tlsdu.tx_reporter.mark_transmitted();
+                }
+            }
+            let Some(t_done) = tlsdu.t_final_done else {
+                continue;
+            };
+            if (t_done.age(now) as u32) < T252_ACK_WAITING_TIMER {
+                continue;
+            }
+            if tlsdu.tlsdu_retransmissions >= max_tlsdu_retransmissions {
+                tracing::warn!("advanced link: N(S) {} exhausted retransmissions", tlsdu.ns);
+                exhausted = true;
+                break;
+            }
+            tlsdu.tlsdu_retransmissions += 1;
+            tracing::info!(
+                "advanced link: retransmitting N(S) {} attempt {}",
+                tlsdu.ns,
+                tlsdu.tlsdu_retransmissions
+            );
+            for ss in 0..tlsdu.segments.len() {
+                tlsdu.pending[ss] = !tlsdu.acked[ss];
+            }
+            tlsdu.final_reporter = None;
+            tlsdu.t_final_done = None;
+        }
+        if exhausted {
+            self.fail(now, out);
+            return;
+        }
+
+        // Send pending segments, the last one of each TL-SDU requesting an acknowledgement
+        for tlsdu in self.tx_window.iter_mut() {
+            let Some(last) = tlsdu.pending.iter().rposition(|&p| p) else {
+                continue;
+            };
+            for ss in 0..=last {
+                if !tlsdu.pending[ss] {
+                    continue;
+                }
+                tlsdu.pending[ss] = false;
+                let header = AlData {
+                    is_final: ss == tlsdu.segments.len() - 1,
+                    ack_request: ss == last,
+                    ns: tlsdu.ns,
+                    ss: ss as u8,
+                };
+                let mut pdu = BitBuffer::new_autoexpand(AlData::HEADER_LEN + AL_SEGMENT_LEN_BITS);
+                header.to_bitbuf(&mut pdu);
+                let mut seg = tlsdu.segments[ss].clone();
+                seg.seek(0);
+                let len = seg.get_len();
+                pdu.copy_bits(&mut seg, len);
+                pdu.seek(0);
+                tracing::debug!("-> {}", header);
+
+                let reporter = header.ack_request.then(TxReporter::new_unacked);
+                if let Some(reporter) = &reporter {
+                    tlsdu.final_reporter = Some(reporter.clone());
+                }
+                out.push(AlOutput::Pdu(pdu, reporter));
+            }
+        }
+    }
+
+    /// Split a TL-SDU with its FCS into segments
+    fn segment(ns: u8, sdu: BitBuffer, tx_reporter: TxReporter) -> TxTlSdu {
+        let len = sdu.get_len();
+        let mut buf = BitBuffer::new_autoexpand(len + 32);
+        let mut src = sdu;
+        src.seek(0);
+        buf.copy_bits(&mut src, len);
+        buf.write_bits(fcs::compute_fcs(&src, 0, len) as u64, 32);
+        buf.seek(0);
+
+        let mut segments = Vec::new();
+        while buf.get_len_remaining() > 0 {
+            let seg_len = buf.get_len_remaining().min(AL_SEGMENT_LEN_BITS);
+            let mut seg = BitBuffer::new_autoexpand(seg_len);
+            seg.copy_bits(&mut buf, seg_len);
+            segments.push(seg);
+        }
+
+        let n = segments.len();
+        TxTlSdu {
+            ns,
+            segments,
+            acked: vec![false; n],
+            pending: vec![true; n],
+            segment_retransmissions: vec![0; n],
+            tlsdu_retransmissions: 0,
+            final_reporter: None,
+            t_final_done: None,
+            tx_reporter,
+        }
+    }
+
+    /// Put all unacknowledged TL-SDUs back in the queue, to be sent from N(S) 0 again
+    fn restart_window(&mut self) {
+        while let Some(tlsdu) = self.tx_window.pop_back() {
+            let mut sdu = BitBuffer::new_autoexpand(tlsdu.segments.len() * AL_SEGMENT_LEN_BITS);
+            for mut seg in tlsdu.segments {
+                seg.seek(0);
+                let len = seg.get_len();
+                sdu.copy_bits(&mut seg, len);
+            }
+            // Drop the FCS, it is added again when segmenting
+            let len = sdu.get_len() - 32;
+            sdu.seek(0);
+            sdu.set_raw_end(sdu.get_raw_start() + len);
+            self.tx_queue.push_front((sdu, tlsdu.tx_reporter));
+        }
+        self.vs = 0;
+        self.peer_not_ready_since = None;
+    }
+
+    /// Link failure: report all TL-SDUs as lost and disconnect
+    fn fail(&mut self, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        self.start_disconnect(AlDiscReport::LinkFailure, now, out);
+    }
+
+    fn start_disconnect(&mut self, report: AlDiscReport, now: TdmaTime, out: &mut Vec<AlOutput>) {
+        self.drop_pending();
+        self.state = AlState::DisconnectPending;
+        self.t_timer = Some(now);
+        self.retries = 0;
+        out.push(Self::build_disc(report));
+    }
+
+    fn release(&mut self) {
+        self.drop_pending();
+        self.state = AlState::Released;
+        self.t_timer = None;
+    }
+
+    fn drop_pending(&mut self) {
+        for tlsdu in self.tx_window.drain(..) {
+            report_lost(&tlsdu.tx_reporter);
+        }
+        for (_, tx_reporter) in self.tx_queue.drain(..) {
+            report_lost(&tx_reporter);
+        }
+        self.rx_partial.clear();
+    }
+
+    fn timer_expired(&self, now: TdmaTime, duration: u32) -> bool {
+        self.t_timer.is_some_and(|t| t.age(now) as u32 >= duration)
+    }
+
+    fn build_setup(params: &AlParams, report: AlSetupReport) -> AlOutput {
+        let pdu = params.to_setup(report);
+        tracing::debug!("-> {}", pdu);
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        AlOutput::Pdu(buf, None)
+    }
+
+    fn build_ack(nr: u8, selective: Option<AlSelectiveAck>) -> AlOutput {
+        let pdu = AlAck {
+            not_ready: false,
+            nr,
+            selective,
+        };
+        tracing::debug!("-> {}", pdu);
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        AlOutput::Pdu(buf, None)
+    }
+
+    fn build_disc(report: AlDiscReport) -> AlOutput {
+        let pdu = AlDisc { report };
+        tracing::debug!("-> {}", pdu);
+        let mut buf = BitBuffer::new_autoexpand(8);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        AlOutput::Pdu(buf, None)
+    }
+}
+
+/// The TL-SDU reporter never passes through the MAC, so it is moved to Transmitted here first
+fn report_acknowledged(tx_reporter: &TxReporter) {
+    if tx_reporter.get_state() == TxState::Pending {
+        tx_reporter.mark_transmitted();
+    }
+    tx_reporter.mark_acknowledged();
+}
+
+fn report_lost(tx_reporter: &TxReporter) {
+    match tx_reporter.get_state() {
+        TxState::Pending => tx_reporter.mark_discarded(),
+        TxState::Transmitted => tx_reporter.mark_lost(),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: TdmaTime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+
+    fn sdu(bits: usize) -> BitBuffer {
+        let mut buf = BitBuffer::new_autoexpand(bits);
+        for i in 0..bits {
+            buf.write_bit((i % 3 == 0) as u8);
+        }
+        buf.seek(0);
+        buf
+    }
+
+    /// Split outputs into PDUs and delivered TL-SDUs
+    fn split(out: Vec<AlOutput>) -> (Vec<BitBuffer>, Vec<BitBuffer>) {
+        let mut pdus = Vec::new();
+        let mut sdus = Vec::new();
+        for o in out {
+            match o {
+                AlOutput::Pdu(pdu, reporter) => {
+                    if let Some(reporter) = reporter {
+                        reporter.mark_transmitted();
+                    }
+                    pdus.push(pdu);
+                }
+                AlOutput::TlSdu(sdu) => sdus.push(sdu),
+            }
+        }
+        (pdus, sdus)
+    }
+
+    /// Feed AL-DATA PDUs into the receiving link, optionally dropping some
+    fn deliver_data(rx: &mut AdvancedLink, pdus: &[BitBuffer], drop: &[usize]) -> Vec<AlOutput> {
+        let mut out = Vec::new();
+        for (i, pdu) in pdus.iter().enumerate() {
+            if drop.contains(&i) {
+                continue;
+            }
+            let mut pdu = pdu.clone();
+            let header = AlData::from_bitbuf(&mut pdu).unwrap();
+            rx.rx_data(&header, pdu, &mut out);
+        }
+        out
+    }
+
+    fn connected_pair() -> (AdvancedLink, AdvancedLink) {
+        let mut out = Vec::new();
+        let mut tx = AdvancedLink::initiate(T0, &mut out);
+        let (mut pdus, _) = split(out);
+        let setup = AlSetup::from_bitbuf(&mut pdus[0]).unwrap();
+
+        let mut out = Vec::new();
+        let rx = AdvancedLink::accept(&setup, T0, &mut out);
+        let (mut pdus, _) = split(out);
+        let confirm = AlSetup::from_bitbuf(&mut pdus[0]).unwrap();
+        assert_eq!(confirm.setup_report, AlSetupReport::SetupConfirm);
+
+        tx.rx_setup(&confirm, &mut Vec::new());
+        assert_eq!(tx.state(), AlState::Connected);
+        (tx, rx)
+    }
+
+    #[test]
+    fn test_segmented_transfer() {
+        let (mut tx, mut rx) = connected_pair();
+        let reporter = TxReporter::new();
+        tx.enqueue(sdu(1000), reporter.clone());
+
+        let mut out = Vec::new();
+        tx.tick(T0, &mut out);
+        let (data, _) = split(out);
+        // 1000 bits plus FCS
+        assert_eq!(data.len(), 1032usize.div_ceil(AL_SEGMENT_LEN_BITS));
+
+        let (mut acks, sdus) = split(deliver_data(&mut rx, &data, &[]));
+        assert_eq!(sdus.len(), 1);
+        assert_eq!(sdus[0].to_bitstr(), sdu(1000).to_bitstr());
+
+        let ack = AlAck::from_bitbuf(&mut acks[0]).unwrap();
+        assert_eq!(ack.selective, None);
+        tx.rx_ack(&ack, T0, &mut Vec::new());
+        assert_eq!(reporter.get_state(), TxState::Acknowledged);
+        assert!(!tx.is_busy());
+    }
+
+    #[test]
+    fn test_selective_retransmission() {
+        let (mut tx, mut rx) = connected_pair();
+        let reporter = TxReporter::new();
+        tx.enqueue(sdu(1000), reporter.clone());
+
+        let mut out = Vec::new();
+        tx.tick(T0, &mut out);
+        let (data, _) = split(out);
+
+        // Segments 1 and 3 get lost; the receiver asks for exactly those
+        let (mut acks, sdus) = split(deliver_data(&mut rx, &data, &[1, 3]));
+        assert!(sdus.is_empty());
+        let ack = AlAck::from_bitbuf(&mut acks[0]).unwrap();
+        let sel = ack.selective.clone().unwrap();
+        assert_eq!(sel.sr, 1);
+        assert_eq!(sel.mask, vec![true, false, true]);
+
+        let mut out = Vec::new();
+        tx.rx_ack(&ack, T0, &mut out);
+        tx.tick(T0, &mut out);
+        let (resent, _) = split(out);
+        let resent_ss: Vec<u8> = resent.iter().map(|p| AlData::from_bitbuf(&mut p.clone()).unwrap().ss).collect();
+        assert_eq!(resent_ss, vec![1, 3]);
+
+        let (mut acks, sdus) = split(deliver_data(&mut rx, &resent, &[]));
+        assert_eq!(sdus[0].to_bitstr(), sdu(1000).to_bitstr());
+        tx.rx_ack(&AlAck::from_bitbuf(&mut acks[0]).unwrap(), T0, &mut Vec::new());
+        assert_eq!(reporter.get_state(), TxState::Acknowledged);
+    }
+
+    #[test]
+    fn test_window_and_retransmission_limit() {
+        let (mut tx, _rx) = connected_pair();
+        let reporters: Vec<TxReporter> = (0..4).map(|_| TxReporter::new()).collect();
+        for r in &reporters {
+            tx.enqueue(sdu(100), r.clone());
+        }
+
+        // Only window_size TL-SDUs are sent before an acknowledgement arrives
+        let mut out = Vec::new();
+        tx.tick(T0, &mut out);
+        let (data, _) = split(out);
+        assert_eq!(data.len(), N272_AL_WINDOW_SIZE_TLSDU_ACKED as usize);
+
+        // Without acknowledgements, the TL-SDUs are sent N.273 more times before the link fails
+        let mut now = T0;
+        for _ in 0..N273_AL_MAX_TLSDU_RETRANSMISSIONS {
+            tx.tick(now, &mut Vec::new());
+            now = now.add_timeslots(T252_ACK_WAITING_TIMER as i32);
+            let mut out = Vec::new();
+            tx.tick(now, &mut out);
+            assert_eq!(split(out).0.len(), N272_AL_WINDOW_SIZE_TLSDU_ACKED as usize);
+        }
+        tx.tick(now, &mut Vec::new());
+        now = now.add_timeslots(T252_ACK_WAITING_TIMER as i32);
+        let mut out = Vec::new();
+        tx.tick(now, &mut out);
+        let (mut pdus, _) = split(out);
+        assert_eq!(AlDisc::from_bitbuf(&mut pdus[0]).unwrap().report, AlDiscReport::LinkFailure);
+        assert_eq!(tx.state(), AlState::DisconnectPending);
+        assert_eq!(reporters[0].get_state(), TxState::Lost);
+        assert_eq!(reporters[3].get_state(), TxState::Discarded);
+    }
+}
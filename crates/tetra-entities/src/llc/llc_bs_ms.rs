@@ -8,14 +8,20 @@ use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress,
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
-use tetra_saps::tla::{TlaTlDataIndBl, TlaTlUnitdataIndBl};
+use tetra_saps::tla::{TlaTlDataIndBl, TlaTlDataReqBl, TlaTlUnitdataIndBl};
 use tetra_saps::tma::TmaUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use crate::llc::components::advanced_link::{AdvancedLink, AlOutput, AlState};
 use crate::llc::components::fcs;
-use tetra_pdus::llc::consts::consts::N252_BL_MAX_TLSDU_RETRANSMITS_ACKED;
+use tetra_pdus::llc::consts::consts::{N251_BL_MAX_TLSDU_LEN_BITS, N252_BL_MAX_TLSDU_RETRANSMITS_ACKED};
 use tetra_pdus::llc::consts::timers::T251_SENDER_RETRY_TIMER;
+use tetra_pdus::llc::enums::al_disc_report::AlDiscReport;
 use tetra_pdus::llc::enums::llc_pdu_type::LlcPduType;
+use tetra_pdus::llc::pdus::al_ack::AlAck;
+use tetra_pdus::llc::pdus::al_data::AlData;
+use tetra_pdus::llc::pdus::al_disc::AlDisc;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
 use tetra_pdus::llc::pdus::bl_ack::BlAck;
 use tetra_pdus::llc::pdus::bl_adata::BlAdata;
 use tetra_pdus::llc::pdus::bl_data::BlData;
@@ -62,6 +68,14 @@ pub struct ScheduledOutAck {
     pub ts: u8,
 }
 
+/// Advanced link towards an MS, see Clause 22.3.3
+pub struct AdvancedLinkEntry {
+    pub addr: TetraAddress,
+    /// Timeslot on which the MS last sent on this link, used for our PDUs as well
+    pub ts: u8,
+    pub link: AdvancedLink,
+}
+
 pub struct Llc {
     dltime: TdmaTime,
     config: SharedConfig,
@@ -78,6 +92,9 @@ pub struct Llc {
 
     /// Per-link send sequence variable per SSI. Alternates between 0 and 1.
     link_send_seq: HashMap<u32, u8>,
+
+    /// Advanced links per SSI. Acknowledged TL-SDUs for an SSI go over its advanced link while one is up.
+    advanced_links: HashMap<u32, AdvancedLinkEntry>,
 }
 
 impl Llc {
@@ -89,6 +106,7 @@ impl Llc {
             outbound_messages: VecDeque::new(),
             outbound_udata_messages: VecDeque::new(),
             link_send_seq: HashMap::new(),
+            advanced_links: HashMap::new(),
         }
     }
 
@@ -247,7 +265,7 @@ impl Llc {
     }
 
     /// See Clause 22.3.2.3 for Acknowledged data transmission in basic link
-    fn rx_tla_tldata_req_bl(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn rx_tla_tldata_req_bl(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_tldata_req_bl");
         let SapMsgInner::TlaTlDataReqBl(mut prim) = message.msg else {
             panic!()
@@ -260,6 +278,16 @@ impl Llc {
             panic!("Can't send BL-DATA for GSSI-addressed message. ");
         }
 
+        // Use the advanced link if one is up towards this SSI, or set one up for TL-SDUs too long for the basic link
+        let use_advanced_link = match self.advanced_links.get(&prim.main_address.ssi) {
+            Some(al) => matches!(al.link.state(), AlState::SetupPending | AlState::Connected),
+            None => self.config.config().cell.advanced_link && prim.tl_sdu.get_len_remaining() > N251_BL_MAX_TLSDU_LEN_BITS as usize,
+        };
+        if use_advanced_link {
+            self.rx_tla_tldata_req_al(queue, message.dltime.t, prim);
+            return;
+        }
+
         // If an ack still needs to be sent, get the relevant expected sequence number
         let out_ack_n = self.get_out_ack_seq_if_any(message.dltime.t, prim.main_address);
 
@@ -338,6 +366,29 @@ impl Llc {
         // a pending message waiting for an ack.
     }
 
+    /// Queues a TL-SDU on the advanced link to its destination, setting up the link first if needed
+    fn rx_tla_tldata_req_al(&mut self, queue: &mut MessageQueue, ts: u8, mut prim: TlaTlDataReqBl) {
+        let tx_reporter = prim.tx_reporter.take().unwrap_or_else(TxReporter::new);
+        let mut out = Vec::new();
+        let dltime = self.dltime;
+        let al = self.advanced_links.entry(prim.main_address.ssi).or_insert_with(|| {
+            tracing::info!("setting up advanced link to SSI {}", prim.main_address.ssi);
+            AdvancedLinkEntry {
+                addr: prim.main_address,
+                ts,
+                link: AdvancedLink::initiate(dltime, &mut out),
+            }
+        });
+        al.link.enqueue(prim.tl_sdu, tx_reporter);
+
+        let (addr, ts) = (al.addr, al.ts);
+        for output in out {
+            if let AlOutput::Pdu(pdu, tx_reporter) = output {
+                queue.push_back(Self::build_al_sapmsg(dltime, addr, ts, pdu, tx_reporter));
+            }
+        }
+    }
+
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match &message.msg {
@@ -365,7 +416,9 @@ impl Llc {
         // Determine which type of TL-SDU we have
         let pdu_type = if let SapMsgInner::TmaUnitdataInd(prim) = &mut message.msg {
             let Some(pdu) = prim.pdu.as_ref() else {
-                panic!("no pdu");
+                // Channel allocation without TM-SDU, nothing to deliver
+                tracing::trace!("rx_tma_unitdata_ind: no TM-SDU");
+                return;
             };
             let Some(bits) = pdu.peek_bits(4) else {
                 tracing::warn!("insufficient bits: {}", pdu.dump_bin());
@@ -395,17 +448,17 @@ impl Llc {
                 self.rx_tma_unitdata_ind_bl(queue, message);
             }
 
-            LlcPduType::AlSetup
-            | LlcPduType::AlDataAlFinal
-            | LlcPduType::AlAlUdataAlUfinal
-            | LlcPduType::AlAckAlRnr
-            | LlcPduType::AlReconnect
-            | LlcPduType::AlDisc => {
+            // Connection oriented advanced link
+            LlcPduType::AlSetup | LlcPduType::AlDataAlFinal | LlcPduType::AlAckAlRnr | LlcPduType::AlDisc => {
+                self.rx_tma_unitdata_ind_al(queue, message, pdu_type);
+            }
+
+            LlcPduType::AlAlUdataAlUfinal | LlcPduType::AlReconnect => {
                 unimplemented_log!("LlcPduType Advanced Link: {}", pdu_type);
             }
 
-            _ => {
-                panic!();
+            LlcPduType::SuppLlcPdu | LlcPduType::L2SigPdu => {
+                tracing::warn!("unsupported pdu type: {}, dropping", pdu_type);
             }
         }
     }
@@ -559,6 +612,165 @@ impl Llc {
         queue.push_back(s);
     }
 
+    /// Clause 22.3.3 Advanced link. Reassembled TL-SDUs are passed to MLE as TL-DATA indication, just like those
+    /// received over the basic link.
+    fn rx_tma_unitdata_ind_al(&mut self, queue: &mut MessageQueue, mut message: SapMsg, pdu_type: LlcPduType) {
+        tracing::trace!("rx_tma_unitdata_ind_al");
+
+        let SapMsgInner::TmaUnitdataInd(prim) = &mut message.msg else {
+            panic!();
+        };
+        let Some(mut pdu) = prim.pdu.take() else {
+            panic!("no pdu");
+        };
+        let addr = prim.main_address;
+        let ts = message.dltime.t;
+        let mut out = Vec::new();
+
+        if pdu_type == LlcPduType::AlSetup {
+            let setup = match AlSetup::from_bitbuf(&mut pdu) {
+                Ok(setup) => setup,
+                Err(e) => {
+                    tracing::warn!("Failed parsing AlSetup: {:?} {}", e, pdu.dump_bin());
+                    return;
+                }
+            };
+            tracing::debug!(ts=%self.dltime, "<- {}", setup);
+
+            if setup.unacknowledged || !self.config.config().cell.advanced_link {
+                tracing::info!("rejecting advanced link setup from SSI {}", addr.ssi);
+                AdvancedLink::reject(AlDiscReport::ServiceNotSupported, &mut out);
+            } else if let Some(al) = self.advanced_links.get_mut(&addr.ssi) {
+                al.ts = ts;
+                al.link.rx_setup(&setup, &mut out);
+            } else {
+                tracing::info!("accepting advanced link from SSI {}", addr.ssi);
+                let link = AdvancedLink::accept(&setup, self.dltime, &mut out);
+                self.advanced_links.insert(addr.ssi, AdvancedLinkEntry { addr, ts, link });
+            }
+        } else {
+            let Some(al) = self.advanced_links.get_mut(&addr.ssi) else {
+                tracing::warn!("received {} from SSI {} without advanced link, ignoring", pdu_type, addr.ssi);
+                return;
+            };
+            al.ts = ts;
+
+            match pdu_type {
+                LlcPduType::AlDataAlFinal => match AlData::from_bitbuf(&mut pdu) {
+                    Ok(data) => {
+                        tracing::debug!(ts=%self.dltime, "<- {}", data);
+                        al.link.rx_data(&data, pdu, &mut out);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlData: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                },
+                LlcPduType::AlAckAlRnr => match AlAck::from_bitbuf(&mut pdu) {
+                    Ok(ack) => {
+                        tracing::debug!(ts=%self.dltime, "<- {}", ack);
+                        al.link.rx_ack(&ack, self.dltime, &mut out);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlAck: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                },
+                LlcPduType::AlDisc => match AlDisc::from_bitbuf(&mut pdu) {
+                    Ok(disc) => {
+                        tracing::debug!(ts=%self.dltime, "<- {}", disc);
+                        al.link.rx_disc(&disc, &mut out);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed parsing AlDisc: {:?} {}", e, pdu.dump_bin());
+                        return;
+                    }
+                },
+                _ => {
+                    panic!();
+                }
+            }
+        }
+
+        for output in out {
+            match output {
+                AlOutput::Pdu(pdu, tx_reporter) => {
+                    queue.push_back(Self::build_al_sapmsg(self.dltime, addr, ts, pdu, tx_reporter));
+                }
+                AlOutput::TlSdu(tl_sdu) => {
+                    let m = TlaTlDataIndBl {
+                        main_address: addr,
+                        link_id: message.dltime.add_timeslots(-2).t as u32,
+                        endpoint_id: prim.endpoint_id,
+                        new_endpoint_id: prim.new_endpoint_id,
+                        css_endpoint_id: prim.css_endpoint_id,
+                        tl_sdu: Some(tl_sdu),
+                        scrambling_code: prim.scrambling_code,
+                        fcs_flag: true,
+                        air_interface_encryption: prim.air_interface_encryption,
+                        chan_change_resp_req: prim.chan_change_response_req,
+                        chan_change_handle: prim.chan_change_handle,
+                        chan_info: prim.chan_info,
+                        req_handle: 0, // TODO FIXME
+                    };
+                    queue.push_back(SapMsg {
+                        sap: Sap::TlaSap,
+                        src: TetraEntity::Llc,
+                        dest: TetraEntity::Mle,
+                        dltime: message.dltime,
+                        msg: SapMsgInner::TlaTlDataIndBl(m),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Wraps an advanced link PDU for the Umac, to be sent on the timeslot the link runs on
+    fn build_al_sapmsg(dltime: TdmaTime, addr: TetraAddress, ts: u8, pdu: BitBuffer, tx_reporter: Option<TxReporter>) -> SapMsg {
+        SapMsg {
+            sap: Sap::TmaSap,
+            src: TetraEntity::Llc,
+            dest: TetraEntity::Umac,
+            dltime: dltime.forward_to_timeslot(ts),
+            msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+                req_handle: 0, // TODO FIXME
+                pdu,
+                main_address: addr,
+                endpoint_id: 0, // todo fixme
+                stealing_permission: false,
+                subscriber_class: 0,            // TODO FIXME
+                air_interface_encryption: None, // TODO FIXME
+                stealing_repeats_flag: None,
+                data_category: None,
+                chan_alloc: None,
+                tx_reporter,
+            }),
+        }
+    }
+
+    /// Runs the timers of all advanced links and sends their pending PDUs down. Released links are dropped.
+    fn submit_advanced_link_pdus_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
+        let mut had_activity = false;
+        for al in self.advanced_links.values_mut() {
+            let mut out = Vec::new();
+            al.link.tick(self.dltime, &mut out);
+            for output in out {
+                if let AlOutput::Pdu(pdu, tx_reporter) = output {
+                    queue.push_back(Self::build_al_sapmsg(self.dltime, al.addr, al.ts, pdu, tx_reporter));
+                    had_activity = true;
+                }
+            }
+        }
+        self.advanced_links.retain(|ssi, al| {
+            let released = al.link.state() == AlState::Released;
+            if released {
+                tracing::info!("advanced link to SSI {} released", ssi);
+            }
+            !released
+        });
+        had_activity
+    }
+
     fn submit_retransmissions_to_umac(&mut self, queue: &mut MessageQueue) -> bool {
         let mut had_activity = false;
         let dltime = self.dltime;
@@ -790,21 +1002,24 @@ impl TetraEntityTrait for Llc {
     fn tick_end(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
         let mut had_activity = false;
 
-        // Step 1 / 4: Check if we have any transmitted messages that were not acked within the expected window
+        // Step 1 / 5: Check if we have any transmitted messages that were not acked within the expected window
         // Schedule a retransmission if appropriate.
         had_activity |= self.submit_retransmissions_to_umac(queue);
 
-        // Step 2 / 4: Check if there are any messages that were not yet sent down, that we can now send down the stack
+        // Step 2 / 5: Check if there are any messages that were not yet sent down, that we can now send down the stack
         // Messages may be kept since the target SSI has not yet acked them . If the link is now free, we can send the message down and register that we expect an ACK for it.
         had_activity |= self.submit_free_messages_to_umac(queue);
 
-        // Step 3 / 4: Check if any unsent ACKs are still here
+        // Step 3 / 5: Check if any unsent ACKs are still here
         // Take oldest element from scheduled_out_acks, and remove it from the list
         had_activity |= self.submit_ack_replies_to_umac(queue);
 
-        // Step 4 / 4: Send any U-DATA messages
+        // Step 4 / 5: Send any U-DATA messages
         had_activity |= self.submit_udata_msgs_to_umac(queue);
 
+        // Step 5 / 5: Run the advanced links, sending segments, acknowledgements and retries
+        had_activity |= self.submit_advanced_link_pdus_to_umac(queue);
+
         had_activity
     }
 }
@@ -1,6 +1,6 @@
 use tetra_config::bluestation::{SharedConfig, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence};
+use tetra_core::{BurstType, Direction, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence};
 use tetra_saps::tmv::TmvUnitdataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
@@ -47,10 +47,10 @@ pub struct LmacBs {
     /// Timeslot time, provided by upper layer and then maintained in sync here
     dltime: TdmaTime,
 
-    /// Per-timeslot UL physical channel indicator from UMAC.
+    /// Per-carrier, per-timeslot UL physical channel indicator from UMAC.
     /// UL bursts arrive 2 timeslots after the corresponding DL slot, so we must
     /// keep this keyed by timeslot rather than a single "latest" value.
-    uplink_phy_chan: [PhysicalChannel; 4],
+    uplink_phy_chan: Vec<[PhysicalChannel; 4]>,
 
     /// Signalled by Umac per timeslot. Set to true when in a traffic burst, the 1st stolen block shows that the 2nd slot is also stolen
     blk2_stolen: bool,
@@ -61,7 +61,7 @@ pub struct LmacBs {
 impl LmacBs {
     pub fn new(config: SharedConfig) -> Self {
         // Retrieve initial basic network params from config
-        let (stack_mode, sc) = {
+        let (stack_mode, sc, num_carriers) = {
             let c = config.config();
             tracing::info!(
                 "LmacBs: initialized with stack mode {:?}, mcc {} mnc {} cc {}",
@@ -73,6 +73,7 @@ impl LmacBs {
             (
                 c.stack_mode,
                 scrambler::tetra_scramb_get_init(c.net.mcc, c.net.mnc, c.cell.colour_code),
+                1 + c.cell.secondary_carriers.len(),
             )
         };
 
@@ -82,7 +83,7 @@ impl LmacBs {
             scrambling_code: sc,
 
             dltime: TdmaTime::default(),
-            uplink_phy_chan: [PhysicalChannel::Unallocated; 4],
+            uplink_phy_chan: vec![[PhysicalChannel::Unallocated; 4]; num_carriers],
             blk2_stolen: false,
         }
     }
@@ -176,6 +177,7 @@ impl LmacBs {
     }
 
     fn rx_blk_traffic(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel, ul_time: TdmaTime) {
+        let carrier = blk.carrier;
         // Only full-slot TCH/S supported for now
         if lchan != LogicalChannel::TchS || blk.block_num != PhyBlockNum::Both {
             tracing::trace!(
@@ -207,7 +209,11 @@ impl LmacBs {
             src: TetraEntity::Lmac,
             dest: TetraEntity::Umac,
             dltime: ul_time,
-            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts: ul_time.t, data }),
+            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd {
+                carrier,
+                ts: ul_time.t,
+                data,
+            }),
         };
         queue.push_back(msg);
     }
@@ -220,6 +226,7 @@ impl LmacBs {
         );
 
         let block_num = blk.block_num;
+        let carrier = blk.carrier;
         let (type1bits, crc_pass) = errorcontrol::decode_cp(lchan, blk, Some(self.scrambling_code));
         let type1bits = type1bits.unwrap(); // Guaranteed since scramb code set
 
@@ -248,6 +255,8 @@ impl LmacBs {
                 block_num,
                 crc_pass,
                 scrambling_code: self.scrambling_code,
+                direction: Direction::Ul,
+                carrier,
             }),
         };
 
@@ -265,7 +274,8 @@ impl LmacBs {
 
         // let pchan = self.determine_phy_chan_ul();
         let ts_idx = ul_time.t as usize - 1;
-        let pchan = self.uplink_phy_chan[ts_idx];
+        let pchan = self.uplink_phy_chan[prim.carrier as usize][ts_idx];
+        let block_num = prim.block_num;
         let lchan = Self::determine_logical_channel_ul(&prim, pchan == PhysicalChannel::Tp, self.blk2_stolen);
 
         // Sanity checks
@@ -290,6 +300,12 @@ impl LmacBs {
                 panic!()
             }
         }
+
+        // Bursts of all carriers arrive within the same tick, so the stolen flag of one
+        // carrier's burst must not carry over into the next carrier's burst
+        if block_num == PhyBlockNum::Block2 {
+            self.blk2_stolen = false;
+        }
     }
 
     fn rx_tmv_configure_req(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
@@ -310,7 +326,7 @@ impl LmacBs {
 
         // Update per-timeslot UL physical channel indicator
         let ts_idx = prim.ts.t as usize - 1;
-        self.uplink_phy_chan[ts_idx] = prim.ul_phy_chan;
+        self.uplink_phy_chan[prim.carrier as usize][ts_idx] = prim.ul_phy_chan;
 
         assert!(prim.bbk.is_some(), "rx_tmv_unitdata_req_slot: bbk must be present");
         assert!(prim.blk1.is_some(), "rx_tmv_unitdata_req_slot: blk1 must be present");
@@ -348,6 +364,7 @@ impl LmacBs {
         };
 
         let mut prim_phy = TpUnitdataReqSlot {
+            carrier: prim.carrier,
             train_type,
             burst_type,
             bbk: None,
@@ -0,0 +1,241 @@
+use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BurstType, Direction, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
+use tetra_saps::tmv::TmvUnitdataInd;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::lmac::components::{errorcontrol, scrambler};
+use crate::lmac::lmac_ms::CurBurst;
+
+/// Lower MAC for monitor mode. Decodes blocks from both the downlink and the uplink of a cell
+/// and passes them to the Umac, without ever transmitting anything.
+pub struct LmacMon {
+    config: SharedConfig,
+
+    /// Retrieved from SYNC frame by the Umac
+    scrambling_code: Option<u32>,
+
+    /// The Phy timestamps slots relative to the first SYNC burst it found. This offset
+    /// (in timeslots) converts those into network time, and is corrected by the Umac
+    /// whenever it learns the actual time from MAC-SYNC or SYSINFO.
+    time_offset: i32,
+
+    /// Details about current downlink burst, parsed from BBK broadcast block
+    cur_burst: CurBurst,
+}
+
+impl LmacMon {
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            scrambling_code: None,
+            time_offset: 0,
+            cur_burst: CurBurst::default(),
+        }
+    }
+
+    fn send_to_umac(&self, queue: &mut MessageQueue, prim: TmvUnitdataInd, time: TdmaTime, prio: bool) {
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime: time,
+            msg: SapMsgInner::TmvUnitdataInd(prim),
+        };
+        if prio {
+            queue.push_prio(m, MessagePrio::Immediate);
+        } else {
+            queue.push_back(m);
+        }
+    }
+
+    fn determine_logical_channel_dl(&self, blk: &TpUnitdataInd, t: &TdmaTime) -> LogicalChannel {
+        match blk.block_type {
+            PhyBlockType::BBK => LogicalChannel::Aach,
+            PhyBlockType::SB1 => LogicalChannel::Bsch,
+            PhyBlockType::SB2 if t.is_mandatory_bnch() => LogicalChannel::Bnch,
+            _ => {
+                if self.cur_burst.is_traffic {
+                    if (blk.block_num == PhyBlockNum::Block1 && self.cur_burst.blk1_stolen)
+                        || (blk.block_num == PhyBlockNum::Block2 && self.cur_burst.blk2_stolen)
+                        || blk.train_type == TrainingSequence::NormalTrainSeq2
+                    {
+                        LogicalChannel::Stch
+                    } else {
+                        LogicalChannel::TchS
+                    }
+                } else if blk.block_num == PhyBlockNum::Both {
+                    LogicalChannel::SchF
+                } else {
+                    LogicalChannel::SchHd
+                }
+            }
+        }
+    }
+
+    /// Without access to the BS schedule, the uplink channel is derived from the burst alone.
+    /// Full slot bursts that fail to decode as SCH/F are assumed to carry traffic.
+    fn determine_logical_channel_ul(blk: &TpUnitdataInd) -> LogicalChannel {
+        match blk.burst_type {
+            BurstType::CUB => LogicalChannel::SchHu,
+            _ => match blk.train_type {
+                TrainingSequence::NormalTrainSeq2 => LogicalChannel::Stch,
+                _ => LogicalChannel::SchF,
+            },
+        }
+    }
+
+    fn rx_bbk(&mut self, queue: &mut MessageQueue, bbk: TpUnitdataInd, time: TdmaTime) {
+        let Some(scrambling_code) = self.scrambling_code else {
+            // Need to receive SYNC first
+            return;
+        };
+
+        let type1 = errorcontrol::decode_aach(bbk.block, scrambling_code);
+        let prim = TmvUnitdataInd {
+            pdu: type1,
+            block_num: PhyBlockNum::Undefined,
+            logical_channel: LogicalChannel::Aach,
+            crc_pass: true,
+            scrambling_code,
+            direction: Direction::Dl,
+            carrier: 0,
+        };
+
+        // The ACCESS-ASSIGN determines how to interpret the other blocks in this burst
+        self.send_to_umac(queue, prim, time, true);
+    }
+
+    fn rx_blk_cp(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel, time: TdmaTime, direction: Direction) {
+        if lchan != LogicalChannel::Bsch && self.scrambling_code.is_none() {
+            // Need to receive SYNC first
+            return;
+        }
+
+        let block_num = blk.block_num;
+        let (type1bits, crc_pass) = errorcontrol::decode_cp(lchan, blk, self.scrambling_code);
+        let Some(type1bits) = type1bits else {
+            return;
+        };
+
+        tracing::trace!(ts=%time, "rx_blk_cp {:?} {:?} CRC: {}", direction, lchan, if crc_pass { "ok" } else { "WRONG" });
+        if !crc_pass {
+            return;
+        }
+
+        let scrambling_code = if lchan == LogicalChannel::Bsch {
+            scrambler::SCRAMB_INIT
+        } else {
+            self.scrambling_code.unwrap() // Guaranteed since we were able to decode
+        };
+
+        let prim = TmvUnitdataInd {
+            pdu: type1bits,
+            block_num,
+            logical_channel: lchan,
+            crc_pass,
+            scrambling_code,
+            direction,
+            carrier: 0,
+        };
+        self.send_to_umac(queue, prim, time, false);
+    }
+
+    fn rx_tp_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let time = message.dltime.add_timeslots(self.time_offset);
+        let SapMsgInner::TpUnitdataInd(prim) = message.msg else { panic!() };
+
+        match prim.burst_type {
+            BurstType::SDB | BurstType::NDB => {
+                let lchan = self.determine_logical_channel_dl(&prim, &time);
+                match lchan {
+                    LogicalChannel::Aach => self.rx_bbk(queue, prim, time),
+                    LogicalChannel::TchS | LogicalChannel::Tch24 | LogicalChannel::Tch48 | LogicalChannel::Tch72 => {
+                        tracing::trace!(ts=%time, "rx_tp_prim: DL traffic not decoded");
+                    }
+                    _ => self.rx_blk_cp(queue, prim, lchan, time, Direction::Dl),
+                }
+            }
+            BurstType::NUB | BurstType::CUB => {
+                let lchan = Self::determine_logical_channel_ul(&prim);
+                self.rx_blk_cp(queue, prim, lchan, time, Direction::Ul);
+            }
+        }
+    }
+
+    fn rx_tmv_configure_req(&mut self, message: SapMsg) {
+        let SapMsgInner::TmvConfigureReq(prim) = &message.msg else {
+            panic!()
+        };
+
+        if let Some(time) = prim.time {
+            // The request refers to the time we stamped on the block it was derived from
+            let correction = time.diff(message.dltime);
+            if correction != 0 {
+                self.time_offset += correction;
+                tracing::info!(
+                    "rx_tmv_configure_req: time {} -> {} (offset {})",
+                    message.dltime,
+                    time,
+                    self.time_offset
+                );
+            }
+        }
+
+        if let Some(scrambling_code) = prim.scrambling_code
+            && self.scrambling_code != Some(scrambling_code)
+        {
+            tracing::info!("rx_tmv_configure_req: set scrambling_code {}", scrambling_code);
+            self.scrambling_code = Some(scrambling_code);
+        }
+
+        if let Some(is_traffic) = prim.is_traffic {
+            self.cur_burst.is_traffic = is_traffic;
+        }
+
+        if let Some(blk2_stolen) = prim.blk2_stolen {
+            self.cur_burst.blk2_stolen = blk2_stolen;
+        }
+    }
+
+    fn rx_tmv_prim(&mut self, message: SapMsg) {
+        match message.msg {
+            SapMsgInner::TmvConfigureReq(_) => {
+                self.rx_tmv_configure_req(message);
+            }
+            _ => {
+                // Nothing is ever transmitted in monitor mode
+                tracing::debug!("rx_tmv_prim: dropping {:?}", message);
+            }
+        }
+    }
+}
+
+impl TetraEntityTrait for LmacMon {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Lmac
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match message.sap {
+            Sap::TpSap => {
+                self.rx_tp_prim(queue, message);
+            }
+            Sap::TmvSap => {
+                self.rx_tmv_prim(message);
+            }
+            _ => {
+                panic!();
+            }
+        }
+    }
+
+    fn tick_start(&mut self, _queue: &mut MessageQueue, _ts: TdmaTime) {
+        self.cur_burst = CurBurst::default();
+    }
+}
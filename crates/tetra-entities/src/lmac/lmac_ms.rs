@@ -1,10 +1,10 @@
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{PhyBlockNum, PhyBlockType, Sap, TdmaTime, unimplemented_log};
+use tetra_core::{BurstType, Direction, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence, unimplemented_log};
 use tetra_saps::tmv::TmvUnitdataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
-use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::{errorcontrol, scrambler};
@@ -42,8 +42,10 @@ pub struct LmacMs {
     /// Traffic channels and associated state
     tchans: [LmacTrafficChan; 64],
 
-    /// Timeslot time, provided by upper layer and then maintained in sync here
-    ts: Option<TdmaTime>,
+    /// The Phy timestamps slots relative to the first SYNC burst it found. This offset
+    /// (in timeslots) converts those into network time, and is corrected by the Umac
+    /// whenever it learns the actual time from MAC-SYNC or SYSINFO.
+    time_offset: i32,
     // mcc: Option<u16>,
     // mnc: Option<u16>,
     // cc: Option<u8>,
@@ -58,12 +60,11 @@ impl LmacMs {
             scrambling_code: None,
             tchans: [LmacTrafficChan::default(); 64],
             cur_burst: CurBurst::default(),
-
-            ts: None,
+            time_offset: 0,
         }
     }
 
-    fn rx_bbk(&mut self, queue: &mut MessageQueue, bbk: TpUnitdataInd) {
+    fn rx_bbk(&mut self, queue: &mut MessageQueue, bbk: TpUnitdataInd, time: TdmaTime) {
         // tracing::trace!("rx_bbk: {:?}", bbk.block.dump_bin());
 
         let type5 = bbk.block;
@@ -82,13 +83,15 @@ impl LmacMs {
             sap: Sap::TmvSap,
             src: TetraEntity::Lmac,
             dest: TetraEntity::Umac,
-            dltime: self.ts.unwrap_or_default(),
+            dltime: time,
             msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
                 pdu: type1,
                 block_num: PhyBlockNum::Undefined,
                 logical_channel: LogicalChannel::Aach,
                 crc_pass: true,
                 scrambling_code,
+                direction: Direction::Dl,
+                carrier: 0,
             }),
         };
 
@@ -108,12 +111,10 @@ impl LmacMs {
             return LogicalChannel::Bsch;
         }
 
-        // Sanity check: this should not be a mandatory BSCH block
-        assert!(
-            !(t.is_mandatory_bsch() && blk.block_num == PhyBlockNum::Block1),
-            "Mandatory BSCH block should be be SB1, not {:?}",
-            blk.block_type
-        );
+        // Sanity check: this should not be a mandatory BSCH block. May happen before we know the network time.
+        if t.is_mandatory_bsch() && blk.block_num == PhyBlockNum::Block1 {
+            tracing::debug!("Mandatory BSCH block should be be SB1, not {:?}", blk.block_type);
+        }
 
         // SB2 is broadcast if scheduled according to time
         if blk.block_type == PhyBlockType::SB2 && t.is_mandatory_bnch() {
@@ -147,7 +148,12 @@ impl LmacMs {
         unimplemented_log!("rx_blk_traffic: Traffic channel reception not implemented yet");
     }
 
-    fn rx_blk_cp(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
+    fn rx_blk_cp(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel, time: TdmaTime) {
+        if lchan != LogicalChannel::Bsch && self.scrambling_code.is_none() {
+            // Need to receive SYNC first
+            return;
+        }
+
         let block_num = blk.block_num;
         let (type1bits, crc_pass) = errorcontrol::decode_cp(lchan, blk, self.scrambling_code);
 
@@ -182,13 +188,15 @@ impl LmacMs {
                 sap: Sap::TmvSap,
                 src: TetraEntity::Lmac,
                 dest: TetraEntity::Umac,
-                dltime: self.ts.unwrap_or_default(),
+                dltime: time,
                 msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
                     pdu: type1bits,
                     block_num,
                     logical_channel: lchan,
                     crc_pass,
                     scrambling_code: scramb_code,
+                    direction: Direction::Dl,
+                    carrier: 0,
                 }),
             };
             queue.push_back(m);
@@ -196,20 +204,21 @@ impl LmacMs {
     }
 
     fn rx_tp_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
-        tracing::debug!("rx_tp_prim: time: {:?} msg {:?}", self.ts, message);
+        let time = message.dltime.add_timeslots(self.time_offset);
+        tracing::debug!("rx_tp_prim: time: {} msg {:?}", time, message);
 
         let SapMsgInner::TpUnitdataInd(prim) = message.msg else { panic!() };
-        let lchan = self.determine_logical_channel_dl(&prim, self.ts.as_ref().unwrap_or(&TdmaTime::default()));
+        let lchan = self.determine_logical_channel_dl(&prim, &time);
 
         match lchan {
             LogicalChannel::Aach => {
-                self.rx_bbk(queue, prim);
+                self.rx_bbk(queue, prim, time);
             }
             LogicalChannel::TchS | LogicalChannel::Tch24 | LogicalChannel::Tch48 | LogicalChannel::Tch72 => {
                 self.rx_blk_traffic(queue, prim, lchan)
             }
             _ => {
-                self.rx_blk_cp(queue, prim, lchan);
+                self.rx_blk_cp(queue, prim, lchan, time);
             }
         }
     }
@@ -221,8 +230,17 @@ impl LmacMs {
         };
 
         if let Some(time) = prim.time {
-            self.ts = Some(time);
-            tracing::debug!("rx_tmv_configure_req: set tdma_time {}", time);
+            // The request refers to the time we stamped on the block it was derived from
+            let correction = time.diff(message.dltime);
+            if correction != 0 {
+                self.time_offset += correction;
+                tracing::info!(
+                    "rx_tmv_configure_req: time {} -> {} (offset {})",
+                    message.dltime,
+                    time,
+                    self.time_offset
+                );
+            }
         }
 
         if let Some(scrambling_code) = prim.scrambling_code {
@@ -234,6 +252,54 @@ impl LmacMs {
             self.cur_burst.is_traffic = is_traffic;
             tracing::debug!("rx_tmv_configure_req: set cur_burst.is_traffic {}", is_traffic);
         }
+
+        if let Some(blk2_stolen) = prim.blk2_stolen {
+            self.cur_burst.blk2_stolen = blk2_stolen;
+        }
+    }
+
+    /// Request from Umac to transmit an uplink burst
+    fn rx_tmv_unitdata_req_slot(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::debug!("rx_tmv_unitdata_req_slot");
+        let SapMsgInner::TmvUnitdataReq(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let Some(blk1) = prim.blk1.take() else {
+            tracing::warn!("rx_tmv_unitdata_req_slot: nothing to transmit");
+            return;
+        };
+        let blk2 = prim.blk2.take();
+
+        // Determine train and burst type, Clause 9.5.1
+        let (burst_type, train_type) = match blk1.logical_channel {
+            LogicalChannel::SchHu => (BurstType::CUB, TrainingSequence::ExtendedTrainSeq),
+            LogicalChannel::SchF => (BurstType::NUB, TrainingSequence::NormalTrainSeq1),
+            LogicalChannel::Stch if blk2.is_some() => (BurstType::NUB, TrainingSequence::NormalTrainSeq2),
+            _ => {
+                unimplemented_log!("rx_tmv_unitdata_req_slot: unsupported uplink channel {:?}", blk1.logical_channel);
+                return;
+            }
+        };
+
+        let prim_phy = TpUnitdataReqSlot {
+            carrier: 0,
+            train_type,
+            burst_type,
+            bbk: None,
+            blk1: Some(errorcontrol::encode_cp(blk1)),
+            blk2: blk2.map(errorcontrol::encode_cp),
+        };
+
+        // Convert network time back to the timestamps used by the Phy
+        let m = SapMsg {
+            sap: Sap::TpSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Phy,
+            dltime: prim.ts.add_timeslots(-self.time_offset),
+            msg: SapMsgInner::TpUnitdataReq(prim_phy),
+        };
+        queue.push_back(m);
     }
 
     fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -244,7 +310,7 @@ impl LmacMs {
                 self.rx_tmv_configure_req(queue, message);
             }
             SapMsgInner::TmvUnitdataReq(_) => {
-                unimplemented_log!("TmvUnitdataReq")
+                self.rx_tmv_unitdata_req_slot(queue, message);
             }
             _ => {
                 panic!();
@@ -275,15 +341,8 @@ impl TetraEntityTrait for LmacMs {
         }
     }
 
-    fn tick_start(&mut self, _queue: &mut MessageQueue, ts: TdmaTime) {
+    fn tick_start(&mut self, _queue: &mut MessageQueue, _ts: TdmaTime) {
         // Reset current burst state
         self.cur_burst = CurBurst::default();
-
-        // Increase TDMA time if it has been set
-        if let Some(mod_time) = self.ts {
-            assert!(mod_time == ts, "time out of sync"); // TODO handle properly
-            self.ts = Some(mod_time.add_timeslots(1));
-            tracing::debug!("tick: new TdmaTime: {:?}", self.ts.unwrap()); // Guaranteed in BS mode
-        }
     }
 }
@@ -1,4 +1,5 @@
 pub mod components;
 
 pub mod lmac_bs;
+pub mod lmac_mon;
 pub mod lmac_ms;
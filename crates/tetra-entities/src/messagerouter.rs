@@ -7,6 +7,7 @@ use tetra_core::{TdmaTime, tetra_entities::TetraEntity};
 use tetra_saps::SapMsg;
 
 use crate::TetraEntityTrait;
+use crate::capture::pdu_capture::PduCapture;
 
 #[derive(Default)]
 pub enum MessagePrio {
@@ -57,6 +58,9 @@ pub struct MessageRouter {
     /// For Bs mode, this is always available
     /// For Ms/Mon mode, it is recovered from a received SYNC frame and communicated in a different way
     ts: TdmaTime,
+
+    /// Optional capture of the PDUs passing the TMV-SAP and TMA-SAP
+    capture: Option<PduCapture>,
 }
 
 impl MessageRouter {
@@ -66,6 +70,7 @@ impl MessageRouter {
             msg_queue: MessageQueue { messages: VecDeque::new() },
             _config: config,
             ts: TdmaTime::default(),
+            capture: None,
         }
     }
 
@@ -75,6 +80,11 @@ impl MessageRouter {
         self.ts = ts;
     }
 
+    /// Captures all PDUs passing the TMV-SAP and TMA-SAP from now on
+    pub fn set_capture(&mut self, capture: PduCapture) {
+        self.capture = Some(capture);
+    }
+
     pub fn register_entity(&mut self, entity: Box<dyn TetraEntityTrait>) {
         let comp_type = entity.entity();
         tracing::debug!("register_entity {:?}", comp_type);
@@ -106,6 +116,14 @@ impl MessageRouter {
                 message.get_dest()
             );
 
+            // Capture before delivery, as the receiving entity consumes the PDU
+            if let Some(capture) = &mut self.capture
+                && let Err(e) = capture.capture(&message)
+            {
+                tracing::error!("deliver_message: capture failed, disabling: {}", e);
+                self.capture = None;
+            }
+
             // Determine the destination entity
             let dest = message.get_dest();
 
@@ -178,6 +196,13 @@ impl MessageRouter {
         }
         self.deliver_all_messages();
 
+        if let Some(capture) = &mut self.capture
+            && let Err(e) = capture.flush()
+        {
+            tracing::error!("tick_end: capture failed, disabling: {}", e);
+            self.capture = None;
+        }
+
         // Increment the TDMA time if set
         self.ts = self.ts.add_timeslots(1);
     }
@@ -208,6 +233,11 @@ impl MessageRouter {
             // Send tick_end event and process final messages
             self.tick_end();
 
+            if let Some(entity) = self.entities.values().find(|e| e.is_finished()) {
+                tracing::info!("run_stack: {:?} finished, stopping", entity.entity());
+                break;
+            }
+
             // Check if we should stop
             ticks += 1;
             if let Some(num_ticks) = num_ticks {
@@ -0,0 +1,334 @@
+//! Management API entity answering operator requests from the stack state and steering CMCE and MM through SapMsgs
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use serde_json::{Value, json};
+
+use crate::{MessageQueue, TetraEntityTrait, brew};
+use tetra_config::bluestation::{AieKeyClass, DisableKind, SharedConfig};
+use tetra_core::{Sap, TdmaTime, TimeslotAllocator, multiframes, tetra_entities::TetraEntity};
+use tetra_saps::control::call_control::{CallControl, CallInfo};
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::control::mm::{DisableTarget, MmControl};
+use tetra_saps::control::sds::CmceSdsData;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use super::protocol::{MgmtRequest, err, ok, parse_hex, simple_text_payload};
+use super::worker::{MgmtCommand, MgmtWorker};
+
+/// Largest SDS type 4 user data, limited by the 11-bit length indicator
+const SDS_TYPE4_MAX_BYTES: usize = 2047 / 8;
+
+/// How long a request may wait for another entity before it is failed
+const PENDING_REQUEST_TIMEOUT: i32 = multiframes!(4);
+
+pub struct MgmtEntity {
+    config: SharedConfig,
+    dltime: TdmaTime,
+
+    command_receiver: Receiver<MgmtCommand>,
+    /// Requests waiting for an answer from another entity, by request id, with the time they were sent
+    pending: HashMap<u64, (TdmaTime, Sender<Value>)>,
+    next_req_id: u64,
+
+    /// Tells the worker thread to stop listening
+    shutdown: Arc<AtomicBool>,
+    worker_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl MgmtEntity {
+    pub fn new(config: SharedConfig) -> Self {
+        let socket_path = config.config().mgmt.as_ref().unwrap().socket_path.clone(); // Never fails
+        let (command_sender, command_receiver) = unbounded::<MgmtCommand>();
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let worker = MgmtWorker::bind(Path::new(&socket_path), command_sender, shutdown.clone())
+            .unwrap_or_else(|e| panic!("failed to bind management socket {}: {}", socket_path, e));
+        let handle = thread::Builder::new()
+            .name("mgmt-worker".to_string())
+            .spawn(move || worker.run())
+            .expect("failed to spawn MgmtWorker thread");
+
+        Self {
+            config,
+            dltime: TdmaTime::default(),
+            command_receiver,
+            pending: HashMap::new(),
+            next_req_id: 0,
+            shutdown,
+            worker_handle: Some(handle),
+        }
+    }
+
+    fn send_control(&self, queue: &mut MessageQueue, dest: TetraEntity, msg: SapMsgInner) {
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mgmt,
+            dest,
+            dltime: self.dltime,
+            msg,
+        });
+    }
+
+    fn subscribers_json(&self) -> Value {
+        let state = self.config.state_read();
+        let mut subscribers: Vec<_> = state.subscribers.iter().collect();
+        subscribers.sort_by_key(|s| s.issi);
+        subscribers
+            .iter()
+            .map(|s| {
+                let mut groups: Vec<u32> = s.attached_groups.iter().copied().collect();
+                groups.sort_unstable();
+                let encryption = match s.aie {
+                    None => "clear",
+                    Some(AieKeyClass::Sck) => "sck",
+                    Some(AieKeyClass::Dck(_)) => "dck",
+                };
+                json!({ "issi": s.issi, "groups": groups, "encryption": encryption })
+            })
+            .collect()
+    }
+
+    fn disabled_json(&self) -> Value {
+        let state = self.config.state_read();
+        let mut disabled: Vec<(u32, DisableKind)> = state.subscribers.iter_disabled().collect();
+        disabled.sort_by_key(|&(issi, _)| issi);
+        disabled
+            .iter()
+            .map(|&(issi, kind)| json!({ "issi": issi, "permanent": kind == DisableKind::Permanent }))
+            .collect()
+    }
+
+    fn timeslots_json(&self) -> Value {
+        let state = self.config.state_read();
+        let alloc = &state.timeslot_alloc;
+        (0..alloc.num_carriers())
+            .flat_map(|carrier| TimeslotAllocator::traffic_timeslots(carrier).map(move |ts| (carrier, ts)))
+            .map(|(carrier, ts)| json!({ "carrier": carrier, "ts": ts, "owner": alloc.owner_on(carrier, ts).map(|o| format!("{:?}", o)) }))
+            .collect()
+    }
+
+    fn brew_json(&self) -> Value {
+        json!({
+            "enabled": brew::is_active(&self.config),
+            "connected": self.config.state_read().network_connected,
+        })
+    }
+
+    fn calls_json(calls: &[CallInfo]) -> Value {
+        calls
+            .iter()
+            .map(|c| {
+                json!({
+                    "call_id": c.call_id,
+                    "type": if c.individual { "individual" } else { "group" },
+                    "source_ssi": c.source_ssi,
+                    "dest_ssi": c.dest_ssi,
+                    "carrier": c.carrier,
+                    "timeslots": c.timeslots,
+                    "network": c.network,
+                    "tx_active": c.tx_active,
+                })
+            })
+            .collect()
+    }
+
+    fn rx_command(&mut self, queue: &mut MessageQueue, command: MgmtCommand) {
+        let response = match command.request {
+            MgmtRequest::Status => ok(json!({
+                "dltime": self.dltime.to_string().trim(),
+                "subscribers": self.config.state_read().subscribers.iter().count(),
+                "brew": self.brew_json(),
+                "timeslots": self.timeslots_json(),
+            })),
+            MgmtRequest::Subscribers => ok(self.subscribers_json()),
+            MgmtRequest::Timeslots => ok(self.timeslots_json()),
+            MgmtRequest::Brew => ok(self.brew_json()),
+            MgmtRequest::Disabled => ok(self.disabled_json()),
+            MgmtRequest::Calls => {
+                // Answered once CMCE reports back
+                let req_id = self.next_req_id;
+                self.next_req_id += 1;
+                self.pending.insert(req_id, (self.dltime, command.reply));
+                self.send_control(
+                    queue,
+                    TetraEntity::Cmce,
+                    SapMsgInner::CmceCallControl(CallControl::ListCalls { req_id }),
+                );
+                return;
+            }
+            MgmtRequest::ReleaseCall { call_id } => {
+                tracing::info!("MgmtEntity: releasing call_id={}", call_id);
+                self.send_control(
+                    queue,
+                    TetraEntity::Cmce,
+                    SapMsgInner::CmceCallControl(CallControl::ReleaseCall { call_id }),
+                );
+                ok(Value::Null)
+            }
+            MgmtRequest::Deregister { issi } => {
+                if self.config.state_read().subscribers.is_registered(issi) {
+                    tracing::info!("MgmtEntity: deregistering ISSI {}", issi);
+                    self.send_control(queue, TetraEntity::Mm, SapMsgInner::MmControl(MmControl::Deregister { issi }));
+                    ok(Value::Null)
+                } else {
+                    err(format!("ISSI {} is not registered", issi))
+                }
+            }
+            MgmtRequest::AttachGroups { issi, gssis } => self.rx_assign_groups(queue, issi, gssis, Vec::new()),
+            MgmtRequest::DetachGroups { issi, gssis } => self.rx_assign_groups(queue, issi, Vec::new(), gssis),
+            MgmtRequest::Disable { issi, .. } if issi == 0 || issi >= 1 << 24 => err(format!("Invalid ISSI {}", issi)),
+            MgmtRequest::Disable {
+                issi,
+                permanent,
+                subscription,
+                tei,
+            } => match Self::disable_target(subscription, tei) {
+                Ok(target) => {
+                    tracing::info!("MgmtEntity: disabling ISSI {}, permanent: {}, {:?}", issi, permanent, target);
+                    self.send_control(
+                        queue,
+                        TetraEntity::Mm,
+                        SapMsgInner::MmControl(MmControl::Disable { issi, permanent, target }),
+                    );
+                    ok(Value::Null)
+                }
+                Err(e) => err(e),
+            },
+            MgmtRequest::Enable { issi, subscription, tei } => match Self::disable_target(subscription, tei) {
+                Ok(target) if target.subscription() && self.config.state_read().subscribers.disabled(issi).is_none() => {
+                    err(format!("ISSI {} is not disabled", issi))
+                }
+                Ok(target) => {
+                    tracing::info!("MgmtEntity: enabling ISSI {}, {:?}", issi, target);
+                    self.send_control(queue, TetraEntity::Mm, SapMsgInner::MmControl(MmControl::Enable { issi, target }));
+                    ok(Value::Null)
+                }
+                Err(e) => err(e),
+            },
+            MgmtRequest::SendSds {
+                source_issi,
+                dest_issi,
+                text,
+                hex,
+            } => self.rx_send_sds(queue, source_issi, dest_issi, text, hex),
+        };
+        let _ = command.reply.send(response);
+    }
+
+    /// What a disable or enable request acts on: the subscription, the equipment with the given TEI, or both
+    fn disable_target(subscription: bool, tei: Option<u64>) -> Result<DisableTarget, String> {
+        match (subscription, tei) {
+            (_, Some(tei)) if tei >= 1 << 60 => Err(format!("Invalid TEI {}", tei)),
+            (true, None) => Ok(DisableTarget::Subscription),
+            (false, Some(tei)) => Ok(DisableTarget::Equipment { tei }),
+            (true, Some(tei)) => Ok(DisableTarget::Both { tei }),
+            (false, None) => Err("tei is needed unless the subscription is targeted".to_string()),
+        }
+    }
+
+    /// Hands a group assignment to MM, which completes it once the MS acknowledges
+    fn rx_assign_groups(&self, queue: &mut MessageQueue, issi: u32, attach: Vec<u32>, detach: Vec<u32>) -> Value {
+        if attach.is_empty() && detach.is_empty() {
+            return err("gssis must not be empty");
+        }
+        if let Some(&gssi) = attach.iter().chain(&detach).find(|&&gssi| gssi == 0 || gssi >= 1 << 24) {
+            return err(format!("Invalid GSSI {}", gssi));
+        }
+        if !self.config.state_read().subscribers.is_registered(issi) {
+            return err(format!("ISSI {} is not registered", issi));
+        }
+        self.send_control(
+            queue,
+            TetraEntity::Mm,
+            SapMsgInner::MmControl(MmControl::AssignGroups { issi, attach, detach }),
+        );
+        ok(Value::Null)
+    }
+
+    fn rx_send_sds(&self, queue: &mut MessageQueue, source_issi: u32, dest_issi: u32, text: Option<String>, hex: Option<String>) -> Value {
+        let payload = match (text, hex) {
+            (Some(text), None) => simple_text_payload(&text),
+            (None, Some(hex)) => match parse_hex(&hex) {
+                Ok(payload) => payload,
+                Err(e) => return err(e),
+            },
+            _ => return err("send_sds needs either text or hex"),
+        };
+        if payload.len() > SDS_TYPE4_MAX_BYTES {
+            return err(format!("SDS payload too long: {} bytes", payload.len()));
+        }
+        if !self.config.state_read().subscribers.is_registered(dest_issi) {
+            return err(format!("ISSI {} is not registered", dest_issi));
+        }
+
+        tracing::info!("MgmtEntity: sending SDS {} -> {}, {} bytes", source_issi, dest_issi, payload.len());
+        let sds = CmceSdsData {
+            source_issi,
+            dest_issi,
+            user_defined_data: SdsUserData::Type4(payload.len() as u16 * 8, payload),
+        };
+        self.send_control(queue, TetraEntity::Cmce, SapMsgInner::CmceSdsData(sds));
+        ok(Value::Null)
+    }
+
+    /// Fails requests whose answer never arrived, so the client is not left waiting
+    fn expire_pending(&mut self) {
+        let now = self.dltime;
+        self.pending.retain(|req_id, (sent, reply)| {
+            if sent.age(now) < PENDING_REQUEST_TIMEOUT {
+                return true;
+            }
+            tracing::warn!("MgmtEntity: request {} timed out", req_id);
+            let _ = reply.send(err("timed out waiting for an answer"));
+            false
+        });
+    }
+}
+
+impl TetraEntityTrait for MgmtEntity {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Mgmt
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+        self.expire_pending();
+        while let Ok(command) = self.command_receiver.try_recv() {
+            self.rx_command(queue, command);
+        }
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match message.msg {
+            SapMsgInner::CmceCallControl(CallControl::CallList { req_id, calls }) => {
+                if let Some((_, reply)) = self.pending.remove(&req_id) {
+                    let _ = reply.send(ok(Self::calls_json(&calls)));
+                }
+            }
+            _ => {
+                tracing::warn!("MgmtEntity: unexpected message: {:?}", message.msg);
+            }
+        }
+    }
+}
+
+impl Drop for MgmtEntity {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,5 @@
+//! Runtime management API, letting an operator inspect and steer the running BS over a local Unix socket
+
+pub mod entity;
+pub mod protocol;
+pub mod worker;
@@ -0,0 +1,171 @@
+//! Management API protocol: one JSON request per line, answered by one JSON response per line
+//!
+//! Requests carry a `cmd` field, such as `{"cmd":"release_call","call_id":5}`.
+//! Responses are `{"ok":true,"result":...}` or `{"ok":false,"error":"..."}`.
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// SDS-TL protocol identifier for simple text messaging (Clause 29.5.2)
+pub const SDS_PROTOCOL_SIMPLE_TEXT_MESSAGING: u8 = 0x02;
+/// Text coding scheme ISO/IEC 8859-1 Latin 1, preceded by a zero fill bit
+pub const SDS_TEXT_CODING_LATIN1: u8 = 0x01;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum MgmtRequest {
+    /// Overview of the stack: network time, subscriber count, Brew and timeslot state
+    Status,
+    /// Registered subscribers with their group attachments
+    Subscribers,
+    /// Active group and individual calls
+    Calls,
+    /// Owner of each traffic timeslot
+    Timeslots,
+    /// Brew backhaul connection state
+    Brew,
+    /// Send an SDS to a registered MS, either as simple text message or as raw type 4 user data in hex
+    SendSds {
+        source_issi: u32,
+        dest_issi: u32,
+        #[serde(default)]
+        text: Option<String>,
+        #[serde(default)]
+        hex: Option<String>,
+    },
+    /// Force-release a call
+    ReleaseCall { call_id: u16 },
+    /// Deregister an MS
+    Deregister { issi: u32 },
+    /// Attach a registered MS to groups (DGNA). The MS is asked to acknowledge the assignment.
+    AttachGroups { issi: u32, gssis: Vec<u32> },
+    /// Detach a registered MS from groups
+    DetachGroups { issi: u32, gssis: Vec<u32> },
+    /// Disable a lost or stolen MS with D-DISABLE, temporarily unless permanent is set. Its subscription is
+    /// disabled unless subscription is false, and its equipment as well if the TEI is given. An MS with a
+    /// disabled subscription is refused when it registers again.
+    Disable {
+        issi: u32,
+        #[serde(default)]
+        permanent: bool,
+        #[serde(default = "default_true")]
+        subscription: bool,
+        #[serde(default)]
+        tei: Option<u64>,
+    },
+    /// Enable a disabled MS again with D-ENABLE, its subscription and/or the equipment with the given TEI
+    Enable {
+        issi: u32,
+        #[serde(default = "default_true")]
+        subscription: bool,
+        #[serde(default)]
+        tei: Option<u64>,
+    },
+    /// Disabled ISSIs
+    Disabled,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub fn parse_request(line: &str) -> Result<MgmtRequest, String> {
+    serde_json::from_str(line).map_err(|e| format!("invalid request: {}", e))
+}
+
+pub fn ok(result: Value) -> Value {
+    json!({ "ok": true, "result": result })
+}
+
+pub fn err(error: impl Into<String>) -> Value {
+    json!({ "ok": false, "error": error.into() })
+}
+
+/// SDS type 4 payload for a simple text message. Characters outside Latin 1 are replaced by '?'.
+pub fn simple_text_payload(text: &str) -> Vec<u8> {
+    let mut payload = vec![SDS_PROTOCOL_SIMPLE_TEXT_MESSAGING, SDS_TEXT_CODING_LATIN1];
+    payload.extend(text.chars().map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?')));
+    payload
+}
+
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, String> {
+    if !hex.is_ascii() {
+        return Err(format!("invalid hex payload: {}", hex));
+    }
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return Err("hex payload must be a non-empty, even number of digits".to_string());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("invalid hex payload: {}", hex)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requests() {
+        assert_eq!(parse_request(r#"{"cmd":"subscribers"}"#), Ok(MgmtRequest::Subscribers));
+        assert_eq!(
+            parse_request(r#"{"cmd":"release_call","call_id":5}"#),
+            Ok(MgmtRequest::ReleaseCall { call_id: 5 })
+        );
+        assert_eq!(
+            parse_request(r#"{"cmd":"send_sds","source_issi":9999,"dest_issi":1001,"text":"hi"}"#),
+            Ok(MgmtRequest::SendSds {
+                source_issi: 9999,
+                dest_issi: 1001,
+                text: Some("hi".to_string()),
+                hex: None,
+            })
+        );
+        assert!(parse_request(r#"{"cmd":"reboot"}"#).is_err());
+        assert!(parse_request(r#"{"cmd":"deregister"}"#).is_err());
+        assert_eq!(
+            parse_request(r#"{"cmd":"attach_groups","issi":1001,"gssis":[91,92]}"#),
+            Ok(MgmtRequest::AttachGroups {
+                issi: 1001,
+                gssis: vec![91, 92]
+            })
+        );
+        assert_eq!(
+            parse_request(r#"{"cmd":"disable","issi":1001}"#),
+            Ok(MgmtRequest::Disable {
+                issi: 1001,
+                permanent: false,
+                subscription: true,
+                tei: None,
+            })
+        );
+        assert_eq!(
+            parse_request(r#"{"cmd":"disable","issi":1001,"subscription":false,"tei":1234}"#),
+            Ok(MgmtRequest::Disable {
+                issi: 1001,
+                permanent: false,
+                subscription: false,
+                tei: Some(1234),
+            })
+        );
+        assert_eq!(
+            parse_request(r#"{"cmd":"enable","issi":1001}"#),
+            Ok(MgmtRequest::Enable {
+                issi: 1001,
+                subscription: true,
+                tei: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_sds_payloads() {
+        assert_eq!(simple_text_payload("Hé"), vec![0x02, 0x01, b'H', 0xe9]);
+        assert_eq!(simple_text_payload("€"), vec![0x02, 0x01, b'?']);
+        assert_eq!(parse_hex("0a8Ff0"), Ok(vec![0x0a, 0x8f, 0xf0]));
+        assert!(parse_hex("abc").is_err());
+        assert!(parse_hex("zz").is_err());
+        // Non-ASCII input must not be sliced mid-character
+        assert!(parse_hex("aéa").is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Management API worker thread accepting Unix socket connections and relaying requests to the MgmtEntity
+
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{Sender, bounded};
+use serde_json::Value;
+
+use super::protocol::{MgmtRequest, err, parse_request};
+
+/// Interval at which the listener checks for new connections and shutdown
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Time to wait for the stack to answer a request
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A request from an operator, passed from the worker to the entity
+#[derive(Debug)]
+pub struct MgmtCommand {
+    pub request: MgmtRequest,
+    /// Where the entity sends the JSON response
+    pub reply: Sender<Value>,
+}
+
+pub struct MgmtWorker {
+    listener: UnixListener,
+    socket_path: PathBuf,
+    command_sender: Sender<MgmtCommand>,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl MgmtWorker {
+    /// Bind the socket, replacing a stale socket file left behind by an earlier run
+    pub fn bind(socket_path: &Path, command_sender: Sender<MgmtCommand>, shutdown: Arc<AtomicBool>) -> io::Result<Self> {
+        if socket_path.exists() {
+            std::fs::remove_file(socket_path)?;
+        }
+        let listener = UnixListener::bind(socket_path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            socket_path: socket_path.to_path_buf(),
+            command_sender,
+            shutdown,
+        })
+    }
+
+    pub fn run(self) {
+        tracing::info!("MgmtWorker: listening on {}", self.socket_path.display());
+        while !self.shutdown.load(Ordering::Relaxed) {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    let command_sender = self.command_sender.clone();
+                    let spawned = thread::Builder::new()
+                        .name("mgmt-conn".to_string())
+                        .spawn(move || Self::serve(stream, command_sender));
+                    if let Err(e) = spawned {
+                        tracing::warn!("MgmtWorker: failed to spawn connection thread: {}", e);
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+                Err(e) => {
+                    tracing::warn!("MgmtWorker: accept failed: {}", e);
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+        let _ = std::fs::remove_file(&self.socket_path);
+        tracing::debug!("MgmtWorker: stopped");
+    }
+
+    /// Answer requests on a connection until the client closes it
+    fn serve(stream: UnixStream, command_sender: Sender<MgmtCommand>) {
+        if let Err(e) = stream.set_nonblocking(false) {
+            tracing::warn!("MgmtWorker: failed to configure connection: {}", e);
+            return;
+        }
+        let Ok(mut writer) = stream.try_clone() else {
+            return;
+        };
+
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match parse_request(&line) {
+                Ok(request) => {
+                    tracing::debug!("MgmtWorker: request {:?}", request);
+                    let (reply, reply_receiver) = bounded(1);
+                    if command_sender.send(MgmtCommand { request, reply }).is_err() {
+                        // Entity is gone, the stack is shutting down
+                        break;
+                    }
+                    reply_receiver
+                        .recv_timeout(REPLY_TIMEOUT)
+                        .unwrap_or_else(|_| err("no response from stack"))
+                }
+                Err(e) => err(e),
+            };
+
+            if writeln!(writer, "{}", response).is_err() {
+                break;
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use tetra_config::bluestation::SharedConfig;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
-use tetra_pdus::mle::{enums::mle_protocol_discriminator::MleProtocolDiscriminator, pdus::d_nwrk_broadcast::DNwrkBroadcast};
+use tetra_config::bluestation::{CfgCellInfo, CfgNeighbourCell, SharedConfig};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, freqs::FreqInfo, tetra_entities::TetraEntity};
+use tetra_pdus::mle::{
+    enums::mle_protocol_discriminator::MleProtocolDiscriminator, fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa,
+    pdus::d_nwrk_broadcast::DNwrkBroadcast,
+};
 use tetra_saps::{SapMsg, SapMsgInner, tla::TlaTlUnitdataReqBl};
 
 use crate::{MessageQueue, mle::components::network_time};
@@ -9,22 +12,28 @@ use crate::{MessageQueue, mle::components::network_time};
 pub enum BroadcastType {
     /// Initial value and value when no broadcast types are enabled
     None,
-    NetworkTime,
+    /// D-NWRK-BROADCAST with the network time and/or the neighbour cells
+    Network,
 }
 
 pub struct MleBroadcast {
     config: SharedConfig,
     last_broadcast_type: BroadcastType,
     time_broadcast: Option<String>,
+    /// Neighbour cell elements, built once from the config
+    neighbours: Vec<NeighbourCellInformationForCa>,
 }
 
 impl MleBroadcast {
     pub fn new(config: SharedConfig) -> Self {
-        let time_broadcast = config.config().cell.timezone.clone();
+        let cfg = config.config();
+        let time_broadcast = cfg.cell.timezone.clone();
+        let neighbours = cfg.neighbour_cells.iter().map(|n| neighbour_cell_info(&cfg.cell, n)).collect();
         Self {
             config,
             last_broadcast_type: BroadcastType::None,
             time_broadcast,
+            neighbours,
         }
     }
 
@@ -34,7 +43,7 @@ impl MleBroadcast {
         self.last_broadcast_type = broadcast_type;
 
         match broadcast_type {
-            BroadcastType::NetworkTime => {
+            BroadcastType::Network => {
                 self.send_d_nwrk_broadcast(queue, ts);
             }
             BroadcastType::None => {
@@ -47,31 +56,31 @@ impl MleBroadcast {
     fn determine_next_broadcast_type(&self) -> BroadcastType {
         match self.last_broadcast_type {
             BroadcastType::None => {
-                if self.time_broadcast.is_some() {
-                    BroadcastType::NetworkTime
+                if self.time_broadcast.is_some() || !self.neighbours.is_empty() {
+                    BroadcastType::Network
                 } else {
                     BroadcastType::None
                 }
             }
-            BroadcastType::NetworkTime => BroadcastType::NetworkTime,
+            BroadcastType::Network => BroadcastType::Network,
         }
     }
 
     fn send_d_nwrk_broadcast(&self, queue: &mut MessageQueue, ts: TdmaTime) {
         // Timezone is validated at config parse time, so encode cannot fail here
-        let tz = self.time_broadcast.as_deref().unwrap();
-        let time_value = network_time::encode_tetra_network_time(tz).unwrap();
+        let tz = self.time_broadcast.as_deref();
+        let time_value = tz.map(|tz| network_time::encode_tetra_network_time(tz).unwrap());
 
         let pdu = DNwrkBroadcast {
             cell_re_select_parameters: 0,
             cell_load_ca: 0,
-            tetra_network_time: Some(time_value),
-            number_of_ca_neighbour_cells: Some(0),
-            neighbour_cell_information_for_ca: None,
+            tetra_network_time: time_value,
+            number_of_ca_neighbour_cells: Some(self.neighbours.len() as u64),
+            neighbour_cell_information_for_ca: self.neighbours.clone(),
         };
 
         // Serialize the PDU (includes 3-bit MLE PDU type)
-        let mut pdu_buf = BitBuffer::new(128);
+        let mut pdu_buf = BitBuffer::new_autoexpand(128);
         if let Err(e) = pdu.to_bitbuf(&mut pdu_buf) {
             tracing::warn!("Failed to serialize D-NWRK-BROADCAST: {:?}", e);
             return;
@@ -117,6 +126,40 @@ impl MleBroadcast {
             }),
         };
         queue.push_back(sapmsg);
-        tracing::info!("D-NWRK-BROADCAST sent (tz={}, time=0x{:012X})", tz, time_value);
+        tracing::info!(
+            "D-NWRK-BROADCAST sent (tz={:?}, time={:012X?}, neighbours={})",
+            tz,
+            time_value,
+            self.neighbours.len()
+        );
+    }
+}
+
+/// Neighbour cell information element for a configured neighbour. Frequency settings not
+/// configured for the neighbour are those of this cell.
+fn neighbour_cell_info(cell: &CfgCellInfo, n: &CfgNeighbourCell) -> NeighbourCellInformationForCa {
+    // Validated at config parse time
+    let offset_id = FreqInfo::freq_offset_hz_to_id(n.freq_offset_hz.unwrap_or(cell.freq_offset_hz)).unwrap();
+    let extension = (n.freq_band.unwrap_or(cell.freq_band) as u64) << 6
+        | (offset_id as u64) << 4
+        | (n.duplex_spacing_id.unwrap_or(cell.duplex_spacing_id) as u64) << 1
+        | n.reverse_operation.unwrap_or(cell.reverse_operation) as u64;
+
+    NeighbourCellInformationForCa {
+        cell_identifier_ca: n.cell_identifier,
+        cell_reselection_types_supported: n.reselection_types_supported,
+        neighbour_cell_synchronized: n.synchronized,
+        cell_load_ca: n.cell_load,
+        main_carrier_number: n.main_carrier,
+        main_carrier_number_extension: Some(extension),
+        mcc: n.mcc.map(u64::from),
+        mnc: n.mnc.map(u64::from),
+        location_area: n.location_area.map(u64::from),
+        maximum_ms_transmit_power: n.max_ms_tx_power.map(u64::from),
+        minimum_rx_access_level: n.min_rx_access_level.map(u64::from),
+        subscriber_class: None,
+        bs_service_details: None,
+        timeshare_cell_information_or_security_parameters: None,
+        tdma_frame_offset: n.tdma_frame_offset.map(u64::from),
     }
 }
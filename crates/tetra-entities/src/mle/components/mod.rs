@@ -1,3 +1,4 @@
 pub mod broadcast;
 pub mod mle_router;
 pub mod network_time;
+pub mod reselection;
@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::{BitBuffer, Sap, TdmaTime, TetraAddress, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, cmce_pdu_type_ul::CmcePduTypeUl};
+use tetra_pdus::mle::{
+    enums::{channel_command_valid::ChannelCommandValid, fail_cause::FailCause, mle_protocol_discriminator::MleProtocolDiscriminator},
+    pdus::{
+        d_new_cell::DNewCell, d_prepare_fail::DPrepareFail, d_restore_ack::DRestoreAck, d_restore_fail::DRestoreFail, u_prepare::UPrepare,
+        u_restore::URestore,
+    },
+};
+use tetra_saps::{SapMsg, SapMsgInner, tla::TlaTlDataReqBl};
+
+use crate::MessageQueue;
+
+/// MLE PDUs that can be sent by the BS in cell reselection signalling
+enum ReselectionPdu {
+    NewCell(DNewCell),
+    PrepareFail(DPrepareFail),
+    RestoreAck(DRestoreAck),
+    RestoreFail(DRestoreFail),
+}
+
+impl ReselectionPdu {
+    /// Serializes the PDU, preceded by the MLE protocol discriminator
+    fn to_tl_sdu(&self) -> BitBuffer {
+        let mut tl_sdu = BitBuffer::new_autoexpand(64);
+        tl_sdu.write_bits(MleProtocolDiscriminator::Mle.into_raw(), 3);
+        let result = match self {
+            ReselectionPdu::NewCell(pdu) => pdu.to_bitbuf(&mut tl_sdu),
+            ReselectionPdu::PrepareFail(pdu) => pdu.to_bitbuf(&mut tl_sdu),
+            ReselectionPdu::RestoreAck(pdu) => pdu.to_bitbuf(&mut tl_sdu),
+            ReselectionPdu::RestoreFail(pdu) => pdu.to_bitbuf(&mut tl_sdu),
+        };
+        result.expect("Failed to serialize MLE PDU");
+        tl_sdu.seek(0);
+        tl_sdu
+    }
+}
+
+/// Announced cell reselection from this cell to a configured neighbour cell (U-PREPARE),
+/// and restoration of the C-plane of MSs arriving on this cell (U-RESTORE).
+pub struct MleReselection {
+    config: SharedConfig,
+    /// ISSIs whose U-CALL RESTORE was passed to the CMCE, awaiting its answer
+    pending_restores: HashSet<u32>,
+}
+
+impl MleReselection {
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            pending_restores: HashSet::new(),
+        }
+    }
+
+    /// Handles U-PREPARE on the serving cell. The MS is released towards the announced cell with
+    /// D-NEW-CELL if it is one of our neighbour cells and supports announced reselection.
+    pub fn rx_u_prepare(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, address: TetraAddress, mut sdu: BitBuffer) {
+        let pdu = match UPrepare::from_bitbuf(&mut sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-PREPARE: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+
+        // Forward registration would need the MM of the neighbour cell, which is a separate BS.
+        // The MS registers there after the reselection instead.
+        if pdu.sdu.is_some() {
+            tracing::debug!("U-PREPARE from {}: not forwarding SDU to the new cell", address);
+        }
+
+        let cfg = self.config.config();
+        let neighbour = pdu
+            .cell_identifier_ca
+            .and_then(|id| cfg.neighbour_cells.iter().find(|n| n.cell_identifier as u64 == id));
+        let response = match neighbour {
+            Some(n) if n.reselection_types_supported > 0 => {
+                tracing::info!("U-PREPARE from {}: reselection to neighbour cell {}", address, n.cell_identifier);
+                ReselectionPdu::NewCell(DNewCell {
+                    channel_command_valid: ChannelCommandValid::ChangeChannelImmediately.into_raw() as u8,
+                    sdu: None,
+                })
+            }
+            Some(n) => {
+                tracing::info!(
+                    "U-PREPARE from {}: neighbour cell {} has no announced reselection",
+                    address,
+                    n.cell_identifier
+                );
+                ReselectionPdu::PrepareFail(DPrepareFail {
+                    fail_cause: FailCause::CellReselectionTypeNotSupported.into_raw() as u8,
+                    sdu: None,
+                })
+            }
+            None => {
+                tracing::info!("U-PREPARE from {}: unknown cell {:?}", address, pdu.cell_identifier_ca);
+                ReselectionPdu::PrepareFail(DPrepareFail {
+                    fail_cause: FailCause::ServicePermanentlyUnavailable.into_raw() as u8,
+                    sdu: None,
+                })
+            }
+        };
+        Self::send(queue, dltime, address, response);
+    }
+
+    /// Handles U-RESTORE on this cell. Returns the U-CALL RESTORE to pass to the CMCE, whose answer
+    /// is then turned into D-RESTORE-ACK or D-RESTORE-FAIL by restore_response.
+    pub fn rx_u_restore(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        address: TetraAddress,
+        mut sdu: BitBuffer,
+    ) -> Option<BitBuffer> {
+        let pdu = match URestore::from_bitbuf(&mut sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing U-RESTORE: {:?} {}", e, sdu.dump_bin());
+                return None;
+            }
+        };
+
+        let Some(cmce_sdu) = pdu.sdu else {
+            // No call to restore, only the C-plane
+            tracing::info!("U-RESTORE from {}: no call to restore", address);
+            Self::send(queue, dltime, address, ReselectionPdu::RestoreAck(DRestoreAck { sdu: None }));
+            return None;
+        };
+        if cmce_sdu.peek_bits(5) != Some(CmcePduTypeUl::UCallRestore.into_raw()) {
+            tracing::warn!("U-RESTORE from {}: SDU is not a U-CALL RESTORE: {}", address, cmce_sdu.dump_bin());
+            let fail = DRestoreFail {
+                fail_cause: FailCause::ServicePermanentlyUnavailable.into_raw() as u8,
+            };
+            Self::send(queue, dltime, address, ReselectionPdu::RestoreFail(fail));
+            return None;
+        }
+
+        tracing::info!("U-RESTORE from {}: restoring call", address);
+        self.pending_restores.insert(address.ssi);
+        Some(cmce_sdu)
+    }
+
+    /// Wraps the CMCE answer to a pending U-RESTORE: D-CALL RESTORE is carried in D-RESTORE-ACK,
+    /// a D-RELEASE becomes a D-RESTORE-FAIL. Returns the TL-SDU to send in place of the CMCE PDU,
+    /// or None if the PDU is not such an answer.
+    pub fn restore_response(&mut self, address: TetraAddress, cmce_sdu: &BitBuffer) -> Option<BitBuffer> {
+        if !self.pending_restores.contains(&address.ssi) {
+            return None;
+        }
+        let pdu_type = cmce_sdu.peek_bits(5).and_then(|bits| CmcePduTypeDl::try_from(bits).ok())?;
+        let response = match pdu_type {
+            CmcePduTypeDl::DCallRestore => ReselectionPdu::RestoreAck(DRestoreAck {
+                sdu: Some(cmce_sdu.clone()),
+            }),
+            // The MS CMCE releases the call itself on restoration failure
+            CmcePduTypeDl::DRelease => ReselectionPdu::RestoreFail(DRestoreFail {
+                fail_cause: FailCause::ServicePermanentlyUnavailable.into_raw() as u8,
+            }),
+            _ => return None,
+        };
+        self.pending_restores.remove(&address.ssi);
+        Some(response.to_tl_sdu())
+    }
+
+    fn send(queue: &mut MessageQueue, dltime: TdmaTime, address: TetraAddress, pdu: ReselectionPdu) {
+        let tl_sdu = pdu.to_tl_sdu();
+        tracing::info!("-> {} sdu {}", address, tl_sdu.dump_bin());
+        queue.push_back(SapMsg {
+            sap: Sap::TlaSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Llc,
+            dltime,
+            msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+                main_address: address,
+                link_id: 0,
+                endpoint_id: 0,
+                tl_sdu,
+                stealing_permission: false,
+                subscriber_class: 0,
+                fcs_flag: false,
+                air_interface_encryption: None,
+                stealing_repeats_flag: None,
+                data_class_info: None,
+                req_handle: 0,
+                graceful_degradation: None,
+                chan_alloc: None,
+                tx_reporter: None,
+            }),
+        });
+    }
+}
@@ -1,22 +1,24 @@
 use crate::mle::components::broadcast::MleBroadcast;
 use crate::mle::components::mle_router::MleRouter;
+use crate::mle::components::reselection::MleReselection;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, unimplemented_log};
+use tetra_core::{BitBuffer, Layer2Service, Sap, TdmaTime, Todo, unimplemented_log};
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
 use tetra_saps::lmm::LmmMleUnitdataInd;
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
-use tetra_saps::tla::{TlaTlDataReqBl, TlaTlUnitdataReqBl};
+use tetra_saps::tla::{TlaTlDataIndBl, TlaTlDataReqBl, TlaTlUnitdataReqBl};
 use tetra_saps::{SapMsg, SapMsgInner};
 
-use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
 
 pub struct MleBs {
     config: SharedConfig,
     router: MleRouter,
     broadcast: MleBroadcast,
+    reselection: MleReselection,
 }
 
 /// Multiframe at which D-NWRK-BROADCAST is sent within each hyperframe, 1-60
@@ -28,68 +30,76 @@ const MLE_BROADCAST_FRAME: u8 = 1;
 impl MleBs {
     pub fn new(config: SharedConfig) -> Self {
         let broadcast = MleBroadcast::new(config.clone());
+        let reselection = MleReselection::new(config.clone());
         Self {
             config,
             router: MleRouter::new(),
             broadcast,
+            reselection,
         }
     }
 
-    fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn rx_tla_mle_pdu(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, prim: &TlaTlDataIndBl, sdu: BitBuffer) {
         tracing::trace!("rx_tla_mle_pdu");
 
-        // Extract tm_sdu from whatever primitive we have
-        let tm_sdu = {
-            match message.msg {
-                SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu,
-                _ => {
-                    panic!();
-                }
-            }
-        };
-        let Some(sdu) = tm_sdu else {
-            tracing::debug!("rx_tla_mle_pdu: no tm_sdu");
-            return;
-        };
-
-        // Determine which type of TL-SDU we have and call handler function
+        // Determine which type of MLE PDU we have and call handler function
         let Some(bits) = sdu.peek_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
             return;
         };
-        let Ok(pdu_type) = MlePduTypeDl::try_from(bits) else {
+        let Ok(pdu_type) = MlePduTypeUl::try_from(bits) else {
             tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
             return;
         };
 
         match pdu_type {
-            MlePduTypeDl::DNewCell => {
-                unimplemented_log!("DNewCell")
+            MlePduTypeUl::UPrepare => {
+                self.reselection.rx_u_prepare(queue, dltime, prim.main_address, sdu);
             }
-            MlePduTypeDl::DPrepareFail => {
-                unimplemented_log!("DPrepareFail")
+            MlePduTypeUl::URestore => {
+                if let Some(cmce_sdu) = self.reselection.rx_u_restore(queue, dltime, prim.main_address, sdu) {
+                    self.deliver_to_cmce(queue, dltime, prim, cmce_sdu);
+                }
             }
-            MlePduTypeDl::DNwrkBroadcast => {
-                unimplemented_log!("DNwrkBroadcast")
+            MlePduTypeUl::UPrepareDa => {
+                unimplemented_log!("UPrepareDa")
             }
-            MlePduTypeDl::DNwrkBroadcastExt => {
-                unimplemented_log!("DNwrkBroadcastExt")
-            } // TODO FIXME CHECK this option and assocaited int
-            MlePduTypeDl::DRestoreAck => {
-                unimplemented_log!("DRestoreAck")
+            MlePduTypeUl::UIrregularChannelAdvice => {
+                unimplemented_log!("UIrregularChannelAdvice")
             }
-            MlePduTypeDl::DRestoreFail => {
-                unimplemented_log!("DRestoreFail")
+            MlePduTypeUl::UChannelClassAdvice => {
+                unimplemented_log!("UChannelClassAdvice")
             }
-            MlePduTypeDl::DChannelResponse => {
-                unimplemented_log!("DChannelResponse")
+            MlePduTypeUl::UChannelRequest => {
+                unimplemented_log!("UChannelRequest")
             }
-            MlePduTypeDl::ExtPdu => {
+            MlePduTypeUl::ExtPdu => {
                 unimplemented_log!("ExtPdu")
             }
         }
     }
 
+    fn deliver_to_cmce(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, prim: &TlaTlDataIndBl, sdu: BitBuffer) {
+        let handle = self.router.create_handle(prim.main_address, prim.link_id, prim.endpoint_id, dltime);
+        let m = LcmcMleUnitdataInd {
+            sdu,
+            handle,
+            received_tetra_address: prim.main_address,
+            endpoint_id: prim.endpoint_id,
+            link_id: prim.link_id,
+            chan_change_resp_req: false, // TODO FIXME
+            chan_change_handle: None,    // TODO FIXME
+        };
+        let msg = SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataInd(m),
+        };
+        queue.push_back(msg);
+    }
+
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_prim");
         match message.msg {
@@ -97,8 +107,7 @@ impl MleBs {
                 self.rx_tla_data_ind_bl(queue, message);
             }
             SapMsgInner::TlaTlUnitdataIndBl(_) => {
-                // self.rx_tla_unitdata_ind_bl(queue, message);
-                panic!("BS can't receive TL-UNITDATA");
+                self.rx_tla_unitdata_ind_bl(queue, message);
             }
             _ => {
                 panic!();
@@ -111,7 +120,10 @@ impl MleBs {
         let SapMsgInner::TlaTlDataIndBl(prim) = &mut message.msg else {
             panic!()
         };
-        let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
+        let Some(mut sdu) = prim.tl_sdu.take() else {
+            tracing::debug!("empty TL-SDU from {}, dropping", prim.main_address);
+            return;
+        };
         assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
         let Some(bits) = sdu.read_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
@@ -143,26 +155,7 @@ impl MleBs {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Cmce => {
-                let handle = self
-                    .router
-                    .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-                let m = LcmcMleUnitdataInd {
-                    sdu,
-                    handle,
-                    received_tetra_address: prim.main_address,
-                    endpoint_id: prim.endpoint_id,
-                    link_id: prim.link_id,
-                    chan_change_resp_req: false, // TODO FIXME
-                    chan_change_handle: None,    // TODO FIXME
-                };
-                let msg = SapMsg {
-                    sap: Sap::LcmcSap,
-                    src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
-                    dltime: message.dltime,
-                    msg: SapMsgInner::LcmcMleUnitdataInd(m),
-                };
-                queue.push_back(msg);
+                self.deliver_to_cmce(queue, message.dltime, prim, sdu);
             }
             MleProtocolDiscriminator::Sndcp => {
                 let m = LtpdMleUnitdataInd {
@@ -174,16 +167,16 @@ impl MleBs {
                     chan_change_handle: None,    // TODO FIXME
                 };
                 let msg = SapMsg {
-                    sap: Sap::LcmcSap,
+                    sap: Sap::TlpdSap,
                     src: TetraEntity::Mle,
-                    dest: TetraEntity::Cmce,
+                    dest: TetraEntity::Sndcp,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
                 };
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
-                self.rx_tla_mle_pdu(queue, message);
+                self.rx_tla_mle_pdu(queue, message.dltime, prim, sdu);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
                 unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
@@ -191,108 +184,49 @@ impl MleBs {
         }
     }
 
-    // fn rx_tla_unitdata_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
-    //     // TODO FIXME NOTE: This function is the same as the rx_tla_data_ind_bl.
-    //     // A cursory glance at the spec does not make clear the difference, except for the relation with
-    //     // either udata or data at the llc.
-    //     // It seems only the SNDCP uses unacknowledged TL-UNITDATA.
-    //     // We should investigate the exact differences and account for them
-
-    //     // Take ownership of bitbuf and read protocol discriminator
-    //     let SapMsgInner::TlaTlUnitdataIndBl(prim) = &mut message.msg else {
-    //         panic!()
-    //     };
-    //     let Some(mut sdu) = prim.tl_sdu.take() else { panic!("no tl_sdu") };
-    //     assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
-
-    //     let Some(bits) = sdu.read_bits(3) else {
-    //         tracing::warn!("insufficient bits: {}", sdu.dump_bin());
-    //         return;
-    //     };
-    //     let Ok(pdu_type) = MleProtocolDiscriminator::try_from(bits) else {
-    //         tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
-    //         return;
-    //     };
-
-    //     // Dispatch to appropriate component (or to self if for MLE)
-    //     match pdu_type {
-    //         MleProtocolDiscriminator::Mm => {
-    //             tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-    //             let handle = self
-    //                 .router
-    //                 .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-    //             let m = LmmMleUnitdataInd {
-    //                 sdu,
-    //                 handle,
-    //                 received_address: prim.main_address,
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LmmSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Mm,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LmmMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Cmce => {
-    //             tracing::warn!("TM-UNITDATA for MM?"); // todo fixme find if ever used
-    //             let handle = self
-    //                 .router
-    //                 .create_handle(prim.main_address, prim.link_id, prim.endpoint_id, message.dltime);
-    //             let m = LcmcMleUnitdataInd {
-    //                 sdu,
-    //                 handle,
-    //                 endpoint_id: prim.endpoint_id,
-    //                 link_id: prim.link_id,
-    //                 received_tetra_address: prim.main_address,
-    //                 chan_change_resp_req: false, // TODO FIXME
-    //                 chan_change_handle: None,    // TODO FIXME
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LcmcSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Cmce,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LcmcMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Sndcp => {
-    //             let m = LtpdMleUnitdataInd {
-    //                 sdu,
-    //                 endpoint_id: prim.endpoint_id,
-    //                 link_id: prim.link_id,
-    //                 received_tetra_address: prim.main_address,
-    //                 chan_change_resp_req: false, // TODO FIXME
-    //                 chan_change_handle: None,    // TODO FIXME
-    //             };
-    //             let msg = SapMsg {
-    //                 sap: Sap::LcmcSap,
-    //                 src: TetraEntity::Mle,
-    //                 dest: TetraEntity::Cmce,
-    //                 dltime: message.dltime,
-    //                 msg: SapMsgInner::LtpdMleUnitdataInd(m),
-    //             };
-    //             queue.push_back(msg);
-    //         }
-    //         MleProtocolDiscriminator::Mle => {
-    //             self.rx_tla_mle_pdu(queue, message);
-    //         }
-    //         MleProtocolDiscriminator::TetraManagementEntity => {
-    //             unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
-    //         }
-    //     }
-    // }
-
-    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tla_unitdata_ind_bl(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        // Only the SNDCP uses the unacknowledged basic link on the uplink
+        let SapMsgInner::TlaTlUnitdataIndBl(prim) = &mut message.msg else {
+            panic!()
+        };
+        let Some(mut sdu) = prim.tl_sdu.take() else {
+            tracing::debug!("empty TL-SDU from {}, dropping", prim.main_address);
+            return;
+        };
+        assert!(sdu.get_pos() == 0); // We should be at the start of the MAC PDU
+        let Some(bits) = sdu.read_bits(3) else {
+            tracing::warn!("insufficient bits: {}", sdu.dump_bin());
+            return;
+        };
+        let Ok(pdu_type) = MleProtocolDiscriminator::try_from(bits) else {
+            tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
+            return;
+        };
+        if pdu_type != MleProtocolDiscriminator::Sndcp {
+            tracing::warn!("TL-UNITDATA for {} from {}, dropping", pdu_type, prim.main_address);
+            return;
+        }
+
+        let m = LtpdMleUnitdataInd {
+            sdu,
+            endpoint_id: prim.endpoint_id,
+            link_id: prim.link_id,
+            received_tetra_address: prim.main_address,
+            chan_change_resp_req: false, // TODO FIXME
+            chan_change_handle: None,    // TODO FIXME
+        };
+        queue.push_back(SapMsg {
+            sap: Sap::TlpdSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Sndcp,
+            dltime: message.dltime,
+            msg: SapMsgInner::LtpdMleUnitdataInd(m),
+        });
+    }
+
+    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmc_prim");
-        unimplemented!("rx_tlmc_prim");
-        // match &message.msg {
-        //     _ => {
-        //         panic!();
-        //     }
-        // }
+        unimplemented_log!("rx_tlmc_prim: {}", message.msg);
     }
 
     fn rx_lmm_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -325,7 +259,7 @@ impl MleBs {
                 stealing_permission: false,
                 subscriber_class: 0, // TODO fixme
                 fcs_flag: false,
-                air_interface_encryption: None,
+                air_interface_encryption: Some(prim.encryption_flag as Todo),
                 stealing_repeats_flag: None,
                 data_class_info: None,
                 req_handle: 0, // TODO FIXME; should we pass the same handle here?
@@ -347,14 +281,80 @@ impl MleBs {
         }
     }
 
-    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_ltpd_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_ltpd_mle_unitdata_req");
+        let SapMsgInner::LtpdMleUnitdataReq(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let mle_prot_discriminator = MleProtocolDiscriminator::Sndcp;
+        let sdu_len = prim.sdu.get_len();
+        let mut pdu = BitBuffer::new(3 + sdu_len);
+        pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
+        pdu.copy_bits(&mut prim.sdu, sdu_len);
+        pdu.seek(0);
+
+        let chan_alloc = prim.chan_alloc.take();
+
+        let sapmsg = if prim.layer2service == Layer2Service::Unacknowledged {
+            SapMsg {
+                sap: Sap::TlaSap,
+                src: TetraEntity::Mle,
+                dest: TetraEntity::Llc,
+                dltime: message.dltime,
+                msg: SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
+                    main_address: prim.main_address,
+                    link_id: prim.link_id,
+                    endpoint_id: prim.endpoint_id,
+                    tl_sdu: pdu,
+                    stealing_permission: prim.stealing_permission,
+                    subscriber_class: 0, // TODO fixme
+                    fcs_flag: prim.fcs_flag,
+                    air_interface_encryption: None,
+                    packet_data_flag: prim.packet_data_flag,
+                    n_tlsdu_repeats: 0,
+                    data_class_info: None,
+                    req_handle: 0,
+                    chan_alloc,
+                    tx_reporter: None,
+                }),
+            }
+        } else {
+            SapMsg {
+                sap: Sap::TlaSap,
+                src: TetraEntity::Mle,
+                dest: TetraEntity::Llc,
+                dltime: message.dltime,
+                msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+                    main_address: prim.main_address,
+                    link_id: prim.link_id,
+                    endpoint_id: prim.endpoint_id,
+                    tl_sdu: pdu,
+                    stealing_permission: prim.stealing_permission,
+                    subscriber_class: 0, // TODO fixme
+                    fcs_flag: prim.fcs_flag,
+                    air_interface_encryption: None,
+                    stealing_repeats_flag: None,
+                    data_class_info: None,
+                    req_handle: 0, // TODO FIXME
+                    graceful_degradation: None,
+                    chan_alloc,
+                    tx_reporter: None,
+                }),
+            }
+        };
+
+        queue.push_back(sapmsg);
+    }
+
+    fn rx_tlpd_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlpd_prim");
-        unimplemented!("rx_tlpd_prim");
-        // match &message.msg {
-        //     _ => {
-        //         panic!();
-        //     }
-        // }
+        match &message.msg {
+            SapMsgInner::LtpdMleUnitdataReq(_) => {
+                self.rx_ltpd_mle_unitdata_req(queue, message);
+            }
+            _ => panic!(),
+        }
     }
 
     fn rx_lcmc_mle_unitdata_req(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -363,12 +363,19 @@ impl MleBs {
             panic!()
         };
 
-        let mle_prot_discriminator = MleProtocolDiscriminator::Cmce;
-        let sdu_len = prim.sdu.get_len();
-        let mut pdu = BitBuffer::new(3 + sdu_len);
-        pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
-        pdu.copy_bits(&mut prim.sdu, sdu_len);
-        pdu.seek(0);
+        // Answers to U-CALL RESTORE are carried in an MLE PDU, other CMCE PDUs go as they are
+        let pdu = match self.reselection.restore_response(prim.main_address, &prim.sdu) {
+            Some(pdu) => pdu,
+            None => {
+                let mle_prot_discriminator = MleProtocolDiscriminator::Cmce;
+                let sdu_len = prim.sdu.get_len();
+                let mut pdu = BitBuffer::new(3 + sdu_len);
+                pdu.write_bits(mle_prot_discriminator.into_raw(), 3);
+                pdu.copy_bits(&mut prim.sdu, sdu_len);
+                pdu.seek(0);
+                pdu
+            }
+        };
 
         // let (_addr, link, endpoint) = self.router.use_handle(prim.handle, message.dltime);
         // assert_eq!(link, prim.link_id);
@@ -447,7 +454,7 @@ impl TetraEntityTrait for MleBs {
     }
 
     fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
-        // Broadcast D-NWRK-BROADCAST once per hyperframe if timezone is configured.
+        // Broadcast D-NWRK-BROADCAST once per hyperframe if timezone or neighbour cells are configured.
         // Use a constant multiframe/frame offset to avoid congestion with other
         // hyperframe-triggered events.
         if ts.m == MLE_BROADCAST_MULTIFRAME && ts.f == MLE_BROADCAST_FRAME && ts.t == 1 {
@@ -1,11 +1,10 @@
 use crate::mle::components::mle_router::MleRouter;
-use crate::mle::components::network_time::encode_tetra_network_time;
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
+use tetra_core::{BitBuffer, Sap, TdmaTime, unimplemented_log};
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
-use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::lmm::{LmmMleActivateConf, LmmMleUnitdataInd};
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
 use tetra_saps::tla::TlaTlDataReqBl;
 use tetra_saps::{SapMsg, SapMsgInner};
@@ -14,11 +13,17 @@ use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
-use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
 
 pub struct MleMs {
     config: SharedConfig,
     router: MleRouter,
+
+    /// Network code of the serving cell, from D-MLE-SYNC
+    cell_mcc_mnc: Option<(u16, u16)>,
+    /// Serving cell information, from D-MLE-SYSINFO
+    cell_sysinfo: Option<DMleSysinfo>,
+    /// Set once the MM was informed that a cell was selected
+    activated: bool,
 }
 
 impl MleMs {
@@ -26,7 +31,46 @@ impl MleMs {
         Self {
             config,
             router: MleRouter::new(),
+            cell_mcc_mnc: None,
+            cell_sysinfo: None,
+            activated: false,
+        }
+    }
+
+    /// Once both SYNC and SYSINFO of the serving cell were received, the MS is camped on it.
+    /// The MM is then informed, so it can register, Clause 18.3.4.
+    fn check_camped(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        if self.activated {
+            return;
         }
+        let (Some((mcc, mnc)), Some(sysinfo)) = (self.cell_mcc_mnc, &self.cell_sysinfo) else {
+            return;
+        };
+
+        tracing::info!(
+            "camped on cell mcc {} mnc {} la {}, registration {}",
+            mcc,
+            mnc,
+            sysinfo.location_area,
+            if sysinfo.bs_service_details.registration {
+                "required"
+            } else {
+                "not required"
+            }
+        );
+        self.activated = true;
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Mm,
+            dltime,
+            msg: SapMsgInner::LmmMleActivateConf(LmmMleActivateConf {
+                registration_required: sysinfo.bs_service_details.registration,
+                la: sysinfo.location_area,
+                cell_type: 0,
+            }),
+        };
+        queue.push_back(msg);
     }
 
     fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
@@ -36,6 +80,7 @@ impl MleMs {
         let tm_sdu = {
             match message.msg {
                 SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu,
+                SapMsgInner::TlaTlUnitdataIndBl(prim) => prim.tl_sdu,
                 _ => {
                     panic!();
                 }
@@ -94,7 +139,7 @@ impl MleMs {
                 self.rx_tla_unitdata_ind_bl(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_tla_prim: {:?} not supported", message.msg);
             }
         }
     }
@@ -128,7 +173,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Mm,
                     dltime: message.dltime,
                     msg: SapMsgInner::LmmMleUnitdataInd(m),
@@ -150,7 +195,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LcmcMleUnitdataInd(m),
@@ -168,7 +213,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
@@ -176,6 +221,7 @@ impl MleMs {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
+                prim.tl_sdu = Some(sdu);
                 self.rx_tla_mle_pdu(queue, message);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
@@ -221,7 +267,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Mm,
                     dltime: message.dltime,
                     msg: SapMsgInner::LmmMleUnitdataInd(m),
@@ -244,7 +290,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LcmcMleUnitdataInd(m),
@@ -262,7 +308,7 @@ impl MleMs {
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
-                    src: self.self_component,
+                    src: TetraEntity::Mle,
                     dest: TetraEntity::Cmce,
                     dltime: message.dltime,
                     msg: SapMsgInner::LtpdMleUnitdataInd(m),
@@ -270,6 +316,7 @@ impl MleMs {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
+                prim.tl_sdu = Some(sdu);
                 self.rx_tla_mle_pdu(queue, message);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
@@ -288,12 +335,12 @@ impl MleMs {
                 self.rx_tlmb_tl_sync_ind(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_tlmb_prim: {:?} not supported", message.msg);
             }
         }
     }
 
-    pub fn rx_tlmb_tl_sysinfo_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tlmb_tl_sysinfo_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sysinfo_ind");
 
         let SapMsgInner::TlmbSysinfoInd(inner) = &mut message.msg else {
@@ -301,7 +348,7 @@ impl MleMs {
         };
 
         // Parse the TL-SDU
-        let _pdu = match DMleSysinfo::from_bitbuf(&mut inner.tl_sdu) {
+        let pdu = match DMleSysinfo::from_bitbuf(&mut inner.tl_sdu) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -312,7 +359,8 @@ impl MleMs {
             }
         };
 
-        unimplemented_log!("rx_tlmb_tl_sysinfo_ind");
+        self.cell_sysinfo = Some(pdu);
+        self.check_camped(queue, message.dltime);
         // let need_global_state_update = {
         //     let cfg = self.config.read();
 
@@ -352,7 +400,7 @@ impl MleMs {
         // }
     }
 
-    pub fn rx_tlmb_tl_sync_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tlmb_tl_sync_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sync_ind");
 
         let SapMsgInner::TlmbSyncInd(inner) = &mut message.msg else {
@@ -360,7 +408,7 @@ impl MleMs {
         };
 
         // Parse the TL-SDU
-        let _pdu = match DMleSync::from_bitbuf(&mut inner.tl_sdu) {
+        let pdu = match DMleSync::from_bitbuf(&mut inner.tl_sdu) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -371,7 +419,8 @@ impl MleMs {
             }
         };
 
-        unimplemented_log!("rx_tlmb_tl_sync_ind");
+        self.cell_mcc_mnc = Some((pdu.mcc, pdu.mnc));
+        self.check_camped(queue, message.dltime);
 
         // let need_global_state_update = {
         //     let cfg = self.config.read();
@@ -395,7 +444,7 @@ impl MleMs {
         //     // TODO FIXME: This is ugly. We should pass the message through all the intermediate layers
         //     let m = SapMsg {
         //         sap: Sap::TlmcSap,
-        //         src: self.self_component,
+        //         src: TetraEntity::Mle,
         //         dest: TetraComponent::Umac,
         //         t_submit: message.t_submit,
         //         msg: SapMsgInner::TlmcConfigureReq(
@@ -414,9 +463,9 @@ impl MleMs {
         // }
     }
 
-    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlmc_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmc_prim");
-        unimplemented!("rx_tlmc_prim");
+        unimplemented_log!("rx_tlmc_prim: {:?} not supported", message.msg);
         // match &message.msg {
         //     _ => {
         //         panic!();
@@ -441,7 +490,7 @@ impl MleMs {
         // assert_eq!(addr.ssi, prim.address.ssi);
         let sapmsg = SapMsg {
             sap: Sap::TlaSap,
-            src: self.self_component,
+            src: TetraEntity::Mle,
             dest: TetraEntity::Llc,
             dltime: message.dltime,
             msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
@@ -474,9 +523,9 @@ impl MleMs {
         }
     }
 
-    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlpd_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlpd_prim");
-        unimplemented!("rx_tlpd_prim");
+        unimplemented_log!("rx_tlpd_prim: {:?} not supported", message.msg);
         // match &message.msg {
         //     _ => {
         //         panic!();
@@ -505,7 +554,7 @@ impl MleMs {
 
         let sapmsg = SapMsg {
             sap: Sap::TlaSap,
-            src: self.self_component,
+            src: TetraEntity::Mle,
             dest: TetraEntity::Llc,
             dltime: message.dltime,
             msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
@@ -568,7 +617,7 @@ impl TetraEntityTrait for MleMs {
                 self.rx_lcmc_prim(queue, message);
             }
             _ => {
-                panic!();
+                tracing::warn!("rx_prim: unexpected message on {:?}", message.sap);
             }
         }
     }
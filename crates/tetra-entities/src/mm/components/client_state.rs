@@ -1,9 +1,15 @@
+use tetra_config::bluestation::CfgAccessPolicy;
+use tetra_core::EnergyEconomySchedule;
+
 #[derive(Debug)]
 pub enum ClientMgrErr {
     ClientNotFound { issi: u32 },
     GroupNotFound { gssi: u32 },
     IssiInGroupRange { issi: u32 },
     GssiInClientRange { gssi: u32 },
+    IssiNotAllowed { issi: u32 },
+    AttachNotAllowed { issi: u32, gssi: u32 },
+    TooManyGroups { issi: u32 },
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -17,6 +23,8 @@ pub struct MmClientProperties {
     pub ssi: u32,
     pub state: MmClientState,
     pub groups: std::collections::HashSet<u32>,
+    /// Wake schedule agreed with the MS, None if it stays alive
+    pub energy_economy: Option<EnergyEconomySchedule>,
     // pub last_seen: TdmaTime,
 }
 
@@ -26,37 +34,39 @@ impl MmClientProperties {
             ssi,
             state: MmClientState::Unknown,
             groups: std::collections::HashSet::new(),
+            energy_economy: None,
             // last_seen: TdmaTime::default(),
         }
     }
 }
 
-/// Stub function, to be replaced with checks based on configuration file
-fn is_individual(_issi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn in_group_range(_gssi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn is_group(_gssi: u32) -> bool {
-    return true;
-}
-/// Stub function, to be replaced with checks based on configuration file
-fn may_attach(_issi: u32, _gssi: u32) -> bool {
-    return true;
-}
-
 pub struct MmClientMgr {
     clients: std::collections::HashMap<u32, MmClientProperties>,
+    /// Restrictions on registration and group attachment
+    policy: CfgAccessPolicy,
 }
 
 impl MmClientMgr {
-    pub fn new() -> Self {
+    pub fn new(policy: CfgAccessPolicy) -> Self {
         MmClientMgr {
             clients: std::collections::HashMap::new(),
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> &CfgAccessPolicy {
+        &self.policy
+    }
+
+    /// Checks whether the access policy lets an ISSI register
+    pub fn may_register(&self, issi: u32) -> Result<(), ClientMgrErr> {
+        if !self.policy.is_individual(issi) {
+            return Err(ClientMgrErr::IssiInGroupRange { issi });
+        }
+        if !self.policy.issi_allowed(issi) {
+            return Err(ClientMgrErr::IssiNotAllowed { issi });
         }
+        Ok(())
     }
 
     pub fn get_client_by_issi(&mut self, issi: u32) -> Option<&MmClientProperties> {
@@ -76,12 +86,19 @@ impl MmClientMgr {
         }
     }
 
+    pub fn set_client_energy_economy(&mut self, issi: u32, schedule: Option<EnergyEconomySchedule>) -> Result<(), ClientMgrErr> {
+        if let Some(client) = self.clients.get_mut(&issi) {
+            client.energy_economy = schedule;
+            Ok(())
+        } else {
+            Err(ClientMgrErr::ClientNotFound { issi })
+        }
+    }
+
     /// Registers a fresh state for a client, based on ssi
     /// If client is already registered, previous state is discarded.
     pub fn try_register_client(&mut self, issi: u32, attached: bool) -> Result<bool, ClientMgrErr> {
-        if !is_individual(issi) {
-            return Err(ClientMgrErr::IssiInGroupRange { issi });
-        };
+        self.may_register(issi)?;
 
         // discard previous state if any
         self.clients.remove(&issi);
@@ -113,18 +130,31 @@ impl MmClientMgr {
         }
     }
 
-    /// Attaches or detaches a client from a group
-    pub fn client_group_attach(&mut self, issi: u32, gssi: u32, do_attach: bool) -> Result<bool, ClientMgrErr> {
-        // Checks
-        if !in_group_range(gssi) {
+    /// Checks whether the access policy lets a registered client attach to a group
+    pub fn may_attach(&self, issi: u32, gssi: u32) -> Result<(), ClientMgrErr> {
+        if !self.policy.is_group(gssi) {
             return Err(ClientMgrErr::GssiInClientRange { gssi });
         };
-        if !is_group(gssi) {
-            return Err(ClientMgrErr::GroupNotFound { gssi });
+        if !self.policy.may_attach(issi, gssi) {
+            return Err(ClientMgrErr::AttachNotAllowed { issi, gssi });
         };
-        if !may_attach(issi, gssi) {
-            return Err(ClientMgrErr::GroupNotFound { gssi });
+        let Some(client) = self.clients.get(&issi) else {
+            return Err(ClientMgrErr::ClientNotFound { issi });
         };
+        if let Some(max) = self.policy.max_groups_per_ms
+            && !client.groups.contains(&gssi)
+            && client.groups.len() >= max
+        {
+            return Err(ClientMgrErr::TooManyGroups { issi });
+        }
+        Ok(())
+    }
+
+    /// Attaches or detaches a client from a group. Detaching is always allowed.
+    pub fn client_group_attach(&mut self, issi: u32, gssi: u32, do_attach: bool) -> Result<bool, ClientMgrErr> {
+        if do_attach {
+            self.may_attach(issi, gssi)?;
+        }
 
         if let Some(client) = self.clients.get_mut(&issi) {
             if do_attach {
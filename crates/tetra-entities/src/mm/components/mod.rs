@@ -1,2 +1,3 @@
 pub mod client_state;
 pub mod not_supported;
+pub mod taa1;
@@ -0,0 +1,117 @@
+//! Authentication algorithms of the TAA1 set (EN 300 392-7 clause 4.2).
+//!
+//! The real TAA1 algorithms are only available under NDA, so they are plugged
+//! in through the Taa1 trait. TestTaa1 is a stand-in for testing and interoperating
+//! with our own MS stack; it provides no security whatsoever.
+
+/// DCK and the derived key streams are 80 bits
+const KEY_80_MASK: u128 = (1 << 80) - 1;
+
+/// Authentication algorithm set. K and session keys KS/KS' are 128 bits,
+/// RS, RAND1, RAND2, DCK1, DCK2 and DCK are 80 bits, RES1 and RES2 are 32 bits.
+pub trait Taa1: Send {
+    /// Session key KS for authenticating the MS, from K and RS
+    fn ta11(&self, k: u128, rs: u128) -> u128;
+    /// RES1 and DCK1 from KS and RAND1
+    fn ta12(&self, ks: u128, rand1: u128) -> (u32, u128);
+    /// Session key KS' for authenticating the SwMI, from K and RS
+    fn ta21(&self, k: u128, rs: u128) -> u128;
+    /// RES2 and DCK2 from KS' and RAND2
+    fn ta22(&self, ks_prime: u128, rand2: u128) -> (u32, u128);
+    /// DCK from DCK1 and DCK2. Without mutual authentication, DCK2 is zero.
+    fn tb4(&self, dck1: u128, dck2: u128) -> u128;
+}
+
+/// Deterministic, insecure stand-in for TAA1. Both ends of a link need to use it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TestTaa1;
+
+impl TestTaa1 {
+    /// splitmix64 finalizer
+    fn mix64(mut x: u64) -> u64 {
+        x = x.wrapping_add(0x9e3779b97f4a7c15);
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+        x ^ (x >> 31)
+    }
+
+    /// Mixes two 128-bit values into a 128-bit output, separated per algorithm by domain
+    fn mix(domain: u64, a: u128, b: u128) -> u128 {
+        let mut h = Self::mix64(domain);
+        for word in [a as u64, (a >> 64) as u64, b as u64, (b >> 64) as u64] {
+            h = Self::mix64(h ^ word);
+        }
+        let low = Self::mix64(h ^ 1);
+        let high = Self::mix64(h ^ 2);
+        ((high as u128) << 64) | low as u128
+    }
+}
+
+impl Taa1 for TestTaa1 {
+    fn ta11(&self, k: u128, rs: u128) -> u128 {
+        Self::mix(11, k, rs)
+    }
+
+    fn ta12(&self, ks: u128, rand1: u128) -> (u32, u128) {
+        let out = Self::mix(12, ks, rand1);
+        ((out >> 96) as u32, out & KEY_80_MASK)
+    }
+
+    fn ta21(&self, k: u128, rs: u128) -> u128 {
+        Self::mix(21, k, rs)
+    }
+
+    fn ta22(&self, ks_prime: u128, rand2: u128) -> (u32, u128) {
+        let out = Self::mix(22, ks_prime, rand2);
+        ((out >> 96) as u32, out & KEY_80_MASK)
+    }
+
+    fn tb4(&self, dck1: u128, dck2: u128) -> u128 {
+        Self::mix(4, dck1, dck2) & KEY_80_MASK
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pinned outputs of TestTaa1. Our MS and BS stacks both rely on these, so they must never change.
+    #[test]
+    fn test_test_taa1_vectors() {
+        let taa1 = TestTaa1;
+        let k = 0x000102030405060708090a0b0c0d0e0f;
+        let rs = 0x0123456789abcdef0123;
+        let rand1 = 0xfedcba9876543210fedc;
+        let rand2 = rand1;
+
+        let ks = taa1.ta11(k, rs);
+        assert_eq!(ks, 0xf2fcede559a744fbdc19b07193dea91a);
+        let (res1, dck1) = taa1.ta12(ks, rand1);
+        assert_eq!(res1, 0xa7725fa9);
+        assert_eq!(dck1, 0x711882f12942c20748d5);
+
+        let ks_prime = taa1.ta21(k, rs);
+        assert_eq!(ks_prime, 0x837eaaa78b146c4a77ef6273636ef232);
+        let (res2, dck2) = taa1.ta22(ks_prime, rand2);
+        assert_eq!(res2, 0x2df8f867);
+        assert_eq!(dck2, 0x0f31ceaa6a6aa76ff916);
+
+        assert_eq!(taa1.tb4(dck1, dck2), 0xce041fd4a0d3ba092195);
+        assert_eq!(taa1.tb4(dck1, 0), 0x74d2ece9e18a45871d57);
+    }
+
+    #[test]
+    fn test_test_taa1_input_sensitivity() {
+        let taa1 = TestTaa1;
+        let k = 0x000102030405060708090a0b0c0d0e0f;
+        let rs = 0x0123456789abcdef0123;
+        let rand1 = 0xfedcba9876543210fedc;
+        let (res1, _) = taa1.ta12(taa1.ta11(k, rs), rand1);
+
+        // Every input affects the response, and the two directions differ
+        assert_ne!(taa1.ta12(taa1.ta11(k ^ 1, rs), rand1).0, res1);
+        assert_ne!(taa1.ta12(taa1.ta11(k, rs ^ 1), rand1).0, res1);
+        assert_ne!(taa1.ta12(taa1.ta11(k, rs), rand1 ^ 1).0, res1);
+        assert_ne!(taa1.ta22(taa1.ta21(k, rs), rand1).0, res1);
+    }
+}
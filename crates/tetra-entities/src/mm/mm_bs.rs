@@ -1,41 +1,348 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
 use crate::{MessageQueue, TetraEntityTrait, brew};
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{AieKeyClass, DisableKind, DisabledList, SecurityClass, SharedConfig, SubscriberJournal};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, assert_warn, unimplemented_log};
+use tetra_core::typed_pdu_fields::Type3FieldGeneric;
+use tetra_core::{
+    BitBuffer, EnergyEconomySchedule, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, assert_warn, multiframes, unimplemented_log,
+};
 use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::mm::{DisableTarget, MmControl};
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
-use crate::mm::components::client_state::{MmClientMgr, MmClientState};
+use crate::mm::components::client_state::{ClientMgrErr, MmClientMgr, MmClientState};
 use crate::mm::components::not_supported::make_ul_mm_pdu_function_not_supported;
+use crate::mm::components::taa1::{Taa1, TestTaa1};
+use tetra_pdus::mm::enums::authentication_sub_type::AuthenticationSubType;
+use tetra_pdus::mm::enums::energy_saving_mode::EnergySavingMode;
+use tetra_pdus::mm::enums::location_update_reject_cause::LocationUpdateRejectCause;
 use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
 use tetra_pdus::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use tetra_pdus::mm::enums::status_downlink::StatusDownlink;
 use tetra_pdus::mm::enums::status_uplink::StatusUplink;
+use tetra_pdus::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use tetra_pdus::mm::fields::ciphering_parameters::{CIPHERING_PARAMETERS_BITS, CipheringParameters};
+use tetra_pdus::mm::fields::energy_saving_information::{ENERGY_SAVING_INFORMATION_BITS, EnergySavingInformation};
 use tetra_pdus::mm::fields::group_identity_attachment::GroupIdentityAttachment;
 use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
 use tetra_pdus::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
 use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity::DAttachDetachGroupIdentity;
 use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_authentication_demand::DAuthenticationDemand;
+use tetra_pdus::mm::pdus::d_authentication_reject::DAuthenticationReject;
+use tetra_pdus::mm::pdus::d_authentication_response::DAuthenticationResponse;
+use tetra_pdus::mm::pdus::d_authentication_result::DAuthenticationResult;
+use tetra_pdus::mm::pdus::d_disable::DDisable;
+use tetra_pdus::mm::pdus::d_enable::DEnable;
 use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
 use tetra_pdus::mm::pdus::d_location_update_command::DLocationUpdateCommand;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::d_mm_status::DMmStatus;
 use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::u_authentication_demand::UAuthenticationDemand;
+use tetra_pdus::mm::pdus::u_authentication_reject::UAuthenticationReject;
+use tetra_pdus::mm::pdus::u_authentication_response::UAuthenticationResponse;
+use tetra_pdus::mm::pdus::u_authentication_result::UAuthenticationResult;
+use tetra_pdus::mm::pdus::u_disable_status::UDisableStatus;
 use tetra_pdus::mm::pdus::u_itsi_detach::UItsiDetach;
 use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
 use tetra_pdus::mm::pdus::u_mm_status::UMmStatus;
 
+/// RS, RAND1 and RAND2 are 80 bits
+const RANDOM_VALUE_MASK: u128 = (1 << 80) - 1;
+
+/// D-AUTHENTICATION REJECT reason: authentication not supported
+const AUTHENTICATION_REJECT_NOT_SUPPORTED: u8 = 0;
+
+/// Interval between subscriber journal snapshots
+const JOURNAL_SNAPSHOT_INTERVAL: i32 = multiframes!(30);
+
+/// Time to wait for the MS to answer during authentication before rejecting its registration
+const AUTHENTICATION_TIMEOUT: i32 = multiframes!(10);
+
+/// Group identity attachment lifetime of SwMI-assigned groups: attachment not needed, it holds until detached
+const ASSIGNED_GROUP_ATTACHMENT_LIFETIME: u8 = 0;
+/// Group identity detachment downlink reason for SwMI-initiated detachment: unknown group identity
+const ASSIGNED_GROUP_DETACHMENT_REASON: u8 = 0;
+/// Time to wait for U-ATTACH/DETACH GROUP IDENTITY ACKNOWLEDGEMENT before repeating a group assignment
+const GROUP_ASSIGNMENT_ACK_TIMEOUT: i32 = multiframes!(5);
+/// Transmissions of a group assignment before giving up
+const GROUP_ASSIGNMENT_MAX_TX: u8 = 3;
+
+/// Progress of a SwMI-initiated authentication
+enum AuthState {
+    /// D-AUTHENTICATION DEMAND sent, waiting for U-AUTHENTICATION RESPONSE
+    AwaitingResponse { rs: u128, xres1: u32, dck1: u128 },
+    /// MS requested mutual authentication and D-AUTHENTICATION RESULT with RES2 was sent,
+    /// waiting for U-AUTHENTICATION RESULT
+    AwaitingResult { dck: u128 },
+}
+
+/// SwMI-initiated authentication of an MS, holding back its registration until completed
+struct AuthSession {
+    state: AuthState,
+    /// Location update to accept once the MS is authenticated
+    demand: ULocationUpdateDemand,
+    handle: u32,
+    /// Time of the last PDU sent to the MS
+    sent: TdmaTime,
+}
+
+/// SwMI-initiated group attachment/detachment, waiting for U-ATTACH/DETACH GROUP IDENTITY ACKNOWLEDGEMENT
+struct GroupAssignment {
+    attach: Vec<u32>,
+    detach: Vec<u32>,
+    /// Time of the last transmission
+    sent: TdmaTime,
+    /// Transmissions so far
+    tx_count: u8,
+}
+
 pub struct MmBs {
     config: SharedConfig,
     pub client_mgr: MmClientMgr,
+    taa1: Box<dyn Taa1>,
+    /// Ongoing SwMI-initiated authentications by ISSI
+    auth_sessions: HashMap<u32, AuthSession>,
+    /// Unacknowledged group assignments by ISSI
+    group_assignments: HashMap<u32, GroupAssignment>,
+    /// On-disk registry snapshot, if configured
+    journal: Option<SubscriberJournal>,
+    /// Registrations are restored from the journal on the first tick
+    journal_restored: bool,
+    /// Time of the last journal snapshot
+    journal_snapshot_time: TdmaTime,
+    /// On-disk list of disabled ISSIs, if configured
+    disabled_list: Option<DisabledList>,
 }
 
 impl MmBs {
+    /// Uses the TestTaa1 stand-in for authentication
     pub fn new(config: SharedConfig) -> Self {
+        Self::with_taa1(config, Box::new(TestTaa1))
+    }
+
+    pub fn with_taa1(config: SharedConfig, taa1: Box<dyn Taa1>) -> Self {
+        let journal = config.config().registry.as_ref().map(SubscriberJournal::new);
+        let policy = config.config().access_policy.clone().unwrap_or_default();
+        let disabled_list = config
+            .config()
+            .registry
+            .as_ref()
+            .and_then(|r| r.disabled_path.as_deref())
+            .map(DisabledList::new);
+        if let Some(list) = &disabled_list {
+            // Starting without the list would let disabled terminals back in, and overwrite it on the next change
+            let entries = list
+                .load()
+                .unwrap_or_else(|e| panic!("failed loading disabled list {}: {}", list.path().display(), e));
+            let mut state = config.state_write();
+            for (issi, kind) in entries {
+                state.subscribers.disable(issi, kind);
+            }
+        }
         Self {
             config,
-            client_mgr: MmClientMgr::new(),
+            client_mgr: MmClientMgr::new(policy),
+            taa1,
+            auth_sessions: HashMap::new(),
+            group_assignments: HashMap::new(),
+            journal,
+            journal_restored: false,
+            journal_snapshot_time: TdmaTime::default(),
+            disabled_list,
+        }
+    }
+
+    /// Restores the registrations from the subscriber journal, so terminals registered before a restart
+    /// are served straight away. CMCE and Brew are told as if the terminals had just registered.
+    fn restore_journal(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        let Some(journal) = &self.journal else {
+            return;
+        };
+        let entries = match journal.load(SystemTime::now()) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("MM: failed loading subscriber journal: {}", e);
+                match journal.set_aside() {
+                    Ok(bad_path) => tracing::warn!("MM: moved subscriber journal to {}, starting afresh", bad_path.display()),
+                    Err(e) => {
+                        // Keep whatever is on disk rather than overwriting it with an empty registry
+                        tracing::warn!("MM: failed moving subscriber journal aside, journaling disabled: {}", e);
+                        self.journal = None;
+                    }
+                }
+                return;
+            }
+        };
+
+        // Only restore terminals using the ciphering the cell currently demands
+        let sck_expected = match self.config.config().security.as_ref().map(|s| s.security_class).unwrap_or_default() {
+            SecurityClass::Class1 => Some(false),
+            SecurityClass::Class2 => Some(true),
+            SecurityClass::Class3 => None,
+        };
+
+        let mut restored = 0;
+        for entry in entries {
+            let issi = entry.issi;
+            if sck_expected != Some(entry.sck) {
+                tracing::debug!("MM: not restoring MS {} with outdated ciphering", issi);
+                continue;
+            }
+            if let Err(cause) = self.check_access(issi) {
+                tracing::info!("MM: not restoring MS {} refused by the access policy: {}", issi, cause);
+                continue;
+            }
+            if let Err(e) = self.client_mgr.try_register_client(issi, true) {
+                tracing::warn!("MM: failed restoring MS {}: {:?}", issi, e);
+                continue;
+            }
+            let mut groups = Vec::new();
+            {
+                let mut state = self.config.state_write();
+                state.subscribers.register(issi);
+                let subscriber = state.subscribers.get_subscriber_mut(issi);
+                subscriber.last_seen = entry.last_seen_time();
+                subscriber.aie = entry.sck.then_some(AieKeyClass::Sck);
+                for gssi in entry.groups {
+                    if self.client_mgr.client_group_attach(issi, gssi, true).is_ok() {
+                        state.subscribers.affiliate(issi, gssi);
+                        groups.push(gssi);
+                    }
+                }
+            }
+            self.emit_subscriber_update(queue, dltime, issi, Vec::new(), BrewSubscriberAction::Register);
+            if !groups.is_empty() {
+                self.emit_subscriber_update(queue, dltime, issi, groups, BrewSubscriberAction::Affiliate);
+            }
+            restored += 1;
+        }
+        tracing::info!("MM: restored {} subscribers from journal", restored);
+    }
+
+    /// Writes the subscriber registry to the journal if it changed
+    fn snapshot_journal(&mut self) {
+        if !self.journal_restored {
+            // Never overwrite the journal before it was loaded
+            return;
+        }
+        let Some(journal) = &mut self.journal else {
+            return;
+        };
+        if let Err(e) = journal.snapshot(&self.config.state_read().subscribers) {
+            tracing::warn!("MM: failed writing subscriber journal: {}", e);
+        }
+    }
+
+    /// Authentication key K of the MS, and whether registration requires one
+    fn auth_key(&self, issi: u32) -> (Option<u128>, bool) {
+        match &self.config.config().security {
+            Some(security) => (security.key(issi), security.authentication_required),
+            None => (None, false),
+        }
+    }
+
+    /// Reject cause for an MS the access policy does not allow to register
+    fn access_reject_cause(err: &ClientMgrErr) -> LocationUpdateRejectCause {
+        match err {
+            ClientMgrErr::IssiNotAllowed { .. } => LocationUpdateRejectCause::ItsiUnknown,
+            _ => LocationUpdateRejectCause::IllegalMs,
+        }
+    }
+
+    /// Checks whether the MS is disabled and the access policy for it, returning the reason for rejecting its registration if it may not register.
+    /// An MS with a configured subscriber class must share at least one class with the cell.
+    fn check_access(&self, issi: u32) -> Result<(), LocationUpdateRejectCause> {
+        if self.config.state_read().subscribers.disabled(issi).is_some() {
+            return Err(LocationUpdateRejectCause::IllegalMs);
+        }
+        if let Err(e) = self.client_mgr.may_register(issi) {
+            tracing::debug!("MM: access policy: {:?}", e);
+            return Err(Self::access_reject_cause(&e));
+        }
+        if let Some(class) = self.client_mgr.policy().subscriber_class(issi)
+            && class & self.config.config().cell.subscriber_class == 0
+        {
+            return Err(LocationUpdateRejectCause::LaNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Checks the ciphering requested by the MS against the security class of the cell.
+    /// Returns the agreed ciphering parameters, or the reason for rejecting the registration.
+    fn check_ciphering(&self, issi: u32, pdu: &ULocationUpdateDemand) -> Result<Option<CipheringParameters>, LocationUpdateRejectCause> {
+        let config = self.config.config();
+        let security = config.security.as_ref();
+        let class = security.map(|s| s.security_class).unwrap_or_default();
+
+        if !pdu.cipher_control {
+            return match class {
+                SecurityClass::Class1 => Ok(None),
+                _ => Err(LocationUpdateRejectCause::CipheringRequired),
+            };
+        }
+        let Some(security) = security.filter(|_| class != SecurityClass::Class1) else {
+            return Err(LocationUpdateRejectCause::NoCipherKsg);
+        };
+        let Some(raw) = pdu.ciphering_parameters else {
+            return Err(LocationUpdateRejectCause::MandatoryElementError);
+        };
+
+        let params = CipheringParameters::from_raw(raw);
+        if params.ksg_number != security.ksg_number {
+            return Err(LocationUpdateRejectCause::IdentifiedCipherKsgNotSupported);
+        }
+        match (class, params.security_class_3) {
+            (SecurityClass::Class2, false) if params.sck_number == security.sck_number => Ok(Some(params)),
+            (SecurityClass::Class2, false) => Err(LocationUpdateRejectCause::IdentifiedCipherKeyNotAvailable),
+            // The DCK results from authentication, so a key K is needed
+            (SecurityClass::Class3, true) if security.key(issi).is_some() => Ok(Some(params)),
+            _ => Err(LocationUpdateRejectCause::RequestedCipherKeyTypeNotAvailable),
         }
     }
 
+    /// Queue an MM PDU for transmission to the MS, in clear as used during registration
+    fn send_mm_sdu(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, sdu: BitBuffer) {
+        Self::queue_mm_sdu(queue, dltime, issi, handle, sdu, false);
+    }
+
+    /// Queue an MM PDU for transmission to a registered MS, encrypted if a cipher key was agreed at registration
+    fn send_mm_sdu_ciphered(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, sdu: BitBuffer) {
+        Self::queue_mm_sdu(queue, dltime, issi, handle, sdu, true);
+    }
+
+    fn queue_mm_sdu(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, handle: u32, sdu: BitBuffer, encryption_flag: bool) {
+        let addr = TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Ssi,
+            ssi: issi,
+        };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+                sdu,
+                handle,
+                address: addr,
+                layer2service: Layer2Service::Todo,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                encryption_flag,
+                is_null_pdu: false,
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
+
     fn emit_subscriber_update(
         &self,
         queue: &mut MessageQueue,
@@ -112,20 +419,183 @@ impl MmBs {
         }
 
         let ssi = prim.received_address.ssi;
-        let detached_client = self.client_mgr.remove_client(ssi);
-        if let Some(client) = detached_client {
-            self.config.state_write().subscribers.deregister(ssi);
-            if !client.groups.is_empty() {
-                let groups: Vec<u32> = client.groups.iter().copied().collect();
-                self.emit_subscriber_update(_queue, message.dltime, ssi, groups, BrewSubscriberAction::Deaffiliate);
-            }
-            self.emit_subscriber_update(_queue, message.dltime, ssi, Vec::new(), BrewSubscriberAction::Deregister);
-        } else {
+        if !self.deregister_client(_queue, message.dltime, ssi) {
             tracing::warn!("Received UItsiDetach for unknown client with SSI: {}", ssi);
             // return;
         };
     }
 
+    /// Forget a registered MS and notify Brew and CMCE. Returns false if the MS was not registered.
+    fn deregister_client(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32) -> bool {
+        self.group_assignments.remove(&issi);
+        let Some(client) = self.client_mgr.remove_client(issi) else {
+            return false;
+        };
+        self.config.state_write().subscribers.deregister(issi);
+        if !client.groups.is_empty() {
+            let groups: Vec<u32> = client.groups.iter().copied().collect();
+            self.emit_subscriber_update(queue, dltime, issi, groups, BrewSubscriberAction::Deaffiliate);
+        }
+        self.emit_subscriber_update(queue, dltime, issi, Vec::new(), BrewSubscriberAction::Deregister);
+        true
+    }
+
+    fn rx_mm_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::MmControl(control) = message.msg else { panic!() };
+        match control {
+            MmControl::Deregister { issi } => {
+                if self.deregister_client(queue, message.dltime, issi) {
+                    tracing::info!("MM: deregistered ISSI {} on request", issi);
+                } else {
+                    tracing::warn!("MM: deregistration requested for unknown ISSI {}", issi);
+                }
+            }
+            MmControl::AssignGroups { issi, attach, detach } => {
+                tracing::info!("MM: assigning groups to ISSI {}, attach {:?} detach {:?}", issi, attach, detach);
+                self.assign_groups(queue, message.dltime, issi, attach, detach);
+            }
+            MmControl::Disable { issi, permanent, target } => {
+                let kind = if permanent {
+                    DisableKind::Permanent
+                } else {
+                    DisableKind::Temporary
+                };
+                self.disable_ms(queue, message.dltime, issi, kind, target);
+            }
+            MmControl::Enable { issi, target } => self.enable_ms(queue, message.dltime, issi, target),
+        }
+    }
+
+    /// MNI of the network, as address extension of the subscription to disable or enable
+    fn mni(&self) -> u64 {
+        let net = &self.config.config().net;
+        ((net.mcc as u64) << 14) | net.mnc as u64
+    }
+
+    /// Disables the subscription and/or equipment of an MS with D-DISABLE and deregisters it.
+    /// A disabled subscription is refused until enabled again, also after a restart if the disabled list
+    /// is configured. The BS does not learn the TEI of registering terminals, so a disabled equipment is
+    /// only signalled.
+    fn disable_ms(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, kind: DisableKind, target: DisableTarget) {
+        tracing::info!("MM: disabling ISSI {} ({:?}, {:?})", issi, kind, target);
+        if target.subscription() {
+            self.config.state_write().subscribers.disable(issi, kind);
+            self.save_disabled_list();
+        }
+
+        let registered = self.client_mgr.client_is_known(issi);
+        self.send_disable(queue, dltime, issi, kind, target, registered);
+        self.deregister_client(queue, dltime, issi);
+    }
+
+    /// Lifts the disabling of the subscription and/or equipment of an MS and sends it D-ENABLE.
+    /// A permanently disabled MS ignores D-ENABLE, so it is only allowed to register again.
+    fn enable_ms(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, target: DisableTarget) {
+        if target.subscription() {
+            match self.config.state_write().subscribers.enable(issi) {
+                Some(DisableKind::Temporary) => tracing::info!("MM: enabling ISSI {}", issi),
+                Some(DisableKind::Permanent) => {
+                    tracing::warn!("MM: ISSI {} was disabled permanently, it has to be enabled locally at the MS", issi)
+                }
+                None => tracing::warn!("MM: enabling ISSI {} which is not disabled", issi),
+            }
+            self.save_disabled_list();
+        }
+        if let Some(tei) = target.tei() {
+            tracing::info!("MM: enabling equipment {} of ISSI {}", tei, issi);
+        }
+
+        let pdu = DEnable {
+            intent_confirm: true,
+            equipment_enable: target.tei().is_some(),
+            tetra_equipment_identity: target.tei(),
+            subscription_enable: target.subscription(),
+            address_extension: target.subscription().then(|| self.mni()),
+            ssi: target.subscription().then_some(issi as u64),
+            authentication_challenge: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_sdu(queue, dltime, issi, 0, sdu);
+    }
+
+    /// Sends D-DISABLE for the subscription and/or equipment of the MS, encrypted if it is registered
+    fn send_disable(
+        &self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        kind: DisableKind,
+        target: DisableTarget,
+        registered: bool,
+    ) {
+        let pdu = DDisable {
+            intent_confirm: true,
+            disabling_type: kind == DisableKind::Permanent,
+            equipment_disable: target.tei().is_some(),
+            tetra_equipment_identity: target.tei(),
+            subscription_disable: target.subscription(),
+            address_extension: target.subscription().then(|| self.mni()),
+            ssi: target.subscription().then_some(issi as u64),
+            authentication_challenge: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::queue_mm_sdu(queue, dltime, issi, 0, sdu, registered);
+    }
+
+    /// Writes the disabled ISSIs to the disabled list, if configured
+    fn save_disabled_list(&self) {
+        let Some(list) = &self.disabled_list else {
+            return;
+        };
+        if let Err(e) = list.save(&self.config.state_read().subscribers) {
+            tracing::warn!("MM: failed writing disabled list: {}", e);
+        }
+    }
+
+    fn rx_u_disable_status(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_disable_status");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UDisableStatus::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UDisableStatus: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        if pdu.enable_disable_result != 0 {
+            tracing::warn!(
+                "MM: MS {} failed enabling/disabling with result {}, subscription status {} equipment status {}",
+                issi,
+                pdu.enable_disable_result,
+                pdu.subscription_status,
+                pdu.equipment_status
+            );
+        } else {
+            tracing::info!(
+                "MM: MS {} reports subscription status {} equipment status {}",
+                issi,
+                pdu.subscription_status,
+                pdu.equipment_status
+            );
+        }
+    }
+
     fn rx_u_location_update_demand(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_location_update_demand");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
@@ -149,36 +619,65 @@ impl MmBs {
             return;
         }
 
-        // Handle Energy Saving Mode request
-        // TODO FIXME this does not yet seem to be functional, and prevents the MS from remaining
-        // properly registered.
-        // let esi = if let Some(esm) = pdu.energy_saving_mode {
-        //     if esm != EnergySavingMode::StayAlive {
-        //         unimplemented_log!("Got req for EnergySavingMode {}, overriding with {}", esm, EnergySavingMode::StayAlive);
-        //     }
-        //     Some(EnergySavingInformation {
-        //         energy_saving_mode: EnergySavingMode::StayAlive,
-        //         frame_number: None,
-        //         multiframe_number: None,
-        //     })
-        // } else {
-        //     None
-        // };
-        let esi = None;
-
-        // Try to register the client
         let issi = prim.received_address.ssi;
         let handle = prim.handle;
+        if let Some(kind) = self.config.state_read().subscribers.disabled(issi) {
+            // The MS may have missed the D-DISABLE, so repeat it
+            tracing::warn!("Rejecting registration of disabled MS {}", issi);
+            self.send_disable(queue, message.dltime, issi, kind, DisableTarget::Subscription, false);
+        }
+        if let Err(cause) = self.check_access(issi) {
+            tracing::warn!("Rejecting registration of MS {} refused by the access policy: {}", issi, cause);
+            self.reject_location_update(queue, message.dltime, issi, handle, pdu.location_update_type, cause);
+            return;
+        }
+        if let Err(cause) = self.check_ciphering(issi, &pdu) {
+            tracing::warn!("Rejecting registration of MS {} with unsuitable ciphering: {}", issi, cause);
+            self.reject_location_update(queue, message.dltime, issi, handle, pdu.location_update_type, cause);
+            return;
+        }
+        match self.auth_key(issi) {
+            (Some(k), _) => {
+                // Registration is completed once the MS has proven to hold K
+                self.start_authentication(queue, message.dltime, issi, handle, k, pdu);
+            }
+            (None, true) => {
+                tracing::warn!("Rejecting registration of MS {} without provisioned authentication key", issi);
+                self.reject_location_update(
+                    queue,
+                    message.dltime,
+                    issi,
+                    handle,
+                    pdu.location_update_type,
+                    LocationUpdateRejectCause::ItsiUnknown,
+                );
+            }
+            (None, false) => self.accept_location_update(queue, message.dltime, issi, handle, pdu, None),
+        }
+    }
+
+    /// Register the MS and send D-LOCATION UPDATE ACCEPT. If the MS was authenticated, dck is the derived cipher key.
+    fn accept_location_update(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        pdu: ULocationUpdateDemand,
+        dck: Option<u128>,
+    ) {
+        // Try to register the client
         let is_new = !self.client_mgr.client_is_known(issi);
         if is_new {
             match self.client_mgr.try_register_client(issi, true) {
                 Ok(_) => {
                     self.config.state_write().subscribers.register(issi);
-                    self.emit_subscriber_update(queue, message.dltime, issi, Vec::new(), BrewSubscriberAction::Register);
+                    self.emit_subscriber_update(queue, dltime, issi, Vec::new(), BrewSubscriberAction::Register);
                 }
                 Err(e) => {
                     tracing::warn!("Failed registering roaming MS {}: {:?}", issi, e);
-                    // unimplemented_log!("Handle failed registration of roaming MS");
+                    let cause = Self::access_reject_cause(&e);
+                    self.reject_location_update(queue, dltime, issi, handle, pdu.location_update_type, cause);
                     return;
                 }
             }
@@ -187,16 +686,35 @@ impl MmBs {
             return;
         }
 
+        // Grant the requested energy saving mode. The MS expects it acknowledged whenever it asked for one.
+        let esi = pdu.energy_saving_mode.map(|mode| self.set_energy_saving_mode(dltime, issi, mode));
+
+        // Ciphering was checked against our security class when the demand arrived.
+        // The MS switches to encryption once it has received the accept, which is still sent in clear.
+        let ciphering = pdu.ciphering_parameters.map(CipheringParameters::from_raw);
+        let aie = match (ciphering, dck) {
+            (Some(params), Some(dck)) if params.security_class_3 => Some(AieKeyClass::Dck(dck)),
+            (Some(params), _) if !params.security_class_3 => Some(AieKeyClass::Sck),
+            _ => None,
+        };
+        self.config.state_write().subscribers.get_subscriber_mut(issi).aie = aie;
+        let security_downlink = ciphering.map(|params| Type3FieldGeneric {
+            field_id: MmType34ElemIdDl::SecurityDownlink.into_raw(),
+            len: CIPHERING_PARAMETERS_BITS,
+            data: params.into_raw(),
+        });
+
         // Process optional GroupIdentityLocationDemand field
         let gila = if let Some(gild) = pdu.group_identity_location_demand {
             // Try to attach to requested groups, then build GroupIdentityLocationAccept element
-            let accepted_groups = if let Some(giu) = &gild.group_identity_uplink {
-                Some(self.try_attach_detach_groups(queue, message.dltime, issi, &giu))
+            let (accepted_groups, any_rejected) = if let Some(giu) = &gild.group_identity_uplink {
+                let (accepted, any_rejected) = self.try_attach_detach_groups(queue, dltime, issi, giu);
+                (Some(accepted), any_rejected)
             } else {
-                None
+                (None, false)
             };
             let gila = GroupIdentityLocationAccept {
-                group_identity_accept_reject: 0, // Accept
+                group_identity_accept_reject: any_rejected as u8, // 1 if at least one group was rejected
                 group_identity_downlink: accepted_groups,
             };
 
@@ -211,11 +729,11 @@ impl MmBs {
             location_update_accept_type: pdu.location_update_type, // Practically identical besides minor migration-related difference
             ssi: Some(issi as u64),
             address_extension: None,
-            subscriber_class: None,
+            subscriber_class: self.client_mgr.policy().subscriber_class(issi).map(|class| class as u64),
             energy_saving_information: esi,
             scch_information_and_distribution_on_18th_frame: None,
             new_registered_area: None,
-            security_downlink: None,
+            security_downlink,
             group_identity_location_accept: gila,
             default_group_attachment_lifetime: None,
             authentication_downlink: None,
@@ -230,37 +748,397 @@ impl MmBs {
         pdu_response.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
         sdu.seek(0);
         tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
-
-        // Build and submit response prim
-        let addr = TetraAddress {
-            encrypted: false,
-            ssi_type: SsiType::Ssi,
-            ssi: issi,
-        };
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            dltime: message.dltime,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
-                sdu,
-                handle: prim.handle,
-                address: addr,
-                layer2service: Layer2Service::Todo,
-                stealing_permission: false,
-                stealing_repeats_flag: false,
-                encryption_flag: false,
-                is_null_pdu: false,
-                tx_reporter: None,
-            }),
-        };
-        queue.push_back(msg);
+        Self::send_mm_sdu(queue, dltime, issi, handle, sdu);
 
         // If this is an unknown returning radio (not ITSI attach), force it to
         // re-register with full group report via D-LOCATION UPDATE COMMAND
         if is_new && pdu.location_update_type != LocationUpdateType::ItsiAttach {
             tracing::info!("Sending D-LOCATION UPDATE COMMAND to returning MS {} to request group report", issi);
-            Self::send_d_location_update_command(queue, message.dltime, issi, handle);
+            Self::send_d_location_update_command(queue, dltime, issi, handle);
+        }
+
+        self.assign_static_groups(queue, dltime, issi);
+    }
+
+    /// Agrees an energy saving mode with a registered MS, starting its wake schedule with the next frame.
+    /// The schedule is shared with the MAC, which holds back signalling to the MS until it is awake.
+    fn set_energy_saving_mode(&mut self, dltime: TdmaTime, issi: u32, mode: EnergySavingMode) -> EnergySavingInformation {
+        let schedule = EnergyEconomySchedule::starting_after(mode.into_raw() as u8, dltime);
+        if let Err(e) = self.client_mgr.set_client_energy_economy(issi, schedule) {
+            tracing::warn!("Failed setting energy saving mode of MS {}: {:?}", issi, e);
+        }
+        self.config.state_write().subscribers.get_subscriber_mut(issi).energy_economy = schedule;
+
+        match schedule {
+            Some(s) => {
+                tracing::info!(
+                    "MS {} in energy saving mode {}, awake from frame {} multiframe {}",
+                    issi,
+                    mode,
+                    s.start_frame,
+                    s.start_multiframe
+                );
+                EnergySavingInformation {
+                    energy_saving_mode: mode,
+                    frame_number: Some(s.start_frame),
+                    multiframe_number: Some(s.start_multiframe),
+                }
+            }
+            None => {
+                tracing::info!("MS {} stays alive", issi);
+                EnergySavingInformation {
+                    energy_saving_mode: EnergySavingMode::StayAlive,
+                    frame_number: None,
+                    multiframe_number: None,
+                }
+            }
+        }
+    }
+
+    /// Send D-LOCATION UPDATE REJECT. An MS that was registered before is deregistered.
+    fn reject_location_update(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        location_update_type: LocationUpdateType,
+        cause: LocationUpdateRejectCause,
+    ) {
+        self.deregister_client(queue, dltime, issi);
+
+        let pdu = DLocationUpdateReject {
+            location_update_type: location_update_type.into_raw() as u8,
+            reject_cause: cause.into_raw() as u8,
+            cipher_control: false,
+            ciphering_parameters: None,
+            address_extension: None,
+            cell_type_control: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_sdu(queue, dltime, issi, handle, sdu);
+    }
+
+    /// Challenge the MS with D-AUTHENTICATION DEMAND, holding back its location update until it responds
+    fn start_authentication(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        k: u128,
+        demand: ULocationUpdateDemand,
+    ) {
+        let rs = rand::random::<u128>() & RANDOM_VALUE_MASK;
+        let rand1 = rand::random::<u128>() & RANDOM_VALUE_MASK;
+        let ks = self.taa1.ta11(k, rs);
+        let (xres1, dck1) = self.taa1.ta12(ks, rand1);
+
+        let pdu = DAuthenticationDemand {
+            random_challenge: rand1,
+            random_seed: rs,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(4 + 2 + 80 + 80 + 1);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::info!("Authenticating MS {}", issi);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_mm_sdu(queue, dltime, issi, handle, sdu);
+
+        // Any earlier unfinished authentication is superseded
+        let session = AuthSession {
+            state: AuthState::AwaitingResponse { rs, xres1, dck1 },
+            demand,
+            handle,
+            sent: dltime,
+        };
+        self.auth_sessions.insert(issi, session);
+    }
+
+    fn rx_u_authentication(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+
+        // Sub-type follows the 4-bit PDU type
+        let Some(bits) = prim.sdu.peek_bits(6) else {
+            tracing::warn!("insufficient bits: {}", prim.sdu.dump_bin());
+            return;
+        };
+        let Ok(sub_type) = AuthenticationSubType::try_from(bits & 0x3) else {
+            // Cannot fail, all 2-bit values are defined
+            return;
+        };
+
+        match sub_type {
+            AuthenticationSubType::Demand => self.rx_u_authentication_demand(queue, message),
+            AuthenticationSubType::Response => self.rx_u_authentication_response(queue, message),
+            AuthenticationSubType::Result => self.rx_u_authentication_result(queue, message),
+            AuthenticationSubType::Reject => self.rx_u_authentication_reject(queue, message),
+        }
+    }
+
+    fn rx_u_authentication_response(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication_response");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UAuthenticationResponse::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UAuthenticationResponse: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        let Some(mut session) = self.auth_sessions.remove(&issi) else {
+            tracing::warn!("Received UAuthenticationResponse from MS {} without ongoing authentication", issi);
+            return;
+        };
+        let AuthState::AwaitingResponse { rs, xres1, dck1 } = session.state else {
+            tracing::warn!("Received unexpected UAuthenticationResponse from MS {}", issi);
+            return;
+        };
+
+        let r1 = pdu.response_value == xres1;
+        let (res2, dck2) = match (r1, pdu.random_challenge) {
+            // MS wants the SwMI to authenticate itself as well, using the same RS
+            (true, Some(rand2)) => {
+                let (k, _) = self.auth_key(issi);
+                let Some(k) = k else {
+                    tracing::warn!("Authentication key for MS {} removed during authentication", issi);
+                    return;
+                };
+                let ks_prime = self.taa1.ta21(k, rs);
+                let (res2, dck2) = self.taa1.ta22(ks_prime, rand2);
+                (Some(res2), dck2)
+            }
+            _ => (None, 0),
+        };
+
+        let pdu_response = DAuthenticationResult {
+            authentication_result: r1,
+            mutual_authentication_flag: res2.is_some(),
+            response_value: res2,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(4 + 2 + 1 + 1 + 32 + 1);
+        pdu_response.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+        Self::send_mm_sdu(queue, message.dltime, issi, prim.handle, sdu);
+
+        if !r1 {
+            tracing::warn!("Authentication of MS {} failed", issi);
+            let location_update_type = session.demand.location_update_type;
+            self.reject_location_update(
+                queue,
+                message.dltime,
+                issi,
+                prim.handle,
+                location_update_type,
+                LocationUpdateRejectCause::AuthenticationFailure,
+            );
+            return;
+        }
+
+        let dck = self.taa1.tb4(dck1, dck2);
+        if res2.is_some() {
+            // Registration completes once the MS has accepted our response
+            session.state = AuthState::AwaitingResult { dck };
+            session.sent = message.dltime;
+            self.auth_sessions.insert(issi, session);
+        } else {
+            tracing::info!("Authenticated MS {}", issi);
+            self.accept_location_update(queue, message.dltime, issi, session.handle, session.demand, Some(dck));
+        }
+    }
+
+    fn rx_u_authentication_result(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication_result");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UAuthenticationResult::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UAuthenticationResult: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        if pdu.mutual_authentication_flag {
+            unimplemented_log!("Mutual authentication in UAuthenticationResult from MS {}", issi);
+        }
+
+        // Either concludes a mutual SwMI-initiated authentication, or an MS-initiated one
+        let session = match self.auth_sessions.remove(&issi) {
+            Some(AuthSession {
+                state: AuthState::AwaitingResult { dck },
+                demand,
+                handle,
+                ..
+            }) => Some((dck, demand, handle)),
+            Some(session) => {
+                self.auth_sessions.insert(issi, session);
+                None
+            }
+            None => None,
+        };
+        let Some((dck, demand, handle)) = session else {
+            if pdu.authentication_result {
+                tracing::info!("MS {} accepted our authentication", issi);
+            } else {
+                tracing::warn!("MS {} rejected our authentication", issi);
+            }
+            return;
+        };
+
+        if pdu.authentication_result {
+            tracing::info!("Mutually authenticated MS {}", issi);
+            self.accept_location_update(queue, message.dltime, issi, handle, demand, Some(dck));
+        } else {
+            // The MS does not trust us and will not expect a registration response
+            tracing::warn!("MS {} rejected our authentication, registration abandoned", issi);
+        }
+    }
+
+    fn rx_u_authentication_demand(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication_demand");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UAuthenticationDemand::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UAuthenticationDemand: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        let (k, _) = self.auth_key(issi);
+        let Some(k) = k else {
+            tracing::warn!("MS {} requested authentication, but has no provisioned key", issi);
+            let pdu_response = DAuthenticationReject {
+                authentication_reject_reason: AUTHENTICATION_REJECT_NOT_SUPPORTED,
+                proprietary: None,
+            };
+            let mut sdu = BitBuffer::new_autoexpand(4 + 2 + 3 + 1);
+            pdu_response.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+            sdu.seek(0);
+            tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+            Self::send_mm_sdu(queue, message.dltime, issi, prim.handle, sdu);
+            return;
+        };
+
+        let rs = rand::random::<u128>() & RANDOM_VALUE_MASK;
+        let ks_prime = self.taa1.ta21(k, rs);
+        let (res2, _dck2) = self.taa1.ta22(ks_prime, pdu.random_challenge);
+
+        let pdu_response = DAuthenticationResponse {
+            random_seed: rs,
+            response_value: res2,
+            mutual_authentication_flag: false,
+            random_challenge: None,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(4 + 2 + 80 + 32 + 1 + 1);
+        pdu_response.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::info!("Authenticating to MS {}", issi);
+        tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+        Self::send_mm_sdu(queue, message.dltime, issi, prim.handle, sdu);
+    }
+
+    fn rx_u_authentication_reject(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication_reject");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UAuthenticationReject::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UAuthenticationReject: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        let Some(session) = self.auth_sessions.remove(&issi) else {
+            tracing::warn!("Received UAuthenticationReject from MS {} without ongoing authentication", issi);
+            return;
+        };
+
+        let (_, required) = self.auth_key(issi);
+        if required {
+            tracing::warn!(
+                "MS {} refused authentication (reason {}), rejecting registration",
+                issi,
+                pdu.authentication_reject_reason
+            );
+            let location_update_type = session.demand.location_update_type;
+            self.reject_location_update(
+                queue,
+                message.dltime,
+                issi,
+                session.handle,
+                location_update_type,
+                LocationUpdateRejectCause::AuthenticationFailure,
+            );
+        } else {
+            tracing::info!(
+                "MS {} refused authentication (reason {}), registering without",
+                issi,
+                pdu.authentication_reject_reason
+            );
+            self.accept_location_update(queue, message.dltime, issi, session.handle, session.demand, None);
+        }
+    }
+
+    /// Rejects the registration of MSs that stopped answering during authentication
+    fn check_auth_sessions(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        let expired: Vec<u32> = self
+            .auth_sessions
+            .iter()
+            .filter(|(_, session)| session.sent.age(ts) >= AUTHENTICATION_TIMEOUT)
+            .map(|(&issi, _)| issi)
+            .collect();
+        for issi in expired {
+            let session = self.auth_sessions.remove(&issi).unwrap(); // Never fails
+            tracing::warn!("MS {} did not complete authentication in time, rejecting registration", issi);
+            self.reject_location_update(
+                queue,
+                ts,
+                issi,
+                session.handle,
+                session.demand.location_update_type,
+                LocationUpdateRejectCause::AuthenticationFailure,
+            );
         }
     }
 
@@ -281,10 +1159,13 @@ impl MmBs {
             }
         };
 
-        let handled = false; // Set to true for properly handled U-MM STATUS messages
+        let mut handled = false; // Set to true for properly handled U-MM STATUS messages
         match pdu.status_uplink {
-            StatusUplink::ChangeOfEnergySavingModeRequest
-            | StatusUplink::ChangeOfEnergySavingModeResponse
+            StatusUplink::ChangeOfEnergySavingModeRequest => {
+                handled =
+                    self.rx_u_change_of_energy_saving_mode_request(queue, message.dltime, prim.received_address.ssi, prim.handle, &pdu);
+            }
+            StatusUplink::ChangeOfEnergySavingModeResponse
             | StatusUplink::DualWatchModeRequest
             | StatusUplink::TerminatingDualWatchModeRequest
             | StatusUplink::ChangeOfDualWatchModeResponse
@@ -321,6 +1202,49 @@ impl MmBs {
         }
     }
 
+    /// Handles U-CHANGE OF ENERGY SAVING MODE REQUEST, answered with D-CHANGE OF ENERGY SAVING MODE RESPONSE.
+    /// Returns false if the request could not be handled.
+    fn rx_u_change_of_energy_saving_mode_request(
+        &mut self,
+        queue: &mut MessageQueue,
+        dltime: TdmaTime,
+        issi: u32,
+        handle: u32,
+        pdu: &UMmStatus,
+    ) -> bool {
+        // Energy saving mode, followed by the O-bit and optional elements we do not use
+        let (Some(info), Some(len)) = (pdu.status_uplink_dependent_information, pdu.status_uplink_dependent_information_len) else {
+            tracing::warn!("Energy saving mode change request from MS {} without energy saving mode", issi);
+            return false;
+        };
+        if len < 3 {
+            tracing::warn!("Energy saving mode change request from MS {} too short", issi);
+            return false;
+        }
+        if !self.client_mgr.client_is_known(issi) {
+            tracing::warn!("Energy saving mode change request from unregistered MS {}", issi);
+            return false;
+        }
+        let mode = EnergySavingMode::try_from(info >> (len - 3)).unwrap(); // Never fails
+        let esi = self.set_energy_saving_mode(dltime, issi, mode);
+
+        let mut info = BitBuffer::new(ENERGY_SAVING_INFORMATION_BITS + 1);
+        esi.to_bitbuf(&mut info).unwrap();
+        info.write_bits(0, 1); // O-bit, no optional elements
+        info.seek(0);
+        let pdu_response = DMmStatus {
+            status_downlink: StatusDownlink::ChangeOfEnergySavingModeResponse,
+            status_downlink_dependent_information: info.read_bits(ENERGY_SAVING_INFORMATION_BITS + 1),
+            status_downlink_dependent_information_len: Some(ENERGY_SAVING_INFORMATION_BITS + 1),
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu_response.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+        Self::send_mm_sdu(queue, dltime, issi, handle, sdu);
+        true
+    }
+
     fn rx_u_attach_detach_group_identity(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_u_attach_detach_group_identity");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
@@ -349,6 +1273,10 @@ impl MmBs {
         // If group_identity_attach_detach_mode == 1, we first detach all groups
         if pdu.group_identity_attach_detach_mode == true {
             if !self.client_mgr.client_is_known(issi) {
+                if self.config.state_read().subscribers.disabled(issi).is_some() {
+                    tracing::warn!("Refusing group attach of disabled MS {}", issi);
+                    return;
+                }
                 // Client unknown (e.g. never registered via location update).
                 // Re-register so group attachment can proceed.
                 match self.client_mgr.try_register_client(issi, true) {
@@ -390,12 +1318,12 @@ impl MmBs {
 
         // Try to attach to requested groups, and retrieve list of accepted GroupIdentityDownlink elements
         // We can unwrap since we did compat check earlier
-        let accepted_gid = self.try_attach_detach_groups(queue, message.dltime, issi, &pdu.group_identity_uplink.unwrap());
+        let (accepted_gid, any_rejected) = self.try_attach_detach_groups(queue, message.dltime, issi, &pdu.group_identity_uplink.unwrap());
 
         // Build reply PDU
         let pdu_response = DAttachDetachGroupIdentityAcknowledgement {
-            group_identity_accept_reject: 0, // Accept
-            reserved: false,                 // TODO FIXME Guessed proper value of reserved field
+            group_identity_accept_reject: any_rejected as u8, // 1 if at least one group was rejected
+            reserved: false,                                  // TODO FIXME Guessed proper value of reserved field
             proprietary: None,
             group_identity_downlink: Some(accepted_gid),
             group_identity_security_related_information: None,
@@ -406,30 +1334,7 @@ impl MmBs {
         pdu_response.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
         sdu.seek(0);
         tracing::debug!("-> {:?} sdu {}", pdu_response, sdu.dump_bin());
-
-        let addr = TetraAddress {
-            encrypted: false,
-            ssi_type: SsiType::Ssi,
-            ssi: issi,
-        };
-        let msg = SapMsg {
-            sap: Sap::LmmSap,
-            src: TetraEntity::Mm,
-            dest: TetraEntity::Mle,
-            dltime: message.dltime,
-            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
-                sdu,
-                handle: prim.handle,
-                address: addr,
-                layer2service: Layer2Service::Todo,
-                stealing_permission: false,
-                stealing_repeats_flag: false,
-                encryption_flag: false,
-                is_null_pdu: false,
-                tx_reporter: None,
-            }),
-        };
-        queue.push_back(msg);
+        Self::send_mm_sdu_ciphered(queue, message.dltime, issi, prim.handle, sdu);
     }
 
     fn rx_lmm_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
@@ -447,9 +1352,10 @@ impl MmBs {
             tracing::warn!("invalid pdu type: {} in {}", bits, prim.sdu.dump_bin());
             return;
         };
+        self.config.state_write().subscribers.touch(prim.received_address.ssi);
 
         match pdu_type {
-            MmPduTypeUl::UAuthentication => unimplemented_log!("UAuthentication"),
+            MmPduTypeUl::UAuthentication => self.rx_u_authentication(queue, message),
             MmPduTypeUl::UItsiDetach => self.rx_u_itsi_detach(queue, message),
             MmPduTypeUl::ULocationUpdateDemand => self.rx_u_location_update_demand(queue, message),
             MmPduTypeUl::UMmStatus => self.rx_u_mm_status(queue, message),
@@ -457,27 +1363,32 @@ impl MmBs {
             MmPduTypeUl::UOtar => unimplemented_log!("UOtar"),
             MmPduTypeUl::UInformationProvide => unimplemented_log!("UInformationProvide"),
             MmPduTypeUl::UAttachDetachGroupIdentity => self.rx_u_attach_detach_group_identity(queue, message),
-            MmPduTypeUl::UAttachDetachGroupIdentityAcknowledgement => unimplemented_log!("UAttachDetachGroupIdentityAcknowledgement"),
+            MmPduTypeUl::UAttachDetachGroupIdentityAcknowledgement => {
+                self.rx_u_attach_detach_group_identity_acknowledgement(queue, message)
+            }
             MmPduTypeUl::UTeiProvide => unimplemented_log!("UTeiProvide"),
-            MmPduTypeUl::UDisableStatus => unimplemented_log!("UDisableStatus"),
+            MmPduTypeUl::UDisableStatus => self.rx_u_disable_status(queue, message),
             MmPduTypeUl::MmPduFunctionNotSupported => unimplemented_log!("MmPduFunctionNotSupported"),
         };
     }
 
+    /// Attaches and detaches the requested groups. Returns the accepted groups, and whether any request was rejected.
     fn try_attach_detach_groups(
         &mut self,
         queue: &mut MessageQueue,
         dltime: TdmaTime,
         issi: u32,
         giu_vec: &Vec<GroupIdentityUplink>,
-    ) -> Vec<GroupIdentityDownlink> {
+    ) -> (Vec<GroupIdentityDownlink>, bool) {
         let mut accepted_groups = Vec::new();
+        let mut any_rejected = false;
         let mut aff_groups = Vec::new();
         let mut deaff_groups = Vec::new();
 
         for giu in giu_vec.iter() {
             if giu.gssi.is_none() || giu.vgssi.is_some() || giu.address_extension.is_some() {
                 unimplemented_log!("Only support GroupIdentityUplink with address_type 0");
+                any_rejected = true;
                 continue;
             }
 
@@ -502,6 +1413,7 @@ impl MmBs {
                     }
                     Err(e) => {
                         tracing::warn!("Failed detaching MS {} from group {}: {:?}", issi, gssi, e);
+                        any_rejected = true;
                     }
                 }
             } else {
@@ -526,6 +1438,7 @@ impl MmBs {
                     }
                     Err(e) => {
                         tracing::warn!("Failed attaching MS {} to group {}: {:?}", issi, gssi, e);
+                        any_rejected = true;
                     }
                 }
             }
@@ -538,7 +1451,193 @@ impl MmBs {
             self.emit_subscriber_update(queue, dltime, issi, deaff_groups, BrewSubscriberAction::Deaffiliate);
         }
 
-        accepted_groups
+        (accepted_groups, any_rejected)
+    }
+
+    /// Attaches a freshly registered MS to the groups configured for it, unless it attached to them itself
+    fn assign_static_groups(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32) {
+        let Some(gssis) = self.config.config().group_assignment.as_ref().map(|g| g.static_gssis_for(issi)) else {
+            return;
+        };
+        let attached = self
+            .client_mgr
+            .get_client_by_issi(issi)
+            .map(|c| c.groups.clone())
+            .unwrap_or_default();
+        let attach: Vec<u32> = gssis.into_iter().filter(|gssi| !attached.contains(gssi)).collect();
+        if !attach.is_empty() {
+            self.assign_groups(queue, dltime, issi, attach, Vec::new());
+        }
+    }
+
+    /// Starts a SwMI-initiated group attachment/detachment (DGNA). Groups the access policy refuses are left out.
+    /// A pending assignment to the same MS is merged into the new one. The registry and Brew follow on acknowledgement.
+    fn assign_groups(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, attach: Vec<u32>, detach: Vec<u32>) {
+        if !self.client_mgr.client_is_known(issi) {
+            tracing::warn!("MM: cannot assign groups to unregistered MS {}", issi);
+            return;
+        }
+        let attach: Vec<u32> = attach
+            .into_iter()
+            .filter(|&gssi| match self.client_mgr.may_attach(issi, gssi) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("MM: not assigning group {} to MS {}: {:?}", gssi, issi, e);
+                    false
+                }
+            })
+            .collect();
+
+        let mut assignment = self.group_assignments.remove(&issi).unwrap_or(GroupAssignment {
+            attach: Vec::new(),
+            detach: Vec::new(),
+            sent: dltime,
+            tx_count: 0,
+        });
+        assignment.attach.retain(|gssi| !detach.contains(gssi));
+        assignment.detach.retain(|gssi| !attach.contains(gssi));
+        for gssi in attach {
+            if !assignment.attach.contains(&gssi) {
+                assignment.attach.push(gssi);
+            }
+        }
+        for gssi in detach {
+            if !assignment.detach.contains(&gssi) {
+                assignment.detach.push(gssi);
+            }
+        }
+        if assignment.attach.is_empty() && assignment.detach.is_empty() {
+            return;
+        }
+
+        assignment.tx_count = 0;
+        Self::send_group_assignment(queue, dltime, issi, &mut assignment);
+        self.group_assignments.insert(issi, assignment);
+    }
+
+    /// Sends D-ATTACH/DETACH GROUP IDENTITY for a pending assignment, asking the MS to acknowledge it
+    fn send_group_assignment(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, assignment: &mut GroupAssignment) {
+        let attachments = assignment.attach.iter().map(|&gssi| GroupIdentityDownlink {
+            group_identity_attachment: Some(GroupIdentityAttachment {
+                group_identity_attachment_lifetime: ASSIGNED_GROUP_ATTACHMENT_LIFETIME,
+                class_of_usage: 0,
+            }),
+            group_identity_detachment_uplink: None,
+            gssi: Some(gssi),
+            address_extension: None,
+            vgssi: None,
+        });
+        let detachments = assignment.detach.iter().map(|&gssi| GroupIdentityDownlink {
+            group_identity_attachment: None,
+            group_identity_detachment_uplink: Some(ASSIGNED_GROUP_DETACHMENT_REASON),
+            gssi: Some(gssi),
+            address_extension: None,
+            vgssi: None,
+        });
+        let pdu = DAttachDetachGroupIdentity {
+            group_identity_report: false,
+            group_identity_acknowledgement_request: true,
+            group_identity_attach_detach_mode: false, // Amendment
+            proprietary: None,
+            group_report_response: None,
+            group_identity_downlink: Some(attachments.chain(detachments).collect()),
+            group_identity_security_related_information: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // We want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        assignment.sent = dltime;
+        assignment.tx_count += 1;
+        Self::send_mm_sdu_ciphered(queue, dltime, issi, 0, sdu);
+    }
+
+    /// Repeats group assignments the MS did not acknowledge in time, giving up after GROUP_ASSIGNMENT_MAX_TX transmissions
+    fn check_group_assignments(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        let expired: Vec<u32> = self
+            .group_assignments
+            .iter()
+            .filter(|(_, a)| a.sent.age(ts) >= GROUP_ASSIGNMENT_ACK_TIMEOUT)
+            .map(|(&issi, _)| issi)
+            .collect();
+        for issi in expired {
+            let mut assignment = self.group_assignments.remove(&issi).unwrap(); // Never fails
+            if assignment.tx_count >= GROUP_ASSIGNMENT_MAX_TX {
+                tracing::warn!("MM: MS {} did not acknowledge group assignment, giving up", issi);
+                continue;
+            }
+            Self::send_group_assignment(queue, ts, issi, &mut assignment);
+            self.group_assignments.insert(issi, assignment);
+        }
+    }
+
+    fn rx_u_attach_detach_group_identity_acknowledgement(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_attach_detach_group_identity_acknowledgement");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match UAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed parsing UAttachDetachGroupIdentityAcknowledgement: {:?} {}",
+                    e,
+                    prim.sdu.dump_bin()
+                );
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        let Some(assignment) = self.group_assignments.remove(&issi) else {
+            tracing::warn!("MM: unexpected group assignment acknowledgement from MS {}", issi);
+            return;
+        };
+
+        // On rejection, the groups listed by the MS are the ones it refused. Without a list, it refused all.
+        let rejected: Vec<u32> = match (pdu.group_identity_acknowledgement_type, &pdu.group_identity_uplink) {
+            (false, _) => Vec::new(),
+            (true, Some(giu)) => giu.iter().filter_map(|giu| giu.gssi).collect(),
+            (true, None) => assignment.attach.iter().chain(&assignment.detach).copied().collect(),
+        };
+        if !rejected.is_empty() {
+            tracing::warn!("MM: MS {} rejected assignment of groups {:?}", issi, rejected);
+        }
+
+        let mut aff_groups = Vec::new();
+        let mut deaff_groups = Vec::new();
+        for &gssi in assignment.attach.iter().filter(|gssi| !rejected.contains(gssi)) {
+            match self.client_mgr.client_group_attach(issi, gssi, true) {
+                Ok(true) => {
+                    self.config.state_write().subscribers.affiliate(issi, gssi);
+                    aff_groups.push(gssi);
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed attaching MS {} to assigned group {}: {:?}", issi, gssi, e),
+            }
+        }
+        for &gssi in assignment.detach.iter().filter(|gssi| !rejected.contains(gssi)) {
+            match self.client_mgr.client_group_attach(issi, gssi, false) {
+                Ok(true) => {
+                    self.config.state_write().subscribers.deaffiliate(issi, gssi);
+                    deaff_groups.push(gssi);
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed detaching MS {} from group {}: {:?}", issi, gssi, e),
+            }
+        }
+
+        if !aff_groups.is_empty() {
+            self.emit_subscriber_update(queue, message.dltime, issi, aff_groups, BrewSubscriberAction::Affiliate);
+        }
+        if !deaff_groups.is_empty() {
+            self.emit_subscriber_update(queue, message.dltime, issi, deaff_groups, BrewSubscriberAction::Deaffiliate);
+        }
     }
 
     /// Sends a D-LOCATION UPDATE COMMAND to force the radio to re-register
@@ -606,20 +1705,9 @@ impl MmBs {
             unimplemented_log!("Unsupported request_to_append_la == true");
             supported = false;
         }
-        if pdu.cipher_control == true {
-            unimplemented_log!("Unsupported cipher_control == true");
-            supported = false;
-        }
-        if pdu.ciphering_parameters.is_some() {
-            unimplemented_log!("Unsupported ciphering_parameters present");
-            supported = false;
-        }
         if pdu.class_of_ms.is_some() {
             unimplemented_log!("Unsupported class_of_ms present");
         }
-        if pdu.energy_saving_mode.is_some() {
-            unimplemented_log!("Unsupported energy_saving_mode present");
-        }
         if pdu.la_information.is_some() {
             unimplemented_log!("Unsupported la_information present");
         }
@@ -633,7 +1721,8 @@ impl MmBs {
             unimplemented_log!("Unsupported group_report_response present");
         }
         if pdu.authentication_uplink.is_some() {
-            unimplemented_log!("Unsupported authentication_uplink present");
+            // Authentication is driven by the SwMI based on the configured keys
+            tracing::debug!("Ignoring authentication_uplink");
         }
         if pdu.extended_capabilities.is_some() {
             unimplemented_log!("Unsupported extended_capabilities present");
@@ -676,20 +1765,42 @@ impl TetraEntityTrait for MmBs {
         self.config = config;
     }
 
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        if !self.journal_restored {
+            self.restore_journal(queue, ts);
+            self.journal_restored = true;
+            self.journal_snapshot_time = ts;
+        }
+        if self.journal_snapshot_time.age(ts) >= JOURNAL_SNAPSHOT_INTERVAL {
+            self.journal_snapshot_time = ts;
+            self.snapshot_journal();
+        }
+        if ts.t == 1 {
+            self.check_auth_sessions(queue, ts);
+            self.check_group_assignments(queue, ts);
+        }
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for MM
-        assert!(message.sap == Sap::LmmSap);
-
-        match message.msg {
-            SapMsgInner::LmmMleUnitdataInd(_) => {
+        match (message.sap, &message.msg) {
+            (Sap::LmmSap, SapMsgInner::LmmMleUnitdataInd(_)) => {
                 self.rx_lmm_mle_unitdata_ind(queue, message);
             }
+            (Sap::Control, SapMsgInner::MmControl(_)) => {
+                self.rx_mm_control(queue, message);
+            }
             _ => {
                 panic!();
             }
         }
     }
 }
+
+impl Drop for MmBs {
+    fn drop(&mut self) {
+        self.snapshot_journal();
+    }
+}
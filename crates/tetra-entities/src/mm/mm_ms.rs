@@ -1,22 +1,290 @@
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{Sap, unimplemented_log};
+use tetra_core::{BitBuffer, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, multiframes, unimplemented_log};
+use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
 use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::fields::group_identity_location_demand::GroupIdentityLocationDemand;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity::DAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+
+/// Number of timeslots to wait for a D-LOCATION UPDATE ACCEPT/REJECT before registering again.
+/// 30 multiframes, roughly 30 seconds, which leaves ample room for random access retries and LLC retransmissions.
+const REGISTRATION_TIMEOUT_SLOTS: i32 = multiframes!(30);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RegistrationState {
+    /// Not camped on a cell yet, or registration was rejected
+    Idle,
+    /// U-LOCATION UPDATE DEMAND sent, awaiting the response for the given number of timeslots
+    Pending(i32),
+    /// Registration accepted by the SwMI
+    Registered,
+}
 
 pub struct MmMs {
-    // config: Option<SharedConfig>,
     config: SharedConfig,
+    state: RegistrationState,
+    /// Groups confirmed as attached by the SwMI
+    attached_groups: Vec<u32>,
 }
 
 impl MmMs {
     pub fn new(config: SharedConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            state: RegistrationState::Idle,
+            attached_groups: Vec::new(),
+        }
+    }
+
+    pub fn is_registered(&self) -> bool {
+        self.state == RegistrationState::Registered
+    }
+
+    pub fn attached_groups(&self) -> &[u32] {
+        &self.attached_groups
+    }
+
+    fn own_address(&self) -> Option<TetraAddress> {
+        let issi = self.config.config().ms.as_ref()?.issi;
+        Some(TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Ssi,
+            ssi: issi,
+        })
+    }
+
+    fn send_mm_pdu(&self, queue: &mut MessageQueue, dltime: TdmaTime, sdu: BitBuffer) {
+        let Some(address) = self.own_address() else {
+            tracing::warn!("send_mm_pdu: no MS identity configured");
+            return;
+        };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq {
+                sdu,
+                handle: 0,
+                address,
+                layer2service: Layer2Service::Todo,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                encryption_flag: false,
+                is_null_pdu: false,
+                tx_reporter: None,
+            }),
+        };
+        queue.push_back(msg);
+    }
+
+    /// Sends an ITSI attach U-LOCATION UPDATE DEMAND, requesting attachment to all configured groups
+    fn send_u_location_update_demand(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        let Some(ms) = self.config.config().ms.clone() else {
+            tracing::warn!("send_u_location_update_demand: no MS identity configured");
+            return;
+        };
+
+        let group_identity_location_demand = if ms.groups.is_empty() {
+            None
+        } else {
+            let groups = ms
+                .groups
+                .iter()
+                .map(|gssi| GroupIdentityUplink {
+                    class_of_usage: Some(0),
+                    group_identity_detachment_uplink: None,
+                    gssi: Some(*gssi),
+                    address_extension: None,
+                    vgssi: None,
+                })
+                .collect();
+            Some(GroupIdentityLocationDemand {
+                group_identity_attach_detach_mode: 0, // Amendment
+                group_identity_uplink: Some(groups),
+            })
+        };
+
+        let pdu = ULocationUpdateDemand {
+            location_update_type: LocationUpdateType::ItsiAttach,
+            request_to_append_la: false,
+            cipher_control: false,
+            ciphering_parameters: None,
+            class_of_ms: None,
+            energy_saving_mode: None,
+            la_information: None,
+            ssi: None,
+            address_extension: None,
+            group_identity_location_demand,
+            group_report_response: None,
+            authentication_uplink: None,
+            extended_capabilities: None,
+            proprietary: None,
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::warn!("Failed serializing ULocationUpdateDemand: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+        tracing::debug!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+
+        tracing::info!("Registering ISSI {} with groups {:?}", ms.issi, ms.groups);
+        self.send_mm_pdu(queue, dltime, sdu);
+        self.state = RegistrationState::Pending(0);
+    }
+
+    fn rx_lmm_mle_activate_conf(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::LmmMleActivateConf(prim) = &message.msg else {
+            panic!()
+        };
+
+        tracing::info!(
+            "Camped on cell in LA {}, registration required: {}",
+            prim.la,
+            prim.registration_required
+        );
+        if self.state == RegistrationState::Idle {
+            self.send_u_location_update_demand(queue, message.dltime);
+        }
+    }
+
+    fn rx_d_location_update_accept(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match DLocationUpdateAccept::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DLocationUpdateAccept: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        self.state = RegistrationState::Registered;
+        self.attached_groups.clear();
+        if let Some(gila) = pdu.group_identity_location_accept
+            && let Some(gids) = gila.group_identity_downlink
+        {
+            for gid in gids {
+                if let Some(gssi) = gid.gssi
+                    && gid.group_identity_attachment.is_some()
+                {
+                    self.attached_groups.push(gssi);
+                }
+            }
+        }
+        tracing::info!(
+            "Registered ({:?}), attached groups {:?}",
+            pdu.location_update_accept_type,
+            self.attached_groups
+        );
+    }
+
+    fn rx_d_location_update_reject(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match DLocationUpdateReject::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DLocationUpdateReject: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // Don't keep hammering the cell, stay idle until we camp on a cell again
+        tracing::warn!("Registration rejected, cause {}", pdu.reject_cause);
+        self.state = RegistrationState::Idle;
+        self.attached_groups.clear();
     }
 
-    fn rx_lmm_mle_unitdata_ind(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    fn rx_d_attach_detach_group_identity(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match DAttachDetachGroupIdentity::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DAttachDetachGroupIdentity: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        if pdu.group_identity_attach_detach_mode {
+            // Detach all current groups, then attach the given ones
+            self.attached_groups.clear();
+        }
+        for gid in pdu.group_identity_downlink.iter().flatten() {
+            let Some(gssi) = gid.gssi else {
+                continue;
+            };
+            if gid.group_identity_attachment.is_some() {
+                if !self.attached_groups.contains(&gssi) {
+                    self.attached_groups.push(gssi);
+                }
+            } else {
+                self.attached_groups.retain(|g| *g != gssi);
+            }
+        }
+        tracing::info!("Group attachment changed by SwMI, attached groups {:?}", self.attached_groups);
+
+        if pdu.group_identity_acknowledgement_request {
+            let ack = UAttachDetachGroupIdentityAcknowledgement {
+                group_identity_acknowledgement_type: false, // Accept
+                group_identity_uplink: None,
+                proprietary: None,
+            };
+            let mut sdu = BitBuffer::new_autoexpand(16);
+            if let Err(e) = ack.to_bitbuf(&mut sdu) {
+                tracing::warn!("Failed serializing UAttachDetachGroupIdentityAcknowledgement: {:?}", e);
+                return;
+            }
+            sdu.seek(0);
+            tracing::debug!("-> {:?} sdu {}", ack, sdu.dump_bin());
+            self.send_mm_pdu(queue, message.dltime, sdu);
+        }
+    }
+
+    fn rx_d_attach_detach_group_identity_ack(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        match DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut prim.sdu) {
+            Ok(pdu) => tracing::debug!("<- {:?}", pdu),
+            Err(e) => tracing::warn!(
+                "Failed parsing DAttachDetachGroupIdentityAcknowledgement: {:?} {}",
+                e,
+                prim.sdu.dump_bin()
+            ),
+        }
+    }
+
+    fn rx_lmm_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
@@ -32,17 +300,21 @@ impl MmMs {
         };
 
         match pdu_type {
+            MmPduTypeDl::DLocationUpdateAccept => self.rx_d_location_update_accept(queue, message),
+            MmPduTypeDl::DLocationUpdateReject => self.rx_d_location_update_reject(queue, message),
+            MmPduTypeDl::DAttachDetachGroupIdentity => self.rx_d_attach_detach_group_identity(queue, message),
+            MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement => self.rx_d_attach_detach_group_identity_ack(queue, message),
+            MmPduTypeDl::DLocationUpdateCommand => {
+                // The SwMI wants a fresh registration, including our group report
+                tracing::info!("<- DLocationUpdateCommand, registering again");
+                self.send_u_location_update_demand(queue, message.dltime);
+            }
             MmPduTypeDl::DOtar => unimplemented_log!("DOtar"),
             MmPduTypeDl::DAuthentication => unimplemented_log!("DAuthentication"),
             MmPduTypeDl::DCkChangeDemand => unimplemented_log!("DCkChangeDemand"),
             MmPduTypeDl::DDisable => unimplemented_log!("DDisable"),
             MmPduTypeDl::DEnable => unimplemented_log!("DEnable"),
-            MmPduTypeDl::DLocationUpdateAccept => unimplemented_log!("DLocationUpdateAccept"),
-            MmPduTypeDl::DLocationUpdateCommand => unimplemented_log!("DLocationUpdateCommand"),
-            MmPduTypeDl::DLocationUpdateReject => unimplemented_log!("DLocationUpdateReject"),
             MmPduTypeDl::DLocationUpdateProceeding => unimplemented_log!("DLocationUpdateProceeding"),
-            MmPduTypeDl::DAttachDetachGroupIdentity => unimplemented_log!("DAttachDetachGroupIdentity"),
-            MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement => unimplemented_log!("DAttachDetachGroupIdentityAcknowledgement"),
             MmPduTypeDl::DMmStatus => unimplemented_log!("DMmStatus"),
             MmPduTypeDl::MmPduFunctionNotSupported => unimplemented_log!("MmPduFunctionNotSupported"),
         };
@@ -63,15 +335,35 @@ impl TetraEntityTrait for MmMs {
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
         // There is only one SAP for MM
-        assert!(message.sap == Sap::LmmSap);
+        if message.sap != Sap::LmmSap {
+            tracing::warn!("rx_prim: unexpected message on {:?}", message.sap);
+            return;
+        }
 
         match message.msg {
             SapMsgInner::LmmMleUnitdataInd(_) => {
                 self.rx_lmm_mle_unitdata_ind(queue, message);
             }
+            SapMsgInner::LmmMleActivateConf(_) => {
+                self.rx_lmm_mle_activate_conf(queue, message);
+            }
             _ => {
-                panic!();
+                tracing::warn!("rx_prim: unexpected {:?}", message.msg);
             }
         }
     }
+
+    fn tick_end(&mut self, queue: &mut MessageQueue, ts: TdmaTime) -> bool {
+        // Count router ticks rather than comparing times, since the router time is not network time in MS mode
+        let RegistrationState::Pending(waited) = self.state else {
+            return false;
+        };
+        if waited < REGISTRATION_TIMEOUT_SLOTS {
+            self.state = RegistrationState::Pending(waited + 1);
+            return false;
+        }
+        tracing::warn!("No response to registration, retrying");
+        self.send_u_location_update_demand(queue, ts);
+        true
+    }
 }
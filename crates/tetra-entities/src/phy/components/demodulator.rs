@@ -373,6 +373,12 @@ impl Demodulator {
         if timing_phase < SPS as RealSample { timing_phase } else { 0.0 }
     }
 
+    /// Reference time of a synchronized downlink demodulator,
+    /// used by an MS to time its uplink transmissions.
+    pub fn dl_reference_time(&self) -> Option<SampleCount> {
+        if self.mode == Mode::Dl { Some(self.reference_time) } else { None }
+    }
+
     /// Synchronize an uplink demodulator to a downlink demodulator
     /// for simultaneous UL/DL monitoring.
     pub fn sync_to_demodulator(&mut self, demod: &Demodulator) {
@@ -0,0 +1,342 @@
+//! Baseband sample files in place of an SDR, to run the complete stack
+//! including the modem DSP chain on machines without a radio.
+//!
+//! Files contain interleaved little-endian 32-bit float I and Q samples (cf32).
+//! There is no hardware clock: time advances as samples are received,
+//! so the stack runs as fast as processing allows. Once the RX file and
+//! an optional tail of silence have been received, the end of data is reported.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use tetra_config::bluestation::{SharedConfig, StackMode};
+use tetra_core::freqs::FreqInfo;
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::dsp_types::*;
+use super::sdr_io::{RxResult, SdrIo};
+use super::soapy_dev::RxTxDevSdr;
+
+/// Sample rate used if none is configured
+pub const DEFAULT_SAMPLE_RATE: f64 = 512e3;
+
+const BYTES_PER_SAMPLE: usize = 8;
+
+pub type RxTxDevFile = RxTxDevSdr<FileIo>;
+
+impl RxTxDevFile {
+    pub fn new(cfg: &SharedConfig) -> Self {
+        let config_guard = cfg.config();
+        let cell = &config_guard.cell;
+        let freq_info = FreqInfo::from_components(
+            cell.freq_band,
+            cell.main_carrier,
+            cell.freq_offset_hz,
+            cell.reverse_operation,
+            cell.duplex_spacing_id,
+            cell.custom_duplex_spacing,
+        )
+        .expect("Invalid cell info frequency settings");
+        let (dl_freq, ul_freq) = freq_info.get_freqs();
+        let (dl_freq, ul_freq) = (dl_freq as f64, ul_freq as f64);
+
+        tracing::info!("Freqs: DL / UL: {:.6} MHz / {:.6} MHz", dl_freq / 1e6, ul_freq / 1e6);
+
+        let stack_mode = config_guard.stack_mode;
+        let io = FileIo::new(cfg, dl_freq, ul_freq).unwrap();
+        let tail_secs = config_guard.phy_io.rx_silence_tail_secs.unwrap_or(0.0);
+        let tail = (tail_secs * io.fs).round() as SampleCount;
+        let io = io.with_rx_silence_tail(tail);
+        Self::with_io(stack_mode, io, dl_freq, ul_freq)
+    }
+}
+
+pub struct FileIo {
+    fs: f64,
+    rx_center_frequency: f64,
+    /// None if TX is disabled
+    tx_center_frequency: Option<f64>,
+
+    /// Received signal. None if not configured or once it has ended.
+    rx_file: Option<BufReader<File>>,
+    /// Sample counter of the next received sample
+    rx_next_count: SampleCount,
+    rx_bytes: Vec<u8>,
+    /// Samples of silence received after the end of the RX file
+    rx_silence_tail: SampleCount,
+    /// Sample counter at which reception ends, once the end of the RX file has been reached
+    rx_end_count: Option<SampleCount>,
+
+    /// Transmitted signal. None if not configured, in which case it is discarded.
+    tx_file: Option<BufWriter<File>>,
+    /// Sample counter of the next sample to be written to tx_file
+    tx_next_count: SampleCount,
+    tx_bytes: Vec<u8>,
+}
+
+impl FileIo {
+    /// Open the files for the configured stack mode. A BS receives the uplink from ul_input_file
+    /// and transmits the downlink to dl_tx_file. An MS or monitor receives the downlink from dl_input_file.
+    pub fn new(cfg: &SharedConfig, dl_freq: f64, ul_freq: f64) -> std::io::Result<Self> {
+        let config_guard = cfg.config();
+        let phy_io = &config_guard.phy_io;
+        let fs = phy_io.sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE);
+
+        match config_guard.stack_mode {
+            StackMode::Bs => Self::open(
+                fs,
+                ul_freq,
+                Some(dl_freq),
+                phy_io.ul_input_file.as_deref(),
+                phy_io.dl_tx_file.as_deref(),
+            ),
+            StackMode::Ms => Self::open(fs, dl_freq, Some(ul_freq), phy_io.dl_input_file.as_deref(), None),
+            StackMode::Mon => Self::open(fs, dl_freq, None, phy_io.dl_input_file.as_deref(), None),
+        }
+    }
+
+    /// Without an RX file, silence is received. Without a TX file, transmitted signal is discarded.
+    /// TX is disabled if tx_center_frequency is None.
+    pub fn open(
+        fs: f64,
+        rx_center_frequency: f64,
+        tx_center_frequency: Option<f64>,
+        rx_path: Option<&str>,
+        tx_path: Option<&str>,
+    ) -> std::io::Result<Self> {
+        let rx_file = match rx_path {
+            Some(path) => {
+                tracing::info!("Receiving signal from {}", path);
+                Some(BufReader::new(File::open(path)?))
+            }
+            None => None,
+        };
+        let tx_file = match tx_path {
+            Some(path) => {
+                tracing::info!("Transmitting signal to {}", path);
+                Some(BufWriter::new(File::create(path)?))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            fs,
+            rx_center_frequency,
+            tx_center_frequency,
+            rx_file,
+            rx_next_count: 0,
+            rx_bytes: Vec::new(),
+            rx_silence_tail: 0,
+            rx_end_count: None,
+            tx_file,
+            tx_next_count: 0,
+            tx_bytes: Vec::new(),
+        })
+    }
+
+    /// Keep receiving silence for the given number of samples after the RX file ends
+    pub fn with_rx_silence_tail(mut self, samples: SampleCount) -> Self {
+        self.rx_silence_tail = samples;
+        self
+    }
+
+    /// Read as many samples as available into buffer, returning the number read
+    fn read_samples(file: &mut BufReader<File>, bytes: &mut Vec<u8>, buffer: &mut [ComplexSample]) -> std::io::Result<usize> {
+        bytes.resize(buffer.len() * BYTES_PER_SAMPLE, 0);
+        let mut filled = 0;
+        while filled < bytes.len() {
+            match file.read(&mut bytes[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let len = filled / BYTES_PER_SAMPLE;
+        for (sample, b) in buffer.iter_mut().zip(bytes.chunks_exact(BYTES_PER_SAMPLE)).take(len) {
+            *sample = ComplexSample::new(
+                f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+                f32::from_le_bytes([b[4], b[5], b[6], b[7]]),
+            );
+        }
+        Ok(len)
+    }
+
+    fn write_samples(&mut self, samples: &[ComplexSample]) -> Result<(), RxTxDevError> {
+        let Some(file) = &mut self.tx_file else {
+            return Ok(());
+        };
+        self.tx_bytes.clear();
+        for sample in samples {
+            self.tx_bytes.extend_from_slice(&sample.re.to_le_bytes());
+            self.tx_bytes.extend_from_slice(&sample.im.to_le_bytes());
+        }
+        file.write_all(&self.tx_bytes).map_err(|e| {
+            tracing::error!("Failed to write TX file: {}", e);
+            RxTxDevError::RxReadError
+        })
+    }
+}
+
+impl SdrIo for FileIo {
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError> {
+        if self.rx_end_count.is_some_and(|end| self.rx_next_count >= end) {
+            return Err(RxTxDevError::RxEndOfData);
+        }
+
+        let mut len = 0;
+        if let Some(file) = &mut self.rx_file {
+            len = Self::read_samples(file, &mut self.rx_bytes, buffer).map_err(|e| {
+                tracing::error!("Failed to read RX file: {}", e);
+                RxTxDevError::RxReadError
+            })?;
+            if len < buffer.len() {
+                let file_end = self.rx_next_count + len as SampleCount;
+                tracing::info!(
+                    "End of RX file after {} samples, receiving {} samples of silence",
+                    file_end,
+                    self.rx_silence_tail
+                );
+                self.rx_file = None;
+                self.rx_end_count = Some(file_end + self.rx_silence_tail);
+            }
+        }
+        buffer[len..].fill(num::zero());
+
+        let count = self.rx_next_count;
+        self.rx_next_count += buffer.len() as SampleCount;
+        Ok(RxResult { len: buffer.len(), count })
+    }
+
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        let count = count.unwrap_or(self.tx_next_count);
+        let mut samples = buffer;
+
+        if count > self.tx_next_count {
+            // Nothing was transmitted in between, fill the gap with silence
+            let gap = vec![num::zero(); (count - self.tx_next_count) as usize];
+            self.write_samples(&gap)?;
+        } else if count < self.tx_next_count {
+            // Overlaps with what was already written, only keep the new part
+            let overlap = ((self.tx_next_count - count) as usize).min(samples.len());
+            samples = &samples[overlap..];
+        }
+
+        self.write_samples(samples)?;
+        self.tx_next_count = self.tx_next_count.max(count + buffer.len() as SampleCount);
+        Ok(())
+    }
+
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        // The latest received sample defines the current time
+        Ok(self.rx_next_count - 1)
+    }
+
+    fn tx_possible(&self) -> bool {
+        self.tx_enabled()
+    }
+
+    fn rx_sample_rate(&self) -> f64 {
+        self.fs
+    }
+
+    fn tx_sample_rate(&self) -> f64 {
+        self.fs
+    }
+
+    fn rx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        Ok(self.rx_center_frequency)
+    }
+
+    fn tx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        self.tx_center_frequency.ok_or(RxTxDevError::RxReadError)
+    }
+
+    fn rx_enabled(&self) -> bool {
+        true
+    }
+
+    fn tx_enabled(&self) -> bool {
+        self.tx_center_frequency.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let mut path = env::temp_dir();
+        path.push(format!(
+            "file_io_test_{}_{}.cf32",
+            name,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        path
+    }
+
+    #[test]
+    fn test_transmit_then_receive() {
+        let path = temp_path("txrx");
+        let path_str = path.to_str().unwrap();
+
+        {
+            let mut io = FileIo::open(DEFAULT_SAMPLE_RATE, 0.0, Some(0.0), None, Some(path_str)).unwrap();
+            // Gap before the first block is filled with silence, overlap with the second block is dropped
+            io.transmit(&[ComplexSample::new(1.0, -1.0); 4], Some(2)).unwrap();
+            io.transmit(&[ComplexSample::new(2.0, -2.0); 4], Some(4)).unwrap();
+        }
+
+        let mut io = FileIo::open(DEFAULT_SAMPLE_RATE, 0.0, None, Some(path_str), None).unwrap();
+        let mut buffer = [ComplexSample::new(9.0, 9.0); 12];
+        let result = io.receive(&mut buffer).unwrap();
+        assert_eq!(result.count, 0);
+        assert_eq!(result.len, 12);
+
+        let zero = ComplexSample::new(0.0, 0.0);
+        let one = ComplexSample::new(1.0, -1.0);
+        let two = ComplexSample::new(2.0, -2.0);
+        assert_eq!(buffer, [zero, zero, one, one, one, one, two, two, zero, zero, zero, zero]);
+
+        // The end of the file was reached
+        assert!(matches!(io.receive(&mut buffer), Err(RxTxDevError::RxEndOfData)));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_receive_silence_tail() {
+        let path = temp_path("tail");
+        let path_str = path.to_str().unwrap();
+        {
+            let mut io = FileIo::open(DEFAULT_SAMPLE_RATE, 0.0, Some(0.0), None, Some(path_str)).unwrap();
+            io.transmit(&[ComplexSample::new(1.0, -1.0); 4], Some(0)).unwrap();
+        }
+
+        let mut io = FileIo::open(DEFAULT_SAMPLE_RATE, 0.0, None, Some(path_str), None)
+            .unwrap()
+            .with_rx_silence_tail(10);
+        let mut buffer = [ComplexSample::new(9.0, 9.0); 8];
+        assert_eq!(io.receive(&mut buffer).unwrap().count, 0);
+        assert_eq!(buffer[4..], [ComplexSample::new(0.0, 0.0); 4]);
+
+        // Time keeps advancing through the tail of silence, then the end of data is reported
+        assert_eq!(io.receive(&mut buffer).unwrap().count, 8);
+        assert_eq!(buffer, [ComplexSample::new(0.0, 0.0); 8]);
+        assert_eq!(io.tx_current_count().unwrap(), 15);
+        assert!(matches!(io.receive(&mut buffer), Err(RxTxDevError::RxEndOfData)));
+
+        // Without an RX file, silence is received forever
+        let mut io = FileIo::open(DEFAULT_SAMPLE_RATE, 0.0, None, None, None).unwrap();
+        for i in 0..4 {
+            assert_eq!(io.receive(&mut buffer).unwrap().count, i * 8);
+        }
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,197 @@
+//! In-process radio channel connecting two stacks, typically a BS and an MS,
+//! without an SDR. What one side transmits is received by the other.
+//!
+//! Both sides run on their own clock, advanced as samples are received.
+//! Receiving blocks until the other side is known to have transmitted
+//! everything up to the end of the requested block, or to be silent until then.
+
+use std::collections::VecDeque;
+
+use crossbeam_channel::{Receiver, RecvError, Sender};
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::dsp_types::*;
+use super::sdr_io::{RxResult, SdrIo};
+
+/// Samples transmitted starting at the given count.
+/// May be empty, in which case it only tells that nothing is transmitted before count.
+type LoopbackMsg = (SampleCount, Vec<ComplexSample>);
+
+pub struct LoopbackIo {
+    fs: f64,
+    rx_center_frequency: f64,
+    tx_center_frequency: f64,
+
+    tx: Sender<LoopbackMsg>,
+    rx: Receiver<LoopbackMsg>,
+    /// False once the other side has been dropped, after which silence is received
+    rx_connected: bool,
+
+    /// Sample counter of the next received sample
+    rx_next_count: SampleCount,
+    /// Samples received from the other side, starting at rx_next_count
+    rx_buffer: VecDeque<ComplexSample>,
+    /// Everything the other side transmits before this count has been received
+    rx_complete_until: SampleCount,
+    /// Nothing is transmitted before this count anymore, as the other side has been told so
+    tx_silent_until: SampleCount,
+}
+
+impl LoopbackIo {
+    /// Create two connected ends. Both run at the same sample rate,
+    /// each receiving on the frequency the other one transmits on.
+    pub fn pair(fs: f64, a_tx_frequency: f64, b_tx_frequency: f64) -> (Self, Self) {
+        let (a_tx, b_rx) = crossbeam_channel::unbounded();
+        let (b_tx, a_rx) = crossbeam_channel::unbounded();
+        (
+            Self::new(fs, b_tx_frequency, a_tx_frequency, a_tx, a_rx),
+            Self::new(fs, a_tx_frequency, b_tx_frequency, b_tx, b_rx),
+        )
+    }
+
+    fn new(fs: f64, rx_center_frequency: f64, tx_center_frequency: f64, tx: Sender<LoopbackMsg>, rx: Receiver<LoopbackMsg>) -> Self {
+        Self {
+            fs,
+            rx_center_frequency,
+            tx_center_frequency,
+            tx,
+            rx,
+            rx_connected: true,
+            rx_next_count: 0,
+            rx_buffer: VecDeque::new(),
+            rx_complete_until: 0,
+            tx_silent_until: 0,
+        }
+    }
+
+    /// Add samples from the other side to rx_buffer
+    fn add_rx_samples(&mut self, (count, samples): LoopbackMsg) {
+        let end = count + samples.len() as SampleCount;
+        self.rx_complete_until = self.rx_complete_until.max(end);
+
+        // Anything before rx_next_count has arrived too late to be received
+        let skip = (self.rx_next_count - count).clamp(0, samples.len() as SampleCount) as usize;
+        if skip == samples.len() {
+            return;
+        }
+        let offset = (count + skip as SampleCount - self.rx_next_count) as usize;
+        let needed = offset + samples.len() - skip;
+        if self.rx_buffer.len() < needed {
+            self.rx_buffer.resize(needed, num::zero());
+        }
+        for (i, sample) in samples[skip..].iter().enumerate() {
+            self.rx_buffer[offset + i] += *sample;
+        }
+    }
+}
+
+impl SdrIo for LoopbackIo {
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError> {
+        let end = self.rx_next_count + buffer.len() as SampleCount;
+
+        // Our transmitter never produces samples this close to the current time,
+        // so let the other side proceed up to the end of this block.
+        if end > self.tx_silent_until {
+            self.tx_silent_until = end;
+            // If the other side is gone, nobody is listening
+            let _ = self.tx.send((end, Vec::new()));
+        }
+
+        while self.rx_connected && self.rx_complete_until < end {
+            match self.rx.recv() {
+                Ok(msg) => self.add_rx_samples(msg),
+                Err(RecvError) => {
+                    tracing::info!("Loopback peer disconnected, receiving silence");
+                    self.rx_connected = false;
+                }
+            }
+        }
+        // Also take everything else already sent, to keep the channel short
+        while let Ok(msg) = self.rx.try_recv() {
+            self.add_rx_samples(msg);
+        }
+
+        for sample in buffer.iter_mut() {
+            *sample = self.rx_buffer.pop_front().unwrap_or(num::zero());
+        }
+
+        let count = self.rx_next_count;
+        self.rx_next_count = end;
+        Ok(RxResult { len: buffer.len(), count })
+    }
+
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        let count = count.unwrap_or(self.tx_silent_until);
+        let late = (self.tx_silent_until - count).clamp(0, buffer.len() as SampleCount);
+        if late > 0 {
+            tracing::warn!("Loopback TX {} samples late, dropping them", late);
+        }
+        let start = count + late;
+        let samples = buffer[late as usize..].to_vec();
+        self.tx_silent_until = self.tx_silent_until.max(start + samples.len() as SampleCount);
+        // If the other side is gone, the signal is simply lost
+        let _ = self.tx.send((start, samples));
+        Ok(())
+    }
+
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        // The latest received sample defines the current time
+        Ok(self.rx_next_count - 1)
+    }
+
+    fn tx_possible(&self) -> bool {
+        true
+    }
+
+    fn rx_sample_rate(&self) -> f64 {
+        self.fs
+    }
+
+    fn tx_sample_rate(&self) -> f64 {
+        self.fs
+    }
+
+    fn rx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        Ok(self.rx_center_frequency)
+    }
+
+    fn tx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        Ok(self.tx_center_frequency)
+    }
+
+    fn rx_enabled(&self) -> bool {
+        true
+    }
+
+    fn tx_enabled(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loopback_pair() {
+        let (mut a, mut b) = LoopbackIo::pair(512e3, 1e6, 2e6);
+        assert_eq!(a.rx_center_frequency().unwrap(), 2e6);
+        assert_eq!(b.rx_center_frequency().unwrap(), 1e6);
+
+        let one = ComplexSample::new(1.0, 0.0);
+        let zero = ComplexSample::new(0.0, 0.0);
+        a.transmit(&[one; 4], Some(2)).unwrap();
+        a.transmit(&[], Some(8)).unwrap();
+
+        let mut buffer = [one; 8];
+        let result = b.receive(&mut buffer).unwrap();
+        assert_eq!(result.count, 0);
+        assert_eq!(buffer, [zero, zero, one, one, one, one, zero, zero]);
+
+        // Once the other side is gone, silence is received
+        drop(a);
+        let result = b.receive(&mut buffer).unwrap();
+        assert_eq!(result.count, 8);
+        assert_eq!(buffer, [zero; 8]);
+    }
+}
@@ -8,11 +8,15 @@ pub mod fir;
 pub mod history;
 pub mod modem_common;
 pub mod modulator;
+pub mod sdr_io;
 pub mod soapy_settings;
 pub mod soapy_time;
 pub mod soapyio;
 
 pub mod soapy_dev;
+
+pub mod file_io;
+pub mod loopback_io;
 // pub mod _rxtxdev_buffer;
 
 pub mod slotter;
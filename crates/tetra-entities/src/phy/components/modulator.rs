@@ -2,6 +2,7 @@ use tetra_core::TdmaTime;
 
 use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
 
+use crate::phy::components::burst_consts::{CUB_HEADBITS_OFFSET, NUB_HEADBITS_OFFSET};
 use crate::phy::components::dsp_types::*;
 use crate::phy::components::fir;
 use crate::phy::components::modem_common::*;
@@ -12,19 +13,29 @@ const SPS: SampleCount = 4;
 /// Samples per slot
 const SAMPLES_SLOT: SampleCount = SPS * 255;
 
+/// Samples per half slot (subslot)
+const SAMPLES_SUBSLOT: SampleCount = SAMPLES_SLOT / 2;
+
 /// Output sample rate
 pub const SAMPLE_RATE: f64 = 18000.0 * SPS as f64;
 
-#[derive(PartialEq)]
+#[derive(Copy, Clone, PartialEq)]
 pub enum Mode {
     /// Downlink modulation.
     Dl,
+    /// Uplink modulation for an MS.
+    /// Bursts are transmitted within the slot and subslots given by TxSlotBits,
+    /// with silence in between. Timing is taken from the downlink,
+    /// so nothing is transmitted until a reference time has been set.
+    Ul,
 }
 
 pub struct Modulator {
     mode: Mode,
     /// Sample counter value at the beginning of hyperframe number 0
     reference_time: SampleCount,
+    /// False for an uplink modulator that has not yet been given a reference time
+    synchronized: bool,
     /// Pulse shaping filter
     filter: fir::FirComplexSym,
     dqpsk: DqpskMapper,
@@ -41,11 +52,28 @@ impl Modulator {
         Self {
             mode,
             reference_time: 0,
+            synchronized: mode == Mode::Dl,
             filter: fir::FirComplexSym::new(CHANNEL_FILTER_TAPS.len()),
             dqpsk: DqpskMapper::new(),
         }
     }
 
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Set the reference time of an uplink modulator from the synchronized downlink demodulator.
+    /// None means downlink synchronization was lost, in which case transmission stops.
+    pub fn set_reference_time(&mut self, reference_time: Option<SampleCount>) {
+        match reference_time {
+            Some(reference_time) => {
+                self.reference_time = reference_time;
+                self.synchronized = true;
+            }
+            None => self.synchronized = false,
+        }
+    }
+
     /// Produce one output sample.
     pub fn sample(&mut self, sample_counter: SampleCount, tx_slot: &TxSlotBits) -> Result<ComplexSample, Error> {
         // Compensate for delay of pulse shaping filter in sample count
@@ -76,6 +104,36 @@ impl Modulator {
                     }
                 }
             }
+            Mode::Ul if !self.synchronized => {
+                // Never transmit without knowing the downlink timing
+            }
+            Mode::Ul => {
+                // Uplink slot numbering is offset from downlink by 2, see also Demodulator
+                let sample_in_slot = sample_counter - slot_begin - 2 * SAMPLES_SLOT;
+                if sample_in_slot >= SAMPLES_SLOT {
+                    return Err(Error::NeedMoreData);
+                } else if sample_in_slot >= 0 {
+                    // Bursts start after the ramp-up period at the beginning of the (sub)slot
+                    let bursts = [
+                        (tx_slot.slot, NUB_HEADBITS_OFFSET as SampleCount / 2 * SPS),
+                        (tx_slot.subslot1, CUB_HEADBITS_OFFSET as SampleCount / 2 * SPS),
+                        (tx_slot.subslot2, SAMPLES_SUBSLOT + CUB_HEADBITS_OFFSET as SampleCount / 2 * SPS),
+                    ];
+                    for (bits, burst_begin) in bursts {
+                        let Some(bits) = bits else {
+                            continue;
+                        };
+                        let sample_in_burst = sample_in_slot - burst_begin;
+                        if sample_in_burst >= 0 && sample_in_burst < bits.len() as SampleCount / 2 * SPS && sample_in_burst % SPS == 0 {
+                            if sample_in_burst == 0 {
+                                self.dqpsk.reset_phase();
+                            }
+                            let symbol_i = (sample_in_burst / SPS) as usize;
+                            sample = self.dqpsk.symbol(bits[symbol_i * 2] != 0, bits[symbol_i * 2 + 1] != 0);
+                        }
+                    }
+                }
+            }
         }
         Ok(self.filter.sample(&CHANNEL_FILTER_TAPS, sample))
     }
@@ -90,7 +148,6 @@ impl DqpskMapper {
         Self { phase: 0 }
     }
 
-    #[allow(dead_code)]
     pub fn reset_phase(&mut self) {
         self.phase = 0;
     }
@@ -0,0 +1,43 @@
+//! Sample stream interface between the modem DSP chain in soapy_dev
+//! and the device producing and consuming the signal.
+
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+
+use super::dsp_types::*;
+
+pub struct RxResult {
+    /// Number of samples read
+    pub len: usize,
+    /// Sample counter for the first sample read
+    pub count: SampleCount,
+}
+
+/// A source of received and sink of transmitted complex baseband samples.
+/// Implemented by SoapyIo for real radios, and by FileIo and LoopbackIo
+/// for running the stack without one.
+pub trait SdrIo {
+    /// Read received samples into buffer. The result tells how many samples were read
+    /// and the sample counter of the first one, which jumps forward if samples were lost.
+    fn receive(&mut self, buffer: &mut [ComplexSample]) -> Result<RxResult, RxTxDevError>;
+
+    /// Transmit samples, starting at the given sample counter if known
+    fn transmit(&mut self, buffer: &[ComplexSample], count: Option<SampleCount>) -> Result<(), RxTxDevError>;
+
+    /// Current time as TX sample count
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError>;
+
+    /// Whether transmit can be called at the moment
+    fn tx_possible(&self) -> bool;
+
+    fn rx_sample_rate(&self) -> f64;
+
+    fn tx_sample_rate(&self) -> f64;
+
+    fn rx_center_frequency(&self) -> Result<f64, RxTxDevError>;
+
+    fn tx_center_frequency(&self) -> Result<f64, RxTxDevError>;
+
+    fn rx_enabled(&self) -> bool;
+
+    fn tx_enabled(&self) -> bool;
+}
@@ -193,6 +193,45 @@ pub fn build_ndb(
     type5
 }
 
+/// Constructs a Normal Uplink Burst (Clause 9.4.4.2.3) from two blocks
+/// Training sequence determines whether blk1 and blk2 are to be considered one full slot or two half slots
+/// blk1: 216-bit BLK1 type5 bits (bkn1)
+/// blk2: 216-bit BLK2 type5 bits (bkn2)
+pub fn build_nub(train_seq: TrainingSequence, blk1: &[u8; NUB_BLK_BITS], blk2: &[u8; NUB_BLK_BITS]) -> [u8; NUB_BITS] {
+    let mut type5 = [0u8; NUB_BITS];
+
+    type5[0..NUB_BLK1_OFFSET].copy_from_slice(&bitseq::t);
+    type5[NUB_BLK1_OFFSET..NUB_TRAINING_OFFSET].copy_from_slice(blk1);
+    match train_seq {
+        TrainingSequence::NormalTrainSeq1 => {
+            type5[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET].copy_from_slice(&bitseq::n);
+        }
+        TrainingSequence::NormalTrainSeq2 => {
+            type5[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET].copy_from_slice(&bitseq::p);
+        }
+        _ => panic!(),
+    }
+    type5[NUB_BLK2_OFFSET..NUB_TAILBITS_OFFSET].copy_from_slice(blk2);
+    type5[NUB_TAILBITS_OFFSET..NUB_BITS].copy_from_slice(&bitseq::t);
+
+    type5
+}
+
+/// Constructs a Control Uplink Burst (Clause 9.4.4.2.2) from two blocks
+/// blk1: 84-bit first half of SCH/HU type5 bits (sb1)
+/// blk2: 84-bit second half of SCH/HU type5 bits (sb2)
+pub fn build_cub(blk1: &[u8; CUB_BLK_BITS], blk2: &[u8; CUB_BLK_BITS]) -> [u8; CUB_BITS] {
+    let mut type5 = [0u8; CUB_BITS];
+
+    type5[0..CUB_BLK1_OFFSET].copy_from_slice(&bitseq::t);
+    type5[CUB_BLK1_OFFSET..CUB_TRAINING_OFFSET].copy_from_slice(blk1);
+    type5[CUB_TRAINING_OFFSET..CUB_BLK2_OFFSET].copy_from_slice(&bitseq::x);
+    type5[CUB_BLK2_OFFSET..CUB_TAILBITS_OFFSET].copy_from_slice(blk2);
+    type5[CUB_TAILBITS_OFFSET..CUB_BITS].copy_from_slice(&bitseq::t);
+
+    type5
+}
+
 #[cfg(test)]
 mod tests {
     use tetra_core::bitbuffer::BitBuffer;
@@ -268,4 +307,23 @@ mod tests {
             BitBuffer::from_bitarr(&expected_burst).dump_bin()
         );
     }
+
+    #[test]
+    fn test_build_nub_and_cub_layout() {
+        let blk1 = [1u8; NUB_BLK_BITS];
+        let blk2 = [0u8; NUB_BLK_BITS];
+        let burst = build_nub(TrainingSequence::NormalTrainSeq2, &blk1, &blk2);
+        assert_eq!(burst[..NUB_BLK1_OFFSET], bitseq::t);
+        assert_eq!(burst[NUB_BLK1_OFFSET..NUB_TRAINING_OFFSET], blk1);
+        assert_eq!(burst[NUB_TRAINING_OFFSET..NUB_BLK2_OFFSET], bitseq::p);
+        assert_eq!(burst[NUB_BLK2_OFFSET..NUB_TAILBITS_OFFSET], blk2);
+        assert_eq!(burst[NUB_TAILBITS_OFFSET..], bitseq::t);
+
+        // Training sequence lands where the uplink demodulator looks for it
+        let burst = build_cub(&[0u8; CUB_BLK_BITS], &[1u8; CUB_BLK_BITS]);
+        assert_eq!(burst[CUB_TRAINING_OFFSET..CUB_BLK2_OFFSET], bitseq::x);
+        assert_eq!(CUB_HEADBITS_OFFSET + CUB_TRAINING_OFFSET, SEQ_EXT_OFFSET_SSB1);
+        assert_eq!(NUB_HEADBITS_OFFSET + NUB_TRAINING_OFFSET, SEQ_NORM_UL_OFFSET);
+        assert_eq!(burst[CUB_BLK2_OFFSET..CUB_TAILBITS_OFFSET], [1u8; CUB_BLK_BITS]);
+    }
 }
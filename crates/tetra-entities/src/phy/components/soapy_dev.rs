@@ -2,7 +2,7 @@
 //! between SDR device and modulator/demodulator code.
 
 use rustfft;
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{SharedConfig, StackMode};
 
 use tetra_pdus::phy::traits::rxtx_dev::RxSlotBits;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDev;
@@ -15,6 +15,7 @@ use super::demodulator;
 use super::dsp_types::*;
 use super::fcfb;
 use super::modulator;
+use super::sdr_io::SdrIo;
 use super::soapyio;
 
 pub struct SdrConfig<'a> {
@@ -35,22 +36,26 @@ pub struct PhyConfig<'a> {
     pub bs_dl_frequencies: &'a [f64],
     /// Uplink carrier frequencies for a BS.
     pub bs_ul_frequencies: &'a [f64],
+    /// Uplink carrier frequencies for an MS.
+    /// Timing is taken from the first monitored downlink.
+    pub ms_ul_frequencies: &'a [f64],
 }
 
-pub struct RxTxDevSoapySdr {
-    sdr: soapyio::SoapyIo,
+/// Modem DSP chain running on top of a source and sink of baseband samples
+pub struct RxTxDevSdr<Io: SdrIo> {
+    sdr: Io,
     rx_dsp: Option<RxDsp>,
     tx_dsp: Option<TxDsp>,
 }
 
+pub type RxTxDevSoapySdr = RxTxDevSdr<soapyio::SoapyIo>;
+
 type FftPlanner = rustfft::FftPlanner<RealSample>;
 
 impl RxTxDevSoapySdr {
     pub fn new(cfg: &SharedConfig) -> Self {
-        let mut fft_planner = rustfft::FftPlanner::new();
-
-        // TODO FIXME currently no MS and MON support in the below statement; need to fix
         let config_guard = cfg.config();
+        let stack_mode = config_guard.stack_mode;
         let soapy_cfg = config_guard
             .as_ref()
             .phy_io
@@ -72,13 +77,97 @@ impl RxTxDevSoapySdr {
             ul_corrected / 1e6
         );
 
-        let phy_config = soapy_dev::PhyConfig {
-            bs_dl_frequencies: &[dl_corrected],
-            bs_ul_frequencies: &[ul_corrected],
-            ..Default::default()
-        };
+        // Secondary carriers get the same PPM correction as the main carrier
+        let mut carriers = vec![(dl_corrected, ul_corrected)];
+        for &carrier_num in &config_guard.cell.secondary_carriers {
+            let freq_info = config_guard
+                .cell
+                .carrier_freq_info(carrier_num)
+                .expect("Secondary carrier frequencies validated in config");
+            let (dl_freq, ul_freq) = freq_info.get_freqs();
+            let (dl_freq, ul_freq) = (dl_freq as f64, ul_freq as f64);
+            let dl_corrected = dl_freq + dl_freq / 1_000_000.0 * soapy_cfg.ppm_err;
+            let ul_corrected = ul_freq + ul_freq / 1_000_000.0 * soapy_cfg.ppm_err;
+            tracing::info!(
+                "Secondary carrier {}: DL / UL: {:.6} MHz / {:.6} MHz, adj {:.6} MHz / {:.6} MHz",
+                carrier_num,
+                dl_freq / 1e6,
+                ul_freq / 1e6,
+                dl_corrected / 1e6,
+                ul_corrected / 1e6
+            );
+            carriers.push((dl_corrected, ul_corrected));
+        }
 
-        let mut sdr = soapyio::SoapyIo::new(cfg).unwrap();
+        let sdr = soapyio::SoapyIo::new(cfg).unwrap();
+        Self::with_io_carriers(stack_mode, sdr, &carriers)
+    }
+}
+
+impl<Io: SdrIo> RxTxDevSdr<Io> {
+    /// Set up the DSP chain for the given stack mode and carrier frequencies
+    /// on an already opened sample source and sink.
+    pub fn with_io(stack_mode: StackMode, sdr: Io, dl_freq: f64, ul_freq: f64) -> Self {
+        Self::with_io_carriers(stack_mode, sdr, &[(dl_freq, ul_freq)])
+    }
+
+    /// Set up the DSP chain for a BS driving several carriers, given as (downlink, uplink)
+    /// frequency pairs with the main carrier first. Other stack modes only use the main carrier.
+    pub fn with_io_carriers(stack_mode: StackMode, mut sdr: Io, carriers: &[(f64, f64)]) -> Self {
+        let (dl_freq, ul_freq) = carriers[0];
+        let mut fft_planner = rustfft::FftPlanner::new();
+
+        let monitor_frequencies: Vec<(f64, Option<f64>)>;
+        let bs_dl_frequencies: Vec<f64>;
+        let bs_ul_frequencies: Vec<f64>;
+        let phy_config = match stack_mode {
+            StackMode::Mon => {
+                // Only monitor the uplink if it fits within the received band
+                let ul_freq = if monitor_fits_in_band(&sdr, ul_freq) {
+                    Some(ul_freq)
+                } else {
+                    tracing::warn!(
+                        "UL frequency {:.6} MHz outside of received band at {:.3} MHz sample rate, monitoring DL only",
+                        ul_freq / 1e6,
+                        sdr.rx_sample_rate() / 1e6
+                    );
+                    None
+                };
+                monitor_frequencies = vec![(dl_freq, ul_freq)];
+                soapy_dev::PhyConfig {
+                    monitor_frequencies: &monitor_frequencies,
+                    ..Default::default()
+                }
+            }
+            StackMode::Ms => {
+                monitor_frequencies = vec![(dl_freq, None)];
+                soapy_dev::PhyConfig {
+                    monitor_frequencies: &monitor_frequencies,
+                    ms_ul_frequencies: &[ul_freq],
+                    ..Default::default()
+                }
+            }
+            _ => {
+                // Calls are allocated on every configured carrier, so one the SDR can't reach is a configuration error
+                for &(dl_freq, ul_freq) in &carriers[1..] {
+                    assert!(
+                        carrier_fits_in_band(sdr.tx_center_frequency(), sdr.tx_sample_rate(), dl_freq)
+                            && carrier_fits_in_band(sdr.rx_center_frequency(), sdr.rx_sample_rate(), ul_freq),
+                        "Secondary carrier {:.6} / {:.6} MHz outside of SDR band at {:.3} MHz sample rate, check cell.secondary_carriers",
+                        dl_freq / 1e6,
+                        ul_freq / 1e6,
+                        sdr.tx_sample_rate() / 1e6
+                    );
+                }
+                bs_dl_frequencies = carriers.iter().map(|&(dl, _)| dl).collect();
+                bs_ul_frequencies = carriers.iter().map(|&(_, ul)| ul).collect();
+                soapy_dev::PhyConfig {
+                    bs_dl_frequencies: &bs_dl_frequencies,
+                    bs_ul_frequencies: &bs_ul_frequencies,
+                    ..Default::default()
+                }
+            }
+        };
 
         Self {
             rx_dsp: if sdr.rx_enabled() {
@@ -115,7 +204,13 @@ impl RxTxDevSoapySdr {
     fn process_tx_block(&mut self, tx_slot: &[TxSlotBits]) -> Result<bool, RxTxDevError> {
         if let Some(tx_dsp) = &mut self.tx_dsp {
             if self.sdr.tx_possible() {
-                tx_dsp.process_block(&mut self.sdr, self.rx_dsp.as_ref().map(|rx_dsp| rx_dsp.rx_block_count), tx_slot)
+                let rx_dsp = self.rx_dsp.as_ref();
+                tx_dsp.process_block(
+                    &mut self.sdr,
+                    rx_dsp.map(|rx_dsp| rx_dsp.rx_block_count),
+                    rx_dsp.and_then(|rx_dsp| rx_dsp.dl_reference_time()),
+                    tx_slot,
+                )
             } else {
                 Ok(false)
             }
@@ -125,7 +220,7 @@ impl RxTxDevSoapySdr {
     }
 }
 
-impl RxTxDev for RxTxDevSoapySdr {
+impl<Io: SdrIo> RxTxDev for RxTxDevSdr<Io> {
     fn rxtx_timeslot<'a>(
         &'a mut self,
         tx_slot: &[TxSlotBits],
@@ -161,7 +256,7 @@ struct RxDsp {
 }
 
 impl RxDsp {
-    fn new(fft_planner: &mut FftPlanner, sdr: &mut soapyio::SoapyIo, phy_config: &PhyConfig) -> Self {
+    fn new(fft_planner: &mut FftPlanner, sdr: &mut impl SdrIo, phy_config: &PhyConfig) -> Self {
         let sdr_sample_rate = sdr.rx_sample_rate();
         let rx_fcfb_params = fcfb::AnalysisInputParameters {
             // Use a bin spacing of 500 Hz.
@@ -202,7 +297,7 @@ impl RxDsp {
         }
     }
 
-    fn process_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<bool, RxTxDevError> {
+    fn process_block(&mut self, sdr: &mut impl SdrIo) -> Result<bool, RxTxDevError> {
         self.receive_block(sdr)?;
 
         let fcfb_result = self.rx_fcfb.process(&self.rx_buffer[..], self.rx_block_count);
@@ -226,7 +321,7 @@ impl RxDsp {
         Ok(continue_processing)
     }
 
-    fn receive_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<(), RxTxDevError> {
+    fn receive_block(&mut self, sdr: &mut impl SdrIo) -> Result<(), RxTxDevError> {
         self.rx_block_count += 1;
 
         // Copy overlapping part from previous block to the beginning
@@ -284,6 +379,11 @@ impl RxDsp {
         }
     }
 
+    /// Reference time of the first synchronized downlink, if any
+    fn dl_reference_time(&self) -> Option<SampleCount> {
+        self.monitors.first().and_then(|pair| pair.dl.demodulator.dl_reference_time())
+    }
+
     fn take_slot_bits<'a>(&'a mut self) -> Vec<Option<RxSlotBits<'a>>> {
         // TODO: avoid dynamic allocation here?
         let mut slot_bits = Vec::with_capacity(2 * self.monitors.len() + self.ul_demodulators.len());
@@ -313,7 +413,7 @@ struct TxDsp {
 }
 
 impl TxDsp {
-    fn new(fft_planner: &mut FftPlanner, sdr: &mut soapyio::SoapyIo, phy_config: &PhyConfig) -> Self {
+    fn new(fft_planner: &mut FftPlanner, sdr: &mut impl SdrIo, phy_config: &PhyConfig) -> Self {
         let sdr_sample_rate = sdr.tx_sample_rate();
         let fcfb_params = fcfb::SynthesisOutputParameters {
             ifft_size: (sdr_sample_rate / 500.0).round() as usize,
@@ -328,6 +428,9 @@ impl TxDsp {
         for dl_freq in phy_config.bs_dl_frequencies {
             modulators.push(ModulatorChannel::new(fft_planner, fcfb_params, *dl_freq, modulator::Mode::Dl));
         }
+        for ul_freq in phy_config.ms_ul_frequencies {
+            modulators.push(ModulatorChannel::new(fft_planner, fcfb_params, *ul_freq, modulator::Mode::Ul));
+        }
 
         Self {
             fcfb,
@@ -339,8 +442,9 @@ impl TxDsp {
 
     fn process_block(
         &mut self,
-        sdr: &mut soapyio::SoapyIo,
+        sdr: &mut impl SdrIo,
         latest_rx_block: Option<fcfb::BlockCount>,
+        dl_reference_time: Option<SampleCount>,
         tx_slot: &[TxSlotBits],
     ) -> Result<bool, RxTxDevError> {
         let current_sample = sdr.tx_current_count()?;
@@ -380,6 +484,10 @@ impl TxDsp {
         }
 
         for (modulator, tx_slot) in self.modulators.iter_mut().zip(tx_slot) {
+            if modulator.modulator.mode() == modulator::Mode::Ul {
+                // TODO: compensate for different RX and TX delays of SDR
+                modulator.modulator.set_reference_time(dl_reference_time);
+            }
             if !modulator.process(&mut self.fcfb, self.block_count, tx_slot) {
                 return Ok(false);
             }
@@ -500,6 +608,19 @@ impl ModulatorChannel {
     }
 }
 
+/// Returns true if a carrier at freq can be demodulated given the current RX center frequency and sample rate.
+/// Leaves some margin at the band edges, where the SDR filters roll off.
+fn monitor_fits_in_band(sdr: &impl SdrIo, freq: f64) -> bool {
+    carrier_fits_in_band(sdr.rx_center_frequency(), sdr.rx_sample_rate(), freq)
+}
+
+fn carrier_fits_in_band(center: Result<f64, RxTxDevError>, sample_rate: f64, freq: f64) -> bool {
+    let Ok(center) = center else {
+        return false;
+    };
+    (freq - center).abs() + 25e3 < sample_rate * 0.4
+}
+
 struct MonitorDlUlPair {
     dl: DemodulatorChannel,
     ul: Option<DemodulatorChannel>,
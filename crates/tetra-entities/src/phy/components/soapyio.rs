@@ -4,6 +4,7 @@ use tetra_config::bluestation::{SharedConfig, StackMode, sec_phy_soapy::CfgSoapy
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
 
 use super::dsp_types::*;
+use super::sdr_io::{RxResult, SdrIo};
 use super::soapy_settings;
 use super::soapy_settings::{SdrSettings, SupportedDevice};
 use super::soapy_time::{ticks_to_time_ns, time_ns_to_ticks};
@@ -11,13 +12,6 @@ use super::soapy_time::{ticks_to_time_ns, time_ns_to_ticks};
 type StreamType = ComplexSample;
 const SOAPY_FREQ_OFFSET: f64 = 20000.0;
 
-pub struct RxResult {
-    /// Number of samples read
-    pub len: usize,
-    /// Sample counter for the first sample read
-    pub count: SampleCount,
-}
-
 pub struct SoapyIo {
     rx_ch: usize,
     tx_ch: usize,
@@ -89,9 +83,12 @@ impl SoapyIo {
                 Some(dl_corrected - SOAPY_FREQ_OFFSET), // Offset RX center frequency from carrier frequency
                 Some(ul_corrected),
             ),
-            StackMode::Mon => {
-                unimplemented!("Monitor mode not implemented yet");
-            }
+            StackMode::Mon => (
+                // Center RX between downlink and uplink so both carriers fit in the band.
+                // A monitor never transmits.
+                Some((dl_corrected + ul_corrected) / 2.0 - SOAPY_FREQ_OFFSET),
+                None,
+            ),
         };
 
         let rx_enabled = rx_freq.is_some();
@@ -336,6 +333,48 @@ impl SoapyIo {
     }
 }
 
+impl SdrIo for SoapyIo {
+    fn receive(&mut self, buffer: &mut [StreamType]) -> Result<RxResult, RxTxDevError> {
+        SoapyIo::receive(self, buffer)
+    }
+
+    fn transmit(&mut self, buffer: &[StreamType], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        SoapyIo::transmit(self, buffer, count)
+    }
+
+    fn tx_current_count(&self) -> Result<SampleCount, RxTxDevError> {
+        SoapyIo::tx_current_count(self)
+    }
+
+    fn tx_possible(&self) -> bool {
+        SoapyIo::tx_possible(self)
+    }
+
+    fn rx_sample_rate(&self) -> f64 {
+        self.rx_fs
+    }
+
+    fn tx_sample_rate(&self) -> f64 {
+        self.tx_fs
+    }
+
+    fn rx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        SoapyIo::rx_center_frequency(self).map_err(|_| RxTxDevError::RxReadError)
+    }
+
+    fn tx_center_frequency(&self) -> Result<f64, RxTxDevError> {
+        SoapyIo::tx_center_frequency(self).map_err(|_| RxTxDevError::RxReadError)
+    }
+
+    fn rx_enabled(&self) -> bool {
+        SoapyIo::rx_enabled(self)
+    }
+
+    fn tx_enabled(&self) -> bool {
+        SoapyIo::tx_enabled(self)
+    }
+}
+
 // Messy logic related to opening a device follows...
 
 /// Struct to temporarily hold stuff related to opening and detecting a device
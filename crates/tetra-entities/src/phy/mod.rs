@@ -1,3 +1,5 @@
 pub mod components;
 
 pub mod phy_bs;
+pub mod phy_mon;
+pub mod phy_ms;
@@ -1,12 +1,12 @@
 use crossbeam_channel::Sender;
 use std::panic;
 
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{PhyBackend, SharedConfig};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
 use tetra_pdus::phy::traits::rxtx_dev::RxBurstBits;
-use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, TxSlotBits};
-use tetra_saps::tp::TpUnitdataInd;
+use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::phy::components::phy_io_file::{FileWriteMsg, PhyIoFileMode};
@@ -33,17 +33,29 @@ pub struct PhyBs<D: RxTxDev> {
     /// RX/TX device
     rxtxdev: D,
 
+    /// Bursts for the secondary carriers, waiting to be transmitted along with the next main carrier burst.
+    /// Index 0 = carrier 1
+    secondary_bursts: Vec<Option<[u8; TIMESLOT_TYPE4_BITS]>>,
+
+    /// Set when the RX device reported end of data, after which the stack stops
+    rx_ended: bool,
+
     tick: u64,
 }
 
 impl<D: RxTxDev> PhyBs<D> {
     pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        let secondary_carriers = config.config().cell.secondary_carriers.len();
         let c = &config.config().phy_io;
 
+        // With the File backend, dl_tx_file and ul_input_file carry the IQ signal instead of burst bits
+        let iq_files = c.backend == PhyBackend::File;
+
         // Create async writers for file logging of generated DL and received UL signals
         let dl_tx_logger = c
             .dl_tx_file
             .as_ref()
+            .filter(|_| !iq_files)
             .and_then(|f| PhyIoFile::create_async_writer(f, "dl_tx_logger".to_string()).ok());
         let ul_rx_logger = c
             .ul_rx_file
@@ -51,16 +63,15 @@ impl<D: RxTxDev> PhyBs<D> {
             .and_then(|f| PhyIoFile::create_async_writer(f, "ul_rx_logger".to_string()).ok());
 
         // Open input files overriding either generated DL or received UL data
-        let dl_input_file = if let Some(ref f) = c.dl_input_file {
-            Some(PhyIoFile::new(f, PhyIoFileMode::ReadRepeat).expect("Failed to open dl_input_file"))
-        } else {
-            None
-        };
-        let ul_input_file = if let Some(ref f) = c.ul_input_file {
-            Some(PhyIoFile::new(f, PhyIoFileMode::Read).expect("Failed to open ul_input_file"))
-        } else {
-            None
-        };
+        let dl_input_file = c
+            .dl_input_file
+            .as_ref()
+            .map(|f| PhyIoFile::new(f, PhyIoFileMode::ReadRepeat).expect("Failed to open dl_input_file"));
+        let ul_input_file = c
+            .ul_input_file
+            .as_ref()
+            .filter(|_| !iq_files)
+            .map(|f| PhyIoFile::new(f, PhyIoFileMode::Read).expect("Failed to open ul_input_file"));
 
         Self {
             config,
@@ -70,19 +81,13 @@ impl<D: RxTxDev> PhyBs<D> {
             dl_input_file,
             ul_input_file,
             rxtxdev,
+            secondary_bursts: vec![None; secondary_carriers],
+            rx_ended: false,
             tick: 0,
         }
     }
 
-    fn send_rxblock_to_lmac(
-        queue: &mut MessageQueue,
-        train_type: TrainingSequence,
-        burst_type: BurstType,
-        block_type: PhyBlockType,
-        block_num: PhyBlockNum,
-        bits: BitBuffer,
-        dltime: TdmaTime,
-    ) {
+    fn send_rxblock_to_lmac(queue: &mut MessageQueue, prim: TpUnitdataInd, dltime: TdmaTime) {
         // Uplink timeslot is two after downlink. Thus was transmitted at dltime - 2
         let msg_ts = dltime.add_timeslots(-2);
         let sapmsg = SapMsg {
@@ -90,20 +95,14 @@ impl<D: RxTxDev> PhyBs<D> {
             src: TetraEntity::Phy,
             dest: TetraEntity::Lmac,
             dltime: msg_ts,
-            msg: SapMsgInner::TpUnitdataInd(TpUnitdataInd {
-                train_type,
-                burst_type,
-                block_type,
-                block_num,
-                block: bits,
-            }),
+            msg: SapMsgInner::TpUnitdataInd(prim),
         };
         queue.push_back(sapmsg);
     }
 
-    fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime) {
+    pub(crate) fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, carrier: u8, dltime: TdmaTime) {
         let train_seq = burst.train_type;
-        match train_seq {
+        let (burst_type, block_type, blocks) = match train_seq {
             TrainingSequence::NormalTrainSeq1 => {
                 assert!(burst.bits.len() == NUB_BITS);
 
@@ -112,7 +111,7 @@ impl<D: RxTxDev> PhyBs<D> {
                 blk.copy_bits_from_bitarr(&burst.bits[NUB_BLK2_OFFSET..NUB_BLK2_OFFSET + NUB_BLK_BITS]);
                 blk.seek(0);
 
-                Self::send_rxblock_to_lmac(queue, train_seq, BurstType::NUB, PhyBlockType::NUB, PhyBlockNum::Both, blk, dltime);
+                (BurstType::NUB, PhyBlockType::NUB, vec![(PhyBlockNum::Both, blk)])
             }
 
             TrainingSequence::NormalTrainSeq2 => {
@@ -121,24 +120,11 @@ impl<D: RxTxDev> PhyBs<D> {
                 let blk1 = BitBuffer::from_bitarr(&burst.bits[NUB_BLK1_OFFSET..NUB_BLK1_OFFSET + NUB_BLK_BITS]);
                 let blk2 = BitBuffer::from_bitarr(&burst.bits[NUB_BLK2_OFFSET..NUB_BLK2_OFFSET + NUB_BLK_BITS]);
 
-                Self::send_rxblock_to_lmac(
-                    queue,
-                    train_seq,
+                (
                     BurstType::NUB,
                     PhyBlockType::NUB,
-                    PhyBlockNum::Block1,
-                    blk1,
-                    dltime,
-                );
-                Self::send_rxblock_to_lmac(
-                    queue,
-                    train_seq,
-                    BurstType::NUB,
-                    PhyBlockType::NUB,
-                    PhyBlockNum::Block2,
-                    blk2,
-                    dltime,
-                );
+                    vec![(PhyBlockNum::Block1, blk1), (PhyBlockNum::Block2, blk2)],
+                )
             }
             TrainingSequence::ExtendedTrainSeq => {
                 assert!(burst.bits.len() == CUB_BITS);
@@ -148,17 +134,69 @@ impl<D: RxTxDev> PhyBs<D> {
                 blk.copy_bits_from_bitarr(&burst.bits[CUB_BLK2_OFFSET..CUB_BLK2_OFFSET + CUB_BLK_BITS]);
                 blk.seek(0);
 
-                Self::send_rxblock_to_lmac(
-                    queue,
-                    train_seq,
-                    BurstType::CUB,
-                    PhyBlockType::SSN1,
-                    PhyBlockNum::Block1,
-                    blk,
-                    dltime,
-                );
+                (BurstType::CUB, PhyBlockType::SSN1, vec![(PhyBlockNum::Block1, blk)])
+            }
+
+            _ => panic!(),
+        };
+
+        for (block_num, block) in blocks {
+            let prim = TpUnitdataInd {
+                carrier,
+                train_type: train_seq,
+                burst_type,
+                block_type,
+                block_num,
+                block,
+            };
+            Self::send_rxblock_to_lmac(queue, prim, dltime);
+        }
+    }
+
+    /// Builds the burst for a slot received from LMAC
+    fn build_dl_burst(prim: TpUnitdataReqSlot) -> [u8; TIMESLOT_TYPE4_BITS] {
+        // Convert BBK block to bitarr
+        assert!(prim.bbk.is_some());
+        let mut bbk = [0u8; 30];
+        prim.bbk.unwrap().to_bitarr(&mut bbk);
+
+        // Build NDB or SDB burst
+        match prim.burst_type {
+            BurstType::SDB => {
+                // SDB burst
+                assert!(prim.train_type == TrainingSequence::SyncTrainSeq);
+                assert!(prim.blk1.is_some() && prim.blk2.is_some());
+
+                let mut blk1 = [0u8; 120];
+                let mut blk2 = [0u8; 216];
+                prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for SDB
+                prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for SDB
+
+                slotter::build_sdb(&blk1, &bbk, &blk2)
             }
+            BurstType::NDB => {
+                let mut blk1 = [0u8; 216];
+                let mut blk2 = [0u8; 216];
+
+                match prim.train_type {
+                    TrainingSequence::NormalTrainSeq1 => {
+                        // Single large block
+                        assert!(prim.blk1.is_some() && prim.blk2.is_none());
+                        let mut blk1_src = prim.blk1.unwrap(); // Guaranteed for NDB
+                        blk1_src.to_bitarr(&mut blk1);
+                        blk1_src.to_bitarr(&mut blk2);
+                    }
+                    TrainingSequence::NormalTrainSeq2 => {
+                        // Two half slots
+                        assert!(prim.blk1.is_some() && prim.blk2.is_some());
+                        prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for NDB
+                        prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for NDB trainseq 2
+                    }
+                    _ => panic!("Unsupported training sequence for NDB burst"),
+                }
 
+                slotter::build_ndb(prim.train_type, &blk1, &bbk, &blk2)
+            }
             _ => panic!(),
         }
     }
@@ -168,68 +206,46 @@ impl<D: RxTxDev> PhyBs<D> {
         // Prepare TxSlotBits for transmission
         // TODO FIXME: optimize
 
-        self.tick += 1;
-
         let SapMsgInner::TpUnitdataReq(prim) = message.msg else { panic!() };
 
+        // Secondary carrier slots are passed down ahead of the main carrier slot,
+        // which then triggers transmission of all carriers at once
+        if prim.carrier != 0 {
+            match self.secondary_bursts.get_mut(prim.carrier as usize - 1) {
+                Some(burst) => *burst = Some(Self::build_dl_burst(prim)),
+                None => tracing::warn!("rx_tpsap_prim: no secondary carrier {}, dropping slot", prim.carrier),
+            }
+            return;
+        }
+
+        if self.rx_ended {
+            return;
+        }
+        self.tick += 1;
+
         // Generate block (from file or from LMAC data)
         let mut dl_burst = [0u8; TIMESLOT_TYPE4_BITS];
         if let Some(dl_input_file) = &mut self.dl_input_file {
             // Code for testing mode, when replaying from DL input file
             dl_input_file.read_block(&mut dl_burst).expect("Failed to read dl_input_file data");
         } else {
-            // We received data from LMAC, convert BBK block to bitarr
-            assert!(prim.bbk.is_some());
-            let mut bbk = [0u8; 30];
-            prim.bbk.unwrap().to_bitarr(&mut bbk);
-
-            // Build NDB or SDB burst
-            dl_burst = match prim.burst_type {
-                BurstType::SDB => {
-                    // SDB burst
-                    assert!(prim.train_type == TrainingSequence::SyncTrainSeq);
-                    assert!(prim.blk1.is_some() && prim.blk2.is_some());
-
-                    let mut blk1 = [0u8; 120];
-                    let mut blk2 = [0u8; 216];
-                    prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for SDB
-                    prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for SDB
-
-                    slotter::build_sdb(&blk1, &bbk, &blk2)
-                }
-                BurstType::NDB => {
-                    let mut blk1 = [0u8; 216];
-                    let mut blk2 = [0u8; 216];
-
-                    match prim.train_type {
-                        TrainingSequence::NormalTrainSeq1 => {
-                            // Single large block
-                            assert!(prim.blk1.is_some() && prim.blk2.is_none());
-                            let mut blk1_src = prim.blk1.unwrap(); // Guaranteed for NDB
-                            blk1_src.to_bitarr(&mut blk1);
-                            blk1_src.to_bitarr(&mut blk2);
-                        }
-                        TrainingSequence::NormalTrainSeq2 => {
-                            // Two half slots
-                            assert!(prim.blk1.is_some() && prim.blk2.is_some());
-                            prim.blk1.unwrap().to_bitarr(&mut blk1); // Guaranteed for NDB
-                            prim.blk2.unwrap().to_bitarr(&mut blk2); // Guaranteed for NDB trainseq 2
-                        }
-                        _ => panic!("Unsupported training sequence for NDB burst"),
-                    }
-
-                    slotter::build_ndb(prim.train_type, &blk1, &bbk, &blk2)
-                }
-                _ => panic!(),
-            };
+            dl_burst = Self::build_dl_burst(prim);
         }
 
-        // Prepare the TX slot for the tx device
-        let tx_slot: [TxSlotBits; 1] = [TxSlotBits {
-            time: message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32),
+        // Prepare the TX slot for the tx device, one per carrier.
+        // Secondary carriers without a burst for this slot stay silent.
+        let time = message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
+        let secondary_bursts: Vec<_> = self.secondary_bursts.iter_mut().map(Option::take).collect();
+        let mut tx_slot = vec![TxSlotBits {
+            time,
             slot: Some(&dl_burst),
             ..Default::default()
         }];
+        tx_slot.extend(secondary_bursts.iter().map(|burst| TxSlotBits {
+            time,
+            slot: burst.as_ref().map(|b| &b[..]),
+            ..Default::default()
+        }));
 
         // Code for testing mode, when capturing all DL output to file
         if let Some(dl_tx_sender) = &self.dl_tx_sender {
@@ -239,7 +255,15 @@ impl<D: RxTxDev> PhyBs<D> {
         // Transmit slot and receive rx data (if any trainseq was found)
         // This function is blocking and the source of timing sync in the whole stack
         // let tick_done = std::time::Instant::now();
-        let rx = self.rxtxdev.rxtx_timeslot(&tx_slot).expect("Got error from rxtx_timeslot");
+        let rx = match self.rxtxdev.rxtx_timeslot(&tx_slot) {
+            Ok(rx) => rx,
+            Err(RxTxDevError::RxEndOfData) => {
+                tracing::info!("rxtx_timeslot: end of RX data, stopping");
+                self.rx_ended = true;
+                return;
+            }
+            Err(e) => panic!("Got error from rxtx_timeslot: {:?}", e),
+        };
         // let new_tick_start = std::time::Instant::now();
         // let elapsed = new_tick_start.duration_since(tick_done);
         // tracing::debug!("rxtx_timeslot: tick_done {:?}, new_tick_start {:?}, elapsed {:?}", tick_done, new_tick_start, elapsed);
@@ -248,22 +272,24 @@ impl<D: RxTxDev> PhyBs<D> {
         // In exceptional cases, we might receive multiple slots (multiple possible detected bursts in one timeslot)
         // This may be due to two subslots, or due to false psoitives in training seq detection
         // The Lmac error correction will eliminate the false positives
-        for rx_slot in rx {
+        // One slot is returned per uplink carrier, in carrier order
+        for (carrier, rx_slot) in rx.into_iter().enumerate() {
+            let carrier = carrier as u8;
             if let Some(rx_slot) = rx_slot {
                 let mut slot_sent = false;
                 if rx_slot.slot.train_type != TrainingSequence::NotFound {
-                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in fullslot", rx_slot.slot.train_type);
+                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in fullslot on carrier {}", rx_slot.slot.train_type, carrier);
 
                     if let Some(ul_rx_sender) = &self.ul_rx_sender {
                         // Log received data to file (non-blocking)
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(3, self.tick, rx_slot.slot.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, carrier, self.dltime);
                     slot_sent = true;
                 }
                 if rx_slot.subslot1.train_type != TrainingSequence::NotFound {
-                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in subslot1", rx_slot.subslot1.train_type);
+                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in subslot1 on carrier {}", rx_slot.subslot1.train_type, carrier);
                     if slot_sent {
                         tracing::warn!("Sending same burst twice to LMAC");
                     }
@@ -272,11 +298,11 @@ impl<D: RxTxDev> PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(1, self.tick, rx_slot.subslot1.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, carrier, self.dltime);
                     slot_sent = true;
                 }
                 if rx_slot.subslot2.train_type != TrainingSequence::NotFound {
-                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in subslot2", rx_slot.subslot2.train_type);
+                    tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in subslot2 on carrier {}", rx_slot.subslot2.train_type, carrier);
                     if slot_sent {
                         tracing::warn!("Sending same burst twice to LMAC");
                     }
@@ -285,7 +311,7 @@ impl<D: RxTxDev> PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(2, self.tick, rx_slot.subslot2.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, carrier, self.dltime);
                 }
             }
         }
@@ -301,6 +327,10 @@ impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyBs<D> {
         TetraEntity::Phy
     }
 
+    fn is_finished(&self) -> bool {
+        self.rx_ended
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
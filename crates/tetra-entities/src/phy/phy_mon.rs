@@ -0,0 +1,214 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
+use tetra_pdus::phy::traits::rxtx_dev::{RxBurstBits, RxTxDev};
+use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::phy::components::train_consts::TIMESLOT_TYPE4_BITS;
+use crate::phy::phy_bs::PhyBs;
+use crate::{MessageQueue, TetraEntityTrait};
+
+/// Receive-only Phy for monitor mode.
+/// The RX/TX device delivers slots as [dl, ul] pairs for every monitored carrier, where the DL demodulator
+/// recovers timing from SYNC bursts and the UL demodulator (if any) follows the DL timing.
+/// Slot times are relative to the first SYNC burst seen. The Lmac maps them to network time.
+pub struct PhyMon<D: RxTxDev> {
+    config: SharedConfig,
+
+    /// RX device. Never transmits.
+    rxtxdev: D,
+
+    /// Set when the RX device reported end of data, after which we stop polling it
+    rx_ended: bool,
+}
+
+impl<D: RxTxDev> PhyMon<D> {
+    pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        Self {
+            config,
+            rxtxdev,
+            rx_ended: false,
+        }
+    }
+
+    fn send_rxblock_to_lmac(
+        queue: &mut MessageQueue,
+        train_type: TrainingSequence,
+        burst_type: BurstType,
+        block_type: PhyBlockType,
+        block_num: PhyBlockNum,
+        bits: &[u8],
+        slot_time: TdmaTime,
+    ) {
+        let sapmsg = SapMsg {
+            sap: Sap::TpSap,
+            src: TetraEntity::Phy,
+            dest: TetraEntity::Lmac,
+            dltime: slot_time,
+            msg: SapMsgInner::TpUnitdataInd(TpUnitdataInd {
+                carrier: 0,
+                train_type,
+                burst_type,
+                block_type,
+                block_num,
+                block: BitBuffer::from_bitarr(bits),
+            }),
+        };
+        queue.push_back(sapmsg);
+    }
+
+    /// Splits a demodulated downlink timeslot into its blocks. Layout as in slotter::build_sdb and slotter::build_ndb.
+    /// The BBK is sent first, since the AACH it contains determines how the Lmac interprets the other blocks.
+    pub(crate) fn split_dl_slot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, slot_time: TdmaTime) {
+        if burst.bits.len() != TIMESLOT_TYPE4_BITS {
+            tracing::warn!("split_dl_slot: unexpected burst length {}", burst.bits.len());
+            return;
+        }
+        let b = burst.bits;
+        let train_type = burst.train_type;
+
+        match train_type {
+            TrainingSequence::SyncTrainSeq => {
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_type,
+                    BurstType::SDB,
+                    PhyBlockType::BBK,
+                    PhyBlockNum::Undefined,
+                    &b[252..282],
+                    slot_time,
+                );
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_type,
+                    BurstType::SDB,
+                    PhyBlockType::SB1,
+                    PhyBlockNum::Block1,
+                    &b[94..214],
+                    slot_time,
+                );
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_type,
+                    BurstType::SDB,
+                    PhyBlockType::SB2,
+                    PhyBlockNum::Block2,
+                    &b[282..498],
+                    slot_time,
+                );
+            }
+            TrainingSequence::NormalTrainSeq1 | TrainingSequence::NormalTrainSeq2 => {
+                // Broadcast block is split around the training sequence
+                let mut bbk = [0u8; 30];
+                bbk[..14].copy_from_slice(&b[230..244]);
+                bbk[14..].copy_from_slice(&b[266..282]);
+                Self::send_rxblock_to_lmac(
+                    queue,
+                    train_type,
+                    BurstType::NDB,
+                    PhyBlockType::BBK,
+                    PhyBlockNum::Undefined,
+                    &bbk,
+                    slot_time,
+                );
+
+                if train_type == TrainingSequence::NormalTrainSeq1 {
+                    // Single full slot block
+                    let mut blk = [0u8; 432];
+                    blk[..216].copy_from_slice(&b[14..230]);
+                    blk[216..].copy_from_slice(&b[282..498]);
+                    Self::send_rxblock_to_lmac(
+                        queue,
+                        train_type,
+                        BurstType::NDB,
+                        PhyBlockType::NDB,
+                        PhyBlockNum::Both,
+                        &blk,
+                        slot_time,
+                    );
+                } else {
+                    // Two half slot blocks
+                    Self::send_rxblock_to_lmac(
+                        queue,
+                        train_type,
+                        BurstType::NDB,
+                        PhyBlockType::NDB,
+                        PhyBlockNum::Block1,
+                        &b[14..230],
+                        slot_time,
+                    );
+                    Self::send_rxblock_to_lmac(
+                        queue,
+                        train_type,
+                        BurstType::NDB,
+                        PhyBlockType::NDB,
+                        PhyBlockNum::Block2,
+                        &b[282..498],
+                        slot_time,
+                    );
+                }
+            }
+            _ => {
+                tracing::debug!("split_dl_slot: ignoring burst with {:?}", train_type);
+            }
+        }
+    }
+
+    fn rx_slots(&mut self, queue: &mut MessageQueue) {
+        if self.rx_ended {
+            return;
+        }
+
+        let rx = match self.rxtxdev.rxtx_timeslot(&[]) {
+            Ok(rx) => rx,
+            Err(e) => {
+                tracing::warn!("rxtx_timeslot: {:?}, no more slots will be received", e);
+                self.rx_ended = true;
+                return;
+            }
+        };
+
+        // Even entries are downlink slots, odd entries the corresponding uplink slots
+        for (i, rx_slot) in rx.into_iter().enumerate() {
+            let Some(rx_slot) = rx_slot else {
+                continue;
+            };
+            if i % 2 == 0 {
+                if rx_slot.slot.train_type != TrainingSequence::NotFound {
+                    tracing::debug!(ts=%rx_slot.time, "rx_slots: DL {:?}", rx_slot.slot.train_type);
+                    Self::split_dl_slot_and_send_to_lmac(queue, &rx_slot.slot, rx_slot.time);
+                }
+            } else {
+                // The uplink demodulator already reports uplink time, while PhyBs derives it from downlink time
+                let dltime = rx_slot.time.add_timeslots(2);
+                for burst in [&rx_slot.slot, &rx_slot.subslot1, &rx_slot.subslot2] {
+                    if burst.train_type != TrainingSequence::NotFound {
+                        tracing::debug!(ts=%rx_slot.time, "rx_slots: UL {:?}", burst.train_type);
+                        PhyBs::<D>::split_rxslot_and_send_to_lmac(queue, burst, 0, dltime);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyMon<D> {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Phy
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rx_ended
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        // Nothing is ever transmitted in monitor mode
+        tracing::debug!("rx_prim: dropping {:?}", message);
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) {
+        // Blocks until the next slot has been demodulated. This is the source of timing in monitor mode.
+        self.rx_slots(queue);
+    }
+}
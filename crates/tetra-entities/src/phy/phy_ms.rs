@@ -0,0 +1,185 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BurstType, Sap, TdmaTime, TrainingSequence};
+use tetra_pdus::phy::traits::rxtx_dev::{RxTxDev, TxSlotBits};
+use tetra_saps::tp::TpUnitdataReqSlot;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::phy::components::{burst_consts::*, slotter};
+use crate::phy::phy_mon::PhyMon;
+use crate::{MessageQueue, TetraEntityTrait};
+
+/// An uplink burst built from Lmac blocks, waiting for its slot
+enum UlBurst {
+    /// Normal uplink burst, occupying the full slot
+    Nub(Box<[u8; NUB_BITS]>),
+    /// Control uplink burst, sent in the first subslot
+    Cub([u8; CUB_BITS]),
+}
+
+/// Phy for an MS.
+/// Like PhyMon, the RX/TX device delivers [dl, ul] slot pairs, of which only the downlink is used. The downlink
+/// demodulator recovers timing from SYNC bursts, which the uplink modulator follows. Slot times are relative to
+/// the first SYNC burst seen, and the Lmac maps them to network time.
+pub struct PhyMs<D: RxTxDev> {
+    config: SharedConfig,
+
+    /// RX/TX device
+    rxtxdev: D,
+
+    /// Set when the RX device reported end of data, after which we stop polling it
+    rx_ended: bool,
+
+    /// Device time of the most recently received downlink slot.
+    /// The uplink slot with the same number starts two slots later, and is the next one to be transmitted.
+    last_dl_time: TdmaTime,
+
+    /// Uplink bursts received from the Lmac, keyed by the device time of the uplink slot
+    tx_queue: Vec<(TdmaTime, UlBurst)>,
+}
+
+impl<D: RxTxDev> PhyMs<D> {
+    pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
+        Self {
+            config,
+            rxtxdev,
+            rx_ended: false,
+            last_dl_time: TdmaTime::default(),
+            tx_queue: Vec::new(),
+        }
+    }
+
+    fn rx_tp_unitdata_req(&mut self, prim: TpUnitdataReqSlot, ul_time: TdmaTime) {
+        let burst = match (prim.burst_type, prim.train_type) {
+            (BurstType::CUB, TrainingSequence::ExtendedTrainSeq) => {
+                // SCH/HU, single block split around the training sequence
+                let Some(mut blk) = prim.blk1 else {
+                    tracing::warn!("rx_tp_unitdata_req: CUB without block");
+                    return;
+                };
+                let mut blk1 = [0u8; CUB_BLK_BITS];
+                let mut blk2 = [0u8; CUB_BLK_BITS];
+                blk.seek(0);
+                blk.to_bitarr(&mut blk1);
+                blk.to_bitarr(&mut blk2);
+                UlBurst::Cub(slotter::build_cub(&blk1, &blk2))
+            }
+            (BurstType::NUB, TrainingSequence::NormalTrainSeq1) => {
+                // Single full slot block
+                let Some(mut blk) = prim.blk1 else {
+                    tracing::warn!("rx_tp_unitdata_req: NUB without block");
+                    return;
+                };
+                let mut blk1 = [0u8; NUB_BLK_BITS];
+                let mut blk2 = [0u8; NUB_BLK_BITS];
+                blk.seek(0);
+                blk.to_bitarr(&mut blk1);
+                blk.to_bitarr(&mut blk2);
+                UlBurst::Nub(Box::new(slotter::build_nub(prim.train_type, &blk1, &blk2)))
+            }
+            (BurstType::NUB, TrainingSequence::NormalTrainSeq2) => {
+                // Two half slot blocks
+                let (Some(mut b1), Some(mut b2)) = (prim.blk1, prim.blk2) else {
+                    tracing::warn!("rx_tp_unitdata_req: NUB with half slots requires two blocks");
+                    return;
+                };
+                let mut blk1 = [0u8; NUB_BLK_BITS];
+                let mut blk2 = [0u8; NUB_BLK_BITS];
+                b1.seek(0);
+                b2.seek(0);
+                b1.to_bitarr(&mut blk1);
+                b2.to_bitarr(&mut blk2);
+                UlBurst::Nub(Box::new(slotter::build_nub(prim.train_type, &blk1, &blk2)))
+            }
+            (burst_type, train_type) => {
+                tracing::warn!("rx_tp_unitdata_req: unsupported {:?} with {:?}", burst_type, train_type);
+                return;
+            }
+        };
+
+        if ul_time.diff(self.last_dl_time) < 0 {
+            tracing::warn!(ts=%ul_time, "rx_tp_unitdata_req: too late for uplink slot, last dl slot {}", self.last_dl_time);
+            return;
+        }
+        self.tx_queue.retain(|(t, _)| *t != ul_time);
+        self.tx_queue.push((ul_time, burst));
+    }
+
+    fn rxtx_slots(&mut self, queue: &mut MessageQueue) {
+        if self.rx_ended {
+            return;
+        }
+
+        // Drop anything we were too late for, and take the burst for the upcoming uplink slot
+        let tx_time = self.last_dl_time;
+        self.tx_queue.retain(|(t, _)| t.diff(tx_time) >= 0);
+        let burst = self
+            .tx_queue
+            .iter()
+            .position(|(t, _)| *t == tx_time)
+            .map(|i| self.tx_queue.swap_remove(i).1);
+
+        let mut tx_slot = TxSlotBits {
+            time: tx_time,
+            ..Default::default()
+        };
+        match &burst {
+            Some(UlBurst::Nub(bits)) => tx_slot.slot = Some(bits.as_ref()),
+            Some(UlBurst::Cub(bits)) => tx_slot.subslot1 = Some(bits),
+            None => {}
+        }
+        if burst.is_some() {
+            tracing::debug!(ts=%tx_time, "rxtx_slots: transmitting uplink burst");
+        }
+
+        let rx = match self.rxtxdev.rxtx_timeslot(&[tx_slot]) {
+            Ok(rx) => rx,
+            Err(e) => {
+                tracing::warn!("rxtx_timeslot: {:?}, no more slots will be received", e);
+                self.rx_ended = true;
+                return;
+            }
+        };
+
+        // Even entries are downlink slots. There is no uplink demodulator in an MS.
+        for rx_slot in rx.into_iter().step_by(2).flatten() {
+            self.last_dl_time = rx_slot.time;
+            if rx_slot.slot.train_type != TrainingSequence::NotFound {
+                tracing::debug!(ts=%rx_slot.time, "rxtx_slots: DL {:?}", rx_slot.slot.train_type);
+                PhyMon::<D>::split_dl_slot_and_send_to_lmac(queue, &rx_slot.slot, rx_slot.time);
+            }
+        }
+    }
+}
+
+impl<D: RxTxDev + Send + 'static> TetraEntityTrait for PhyMs<D> {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Phy
+    }
+
+    fn is_finished(&self) -> bool {
+        self.rx_ended
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match (message.sap, message.msg) {
+            (Sap::TpSap, SapMsgInner::TpUnitdataReq(prim)) => {
+                self.rx_tp_unitdata_req(prim, message.dltime);
+            }
+            (sap, _) => {
+                tracing::warn!("rx_prim: unexpected message on {:?}", sap);
+            }
+        }
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) {
+        // Blocks until the next downlink slot has been demodulated. This is the source of timing in MS mode.
+        self.rxtx_slots(queue);
+    }
+}
@@ -1 +1,3 @@
+pub mod packet_io;
 pub mod sndcp_bs;
+pub mod tun;
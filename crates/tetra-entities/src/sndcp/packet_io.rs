@@ -0,0 +1,52 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Network side of the SNDCP: exchanges IPv4 packets between the terminals and an IP network
+pub trait PacketIo: Send {
+    /// Forward a packet sent by a terminal to the network
+    fn send(&mut self, packet: &[u8]);
+
+    /// Take the next packet from the network destined to a terminal, if any. Never blocks.
+    fn recv(&mut self) -> Option<Vec<u8>>;
+}
+
+#[derive(Default)]
+struct MemoryPackets {
+    /// Packets sent by terminals
+    sent: Vec<Vec<u8>>,
+    /// Packets waiting to be delivered to terminals
+    pending: VecDeque<Vec<u8>>,
+}
+
+/// In-memory PacketIo, for testing. Clones share the same packet queues, so a test can keep one
+/// to inspect packets after handing another to the SNDCP entity.
+#[derive(Clone, Default)]
+pub struct MemoryPacketSink {
+    inner: Arc<Mutex<MemoryPackets>>,
+}
+
+impl MemoryPacketSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a packet from the network, to be delivered to a terminal
+    pub fn inject(&self, packet: Vec<u8>) {
+        self.inner.lock().unwrap().pending.push_back(packet);
+    }
+
+    /// Take all packets sent by terminals so far
+    pub fn take_sent(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.inner.lock().unwrap().sent)
+    }
+}
+
+impl PacketIo for MemoryPacketSink {
+    fn send(&mut self, packet: &[u8]) {
+        self.inner.lock().unwrap().sent.push(packet.to_vec());
+    }
+
+    fn recv(&mut self) -> Option<Vec<u8>> {
+        self.inner.lock().unwrap().pending.pop_front()
+    }
+}
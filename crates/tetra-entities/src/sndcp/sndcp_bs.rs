@@ -1,18 +1,473 @@
-use crate::{MessageQueue, TetraEntityTrait};
-use tetra_config::bluestation::SharedConfig;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use crate::{MessageQueue, TetraEntityTrait, brew};
+use tetra_config::bluestation::{CfgSndcp, SharedConfig};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{Sap, unimplemented_log};
-use tetra_saps::SapMsg;
+use tetra_core::{BitBuffer, Direction, Layer2Service, Sap, SsiType, TdmaTime, TetraAddress, TimeslotOwner, multiframes};
+use tetra_pdus::sndcp::enums::{
+    activation_reject_cause::ActivationRejectCause, address_type_identifier::AddressTypeIdentifier, deactivation_type::DeactivationType,
+    sn_pdu_type_ul::SnPduTypeUl,
+};
+use tetra_pdus::sndcp::pdus::{
+    sn_activate_pdp_context_accept::SnActivatePdpContextAccept, sn_activate_pdp_context_demand::SnActivatePdpContextDemand,
+    sn_activate_pdp_context_reject::SnActivatePdpContextReject, sn_data::SnData,
+    sn_deactivate_pdp_context_accept::SnDeactivatePdpContextAccept, sn_deactivate_pdp_context_demand::SnDeactivatePdpContextDemand,
+    sn_not_supported::SnNotSupported, sn_unitdata::SnUnitdata,
+};
+use tetra_saps::control::packet_data::SndcpPacketData;
+use tetra_saps::control::pdch::PdchControl;
+use tetra_saps::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::ltpd::LtpdMleUnitdataReq;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use super::packet_io::PacketIo;
+
+/// Maximum number of packets taken from the network per timeslot
+const MAX_DL_PACKETS_PER_TICK: usize = 4;
+/// Time the PDCH is kept open after the last PDP context is gone, so the final
+/// SN-DEACTIVATE PDP CONTEXT ACCEPT can still be (re)transmitted on it
+const PDCH_LINGER_TIME: i32 = multiframes!(5);
+
+/// PDU priority max, READY timer, STANDBY timer and response wait time as signalled
+/// in SN-ACTIVATE PDP CONTEXT ACCEPT (Clause 28.4.4)
+const PDU_PRIORITY_MAX: u8 = 7;
+const READY_TIMER: u8 = 6;
+const STANDBY_TIMER: u8 = 1;
+const RESPONSE_WAIT_TIME: u8 = 3;
 
+#[derive(Debug)]
+struct PdpContext {
+    ip: Ipv4Addr,
+}
+
+/// Clause 28 SNDCP, BS side. Maintains the PDP contexts of the terminals, assigns them a
+/// packet data channel (PDCH) and forwards their IPv4 traffic to and from a PacketIo,
+/// or to and from Brew if packet data over Brew is enabled.
 pub struct Sndcp {
-    // config: Option<SharedConfig>,
     config: SharedConfig,
+    packet_io: Option<Box<dyn PacketIo>>,
+    /// Active PDP contexts by (ISSI, NSAPI)
+    contexts: HashMap<(u32, u8), PdpContext>,
+    /// Timeslot of the PDCH, if one is open
+    pdch_ts: Option<u8>,
+    /// Set when the last PDP context is gone while the PDCH is open
+    pdch_idle_since: Option<TdmaTime>,
+    dltime: TdmaTime,
 }
 
 impl Sndcp {
     pub fn new(config: SharedConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            packet_io: None,
+            contexts: HashMap::new(),
+            pdch_ts: None,
+            pdch_idle_since: None,
+            dltime: TdmaTime::default(),
+        }
+    }
+
+    /// Attach the network side. Without one, PDP contexts can be activated but packets are dropped.
+    pub fn set_packet_io(&mut self, packet_io: Box<dyn PacketIo>) {
+        self.packet_io = Some(packet_io);
+    }
+
+    fn has_contexts(&self, issi: u32) -> bool {
+        self.contexts.keys().any(|(i, _)| *i == issi)
+    }
+
+    fn context_by_ip(&self, ip: Ipv4Addr) -> Option<(u32, u8)> {
+        self.contexts.iter().find(|(_, ctx)| ctx.ip == ip).map(|(key, _)| *key)
+    }
+
+    fn allocate_address(&self, cfg: &CfgSndcp) -> Option<Ipv4Addr> {
+        cfg.pool().find(|ip| self.context_by_ip(*ip).is_none())
+    }
+
+    fn pdch_chan_alloc(ts: u8, alloc_type: ChanAllocType) -> CmceChanAllocReq {
+        let mut timeslots = [false; 4];
+        timeslots[(ts - 1) as usize] = true;
+        CmceChanAllocReq {
+            usage: None,
+            carrier: None,
+            timeslots,
+            alloc_type,
+            ul_dl_assigned: UlDlAssignment::Both,
+        }
+    }
+
+    fn build_sapmsg(
+        sdu: BitBuffer,
+        dltime: TdmaTime,
+        issi: u32,
+        layer2service: Layer2Service,
+        chan_alloc: Option<CmceChanAllocReq>,
+    ) -> SapMsg {
+        SapMsg {
+            sap: Sap::TlpdSap,
+            src: TetraEntity::Sndcp,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LtpdMleUnitdataReq(LtpdMleUnitdataReq {
+                sdu,
+                handle: 0,
+                layer2service,
+                pdu_prio: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                stealing_permission: false,
+                packet_data_flag: true,
+                fcs_flag: false,
+                main_address: TetraAddress::new(issi, SsiType::Issi),
+                chan_alloc,
+            }),
+        }
+    }
+
+    /// Open a PDCH on a free timeslot, if configured and not open yet
+    fn open_pdch(&mut self, queue: &mut MessageQueue, cfg: &CfgSndcp) {
+        self.pdch_idle_since = None;
+        if !cfg.pdch || self.pdch_ts.is_some() {
+            return;
+        }
+        let Some(ts) = self.config.state_write().timeslot_alloc.allocate_any(TimeslotOwner::Sndcp) else {
+            tracing::info!("Sndcp: no free timeslot for PDCH, carrying packet data on MCCH");
+            return;
+        };
+        tracing::info!("Sndcp: opening PDCH on ts {}", ts);
+        self.pdch_ts = Some(ts);
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Sndcp,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::PdchControl(PdchControl::Open { ts }),
+        });
+    }
+
+    fn close_pdch(&mut self, queue: &mut MessageQueue) {
+        self.pdch_idle_since = None;
+        let Some(ts) = self.pdch_ts.take() else {
+            return;
+        };
+        tracing::info!("Sndcp: closing PDCH on ts {}", ts);
+        if let Err(e) = self.config.state_write().timeslot_alloc.release(TimeslotOwner::Sndcp, ts) {
+            tracing::warn!("Sndcp: failed releasing PDCH ts {}: {:?}", ts, e);
+        }
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Sndcp,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime,
+            msg: SapMsgInner::PdchControl(PdchControl::Close { ts }),
+        });
     }
+
+    /// Start the PDCH linger time once the last PDP context is gone
+    fn check_pdch_idle(&mut self) {
+        if self.pdch_ts.is_some() && self.contexts.is_empty() && self.pdch_idle_since.is_none() {
+            self.pdch_idle_since = Some(self.dltime);
+        }
+    }
+
+    fn send_reject(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, nsapi: u8, cause: ActivationRejectCause) {
+        let pdu = SnActivatePdpContextReject {
+            nsapi,
+            activation_reject_cause: cause,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize SnActivatePdpContextReject");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+        queue.push_back(Self::build_sapmsg(sdu, dltime, issi, Layer2Service::Acknowledged, None));
+    }
+
+    fn rx_activate_demand(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: &mut BitBuffer) {
+        let pdu = match SnActivatePdpContextDemand::from_bitbuf(sdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing SnActivatePdpContextDemand: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+
+        let Some(cfg) = self.config.config().sndcp.clone() else {
+            Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::MsNotProvisionedForPacketData);
+            return;
+        };
+        if !self.config.state_read().subscribers.is_registered(issi) {
+            tracing::info!("Sndcp: rejecting PDP context for unregistered ISSI {}", issi);
+            Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::MsNotProvisionedForPacketData);
+            return;
+        }
+
+        // A repeated demand for an active NSAPI keeps its address
+        let existing = self.contexts.get(&(issi, pdu.nsapi)).map(|ctx| ctx.ip);
+        let ip = match pdu.address_type_identifier {
+            AddressTypeIdentifier::Ipv4Dynamic => match existing.or_else(|| self.allocate_address(&cfg)) {
+                Some(ip) => ip,
+                None => {
+                    tracing::warn!("Sndcp: address pool exhausted, rejecting ISSI {}", issi);
+                    Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::InsufficientResources);
+                    return;
+                }
+            },
+            AddressTypeIdentifier::Ipv4Static => {
+                let ip = Ipv4Addr::from(pdu.ip_address.unwrap_or(0));
+                let in_pool = cfg.pool().any(|a| a == ip);
+                let owner = self.context_by_ip(ip);
+                if !in_pool || owner.is_some_and(|key| key != (issi, pdu.nsapi)) {
+                    tracing::info!("Sndcp: rejecting static address {} for ISSI {}", ip, issi);
+                    Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::Ipv4StaticAddressNotCorrect);
+                    return;
+                }
+                ip
+            }
+            AddressTypeIdentifier::Ipv6 => {
+                Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::Ipv6NotSupported);
+                return;
+            }
+            AddressTypeIdentifier::Ipv4MobileForeignAgent | AddressTypeIdentifier::Ipv4MobileCoLocated => {
+                Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::MobileIpv4NotSupported);
+                return;
+            }
+            AddressTypeIdentifier::NoAddress => {
+                Self::send_reject(queue, dltime, issi, pdu.nsapi, ActivationRejectCause::Ipv4NotSupported);
+                return;
+            }
+        };
+
+        // Terminals get moved onto the PDCH along with their first PDP context
+        let first_context = !self.has_contexts(issi);
+        self.open_pdch(queue, &cfg);
+        self.contexts.insert((issi, pdu.nsapi), PdpContext { ip });
+        tracing::info!("Sndcp: ISSI {} NSAPI {} activated with address {}", issi, pdu.nsapi, ip);
+
+        let accept = SnActivatePdpContextAccept {
+            nsapi: pdu.nsapi,
+            pdu_priority_max: PDU_PRIORITY_MAX,
+            ready_timer: READY_TIMER,
+            standby_timer: STANDBY_TIMER,
+            response_wait_time: RESPONSE_WAIT_TIME,
+            address_type_identifier: pdu.address_type_identifier,
+            ip_address: Some(u32::from(ip)),
+            pcomp_negotiation: 0,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(80);
+        accept.to_bitbuf(&mut sdu).expect("Failed to serialize SnActivatePdpContextAccept");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", accept, sdu.dump_bin());
+
+        let chan_alloc = match self.pdch_ts {
+            Some(ts) if first_context => Some(Self::pdch_chan_alloc(ts, ChanAllocType::Replace)),
+            _ => None,
+        };
+        queue.push_back(Self::build_sapmsg(sdu, dltime, issi, Layer2Service::Acknowledged, chan_alloc));
+    }
+
+    fn rx_deactivate_demand(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: &mut BitBuffer) {
+        let pdu = match SnDeactivatePdpContextDemand::from_bitbuf(sdu, Direction::Ul) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing SnDeactivatePdpContextDemand: {:?} {}", e, sdu.dump_bin());
+                return;
+            }
+        };
+
+        match (pdu.deactivation_type, pdu.nsapi) {
+            (DeactivationType::GivenNsapi, Some(nsapi)) => {
+                self.contexts.remove(&(issi, nsapi));
+            }
+            _ => self.contexts.retain(|(i, _), _| *i != issi),
+        }
+        tracing::info!("Sndcp: ISSI {} deactivated {:?} {:?}", issi, pdu.deactivation_type, pdu.nsapi);
+
+        let accept = SnDeactivatePdpContextAccept {
+            deactivation_type: pdu.deactivation_type,
+            nsapi: pdu.nsapi,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(16);
+        accept
+            .to_bitbuf(&mut sdu, Direction::Dl)
+            .expect("Failed to serialize SnDeactivatePdpContextAccept");
+        sdu.seek(0);
+        tracing::info!("-> {:?} sdu {}", accept, sdu.dump_bin());
+
+        // Send the terminal back to the MCCH along with its last PDP context
+        let chan_alloc = match self.pdch_ts {
+            Some(ts) if !self.has_contexts(issi) => Some(Self::pdch_chan_alloc(ts, ChanAllocType::QuitAndGo)),
+            _ => None,
+        };
+        queue.push_back(Self::build_sapmsg(sdu, dltime, issi, Layer2Service::Acknowledged, chan_alloc));
+        self.check_pdch_idle();
+    }
+
+    /// Forward an N-PDU received from a terminal to the network
+    fn rx_n_pdu(&mut self, queue: &mut MessageQueue, issi: u32, nsapi: u8, n_pdu: &[u8]) {
+        let Some(ctx) = self.contexts.get(&(issi, nsapi)) else {
+            tracing::warn!("Sndcp: N-PDU from ISSI {} on inactive NSAPI {}, dropping", issi, nsapi);
+            return;
+        };
+        let Some((src, _)) = ipv4_addresses(n_pdu) else {
+            tracing::warn!("Sndcp: N-PDU from ISSI {} is not an IPv4 packet, dropping", issi);
+            return;
+        };
+        if src != ctx.ip {
+            tracing::warn!("Sndcp: N-PDU from ISSI {} has source {}, expected {}, dropping", issi, src, ctx.ip);
+            return;
+        }
+        if brew::feature_packet_data_enabled(&self.config) {
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Sndcp,
+                dest: TetraEntity::Brew,
+                dltime: self.dltime,
+                msg: SapMsgInner::SndcpPacketData(SndcpPacketData {
+                    issi: Some(issi),
+                    packet: n_pdu.to_vec(),
+                }),
+            });
+            return;
+        }
+        let Some(packet_io) = &mut self.packet_io else {
+            tracing::debug!("Sndcp: no packet io, dropping N-PDU from ISSI {}", issi);
+            return;
+        };
+        packet_io.send(n_pdu);
+    }
+
+    fn rx_ltpd_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        let SapMsgInner::LtpdMleUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let issi = prim.received_tetra_address.ssi;
+        let sdu = &mut prim.sdu;
+
+        let Some(bits) = sdu.peek_bits(4) else {
+            tracing::warn!("insufficient bits: {}", sdu.dump_bin());
+            return;
+        };
+        let Ok(pdu_type) = SnPduTypeUl::try_from(bits) else {
+            tracing::warn!("invalid pdu type: {} in {}", bits, sdu.dump_bin());
+            return;
+        };
+
+        match pdu_type {
+            SnPduTypeUl::SnActivatePdpContextDemand => self.rx_activate_demand(queue, message.dltime, issi, sdu),
+            SnPduTypeUl::SnDeactivatePdpContextDemand => self.rx_deactivate_demand(queue, message.dltime, issi, sdu),
+            SnPduTypeUl::SnUnitdata => match SnUnitdata::from_bitbuf(sdu) {
+                Ok(pdu) => {
+                    tracing::debug!("<- SnUnitdata nsapi {} len {}", pdu.nsapi, pdu.n_pdu.len());
+                    self.rx_n_pdu(queue, issi, pdu.nsapi, &pdu.n_pdu);
+                }
+                Err(e) => tracing::warn!("Failed parsing SnUnitdata: {:?} {}", e, sdu.dump_bin()),
+            },
+            SnPduTypeUl::SnData => match SnData::from_bitbuf(sdu) {
+                Ok(pdu) => {
+                    tracing::debug!("<- SnData nsapi {} len {}", pdu.nsapi, pdu.n_pdu.len());
+                    self.rx_n_pdu(queue, issi, pdu.nsapi, &pdu.n_pdu);
+                }
+                Err(e) => tracing::warn!("Failed parsing SnData: {:?} {}", e, sdu.dump_bin()),
+            },
+            _ => {
+                tracing::info!("Sndcp: {} not supported", pdu_type);
+                let pdu = SnNotSupported {
+                    not_supported_sn_pdu_type: pdu_type.into_raw() as u8,
+                };
+                let mut sdu = BitBuffer::new_autoexpand(16);
+                pdu.to_bitbuf(&mut sdu).expect("Failed to serialize SnNotSupported");
+                sdu.seek(0);
+                tracing::info!("-> {:?} sdu {}", pdu, sdu.dump_bin());
+                queue.push_back(Self::build_sapmsg(sdu, message.dltime, issi, Layer2Service::Acknowledged, None));
+            }
+        }
+    }
+
+    /// Deliver packets from the network to the terminals owning their destination address
+    fn poll_packet_io(&mut self, queue: &mut MessageQueue) {
+        for _ in 0..MAX_DL_PACKETS_PER_TICK {
+            let Some(packet_io) = &mut self.packet_io else {
+                return;
+            };
+            let Some(packet) = packet_io.recv() else {
+                return;
+            };
+            self.tx_n_pdu(queue, None, packet);
+        }
+    }
+
+    /// Deliver a packet from the network in SN-DATA to the terminal owning its destination address.
+    /// If the intended ISSI is known, the packet is dropped when the address belongs to another terminal.
+    fn tx_n_pdu(&mut self, queue: &mut MessageQueue, expected_issi: Option<u32>, packet: Vec<u8>) {
+        let Some((_, dst)) = ipv4_addresses(&packet) else {
+            tracing::debug!("Sndcp: dropping non-IPv4 packet from network");
+            return;
+        };
+        let Some((issi, nsapi)) = self.context_by_ip(dst) else {
+            tracing::debug!("Sndcp: no PDP context for {}, dropping packet", dst);
+            return;
+        };
+        if let Some(expected) = expected_issi
+            && expected != issi
+        {
+            tracing::warn!(
+                "Sndcp: packet for ISSI {} addressed to {} of ISSI {}, dropping",
+                expected,
+                dst,
+                issi
+            );
+            return;
+        }
+
+        let pdu = SnData {
+            nsapi,
+            pcomp: 0,
+            dcomp: 0,
+            n_pdu: packet,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(16 + pdu.n_pdu.len() * 8);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::warn!("Sndcp: failed serializing SnData: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+        tracing::debug!("-> SnData issi {} nsapi {} len {}", issi, nsapi, pdu.n_pdu.len());
+        let dltime = self.dltime.forward_to_timeslot(self.pdch_ts.unwrap_or(1));
+        queue.push_back(Self::build_sapmsg(sdu, dltime, issi, Layer2Service::Acknowledged, None));
+    }
+
+    /// Drop the PDP contexts of terminals that are no longer registered
+    fn expire_contexts(&mut self) {
+        let before = self.contexts.len();
+        let state = self.config.state_read();
+        self.contexts.retain(|(issi, _), _| state.subscribers.is_registered(*issi));
+        drop(state);
+        if self.contexts.len() != before {
+            tracing::info!(
+                "Sndcp: dropped {} PDP contexts of deregistered terminals",
+                before - self.contexts.len()
+            );
+            self.check_pdch_idle();
+        }
+    }
+}
+
+/// Source and destination address of an IPv4 packet
+fn ipv4_addresses(packet: &[u8]) -> Option<(Ipv4Addr, Ipv4Addr)> {
+    if packet.len() < 20 || packet[0] >> 4 != 4 {
+        return None;
+    }
+    let src = Ipv4Addr::new(packet[12], packet[13], packet[14], packet[15]);
+    let dst = Ipv4Addr::new(packet[16], packet[17], packet[18], packet[19]);
+    Some((src, dst))
 }
 
 impl TetraEntityTrait for Sndcp {
@@ -20,13 +475,33 @@ impl TetraEntityTrait for Sndcp {
         TetraEntity::Sndcp
     }
 
-    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.dltime = ts;
+
+        if ts.t == 1 && ts.f == 1 {
+            self.expire_contexts();
+        }
+        if let Some(since) = self.pdch_idle_since
+            && since.age(ts) >= PDCH_LINGER_TIME
+        {
+            self.close_pdch(queue);
+        }
+        self.poll_packet_io(queue);
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
-        // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
 
-        // There is only one SAP for SNDCP
-        // OR.. SN-SAP? TODO FIXME check docs
-        assert!(message.sap == Sap::TlpdSap);
-        unimplemented_log!("sndcp not implemented");
+        // TLPD-SAP from the MLE, and packet data bridged from Brew on the control SAP
+        assert!(message.sap == Sap::TlpdSap || message.sap == Sap::Control);
+        match message.msg {
+            SapMsgInner::LtpdMleUnitdataInd(_) => self.rx_ltpd_mle_unitdata_ind(queue, message),
+            SapMsgInner::SndcpPacketData(data) => self.tx_n_pdu(queue, data.issi, data.packet),
+            _ => tracing::warn!("Sndcp: unexpected prim {:?}", message.msg),
+        }
     }
 }
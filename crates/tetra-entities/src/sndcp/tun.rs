@@ -0,0 +1,132 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::Ipv4Addr;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::OpenOptionsExt;
+
+use tetra_config::bluestation::CfgSndcp;
+
+use super::packet_io::PacketIo;
+
+/// PacketIo backed by a Linux TUN device. The device is created (or attached to, if it already
+/// exists) on open, gets the gateway address of the SNDCP address pool and is brought up.
+/// Reads are non-blocking, so the device can be polled from the stack tick.
+pub struct TunDevice {
+    file: File,
+    name: String,
+    mtu: usize,
+}
+
+impl TunDevice {
+    pub fn open(cfg: &CfgSndcp) -> io::Result<Self> {
+        if cfg.tun_name.is_empty() || cfg.tun_name.len() >= libc::IFNAMSIZ {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid TUN device name"));
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/net/tun")?;
+
+        let mut ifr = Self::ifreq(&cfg.tun_name);
+        ifr.ifr_ifru.ifru_flags = (libc::IFF_TUN | libc::IFF_NO_PI) as libc::c_short;
+        // SAFETY: ifr is a valid ifreq that outlives the call
+        if unsafe { libc::ioctl(file.as_raw_fd(), libc::TUNSETIFF, &mut ifr) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let dev = Self {
+            file,
+            name: cfg.tun_name.clone(),
+            mtu: cfg.mtu as usize,
+        };
+        dev.configure(cfg.gateway(), cfg.prefix_len)?;
+        tracing::info!("TunDevice: {} up with address {}/{}", dev.name, cfg.gateway(), cfg.prefix_len);
+        Ok(dev)
+    }
+
+    fn ifreq(name: &str) -> libc::ifreq {
+        // SAFETY: ifreq is plain old data for which all zeroes is a valid value
+        let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+        for (dst, src) in ifr.ifr_name.iter_mut().zip(name.bytes()) {
+            *dst = src as libc::c_char;
+        }
+        ifr
+    }
+
+    fn sockaddr(addr: Ipv4Addr) -> libc::sockaddr {
+        let sin = libc::sockaddr_in {
+            sin_family: libc::AF_INET as libc::sa_family_t,
+            sin_port: 0,
+            sin_addr: libc::in_addr {
+                s_addr: u32::from(addr).to_be(),
+            },
+            sin_zero: [0; 8],
+        };
+        // SAFETY: sockaddr_in and sockaddr have the same size, and sockaddr is only ever read as sockaddr_in
+        unsafe { std::mem::transmute::<libc::sockaddr_in, libc::sockaddr>(sin) }
+    }
+
+    /// Set address, netmask and MTU of the interface and bring it up
+    fn configure(&self, addr: Ipv4Addr, prefix_len: u8) -> io::Result<()> {
+        // SAFETY: plain socket creation, the fd is owned right away
+        let sock = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+        if sock < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: sock is a freshly created, valid fd
+        let sock = unsafe { OwnedFd::from_raw_fd(sock) };
+
+        let ioctl = |request: libc::Ioctl, ifr: &mut libc::ifreq| -> io::Result<()> {
+            // SAFETY: ifr is a valid ifreq that outlives the call
+            if unsafe { libc::ioctl(sock.as_raw_fd(), request, ifr as *mut libc::ifreq) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        };
+
+        let mut ifr = Self::ifreq(&self.name);
+        ifr.ifr_ifru.ifru_addr = Self::sockaddr(addr);
+        ioctl(libc::SIOCSIFADDR, &mut ifr)?;
+
+        let mut ifr = Self::ifreq(&self.name);
+        ifr.ifr_ifru.ifru_netmask = Self::sockaddr(Ipv4Addr::from(u32::MAX << (32 - prefix_len as u32)));
+        ioctl(libc::SIOCSIFNETMASK, &mut ifr)?;
+
+        let mut ifr = Self::ifreq(&self.name);
+        ifr.ifr_ifru.ifru_mtu = self.mtu as libc::c_int;
+        ioctl(libc::SIOCSIFMTU, &mut ifr)?;
+
+        let mut ifr = Self::ifreq(&self.name);
+        ioctl(libc::SIOCGIFFLAGS, &mut ifr)?;
+        // SAFETY: SIOCGIFFLAGS filled in the flags member
+        unsafe { ifr.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short };
+        ioctl(libc::SIOCSIFFLAGS, &mut ifr)?;
+
+        Ok(())
+    }
+}
+
+impl PacketIo for TunDevice {
+    fn send(&mut self, packet: &[u8]) {
+        if let Err(e) = self.file.write(packet) {
+            tracing::warn!("TunDevice: failed writing {} bytes to {}: {}", packet.len(), self.name, e);
+        }
+    }
+
+    fn recv(&mut self) -> Option<Vec<u8>> {
+        let mut buf = vec![0u8; self.mtu];
+        match self.file.read(&mut buf) {
+            Ok(len) => {
+                buf.truncate(len);
+                Some(buf)
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => None,
+            Err(e) => {
+                tracing::warn!("TunDevice: failed reading from {}: {}", self.name, e);
+                None
+            }
+        }
+    }
+}
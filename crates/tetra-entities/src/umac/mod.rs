@@ -1,4 +1,5 @@
 pub mod subcomp;
 
 pub mod umac_bs;
+pub mod umac_mon;
 pub mod umac_ms;
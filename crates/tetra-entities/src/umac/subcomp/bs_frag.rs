@@ -1,9 +1,10 @@
 use std::cmp::min;
 
-use tetra_core::{BitBuffer, TxReporter};
+use tetra_core::{BitBuffer, Direction, TdmaTime, TxReporter};
 
 use tetra_pdus::umac::pdus::{mac_end_dl::MacEndDl, mac_frag_dl::MacFragDl, mac_resource::MacResource};
 
+use crate::umac::subcomp::aie::AieCipher;
 use crate::umac::subcomp::fillbits;
 
 #[derive(Debug)]
@@ -13,6 +14,8 @@ pub struct BsFragger {
     is_fully_transmitted: bool,
     sdu: BitBuffer,
     tx_reporter: Option<TxReporter>,
+    /// If set, the SDU is encrypted once the timeslot carrying the MAC-RESOURCE is known
+    cipher: Option<AieCipher>,
 }
 
 /// We won't start fragmentation if less than MIN_SLOT_CAP_FOR_FRAG_START bits are free in the slot
@@ -32,6 +35,21 @@ impl BsFragger {
             is_fully_transmitted: false,
             sdu,
             tx_reporter,
+            cipher: None,
+        }
+    }
+
+    /// Encrypt the SDU with the given cipher when it is sent. The whole SDU is encrypted
+    /// with the keystream for the timeslot carrying the MAC-RESOURCE, also if fragmented.
+    pub fn with_cipher(mut self, cipher: Option<AieCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Encrypts the SDU, once, before its first bits are written to a slot at time ts
+    fn encrypt_sdu(&mut self, ts: TdmaTime) {
+        if let Some(cipher) = self.cipher.take() {
+            cipher.apply(&mut self.sdu, ts, Direction::Dl);
         }
     }
 
@@ -39,7 +57,7 @@ impl BsFragger {
     /// Then, writes as many SDU bits as possible.
     /// Returns true if the entire SDU was consumed, false if the PDU is fragmented
     /// and more chunks are needed.
-    fn get_resource_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+    fn get_resource_chunk(&mut self, mac_block: &mut BitBuffer, ts: TdmaTime) -> bool {
         // Some sanity checks
         assert!(self.sdu.get_pos() == 0, "SDU must be at the start of the buffer");
         assert!(!self.mac_hdr_is_written, "MAC header should not be written yet");
@@ -80,6 +98,7 @@ impl BsFragger {
             );
 
             // Write MAC-RESOURCE header, followed by TM-SDU, to MAC block
+            self.encrypt_sdu(ts);
             self.resource.to_bitbuf(mac_block);
             mac_block.copy_bits(&mut self.sdu, sdu_len_bits);
             fillbits::addition::write(mac_block, Some(num_fill_bits));
@@ -111,6 +130,7 @@ impl BsFragger {
                     .raw_dump_bin(false, false, self.sdu.get_pos(), self.sdu.get_pos() + sdu_bits)
             );
 
+            self.encrypt_sdu(ts);
             self.resource.to_bitbuf(mac_block);
             mac_block.copy_bits(&mut self.sdu, sdu_bits);
             fillbits::addition::write(mac_block, None);
@@ -204,8 +224,9 @@ impl BsFragger {
     /// Writes the next chunk to the bitbuffer, if there is space.
     /// First chunk is the provided resource, possibly changed to indicate fragmentation.
     /// Subsequent chunks are MAC-FRAG or MAC-END.
+    /// ts is the time of the slot the chunk is sent in.
     /// Returns bool is_fully_transmitted
-    pub fn get_next_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+    pub fn get_next_chunk(&mut self, mac_block: &mut BitBuffer, ts: TdmaTime) -> bool {
         assert!(!self.is_fully_transmitted, "all fragments have already been produced");
         assert!(
             mac_block.get_len_written() % 8 == 0 || mac_block.get_len_remaining() == 0,
@@ -214,7 +235,7 @@ impl BsFragger {
 
         self.is_fully_transmitted = if !self.mac_hdr_is_written {
             // First chunk, write MAC-RESOURCE
-            self.get_resource_chunk(mac_block)
+            self.get_resource_chunk(mac_block, ts)
         } else {
             // Subsequent chunks, write MAC-FRAG or MAC-END
             self.get_frag_or_end_chunk(mac_block)
@@ -281,7 +302,7 @@ mod tests {
         let mut mac_block = BitBuffer::new(SCH_F_CAP);
 
         let mut fragger = BsFragger::new(pdu, sdu, None);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
 
         assert!(done, "Should be done in single chunk");
@@ -298,7 +319,7 @@ mod tests {
         let mut fragger = BsFragger::new(pdu, sdu, None);
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacResource::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -308,7 +329,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -318,7 +339,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -328,7 +349,7 @@ mod tests {
         assert!(!done, "Should take four blocks");
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacEndDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -356,7 +377,7 @@ mod tests {
         let mut fragger = BsFragger::new(pdu, sdu, Some(reporter.clone()));
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacResource::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -367,7 +388,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -378,7 +399,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacFragDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
@@ -389,7 +410,7 @@ mod tests {
         assert!(!reporter.is_in_final_state() && !reporter.is_transmitted());
 
         let mut mac_block = BitBuffer::new(SCH_HD_CAP);
-        let done = fragger.get_next_chunk(&mut mac_block);
+        let done = fragger.get_next_chunk(&mut mac_block, TdmaTime::default());
         mac_block.seek(0);
         let pdu = MacEndDl::from_bitbuf(&mut mac_block).unwrap();
         mac_block.set_raw_start(mac_block.get_raw_pos());
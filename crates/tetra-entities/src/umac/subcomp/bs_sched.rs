@@ -1,4 +1,9 @@
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, TxReporter, unimplemented_log};
+use std::collections::HashMap;
+
+use tetra_core::{
+    BitBuffer, Direction, EnergyEconomySchedule, PhyBlockNum, PhysicalChannel, SsiType, TdmaTime, TetraAddress, Todo, TxReporter,
+    multiframes, unimplemented_log,
+};
 use tetra_saps::{
     control::call_control::Circuit,
     tmv::{TmvUnitdataReq, TmvUnitdataReqSlot, enums::logical_chans::LogicalChannel},
@@ -25,7 +30,7 @@ use tetra_pdus::{
 
 use crate::{
     lmac::components::scrambler,
-    umac::subcomp::{bs_frag::BsFragger, circuit_mgr::CircuitMgr},
+    umac::subcomp::{aie::AieCipher, bs_frag::BsFragger, circuit_mgr::CircuitMgr},
 };
 
 /// We submit this many TX timeslots ahead of the current time
@@ -40,6 +45,9 @@ pub const SCH_HD_CAP: usize = 124;
 pub const SCH_F_CAP: usize = 268;
 pub const TCH_S_CAP: usize = 274;
 
+/// An MS in energy economy that was heard on the uplink stays awake this many timeslots for the answer
+const ENERGY_ECONOMY_AWAKE_AFTER_UL: i32 = multiframes!(1);
+
 #[derive(Debug)]
 pub struct PrecomputedUmacPdus {
     pub mac_sysinfo1: MacSysinfo,
@@ -59,6 +67,8 @@ pub struct TimeslotSchedule {
 // #[derive(Debug)]
 pub struct BsChannelScheduler {
     pub cur_dltime: TdmaTime,
+    /// Carrier index, 0 for the main carrier. Secondary carriers carry no common control or broadcast.
+    carrier: u8,
     scrambling_code: u32,
     precomps: PrecomputedUmacPdus,
     /// Collect dltx traffic here that can't be sent this slot.
@@ -75,10 +85,20 @@ pub struct BsChannelScheduler {
     /// and signal UL usage as AssignedOnly so MS can request the floor.
     hangtime: [bool; 4],
 
+    /// When true, the given timeslot is a packet data channel: an assigned control channel
+    /// that stays in signalling mode, idling with Null PDUs, for MSs sent there by SNDCP.
+    pdch: [bool; 4],
+
     /// Per-timeslot set of SSIs whose RandomAccessAck was dropped by dl_drop_all_except_stolen.
     /// The next STCH built for a matching SSI should carry random_access_flag=true to properly
     /// acknowledge the random access per ETSI 21.4.3.1.
     pending_ra_acks: [Vec<u32>; 4],
+
+    /// Wake schedules of MSs in energy economy mode, by SSI. Individually addressed signalling
+    /// on the MCCH is held back until the MS is awake.
+    energy_economy: HashMap<u32, EnergyEconomySchedule>,
+    /// Last time an MS was heard on the uplink, pruned after ENERGY_ECONOMY_AWAKE_AFTER_UL
+    last_heard: HashMap<u32, TdmaTime>,
 }
 
 #[derive(Debug)]
@@ -94,7 +114,7 @@ pub enum DlSchedElem {
     Grant(TetraAddress, BasicSlotgrant),
 
     /// A MAC-RESOURCE PDU. May be split into fragments upon processing, in which case a FragBuf will be inserted after processing the resource.
-    Resource(MacResource, BitBuffer, Option<TxReporter>),
+    Resource(MacResource, BitBuffer, Option<TxReporter>, Option<AieCipher>),
 
     /// A FragBuf containing remaining non-transmitted information after a MAC-RESOURCE start has been transmitted
     FragBuf(BsFragger),
@@ -115,8 +135,13 @@ const EMPTY_SCHED: [[TimeslotSchedule; MACSCHED_NUM_FRAMES]; 4] = [EMPTY_SCHED_C
 
 impl BsChannelScheduler {
     pub fn new(scrambling_code: u32, precomps: PrecomputedUmacPdus) -> Self {
+        Self::new_for_carrier(0, scrambling_code, precomps)
+    }
+
+    pub fn new_for_carrier(carrier: u8, scrambling_code: u32, precomps: PrecomputedUmacPdus) -> Self {
         BsChannelScheduler {
             cur_dltime: TdmaTime { t: 0, f: 0, m: 0, h: 0 }, // Intentionally invalid, updated in tick function
+            carrier,
             scrambling_code,
             precomps,
             dltx_next_slot_queue: Vec::new(),
@@ -124,7 +149,10 @@ impl BsChannelScheduler {
             ulsched: EMPTY_SCHED,
             circuits: CircuitMgr::new(),
             hangtime: [false, false, false, false],
+            pdch: [false, false, false, false],
             pending_ra_acks: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            energy_economy: HashMap::new(),
+            last_heard: HashMap::new(),
         }
     }
 
@@ -152,6 +180,38 @@ impl BsChannelScheduler {
         );
     }
 
+    /// Start or stop operating a timeslot (2..=4) as a packet data channel.
+    pub fn set_pdch(&mut self, ts: u8, active: bool) {
+        if !(2..=4).contains(&ts) {
+            tracing::warn!("BsChannelScheduler::set_pdch: invalid ts {}", ts);
+            return;
+        }
+        self.pdch[ts as usize - 1] = active;
+        if !active {
+            // Signalling still queued for MSs on the PDCH can't be delivered anymore
+            self.dltx_queues[ts as usize - 1].clear();
+        }
+
+        tracing::info!(
+            "BsChannelScheduler: pdch {} for ts {}",
+            if active { "ENABLED" } else { "DISABLED" },
+            ts,
+        );
+    }
+
+    pub fn carrier(&self) -> u8 {
+        self.carrier
+    }
+
+    /// Whether the timeslot can carry traffic: TS2..TS4 on the main carrier, all timeslots on a secondary carrier
+    fn is_traffic_ts(&self, ts: u8) -> bool {
+        (2..=4).contains(&ts) || (self.carrier != 0 && ts == 1)
+    }
+
+    pub fn is_pdch(&self, ts: u8) -> bool {
+        self.pdch[ts as usize - 1]
+    }
+
     pub fn is_hangtime(&self, ts: u8) -> bool {
         self.hangtime[ts as usize - 1]
     }
@@ -204,6 +264,30 @@ impl BsChannelScheduler {
         }
     }
 
+    /// Sets the wake schedule of an MS in energy economy mode, or clears it if the MS stays alive
+    pub fn set_energy_economy(&mut self, ssi: u32, schedule: Option<EnergyEconomySchedule>) {
+        match schedule {
+            Some(schedule) => self.energy_economy.insert(ssi, schedule),
+            None => self.energy_economy.remove(&ssi),
+        };
+    }
+
+    /// Records uplink activity of an MS, which keeps it awake for the answer
+    fn mark_heard(&mut self, ssi: u32) {
+        let now = self.cur_dltime;
+        self.last_heard.retain(|_, t| t.age(now) < ENERGY_ECONOMY_AWAKE_AFTER_UL);
+        self.last_heard.insert(ssi, now);
+    }
+
+    /// Returns true if the MS monitors the MCCH at the given time. An MS in energy economy does so
+    /// in its awake frames, and for a while after it transmitted.
+    fn ms_is_awake(&self, ssi: u32, ts: TdmaTime) -> bool {
+        let Some(schedule) = self.energy_economy.get(&ssi) else {
+            return true;
+        };
+        schedule.is_awake(ts) || self.last_heard.get(&ssi).is_some_and(|t| t.age(ts) < ENERGY_ECONOMY_AWAKE_AFTER_UL)
+    }
+
     /// Fully wipe the schedule
     pub fn purge_schedule(&mut self) {
         self.dltx_queues = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
@@ -269,6 +353,10 @@ impl BsChannelScheduler {
                 // Something is here, clear our grant timeslots
                 opportunities_skipped += grant_timeslots.len() + 1;
                 grant_timeslots.clear();
+                if opportunities_skipped > 13 {
+                    // Beyond what the granting delay element can signal
+                    return None;
+                }
             }
 
             // Check if done
@@ -401,6 +489,7 @@ impl BsChannelScheduler {
     /// Registers that we should transmit a MAC-RESOURCE or similar with a grant, somewhere this tick
     pub fn dl_enqueue_grant(&mut self, ts: u8, addr: TetraAddress, grant: BasicSlotgrant) {
         tracing::debug!("dl_enqueue_grant: ts {} enqueueing PDU {:?} for addr {}", ts, grant, addr);
+        self.mark_heard(addr.ssi);
         let elem = DlSchedElem::Grant(addr, grant);
         self.dltx_queues[ts as usize - 1].push(elem);
     }
@@ -411,11 +500,14 @@ impl BsChannelScheduler {
             ts,
             addr
         );
+        self.mark_heard(addr.ssi);
         let elem = DlSchedElem::RandomAccessAck(addr);
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
-    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>) {
+    /// Enqueue a MAC-RESOURCE with TM-SDU. If a cipher is given, the SDU is encrypted
+    /// when transmitted, so pdu should signal the encryption mode.
+    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer, tx_reporter: Option<TxReporter>, cipher: Option<AieCipher>) {
         tracing::debug!(
             "dl_enqueue_tma: ts {} enqueueing {} PDU {:?} SDU {}",
             if tx_reporter.is_some() { "reported" } else { "" },
@@ -423,7 +515,7 @@ impl BsChannelScheduler {
             pdu,
             sdu.dump_bin(),
         );
-        let elem = DlSchedElem::Resource(pdu, sdu, tx_reporter);
+        let elem = DlSchedElem::Resource(pdu, sdu, tx_reporter, cipher);
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
@@ -469,6 +561,11 @@ impl BsChannelScheduler {
         self.circuits.is_active(dir, ts)
     }
 
+    /// Usage marker of the circuit on ts, if any
+    pub fn circuit_usage(&self, dir: Direction, ts: u8) -> Option<u8> {
+        self.circuits.get_usage(dir, ts)
+    }
+
     pub fn close_circuit(&mut self, dir: Direction, ts: u8) -> Option<Circuit> {
         // Clearing hangtime here is safe: if the circuit is gone, this timeslot is no longer in use.
         if (1..=4).contains(&ts) {
@@ -531,13 +628,12 @@ impl BsChannelScheduler {
 
         for index in 0..queue.len() {
             let elem = &mut queue[index];
-            if let DlSchedElem::Resource(pdu, _sdu, _repeat) = elem {
-                if let Some(pdu_ssi) = pdu.addr {
-                    if pdu_ssi.ssi == addr.ssi {
-                        // Found a resource for this address
-                        return queue.get_mut(index);
-                    }
-                }
+            if let DlSchedElem::Resource(pdu, _sdu, _repeat, _cipher) = elem
+                && let Some(pdu_ssi) = pdu.addr
+                && pdu_ssi.ssi == addr.ssi
+            {
+                // Found a resource for this address
+                return queue.get_mut(index);
             }
         }
         // No resource for this address was found
@@ -599,7 +695,7 @@ impl BsChannelScheduler {
                 tracing::warn!("dl_drop_all_except_stolen: discarding scheduled {:?} on ts {}", elem, timeslot);
 
                 match elem {
-                    DlSchedElem::Resource(_, _, tx_reporter) => {
+                    DlSchedElem::Resource(_, _, tx_reporter, _) => {
                         // Report as discarded manually
                         if let Some(tx_reporter) = tx_reporter {
                             tx_reporter.mark_discarded();
@@ -641,7 +737,7 @@ impl BsChannelScheduler {
             };
             let mac_resource = self.dl_get_scheduled_resource_for_ssi(ts, addr);
             match mac_resource {
-                Some(DlSchedElem::Resource(pdu, _sdu, _repeat)) => {
+                Some(DlSchedElem::Resource(pdu, _sdu, _repeat, _cipher)) => {
                     // Integrate grant into the resource
                     match &elem {
                         DlSchedElem::Grant(_, grant) => {
@@ -685,7 +781,7 @@ impl BsChannelScheduler {
                     };
 
                     // Push new resource into the queue. These do not need a tx_reporter
-                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None);
+                    let dlsched_res = DlSchedElem::Resource(pdu, BitBuffer::new(0), None, None);
                     self.dltx_queues[ts.t as usize - 1].push(dlsched_res);
                 }
                 _ => panic!(),
@@ -706,12 +802,12 @@ impl BsChannelScheduler {
                             unimplemented_log!("finalize_ts_for_tick: Broadcast scheduling not implemented");
                         }
 
-                        DlSchedElem::Resource(pdu, sdu, tx_reporter) => {
+                        DlSchedElem::Resource(pdu, sdu, tx_reporter, cipher) => {
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
                             // Create fragger, either to send the whole PDU or to start fragmentation
-                            let mut fragger = BsFragger::new(pdu, sdu, tx_reporter);
-                            if !fragger.get_next_chunk(&mut buf) {
+                            let mut fragger = BsFragger::new(pdu, sdu, tx_reporter).with_cipher(cipher);
+                            if !fragger.get_next_chunk(&mut buf, ts) {
                                 // Fragmentation was started and we have more chunks to send
                                 // Enqueue fragger with remaining data for retrieval next frame
                                 self.dl_enqueue_tma_frag_next_frame(fragger);
@@ -722,7 +818,7 @@ impl BsChannelScheduler {
                         DlSchedElem::FragBuf(mut fragger) => {
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
-                            if !fragger.get_next_chunk(&mut buf) {
+                            if !fragger.get_next_chunk(&mut buf, ts) {
                                 // Fragmentation was continued and we still have more chunks to send
                                 // Re-enqueue fragger with remaining data for retrieval next frame
                                 self.dl_enqueue_tma_frag_next_frame(fragger);
@@ -752,12 +848,11 @@ impl BsChannelScheduler {
         }
 
         // If any signalling could not be sent this slot, it should be in the next slot queue
-        // Swap next slot queue into current slot queue, to schedule it for next frame
+        // Move it into the current slot queue, to schedule it for next frame. Only signalling held
+        // back for MSs in energy economy may still be queued there.
         if !self.dltx_next_slot_queue.is_empty() {
             let a = &mut self.dltx_queues[ts.t as usize - 1];
-            let b = &mut self.dltx_next_slot_queue;
-            assert!(a.is_empty(), "queue should be empty");
-            std::mem::swap(a, b);
+            a.append(&mut self.dltx_next_slot_queue);
         }
 
         buf_opt
@@ -809,7 +904,7 @@ impl BsChannelScheduler {
 
     /// Return first queued grant.
     /// If none; return first in-progress fragmented message.
-    /// If none; return first to-be-transmitted resource.
+    /// If none; return first to-be-transmitted resource, skipping MCCH resources for MSs in energy economy that are asleep.
     /// If none, return None.
     pub fn dl_take_prioritized_sched_item(&mut self, ts: TdmaTime) -> Option<DlSchedElem> {
         if ts.f == 18 {
@@ -832,8 +927,16 @@ impl BsChannelScheduler {
         }
 
         // Return Resources last
-        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(_, _, _))) {
-            return Some(q.remove(i));
+        let is_mcch = self.carrier == 0 && ts.t == 1;
+        let pos = self.dltx_queues[slot].iter().position(|e| match e {
+            DlSchedElem::Resource(pdu, ..) => match pdu.addr {
+                Some(addr) if is_mcch && addr.ssi_type != SsiType::Gssi => self.ms_is_awake(addr.ssi, ts),
+                _ => true,
+            },
+            _ => false,
+        });
+        if let Some(i) = pos {
+            return Some(self.dltx_queues[slot].remove(i));
         }
 
         None
@@ -867,7 +970,7 @@ impl BsChannelScheduler {
 
         // During hangtime we stop sending traffic frames and switch to signalling mode.
         // Keep traffic mode while FACCH/stealing is still queued for delivery.
-        let hang_effective = if (2..=4).contains(&ts.t) {
+        let hang_effective = if self.is_traffic_ts(ts.t) {
             self.is_hangtime_effective(ts.t)
         } else {
             false
@@ -894,6 +997,7 @@ impl BsChannelScheduler {
                 );
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: self.carrier,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::Stch,
                         mac_block: stch_buf,
@@ -911,6 +1015,7 @@ impl BsChannelScheduler {
                 // Normal traffic: full-slot TCH
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: self.carrier,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::TchS,
                         mac_block: tch_buf,
@@ -931,6 +1036,7 @@ impl BsChannelScheduler {
             if let Some(buf) = buf {
                 TmvUnitdataReqSlot {
                     ts,
+                    carrier: self.carrier,
                     blk1: Some(TmvUnitdataReq {
                         logical_channel: LogicalChannel::SchF,
                         mac_block: buf,
@@ -941,11 +1047,14 @@ impl BsChannelScheduler {
                     ul_phy_chan: ul_phy,
                 }
             } else {
-                // If this is an allocated traffic slot in hangtime, keep it alive with an idle SCH/F (Null PDU).
-                // Otherwise, fall back to default SYNC/SYSINFO.
-                if hang_effective && dl_circuit_active {
+                // If this is an allocated traffic slot in hangtime or a packet data channel, keep it alive
+                // with an idle SCH/F (Null PDU). Otherwise, fall back to default SYNC/SYSINFO, which
+                // only the main carrier transmits.
+                let pdch_idle = (2..=4).contains(&ts.t) && self.pdch[ts.t as usize - 1] && ts.f != 18;
+                if (hang_effective && dl_circuit_active) || pdch_idle || self.carrier != 0 {
                     TmvUnitdataReqSlot {
                         ts,
+                        carrier: self.carrier,
                         blk1: Some(TmvUnitdataReq {
                             logical_channel: LogicalChannel::SchF,
                             mac_block: self.generate_hangtime_idle_schf(),
@@ -959,6 +1068,7 @@ impl BsChannelScheduler {
                     // Put default SYNC/SYSINFO frame
                     TmvUnitdataReqSlot {
                         ts,
+                        carrier: self.carrier,
                         blk1: None,
                         blk2: None,
                         bbk: None,
@@ -1050,6 +1160,27 @@ impl BsChannelScheduler {
         elem
     }
 
+    /// Prepares a scheduled FUTURE timeslot of a secondary carrier, like finalize_ts_for_tick.
+    /// Returns None if the timeslot is idle or in frame 18, in which case nothing is transmitted.
+    pub fn finalize_secondary_ts_for_tick(&mut self) -> Option<TmvUnitdataReqSlot> {
+        assert!(self.carrier != 0, "finalize_secondary_ts_for_tick called for the main carrier");
+        let ts = self.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
+        let idx = ts.t as usize - 1;
+        let in_use = self.circuits.is_active(Direction::Dl, ts.t)
+            || self.circuits.is_active(Direction::Ul, ts.t)
+            || self.hangtime[idx]
+            || !self.dltx_queues[idx].is_empty();
+
+        if ts.f == 18 || !in_use {
+            // Clear UL schedule for this timeslot
+            let index = self.ul_ts_to_sched_index(&ts.add_timeslots(-4));
+            self.ulsched[idx][index].ul1 = None;
+            self.ulsched[idx][index].ul2 = None;
+            return None;
+        }
+        Some(self.finalize_ts_for_tick())
+    }
+
     fn generate_bbk_block(&self, ts: TdmaTime) -> TmvUnitdataReq {
         let (ul_traffic_usage, dl_traffic_usage) = if ts.f == 18 {
             (None, None)
@@ -1066,7 +1197,7 @@ impl BsChannelScheduler {
             let mut aach = AccessAssign::default();
 
             match ts.t {
-                1 => {
+                1 if self.carrier == 0 => {
                     assert!(dl_traffic_usage.is_none(), "DL ts 1 can't be traffic");
                     assert!(ul_traffic_usage.is_none(), "UL ts 1 can't be traffic (is this allowed?"); // TODO FIXME check spec
 
@@ -1083,16 +1214,17 @@ impl BsChannelScheduler {
                         base_frame_len: 4,
                     });
                 }
-                2..=4 => {
-                    // Additional channels (TS2..TS4).
+                1..=4 => {
+                    // Additional channels (TS2..TS4, or any timeslot on a secondary carrier).
                     // Normal operation: Traffic(usage) when a circuit is active, else Unallocated.
                     // Hangtime: immediately switch AACH to AssignedControl so radios
                     // detect the end of traffic in the same frame as D-TX CEASED.
                     // The timeslot may still be in traffic mode (for STCH delivery) but
                     // the AACH reflects the new channel state.
-                    let in_hangtime = (2..=4).contains(&ts.t) && self.hangtime[ts.t as usize - 1];
+                    let in_hangtime = self.is_traffic_ts(ts.t) && self.hangtime[ts.t as usize - 1];
+                    let is_pdch = self.pdch[ts.t as usize - 1] && dl_traffic_usage.is_none() && ul_traffic_usage.is_none();
 
-                    if in_hangtime && (dl_traffic_usage.is_some() || ul_traffic_usage.is_some()) {
+                    if (in_hangtime && (dl_traffic_usage.is_some() || ul_traffic_usage.is_some())) || is_pdch {
                         aach.dl_usage = AccessAssignDlUsage::AssignedControl;
                         // AssignedOnly (Header 2) allows random access for MSs on
                         // the assigned channel while blocking common control MSs.
@@ -1484,7 +1616,7 @@ mod tests {
         };
         let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
         let sdu = BitBuffer::new(0);
-        sched.dl_enqueue_tma(ts.t, pdu, sdu, None);
+        sched.dl_enqueue_tma(ts.t, pdu, sdu, None, None);
 
         let grant = BasicSlotgrant {
             capacity_allocation: BasicSlotgrantCapAlloc::FirstSubslotGranted,
@@ -1507,4 +1639,54 @@ mod tests {
 
         assert!(sched.dltx_queues[ts.t as usize - 1].len() == 1);
     }
+
+    #[test]
+    fn test_dl_energy_economy_holds_mcch_resource() {
+        let mut sched = get_testing_slotter();
+        let addr = TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Issi,
+            ssi: 1234,
+        };
+        // EG2: awake on frames 2, 5, 8, ... of every multiframe
+        let schedule = EnergyEconomySchedule::starting_after(2, TdmaTime::default()).unwrap();
+        sched.set_energy_economy(addr.ssi, Some(schedule));
+
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        sched.dl_enqueue_tma(1, pdu, BitBuffer::new(0), None, None);
+
+        // Held back while the MS sleeps, sent once it is awake
+        let asleep = TdmaTime { t: 1, f: 3, m: 1, h: 0 };
+        assert!(sched.dl_take_prioritized_sched_item(asleep).is_none());
+        assert_eq!(sched.dltx_queues[0].len(), 1);
+        let awake = TdmaTime { t: 1, f: 5, m: 1, h: 0 };
+        assert!(matches!(
+            sched.dl_take_prioritized_sched_item(awake),
+            Some(DlSchedElem::Resource(..))
+        ));
+
+        // An MS that just transmitted listens for the answer
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        sched.dl_enqueue_tma(1, pdu, BitBuffer::new(0), None, None);
+        sched.dl_enqueue_random_access_ack(1, addr);
+        sched.dl_integrate_sched_elems_for_timeslot(asleep);
+        assert!(matches!(
+            sched.dl_take_prioritized_sched_item(asleep),
+            Some(DlSchedElem::Resource(..))
+        ));
+
+        // Group addressed signalling is never held back
+        let group = TetraAddress {
+            encrypted: false,
+            ssi_type: SsiType::Gssi,
+            ssi: 1234,
+        };
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&group, None, false);
+        sched.dl_enqueue_tma(1, pdu, BitBuffer::new(0), None, None);
+        sched.last_heard.clear();
+        assert!(matches!(
+            sched.dl_take_prioritized_sched_item(asleep),
+            Some(DlSchedElem::Resource(..))
+        ));
+    }
 }
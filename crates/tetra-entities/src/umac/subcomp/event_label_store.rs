@@ -1,44 +1,138 @@
-use tetra_core::TetraAddress;
+use std::collections::HashMap;
+
+use tetra_core::{TdmaTime, TetraAddress, TxReporter, multiframes};
 use tetra_pdus::umac::fields::EventLabel;
 
+/// Event labels are 10 bits. Label 0 is left unused.
+const EVENT_LABEL_MAX: EventLabel = 0x3FF;
+
+/// An event label not used in either direction for this long is released. The MS is then addressed by its
+/// SSI again, along with a fresh label.
+pub const EVENT_LABEL_IDLE_TIMEOUT: i32 = multiframes!(60); // About one minute
+
 pub struct EventLabelMapping {
-    // pub valid_until: TdmaTime,
     pub addr: TetraAddress,
     pub label: EventLabel,
+    /// Carrier and timeslot of the channel the label was assigned on. The label is only valid on that channel.
+    pub carrier: u8,
+    pub ts: u8,
+    /// Set once the MS is known to hold the label, as it used the label on the uplink or acknowledged a PDU
+    /// that assigned it. Until then, the label is only sent along with the SSI.
+    pub confirmed: bool,
+    /// Reporter of the last PDU that assigned the label
+    pub assignment: Option<TxReporter>,
+    pub last_used: TdmaTime,
+}
+
+/// How to address a downlink MAC-RESOURCE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DlAddressing {
+    /// SSI only
+    Ssi,
+    /// SSI along with the event label, assigning it to the MS
+    SsiAndLabel(EventLabel),
+    /// Event label only
+    Label(EventLabel),
 }
 
+/// Clause 23.4.2.1.5 event labels, assigned per individual address and channel. An MS gets one on the MCCH, and
+/// one per call on the traffic channel of the call, released once the call on that channel ends.
 pub struct EventLabelStore {
-    labels: std::collections::HashMap<EventLabel, EventLabelMapping>,
+    labels: HashMap<EventLabel, EventLabelMapping>,
+    /// Label by SSI, carrier and timeslot
+    by_chan: HashMap<(u32, u8, u8), EventLabel>,
     next_label: EventLabel,
 }
 
 impl EventLabelStore {
     pub fn new() -> Self {
         Self {
-            labels: std::collections::HashMap::new(),
+            labels: HashMap::new(),
+            by_chan: HashMap::new(),
             next_label: 1,
         }
     }
 
-    /// Get the next free event label. Event labels are allocated linearly, and we assume the next one to be
-    /// free. Upon rollover, we assume old labels to have been dropped by now. If not, we'll crash when inserting
-    /// a label into the labels hashmap.
-    pub fn get_free_label(&mut self) -> EventLabel {
-        let ret = self.next_label;
-        self.next_label = (self.next_label + 1) % 0x1FF;
-        ret
+    /// Get the next free event label. Labels are handed out round robin, so a released label is reused as late
+    /// as possible, leaving an MS that still holds it plenty of time to drop it. Returns None if all are in use.
+    pub fn get_free_label(&mut self) -> Option<EventLabel> {
+        for _ in 0..EVENT_LABEL_MAX {
+            let label = self.next_label;
+            self.next_label = if self.next_label == EVENT_LABEL_MAX {
+                1
+            } else {
+                self.next_label + 1
+            };
+            if !self.labels.contains_key(&label) {
+                return Some(label);
+            }
+        }
+        None
     }
 
-    /// Create an event label for a TetraAddress. There should not yet exist a label for this address, or we
-    /// crash. Returns the generated event label.
-    fn create_label_for_addr(&mut self, addr: TetraAddress) -> EventLabel {
-        assert!(self.get_label_by_ssi(addr.ssi).is_none(), "an event label for SSI already exists");
+    /// Create an event label for a TetraAddress that does not have one on the channel yet. Returns None if no label
+    /// is free.
+    fn create_label_for_addr(&mut self, addr: TetraAddress, carrier: u8, ts: u8, now: TdmaTime) -> Option<EventLabel> {
+        assert!(
+            self.get_label(addr.ssi, carrier, ts).is_none(),
+            "an event label for SSI already exists on the channel"
+        );
 
-        let label = self.get_free_label();
-        let entry = EventLabelMapping { addr, label };
+        let label = self.get_free_label()?;
+        let entry = EventLabelMapping {
+            addr,
+            label,
+            carrier,
+            ts,
+            confirmed: false,
+            assignment: None,
+            last_used: now,
+        };
         self.labels.insert(label, entry);
+        self.by_chan.insert((addr.ssi, carrier, ts), label);
+        tracing::debug!("assigned event label {} to {} on carrier {} ts {}", label, addr, carrier, ts);
+
+        Some(label)
+    }
+
+    /// Determines how to address a downlink PDU for addr on the channel at carrier and ts, assigning a label if
+    /// there is none yet. The tx_reporter of the PDU, if any, is used to learn whether the MS received an assignment.
+    pub fn dl_addressing(
+        &mut self,
+        addr: TetraAddress,
+        carrier: u8,
+        ts: u8,
+        now: TdmaTime,
+        tx_reporter: Option<&TxReporter>,
+    ) -> DlAddressing {
+        let label = match self.get_label(addr.ssi, carrier, ts) {
+            Some(label) => label,
+            None => match self.create_label_for_addr(addr, carrier, ts, now) {
+                Some(label) => label,
+                None => return DlAddressing::Ssi,
+            },
+        };
+
+        let mapping = self.labels.get_mut(&label).unwrap(); // Never fails
+        mapping.last_used = now;
+        if !mapping.confirmed && mapping.assignment.as_ref().is_some_and(|r| r.is_acknowledged()) {
+            mapping.confirmed = true;
+        }
+        if mapping.confirmed {
+            return DlAddressing::Label(label);
+        }
+        if let Some(tx_reporter) = tx_reporter {
+            mapping.assignment = Some(tx_reporter.clone());
+        }
+        DlAddressing::SsiAndLabel(label)
+    }
 
-        label
+    /// Resolves an event label received on the uplink. The MS evidently holds it, so it counts as confirmed.
+    pub fn resolve(&mut self, label: EventLabel, now: TdmaTime) -> Option<TetraAddress> {
+        let mapping = self.labels.get_mut(&label)?;
+        mapping.confirmed = true;
+        mapping.last_used = now;
+        Some(mapping.addr)
     }
 
     /// Retrieve an address by its label. The returned address may be encrypted if
@@ -47,27 +141,124 @@ impl EventLabelStore {
         self.labels.get(&label).map(|event_label| event_label.addr)
     }
 
-    /// Find if a label is associated with some SSI.
-    pub fn get_label_by_ssi(&self, ssi: u32) -> Option<EventLabel> {
-        self.labels
+    /// Find if a label is associated with some SSI on the channel at carrier and ts.
+    pub fn get_label(&self, ssi: u32, carrier: u8, ts: u8) -> Option<EventLabel> {
+        self.by_chan.get(&(ssi, carrier, ts)).copied()
+    }
+
+    pub fn remove_label(&mut self, label: EventLabel) -> Option<EventLabelMapping> {
+        let mapping = self.labels.remove(&label)?;
+        self.by_chan.remove(&(mapping.addr.ssi, mapping.carrier, mapping.ts));
+        tracing::debug!("released event label {} of {}", label, mapping.addr);
+        Some(mapping)
+    }
+
+    /// Releases all labels of the channel at carrier and ts, once the call on it ended
+    pub fn release_channel(&mut self, carrier: u8, ts: u8) {
+        let released: Vec<EventLabel> = self
+            .labels
+            .values()
+            .filter(|m| m.carrier == carrier && m.ts == ts)
+            .map(|m| m.label)
+            .collect();
+        for label in released {
+            self.remove_label(label);
+        }
+    }
+
+    /// Releases the labels idle for EVENT_LABEL_IDLE_TIMEOUT, and those for which keep returns false
+    pub fn expire(&mut self, now: TdmaTime, mut keep: impl FnMut(&TetraAddress) -> bool) {
+        let expired: Vec<EventLabel> = self
+            .labels
             .values()
-            .find(|event_label| event_label.addr.ssi == ssi)
-            .map(|event_label| event_label.label)
+            .filter(|m| m.last_used.age(now) >= EVENT_LABEL_IDLE_TIMEOUT || !keep(&m.addr))
+            .map(|m| m.label)
+            .collect();
+        for label in expired {
+            self.remove_label(label);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::SsiType;
+
+    use super::*;
+
+    const T0: TdmaTime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+
+    fn issi(ssi: u32) -> TetraAddress {
+        TetraAddress::new(ssi, SsiType::Issi)
+    }
+
+    #[test]
+    fn test_label_confirmed_by_ack_or_uplink() {
+        let mut store = EventLabelStore::new();
+
+        // Assigned along with the SSI until the MS acknowledged the assigning PDU
+        let reporter = TxReporter::new();
+        let DlAddressing::SsiAndLabel(label) = store.dl_addressing(issi(1001), 0, 1, T0, Some(&reporter)) else {
+            panic!("expected assignment");
+        };
+        assert_eq!(store.dl_addressing(issi(1001), 0, 1, T0, None), DlAddressing::SsiAndLabel(label));
+        reporter.mark_transmitted();
+        reporter.mark_acknowledged();
+        assert_eq!(store.dl_addressing(issi(1001), 0, 1, T0, None), DlAddressing::Label(label));
+
+        // Labels are only valid on the channel they were assigned on. The traffic channel of a call gets its own,
+        // released along with the call
+        let DlAddressing::SsiAndLabel(call_label) = store.dl_addressing(issi(1001), 0, 3, T0, None) else {
+            panic!("expected assignment");
+        };
+        assert_ne!(label, call_label);
+        store.release_channel(0, 3);
+        assert_eq!(store.get_label(1001, 0, 3), None);
+        assert_eq!(store.dl_addressing(issi(1001), 0, 1, T0, None), DlAddressing::Label(label));
+
+        // Using the label on the uplink confirms it as well
+        let DlAddressing::SsiAndLabel(other) = store.dl_addressing(issi(1002), 0, 1, T0, None) else {
+            panic!("expected assignment");
+        };
+        assert_ne!(label, other);
+        assert_eq!(store.resolve(other, T0).map(|addr| addr.ssi), Some(1002));
+        assert_eq!(store.dl_addressing(issi(1002), 0, 1, T0, None), DlAddressing::Label(other));
     }
 
-    // pub fn remove_label(&mut self, label: EventLabel) -> Option<EventLabel> {
-    //     self.labels.remove(&label)
-    // }
+    #[test]
+    fn test_label_expiry_and_wraparound() {
+        let mut store = EventLabelStore::new();
+        store.dl_addressing(issi(1001), 0, 1, T0, None);
+        store.dl_addressing(issi(1002), 0, 1, T0, None);
 
-    // pub fn contains_label(&self, label: EventLabel) -> bool {
-    //     self.labels.contains_key(&label)
-    // }
+        // Released by the caller, or after idling too long
+        store.expire(T0, |addr| addr.ssi != 1002);
+        assert_eq!(store.len(), 1);
+        let later = T0.add_timeslots(EVENT_LABEL_IDLE_TIMEOUT);
+        store.expire(later, |_| true);
+        assert!(store.is_empty());
 
-    // pub fn len(&self) -> usize {
-    //     self.labels.len()
-    // }
+        // All labels in use: fall back to SSI addressing, no matter how often the counter wraps
+        for ssi in 0..EVENT_LABEL_MAX as u32 {
+            assert!(matches!(
+                store.dl_addressing(issi(2000 + ssi), 0, 1, T0, None),
+                DlAddressing::SsiAndLabel(_)
+            ));
+        }
+        assert_eq!(store.len(), EVENT_LABEL_MAX as usize);
+        assert_eq!(store.dl_addressing(issi(9999), 0, 1, T0, None), DlAddressing::Ssi);
 
-    // pub fn is_empty(&self) -> bool {
-    //     self.labels.is_empty()
-    // }
+        // A released label is handed out again
+        let label = store.get_label(2000, 0, 1).unwrap();
+        store.remove_label(label);
+        assert_eq!(store.dl_addressing(issi(9999), 0, 1, T0, None), DlAddressing::SsiAndLabel(label));
+    }
 }
@@ -1,3 +1,4 @@
+pub mod aie;
 pub mod bs_defrag;
 pub mod bs_frag;
 pub mod bs_sched;
@@ -6,6 +7,7 @@ pub mod defrag;
 pub mod circuit_mgr;
 
 pub mod ms_defrag;
+pub mod ms_frag;
 
 pub mod event_label_store;
 pub mod fillbits;
@@ -1,5 +1,6 @@
-use tetra_core::{BitBuffer, TdmaTime, TetraAddress, Todo};
+use tetra_core::{BitBuffer, TdmaTime, TetraAddress};
 
+use crate::umac::subcomp::aie::AieCipher;
 use crate::umac::subcomp::defrag::{DefragBuffer, DefragBufferState};
 
 const DEFRAG_BUF_MAX_LEN: usize = 4096;
@@ -35,7 +36,7 @@ impl MsDefrag {
     }
 
     /// Inserts a first fragment into a fragbuffer.
-    pub fn insert_first(&mut self, bitbuffer: &mut BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<Todo>) {
+    pub fn insert_first(&mut self, bitbuffer: &mut BitBuffer, t: TdmaTime, addr: TetraAddress, aie_info: Option<AieCipher>) {
         // Reset target buffer if needed
         let ts = (t.t - 1) as usize;
         if self.buffers[ts].state != DefragBufferState::Inactive {
@@ -127,7 +128,7 @@ impl MsDefrag {
     }
 
     /// Retrieves a reference to the AIE info associated with a defrag buffer
-    pub fn get_aie_info(&self, t: TdmaTime) -> Option<&Todo> {
+    pub fn get_aie_info(&self, t: TdmaTime) -> Option<&AieCipher> {
         let ts = (t.t - 1) as usize;
         if self.buffers[ts].state != DefragBufferState::Active {
             tracing::warn!("Defrag buffer {} is not active", ts);
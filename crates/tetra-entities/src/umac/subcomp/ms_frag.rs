@@ -0,0 +1,313 @@
+use std::cmp::min;
+
+use tetra_core::{BitBuffer, TetraAddress, TxReporter};
+
+use tetra_pdus::umac::enums::reservation_requirement::ReservationRequirement;
+use tetra_pdus::umac::pdus::{mac_access::MacAccess, mac_end_ul::MacEndUl, mac_frag_ul::MacFragUl};
+
+use crate::umac::subcomp::bs_sched::SCH_F_CAP;
+use crate::umac::subcomp::fillbits;
+
+/// Capacity of a SCH/HU block (control uplink burst), in bits
+pub const SCH_HU_CAP: usize = 92;
+
+/// MAC-ACCESS header length with SSI address and either a length indication or a capacity request
+const MAC_ACCESS_HDR_LEN: usize = 36;
+
+/// MAC-FRAG (uplink) header length
+const MAC_FRAG_UL_HDR_LEN: usize = 4;
+
+/// MAC-END (uplink) header length, with length indication
+const MAC_END_UL_HDR_LEN: usize = 10;
+
+/// Fragments a TM-SDU for uplink transmission by an MS.
+/// The first chunk is a MAC-ACCESS sent on a random access opportunity (SCH/HU). If the SDU does not fit,
+/// the MAC-ACCESS starts fragmentation and requests capacity for the remainder, which is then sent
+/// as MAC-FRAG and MAC-END in the granted SCH/F slots.
+/// Unlike on the downlink, writing the last chunk does not complete the transfer, as random access may
+/// need to be repeated until the BS acknowledges it. The Umac calls mark_transmitted once that is done.
+#[derive(Debug)]
+pub struct MsFragger {
+    addr: TetraAddress,
+    mac_hdr_is_written: bool,
+    is_fully_written: bool,
+    is_transmitted: bool,
+    sdu: BitBuffer,
+    tx_reporter: Option<TxReporter>,
+}
+
+impl MsFragger {
+    pub fn new(addr: TetraAddress, sdu: BitBuffer, tx_reporter: Option<TxReporter>) -> Self {
+        assert!(sdu.get_pos() == 0, "SDU must be at the start of the buffer");
+        MsFragger {
+            addr,
+            mac_hdr_is_written: false,
+            is_fully_written: false,
+            is_transmitted: false,
+            sdu,
+            tx_reporter,
+        }
+    }
+
+    /// Number of full slots needed to send the given number of SDU bits as MAC-FRAG / MAC-END.
+    fn num_slots_for_remainder(mut sdu_bits: usize) -> usize {
+        let mut num_slots = 0;
+        loop {
+            num_slots += 1;
+            if (MAC_END_UL_HDR_LEN + sdu_bits).div_ceil(8) * 8 <= SCH_F_CAP {
+                return num_slots;
+            }
+            sdu_bits -= min(SCH_F_CAP - MAC_FRAG_UL_HDR_LEN, sdu_bits);
+        }
+    }
+
+    /// Writes MAC-ACCESS to mac_block, starting fragmentation and requesting capacity if needed.
+    /// Returns true if the entire SDU was consumed.
+    fn get_access_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+        assert!(self.sdu.get_pos() == 0, "SDU must be at the start of the buffer");
+
+        let sdu_len_bits = self.sdu.get_len_remaining();
+        let slot_cap_bits = mac_block.get_len_remaining();
+        let num_fill_bits = fillbits::addition::compute_required(MAC_ACCESS_HDR_LEN + sdu_len_bits, slot_cap_bits);
+        let total_len_bits = MAC_ACCESS_HDR_LEN + sdu_len_bits + num_fill_bits;
+
+        let mut pdu = MacAccess {
+            fill_bits: num_fill_bits > 0,
+            encrypted: self.addr.encrypted,
+            addr: Some(self.addr),
+            event_label: None,
+            length_ind: None,
+            frag_flag: None,
+            reservation_req: None,
+        };
+
+        let (sdu_bits, done) = if total_len_bits <= slot_cap_bits {
+            // Fits in a single MAC-ACCESS. The length may round up past the end of the slot.
+            pdu.length_ind = Some(total_len_bits.div_ceil(8) as u8);
+            (sdu_len_bits, true)
+        } else {
+            // Start fragmentation, filling the slot, and request capacity for the remainder
+            let sdu_bits = slot_cap_bits - MAC_ACCESS_HDR_LEN;
+            let num_slots = Self::num_slots_for_remainder(sdu_len_bits - sdu_bits);
+            pdu.fill_bits = false;
+            pdu.frag_flag = Some(true);
+            pdu.reservation_req = Some(ReservationRequirement::from_req_slotcount(num_slots));
+            (sdu_bits, false)
+        };
+
+        tracing::debug!(
+            "-> {} sdu {}",
+            pdu,
+            self.sdu
+                .raw_dump_bin(false, false, self.sdu.get_pos(), self.sdu.get_pos() + sdu_bits)
+        );
+
+        pdu.to_bitbuf(mac_block);
+        mac_block.copy_bits(&mut self.sdu, sdu_bits);
+        if done {
+            fillbits::addition::write(mac_block, Some(num_fill_bits));
+        }
+
+        self.mac_hdr_is_written = true;
+        done
+    }
+
+    /// After the MAC-ACCESS was sent, writes the next MAC-FRAG or MAC-END to a granted slot.
+    /// Returns true when MAC-END was created and no further fragments are needed.
+    fn get_frag_or_end_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+        assert!(self.mac_hdr_is_written, "MAC header should be previously written");
+
+        let sdu_bits = self.sdu.get_len_remaining();
+        let macend_len_bits = MAC_END_UL_HDR_LEN + sdu_bits;
+        let macend_len_bytes = macend_len_bits.div_ceil(8);
+        let slot_cap_bits = mac_block.get_len_remaining();
+
+        if macend_len_bytes * 8 <= slot_cap_bits {
+            // Fits in single MAC-END
+            let num_fill_bits = fillbits::addition::compute_required(macend_len_bits, slot_cap_bits);
+            let pdu = MacEndUl {
+                fill_bits: num_fill_bits > 0,
+                length_ind: Some(macend_len_bytes as u8),
+                reservation_req: None,
+            };
+
+            tracing::debug!(
+                "-> {} sdu {}",
+                pdu,
+                self.sdu
+                    .raw_dump_bin(false, false, self.sdu.get_pos(), self.sdu.get_pos() + sdu_bits)
+            );
+
+            if let Err(e) = pdu.to_bitbuf(mac_block) {
+                tracing::warn!("Failed to serialize MAC-END: {:?}", e);
+                return false;
+            }
+            mac_block.copy_bits(&mut self.sdu, sdu_bits);
+            fillbits::addition::write(mac_block, Some(num_fill_bits));
+            true
+        } else {
+            // MAC-FRAG fills the slot
+            let sdu_bits_in_frag = min(slot_cap_bits - MAC_FRAG_UL_HDR_LEN, sdu_bits);
+            let num_fill_bits = slot_cap_bits - MAC_FRAG_UL_HDR_LEN - sdu_bits_in_frag;
+            let pdu = MacFragUl {
+                fill_bits: num_fill_bits > 0,
+            };
+
+            tracing::debug!(
+                "-> {} sdu {}",
+                pdu,
+                self.sdu
+                    .raw_dump_bin(false, false, self.sdu.get_pos(), self.sdu.get_pos() + sdu_bits_in_frag)
+            );
+
+            pdu.to_bitbuf(mac_block);
+            mac_block.copy_bits(&mut self.sdu, sdu_bits_in_frag);
+            fillbits::addition::write(mac_block, Some(num_fill_bits));
+            false
+        }
+    }
+
+    /// Writes the next chunk to mac_block. The first chunk is a MAC-ACCESS, to be sent in a SCH/HU block.
+    /// Subsequent chunks are MAC-FRAG or MAC-END, to be sent in SCH/F blocks.
+    /// Returns bool is_fully_written
+    pub fn get_next_chunk(&mut self, mac_block: &mut BitBuffer) -> bool {
+        assert!(!self.is_fully_written, "all fragments have already been produced");
+
+        self.is_fully_written = if !self.mac_hdr_is_written {
+            self.get_access_chunk(mac_block)
+        } else {
+            self.get_frag_or_end_chunk(mac_block)
+        };
+        self.is_fully_written
+    }
+
+    /// Reports the SDU as transmitted, after all chunks were sent and the random access was acknowledged
+    pub fn mark_transmitted(&mut self) {
+        assert!(self.is_fully_written, "SDU was not fully written");
+        self.is_transmitted = true;
+        if let Some(tx_reporter) = &self.tx_reporter {
+            tx_reporter.mark_transmitted();
+        }
+    }
+
+    /// True if chunks remain to be written after the MAC-ACCESS
+    pub fn needs_more_chunks(&self) -> bool {
+        !self.is_fully_written
+    }
+
+    /// True if the next chunk is the MAC-ACCESS, requiring a random access opportunity
+    pub fn needs_random_access(&self) -> bool {
+        !self.mac_hdr_is_written
+    }
+
+    /// Restart from the MAC-ACCESS, for instance when random access was not acknowledged
+    /// or the granted capacity was lost.
+    pub fn rewind(&mut self) {
+        assert!(!self.is_transmitted, "can't rewind a transmitted SDU");
+        self.sdu.seek(0);
+        self.mac_hdr_is_written = false;
+        self.is_fully_written = false;
+    }
+}
+
+impl Drop for MsFragger {
+    fn drop(&mut self) {
+        if !self.is_transmitted
+            && let Some(tx_reporter) = &self.tx_reporter
+            && tx_reporter.get_state() == tetra_core::TxState::Pending
+        {
+            tx_reporter.mark_discarded();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::{SsiType, TxState, debug};
+
+    use super::*;
+
+    fn get_default_addr() -> TetraAddress {
+        TetraAddress::new(1234, SsiType::Ssi)
+    }
+
+    #[test]
+    fn test_single_access() {
+        debug::setup_logging_verbose();
+        let sdu = BitBuffer::from_bitstr("111000111");
+        let mut fragger = MsFragger::new(get_default_addr(), sdu, None);
+
+        let mut mac_block = BitBuffer::new(SCH_HU_CAP);
+        assert!(fragger.get_next_chunk(&mut mac_block), "Should be done in single chunk");
+
+        mac_block.seek(0);
+        let pdu = MacAccess::from_bitbuf(&mut mac_block).unwrap();
+        assert_eq!(pdu.length_ind, Some(6));
+        assert!(pdu.fill_bits);
+        assert_eq!(mac_block.read_bits(9), Some(0b111000111));
+    }
+
+    #[test]
+    fn test_access_frag_end() {
+        debug::setup_logging_verbose();
+        let vec = "01010110010011000010101010010010110101010110010011001011111110101011001010010110111001011111111111100010011000000011010011001110010111110010100100010111010110000010010001101000011000000111101011010001001111001110110100000101010111110100010000100101001100011110010111001010101001110110111010001001101101111100111001000001111100101010000010111";
+        let reporter = TxReporter::new_unacked();
+        let mut fragger = MsFragger::new(get_default_addr(), BitBuffer::from_bitstr(vec), Some(reporter.clone()));
+        let mut reconstructed = String::new();
+
+        let mut mac_block = BitBuffer::new(SCH_HU_CAP);
+        assert!(!fragger.get_next_chunk(&mut mac_block));
+        mac_block.seek(0);
+        let pdu = MacAccess::from_bitbuf(&mut mac_block).unwrap();
+        assert!(pdu.is_frag_start());
+        assert_eq!(pdu.reservation_req, Some(ReservationRequirement::Req2Slots));
+        mac_block.set_raw_start(mac_block.get_raw_pos());
+        reconstructed += &mac_block.to_bitstr();
+        assert!(!reporter.is_in_final_state());
+
+        let mut mac_block = BitBuffer::new(SCH_F_CAP);
+        assert!(!fragger.get_next_chunk(&mut mac_block));
+        mac_block.seek(0);
+        MacFragUl::from_bitbuf(&mut mac_block).unwrap();
+        mac_block.set_raw_start(mac_block.get_raw_pos());
+        reconstructed += &mac_block.to_bitstr();
+        assert!(!reporter.is_in_final_state());
+
+        let mut mac_block = BitBuffer::new(SCH_F_CAP);
+        assert!(fragger.get_next_chunk(&mut mac_block));
+        mac_block.seek(0);
+        MacEndUl::from_bitbuf(&mut mac_block).unwrap();
+        mac_block.set_raw_start(mac_block.get_raw_pos());
+        reconstructed += &mac_block.to_bitstr();
+        assert!(!reporter.is_in_final_state());
+
+        fragger.mark_transmitted();
+        assert!(reporter.is_in_final_state() && reporter.is_transmitted());
+
+        assert!(
+            reconstructed.starts_with(vec),
+            "Original vec should be contained in reconstructed string"
+        );
+    }
+
+    #[test]
+    fn test_rewind_and_drop() {
+        debug::setup_logging_verbose();
+        let sdu = BitBuffer::from_bitstr(&"1".repeat(100));
+        let reporter = TxReporter::new_unacked();
+        let mut fragger = MsFragger::new(get_default_addr(), sdu, Some(reporter.clone()));
+
+        let mut first = BitBuffer::new(SCH_HU_CAP);
+        assert!(!fragger.get_next_chunk(&mut first));
+        assert!(!fragger.needs_random_access());
+
+        fragger.rewind();
+        assert!(fragger.needs_random_access());
+        let mut again = BitBuffer::new(SCH_HU_CAP);
+        assert!(!fragger.get_next_chunk(&mut again));
+        assert_eq!(first.to_bitstr(), again.to_bitstr());
+
+        drop(fragger);
+        assert_eq!(reporter.get_state(), TxState::Discarded);
+    }
+}
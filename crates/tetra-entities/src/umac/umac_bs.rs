@@ -1,15 +1,16 @@
 use std::panic;
+use std::sync::Arc;
 
-use tetra_config::bluestation::SharedConfig;
+use tetra_config::bluestation::{AieKeyClass, CfgCellInfo, SecurityClass, SharedConfig};
 use tetra_core::freqs::FreqInfo;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, TimeslotAllocator, Todo, unimplemented_log};
 use tetra_pdus::mle::fields::bs_service_details::BsServiceDetails;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
 use tetra_pdus::umac::enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag;
-use tetra_pdus::umac::fields::channel_allocation::ChanAllocElement;
+use tetra_pdus::umac::fields::channel_allocation::{ChanAllocElement, ExtCarrierNum};
 use tetra_pdus::umac::fields::sysinfo_default_def_for_access_code_a::SysinfoDefaultDefForAccessCodeA;
 use tetra_pdus::umac::fields::sysinfo_ext_services::SysinfoExtendedServices;
 use tetra_pdus::umac::pdus::mac_access::MacAccess;
@@ -23,6 +24,7 @@ use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 use tetra_pdus::umac::pdus::mac_u_blck::MacUBlck;
 use tetra_pdus::umac::pdus::mac_u_signal::MacUSignal;
 use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::pdch::PdchControl;
 use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
@@ -32,11 +34,13 @@ use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::lmac::components::scrambler;
+use crate::umac::subcomp::aie::{AieCipher, ENCRYPTION_MODE, KeystreamGenerator, TestKsg};
 use crate::umac::subcomp::bs_sched::{BsChannelScheduler, PrecomputedUmacPdus, TCH_S_CAP};
 use crate::umac::subcomp::fillbits;
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
 use super::subcomp::bs_defrag::BsDefrag;
+use super::subcomp::event_label_store::{DlAddressing, EventLabelStore};
 
 pub struct UmacBs {
     self_component: TetraEntity,
@@ -48,18 +52,27 @@ pub struct UmacBs {
     /// When using only a single base radio, we can set this to a fixed value
     endpoint_id: u32,
 
+    /// Keystream generator for air interface encryption
+    ksg: Arc<dyn KeystreamGenerator>,
+
     /// Subcomponents
     defrag: BsDefrag,
     /// Pending STCH MAC-DATA spanning block1+block2 (length_ind=0b111110), keyed by timeslot.
     pending_stch: Option<PendingStch>,
-    // event_label_store: EventLabelStore,
+    /// Event labels of the MSs addressed on the MCCH, and on the traffic channels of their calls
+    event_label_store: EventLabelStore,
     /// Contains UL/DL scheduling logic
     /// Access to this field is used only by testing code
     pub channel_scheduler: BsChannelScheduler,
+    /// Schedulers for the secondary carriers, carrying traffic channels only. Index 0 is carrier 1.
+    pub secondary_schedulers: Vec<BsChannelScheduler>,
     // ulrx_scheduler: UlScheduler,
-    /// Timestamp of last received UL voice frame per timeslot (0-indexed: ts1..ts4).
+    /// Timestamp of last received UL voice frame per carrier and timeslot (0-indexed: ts1..ts4).
     /// Used to detect UL inactivity when a radio disappears mid-transmission.
-    last_ul_voice: [Option<TdmaTime>; 4],
+    last_ul_voice: Vec<[Option<TdmaTime>; 4]>,
+    /// Per carrier and timeslot (0-indexed), the timeslot whose DL carries its UL voice in a duplex call.
+    /// Duplex peers are always on the same carrier. Timeslots without an entry loop UL voice back onto their own DL.
+    duplex_peer: Vec<[Option<u8>; 4]>,
 }
 
 struct PendingStch {
@@ -71,25 +84,56 @@ struct PendingStch {
 }
 
 impl UmacBs {
+    /// Uses the TestKsg stand-in for air interface encryption
     pub fn new(config: SharedConfig) -> Self {
+        Self::with_ksg(config, Arc::new(TestKsg))
+    }
+
+    pub fn with_ksg(config: SharedConfig, ksg: Arc<dyn KeystreamGenerator>) -> Self {
         let c = config.config();
         let scrambling_code = scrambler::tetra_scramb_get_init(c.net.mcc, c.net.mnc, c.cell.colour_code);
         let system_wide_services = Self::get_system_wide_services_state(&config);
         let precomps = Self::generate_precomps(&config);
+        let num_carriers = 1 + c.cell.secondary_carriers.len();
+        let secondary_schedulers = (1..num_carriers)
+            .map(|carrier| BsChannelScheduler::new_for_carrier(carrier as u8, scrambling_code, Self::generate_precomps(&config)))
+            .collect();
         Self {
             self_component: TetraEntity::Umac,
             config,
             dltime: TdmaTime::default(),
             system_wide_services,
             endpoint_id: 1,
+            ksg,
             defrag: BsDefrag::new(),
             pending_stch: None,
-            // event_label_store: EventLabelStore::new(),
+            event_label_store: EventLabelStore::new(),
             channel_scheduler: BsChannelScheduler::new(scrambling_code, precomps),
-            last_ul_voice: [None; 4],
+            secondary_schedulers,
+            last_ul_voice: vec![[None; 4]; num_carriers],
+            duplex_peer: vec![[None; 4]; num_carriers],
+        }
+    }
+
+    /// Scheduler for the given carrier index, 0 being the main carrier
+    pub fn scheduler(&self, carrier: u8) -> &BsChannelScheduler {
+        match carrier {
+            0 => &self.channel_scheduler,
+            _ => &self.secondary_schedulers[carrier as usize - 1],
+        }
+    }
+
+    pub fn scheduler_mut(&mut self, carrier: u8) -> &mut BsChannelScheduler {
+        match carrier {
+            0 => &mut self.channel_scheduler,
+            _ => &mut self.secondary_schedulers[carrier as usize - 1],
         }
     }
 
+    fn num_carriers(&self) -> u8 {
+        1 + self.secondary_schedulers.len() as u8
+    }
+
     /// Precomputes SYNC, SYSINFO messages (and subfield variants) for faster TX msg building
     /// Precomputed PDUs are passed to scheduler
     /// Needs to be re-invoked if any network parameter changes
@@ -97,16 +141,19 @@ impl UmacBs {
         let c = config.config();
 
         // TODO FIXME make more/all parameters configurable
+        let security = c.security.as_ref();
+        let security_class = security.map(|s| s.security_class).unwrap_or_default();
+        let class3 = security_class == SecurityClass::Class3;
         let ext_services = SysinfoExtendedServices {
-            auth_required: false,
-            class1_supported: true,
-            class2_supported: true,
-            class3_supported: false,
-            sck_n: Some(0),
-            dck_retrieval_during_cell_select: None,
-            dck_retrieval_during_cell_reselect: None,
-            linked_gck_crypto_periods: None,
-            short_gck_vn: None,
+            auth_required: security.is_some_and(|s| s.authentication_required),
+            class1_supported: security_class == SecurityClass::Class1,
+            class2_supported: security_class == SecurityClass::Class2,
+            class3_supported: class3,
+            sck_n: (!class3).then(|| security.map(|s| s.sck_number - 1).unwrap_or(0)),
+            dck_retrieval_during_cell_select: class3.then_some(false),
+            dck_retrieval_during_cell_reselect: class3.then_some(false),
+            linked_gck_crypto_periods: class3.then_some(false),
+            short_gck_vn: class3.then_some(0),
             sdstl_addressing_method: 2,
             gck_supported: false,
             section: 0,
@@ -206,6 +253,25 @@ impl UmacBs {
         }
     }
 
+    /// Cipher for traffic to or from the given address, if air interface encryption applies.
+    /// Individual addresses use the key agreed at registration. Group addresses use the SCK
+    /// in class 2, and are in clear in class 3 as group cipher keys are not supported.
+    fn aie_cipher_for(&self, addr: &TetraAddress) -> Option<AieCipher> {
+        let config = self.config.config();
+        let security = config.security.as_ref()?;
+        let ck = match addr.ssi_type {
+            SsiType::Gssi => match security.security_class {
+                SecurityClass::Class2 => security.sck?,
+                _ => return None,
+            },
+            _ => match self.config.state_read().subscribers.get_subscriber(addr.ssi)?.aie? {
+                AieKeyClass::Sck => security.sck?,
+                AieKeyClass::Dck(dck) => dck,
+            },
+        };
+        Some(AieCipher::new(self.ksg.clone(), ck))
+    }
+
     /// Retrieve currently set value of system-wide services. If SwMI is active, this governs connection state
     /// Otherwise, value from config is used.
     fn get_system_wide_services_state(config: &SharedConfig) -> bool {
@@ -228,10 +294,23 @@ impl UmacBs {
         }
     }
 
-    fn cmce_to_mac_chanalloc(chan_alloc: &CmceChanAllocReq, carrier_num: u16) -> ChanAllocElement {
+    fn cmce_to_mac_chanalloc(chan_alloc: &CmceChanAllocReq, cell: &CfgCellInfo) -> ChanAllocElement {
         // We grant clch permission for Replace and Additional allocations on the uplink
         let clch_permission = (chan_alloc.alloc_type == ChanAllocType::Replace || chan_alloc.alloc_type == ChanAllocType::Additional)
             && (chan_alloc.ul_dl_assigned == UlDlAssignment::Ul || chan_alloc.ul_dl_assigned == UlDlAssignment::Both);
+        // Secondary carriers are numbered explicitly, with the band, offset and duplex spacing of the main carrier
+        let (carrier_num, ext) = match chan_alloc.carrier {
+            None | Some(0) => (cell.main_carrier, None),
+            Some(idx) => (
+                cell.secondary_carriers[idx as usize - 1],
+                Some(ExtCarrierNum {
+                    freq_band: cell.freq_band,
+                    offset: FreqInfo::freq_offset_hz_to_id(cell.freq_offset_hz).expect("FreqInfo only accepts offsets with an id"),
+                    duplex_spacing: cell.duplex_spacing_id,
+                    reverse_operation: cell.reverse_operation,
+                }),
+            ),
+        };
         ChanAllocElement {
             alloc_type: chan_alloc.alloc_type,
             ts_assigned: chan_alloc.timeslots,
@@ -239,7 +318,7 @@ impl UmacBs {
             clch_permission,
             cell_change_flag: false,
             carrier_num,
-            ext: None,
+            ext,
             mon_pattern: 0,
             frame18_mon_pattern: Some(0),
         }
@@ -421,6 +500,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let carrier = prim.carrier;
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         let pdu = match MacData::from_bitbuf(&mut prim.pdu) {
@@ -435,11 +515,15 @@ impl UmacBs {
         };
 
         // Get addr, either from pdu addr field or by resolving the event label
-        if pdu.event_label.is_some() {
-            unimplemented_log!("event labels not implemented");
-            return;
-        }
-        let addr = pdu.addr.unwrap();
+        let addr = if let Some(label) = pdu.event_label {
+            let Some(addr) = self.event_label_store.resolve(label, message.dltime) else {
+                tracing::warn!("rx_mac_data: unknown event label {}", label);
+                return;
+            };
+            addr
+        } else {
+            pdu.addr.unwrap()
+        };
 
         let (mut pdu_len_bits, is_frag_start, second_half_stolen, is_null_pdu) = {
             if let Some(len_ind) = pdu.length_ind {
@@ -458,7 +542,10 @@ impl UmacBs {
                         // Start of fragmentation
                         (prim.pdu.get_len(), true, false, false)
                     }
-                    _ => panic!("rx_mac_data: Invalid length_ind {}", len_ind),
+                    _ => {
+                        tracing::warn!("rx_mac_data: reserved length_ind {}, discarding", len_ind);
+                        return;
+                    }
                 }
             } else {
                 // We have a capacity request
@@ -480,6 +567,10 @@ impl UmacBs {
             tracing::warn!("truncating MAC-DATA len from {} to {}", pdu_len_bits, prim.pdu.get_len());
             pdu_len_bits = prim.pdu.get_len() as usize;
         }
+        if pdu_len_bits < prim.pdu.get_pos() {
+            tracing::warn!("discarding MAC-DATA with length {} shorter than its header", pdu_len_bits);
+            return;
+        }
 
         // Strip fill bits. Maintain original end to allow for later parsing of a second mac block
         tracing::trace!("rx_mac_data: {}", prim.pdu.dump_bin_full(true));
@@ -508,19 +599,24 @@ impl UmacBs {
         }
 
         // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_data: Encryption mode > 0");
-            return;
-        }
+        let cipher = if pdu.encrypted {
+            let Some(cipher) = self.aie_cipher_for(&addr) else {
+                tracing::warn!("rx_mac_data: encrypted PDU from {} without agreed cipher key", addr);
+                return;
+            };
+            Some(cipher)
+        } else {
+            None
+        };
 
         // Handle reservation if present
         // let ul_time = message.dltime.add_timeslots(-2);
         if let Some(res_req) = &pdu.reservation_req {
             tracing::error!("rx_mac_data: time {:?}", message.dltime);
-            let grant = self.channel_scheduler.ul_process_cap_req(message.dltime.t, addr, res_req);
+            let grant = self.scheduler_mut(carrier).ul_process_cap_req(message.dltime.t, addr, res_req);
             if let Some(grant) = grant {
                 // Schedule grant
-                self.channel_scheduler.dl_enqueue_grant(message.dltime.t, addr, grant);
+                self.scheduler_mut(carrier).dl_enqueue_grant(message.dltime.t, addr, grant);
             } else {
                 tracing::warn!("rx_mac_data: No grant for reservation request {:?}", res_req);
             }
@@ -528,9 +624,13 @@ impl UmacBs {
 
         tracing::debug!("rx_mac_data: {}", prim.pdu.dump_bin_full(true));
         if is_frag_start {
-            // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+            // Fragmentation start, add to defragmenter. Decrypted once complete.
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, cipher);
         } else {
+            if let Some(cipher) = &cipher {
+                cipher.apply(&mut prim.pdu, message.dltime, Direction::Ul);
+            }
+
             // Pass directly to LLC
             let sdu = {
                 if prim.pdu.get_len_remaining() == 0 {
@@ -583,6 +683,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let carrier = prim.carrier;
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         let pdu = match MacAccess::from_bitbuf(&mut prim.pdu) {
@@ -597,9 +698,12 @@ impl UmacBs {
         };
 
         // Resolve event label (if supplied)
-        let addr = if let Some(_label) = pdu.event_label {
-            tracing::warn!("event labels not implemented");
-            return;
+        let addr = if let Some(label) = pdu.event_label {
+            let Some(addr) = self.event_label_store.resolve(label, message.dltime) else {
+                tracing::warn!("rx_mac_access: unknown event label {}", label);
+                return;
+            };
+            addr
         } else if let Some(addr) = pdu.addr {
             addr
         } else {
@@ -630,6 +734,10 @@ impl UmacBs {
             tracing::warn!("truncating MAC-ACCESS len from {} to {}", pdu_len_bits, prim.pdu.get_len());
             pdu_len_bits = prim.pdu.get_len();
         }
+        if pdu_len_bits < prim.pdu.get_pos() {
+            tracing::warn!("discarding MAC-ACCESS with length {} shorter than its header", pdu_len_bits);
+            return;
+        }
 
         // Strip fill bits. Maintain original end to allow for later parsing of a second mac block
         // tracing::trace!("rx_mac_access: {}", prim.pdu.dump_bin_full(true));
@@ -654,22 +762,27 @@ impl UmacBs {
             return;
         }
 
+        // Decrypt if needed. Without a key, the PDU is dropped unacknowledged.
+        let cipher = if pdu.encrypted {
+            let Some(cipher) = self.aie_cipher_for(&addr) else {
+                tracing::warn!("rx_mac_access: encrypted PDU from {} without agreed cipher key", addr);
+                return;
+            };
+            Some(cipher)
+        } else {
+            None
+        };
+
         // Schedule acknowledgement of this message
         // let ul_time = message.dltime.add_timeslots(-2);
-        self.channel_scheduler.dl_enqueue_random_access_ack(message.dltime.t, addr);
-
-        // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_access: Encryption mode > 0");
-            return;
-        }
+        self.scheduler_mut(carrier).dl_enqueue_random_access_ack(message.dltime.t, addr);
 
         // Handle reservation if present
         if let Some(res_req) = &pdu.reservation_req {
-            let grant = self.channel_scheduler.ul_process_cap_req(message.dltime.t, addr, res_req);
+            let grant = self.scheduler_mut(carrier).ul_process_cap_req(message.dltime.t, addr, res_req);
             if let Some(grant) = grant {
                 // Schedule grant
-                self.channel_scheduler.dl_enqueue_grant(message.dltime.t, addr, grant);
+                self.scheduler_mut(carrier).dl_enqueue_grant(message.dltime.t, addr, grant);
             } else {
                 tracing::warn!("rx_mac_access: No grant for reservation request {:?}", res_req);
             }
@@ -677,9 +790,13 @@ impl UmacBs {
 
         // tracing::debug!("rx_mac_access: {}", prim.pdu.dump_bin_full(true));
         if pdu.is_frag_start() {
-            // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+            // Fragmentation start, add to defragmenter. Decrypted once complete.
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, cipher);
         } else {
+            if let Some(cipher) = &cipher {
+                cipher.apply(&mut prim.pdu, message.dltime, Direction::Ul);
+            }
+
             // Pass directly to LLC
             if prim.pdu.get_len_remaining() == 0 {
                 // Either this is a null pdu or we are at the end of the block
@@ -739,6 +856,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let carrier = prim.carrier;
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
@@ -766,17 +884,13 @@ impl UmacBs {
         prim.pdu.set_raw_end(prim.pdu.get_raw_start() + pdu_len_bits);
         tracing::debug!("rx_mac_frag_ul: pdu_len_bits: {} fill_bits: {}", pdu_len_bits, num_fill_bits);
 
-        // Get slot owner from schedule, decrypt if needed
+        // Get slot owner from schedule. Encrypted fragments are decrypted once complete
         // let ul_time = message.dltime.add_timeslots(-2);
-        let Some(slot_owner) = self.channel_scheduler.ul_get_slot_owner(message.dltime, prim.block_num) else {
+        let Some(slot_owner) = self.scheduler(carrier).ul_get_slot_owner(message.dltime, prim.block_num) else {
             tracing::warn!("rx_mac_frag_ul: Received MAC-FRAG-UL for unassigned block {:?}", prim.block_num);
-            self.channel_scheduler.dump_ul_schedule_full(true);
+            self.scheduler(carrier).dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented_log!("rx_mac_frag_ul: Encryption not supported");
-            return;
-        }
 
         // Insert into defragmenter
         self.defrag.insert_next(&mut prim.pdu, slot_owner, message.dltime);
@@ -787,6 +901,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let carrier = prim.carrier;
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
@@ -812,6 +927,10 @@ impl UmacBs {
             tracing::warn!("truncating MAC-END-UL len from {} to {}", pdu_len_bits, prim.pdu.get_len());
             pdu_len_bits = prim.pdu.get_len();
         }
+        if pdu_len_bits < prim.pdu.get_pos() {
+            tracing::warn!("discarding MAC-END-UL with length {} shorter than its header", pdu_len_bits);
+            return;
+        }
 
         // Strip fill bits if any
         let num_fill_bits = {
@@ -834,28 +953,34 @@ impl UmacBs {
 
         // Get slot owner from schedule, decrypt if needed
         // let ul_time = message.dltime.add_timeslots(-2);
-        let Some(slot_owner) = self.channel_scheduler.ul_get_slot_owner(message.dltime, prim.block_num) else {
+        let Some(slot_owner) = self.scheduler(carrier).ul_get_slot_owner(message.dltime, prim.block_num) else {
             tracing::warn!("rx_mac_end_ul: Received MAC-END-UL for unassigned block {:?}", prim.block_num);
-            self.channel_scheduler.dump_ul_schedule_full(true);
+            self.scheduler(carrier).dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented!("rx_mac_end_ul: Encryption not supported");
-        }
 
         // Insert last fragment and retrieve finalized block
         let defragbuf = self.defrag.insert_last(&mut prim.pdu, slot_owner, message.dltime);
-        let Some(defragbuf) = defragbuf else {
+        let Some(mut defragbuf) = defragbuf else {
             tracing::warn!("rx_mac_end_ul: could not obtain defragged buf");
             return;
         };
 
+        // Encrypted as a whole, with the keystream for the slot of the first fragment
+        let encrypted = defragbuf.aie_info.is_some();
+        if let Some(cipher) = &defragbuf.aie_info {
+            cipher.apply(&mut defragbuf.buffer, defragbuf.t_first, Direction::Ul);
+        }
+
         // Handle reservation if present
         if let Some(res_req) = &pdu.reservation_req {
-            let grant = self.channel_scheduler.ul_process_cap_req(message.dltime.t, defragbuf.addr, res_req);
+            let grant = self
+                .scheduler_mut(carrier)
+                .ul_process_cap_req(message.dltime.t, defragbuf.addr, res_req);
             if let Some(grant) = grant {
                 // Schedule grant
-                self.channel_scheduler.dl_enqueue_grant(message.dltime.t, defragbuf.addr, grant);
+                self.scheduler_mut(carrier)
+                    .dl_enqueue_grant(message.dltime.t, defragbuf.addr, grant);
             } else {
                 tracing::warn!("rx_mac_end_ul: No grant for reservation request {:?}", res_req);
             }
@@ -874,10 +999,10 @@ impl UmacBs {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
-                new_endpoint_id: None,       // TODO FIXME
-                css_endpoint_id: None,       // TODO FIXME
-                air_interface_encryption: 0, // TODO FIXME implement
+                endpoint_id: 0,        // TODO FIXME
+                new_endpoint_id: None, // TODO FIXME
+                css_endpoint_id: None, // TODO FIXME
+                air_interface_encryption: encrypted as Todo,
                 chan_change_response_req: false,
                 chan_change_handle: None,
                 chan_info: None,
@@ -897,6 +1022,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
+        let carrier = prim.carrier;
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
@@ -928,6 +1054,10 @@ impl UmacBs {
             tracing::warn!("truncating MAC-END-HU len from {} to {}", pdu_len_bits, prim.pdu.get_len());
             pdu_len_bits = prim.pdu.get_len();
         }
+        if pdu_len_bits < prim.pdu.get_pos() {
+            tracing::warn!("discarding MAC-END-HU with length {} shorter than its header", pdu_len_bits);
+            return;
+        }
 
         // Strip fill bits if any
         let num_fill_bits = {
@@ -951,28 +1081,34 @@ impl UmacBs {
         );
 
         // Get slot owner from schedule, decrypt if needed
-        let Some(slot_owner) = self.channel_scheduler.ul_get_slot_owner(message.dltime, prim.block_num) else {
+        let Some(slot_owner) = self.scheduler(carrier).ul_get_slot_owner(message.dltime, prim.block_num) else {
             tracing::warn!("rx_mac_end_hu: Received MAC-END-HU for unassigned block {:?}", prim.block_num);
-            self.channel_scheduler.dump_ul_schedule_full(true);
+            self.scheduler(carrier).dump_ul_schedule_full(true);
             return;
         };
-        if let Some(_aie_info) = self.defrag.get_aie_info(slot_owner, message.dltime) {
-            unimplemented!("rx_mac_end_hu: Encryption not supported");
-        }
 
         // Insert last fragment and retrieve finalized block
         let defragbuf = self.defrag.insert_last(&mut prim.pdu, slot_owner, message.dltime);
-        let Some(defragbuf) = defragbuf else {
+        let Some(mut defragbuf) = defragbuf else {
             tracing::warn!("rx_mac_end_hu: could not obtain defragged buf");
             return;
         };
 
+        // Encrypted as a whole, with the keystream for the slot of the first fragment
+        let encrypted = defragbuf.aie_info.is_some();
+        if let Some(cipher) = &defragbuf.aie_info {
+            cipher.apply(&mut defragbuf.buffer, defragbuf.t_first, Direction::Ul);
+        }
+
         // Handle reservation if present
         if let Some(res_req) = &pdu.reservation_req {
-            let grant = self.channel_scheduler.ul_process_cap_req(message.dltime.t, defragbuf.addr, res_req);
+            let grant = self
+                .scheduler_mut(carrier)
+                .ul_process_cap_req(message.dltime.t, defragbuf.addr, res_req);
             if let Some(grant) = grant {
                 // Schedule grant
-                self.channel_scheduler.dl_enqueue_grant(message.dltime.t, defragbuf.addr, grant);
+                self.scheduler_mut(carrier)
+                    .dl_enqueue_grant(message.dltime.t, defragbuf.addr, grant);
             } else {
                 tracing::warn!("rx_mac_end_hu: No grant for reservation request {:?}", res_req);
             }
@@ -991,10 +1127,10 @@ impl UmacBs {
                 pdu: Some(defragbuf.buffer),
                 main_address: defragbuf.addr,
                 scrambling_code: prim.scrambling_code,
-                endpoint_id: 0,              // TODO FIXME
-                new_endpoint_id: None,       // TODO FIXME
-                css_endpoint_id: None,       // TODO FIXME
-                air_interface_encryption: 0, // TODO FIXME implement
+                endpoint_id: 0,        // TODO FIXME
+                new_endpoint_id: None, // TODO FIXME
+                css_endpoint_id: None, // TODO FIXME
+                air_interface_encryption: encrypted as Todo,
                 chan_change_response_req: false,
                 chan_change_handle: None,
                 chan_info: None,
@@ -1078,7 +1214,7 @@ impl UmacBs {
             panic!()
         };
 
-        let _pdu = match MacUBlck::from_bitbuf(&mut prim.pdu) {
+        let pdu = match MacUBlck::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -1091,16 +1227,25 @@ impl UmacBs {
 
         // Handle reservation if present
         // TODO implement slightly different handling since enum is not the same.
-        unimplemented!();
+        unimplemented_log!("rx_ul_mac_u_blck: dropping MAC-U-BLCK from event label {}", pdu.event_label);
     }
 
     fn rx_ul_tma_unitdata_req(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_ul_tma_unitdata_req");
 
         // Extract sdu
-        let SapMsgInner::TmaUnitdataReq(prim) = message.msg else { panic!() };
+        let SapMsgInner::TmaUnitdataReq(mut prim) = message.msg else {
+            panic!()
+        };
         let mut sdu = prim.pdu;
 
+        // A packet data channel carries no traffic to steal from. Signalling meant for it, such as
+        // BL-ACKs for PDUs received on it, goes through the normal path on that timeslot.
+        if prim.stealing_permission && self.channel_scheduler.is_pdch(message.dltime.t) {
+            prim.stealing_permission = false;
+            prim.chan_alloc = None;
+        }
+
         // ── FACCH/Stealing path ──────────────────────────────────────────
         // stealing_permission → STCH on traffic channel for time-critical signaling
         // (D-TX CEASED, D-TX GRANTED) per EN 300 392-2, clause 23.5.
@@ -1111,31 +1256,68 @@ impl UmacBs {
             let traffic_ts = prim
                 .chan_alloc
                 .as_ref()
-                .and_then(|ca| ca.timeslots.iter().enumerate().find(|&(_, &set)| set).map(|(i, _)| (i + 1) as u8))
-                .or_else(|| (2..=4u8).find(|&t| self.channel_scheduler.circuit_is_active(Direction::Dl, t)));
+                .and_then(|ca| {
+                    let carrier = ca.carrier.unwrap_or(0);
+                    ca.timeslots
+                        .iter()
+                        .enumerate()
+                        .find(|&(_, &set)| set)
+                        .map(|(i, _)| (carrier, (i + 1) as u8))
+                })
+                .filter(|&(carrier, _)| carrier < self.num_carriers())
+                .or_else(|| {
+                    (0..self.num_carriers()).find_map(|carrier| {
+                        TimeslotAllocator::traffic_timeslots(carrier)
+                            .find(|&t| self.scheduler(carrier).circuit_is_active(Direction::Dl, t))
+                            .map(|t| (carrier, t))
+                    })
+                });
 
-            if let Some(ts) = traffic_ts {
+            if let Some((carrier, ts)) = traffic_ts {
                 // Build MAC-RESOURCE PDU for the STCH half-slot (124 type1 bits).
                 // Same format as MCCH signaling, just in 124 bits instead of 268.
                 const STCH_CAP: usize = 124;
 
-                let usage_marker = prim.chan_alloc.as_ref().and_then(|ca| ca.usage);
+                // An individual MS is addressed by the event label it holds for the call on this channel, assigned
+                // on first use and released once the call ends. A label can't go along with a usage marker, so
+                // PDUs allocating a channel, and any MS addressed by SSI, get the marker instead: MSs on the traffic
+                // channel know the call by it, so the marker of the circuit is used unless a PDU allocates its own
+                let addressing =
+                    if self.config.config().cell.event_labels && prim.main_address.ssi_type == SsiType::Issi && prim.chan_alloc.is_none() {
+                        self.event_label_store
+                            .dl_addressing(prim.main_address, carrier, ts, message.dltime, prim.tx_reporter.as_ref())
+                    } else {
+                        DlAddressing::Ssi
+                    };
+                let (addr, event_label) = match addressing {
+                    DlAddressing::Ssi => (Some(prim.main_address), None),
+                    DlAddressing::SsiAndLabel(label) => (Some(prim.main_address), Some(label)),
+                    DlAddressing::Label(label) => (None, Some(label)),
+                };
+                let usage_marker = if event_label.is_some() {
+                    None
+                } else {
+                    prim.chan_alloc
+                        .as_ref()
+                        .and_then(|ca| ca.usage)
+                        .or_else(|| self.scheduler(carrier).circuit_usage(Direction::Dl, ts))
+                };
                 // Per ETSI 21.4.3.1: "The random access flag shall be used for the BS to
                 // acknowledge a successful random access so as to prevent the MS sending
                 // further random access requests."
                 // Set the flag if this address has a pending RA (dropped by
                 // dl_drop_all_except_stolen when leaving hangtime), or if the address
                 // is ISSI (direct CC-level response to a MAC-ACCESS).
-                let has_pending_ra = self.channel_scheduler.take_pending_ra_ack(ts, prim.main_address.ssi);
+                let has_pending_ra = self.scheduler_mut(carrier).take_pending_ra_ack(ts, prim.main_address.ssi);
                 let is_random_access_response = has_pending_ra || prim.main_address.ssi_type == SsiType::Issi;
                 let mut mac_pdu = MacResource {
                     fill_bits: false,
                     pos_of_grant: 0,
-                    encryption_mode: 0,
+                    encryption_mode: 0, // TODO encrypt stolen signalling, sent in clear for now
                     random_access_flag: is_random_access_response,
                     length_ind: 0,
-                    addr: Some(prim.main_address),
-                    event_label: None,
+                    addr,
+                    event_label,
                     usage_marker,
                     power_control_element: None,
                     slot_granting_element: None,
@@ -1154,13 +1336,14 @@ impl UmacBs {
                 // Remaining bits beyond length_ind are ignored by the receiver.
 
                 tracing::info!(
-                    "rx_ul_tma_unitdata_req: FACCH stealing on ts {} (MAC-RESOURCE + {} SDU bits → {} STCH bits)",
+                    "rx_ul_tma_unitdata_req: FACCH stealing on carrier {} ts {} (MAC-RESOURCE + {} SDU bits → {} STCH bits)",
+                    carrier,
                     ts,
                     sdu_len,
                     stch_block.get_len()
                 );
 
-                self.channel_scheduler.dl_enqueue_stealing(ts, stch_block, prim.tx_reporter);
+                self.scheduler_mut(carrier).dl_enqueue_stealing(ts, stch_block, prim.tx_reporter);
 
                 return;
             } else {
@@ -1173,7 +1356,7 @@ impl UmacBs {
         let (usage_marker, mac_chan_alloc) = if let Some(chan_alloc) = prim.chan_alloc {
             (
                 chan_alloc.usage,
-                Some(Self::cmce_to_mac_chanalloc(&chan_alloc, self.config.config().cell.main_carrier)),
+                Some(Self::cmce_to_mac_chanalloc(&chan_alloc, &self.config.config().cell)),
             )
         } else {
             (None, None)
@@ -1184,28 +1367,73 @@ impl UmacBs {
         // false for GSSI-addressed (unsolicited group signaling like D-SETUP).
         // A radio will reject a random-access-flagged message if it didn't initiate one.
         let is_random_access_response = prim.main_address.ssi_type != SsiType::Gssi;
+
+        // Encrypt unless sent in clear on request, such as the registration exchange
+        let cipher = if sdu.get_len() > 0 && prim.air_interface_encryption != Some(0) {
+            self.aie_cipher_for(&prim.main_address)
+        } else {
+            None
+        };
+        // // Per ETSI EN 300 392-2 Clause 23.3.1.1.2: idle MSes monitor the MCCH (slot 1)
+        // // for signaling. Without common SCCHs, all MSes listen on slot 1.
+        // // All signaling on the normal path (non-FACCH) must go to the MCCH.
+        if message.dltime.t != 1 && !self.channel_scheduler.is_pdch(message.dltime.t) {
+            tracing::warn!("rx_ul_tma_unitdata_req: signaling scheduled for non-MCCH {}", message.dltime.t);
+        }
+        // Signalling to an MS in energy economy waits for its awake frames, as agreed by the MM
+        let mut energy_economy = false;
+        if prim.main_address.ssi_type != SsiType::Gssi {
+            let ssi = prim.main_address.ssi;
+            let schedule = self
+                .config
+                .state_read()
+                .subscribers
+                .get_subscriber(ssi)
+                .and_then(|s| s.energy_economy);
+            energy_economy = schedule.is_some();
+            self.channel_scheduler.set_energy_economy(ssi, schedule);
+        }
+
+        // An individual MS on the MCCH is addressed by its event label once it holds one. Not for MSs in
+        // energy economy, as the scheduler holds their signalling by SSI, nor for PDUs sending the MS elsewhere:
+        // the label is only valid on the MCCH, and a usage marker can't be sent along with a label anyway.
+        let addressing = if self.config.config().cell.event_labels
+            && prim.main_address.ssi_type == SsiType::Issi
+            && message.dltime.t == 1
+            && !energy_economy
+            && usage_marker.is_none()
+            && mac_chan_alloc.is_none()
+        {
+            self.event_label_store
+                .dl_addressing(prim.main_address, 0, message.dltime.t, message.dltime, prim.tx_reporter.as_ref())
+        } else {
+            DlAddressing::Ssi
+        };
+        let (addr, event_label) = match addressing {
+            DlAddressing::Ssi => (Some(prim.main_address), None),
+            DlAddressing::SsiAndLabel(label) => (Some(prim.main_address), Some(label)),
+            DlAddressing::Label(label) => (None, Some(label)),
+        };
+
         let mut pdu = MacResource {
             fill_bits: false, // Updated later
             pos_of_grant: 0,
-            encryption_mode: 0,
+            encryption_mode: if cipher.is_some() { ENCRYPTION_MODE } else { 0 },
             random_access_flag: is_random_access_response,
             length_ind: 0, // Updated later
-            addr: Some(prim.main_address),
-            event_label: None,
+            addr: addr.map(|mut addr| {
+                addr.encrypted = cipher.is_some();
+                addr
+            }),
+            event_label,
             usage_marker,
             power_control_element: None,
             slot_granting_element: None,
             chan_alloc_element: mac_chan_alloc,
         };
         pdu.update_len_and_fill_ind(sdu.get_len());
-
-        // // Per ETSI EN 300 392-2 Clause 23.3.1.1.2: idle MSes monitor the MCCH (slot 1)
-        // // for signaling. Without common SCCHs, all MSes listen on slot 1.
-        // // All signaling on the normal path (non-FACCH) must go to the MCCH.
-        if message.dltime.t != 1 {
-            tracing::warn!("rx_ul_tma_unitdata_req: signaling scheduled for non-MCCH {}", message.dltime.t);
-        }
-        self.channel_scheduler.dl_enqueue_tma(message.dltime.t, pdu, sdu, prim.tx_reporter);
+        self.channel_scheduler
+            .dl_enqueue_tma(message.dltime.t, pdu, sdu, prim.tx_reporter, cipher);
 
         // let enqueue_ts = 1;
         // self.channel_scheduler.dl_enqueue_tma(enqueue_ts, pdu, sdu, prim.tx_reporter);
@@ -1233,17 +1461,22 @@ impl UmacBs {
         match message.msg {
             // DL voice from Brew/upper layer → schedule for DL transmission
             SapMsgInner::TmdCircuitDataReq(prim) => {
-                let ts = prim.ts;
+                let (carrier, ts) = (prim.carrier, prim.ts);
+                if carrier >= self.num_carriers() {
+                    tracing::warn!("rx_tmd_prim: dropping DL voice for unknown carrier {}", carrier);
+                    return;
+                }
                 // Refresh UL inactivity timer when DL voice is being fed (network call scenario).
                 // This prevents false timeout when Brew is the speaker and no UL radio is transmitting.
-                if (1..=4).contains(&ts) && self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
-                    self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                if (1..=4).contains(&ts) && self.scheduler(carrier).circuit_is_active(Direction::Ul, ts) {
+                    self.last_ul_voice[carrier as usize][ts as usize - 1] = Some(self.dltime);
                 }
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
-                    self.channel_scheduler.dl_schedule_tmd(ts, prim.data);
+                if self.scheduler(carrier).circuit_is_active(Direction::Dl, ts) {
+                    self.scheduler_mut(carrier).dl_schedule_tmd(ts, prim.data);
                 } else {
                     tracing::warn!(
-                        "rx_tmd_prim: dropping DL voice on inactive circuit ts={} src={:?} dltime={}",
+                        "rx_tmd_prim: dropping DL voice on inactive circuit carrier={} ts={} src={:?} dltime={}",
+                        carrier,
                         ts,
                         src,
                         dltime
@@ -1252,23 +1485,27 @@ impl UmacBs {
             }
             // UL voice from LMAC → forward to Brew + optional loopback to DL
             SapMsgInner::TmdCircuitDataInd(prim) => {
-                let ts = prim.ts;
+                let (carrier, ts) = (prim.carrier, prim.ts);
                 let data = prim.data;
 
                 // Track last UL voice frame time for inactivity detection
                 if (1..=4).contains(&ts) {
-                    self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                    self.last_ul_voice[carrier as usize][ts as usize - 1] = Some(self.dltime);
                 }
 
                 // Forward UL voice to Brew (User plane) if loaded
                 if self.config.config().brew.is_some() {
-                    if self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
+                    if self.scheduler(carrier).circuit_is_active(Direction::Ul, ts) {
                         let msg = SapMsg {
                             sap: Sap::TmdSap,
                             src: TetraEntity::Umac,
                             dest: TetraEntity::Brew,
                             dltime,
-                            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd { ts, data: data.clone() }),
+                            msg: SapMsgInner::TmdCircuitDataInd(tetra_saps::tmd::TmdCircuitDataInd {
+                                carrier,
+                                ts,
+                                data: data.clone(),
+                            }),
                         };
                         queue.push_back(msg);
                     } else {
@@ -1276,11 +1513,17 @@ impl UmacBs {
                     }
                 }
 
-                // Loopback only if there's an active DL circuit on this timeslot
-                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts) {
-                    tracing::trace!("rx_tmd_prim: loopback UL voice on ts={}", ts);
+                // Loopback only if there's an active DL circuit on the target timeslot.
+                // For duplex calls that is the DL of the other party's timeslot.
+                let dl_ts = if (1..=4).contains(&ts) {
+                    self.duplex_peer[carrier as usize][ts as usize - 1].unwrap_or(ts)
+                } else {
+                    ts
+                };
+                if self.scheduler(carrier).circuit_is_active(Direction::Dl, dl_ts) {
+                    tracing::trace!("rx_tmd_prim: loopback UL voice on carrier={} ts={} to DL ts={}", carrier, ts, dl_ts);
                     if let Some(packed) = pack_ul_acelp_bits(&data) {
-                        self.channel_scheduler.dl_schedule_tmd(ts, packed);
+                        self.scheduler_mut(carrier).dl_schedule_tmd(dl_ts, packed);
                     } else {
                         tracing::warn!(
                             "rx_tmd_prim: unsupported UL voice length {} on ts={}, skipping loopback",
@@ -1289,7 +1532,7 @@ impl UmacBs {
                         );
                     }
                 } else {
-                    tracing::trace!("rx_tmd_prim: no active DL circuit on ts={}, skipping loopback", ts);
+                    tracing::trace!("rx_tmd_prim: no active DL circuit on ts={}, skipping loopback", dl_ts);
                 }
             }
             _ => {
@@ -1369,8 +1612,12 @@ impl UmacBs {
 
     fn rx_control_circuit_open(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
         let CallControl::Open(circuit) = prim else { panic!() };
-        let ts = circuit.ts;
+        let (carrier, ts) = (circuit.carrier, circuit.ts);
         let dir = circuit.direction;
+        if carrier >= self.num_carriers() {
+            tracing::warn!("rx_control_circuit_open: unknown carrier {}, ignoring", carrier);
+            return;
+        }
 
         // Direction::Both needs to be split into separate DL and UL operations
         // because the UMAC circuit manager tracks them independently.
@@ -1385,32 +1632,44 @@ impl UmacBs {
 
         for d in dirs {
             // See if pre-existing circuit somehow needs to be closed
-            if self.channel_scheduler.circuit_is_active(d, ts) {
-                tracing::warn!("rx_control_circuit_open: Circuit already exists for {:?} {}, closing first", d, ts);
-                self.channel_scheduler.close_circuit(d, ts);
+            if self.scheduler(carrier).circuit_is_active(d, ts) {
+                tracing::warn!(
+                    "rx_control_circuit_open: Circuit already exists for {:?} carrier {} ts {}, closing first",
+                    d,
+                    carrier,
+                    ts
+                );
+                self.scheduler_mut(carrier).close_circuit(d, ts);
             }
 
             let c = Circuit {
                 direction: d,
+                carrier,
                 ts: circuit.ts,
                 usage: circuit.usage,
                 circuit_mode: circuit.circuit_mode,
                 speech_service: circuit.speech_service,
                 etee_encrypted: circuit.etee_encrypted,
+                peer_ts: circuit.peer_ts,
             };
-            self.channel_scheduler.create_circuit(d, c);
+            self.scheduler_mut(carrier).create_circuit(d, c);
 
             // Start UL inactivity timer when opening a UL circuit
             if d == Direction::Ul && (1..=4).contains(&ts) {
-                self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                self.last_ul_voice[carrier as usize][ts as usize - 1] = Some(self.dltime);
+                self.duplex_peer[carrier as usize][ts as usize - 1] = circuit.peer_ts;
             }
 
-            tracing::debug!("  rx_control_circuit_open: Setup {:?} circuit for ts {}", d, ts);
+            tracing::debug!("  rx_control_circuit_open: Setup {:?} circuit for carrier {} ts {}", d, carrier, ts);
         }
     }
 
     fn rx_control_circuit_close(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
-        let CallControl::Close(dir, ts) = prim else { panic!() };
+        let CallControl::Close(dir, carrier, ts) = prim else { panic!() };
+        if carrier >= self.num_carriers() {
+            tracing::warn!("rx_control_circuit_close: unknown carrier {}, ignoring", carrier);
+            return;
+        }
 
         // Direction::Both needs to be split into separate DL and UL close operations
         let dirs: Vec<Direction> = match dir {
@@ -1423,19 +1682,32 @@ impl UmacBs {
         };
 
         for d in dirs {
-            match self.channel_scheduler.close_circuit(d, ts) {
+            match self.scheduler_mut(carrier).close_circuit(d, ts) {
                 Some(_) => {
                     // Clear UL inactivity timer when closing a UL circuit
                     if d == Direction::Ul && (1..=4).contains(&ts) {
-                        self.last_ul_voice[ts as usize - 1] = None;
+                        self.last_ul_voice[carrier as usize][ts as usize - 1] = None;
+                        self.duplex_peer[carrier as usize][ts as usize - 1] = None;
                     }
-                    tracing::info!("  rx_control_circuit_close: Closed {:?} circuit for ts {}", d, ts);
+                    tracing::info!(
+                        "  rx_control_circuit_close: Closed {:?} circuit for carrier {} ts {}",
+                        d,
+                        carrier,
+                        ts
+                    );
                 }
                 None => {
-                    tracing::warn!("  rx_control_circuit_close: No {:?} circuit to close for ts {}", d, ts);
+                    tracing::warn!(
+                        "  rx_control_circuit_close: No {:?} circuit to close for carrier {} ts {}",
+                        d,
+                        carrier,
+                        ts
+                    );
                 }
             }
         }
+        // The call on the channel is over, and so are the event labels its MSs held there
+        self.event_label_store.release_channel(carrier, ts);
     }
 
     /// Check for UL inactivity on traffic timeslots. If no voice frames have arrived
@@ -1445,82 +1717,122 @@ impl UmacBs {
         // 3 multiframes ~ 3s. Above T.213 (1s) to tolerate DTX and brief RF fading.
         const UL_INACTIVITY_TIMESLOTS: i32 = 3 * 18 * 4;
 
-        for ts in 1..=4u8 {
+        for (carrier, ts) in (0..self.num_carriers()).flat_map(|c| (1..=4u8).map(move |t| (c, t))) {
             let idx = ts as usize - 1;
 
             // Only check timeslots with an active UL circuit
-            if !self.channel_scheduler.circuit_is_active(Direction::Ul, ts) {
+            if !self.scheduler(carrier).circuit_is_active(Direction::Ul, ts) {
                 continue;
             }
 
             // Skip if in hangtime (no voice expected)
-            if self.channel_scheduler.is_hangtime(ts) {
+            if self.scheduler(carrier).is_hangtime(ts) {
                 continue;
             }
 
             // Check if we've exceeded the inactivity threshold
-            let timed_out = match self.last_ul_voice[idx] {
+            let timed_out = match self.last_ul_voice[carrier as usize][idx] {
                 Some(t) => t.age(self.dltime) > UL_INACTIVITY_TIMESLOTS,
                 None => false, // Initialized at circuit open; shouldn't be None here
             };
 
             if timed_out {
-                tracing::warn!("UL inactivity timeout on ts={}, sending notification to CMCE", ts);
-                self.last_ul_voice[idx] = None;
+                tracing::warn!(
+                    "UL inactivity timeout on carrier={} ts={}, sending notification to CMCE",
+                    carrier,
+                    ts
+                );
+                self.last_ul_voice[carrier as usize][idx] = None;
 
                 queue.push_back(SapMsg {
                     sap: Sap::Control,
                     src: TetraEntity::Umac,
                     dest: TetraEntity::Cmce,
                     dltime: self.dltime,
-                    msg: SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { ts }),
+                    msg: SapMsgInner::CmceCallControl(CallControl::UlInactivityTimeout { carrier, ts }),
                 });
             }
         }
     }
 
+    fn rx_pdch_control(&mut self, prim: PdchControl) {
+        match prim {
+            PdchControl::Open { ts } => {
+                if self.channel_scheduler.circuit_is_active(Direction::Dl, ts)
+                    || self.channel_scheduler.circuit_is_active(Direction::Ul, ts)
+                {
+                    tracing::warn!("rx_pdch_control: circuit active on ts {}, not opening PDCH", ts);
+                    return;
+                }
+                self.channel_scheduler.set_pdch(ts, true);
+            }
+            PdchControl::Close { ts } => {
+                self.channel_scheduler.set_pdch(ts, false);
+            }
+        }
+    }
+
     fn rx_control(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_control");
-        let SapMsgInner::CmceCallControl(prim) = message.msg else {
-            panic!()
+        let prim = match message.msg {
+            SapMsgInner::CmceCallControl(prim) => prim,
+            SapMsgInner::PdchControl(prim) => {
+                self.rx_pdch_control(prim);
+                return;
+            }
+            _ => panic!(),
         };
 
         match prim {
             CallControl::Open(_) => {
                 self.rx_control_circuit_open(queue, prim);
             }
-            CallControl::Close(_, _) => {
+            CallControl::Close(..) => {
                 self.rx_control_circuit_close(queue, prim);
             }
             // Floor-control signals drive traffic↔signalling transitions during hangtime.
-            CallControl::FloorReleased { ts, .. } => {
-                self.channel_scheduler.set_hangtime(ts, true);
+            CallControl::FloorReleased { carrier, ts, .. } if carrier < self.num_carriers() => {
+                self.scheduler_mut(carrier).set_hangtime(ts, true);
                 // Stop checking UL inactivity during hangtime
                 if (1..=4).contains(&ts) {
-                    self.last_ul_voice[ts as usize - 1] = None;
+                    self.last_ul_voice[carrier as usize][ts as usize - 1] = None;
                 }
             }
-            CallControl::FloorGranted { ts, .. } => {
-                self.channel_scheduler.set_hangtime(ts, false);
+            CallControl::FloorGranted { carrier, ts, .. } if carrier < self.num_carriers() => {
+                self.scheduler_mut(carrier).set_hangtime(ts, false);
                 // Restart UL inactivity timer when new speaker gets floor
                 if (1..=4).contains(&ts) {
-                    self.last_ul_voice[ts as usize - 1] = Some(self.dltime);
+                    self.last_ul_voice[carrier as usize][ts as usize - 1] = Some(self.dltime);
                 }
             }
-            CallControl::CallEnded { ts, .. } => {
-                self.channel_scheduler.set_hangtime(ts, false);
+            CallControl::CallEnded { carrier, ts, .. } if carrier < self.num_carriers() => {
+                self.scheduler_mut(carrier).set_hangtime(ts, false);
+                self.event_label_store.release_channel(carrier, ts);
                 if (1..=4).contains(&ts) {
-                    self.last_ul_voice[ts as usize - 1] = None;
+                    self.last_ul_voice[carrier as usize][ts as usize - 1] = None;
                 }
             }
+            CallControl::FloorReleased { carrier, .. }
+            | CallControl::FloorGranted { carrier, .. }
+            | CallControl::CallEnded { carrier, .. } => {
+                tracing::warn!("rx_control: floor control for unknown carrier {}", carrier);
+            }
 
             // UlInactivityTimeout is UMAC→CMCE only, UMAC won't receive it back
             CallControl::UlInactivityTimeout { .. } => {}
 
-            // NetworkCall* are for CMCE ↔ Brew, not UMAC (for now)
-            CallControl::NetworkCallStart { .. } | CallControl::NetworkCallReady { .. } | CallControl::NetworkCallEnd { .. } => {
+            // NetworkCall* and Dtmf are for CMCE ↔ Brew, not UMAC (for now)
+            CallControl::NetworkCallStart { .. }
+            | CallControl::NetworkCallReady { .. }
+            | CallControl::NetworkCallEnd { .. }
+            | CallControl::Dtmf { .. } => {
                 tracing::trace!("rx_control: ignoring CMCE-Brew notification (not for UMAC)");
             }
+
+            // Management API requests are for CMCE
+            CallControl::ListCalls { .. } | CallControl::CallList { .. } | CallControl::ReleaseCall { .. } => {
+                tracing::warn!("rx_control: unexpected management request {:?}", prim);
+            }
         }
     }
 }
@@ -1552,7 +1864,7 @@ impl TetraEntityTrait for UmacBs {
                 self.rx_tlmb_prim(queue, message);
             }
             Sap::TlmcSap => {
-                unimplemented!();
+                tracing::warn!("rx_prim: TLMC-SAP not supported, dropping {}", message.msg);
             }
             Sap::Control => {
                 self.rx_control(queue, message);
@@ -1567,17 +1879,39 @@ impl TetraEntityTrait for UmacBs {
         self.dltime = ts;
         self.refresh_system_wide_services();
 
-        if self.channel_scheduler.cur_dltime != ts && self.channel_scheduler.cur_dltime == (TdmaTime { t: 0, f: 0, m: 0, h: 0 }) {
-            // Upon start of the system, we need to set the dl time for the channel scheduler
-            self.channel_scheduler.set_dl_time(ts);
-        } else {
-            // When running, we adopt the new time and check for desync
-            self.channel_scheduler.tick_start(ts);
+        for carrier in 0..self.num_carriers() {
+            let scheduler = self.scheduler_mut(carrier);
+            if scheduler.cur_dltime != ts && scheduler.cur_dltime == (TdmaTime { t: 0, f: 0, m: 0, h: 0 }) {
+                // Upon start of the system, we need to set the dl time for the channel scheduler
+                scheduler.set_dl_time(ts);
+            } else {
+                // When running, we adopt the new time and check for desync
+                scheduler.tick_start(ts);
+            }
         }
 
         // Check for UL inactivity (stuck transmitter detection)
         self.check_ul_inactivity(queue);
 
+        // Once per multiframe, release the event labels of idle and deregistered MSs
+        if ts.f == 1 && ts.t == 1 {
+            let state = self.config.state_read();
+            self.event_label_store.expire(ts, |addr| state.subscribers.is_registered(addr.ssi));
+        }
+
+        // Secondary carriers go first, so the Phy has them buffered once the main carrier slot arrives
+        for scheduler in self.secondary_schedulers.iter_mut() {
+            if let Some(elem) = scheduler.finalize_secondary_ts_for_tick() {
+                queue.push_back(SapMsg {
+                    sap: Sap::TmvSap,
+                    src: self.self_component,
+                    dest: TetraEntity::Lmac,
+                    dltime: ts.add_timeslots(-1),
+                    msg: SapMsgInner::TmvUnitdataReq(elem),
+                });
+            }
+        }
+
         // Collect/construct traffic that should be sent down to the LMAC
         // This is basically the _previous_ timeslot
         let elem = self.channel_scheduler.finalize_ts_for_tick();
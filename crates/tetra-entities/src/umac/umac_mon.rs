@@ -0,0 +1,718 @@
+use tetra_config::bluestation::SharedConfig;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
+use tetra_pdus::decode::decode_tm_sdu;
+use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
+use tetra_pdus::umac::enums::broadcast_type::BroadcastType;
+use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
+use tetra_pdus::umac::pdus::access_assign::AccessAssign;
+use tetra_pdus::umac::pdus::access_assign_fr18::AccessAssignFr18;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_data::MacData;
+use tetra_pdus::umac::pdus::mac_end_dl::MacEndDl;
+use tetra_pdus::umac::pdus::mac_end_hu::MacEndHu;
+use tetra_pdus::umac::pdus::mac_end_ul::MacEndUl;
+use tetra_pdus::umac::pdus::mac_frag_dl::MacFragDl;
+use tetra_pdus::umac::pdus::mac_frag_ul::MacFragUl;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_pdus::umac::pdus::mac_sync::MacSync;
+use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
+use tetra_pdus::umac::pdus::mac_u_signal::MacUSignal;
+use tetra_saps::tmv::TmvConfigureReq;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::lmac::components::scrambler;
+use crate::umac::subcomp::bs_defrag::BsDefrag;
+use crate::umac::subcomp::fillbits;
+use crate::umac::subcomp::ms_defrag::MsDefrag;
+use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
+
+/// Upper MAC for monitor mode.
+/// Parses MAC PDUs from both directions, reassembles fragmented TM-SDUs and decodes them
+/// through the higher layers with tetra_pdus::decode. Nothing is passed up the stack, and
+/// no state is kept beyond what is needed to follow the cell (time, scrambling, fragments).
+pub struct UmacMon {
+    config: SharedConfig,
+
+    /// Downlink fragments, one per timeslot
+    dl_defrag: MsDefrag,
+    /// Uplink fragments, per timeslot and SSI
+    ul_defrag: BsDefrag,
+    /// SSI of the last uplink fragmentation start per timeslot. Without access to the uplink
+    /// schedule, we assume subsequent MAC-FRAG/MAC-END PDUs in that timeslot belong to it.
+    ul_frag_owner: [Option<u32>; 4],
+
+    /// Derived from MAC-SYNC and D-MLE-SYNC, and passed to lmac
+    scrambling_code: Option<u32>,
+}
+
+impl UmacMon {
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            dl_defrag: MsDefrag::new(),
+            ul_defrag: BsDefrag::new(),
+            ul_frag_owner: [None; 4],
+            scrambling_code: None,
+        }
+    }
+
+    fn send_configure_req(queue: &mut MessageQueue, dltime: TdmaTime, prim: TmvConfigureReq, prio: bool) {
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::Lmac,
+            dltime,
+            msg: SapMsgInner::TmvConfigureReq(prim),
+        };
+        if prio {
+            queue.push_prio(m, MessagePrio::Immediate);
+        } else {
+            queue.push_back(m);
+        }
+    }
+
+    /// Decodes a complete TM-SDU through the higher layers and logs the result
+    fn deliver_sdu(sdu: &BitBuffer, addr: TetraAddress, dltime: TdmaTime, direction: Direction) {
+        for pdu in decode_tm_sdu(sdu, direction) {
+            match pdu.contents {
+                Ok(contents) => {
+                    tracing::info!(ts=%dltime, "{:?} {} {:?} {}", direction, addr, pdu.layer, contents);
+                }
+                Err(e) => {
+                    tracing::warn!(ts=%dltime, "{:?} {} failed parsing {:?} {}: {}", direction, addr, pdu.layer, pdu.name, e);
+                }
+            }
+        }
+    }
+
+    /// Restricts the MAC block to the current PDU minus its fill bits.
+    /// Returns the stripped pdu length, the number of fill bits and the original end, for use with skip_pdu.
+    fn trim_pdu(pdu: &mut BitBuffer, mut pdu_len_bits: usize, fill_bits: bool, is_null_pdu: bool) -> (usize, usize, usize) {
+        if pdu_len_bits > pdu.get_len() {
+            tracing::debug!("truncating MAC PDU len from {} to {}", pdu_len_bits, pdu.get_len());
+            pdu_len_bits = pdu.get_len();
+        }
+        let num_fill_bits = if fill_bits {
+            fillbits::removal::get_num_fill_bits(pdu, pdu_len_bits, is_null_pdu)
+        } else {
+            0
+        };
+        pdu_len_bits -= num_fill_bits;
+        let orig_end = pdu.get_raw_end();
+        pdu.set_raw_end(pdu.get_raw_start() + pdu_len_bits);
+        (pdu_len_bits, num_fill_bits, orig_end)
+    }
+
+    /// Moves the MAC block past the current PDU, so the parent function can continue parsing
+    fn skip_pdu(pdu: &mut BitBuffer, pdu_len_bits: usize, num_fill_bits: usize, orig_end: usize) {
+        pdu.set_raw_end(orig_end);
+        pdu.set_raw_pos(pdu.get_raw_start() + pdu_len_bits + num_fill_bits);
+        pdu.set_raw_start(pdu.get_raw_pos());
+    }
+
+    fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::trace!("rx_tmv_prim");
+        match message.msg {
+            SapMsgInner::TmvUnitdataInd(_) => {
+                self.rx_tmv_unitdata_ind(queue, message);
+            }
+            _ => {
+                tracing::debug!("rx_tmv_prim: dropping {:?}", message);
+            }
+        }
+    }
+
+    pub fn rx_tmv_unitdata_ind(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let SapMsgInner::TmvUnitdataInd(prim) = &message.msg else {
+            panic!()
+        };
+        tracing::trace!("rx_tmv_unitdata_ind: {:?} {:?}", prim.direction, prim.logical_channel);
+
+        self.dl_defrag.age_buffers(message.dltime);
+        self.ul_defrag.age_buffers(message.dltime);
+
+        match prim.logical_channel {
+            LogicalChannel::Aach => self.rx_tmv_aach(queue, message),
+            LogicalChannel::Bsch => self.rx_tmv_bsch(queue, message),
+            LogicalChannel::Bnch | LogicalChannel::SchF | LogicalChannel::SchHd | LogicalChannel::SchHu | LogicalChannel::Stch => {
+                self.rx_tmv_sch(queue, message)
+            }
+            _ => {
+                tracing::debug!("rx_tmv_unitdata_ind: ignoring {:?}", prim.logical_channel);
+            }
+        }
+    }
+
+    fn rx_tmv_aach(&self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_tmv_aach");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let is_traffic = if message.dltime.f != 18 {
+            match AccessAssign::from_bitbuf(&mut prim.pdu) {
+                Ok(pdu) => {
+                    tracing::debug!("<- {:?}", pdu);
+                    pdu.dl_usage.is_traffic()
+                }
+                Err(e) => {
+                    tracing::warn!("Failed parsing AccessAssign: {:?} {}", e, prim.pdu.dump_bin());
+                    return;
+                }
+            }
+        } else {
+            match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
+                Ok(pdu) => {
+                    tracing::debug!("<- {:?}", pdu);
+                    false
+                }
+                Err(e) => {
+                    tracing::warn!("Failed parsing AccessAssignFr18: {:?} {}", e, prim.pdu.dump_bin());
+                    return;
+                }
+            }
+        };
+
+        // This message needs to be processed NOW since it affects the other blocks in this timeslot
+        let req = TmvConfigureReq {
+            is_traffic: Some(is_traffic),
+            ..Default::default()
+        };
+        Self::send_configure_req(queue, message.dltime, req, true);
+    }
+
+    fn rx_tmv_bsch(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_tmv_bsch");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let mac_sync = match MacSync::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacSync: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+        let mle_sync = match DMleSync::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing DMleSync: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        // Adopt network time. MAC-SYNC does not contain the hyperframe, which is retrieved from SYSINFO.
+        let t = TdmaTime {
+            h: message.dltime.h,
+            ..mac_sync.time
+        };
+        let time = if t != message.dltime {
+            tracing::info!("rx_tmv_bsch: synchronized to network time {} (was {})", t, message.dltime);
+            Some(t)
+        } else {
+            None
+        };
+
+        let scrambling_code = scrambler::tetra_scramb_get_init(mle_sync.mcc, mle_sync.mnc, mac_sync.colour_code);
+        let scrambling_code = if self.scrambling_code != Some(scrambling_code) {
+            tracing::info!(
+                "rx_tmv_bsch: cell mcc {} mnc {} cc {}, scrambling code {}",
+                mle_sync.mcc,
+                mle_sync.mnc,
+                mac_sync.colour_code,
+                scrambling_code
+            );
+            self.scrambling_code = Some(scrambling_code);
+            Some(scrambling_code)
+        } else {
+            None
+        };
+
+        if time.is_some() || scrambling_code.is_some() {
+            // Apply before any further blocks are processed
+            let req = TmvConfigureReq {
+                time,
+                scrambling_code,
+                ..Default::default()
+            };
+            Self::send_configure_req(queue, message.dltime, req, true);
+        }
+    }
+
+    /// Receive signalling (SCH, or STCH / BNCH), from either direction
+    fn rx_tmv_sch(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_tmv_sch");
+
+        // Iterate until no more messages left in mac block
+        loop {
+            let SapMsgInner::TmvUnitdataInd(prim) = &message.msg else {
+                panic!()
+            };
+            let Some(bits) = prim.pdu.peek_bits(3) else {
+                tracing::warn!("insufficient bits: {}", prim.pdu.dump_bin());
+                return;
+            };
+            let orig_start = prim.pdu.get_raw_start();
+            let lchan = prim.logical_channel;
+            let direction = prim.direction;
+
+            if lchan == LogicalChannel::SchHu {
+                // Need only 1 bit for a single subtype distinction
+                if (bits >> 2) & 1 == 0 {
+                    self.rx_mac_access(&mut message);
+                } else {
+                    self.rx_mac_end_hu(&mut message);
+                }
+            } else {
+                let Ok(pdu_type) = MacPduType::try_from(bits >> 1) else {
+                    tracing::warn!("invalid pdu type: {}", bits >> 1);
+                    return;
+                };
+
+                match (direction, pdu_type) {
+                    (Direction::Dl, MacPduType::MacResourceMacData) => self.rx_mac_resource(&mut message),
+                    (Direction::Dl, MacPduType::MacFragMacEnd) => {
+                        // Also need third bit; designates mac-frag versus mac-end
+                        if bits & 1 == 0 {
+                            self.rx_mac_frag_dl(&mut message);
+                        } else {
+                            self.rx_mac_end_dl(&mut message);
+                        }
+                    }
+                    (Direction::Dl, MacPduType::Broadcast) => self.rx_broadcast(queue, &mut message),
+                    (Direction::Ul, MacPduType::MacResourceMacData) => self.rx_mac_data(&mut message),
+                    (Direction::Ul, MacPduType::MacFragMacEnd) => {
+                        if bits & 1 == 0 {
+                            self.rx_mac_frag_ul(&mut message);
+                        } else {
+                            self.rx_mac_end_ul(&mut message);
+                        }
+                    }
+                    (_, MacPduType::SuppMacUSignal) if lchan == LogicalChannel::Stch => self.rx_mac_u_signal(&mut message),
+                    _ => {
+                        unimplemented_log!("rx_tmv_sch: {:?} {} on {:?} not supported", direction, pdu_type, lchan);
+                        return;
+                    }
+                }
+            }
+
+            // Check if end of message reached by re-borrowing inner
+            // If start was not updated, we also consider it end of message
+            // If 16 or more bits remain (len of null pdu), we continue parsing
+            if let SapMsgInner::TmvUnitdataInd(prim) = &message.msg {
+                if prim.pdu.get_raw_start() != orig_start && prim.pdu.get_len() >= 16 {
+                    tracing::trace!(
+                        "rx_tmv_sch: Remaining {} bits: {:?}",
+                        prim.pdu.get_len_remaining(),
+                        prim.pdu.dump_bin_full(true)
+                    );
+                } else {
+                    tracing::trace!("rx_tmv_sch: End of message reached");
+                    break;
+                }
+            }
+        }
+    }
+
+    fn rx_broadcast(&self, queue: &mut MessageQueue, message: &mut SapMsg) {
+        tracing::trace!("rx_broadcast");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let Some(bits) = prim.pdu.peek_bits_posoffset(2, 2) else {
+            return;
+        };
+        match BroadcastType::try_from(bits) {
+            Ok(BroadcastType::Sysinfo) => {}
+            Ok(bcast_type) => {
+                unimplemented_log!("rx_broadcast: {:?} not supported", bcast_type);
+                return;
+            }
+            Err(_) => {
+                tracing::warn!("rx_broadcast: invalid broadcast type {}", bits);
+                return;
+            }
+        }
+
+        let pdu = match MacSysinfo::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacSysinfo: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        if let Some(h) = pdu.hyperframe_number
+            && h != message.dltime.h
+        {
+            let t = TdmaTime { h, ..message.dltime };
+            tracing::info!("rx_broadcast: hyperframe {} -> {}", message.dltime.h, h);
+            let req = TmvConfigureReq {
+                time: Some(t),
+                ..Default::default()
+            };
+            Self::send_configure_req(queue, message.dltime, req, false);
+        }
+
+        // The remainder is a D-MLE-SYSINFO TL-SDU
+        for pdu in tetra_pdus::decode::decode_tl_sdu(&mut prim.pdu, Direction::Dl) {
+            tracing::info!(ts=%message.dltime, "Dl broadcast {:?} {}", pdu.layer, pdu.contents.unwrap_or_else(|e| e));
+        }
+    }
+
+    fn rx_mac_resource(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_resource");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacResource::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacResource: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        let pdu_len_bits = match pdu.length_ind {
+            0b000001..0b111010 => pdu.length_ind as usize * 8,
+            // Second half slot stolen, or start of fragmentation
+            0b111110 | 0b111111 => prim.pdu.get_len(),
+            _ => {
+                tracing::warn!("rx_mac_resource: invalid length_ind {}", pdu.length_ind);
+                return;
+            }
+        };
+        let (pdu_len_bits, num_fill_bits, orig_end) = Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, pdu.is_null_pdu());
+
+        if let Some(addr) = pdu.addr {
+            if pdu.encryption_mode > 0 {
+                unimplemented_log!("rx_mac_resource: encrypted PDU for ssi {} not decoded", addr.ssi);
+            } else if pdu.length_ind == 0b111111 {
+                self.dl_defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+            } else if prim.pdu.get_len_remaining() > 0 {
+                Self::deliver_sdu(&prim.pdu, addr, message.dltime, Direction::Dl);
+            }
+        }
+
+        Self::skip_pdu(&mut prim.pdu, pdu_len_bits, num_fill_bits, orig_end);
+    }
+
+    fn rx_mac_frag_dl(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_frag_dl");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacFragDl::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacFragDl: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        // This message is known to fill the slot
+        let pdu_len_bits = prim.pdu.get_len();
+        Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, false);
+        self.dl_defrag.insert_next(&mut prim.pdu, message.dltime);
+    }
+
+    fn rx_mac_end_dl(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_end_dl");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacEndDl::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacEndDl: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+        if pdu.length_ind == 0 {
+            tracing::warn!("rx_mac_end_dl: reserved length_ind 0");
+            return;
+        }
+
+        let pdu_len_bits = pdu.length_ind as usize * 8;
+        let (pdu_len_bits, num_fill_bits, orig_end) = Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, false);
+
+        self.dl_defrag.insert_last(&mut prim.pdu, message.dltime);
+        if let Some(defragbuf) = self.dl_defrag.take_defragged_buf(message.dltime) {
+            Self::deliver_sdu(&defragbuf.buffer, defragbuf.addr, message.dltime, Direction::Dl);
+        } else {
+            tracing::debug!("rx_mac_end_dl: no defragged buf, missed first fragment?");
+        }
+
+        Self::skip_pdu(&mut prim.pdu, pdu_len_bits, num_fill_bits, orig_end);
+    }
+
+    fn rx_mac_data(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_data");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacData::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacData: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        let Some(addr) = pdu.addr else {
+            unimplemented_log!("rx_mac_data: event labels not supported");
+            return;
+        };
+
+        let (pdu_len_bits, is_frag_start, is_null_pdu) = match pdu.length_ind {
+            Some(0b000000) => (37, false, true),
+            Some(len_ind @ 0b000010..0b111000) => (len_ind as usize * 8, false, false),
+            Some(0b111110) => (prim.pdu.get_len(), false, false),
+            Some(0b111111) => (prim.pdu.get_len(), true, false),
+            Some(len_ind) => {
+                tracing::warn!("rx_mac_data: invalid length_ind {}", len_ind);
+                return;
+            }
+            // Capacity request
+            None => (prim.pdu.get_len(), pdu.frag_flag.unwrap_or(false), false),
+        };
+        let (pdu_len_bits, num_fill_bits, orig_end) = Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, is_null_pdu);
+
+        if is_null_pdu {
+            return;
+        }
+
+        if pdu.encrypted {
+            unimplemented_log!("rx_mac_data: encrypted PDU for ssi {} not decoded", addr.ssi);
+        } else if is_frag_start {
+            self.ul_frag_owner[(message.dltime.t - 1) as usize] = Some(addr.ssi);
+            self.ul_defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+        } else if prim.pdu.get_len_remaining() > 0 {
+            Self::deliver_sdu(&prim.pdu, addr, message.dltime, Direction::Ul);
+        }
+
+        Self::skip_pdu(&mut prim.pdu, pdu_len_bits, num_fill_bits, orig_end);
+    }
+
+    fn rx_mac_access(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_access");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacAccess::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacAccess: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        let Some(addr) = pdu.addr else {
+            unimplemented_log!("rx_mac_access: event labels not supported");
+            return;
+        };
+
+        let pdu_len_bits = match pdu.length_ind {
+            Some(0) => 36,
+            Some(length_ind) => length_ind as usize * 8,
+            // Capacity request, fills slot
+            None => prim.pdu.get_len(),
+        };
+        let (pdu_len_bits, num_fill_bits, orig_end) = Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, pdu.is_null_pdu());
+
+        if pdu.is_null_pdu() {
+            return;
+        }
+
+        if pdu.encrypted {
+            unimplemented_log!("rx_mac_access: encrypted PDU for ssi {} not decoded", addr.ssi);
+        } else if pdu.is_frag_start() {
+            self.ul_frag_owner[(message.dltime.t - 1) as usize] = Some(addr.ssi);
+            self.ul_defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
+        } else if prim.pdu.get_len_remaining() > 0 {
+            Self::deliver_sdu(&prim.pdu, addr, message.dltime, Direction::Ul);
+        }
+
+        Self::skip_pdu(&mut prim.pdu, pdu_len_bits, num_fill_bits, orig_end);
+    }
+
+    fn rx_mac_frag_ul(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_frag_ul");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacFragUl::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacFragUl: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        // This message is known to fill the slot
+        let pdu_len_bits = prim.pdu.get_len();
+        Self::trim_pdu(&mut prim.pdu, pdu_len_bits, pdu.fill_bits, false);
+
+        let Some(ssi) = self.ul_frag_owner[(message.dltime.t - 1) as usize] else {
+            tracing::debug!("rx_mac_frag_ul: no fragmentation in progress, missed first fragment?");
+            return;
+        };
+        self.ul_defrag.insert_next(&mut prim.pdu, ssi, message.dltime);
+    }
+
+    fn rx_mac_end_ul(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_end_ul");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacEndUl::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacEndUl: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        let pdu_len_bits = pdu.length_ind.map_or(prim.pdu.get_len(), |l| l as usize * 8);
+        self.rx_ul_last_frag(message, pdu_len_bits, pdu.fill_bits);
+    }
+
+    fn rx_mac_end_hu(&mut self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_end_hu");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacEndHu::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacEndHu: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+        if pdu.length_ind == Some(0) {
+            // Table 21.44: length indication 0 is reserved, discard PDU
+            return;
+        }
+
+        let pdu_len_bits = pdu.length_ind.map_or(prim.pdu.get_len(), |l| l as usize * 8);
+        self.rx_ul_last_frag(message, pdu_len_bits, pdu.fill_bits);
+    }
+
+    /// Completes uplink reassembly for MAC-END and MAC-END-HU, whose headers have already been parsed
+    fn rx_ul_last_frag(&mut self, message: &mut SapMsg, pdu_len_bits: usize, fill_bits: bool) {
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+        let (pdu_len_bits, num_fill_bits, orig_end) = Self::trim_pdu(&mut prim.pdu, pdu_len_bits, fill_bits, false);
+
+        let ts = (message.dltime.t - 1) as usize;
+        if let Some(ssi) = self.ul_frag_owner[ts].take() {
+            if let Some(defragbuf) = self.ul_defrag.insert_last(&mut prim.pdu, ssi, message.dltime) {
+                Self::deliver_sdu(&defragbuf.buffer, defragbuf.addr, message.dltime, Direction::Ul);
+            }
+        } else {
+            tracing::debug!("rx_ul_last_frag: no fragmentation in progress, missed first fragment?");
+        }
+
+        Self::skip_pdu(&mut prim.pdu, pdu_len_bits, num_fill_bits, orig_end);
+    }
+
+    /// MAC-U-SIGNAL on STCH. Carries a TM-SDU filling the rest of the block, in either direction.
+    fn rx_mac_u_signal(&self, message: &mut SapMsg) {
+        tracing::trace!("rx_mac_u_signal");
+        let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
+            panic!()
+        };
+
+        let pdu = match MacUSignal::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing MacUSignal: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+        if pdu.second_half_stolen {
+            tracing::debug!("rx_mac_u_signal: second half stolen");
+        }
+
+        // No address is present, the STCH belongs to the current traffic channel user
+        let addr = TetraAddress::new(0, SsiType::Unknown);
+        Self::deliver_sdu(&prim.pdu, addr, message.dltime, prim.direction);
+
+        // Fills the remainder of the block
+        let end = prim.pdu.get_raw_end();
+        prim.pdu.set_raw_start(end);
+    }
+}
+
+impl TetraEntityTrait for UmacMon {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::Umac
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message);
+
+        match message.sap {
+            Sap::TmvSap => {
+                self.rx_tmv_prim(queue, message);
+            }
+            _ => {
+                // No higher layer entities are present in monitor mode
+                tracing::debug!("rx_prim: dropping {:?}", message);
+            }
+        }
+    }
+}
@@ -1,16 +1,21 @@
-use std::panic;
+use std::collections::VecDeque;
 
 use tetra_config::bluestation::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, Todo, unimplemented_log};
-use tetra_saps::tlmb::TlmbSysinfoInd;
+use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_saps::tlmb::{TlmbSyncInd, TlmbSysinfoInd};
 use tetra_saps::tma::TmaUnitdataInd;
-use tetra_saps::tmv::TmvConfigureReq;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+use tetra_saps::tmv::{TmvConfigureReq, TmvUnitdataReq, TmvUnitdataReqSlot};
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
+use tetra_pdus::umac::enums::access_assign_ul_usage::AccessAssignUlUsage;
+use tetra_pdus::umac::enums::basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc;
+use tetra_pdus::umac::enums::basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay;
 use tetra_pdus::umac::enums::broadcast_type::BroadcastType;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
+use tetra_pdus::umac::fields::basic_slotgrant::BasicSlotgrant;
 use tetra_pdus::umac::pdus::access_assign::AccessAssign;
 use tetra_pdus::umac::pdus::access_assign_fr18::AccessAssignFr18;
 use tetra_pdus::umac::pdus::mac_end_dl::MacEndDl;
@@ -19,10 +24,33 @@ use tetra_pdus::umac::pdus::mac_resource::MacResource;
 use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 
+use crate::lmac::components::scrambler;
+use crate::umac::subcomp::bs_sched::SCH_F_CAP;
 use crate::umac::subcomp::fillbits;
 use crate::umac::subcomp::ms_defrag::MsDefrag;
+use crate::umac::subcomp::ms_frag::{MsFragger, SCH_HU_CAP};
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 
+/// Number of uplink slots to wait for a random access response before retrying
+const RANDOM_ACCESS_RESPONSE_SLOTS: i32 = 4 * 8;
+
+/// Maximum number of random access attempts for a single TM-SDU
+const RANDOM_ACCESS_MAX_ATTEMPTS: usize = 5;
+
+/// Group address reaching all MSs in the cell
+const SSI_ALL: u32 = 0xFFFFFF;
+
+/// Uplink state of the TM-SDU at the head of the uplink queue
+#[derive(Debug, Clone, PartialEq)]
+enum UlState {
+    /// Waiting for a random access opportunity at or after the given uplink time
+    Idle { not_before: Option<TdmaTime> },
+    /// MAC-ACCESS sent in the given uplink slot, awaiting acknowledgement (and a grant if fragmented)
+    AwaitingResponse { sent: TdmaTime },
+    /// Random access acknowledged. Remaining fragments go in these uplink slots.
+    Granted { slots: VecDeque<TdmaTime> },
+}
+
 pub struct UmacMs {
     // config: Option<SharedConfig>,
     self_component: TetraEntity,
@@ -37,6 +65,16 @@ pub struct UmacMs {
     cc: Option<u8>,
     /// Derived from mcc/mnc, and passed to lmac
     scrambling_code: Option<u32>,
+
+    /// Uplink slot announced by the ACCESS-ASSIGN of the current downlink slot, with its usage.
+    /// What to transmit in it is decided at the end of the tick, once the rest of the downlink slot was processed.
+    cur_ul: Option<(TdmaTime, AccessAssignUlUsage)>,
+    /// TM-SDUs waiting to be sent on the uplink. The head is the one currently in progress.
+    ul_queue: VecDeque<MsFragger>,
+    /// Progress of the TM-SDU at the head of ul_queue
+    ul_state: UlState,
+    /// Random access attempts made for the TM-SDU at the head of ul_queue
+    ra_attempts: usize,
 }
 
 impl UmacMs {
@@ -50,9 +88,26 @@ impl UmacMs {
             mnc: None,
             cc: None,
             scrambling_code: None,
+
+            cur_ul: None,
+            ul_queue: VecDeque::new(),
+            ul_state: UlState::Idle { not_before: None },
+            ra_attempts: 0,
         }
     }
 
+    /// Whether a downlink PDU with this address is meant for us: our ISSI, one of our groups, or all MSs
+    fn is_own_address(&self, addr: &TetraAddress) -> bool {
+        if addr.ssi == SSI_ALL {
+            return true;
+        }
+        let config = self.config.config();
+        let Some(ms) = &config.ms else {
+            return false;
+        };
+        addr.ssi == ms.issi || ms.groups.contains(&addr.ssi)
+    }
+
     fn rx_tmv_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tmv_prim");
         match message.msg {
@@ -60,7 +115,7 @@ impl UmacMs {
                 self.rx_tmv_unitdata_ind(queue, message);
             }
             _ => {
-                panic!();
+                tracing::warn!("rx_tmv_prim: unexpected {:?}", message);
             }
         }
     }
@@ -101,7 +156,9 @@ impl UmacMs {
                 );
                 self.rx_tmv_sch(queue, message);
             }
-            _ => unreachable!("invalid channel: {:?}", prim.logical_channel),
+            _ => {
+                tracing::warn!("rx_tmv_unitdata_ind: unexpected channel {:?}", prim.logical_channel);
+            }
         }
     }
 
@@ -179,15 +236,21 @@ impl UmacMs {
         };
         assert!(prim.pdu.peek_bits(2).unwrap() == MacPduType::Broadcast.into_raw()); // MAC PDU type
 
-        let bits = prim.pdu.peek_bits_posoffset(2, 2).unwrap();
-        let bcast_type = BroadcastType::try_from(bits).expect("invalid broadcast type");
+        let Some(bits) = prim.pdu.peek_bits_posoffset(2, 2) else {
+            tracing::warn!("insufficient bits: {}", prim.pdu.dump_bin());
+            return;
+        };
+        let Ok(bcast_type) = BroadcastType::try_from(bits) else {
+            tracing::warn!("invalid broadcast type: {}", bits);
+            return;
+        };
 
         match bcast_type {
             BroadcastType::Sysinfo => {
                 self.rx_broadcast_sysinfo(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_broadcast: {:?} not supported", bcast_type);
             }
         }
     }
@@ -291,7 +354,10 @@ impl UmacMs {
                     // tracing::trace!("rx_mac_resource: frag start length_ind {}", pdu.length_ind);
                     prim.pdu.get_len()
                 }
-                _ => panic!("rx_mac_resource: Invalid length_ind {}", pdu.length_ind),
+                _ => {
+                    tracing::warn!("rx_mac_resource: Invalid length_ind {}", pdu.length_ind);
+                    return;
+                }
             }
         };
 
@@ -328,10 +394,16 @@ impl UmacMs {
             prim.pdu.dump_bin_full(true)
         );
 
-        if pdu.addr.is_none() {
+        let Some(addr) = pdu.addr else {
             // TODO not sure if there is scenarios in which we want to pass a null pdu to the LLC
             // tracing::warn!("rx_mac_resource: Null PDU not passed to LLC");
             return;
+        };
+        let is_own_address = self.is_own_address(&addr);
+
+        // Random access acknowledgement and slot grant for our uplink transmission
+        if is_own_address && addr.ssi != SSI_ALL {
+            self.rx_ul_response(message.dltime, pdu.random_access_flag, pdu.slot_granting_element.as_ref());
         }
 
         // Decrypt if needed
@@ -348,10 +420,12 @@ impl UmacMs {
 
         tracing::debug!("rx_mac_resource: {}", prim.pdu.dump_bin_full(true));
         if pdu.length_ind == 0b111111 {
-            // Fragmentation start, add to defragmenter
-            self.defrag.insert_first(&mut prim.pdu, message.dltime, pdu.addr.unwrap(), None);
+            // Fragmentation start, add to defragmenter. Fragments for other addresses are dropped when complete.
+            self.defrag.insert_first(&mut prim.pdu, message.dltime, addr, None);
         } else if pdu.length_ind == 0b111110 {
             tracing::warn!("rx_mac_resource: SECOND HALF SLOT STOLEN IN STCH but not implemented");
+        } else if !is_own_address {
+            tracing::trace!("rx_mac_resource: not addressed to us: {}", addr);
         } else {
             // Pass directly to LLC
             let sdu = {
@@ -377,7 +451,7 @@ impl UmacMs {
 
                     msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
                         pdu: sdu,
-                        main_address: pdu.addr.unwrap(),
+                        main_address: addr,
                         scrambling_code: prim.scrambling_code,
                         endpoint_id: 0,        // TODO FIXME
                         new_endpoint_id: None, // TODO FIXME
@@ -436,7 +510,7 @@ impl UmacMs {
         tracing::debug!("rx_mac_frag: pdu_len_bits: {} fill_bits: {}", pdu_len_bits, num_fill_bits);
 
         // Decrypt if needed
-        if let Some(_aie_info) = self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info {
+        if self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info.is_some() {
             // TODO FIXME implement
             unimplemented_log!("rx_mac_frag: Encryption not supported");
             return;
@@ -466,7 +540,10 @@ impl UmacMs {
         };
 
         // Compute len
-        assert!(pdu.length_ind != 0); // Reserved
+        if pdu.length_ind == 0 {
+            tracing::warn!("rx_mac_end: reserved length_ind 0");
+            return;
+        }
         let mut pdu_len_bits = pdu.length_ind as usize * 8;
 
         // Strip fill bits. Maintain original end to allow for later parsing of a second mac block
@@ -483,10 +560,11 @@ impl UmacMs {
         tracing::debug!("rx_mac_end: pdu_len_bits: {} fill_bits: {}", pdu_len_bits, num_fill_bits);
 
         // Decrypt if needed
-        if let Some(_aie_info) = self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info {
+        if self.defrag.buffers[(message.dltime.t - 1) as usize].aie_info.is_some() {
             // TODO FIXME implement
-            unimplemented!("rx_mac_end: Encryption not supported");
             // TODO FIXME Also re-parse chanalloc
+            unimplemented_log!("rx_mac_end: Encryption not supported");
+            return;
         }
 
         // Insert into defragmenter
@@ -498,6 +576,13 @@ impl UmacMs {
             tracing::warn!("rx_mac_end: could not obtain defragged buf");
             return;
         };
+        if !self.is_own_address(&defragbuf.addr) {
+            tracing::trace!("rx_mac_end: not addressed to us: {}", defragbuf.addr);
+            prim.pdu.set_raw_end(orig_end);
+            prim.pdu.set_raw_pos(prim.pdu.get_raw_start() + pdu_len_bits + num_fill_bits);
+            prim.pdu.set_raw_start(prim.pdu.get_raw_pos());
+            return;
+        }
 
         // Pass block directly to LLC
         tracing::debug!("rx_mac_end: sdu: {:?}", defragbuf.buffer.dump_bin());
@@ -535,7 +620,7 @@ impl UmacMs {
         let SapMsgInner::TmvUnitdataInd(_prim) = &mut message.msg else {
             panic!()
         };
-        unimplemented!("rx_usignal");
+        unimplemented_log!("rx_usignal");
     }
 
     fn rx_supp(&self, _queue: &mut MessageQueue, message: &mut SapMsg) {
@@ -545,11 +630,14 @@ impl UmacMs {
             panic!()
         };
         // Check we're indeed on the right channel (Clause 21.4.1 Table 21.48)
-        assert!(prim.logical_channel != LogicalChannel::Stch && prim.logical_channel != LogicalChannel::SchHd);
-        unimplemented!("rx_supp");
+        if prim.logical_channel == LogicalChannel::Stch || prim.logical_channel == LogicalChannel::SchHd {
+            tracing::warn!("rx_supp: not allowed on {:?}", prim.logical_channel);
+            return;
+        }
+        unimplemented_log!("rx_supp");
     }
 
-    pub fn rx_tmv_aach(&self, queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tmv_aach(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tmv_aach");
 
         // TODO FIXME, more extensively store and process AACH state in both LMAC and UMAC
@@ -571,9 +659,10 @@ impl UmacMs {
                 }
             };
 
+            self.cur_ul = Some((message.dltime, pdu.ul_usage));
             pdu.dl_usage.is_traffic()
         } else {
-            let _pdu = match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
+            let pdu = match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
                 Ok(pdu) => {
                     tracing::debug!("<- {:?}", pdu);
                     pdu
@@ -584,6 +673,7 @@ impl UmacMs {
                 }
             };
 
+            self.cur_ul = Some((message.dltime, pdu.ul_usage));
             false
         };
 
@@ -601,14 +691,14 @@ impl UmacMs {
         queue.push_prio(m, MessagePrio::Immediate);
     }
 
-    pub fn rx_tmv_bsch(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tmv_bsch(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tmv_bsch");
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {
             panic!()
         };
 
         // Unpack and validate with expected state
-        let _pdu = match MacSync::from_bitbuf(&mut prim.pdu) {
+        let mac_sync = match MacSync::from_bitbuf(&mut prim.pdu) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -619,97 +709,263 @@ impl UmacMs {
             }
         };
 
-        unimplemented_log!("can't update global state");
-
-        // let netinfo_changed = {
-        //     let config_r = self.config.read();
-        //         mac_sync.system_code != config_r.la_info.system_code
-        //             || mac_sync.sharing_mode != config_r.la_info.sharing_mode
-        //             || mac_sync.ts_reserved_frames != config_r.la_info.ts_reserved_frames
-        //             || mac_sync.u_plane_dtx != config_r.la_info.u_plane_dtx
-        //             || mac_sync.frame_18_ext != config_r.la_info.frame_18_ext
-        // };
-        // // tracing::trace!("rx_tmv_bsch: netinfo_changed: {}, cc_changed: {}, tdma_time_changed: {}", netinfo_changed, cc_changed, tdma_time_changed);
-
-        // // Update global state if needed
-        // if netinfo_changed  {
-        //     let mut config_w = self.config.write();
-        //     config_w.la_info.system_code = mac_sync.system_code;
-        //     // config_w.netinfo.colour_code = mac_sync.colour_code;
-        //     config_w.la_info.sharing_mode = mac_sync.sharing_mode;
-        //     config_w.la_info.ts_reserved_frames = mac_sync.ts_reserved_frames;
-        //     config_w.la_info.u_plane_dtx = mac_sync.u_plane_dtx;
-        //     config_w.la_info.frame_18_ext = mac_sync.frame_18_ext;
-        //     tracing::info!("rx_tmv_bsch: Updated TetraGlobalState: {:?}", mac_sync);
-        // }
-
-        // if mac_sync.time.t != message.t_submit.t || mac_sync.time.f != message.t_submit.f || mac_sync.time.m != message.t_submit.m {
-        //     // TODO warn/bail when really not in line with expected time
-        //     let t = TdmaTime{
-        //         t: mac_sync.time.t,
-        //         f: mac_sync.time.f,
-        //         m: mac_sync.time.m,
-        //         h: message.t_submit.h,
-        //     };
-        //     let m = SapMsg {
-        //         sap: Sap::TmvSap,
-        //         src: self.self_component,
-        //         dest: TetraComponent::Lmac,
-        //         t_submit: message.t_submit,
-        //         msg: SapMsgInner::TmvConfigureReq(
-        //             TmvConfigureReq{
-        //                 time: Some(t),
-        //                 .. Default::default()
-        //             }
-        //         )
-        //     };
-        //     tracing::info!("rx_tmv_bsch: Updated TdmaTime: {:?} -> {:?}", message.t_submit, t);
-        //     queue.push_back(m);
-        // }
-
-        // if Some(mac_sync.colour_code) != self.cc {
-        //     // Update scrambling code
-        //     tracing::info!("rx_tmv_bsch: Updated colour code: {:?} -> {:?}", self.cc, mac_sync.colour_code);
-        //     self.cc = Some(mac_sync.colour_code);
-        //     self.update_scrambing_and_submit_to_lmac(queue, &message);
-
-        // } else {
-        //     tracing::trace!("rx_tmv_bsch: Colour code unchanged: {:?}", self.cc);
-        // }
-
-        // // Take ownership of prim and sdu
-        // let prim = if let SapMsgInner::TmvUnitdataInd(inner) = message.msg {
-        //     inner
-        // } else {
-        //     panic!();
-        // };
-        // let tlsdu = prim.pdu;
-
-        // let m = SapMsg {
-        //     sap: Sap::TlmbSap,
-        //     src: TetraComponent::Umac,
-        //     dest: TetraComponent::Mle,
-        //     t_submit: message.t_submit,
-
-        //     msg: SapMsgInner::TlmbSyncInd(
-        //         TlmbSyncInd {
-        //             endpoint_id: 0,
-        //             tl_sdu: tlsdu
-        //         }
-        //     )
-        // };
-        // tracing::info!("rx_tmv_bsch: {:?}", m.msg);
-        // queue.push_back(m);
+        // The remainder is the TL-SDU for the MLE, which also holds the cell's network code
+        let tl_sdu = BitBuffer::from_bitbuffer_pos(&prim.pdu);
+        let mle_sync = match DMleSync::from_bitbuf(&mut prim.pdu) {
+            Ok(pdu) => pdu,
+            Err(e) => {
+                tracing::warn!("Failed parsing DMleSync: {:?} {}", e, prim.pdu.dump_bin());
+                return;
+            }
+        };
+
+        // Adopt network time. MAC-SYNC does not contain the hyperframe, which is retrieved from SYSINFO.
+        let t = TdmaTime {
+            h: message.dltime.h,
+            ..mac_sync.time
+        };
+        let time = if t != message.dltime {
+            tracing::info!("rx_tmv_bsch: synchronized to network time {} (was {})", t, message.dltime);
+            // Anything derived from the old time is no longer valid
+            self.cur_ul = None;
+            if let UlState::Granted { .. } | UlState::AwaitingResponse { .. } = self.ul_state {
+                self.restart_random_access(None);
+            }
+            Some(t)
+        } else {
+            None
+        };
+
+        self.mcc = Some(mle_sync.mcc);
+        self.mnc = Some(mle_sync.mnc);
+        self.cc = Some(mac_sync.colour_code);
+        let scrambling_code = scrambler::tetra_scramb_get_init(mle_sync.mcc, mle_sync.mnc, mac_sync.colour_code);
+        let scrambling_code = if self.scrambling_code != Some(scrambling_code) {
+            tracing::info!(
+                "rx_tmv_bsch: cell mcc {} mnc {} cc {}, scrambling code {}",
+                mle_sync.mcc,
+                mle_sync.mnc,
+                mac_sync.colour_code,
+                scrambling_code
+            );
+            self.scrambling_code = Some(scrambling_code);
+            Some(scrambling_code)
+        } else {
+            None
+        };
+
+        if time.is_some() || scrambling_code.is_some() {
+            // Apply before any further blocks are processed
+            let m = SapMsg {
+                sap: Sap::TmvSap,
+                src: self.self_component,
+                dest: TetraEntity::Lmac,
+                dltime: message.dltime,
+                msg: SapMsgInner::TmvConfigureReq(TmvConfigureReq {
+                    time,
+                    scrambling_code,
+                    ..Default::default()
+                }),
+            };
+            queue.push_prio(m, MessagePrio::Immediate);
+        }
+
+        let m = SapMsg {
+            sap: Sap::TlmbSap,
+            src: self.self_component,
+            dest: TetraEntity::Mle,
+            dltime: t,
+            msg: SapMsgInner::TlmbSyncInd(TlmbSyncInd { endpoint_id: 0, tl_sdu }),
+        };
+        queue.push_back(m);
     }
 
-    fn rx_tma_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tma_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tma_prim");
-        unimplemented!();
+        match message.msg {
+            SapMsgInner::TmaUnitdataReq(prim) => {
+                // Queue for transmission. Sent using random access, see tick_end.
+                tracing::debug!("rx_tma_prim: queueing {} bits for {}", prim.pdu.get_len(), prim.main_address);
+                let mut sdu = prim.pdu;
+                sdu.seek(0);
+                self.ul_queue.push_back(MsFragger::new(prim.main_address, sdu, prim.tx_reporter));
+            }
+            _ => {
+                unimplemented_log!("rx_tma_prim: {:?} not supported", message.msg);
+            }
+        }
     }
 
-    fn rx_tlmb_prim(&mut self, _queue: &mut MessageQueue, _message: SapMsg) {
+    fn rx_tlmb_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tlmb_prim");
-        unimplemented!();
+        unimplemented_log!("rx_tlmb_prim: {:?} not supported", message.msg);
+    }
+
+    /// Handles a random access acknowledgement and/or slot grant addressed to us, received in downlink slot dltime
+    fn rx_ul_response(&mut self, dltime: TdmaTime, random_access_flag: bool, grant: Option<&BasicSlotgrant>) {
+        let UlState::AwaitingResponse { sent } = self.ul_state else {
+            if random_access_flag || grant.is_some() {
+                tracing::debug!("rx_ul_response: not awaiting a response, ignoring");
+            }
+            return;
+        };
+        let Some(fragger) = self.ul_queue.front_mut() else {
+            return;
+        };
+
+        if random_access_flag {
+            tracing::debug!(ts=%dltime, "rx_ul_response: random access in {} acknowledged", sent);
+            if !fragger.needs_more_chunks() {
+                // Whole TM-SDU fitted in the MAC-ACCESS
+                fragger.mark_transmitted();
+                self.ul_queue.pop_front();
+                self.reset_ul_state();
+                return;
+            }
+        }
+
+        if let Some(grant) = grant {
+            match Self::granted_slots(dltime, grant) {
+                Some(slots) => {
+                    tracing::debug!(ts=%dltime, "rx_ul_response: granted {:?}", slots);
+                    self.ul_state = UlState::Granted { slots };
+                }
+                None => {
+                    unimplemented_log!("rx_ul_response: unsupported grant {}", grant);
+                }
+            }
+        }
+    }
+
+    /// Computes the uplink slots granted by a MAC-RESOURCE received in downlink slot dltime, Clause 23.5.2.2.
+    /// Opportunities are the uplink slots on the same timeslot, where the next opportunity has the same time
+    /// as the downlink slot carrying the grant. Slots reserved for the CLCH are no opportunity.
+    /// Returns None for half slot grants and delays that are not supported.
+    fn granted_slots(dltime: TdmaTime, grant: &BasicSlotgrant) -> Option<VecDeque<TdmaTime>> {
+        let num_slots = match grant.capacity_allocation {
+            BasicSlotgrantCapAlloc::FirstSubslotGranted | BasicSlotgrantCapAlloc::SecondSubslotGranted => return None,
+            cap_alloc => cap_alloc.to_req_slotcount(),
+        };
+        let skip = match grant.granting_delay {
+            BasicSlotgrantGrantingDelay::CapAllocAtNextOpportunity => 0,
+            BasicSlotgrantGrantingDelay::DelayNOpportunities(n) => n as usize,
+            BasicSlotgrantGrantingDelay::AllocStartsAtOpportunityInFr18 | BasicSlotgrantGrantingDelay::WaitForAnotherSlotgrantMessage => {
+                return None;
+            }
+        };
+
+        let opportunities = (0..)
+            .map(|i| dltime.add_timeslots(i * 4))
+            .filter(|t| !t.is_mandatory_clch())
+            .skip(skip)
+            .take(num_slots);
+        Some(opportunities.collect())
+    }
+
+    fn reset_ul_state(&mut self) {
+        self.ul_state = UlState::Idle { not_before: None };
+        self.ra_attempts = 0;
+    }
+
+    /// Starts the TM-SDU at the head of the uplink queue over, after a failed attempt.
+    /// Gives up after RANDOM_ACCESS_MAX_ATTEMPTS, in which case the TM-SDU is reported as discarded.
+    fn restart_random_access(&mut self, ul_time: Option<TdmaTime>) {
+        self.ra_attempts += 1;
+        if self.ra_attempts >= RANDOM_ACCESS_MAX_ATTEMPTS {
+            tracing::warn!("random access failed after {} attempts, discarding TM-SDU", self.ra_attempts);
+            self.ul_queue.pop_front();
+            self.reset_ul_state();
+            return;
+        }
+        if let Some(fragger) = self.ul_queue.front_mut() {
+            fragger.rewind();
+        }
+        // Back off a number of frames, increasing with every attempt
+        let not_before = ul_time.map(|t| t.add_timeslots(4 * self.ra_attempts as i32));
+        self.ul_state = UlState::Idle { not_before };
+    }
+
+    fn send_ul_block(&self, queue: &mut MessageQueue, ul_time: TdmaTime, mac_block: BitBuffer, logical_channel: LogicalChannel) {
+        let Some(scrambling_code) = self.scrambling_code else {
+            return;
+        };
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: self.self_component,
+            dest: TetraEntity::Lmac,
+            dltime: ul_time,
+            msg: SapMsgInner::TmvUnitdataReq(TmvUnitdataReqSlot {
+                carrier: 0,
+                ts: ul_time,
+                ul_phy_chan: PhysicalChannel::Cp,
+                blk1: Some(TmvUnitdataReq {
+                    mac_block,
+                    logical_channel,
+                    scrambling_code,
+                }),
+                blk2: None,
+                bbk: None,
+            }),
+        };
+        queue.push_back(m);
+    }
+
+    /// Decides what to transmit in the uplink slot announced by the last ACCESS-ASSIGN:
+    /// the next fragment if the slot was granted to us, or a MAC-ACCESS if random access is allowed.
+    fn tx_ul_slot(&mut self, queue: &mut MessageQueue, ul_time: TdmaTime, ul_usage: AccessAssignUlUsage) {
+        if self.ul_queue.is_empty() || self.scrambling_code.is_none() {
+            return;
+        }
+
+        match &mut self.ul_state {
+            UlState::Granted { slots } => {
+                // Granted slots we missed can't be recovered
+                while slots.front().is_some_and(|t| t.diff(ul_time) < 0) {
+                    slots.pop_front();
+                }
+                if slots.front() != Some(&ul_time) {
+                    if slots.is_empty() {
+                        tracing::warn!(ts=%ul_time, "tx_ul_slot: granted capacity used up before TM-SDU was sent");
+                        self.restart_random_access(Some(ul_time));
+                    }
+                    return;
+                }
+                slots.pop_front();
+
+                let fragger = self.ul_queue.front_mut().unwrap(); // Checked above
+                let mut mac_block = BitBuffer::new(SCH_F_CAP);
+                let done = fragger.get_next_chunk(&mut mac_block);
+                self.send_ul_block(queue, ul_time, mac_block, LogicalChannel::SchF);
+                if done {
+                    let fragger = self.ul_queue.front_mut().unwrap();
+                    fragger.mark_transmitted();
+                    self.ul_queue.pop_front();
+                    self.reset_ul_state();
+                }
+            }
+            UlState::AwaitingResponse { sent } => {
+                if ul_time.diff(*sent) > RANDOM_ACCESS_RESPONSE_SLOTS {
+                    tracing::info!(ts=%ul_time, "tx_ul_slot: no response to random access in {}", sent);
+                    self.restart_random_access(Some(ul_time));
+                }
+            }
+            UlState::Idle { not_before } => {
+                if not_before.is_some_and(|t| ul_time.diff(t) < 0) {
+                    return;
+                }
+                // Random access on the MCCH, Clause 23.5.1
+                let allowed = matches!(ul_usage, AccessAssignUlUsage::CommonOnly | AccessAssignUlUsage::CommonAndAssigned);
+                if ul_time.t != 1 || !allowed || ul_time.is_mandatory_clch() {
+                    return;
+                }
+
+                let fragger = self.ul_queue.front_mut().unwrap(); // Checked above
+                let mut mac_block = BitBuffer::new(SCH_HU_CAP);
+                fragger.get_next_chunk(&mut mac_block);
+                self.send_ul_block(queue, ul_time, mac_block, LogicalChannel::SchHu);
+                self.ul_state = UlState::AwaitingResponse { sent: ul_time };
+            }
+        }
     }
 
     fn update_scrambing_and_submit_to_lmac(&mut self, queue: &mut MessageQueue, message: &SapMsg) {
@@ -764,7 +1020,7 @@ impl UmacMs {
                 self.rx_tlmc_configure_req(queue, message);
             }
             _ => {
-                panic!();
+                unimplemented_log!("rx_tlmc_prim: {:?} not supported", message.msg);
             }
         }
     }
@@ -775,6 +1031,18 @@ impl TetraEntityTrait for UmacMs {
         TetraEntity::Umac
     }
 
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn tick_end(&mut self, queue: &mut MessageQueue, _ts: TdmaTime) -> bool {
+        let Some((ul_time, ul_usage)) = self.cur_ul.take() else {
+            return false;
+        };
+        self.tx_ul_slot(queue, ul_time, ul_usage);
+        false
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
@@ -797,7 +1065,7 @@ impl TetraEntityTrait for UmacMs {
             }
 
             _ => {
-                panic!()
+                tracing::warn!("rx_prim: unexpected message on {:?}", message.sap);
             }
         }
     }
@@ -0,0 +1,49 @@
+//! Helpers for inspecting the output of the CMCE in component tests
+
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, TimeslotOwner};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use super::ComponentTest;
+
+/// Occupy the given traffic timeslots of the main carrier with packet data
+pub fn occupy(test: &mut ComponentTest, timeslots: &[u8]) {
+    let mut state = test.config.state_write();
+    for &ts in timeslots {
+        state.timeslot_alloc.reserve(TimeslotOwner::Sndcp, ts).unwrap();
+    }
+}
+
+/// Collects the CMCE PDUs of the given type sent to MLE, with their address
+pub fn extract_pdus(msgs: &[SapMsg], pdu_type: CmcePduTypeDl) -> Vec<(u32, BitBuffer)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(pdu_type.into_raw()) => {
+                Some((prim.main_address.ssi, prim.sdu.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Disconnect causes of the D-RELEASE PDUs sent to MLE, with their address
+pub fn d_release_causes(msgs: &[SapMsg]) -> Vec<(u32, DisconnectCause)> {
+    extract_pdus(msgs, CmcePduTypeDl::DRelease)
+        .into_iter()
+        .map(|(ssi, mut sdu)| (ssi, DRelease::from_bitbuf(&mut sdu).unwrap().disconnect_cause))
+        .collect()
+}
+
+/// Timeslots opened towards UMAC
+pub fn extract_opens(msgs: &[SapMsg]) -> Vec<u8> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) if m.dest == TetraEntity::Umac => Some(circuit.ts),
+            _ => None,
+        })
+        .collect()
+}
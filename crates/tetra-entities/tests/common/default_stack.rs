@@ -1,4 +1,4 @@
-use tetra_config::bluestation::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackConfig, StackMode};
+use tetra_config::bluestation::{CfgCellInfo, CfgMsInfo, CfgNetInfo, CfgPhyIo, PhyBackend, StackConfig, StackMode};
 use tetra_core::{freqs::FreqInfo, ranges::SortedDisjointSsiRanges};
 
 /// Creates a default config for testing. It can still be modified as needed
@@ -13,10 +13,21 @@ pub fn default_test_config_bs() -> StackConfig {
     StackConfig {
         stack_mode: StackMode::Bs,
         debug_log: None,
+        capture_file: None,
         phy_io,
         net: net_info,
         cell: cell_info,
         brew: None,
+        ms: None,
+        security: None,
+        sndcp: None,
+        mgmt: None,
+        registry: None,
+        call_queue: None,
+        emergency: None,
+        access_policy: None,
+        group_assignment: None,
+        neighbour_cells: Vec::new(),
     }
 }
 
@@ -27,6 +38,8 @@ pub fn default_phy_io() -> CfgPhyIo {
         ul_rx_file: None,
         ul_input_file: None,
         dl_input_file: None,
+        sample_rate: None,
+        rx_silence_tail_secs: None,
         soapysdr: None,
     }
 }
@@ -45,6 +58,7 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         duplex_spacing_id: freq_info.duplex_spacing_id,
         custom_duplex_spacing: None,
         reverse_operation: freq_info.reverse_operation,
+        secondary_carriers: vec![],
         neighbor_cell_broadcast: 0,
         late_entry_supported: false,
         subscriber_class: 65535, // All subscriber classes allowed
@@ -59,6 +73,7 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
         sndcp_service: false,
         aie_service: false,
         advanced_link: false,
+        event_labels: false,
         system_code: 3, // 3 = ETSI EN 300 392-2 V3.1.1
         sharing_mode: 0,
         ts_reserved_frames: 0,
@@ -72,5 +87,15 @@ pub fn default_cell_info(freq_info: FreqInfo) -> CfgCellInfo {
 pub fn default_test_config_ms() -> StackConfig {
     let mut config = default_test_config_bs();
     config.stack_mode = StackMode::Ms;
+    config.ms = Some(CfgMsInfo {
+        issi: 2040814,
+        groups: vec![91, 92],
+    });
+    config
+}
+
+pub fn default_test_config_mon() -> StackConfig {
+    let mut config = default_test_config_bs();
+    config.stack_mode = StackMode::Mon;
     config
 }
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+pub mod cmce;
 pub mod component_test;
 pub mod default_stack;
 pub mod sink;
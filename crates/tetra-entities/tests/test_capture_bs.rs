@@ -0,0 +1,117 @@
+mod common;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, TdmaTime, debug};
+use tetra_entities::capture::gsmtap::GSMTAP_UDP_PORT;
+use tetra_entities::capture::pdu_capture::PduCapture;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::ComponentTest;
+
+/// Capture output, shared between the test and the router
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Packet {
+    gsmtap: Vec<u8>,
+    comment: String,
+}
+
+/// Extracts the GSMTAP header and payload, and the comment, of every packet in a pcapng capture
+fn parse_capture(data: &[u8]) -> Vec<Packet> {
+    let u32_at = |pos: usize| u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+    let mut packets = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let (block_type, block_len) = (u32_at(pos), u32_at(pos + 4));
+        assert_eq!(u32_at(pos + block_len - 4), block_len);
+        if block_type == 6 {
+            let pkt_len = u32_at(pos + 20);
+            let pkt = &data[pos + 28..pos + 28 + pkt_len];
+            assert_eq!(u16::from_be_bytes([pkt[22], pkt[23]]), GSMTAP_UDP_PORT);
+
+            let opt = pos + 28 + pkt_len.div_ceil(4) * 4;
+            assert_eq!(u16::from_le_bytes([data[opt], data[opt + 1]]), 1);
+            let comment_len = u16::from_le_bytes([data[opt + 2], data[opt + 3]]) as usize;
+            packets.push(Packet {
+                gsmtap: pkt[28..].to_vec(),
+                comment: String::from_utf8(data[opt + 4..opt + 4 + comment_len].to_vec()).unwrap(),
+            });
+        }
+        pos += block_len;
+    }
+    packets
+}
+
+#[test]
+fn test_capture_ul_and_dl_blocks() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, None);
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac, TetraEntity::Llc]);
+    let buf = SharedBuf::default();
+    let config = test.get_shared_config().config();
+    test.router.set_capture(PduCapture::new(Box::new(buf.clone()), &config).unwrap());
+
+    // A few downlink slots, carrying the broadcast blocks
+    test.run_stack(Some(4));
+
+    // An uplink block failing the CRC
+    let block =
+        "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+    test.submit_message(SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime { h: 0, m: 1, f: 2, t: 3 },
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: BitBuffer::from_bitstr(block),
+            block_num: PhyBlockNum::Block2,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: false,
+            scrambling_code: 864282631,
+            direction: Direction::Ul,
+            carrier: 0,
+        }),
+    });
+    test.run_stack(Some(1));
+
+    let packets = parse_capture(&buf.0.lock().unwrap());
+    let main_carrier = config.cell.main_carrier;
+
+    // Downlink: BSCH, among others, and AACH in every slot
+    let dl: Vec<&Packet> = packets.iter().filter(|p| p.comment.starts_with("Dl ")).collect();
+    assert!(dl.iter().any(|p| p.gsmtap[12] == 1 && p.comment.contains("Bsch")));
+    assert!(dl.iter().filter(|p| p.gsmtap[12] == 2).count() >= 4);
+    for p in &dl {
+        assert_eq!(&p.gsmtap[..3], &[2, 4, 5]);
+        assert_eq!(u16::from_be_bytes([p.gsmtap[4], p.gsmtap[5]]), main_carrier);
+    }
+
+    // Uplink: the SCH/HU block, in the second half slot, at the time it was received
+    let ul = packets
+        .iter()
+        .find(|p| p.comment.starts_with("Ul ") && p.gsmtap[12] == 3)
+        .expect("uplink block captured");
+    assert_eq!(ul.gsmtap[3], 3);
+    assert_eq!(u16::from_be_bytes([ul.gsmtap[4], ul.gsmtap[5]]), 0x4000 | main_carrier);
+    assert_eq!(u32::from_be_bytes(ul.gsmtap[8..12].try_into().unwrap()), 1);
+    assert_eq!(ul.gsmtap[14], 1);
+    assert_eq!(ul.gsmtap[16..], [0u8; 16]);
+    assert_eq!(ul.comment, "Ul 0/01/02/3 SchHu carrier 0 blk 2 CRC FAIL");
+}
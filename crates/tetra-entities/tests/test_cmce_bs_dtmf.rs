@@ -0,0 +1,217 @@
+mod common;
+
+use std::time::Duration;
+
+use tetra_config::bluestation::{CfgBrew, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::dtmf_type::DtmfType;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::fields::dtmf::Dtmf;
+use tetra_pdus::cmce::pdus::d_info::DInfo;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_info::UInfo;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+use crate::common::cmce::extract_pdus;
+
+const GSSI: u32 = 91;
+const ISSI: u32 = 1000001;
+
+fn setup(dltime: TdmaTime, feature_dtmf_enabled: bool) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.brew = Some(CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: true,
+        feature_dtmf_enabled,
+        feature_packet_data_enabled: false,
+        whitelisted_ssis: None,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    for (groups, action) in [
+        (vec![], BrewSubscriberAction::Register),
+        (vec![GSSI], BrewSubscriberAction::Affiliate),
+    ] {
+        test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+                issi: ISSI,
+                groups,
+                action,
+            }),
+        });
+        test.run_stack(Some(1));
+    }
+    test.dump_sinks();
+    test
+}
+
+fn build_lcmc_ind(dltime: TdmaTime, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(ISSI, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+/// Set up a group call from ISSI to GSSI and return its call identifier
+fn start_group_call(test: &mut ComponentTest, dltime: TdmaTime) -> u16 {
+    let pdu = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, sdu));
+    test.run_stack(Some(1));
+
+    let mut sdu = extract_pdus(&test.dump_sinks(), CmcePduTypeDl::DSetup).remove(0).1;
+    DSetup::from_bitbuf(&mut sdu).unwrap().call_identifier
+}
+
+fn send_u_info(test: &mut ComponentTest, dltime: TdmaTime, call_id: u16, dtmf: Dtmf) {
+    let pdu = UInfo {
+        call_identifier: call_id,
+        poll_response: false,
+        modify: None,
+        dtmf: Some(dtmf.to_type3()),
+        facility: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, sdu));
+    test.run_stack(Some(1));
+}
+
+/// DTMF digits sent to Brew, by call identifier
+fn dtmf_to_brew(msgs: &[SapMsg]) -> Vec<(u16, String)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Dtmf { call_id, digits }) if m.dest == TetraEntity::Brew => {
+                Some((*call_id, digits.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_dtmf_forwarded_to_brew() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, true);
+    let call_id = start_group_call(&mut test, dltime);
+
+    send_u_info(&mut test, dltime, call_id, Dtmf::tone_start("91#").unwrap());
+    assert_eq!(dtmf_to_brew(&test.dump_sinks()), vec![(call_id, "91#".to_string())]);
+
+    // Tone end carries no digits and is not forwarded
+    let tone_end = Dtmf {
+        dtmf_type: DtmfType::DtmfToneEnd,
+        digits: String::new(),
+    };
+    send_u_info(&mut test, dltime, call_id, tone_end);
+    assert!(dtmf_to_brew(&test.dump_sinks()).is_empty());
+
+    // Nor is DTMF on a call that doesn't exist
+    send_u_info(&mut test, dltime, call_id + 1, Dtmf::tone_start("1").unwrap());
+    assert!(dtmf_to_brew(&test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_dtmf_not_forwarded_when_disabled() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, false);
+    let call_id = start_group_call(&mut test, dltime);
+
+    send_u_info(&mut test, dltime, call_id, Dtmf::tone_start("91#").unwrap());
+    assert!(dtmf_to_brew(&test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_dtmf_from_brew_delivered_in_d_info() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, true);
+    let call_id = start_group_call(&mut test, dltime);
+
+    // More digits than fit into one DTMF element are split over two D-INFO PDUs
+    let digits = "0123456789*#ABCD1";
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Brew,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::CmceCallControl(CallControl::Dtmf {
+            call_id,
+            digits: digits.to_string(),
+        }),
+    });
+    test.run_stack(Some(1));
+
+    let d_infos = extract_pdus(&test.dump_sinks(), CmcePduTypeDl::DInfo);
+    assert_eq!(d_infos.len(), 2);
+    let mut received = String::new();
+    for (ssi, mut sdu) in d_infos {
+        assert_eq!(ssi, GSSI);
+        let pdu = DInfo::from_bitbuf(&mut sdu).expect("Failed parsing D-INFO");
+        assert_eq!(pdu.call_identifier, call_id);
+        let dtmf = Dtmf::from_type3(&pdu.dtmf.unwrap()).unwrap();
+        assert_eq!(dtmf.dtmf_type, DtmfType::DtmfToneStart);
+        received.push_str(&dtmf.digits);
+    }
+    assert_eq!(received, digits);
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+use crate::common::cmce::{d_release_causes, extract_opens, extract_pdus, occupy};
+
+const GSSI_A: u32 = 91;
+const GSSI_B: u32 = 92;
+const ISSI_A: u32 = 1000001;
+const ISSI_B: u32 = 1000002;
+const ISSI_C: u32 = 1000003;
+const EMERGENCY: u8 = 15;
+
+/// Default config: no call queue, emergency calls pre-empt
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    affiliate(&mut test, dltime, ISSI_A, GSSI_A);
+    affiliate(&mut test, dltime, ISSI_B, GSSI_B);
+    test.dump_sinks();
+    test
+}
+
+fn affiliate(test: &mut ComponentTest, dltime: TdmaTime, issi: u32, gssi: u32) {
+    for (groups, action) in [
+        (vec![], BrewSubscriberAction::Register),
+        (vec![gssi], BrewSubscriberAction::Affiliate),
+    ] {
+        test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate { issi, groups, action }),
+        });
+        test.run_stack(Some(1));
+    }
+}
+
+fn build_u_setup_msg(dltime: TdmaTime, issi: u32, ssi: u32, comm_type: CommunicationType, call_priority: u8) -> SapMsg {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: comm_type,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: comm_type == CommunicationType::P2p,
+        call_priority,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(ssi as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+fn d_setup_priorities(msgs: &[SapMsg]) -> Vec<(u32, u8)> {
+    extract_pdus(msgs, CmcePduTypeDl::DSetup)
+        .into_iter()
+        .map(|(ssi, mut sdu)| (ssi, DSetup::from_bitbuf(&mut sdu).unwrap().call_priority))
+        .collect()
+}
+
+#[test]
+fn test_emergency_call_to_unlistened_group() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    const UNLISTENED_GSSI: u32 = 99;
+
+    // An ordinary call to a group nobody is attached to is not set up
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, UNLISTENED_GSSI, CommunicationType::P2Mp, 0));
+    test.run_stack(Some(1));
+    assert!(extract_opens(&test.dump_sinks()).is_empty());
+
+    // An emergency call is, and carries its priority in D-SETUP
+    test.submit_message(build_u_setup_msg(
+        dltime,
+        ISSI_A,
+        UNLISTENED_GSSI,
+        CommunicationType::P2Mp,
+        EMERGENCY,
+    ));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(extract_opens(&msgs).len(), 1);
+    let priorities = d_setup_priorities(&msgs);
+    assert!(!priorities.is_empty());
+    assert!(priorities.iter().all(|&p| p == (UNLISTENED_GSSI, EMERGENCY)));
+}
+
+#[test]
+fn test_emergency_group_call_preempts() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    occupy(&mut test, &[3, 4]);
+
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, CommunicationType::P2Mp, 11));
+    test.run_stack(Some(1));
+    assert_eq!(extract_opens(&test.dump_sinks()), vec![2]);
+
+    // Without a call queue, an ordinary call is turned away
+    test.submit_message(build_u_setup_msg(dltime, ISSI_B, GSSI_B, CommunicationType::P2Mp, 11));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(ISSI_B, DisconnectCause::CongestionInInfrastructure)]);
+
+    // An emergency call releases the ongoing call and takes its timeslot
+    test.submit_message(build_u_setup_msg(dltime, ISSI_B, GSSI_B, CommunicationType::P2Mp, EMERGENCY));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(GSSI_A, DisconnectCause::PreEmptiveUseOfResource)]);
+    assert_eq!(extract_opens(&msgs), vec![2]);
+}
+
+#[test]
+fn test_emergency_individual_call_preempts() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    test.config.state_write().subscribers.register(ISSI_C);
+    occupy(&mut test, &[3, 4]);
+
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, CommunicationType::P2Mp, 0));
+    test.run_stack(Some(1));
+    assert_eq!(extract_opens(&test.dump_sinks()), vec![2]);
+
+    // Direct simplex individual emergency call, connected right away on the freed timeslot
+    test.submit_message(build_u_setup_msg(dltime, ISSI_B, ISSI_C, CommunicationType::P2p, EMERGENCY));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(GSSI_A, DisconnectCause::PreEmptiveUseOfResource)]);
+    assert_eq!(extract_opens(&msgs), vec![2]);
+    assert_eq!(d_setup_priorities(&msgs), vec![(ISSI_C, EMERGENCY)]);
+}
@@ -0,0 +1,417 @@
+mod common;
+
+use tetra_config::bluestation::{CallForwardingCondition, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_connect::DConnect;
+use tetra_pdus::cmce::pdus::d_connect_acknowledge::DConnectAcknowledge;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::d_tx_granted::DTxGranted;
+use tetra_pdus::cmce::pdus::u_alert::UAlert;
+use tetra_pdus::cmce::pdus::u_connect::UConnect;
+use tetra_pdus::cmce::pdus::u_disconnect::UDisconnect;
+use tetra_pdus::cmce::pdus::u_release::URelease;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_tx_ceased::UTxCeased;
+use tetra_pdus::cmce::pdus::u_tx_demand::UTxDemand;
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const CALLING_ISSI: u32 = 1000001;
+const CALLED_ISSI: u32 = 1000002;
+const OTHER_ISSI: u32 = 1000003;
+
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    let mut state = test.config.state_write();
+    state.subscribers.register(CALLING_ISSI);
+    state.subscribers.register(CALLED_ISSI);
+    drop(state);
+    test
+}
+
+fn build_lcmc_ind(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+/// Build a U-SETUP for an individual call from CALLING_ISSI to `called_issi`
+fn build_u_setup_msg(dltime: TdmaTime, called_issi: u32, hook: bool, duplex: bool) -> SapMsg {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: hook,
+        simplex_duplex_selection: duplex,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2p,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: true,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(called_issi as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_lcmc_ind(dltime, CALLING_ISSI, sdu)
+}
+
+/// Collects the CMCE PDUs of the given type sent to MLE, with their address and channel allocation
+fn extract_pdus(msgs: &[SapMsg], pdu_type: CmcePduTypeDl) -> Vec<(u32, BitBuffer, Option<CmceChanAllocReq>)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(pdu_type.into_raw()) => {
+                Some((prim.main_address.ssi, prim.sdu.clone(), prim.chan_alloc.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn extract_umac_opens(msgs: &[SapMsg]) -> Vec<(u8, Option<u8>)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) if m.dest == TetraEntity::Umac => Some((circuit.ts, circuit.peer_ts)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn allocated_ts(chan_alloc: &Option<CmceChanAllocReq>) -> u8 {
+    let chan_alloc = chan_alloc.as_ref().expect("no channel allocation");
+    chan_alloc.timeslots.iter().position(|&t| t).unwrap() as u8 + 1
+}
+
+fn call_id_of_d_setup(msgs: &[SapMsg]) -> u16 {
+    let (_, mut sdu, _) = extract_pdus(msgs, CmcePduTypeDl::DSetup).remove(0);
+    DSetup::from_bitbuf(&mut sdu).unwrap().call_identifier
+}
+
+fn d_release_causes(msgs: &[SapMsg]) -> Vec<(u32, DisconnectCause)> {
+    extract_pdus(msgs, CmcePduTypeDl::DRelease)
+        .into_iter()
+        .map(|(issi, mut sdu, _)| (issi, DRelease::from_bitbuf(&mut sdu).unwrap().disconnect_cause))
+        .collect()
+}
+
+#[test]
+fn test_direct_simplex_call_with_floor_control() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime);
+
+    test.submit_message(build_u_setup_msg(dltime, CALLED_ISSI, false, false));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    // Caller gets D-CALL-PROCEEDING and D-CONNECT with the floor
+    assert_eq!(extract_pdus(&msgs, CmcePduTypeDl::DCallProceeding).len(), 1);
+    let mut connects = extract_pdus(&msgs, CmcePduTypeDl::DConnect);
+    assert_eq!(connects.len(), 1);
+    let (issi, sdu, connect_alloc) = &mut connects[0];
+    assert_eq!(*issi, CALLING_ISSI);
+    let d_connect = DConnect::from_bitbuf(sdu).unwrap();
+    assert_eq!(d_connect.transmission_grant, TransmissionGrant::Granted);
+    assert!(!d_connect.simplex_duplex_selection);
+
+    // Called party gets D-SETUP with the same traffic channel, and the other user talking
+    let mut setups = extract_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    let (issi, sdu, setup_alloc) = &mut setups[0];
+    assert_eq!(*issi, CALLED_ISSI);
+    let d_setup = DSetup::from_bitbuf(sdu).unwrap();
+    assert_eq!(d_setup.transmission_grant, TransmissionGrant::GrantedToOtherUser);
+    assert_eq!(d_setup.calling_party_address_ssi, Some(CALLING_ISSI));
+    let ts = allocated_ts(connect_alloc);
+    assert_eq!(allocated_ts(setup_alloc), ts);
+    assert_eq!(extract_umac_opens(&msgs), vec![(ts, None)]);
+    let call_id = d_setup.call_identifier;
+
+    // Caller releases the floor, both parties are told
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UTxCeased {
+        call_identifier: call_id,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLING_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let mut ceased: Vec<u32> = extract_pdus(&msgs, CmcePduTypeDl::DTxCeased)
+        .iter()
+        .map(|(issi, _, _)| *issi)
+        .collect();
+    ceased.sort();
+    assert_eq!(ceased, vec![CALLING_ISSI, CALLED_ISSI]);
+
+    // Called party takes the floor
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UTxDemand {
+        call_identifier: call_id,
+        tx_demand_priority: 0,
+        encryption_control: false,
+        reserved: false,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLED_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let grants: Vec<(u32, u8)> = extract_pdus(&msgs, CmcePduTypeDl::DTxGranted)
+        .into_iter()
+        .map(|(issi, mut sdu, _)| (issi, DTxGranted::from_bitbuf(&mut sdu).unwrap().transmission_grant))
+        .collect();
+    assert!(grants.contains(&(CALLED_ISSI, TransmissionGrant::Granted.into_raw() as u8)));
+    assert!(grants.contains(&(CALLING_ISSI, TransmissionGrant::GrantedToOtherUser.into_raw() as u8)));
+
+    // Called party disconnects, both parties get D-RELEASE and the circuit is closed
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UDisconnect {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLED_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let mut released: Vec<u32> = d_release_causes(&msgs).iter().map(|(issi, _)| *issi).collect();
+    released.sort();
+    assert_eq!(released, vec![CALLING_ISSI, CALLED_ISSI]);
+    assert!(msgs.iter().any(|m| matches!(
+        &m.msg,
+        SapMsgInner::CmceCallControl(CallControl::Close(Direction::Both, 0, closed_ts)) if *closed_ts == ts
+    )));
+    assert!(test.config.state_read().timeslot_alloc.is_free(ts));
+}
+
+#[test]
+fn test_hook_duplex_call() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime);
+
+    test.submit_message(build_u_setup_msg(dltime, CALLED_ISSI, true, true));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    // Called party is offered the call, but no channel until it answers
+    let setups = extract_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    assert_eq!(setups[0].0, CALLED_ISSI);
+    assert!(setups[0].2.is_none());
+    assert!(extract_pdus(&msgs, CmcePduTypeDl::DConnect).is_empty());
+    assert!(extract_umac_opens(&msgs).is_empty());
+    let call_id = call_id_of_d_setup(&msgs);
+
+    // Called party rings, caller gets D-ALERT
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UAlert {
+        call_identifier: call_id,
+        reserved: true,
+        simplex_duplex_selection: true,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLED_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let alerts = extract_pdus(&msgs, CmcePduTypeDl::DAlert);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].0, CALLING_ISSI);
+
+    // Called party answers: both get their own traffic channel, cross-routed in UMAC
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect {
+        call_identifier: call_id,
+        hook_method_selection: true,
+        simplex_duplex_selection: true,
+        basic_service_information: None,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLED_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    let mut connects = extract_pdus(&msgs, CmcePduTypeDl::DConnect);
+    assert_eq!(connects.len(), 1);
+    let (issi, sdu, calling_alloc) = &mut connects[0];
+    assert_eq!(*issi, CALLING_ISSI);
+    let d_connect = DConnect::from_bitbuf(sdu).unwrap();
+    assert!(d_connect.simplex_duplex_selection);
+    assert_eq!(d_connect.transmission_grant, TransmissionGrant::Granted);
+
+    let mut acks = extract_pdus(&msgs, CmcePduTypeDl::DConnectAcknowledge);
+    assert_eq!(acks.len(), 1);
+    let (issi, sdu, called_alloc) = &mut acks[0];
+    assert_eq!(*issi, CALLED_ISSI);
+    let d_connect_ack = DConnectAcknowledge::from_bitbuf(sdu).unwrap();
+    assert_eq!(d_connect_ack.transmission_grant, TransmissionGrant::Granted.into_raw() as u8);
+
+    let ts_calling = allocated_ts(calling_alloc);
+    let ts_called = allocated_ts(called_alloc);
+    assert_ne!(ts_calling, ts_called);
+    let mut opens = extract_umac_opens(&msgs);
+    opens.sort();
+    let mut expected = vec![(ts_calling, Some(ts_called)), (ts_called, Some(ts_calling))];
+    expected.sort();
+    assert_eq!(opens, expected);
+
+    // Caller hangs up with U-RELEASE, only the called party needs a D-RELEASE
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    URelease {
+        call_identifier: call_id,
+        disconnect_cause: DisconnectCause::UserRequestedDisconnection,
+        facility: None,
+        proprietary: None,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, CALLING_ISSI, sdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(
+        d_release_causes(&msgs),
+        vec![(CALLED_ISSI, DisconnectCause::UserRequestedDisconnection)]
+    );
+    let state = test.config.state_read();
+    assert!(state.timeslot_alloc.is_free(ts_calling));
+    assert!(state.timeslot_alloc.is_free(ts_called));
+}
+
+#[test]
+fn test_call_to_unreachable_and_busy_party() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime);
+
+    // Not registered
+    test.submit_message(build_u_setup_msg(dltime, OTHER_ISSI, false, false));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(
+        d_release_causes(&msgs),
+        vec![(CALLING_ISSI, DisconnectCause::CalledPartyNotReachable)]
+    );
+    assert!(extract_pdus(&msgs, CmcePduTypeDl::DSetup).is_empty());
+
+    // Busy in another individual call
+    test.submit_message(build_u_setup_msg(dltime, CALLED_ISSI, true, false));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+    test.config.state_write().subscribers.register(OTHER_ISSI);
+
+    let mut msg = build_u_setup_msg(dltime, CALLED_ISSI, false, false);
+    let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut msg.msg else {
+        panic!()
+    };
+    prim.received_tetra_address = TetraAddress::new(OTHER_ISSI, SsiType::Issi);
+    test.submit_message(msg);
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(OTHER_ISSI, DisconnectCause::CalledPartyBusy)]);
+}
+
+#[test]
+fn test_call_forwarding_applied() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime);
+    {
+        let mut state = test.config.state_write();
+        state.subscribers.register(OTHER_ISSI);
+        state
+            .subscribers
+            .set_call_forwarding(CALLED_ISSI, CallForwardingCondition::Unconditional, Some(OTHER_ISSI));
+    }
+
+    test.submit_message(build_u_setup_msg(dltime, CALLED_ISSI, false, false));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let setups = extract_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    assert_eq!(setups[0].0, OTHER_ISSI);
+}
+
+#[test]
+fn test_hook_call_forwarded_on_no_reply() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    {
+        let mut state = test.config.state_write();
+        state.subscribers.register(OTHER_ISSI);
+        state
+            .subscribers
+            .set_call_forwarding(CALLED_ISSI, CallForwardingCondition::NoReply, Some(OTHER_ISSI));
+    }
+
+    test.submit_message(build_u_setup_msg(dltime, CALLED_ISSI, true, false));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(extract_pdus(&msgs, CmcePduTypeDl::DSetup)[0].0, CALLED_ISSI);
+
+    // Let the no-answer timer expire (about 60 seconds)
+    test.run_stack(Some(62 * 18 * 4));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(CALLED_ISSI, DisconnectCause::ExpiryOfTimer)]);
+    let setups = extract_pdus(&msgs, CmcePduTypeDl::DSetup);
+    assert_eq!(setups.len(), 1);
+    assert_eq!(setups[0].0, OTHER_ISSI);
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TimeslotOwner, debug};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const TEST_GSSI: u32 = 91;
+const TEST_ISSI: u32 = 1000001;
+
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.secondary_carriers = vec![config.cell.main_carrier + 1];
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test
+}
+
+fn submit_subscriber_update(test: &mut ComponentTest, dltime: TdmaTime, groups: Vec<u32>, action: BrewSubscriberAction) {
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: TEST_ISSI,
+            groups,
+            action,
+        }),
+    });
+    test.run_stack(Some(1));
+}
+
+fn build_u_setup_msg(dltime: TdmaTime) -> SapMsg {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(TEST_GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).expect("Failed to serialize USetup");
+    sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(TEST_ISSI, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+/// Channel allocations of all D-SETUPs sent to the group
+fn d_setup_chan_allocs(msgs: &[SapMsg]) -> Vec<CmceChanAllocReq> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.main_address.ssi == TEST_GSSI => {
+                prim.chan_alloc.clone().filter(|ca| ca.usage.is_some())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_group_call_on_secondary_carrier() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    assert_eq!(test.config.state_read().timeslot_alloc.num_carriers(), 2);
+
+    // Occupy all traffic timeslots of the main carrier
+    {
+        let mut state = test.config.state_write();
+        for ts in 2..=4 {
+            state.timeslot_alloc.reserve(TimeslotOwner::Sndcp, ts).unwrap();
+        }
+    }
+
+    submit_subscriber_update(&mut test, dltime, vec![], BrewSubscriberAction::Register);
+    submit_subscriber_update(&mut test, dltime, vec![TEST_GSSI], BrewSubscriberAction::Affiliate);
+    test.dump_sinks();
+
+    test.submit_message(build_u_setup_msg(dltime));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    // The call lands on TS1 of the secondary carrier
+    let chan_allocs = d_setup_chan_allocs(&msgs);
+    assert!(!chan_allocs.is_empty(), "no D-SETUP sent to the group");
+    for chan_alloc in chan_allocs {
+        assert_eq!(chan_alloc.carrier, Some(1));
+        assert_eq!(chan_alloc.timeslots, [true, false, false, false]);
+    }
+    let opened: Vec<(u8, u8)> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::CmceCallControl(CallControl::Open(circuit)) => Some((circuit.carrier, circuit.ts)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(opened, vec![(1, 1)]);
+    assert_eq!(test.config.state_read().timeslot_alloc.owner_on(1, 1), Some(TimeslotOwner::Cmce));
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use std::time::Duration;
+
+use tetra_config::bluestation::{CfgCallQueue, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TimeslotOwner, debug};
+use tetra_pdus::cmce::enums::call_status::CallStatus;
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::disconnect_cause::DisconnectCause;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_call_proceeding::DCallProceeding;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+use crate::common::cmce::{d_release_causes, extract_opens, extract_pdus, occupy};
+
+const GSSI_A: u32 = 91;
+const GSSI_B: u32 = 92;
+const ISSI_A: u32 = 1000001;
+const ISSI_B: u32 = 1000002;
+
+fn setup(dltime: TdmaTime, timeout_secs: u64) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.call_queue = Some(CfgCallQueue {
+        max_length: 4,
+        timeout: Duration::from_secs(timeout_secs),
+        emergency_timeout: Duration::from_secs(timeout_secs * 2),
+        preemption: true,
+        preemption_priority: 12,
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    affiliate(&mut test, dltime, ISSI_A, GSSI_A);
+    affiliate(&mut test, dltime, ISSI_B, GSSI_B);
+    test.dump_sinks();
+    test
+}
+
+fn affiliate(test: &mut ComponentTest, dltime: TdmaTime, issi: u32, gssi: u32) {
+    for (groups, action) in [
+        (vec![], BrewSubscriberAction::Register),
+        (vec![gssi], BrewSubscriberAction::Affiliate),
+    ] {
+        test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate { issi, groups, action }),
+        });
+        test.run_stack(Some(1));
+    }
+}
+
+fn build_u_setup_msg(dltime: TdmaTime, issi: u32, gssi: u32, call_priority: u8) -> SapMsg {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(gssi as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+#[test]
+fn test_group_call_queued_until_timeslot_free() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, 30);
+    occupy(&mut test, &[2, 3, 4]);
+
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, 0));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+
+    // The caller is told its call is queued, nothing is set up yet
+    let mut proceeding = extract_pdus(&msgs, CmcePduTypeDl::DCallProceeding);
+    assert_eq!(proceeding.len(), 1);
+    let (ssi, sdu) = &mut proceeding[0];
+    assert_eq!(*ssi, ISSI_A);
+    let pdu = DCallProceeding::from_bitbuf(sdu).unwrap();
+    assert_eq!(pdu.call_status, Some(CallStatus::Callqueued));
+    assert!(extract_opens(&msgs).is_empty());
+    assert!(extract_pdus(&msgs, CmcePduTypeDl::DSetup).is_empty());
+
+    // Packet data gives up a timeslot, the queued call is served on it
+    test.config.state_write().timeslot_alloc.release(TimeslotOwner::Sndcp, 3).unwrap();
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(extract_opens(&msgs), vec![3]);
+    assert_eq!(extract_pdus(&msgs, CmcePduTypeDl::DConnect).len(), 1);
+    assert!(extract_pdus(&msgs, CmcePduTypeDl::DSetup).iter().any(|(ssi, _)| *ssi == GSSI_A));
+}
+
+#[test]
+fn test_queued_call_times_out() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, 1);
+    occupy(&mut test, &[2, 3, 4]);
+
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, 0));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    test.run_stack(Some(2 * 18 * 4));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(ISSI_A, DisconnectCause::ExpiryOfTimer)]);
+    assert!(extract_opens(&msgs).is_empty());
+}
+
+#[test]
+fn test_preemption_of_lower_priority_call() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime, 30);
+    occupy(&mut test, &[3, 4]);
+
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, 0));
+    test.run_stack(Some(1));
+    assert_eq!(extract_opens(&test.dump_sinks()), vec![2]);
+
+    // A pre-emptive priority call takes over the only traffic timeslot
+    test.submit_message(build_u_setup_msg(dltime, ISSI_B, GSSI_B, 12));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(d_release_causes(&msgs), vec![(GSSI_A, DisconnectCause::PreEmptiveUseOfResource)]);
+    assert_eq!(extract_opens(&msgs), vec![2]);
+    assert!(extract_pdus(&msgs, CmcePduTypeDl::DSetup).iter().any(|(ssi, _)| *ssi == GSSI_B));
+
+    // A call below the pre-emptive priorities has to wait
+    test.submit_message(build_u_setup_msg(dltime, ISSI_A, GSSI_A, 11));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(d_release_causes(&msgs).is_empty());
+    assert!(extract_opens(&msgs).is_empty());
+}
@@ -3,10 +3,22 @@ mod common;
 use common::ComponentTest;
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, TxState, debug};
+use tetra_entities::llc::components::advanced_link::AL_SEGMENT_LEN_BITS;
+use tetra_entities::llc::components::fcs;
+use tetra_pdus::llc::enums::al_disc_report::AlDiscReport;
+use tetra_pdus::llc::enums::al_setup_report::AlSetupReport;
+use tetra_pdus::llc::enums::llc_pdu_type::LlcPduType;
+use tetra_pdus::llc::pdus::al_ack::AlAck;
+use tetra_pdus::llc::pdus::al_data::AlData;
+use tetra_pdus::llc::pdus::al_disc::AlDisc;
+use tetra_pdus::llc::pdus::al_setup::AlSetup;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::TlaTlDataReqBl;
 use tetra_saps::tma::TmaUnitdataInd;
 
+const ISSI: u32 = 2065022;
+
 #[test]
 fn test_udata_with_broken_mm_payload() {
     // INCOMPLETE VECTOR replace with something more meaningful
@@ -55,3 +67,219 @@ fn test_udata_with_broken_mm_payload() {
     assert_eq!(sink_msgs.len(), 1);
     tracing::warn!("Validation of result not implemented");
 }
+
+fn setup_al(advanced_link: bool) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.advanced_link = advanced_link;
+    let mut test = ComponentTest::from_config(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Llc], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    test
+}
+
+/// Submits an LLC PDU as received from the MS on timeslot 1
+fn submit_from_ms(test: &mut ComponentTest, pdu: BitBuffer) {
+    test.submit_message(SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Umac,
+        dest: TetraEntity::Llc,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: Some(pdu),
+            main_address: TetraAddress::new(ISSI, SsiType::Issi),
+            scrambling_code: 864282631,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    });
+    test.run_stack(Some(1));
+}
+
+#[test]
+fn test_unsupported_pdu_types_dropped() {
+    let mut test = setup_al(true);
+
+    // Supplementary LLC PDU and layer 2 signalling PDU
+    submit_from_ms(&mut test, BitBuffer::from_bitstr("11010000101100"));
+    submit_from_ms(&mut test, BitBuffer::from_bitstr("11100000101100"));
+    assert!(test.dump_sinks().is_empty());
+
+    // TMA-UNITDATA without TM-SDU
+    test.submit_message(SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Umac,
+        dest: TetraEntity::Llc,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmaUnitdataInd(TmaUnitdataInd {
+            pdu: None,
+            main_address: TetraAddress::new(ISSI, SsiType::Issi),
+            scrambling_code: 864282631,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            air_interface_encryption: 0,
+            chan_change_response_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    assert!(test.dump_sinks().is_empty());
+}
+
+fn al_setup(setup_report: AlSetupReport) -> BitBuffer {
+    let mut buf = BitBuffer::new_autoexpand(32);
+    AlSetup {
+        unacknowledged: false,
+        setup_report,
+        max_tlsdu_len: 7,
+        window_size: 3,
+        max_tlsdu_retransmissions: 3,
+        max_segment_retransmissions: 3,
+        num_timeslots: 0,
+    }
+    .to_bitbuf(&mut buf);
+    buf.seek(0);
+    buf
+}
+
+fn test_sdu(bits: usize) -> BitBuffer {
+    let mut buf = BitBuffer::new_autoexpand(bits);
+    for i in 0..bits {
+        buf.write_bit((i % 5 == 0) as u8);
+    }
+    buf.seek(0);
+    buf
+}
+
+/// LLC PDUs of the given type sent to the Umac
+fn pdus_to_umac(msgs: &[SapMsg], pdu_type: LlcPduType) -> Vec<BitBuffer> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TmaUnitdataReq(prim) if prim.pdu.peek_bits(4) == Some(pdu_type.into_raw()) => Some(prim.pdu.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_al_setup_rejected_when_disabled() {
+    let mut test = setup_al(false);
+    submit_from_ms(&mut test, al_setup(AlSetupReport::SetupRequest));
+
+    let mut discs = pdus_to_umac(&test.dump_sinks(), LlcPduType::AlDisc);
+    assert_eq!(discs.len(), 1);
+    assert_eq!(
+        AlDisc::from_bitbuf(&mut discs[0]).unwrap().report,
+        AlDiscReport::ServiceNotSupported
+    );
+}
+
+#[test]
+fn test_al_uplink_segmented_tlsdu() {
+    let mut test = setup_al(true);
+    submit_from_ms(&mut test, al_setup(AlSetupReport::SetupRequest));
+    let mut setups = pdus_to_umac(&test.dump_sinks(), LlcPduType::AlSetup);
+    assert_eq!(
+        AlSetup::from_bitbuf(&mut setups[0]).unwrap().setup_report,
+        AlSetupReport::SetupConfirm
+    );
+
+    // TL-SDU plus FCS, split into AL-DATA segments, the last one being AL-FINAL-AR
+    let sdu = test_sdu(600);
+    let mut with_fcs = BitBuffer::new_autoexpand(632);
+    with_fcs.copy_bits(&mut sdu.clone(), 600);
+    with_fcs.write_bits(fcs::compute_fcs(&sdu, 0, 600) as u64, 32);
+    with_fcs.seek(0);
+    let num_segments = 632usize.div_ceil(AL_SEGMENT_LEN_BITS);
+    for ss in 0..num_segments {
+        let is_final = ss == num_segments - 1;
+        let mut pdu = BitBuffer::new_autoexpand(AlData::HEADER_LEN + AL_SEGMENT_LEN_BITS);
+        AlData {
+            is_final,
+            ack_request: is_final,
+            ns: 0,
+            ss: ss as u8,
+        }
+        .to_bitbuf(&mut pdu);
+        let len = with_fcs.get_len_remaining().min(AL_SEGMENT_LEN_BITS);
+        pdu.copy_bits(&mut with_fcs, len);
+        pdu.seek(0);
+        submit_from_ms(&mut test, pdu);
+    }
+
+    let msgs = test.dump_sinks();
+    let mut acks = pdus_to_umac(&msgs, LlcPduType::AlAckAlRnr);
+    assert_eq!(acks.len(), 1);
+    let ack = AlAck::from_bitbuf(&mut acks[0]).unwrap();
+    assert_eq!((ack.nr, ack.selective), (0, None));
+
+    let delivered: Vec<String> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TlaTlDataIndBl(prim) => prim.tl_sdu.as_ref().map(|s| s.to_bitstr()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(delivered, vec![sdu.to_bitstr()]);
+}
+
+#[test]
+fn test_al_downlink_long_tlsdu() {
+    let mut test = setup_al(true);
+    let tx_reporter = TxReporter::new();
+    test.submit_message(SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Llc,
+        dltime: TdmaTime::default().add_timeslots(2),
+        msg: SapMsgInner::TlaTlDataReqBl(TlaTlDataReqBl {
+            main_address: TetraAddress::new(ISSI, SsiType::Issi),
+            link_id: 0,
+            endpoint_id: 0,
+            tl_sdu: test_sdu(3000),
+            stealing_permission: false,
+            subscriber_class: 0,
+            fcs_flag: false,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_class_info: None,
+            req_handle: 0,
+            graceful_degradation: None,
+            chan_alloc: None,
+            tx_reporter: Some(tx_reporter.clone()),
+        }),
+    });
+    test.run_stack(Some(1));
+
+    // Too long for the basic link, so the BS sets up an advanced link first
+    let msgs = test.dump_sinks();
+    assert!(pdus_to_umac(&msgs, LlcPduType::BlData).is_empty());
+    let mut setups = pdus_to_umac(&msgs, LlcPduType::AlSetup);
+    assert_eq!(
+        AlSetup::from_bitbuf(&mut setups[0]).unwrap().setup_report,
+        AlSetupReport::SetupRequest
+    );
+
+    submit_from_ms(&mut test, al_setup(AlSetupReport::SetupConfirm));
+    let data = pdus_to_umac(&test.dump_sinks(), LlcPduType::AlDataAlFinal);
+    assert_eq!(data.len(), 3032usize.div_ceil(AL_SEGMENT_LEN_BITS));
+    let last = AlData::from_bitbuf(&mut data.last().unwrap().clone()).unwrap();
+    assert!(last.is_final && last.ack_request);
+
+    let mut ack = BitBuffer::new_autoexpand(16);
+    AlAck {
+        not_ready: false,
+        nr: 0,
+        selective: None,
+    }
+    .to_bitbuf(&mut ack);
+    ack.seek(0);
+    submit_from_ms(&mut test, ack);
+    assert_eq!(tx_reporter.get_state(), TxState::Acknowledged);
+}
@@ -0,0 +1,325 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{Value, json};
+use tetra_config::bluestation::{CfgMgmt, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::mgmt::entity::MgmtEntity;
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::control::mm::{DisableTarget, MmControl};
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI_A: u32 = 1000001;
+const ISSI_B: u32 = 1000002;
+
+fn socket_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bluestation-mgmt-{}-{}.sock", std::process::id(), name))
+}
+
+fn setup(dltime: TdmaTime, name: &str) -> ComponentTest {
+    setup_with(
+        dltime,
+        name,
+        vec![TetraEntity::Mm, TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac],
+    )
+}
+
+fn setup_with(dltime: TdmaTime, name: &str, components: Vec<TetraEntity>, sinks: Vec<TetraEntity>) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.mgmt = Some(CfgMgmt {
+        socket_path: socket_path(name).to_string_lossy().into_owned(),
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    let mgmt = MgmtEntity::new(test.get_shared_config());
+    test.register_entity(mgmt);
+    test.populate_entities(components, sinks);
+    test
+}
+
+fn register(test: &mut ComponentTest, issi: u32) {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(issi, SsiType::Issi),
+        }),
+    });
+    test.run_stack(Some(1));
+    test.dump_sinks();
+}
+
+/// Direct signalling simplex individual call from ISSI_A to ISSI_B, through-connected straight away
+fn start_individual_call(test: &mut ComponentTest, dltime: TdmaTime) {
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2p,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: true,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(ISSI_B as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(ISSI_A, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    test.dump_sinks();
+}
+
+/// Send requests over the management socket while running the stack, returning the responses
+fn request(test: &mut ComponentTest, name: &str, requests: Vec<Value>) -> Vec<Value> {
+    let path = socket_path(name);
+    let client = thread::spawn(move || {
+        let mut stream = UnixStream::connect(path).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        requests
+            .iter()
+            .map(|req| {
+                writeln!(stream, "{}", req).unwrap();
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                serde_json::from_str::<Value>(&line).unwrap()
+            })
+            .collect::<Vec<Value>>()
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !client.is_finished() {
+        assert!(Instant::now() < deadline, "management API did not respond");
+        test.run_stack(Some(1));
+        thread::sleep(Duration::from_millis(1));
+    }
+    client.join().unwrap()
+}
+
+fn count_pdus(msgs: &[SapMsg], pdu_type: CmcePduTypeDl) -> Vec<u32> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(pdu_type.into_raw()) => Some(prim.main_address.ssi),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_mgmt_inspect_and_deregister() {
+    let name = "inspect";
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime, name);
+    register(&mut test, ISSI_A);
+    register(&mut test, ISSI_B);
+
+    let responses = request(
+        &mut test,
+        name,
+        vec![
+            json!({"cmd": "subscribers"}),
+            json!({"cmd": "timeslots"}),
+            json!({"cmd": "brew"}),
+            json!({"cmd": "deregister", "issi": ISSI_B}),
+            json!({"cmd": "deregister", "issi": 1234}),
+            json!({"cmd": "bogus"}),
+        ],
+    );
+    assert_eq!(
+        responses[0],
+        json!({"ok": true, "result": [
+            {"issi": ISSI_A, "groups": [], "encryption": "clear"},
+            {"issi": ISSI_B, "groups": [], "encryption": "clear"},
+        ]})
+    );
+    assert_eq!(
+        responses[1],
+        json!({"ok": true, "result": [
+            {"carrier": 0, "ts": 2, "owner": null}, {"carrier": 0, "ts": 3, "owner": null}, {"carrier": 0, "ts": 4, "owner": null},
+        ]})
+    );
+    assert_eq!(responses[2], json!({"ok": true, "result": {"enabled": false, "connected": false}}));
+    assert_eq!(responses[3], json!({"ok": true, "result": null}));
+    assert_eq!(responses[4]["ok"], json!(false));
+    assert_eq!(responses[5]["ok"], json!(false));
+
+    test.run_stack(Some(1));
+    let state = test.config.state_read();
+    assert!(state.subscribers.is_registered(ISSI_A));
+    assert!(!state.subscribers.is_registered(ISSI_B));
+}
+
+#[test]
+fn test_mgmt_calls_and_sds() {
+    let name = "calls";
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup(dltime, name);
+    register(&mut test, ISSI_A);
+    register(&mut test, ISSI_B);
+    start_individual_call(&mut test, dltime);
+
+    let responses = request(
+        &mut test,
+        name,
+        vec![
+            json!({"cmd": "calls"}),
+            json!({"cmd": "send_sds", "source_issi": 9999, "dest_issi": ISSI_A, "text": "hello"}),
+            json!({"cmd": "send_sds", "source_issi": 9999, "dest_issi": 1234, "text": "hello"}),
+        ],
+    );
+    let calls = responses[0]["result"].as_array().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["type"], json!("individual"));
+    assert_eq!(calls[0]["source_ssi"], json!(ISSI_A));
+    assert_eq!(calls[0]["dest_ssi"], json!(ISSI_B));
+    let call_id = calls[0]["call_id"].clone();
+    assert_eq!(responses[1], json!({"ok": true, "result": null}));
+    assert_eq!(responses[2]["ok"], json!(false));
+
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(count_pdus(&msgs, CmcePduTypeDl::DSdsData), vec![ISSI_A]);
+
+    // Force release sends D-RELEASE to both parties
+    let responses = request(
+        &mut test,
+        name,
+        vec![json!({"cmd": "release_call", "call_id": call_id}), json!({"cmd": "calls"})],
+    );
+    assert_eq!(responses[0], json!({"ok": true, "result": null}));
+    assert_eq!(responses[1], json!({"ok": true, "result": []}));
+    let mut released = count_pdus(&test.dump_sinks(), CmcePduTypeDl::DRelease);
+    released.sort_unstable();
+    assert_eq!(released, vec![ISSI_A, ISSI_B]);
+}
+
+#[test]
+fn test_mgmt_pending_request_times_out() {
+    // CMCE is a sink here, so the call list request is never answered
+    let name = "timeout";
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup_with(
+        dltime,
+        name,
+        vec![TetraEntity::Mm],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Cmce],
+    );
+
+    let responses = request(&mut test, name, vec![json!({"cmd": "calls"}), json!({"cmd": "brew"})]);
+    assert_eq!(responses[0]["ok"], json!(false));
+    assert_eq!(responses[1]["ok"], json!(true));
+}
+
+#[test]
+fn test_mgmt_disable_equipment() {
+    // MM is a sink, so the requests it receives can be inspected
+    let name = "disable";
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = setup_with(dltime, name, vec![], vec![TetraEntity::Mm]);
+
+    let responses = request(
+        &mut test,
+        name,
+        vec![
+            json!({"cmd": "disable", "issi": ISSI_A, "subscription": false, "tei": 1234}),
+            json!({"cmd": "disable", "issi": ISSI_A, "subscription": false}),
+            json!({"cmd": "disable", "issi": ISSI_A, "tei": 1u64 << 60}),
+            json!({"cmd": "enable", "issi": ISSI_A, "subscription": false, "tei": 1234}),
+        ],
+    );
+    assert_eq!(responses[0], json!({"ok": true, "result": null}));
+    assert_eq!(responses[1]["ok"], json!(false));
+    assert_eq!(responses[2]["ok"], json!(false));
+    assert_eq!(responses[3], json!({"ok": true, "result": null}));
+
+    test.run_stack(Some(1));
+    let controls: Vec<MmControl> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::MmControl(control) => Some(control),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        controls[..],
+        [
+            MmControl::Disable {
+                issi: ISSI_A,
+                permanent: false,
+                target: DisableTarget::Equipment { tei: 1234 },
+            },
+            MmControl::Enable {
+                issi: ISSI_A,
+                target: DisableTarget::Equipment { tei: 1234 },
+            },
+        ]
+    ));
+}
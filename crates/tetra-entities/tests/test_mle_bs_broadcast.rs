@@ -0,0 +1,72 @@
+mod common;
+
+use tetra_config::bluestation::{CfgNeighbourCell, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{TdmaTime, debug};
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+fn neighbour(cell_identifier: u8, main_carrier: u16) -> CfgNeighbourCell {
+    CfgNeighbourCell {
+        cell_identifier,
+        main_carrier,
+        freq_band: None,
+        freq_offset_hz: None,
+        duplex_spacing_id: None,
+        reverse_operation: None,
+        reselection_types_supported: 1,
+        synchronized: false,
+        cell_load: 0,
+        mcc: None,
+        mnc: None,
+        location_area: Some(cell_identifier as u16 + 100),
+        max_ms_tx_power: None,
+        min_rx_access_level: None,
+        tdma_frame_offset: None,
+    }
+}
+
+/// D-NWRK-BROADCASTs sent to LLC, with the MLE protocol discriminator stripped
+fn extract_d_nwrk_broadcasts(msgs: &[SapMsg]) -> Vec<DNwrkBroadcast> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::TlaTlUnitdataReqBl(prim) => {
+                let mut sdu = prim.tl_sdu.clone();
+                sdu.seek(3);
+                Some(DNwrkBroadcast::from_bitbuf(&mut sdu).unwrap())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_d_nwrk_broadcast_neighbour_cells() {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.neighbour_cells = vec![
+        neighbour(1, config.cell.main_carrier + 4),
+        neighbour(2, config.cell.main_carrier + 8),
+    ];
+    let main_carrier = config.cell.main_carrier;
+
+    // Start just before the broadcast slot, multiframe 20 frame 1
+    let dltime = TdmaTime { h: 0, m: 19, f: 18, t: 3 };
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc]);
+    test.run_stack(Some(4));
+
+    let broadcasts = extract_d_nwrk_broadcasts(&test.dump_sinks());
+    assert_eq!(broadcasts.len(), 1);
+    let pdu = &broadcasts[0];
+    assert_eq!(pdu.tetra_network_time, None);
+    assert_eq!(pdu.number_of_ca_neighbour_cells, Some(2));
+    let carriers: Vec<(u8, u16, Option<u64>)> = pdu
+        .neighbour_cell_information_for_ca
+        .iter()
+        .map(|n| (n.cell_identifier_ca, n.main_carrier_number, n.location_area))
+        .collect();
+    assert_eq!(carriers, vec![(1, main_carrier + 4, Some(101)), (2, main_carrier + 8, Some(102))]);
+}
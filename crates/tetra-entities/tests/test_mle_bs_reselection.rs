@@ -0,0 +1,285 @@
+mod common;
+
+use tetra_config::bluestation::{CfgNeighbourCell, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::d_call_restore::DCallRestore;
+use tetra_pdus::cmce::pdus::u_call_restore::UCallRestore;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::mle::enums::channel_command_valid::ChannelCommandValid;
+use tetra_pdus::mle::enums::fail_cause::FailCause;
+use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mle::pdus::d_new_cell::DNewCell;
+use tetra_pdus::mle::pdus::d_prepare_fail::DPrepareFail;
+use tetra_pdus::mle::pdus::d_restore_ack::DRestoreAck;
+use tetra_pdus::mle::pdus::d_restore_fail::DRestoreFail;
+use tetra_pdus::mle::pdus::u_prepare::UPrepare;
+use tetra_pdus::mle::pdus::u_restore::URestore;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tla::TlaTlDataIndBl;
+
+use crate::common::ComponentTest;
+
+const GSSI: u32 = 91;
+const ISSI_A: u32 = 1000001;
+const ISSI_B: u32 = 1000002;
+
+fn neighbour(cell_identifier: u8, reselection_types_supported: u8, main_carrier: u16) -> CfgNeighbourCell {
+    CfgNeighbourCell {
+        cell_identifier,
+        main_carrier,
+        freq_band: None,
+        freq_offset_hz: None,
+        duplex_spacing_id: None,
+        reverse_operation: None,
+        reselection_types_supported,
+        synchronized: false,
+        cell_load: 0,
+        mcc: None,
+        mnc: None,
+        location_area: None,
+        max_ms_tx_power: None,
+        min_rx_access_level: None,
+        tdma_frame_offset: None,
+    }
+}
+
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.neighbour_cells = vec![
+        neighbour(1, 3, config.cell.main_carrier + 4),
+        neighbour(2, 0, config.cell.main_carrier + 8),
+    ];
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Mle, TetraEntity::Cmce],
+        vec![TetraEntity::Llc, TetraEntity::Mm, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test
+}
+
+/// MLE PDU received from an MS, preceded by the MLE protocol discriminator
+fn build_mle_pdu_msg(dltime: TdmaTime, issi: u32, write: impl FnOnce(&mut BitBuffer)) -> SapMsg {
+    let mut tl_sdu = BitBuffer::new_autoexpand(80);
+    tl_sdu.write_bits(MleProtocolDiscriminator::Mle.into_raw(), 3);
+    write(&mut tl_sdu);
+    tl_sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Mle,
+        dltime,
+        msg: SapMsgInner::TlaTlDataIndBl(TlaTlDataIndBl {
+            main_address: TetraAddress::new(issi, SsiType::Issi),
+            link_id: 1,
+            endpoint_id: 1,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(tl_sdu),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            req_handle: 0,
+        }),
+    }
+}
+
+fn build_u_prepare_msg(dltime: TdmaTime, issi: u32, cell_identifier_ca: u64) -> SapMsg {
+    let pdu = UPrepare {
+        cell_identifier_ca: Some(cell_identifier_ca),
+        sdu: None,
+    };
+    build_mle_pdu_msg(dltime, issi, |buf| pdu.to_bitbuf(buf).unwrap())
+}
+
+fn build_u_restore_msg(dltime: TdmaTime, issi: u32, old_call_id: u16, gssi: u32) -> SapMsg {
+    let u_call_restore = UCallRestore {
+        call_identifier: old_call_id,
+        request_to_transmit_send_data: false,
+        other_party_type_identifier: 1,
+        other_party_short_number_address: None,
+        other_party_ssi: Some(gssi as u64),
+        other_party_extension: None,
+        basic_service_information: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    u_call_restore.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+
+    let pdu = URestore {
+        mcc: None,
+        mnc: None,
+        la: Some(5),
+        sdu: Some(sdu),
+    };
+    build_mle_pdu_msg(dltime, issi, |buf| pdu.to_bitbuf(buf).unwrap())
+}
+
+fn start_group_call(test: &mut ComponentTest, dltime: TdmaTime) {
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: ISSI_A,
+            groups: vec![GSSI],
+            action: BrewSubscriberAction::Affiliate,
+        }),
+    });
+
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: true,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(ISSI_A, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    });
+    test.run_stack(Some(1));
+}
+
+/// MLE PDUs of the given type sent to LLC for the given ISSI, positioned at the PDU type,
+/// with the timeslot of their channel allocation if any
+fn extract_mle_pdus(msgs: &[SapMsg], issi: u32, pdu_type: MlePduTypeDl) -> Vec<(BitBuffer, Option<u8>)> {
+    msgs.iter()
+        .filter_map(|m| {
+            let (address, tl_sdu, chan_alloc) = match &m.msg {
+                SapMsgInner::TlaTlDataReqBl(prim) => (prim.main_address, &prim.tl_sdu, &prim.chan_alloc),
+                SapMsgInner::TlaTlUnitdataReqBl(prim) => (prim.main_address, &prim.tl_sdu, &prim.chan_alloc),
+                _ => return None,
+            };
+            let mut sdu = tl_sdu.clone();
+            if address.ssi != issi || sdu.read_bits(3) != Some(MleProtocolDiscriminator::Mle.into_raw()) {
+                return None;
+            }
+            if sdu.peek_bits(3) != Some(pdu_type.into_raw()) {
+                return None;
+            }
+            let ts = chan_alloc
+                .as_ref()
+                .and_then(|c| c.timeslots.iter().position(|&t| t).map(|i| i as u8 + 1));
+            Some((sdu, ts))
+        })
+        .collect()
+}
+
+#[test]
+fn test_u_prepare_to_neighbour() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+
+    test.submit_message(build_u_prepare_msg(dltime, ISSI_A, 1));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let new_cells = extract_mle_pdus(&msgs, ISSI_A, MlePduTypeDl::DNewCell);
+    assert_eq!(new_cells.len(), 1);
+    let d_new_cell = DNewCell::from_bitbuf(&mut new_cells[0].0.clone()).unwrap();
+    assert_eq!(
+        d_new_cell.channel_command_valid as u64,
+        ChannelCommandValid::ChangeChannelImmediately.into_raw()
+    );
+
+    // Neighbour without announced reselection
+    test.submit_message(build_u_prepare_msg(dltime, ISSI_A, 2));
+    test.run_stack(Some(1));
+    let fails = extract_mle_pdus(&test.dump_sinks(), ISSI_A, MlePduTypeDl::DPrepareFail);
+    assert_eq!(fails.len(), 1);
+    let d_prepare_fail = DPrepareFail::from_bitbuf(&mut fails[0].0.clone()).unwrap();
+    assert_eq!(
+        d_prepare_fail.fail_cause as u64,
+        FailCause::CellReselectionTypeNotSupported.into_raw()
+    );
+
+    // Cell we don't know
+    test.submit_message(build_u_prepare_msg(dltime, ISSI_A, 9));
+    test.run_stack(Some(1));
+    let fails = extract_mle_pdus(&test.dump_sinks(), ISSI_A, MlePduTypeDl::DPrepareFail);
+    assert_eq!(fails.len(), 1);
+}
+
+#[test]
+fn test_u_restore_group_call() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    start_group_call(&mut test, dltime);
+    test.dump_sinks();
+
+    // ISSI B was in a call to the same group on the old cell, under another call identifier
+    test.submit_message(build_u_restore_msg(dltime, ISSI_B, 1234, GSSI));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let acks = extract_mle_pdus(&msgs, ISSI_B, MlePduTypeDl::DRestoreAck);
+    assert_eq!(acks.len(), 1);
+    let (mut sdu, ts) = acks[0].clone();
+    assert_eq!(ts, Some(2));
+    let d_restore_ack = DRestoreAck::from_bitbuf(&mut sdu).unwrap();
+    let d_call_restore = DCallRestore::from_bitbuf(&mut d_restore_ack.sdu.unwrap()).unwrap();
+    assert_eq!(d_call_restore.call_identifier, 1234);
+    assert!(d_call_restore.new_call_identifier.is_some());
+    assert_ne!(d_call_restore.new_call_identifier, Some(1234));
+}
+
+#[test]
+fn test_u_restore_unknown_call() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    start_group_call(&mut test, dltime);
+    test.dump_sinks();
+
+    test.submit_message(build_u_restore_msg(dltime, ISSI_B, 1234, GSSI + 1));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(extract_mle_pdus(&msgs, ISSI_B, MlePduTypeDl::DRestoreAck).is_empty());
+    let fails = extract_mle_pdus(&msgs, ISSI_B, MlePduTypeDl::DRestoreFail);
+    assert_eq!(fails.len(), 1);
+    DRestoreFail::from_bitbuf(&mut fails[0].0.clone()).unwrap();
+}
@@ -0,0 +1,311 @@
+mod common;
+
+use std::collections::HashMap;
+
+use tetra_config::bluestation::{AieKeyClass, CfgSecurity, SecurityClass, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::mm::components::taa1::{Taa1, TestTaa1};
+use tetra_pdus::mm::enums::location_update_reject_cause::LocationUpdateRejectCause;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::fields::ciphering_parameters::CipheringParameters;
+use tetra_pdus::mm::pdus::d_authentication_demand::DAuthenticationDemand;
+use tetra_pdus::mm::pdus::d_authentication_response::DAuthenticationResponse;
+use tetra_pdus::mm::pdus::d_authentication_result::DAuthenticationResult;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_authentication_demand::UAuthenticationDemand;
+use tetra_pdus::mm::pdus::u_authentication_response::UAuthenticationResponse;
+use tetra_pdus::mm::pdus::u_authentication_result::UAuthenticationResult;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 2040814;
+const K: u128 = 0x000102030405060708090a0b0c0d0e0f;
+
+fn setup(authentication_required: bool) -> ComponentTest {
+    setup_with_class(authentication_required, SecurityClass::Class1)
+}
+
+fn setup_with_class(authentication_required: bool, security_class: SecurityClass) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.aie_service = security_class != SecurityClass::Class1;
+    config.security = Some(CfgSecurity {
+        authentication_required,
+        keys: HashMap::from([(ISSI, K)]),
+        security_class,
+        ..Default::default()
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    test
+}
+
+fn build_ind(issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(issi, SsiType::Issi),
+        }),
+    }
+}
+
+fn build_location_update_demand(issi: u32) -> SapMsg {
+    build_ciphered_location_update_demand(issi, None)
+}
+
+fn build_ciphered_location_update_demand(issi: u32, ciphering: Option<CipheringParameters>) -> SapMsg {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: ciphering.is_some(),
+        ciphering_parameters: ciphering.map(CipheringParameters::into_raw),
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_ind(issi, sdu)
+}
+
+fn build_auth_response(res1: u32, rand2: Option<u128>) -> SapMsg {
+    let pdu = UAuthenticationResponse {
+        response_value: res1,
+        mutual_authentication_flag: rand2.is_some(),
+        random_challenge: rand2,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(128);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_ind(ISSI, sdu)
+}
+
+/// Returns the MM PDUs sent to the MLE sink, for parsing
+fn collect_sdus(msgs: Vec<SapMsg>) -> Vec<BitBuffer> {
+    msgs.into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(prim.sdu),
+            _ => None,
+        })
+        .collect()
+}
+
+fn pdu_type(sdu: &BitBuffer) -> MmPduTypeDl {
+    MmPduTypeDl::try_from(sdu.peek_bits(4).unwrap()).unwrap()
+}
+
+/// Submits a location update and returns the D-AUTHENTICATION DEMAND it provokes
+fn start_registration(test: &mut ComponentTest) -> DAuthenticationDemand {
+    test.submit_message(build_location_update_demand(ISSI));
+    test.run_stack(Some(1));
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    DAuthenticationDemand::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION DEMAND")
+}
+
+#[test]
+fn test_authentication_success() {
+    let mut test = setup(true);
+    let demand = start_registration(&mut test);
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI));
+
+    let taa1 = TestTaa1;
+    let (res1, _) = taa1.ta12(taa1.ta11(K, demand.random_seed), demand.random_challenge);
+    test.submit_message(build_auth_response(res1, None));
+    test.run_stack(Some(1));
+
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 2);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION RESULT");
+    assert!(result.authentication_result);
+    assert!(!result.mutual_authentication_flag);
+    DLocationUpdateAccept::from_bitbuf(&mut sdus[1]).expect("valid D-LOCATION UPDATE ACCEPT");
+    assert!(test.config.state_read().subscribers.is_registered(ISSI));
+}
+
+#[test]
+fn test_mutual_authentication() {
+    let mut test = setup(true);
+    let demand = start_registration(&mut test);
+
+    let taa1 = TestTaa1;
+    let rand2 = 0x0123456789abcdef0123;
+    let (res1, _) = taa1.ta12(taa1.ta11(K, demand.random_seed), demand.random_challenge);
+    test.submit_message(build_auth_response(res1, Some(rand2)));
+    test.run_stack(Some(1));
+
+    // SwMI proves to hold K as well, registration waits for the MS to confirm
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION RESULT");
+    assert!(result.authentication_result);
+    let (xres2, _) = taa1.ta22(taa1.ta21(K, demand.random_seed), rand2);
+    assert_eq!(result.response_value, Some(xres2));
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI));
+
+    let pdu = UAuthenticationResult {
+        authentication_result: true,
+        mutual_authentication_flag: false,
+        response_value: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ind(ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateAccept);
+    assert!(test.config.state_read().subscribers.is_registered(ISSI));
+}
+
+#[test]
+fn test_authentication_wrong_response() {
+    let mut test = setup(true);
+    let demand = start_registration(&mut test);
+
+    let taa1 = TestTaa1;
+    let (res1, _) = taa1.ta12(taa1.ta11(K ^ 1, demand.random_seed), demand.random_challenge);
+    test.submit_message(build_auth_response(res1, None));
+    test.run_stack(Some(1));
+
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 2);
+    let result = DAuthenticationResult::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION RESULT");
+    assert!(!result.authentication_result);
+    let reject = DLocationUpdateReject::from_bitbuf(&mut sdus[1]).expect("valid D-LOCATION UPDATE REJECT");
+    assert_eq!(
+        reject.reject_cause as u64,
+        LocationUpdateRejectCause::AuthenticationFailure.into_raw()
+    );
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI));
+}
+
+#[test]
+fn test_authentication_timeout() {
+    let mut test = setup(true);
+    start_registration(&mut test);
+
+    // The MS never answers the challenge
+    test.run_stack(Some(4 * 18 * 9));
+    assert!(collect_sdus(test.dump_sinks()).is_empty());
+    test.run_stack(Some(4 * 18 * 2));
+
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let reject = DLocationUpdateReject::from_bitbuf(&mut sdus[0]).expect("valid D-LOCATION UPDATE REJECT");
+    assert_eq!(
+        reject.reject_cause as u64,
+        LocationUpdateRejectCause::AuthenticationFailure.into_raw()
+    );
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI));
+
+    // A late response finds no authentication to complete
+    test.submit_message(build_auth_response(0, None));
+    test.run_stack(Some(1));
+    assert!(collect_sdus(test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_unprovisioned_issi() {
+    // Rejected if authentication is required
+    let mut test = setup(true);
+    test.submit_message(build_location_update_demand(ISSI + 1));
+    test.run_stack(Some(1));
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let reject = DLocationUpdateReject::from_bitbuf(&mut sdus[0]).expect("valid D-LOCATION UPDATE REJECT");
+    assert_eq!(reject.reject_cause as u64, LocationUpdateRejectCause::ItsiUnknown.into_raw());
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI + 1));
+
+    // Otherwise registered without authentication
+    let mut test = setup(false);
+    test.submit_message(build_location_update_demand(ISSI + 1));
+    test.run_stack(Some(1));
+    let sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(pdu_type(&sdus[0]), MmPduTypeDl::DLocationUpdateAccept);
+    assert!(test.config.state_read().subscribers.is_registered(ISSI + 1));
+}
+
+#[test]
+fn test_ms_initiated_authentication() {
+    let mut test = setup(true);
+    let rand2 = 0xfedcba9876543210fedc;
+    let pdu = UAuthenticationDemand {
+        random_challenge: rand2,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(96);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ind(ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let response = DAuthenticationResponse::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION RESPONSE");
+    let taa1 = TestTaa1;
+    let (xres2, _) = taa1.ta22(taa1.ta21(K, response.random_seed), rand2);
+    assert_eq!(response.response_value, xres2);
+    assert!(!response.mutual_authentication_flag);
+}
+
+#[test]
+fn test_class3_ciphering() {
+    let mut test = setup_with_class(true, SecurityClass::Class3);
+
+    // Registration without ciphering is refused
+    test.submit_message(build_location_update_demand(ISSI));
+    test.run_stack(Some(1));
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 1);
+    let reject = DLocationUpdateReject::from_bitbuf(&mut sdus[0]).expect("valid D-LOCATION UPDATE REJECT");
+    assert_eq!(reject.reject_cause as u64, LocationUpdateRejectCause::CipheringRequired.into_raw());
+
+    // With class 3 ciphering, the DCK from authentication becomes the cipher key
+    let params = CipheringParameters {
+        ksg_number: 0,
+        security_class_3: true,
+        sck_number: 1,
+    };
+    test.submit_message(build_ciphered_location_update_demand(ISSI, Some(params)));
+    test.run_stack(Some(1));
+    let mut sdus = collect_sdus(test.dump_sinks());
+    let demand = DAuthenticationDemand::from_bitbuf(&mut sdus[0]).expect("valid D-AUTHENTICATION DEMAND");
+
+    let taa1 = TestTaa1;
+    let (res1, dck1) = taa1.ta12(taa1.ta11(K, demand.random_seed), demand.random_challenge);
+    test.submit_message(build_auth_response(res1, None));
+    test.run_stack(Some(1));
+
+    let mut sdus = collect_sdus(test.dump_sinks());
+    assert_eq!(sdus.len(), 2);
+    let accept = DLocationUpdateAccept::from_bitbuf(&mut sdus[1]).expect("valid D-LOCATION UPDATE ACCEPT");
+    let security_downlink = accept.security_downlink.expect("ciphering parameters in accept");
+    assert_eq!(CipheringParameters::from_raw(security_downlink.data), params);
+    let aie = test.config.state_read().subscribers.get_subscriber(ISSI).and_then(|s| s.aie);
+    assert_eq!(aie, Some(AieKeyClass::Dck(taa1.tb4(dck1, 0))));
+}
@@ -0,0 +1,245 @@
+mod common;
+
+use std::time::Duration;
+
+use tetra_config::bluestation::{CfgRegistry, DisableKind, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::location_update_reject_cause::LocationUpdateRejectCause;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::pdus::d_disable::DDisable;
+use tetra_pdus::mm::pdus::d_enable::DEnable;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::brew::BrewSubscriberAction;
+use tetra_saps::control::mm::{DisableTarget, MmControl};
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 1000001;
+
+fn setup(disabled_path: &str) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.registry = Some(CfgRegistry {
+        journal_path: format!("{}.journal", disabled_path),
+        ttl: Duration::from_secs(3600),
+        disabled_path: Some(disabled_path.to_string()),
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn register(test: &mut ComponentTest) {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(ISSI, SsiType::Issi),
+        }),
+    });
+    test.run_stack(Some(1));
+}
+
+fn control(test: &mut ComponentTest, control: MmControl) {
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mgmt,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::MmControl(control),
+    });
+    test.run_stack(Some(1));
+}
+
+/// MM PDUs sent towards the MLE, and subscriber updates sent to CMCE
+fn take_output(test: &mut ComponentTest) -> (Vec<BitBuffer>, Vec<BrewSubscriberAction>) {
+    let mut sdus = Vec::new();
+    let mut updates = Vec::new();
+    for msg in test.dump_sinks() {
+        match msg.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => sdus.push(prim.sdu),
+            SapMsgInner::MmSubscriberUpdate(update) => updates.push(update.action),
+            _ => {}
+        }
+    }
+    (sdus, updates)
+}
+
+fn expect_disable(sdu: &mut BitBuffer, permanent: bool) {
+    let pdu = DDisable::from_bitbuf(sdu).expect("Failed parsing D-DISABLE");
+    assert!(pdu.intent_confirm);
+    assert!(pdu.subscription_disable);
+    assert!(!pdu.equipment_disable);
+    assert_eq!(pdu.disabling_type, permanent);
+    assert_eq!(pdu.ssi, Some(ISSI as u64));
+}
+
+#[test]
+fn test_disable_and_enable() {
+    let path = std::env::temp_dir().join(format!("bluestation-disable-{}.list", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    let _ = std::fs::remove_file(&path);
+    let mut test = setup(&path);
+    register(&mut test);
+    test.dump_sinks();
+
+    // The MS is told to disable itself and deregistered
+    control(
+        &mut test,
+        MmControl::Disable {
+            issi: ISSI,
+            permanent: false,
+            target: DisableTarget::Subscription,
+        },
+    );
+    let (mut sdus, updates) = take_output(&mut test);
+    assert_eq!(sdus.len(), 1);
+    expect_disable(&mut sdus[0], false);
+    assert_eq!(updates, vec![BrewSubscriberAction::Deregister]);
+    let state = test.config.state_read();
+    assert!(!state.subscribers.is_registered(ISSI));
+    assert_eq!(state.subscribers.disabled(ISSI), Some(DisableKind::Temporary));
+    drop(state);
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().last(), Some("1000001 temporary"));
+
+    // Registering again is refused, and D-DISABLE repeated
+    register(&mut test);
+    let (mut sdus, _) = take_output(&mut test);
+    assert_eq!(sdus.len(), 2);
+    expect_disable(&mut sdus[0], false);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdus[1]).expect("Failed parsing D-LOCATION UPDATE REJECT");
+    assert_eq!(pdu.reject_cause, LocationUpdateRejectCause::IllegalMs.into_raw() as u8);
+    assert!(!test.config.state_read().subscribers.is_registered(ISSI));
+
+    // The disabled list is reloaded at startup
+    let mut restarted = setup(&path);
+    assert_eq!(
+        restarted.config.state_read().subscribers.disabled(ISSI),
+        Some(DisableKind::Temporary)
+    );
+
+    // Once enabled, the MS may register again
+    control(
+        &mut restarted,
+        MmControl::Enable {
+            issi: ISSI,
+            target: DisableTarget::Subscription,
+        },
+    );
+    let (mut sdus, _) = take_output(&mut restarted);
+    assert_eq!(sdus.len(), 1);
+    let pdu = DEnable::from_bitbuf(&mut sdus[0]).expect("Failed parsing D-ENABLE");
+    assert!(pdu.subscription_enable);
+    assert_eq!(pdu.ssi, Some(ISSI as u64));
+    register(&mut restarted);
+    let (sdus, _) = take_output(&mut restarted);
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(sdus[0].peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    assert!(restarted.config.state_read().subscribers.is_registered(ISSI));
+    assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(format!("{}.journal", path));
+}
+
+#[test]
+fn test_disable_equipment() {
+    const TEI: u64 = 0x0123_4567_89ab_cde;
+    let path = std::env::temp_dir().join(format!("bluestation-disable-tei-{}.list", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    let _ = std::fs::remove_file(&path);
+    let mut test = setup(&path);
+    register(&mut test);
+    test.dump_sinks();
+
+    // Only the terminal is disabled, the subscription stays usable
+    control(
+        &mut test,
+        MmControl::Disable {
+            issi: ISSI,
+            permanent: true,
+            target: DisableTarget::Equipment { tei: TEI },
+        },
+    );
+    let (mut sdus, updates) = take_output(&mut test);
+    assert_eq!(sdus.len(), 1);
+    let pdu = DDisable::from_bitbuf(&mut sdus[0]).expect("Failed parsing D-DISABLE");
+    assert!(pdu.disabling_type);
+    assert!(pdu.equipment_disable);
+    assert_eq!(pdu.tetra_equipment_identity, Some(TEI));
+    assert!(!pdu.subscription_disable);
+    assert_eq!(pdu.ssi, None);
+    assert_eq!(updates, vec![BrewSubscriberAction::Deregister]);
+    assert_eq!(test.config.state_read().subscribers.disabled(ISSI), None);
+
+    // Both at once
+    control(
+        &mut test,
+        MmControl::Disable {
+            issi: ISSI,
+            permanent: false,
+            target: DisableTarget::Both { tei: TEI },
+        },
+    );
+    let (mut sdus, _) = take_output(&mut test);
+    let pdu = DDisable::from_bitbuf(&mut sdus[0]).expect("Failed parsing D-DISABLE");
+    assert!(pdu.equipment_disable && pdu.subscription_disable);
+    assert_eq!(pdu.tetra_equipment_identity, Some(TEI));
+    assert_eq!(pdu.ssi, Some(ISSI as u64));
+    assert_eq!(test.config.state_read().subscribers.disabled(ISSI), Some(DisableKind::Temporary));
+
+    control(
+        &mut test,
+        MmControl::Enable {
+            issi: ISSI,
+            target: DisableTarget::Equipment { tei: TEI },
+        },
+    );
+    let (mut sdus, _) = take_output(&mut test);
+    let pdu = DEnable::from_bitbuf(&mut sdus[0]).expect("Failed parsing D-ENABLE");
+    assert!(pdu.equipment_enable);
+    assert_eq!(pdu.tetra_equipment_identity, Some(TEI));
+    assert!(!pdu.subscription_enable);
+    // Enabling the equipment leaves the subscription disabled
+    assert_eq!(test.config.state_read().subscribers.disabled(ISSI), Some(DisableKind::Temporary));
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_file(format!("{}.journal", path));
+}
+
+#[test]
+#[should_panic(expected = "failed loading disabled list")]
+fn test_malformed_disabled_list_fails_startup() {
+    let path = std::env::temp_dir().join(format!("bluestation-disable-bad-{}.list", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    std::fs::write(&path, "1000001 stunned\n").unwrap();
+    setup(&path);
+}
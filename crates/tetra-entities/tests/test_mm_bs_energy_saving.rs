@@ -0,0 +1,150 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, EnergyEconomySchedule, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::energy_saving_mode::EnergySavingMode;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::status_downlink::StatusDownlink;
+use tetra_pdus::mm::fields::energy_saving_information::EnergySavingInformation;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_mm_status::DMmStatus;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 2040814;
+
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn build_mm_msg(sdu: BitBuffer, dltime: TdmaTime) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(ISSI, SsiType::Issi),
+        }),
+    }
+}
+
+fn build_location_update_demand(energy_saving_mode: Option<EnergySavingMode>, dltime: TdmaTime) -> SapMsg {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_mm_msg(sdu, dltime)
+}
+
+/// Single MM PDU sent towards the MLE
+fn take_mm_sdu(test: &mut ComponentTest) -> BitBuffer {
+    let mut sdus: Vec<BitBuffer> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(prim.sdu),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sdus.len(), 1);
+    sdus.remove(0)
+}
+
+fn energy_economy(test: &ComponentTest) -> Option<EnergyEconomySchedule> {
+    test.config
+        .state_read()
+        .subscribers
+        .get_subscriber(ISSI)
+        .and_then(|s| s.energy_economy)
+}
+
+#[test]
+fn test_location_update_energy_saving_mode() {
+    let dltime = TdmaTime { t: 1, f: 6, m: 20, h: 0 };
+    let mut test = setup(dltime);
+    test.submit_message(build_location_update_demand(Some(EnergySavingMode::Eg4), dltime));
+    test.run_stack(Some(1));
+
+    // The requested group is granted, starting with the next frame
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    assert_eq!(
+        pdu.energy_saving_information,
+        Some(EnergySavingInformation {
+            energy_saving_mode: EnergySavingMode::Eg4,
+            frame_number: Some(7),
+            multiframe_number: Some(20),
+        })
+    );
+    assert_eq!(
+        energy_economy(&test),
+        Some(EnergyEconomySchedule {
+            group: 4,
+            start_frame: 7,
+            start_multiframe: 20,
+        })
+    );
+
+    // Asking to stay alive clears the schedule, and is acknowledged as well
+    test.submit_message(build_location_update_demand(Some(EnergySavingMode::StayAlive), dltime));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    let esi = pdu.energy_saving_information.expect("Energy saving information missing");
+    assert_eq!(esi.energy_saving_mode, EnergySavingMode::StayAlive);
+    assert_eq!(energy_economy(&test), None);
+}
+
+#[test]
+fn test_change_of_energy_saving_mode_request() {
+    let dltime = TdmaTime::default();
+    let mut test = setup(dltime);
+    test.submit_message(build_location_update_demand(None, dltime));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    assert!(pdu.energy_saving_information.is_none());
+    assert_eq!(energy_economy(&test), None);
+
+    // U-MM STATUS, change of energy saving mode request for EG1, as sent by a Motorola MTH800
+    test.submit_message(build_mm_msg(BitBuffer::from_bitstr("00110000010010"), dltime));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DMmStatus::from_bitbuf(&mut sdu).expect("Failed parsing D-MM STATUS");
+    assert_eq!(pdu.status_downlink, StatusDownlink::ChangeOfEnergySavingModeResponse);
+
+    // Energy saving information followed by the O-bit
+    let mut info = BitBuffer::new(15);
+    info.write_bits(pdu.status_downlink_dependent_information.unwrap(), 15);
+    info.seek(0);
+    let esi = EnergySavingInformation::from_bitbuf(&mut info).unwrap();
+    assert_eq!(esi.energy_saving_mode, EnergySavingMode::Eg1);
+    let schedule = energy_economy(&test).expect("No wake schedule stored");
+    assert_eq!((schedule.group, Some(schedule.start_frame)), (1, esi.frame_number));
+    assert_eq!(info.read_bits(1), Some(0));
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use std::collections::HashMap;
+
+use tetra_config::bluestation::{CfgGroupAssignment, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug, multiframes};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity::DAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::brew::BrewSubscriberAction;
+use tetra_saps::control::mm::MmControl;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 1000001;
+
+fn setup(group_assignment: Option<CfgGroupAssignment>) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.group_assignment = group_assignment;
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn build_mm_msg(sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(ISSI, SsiType::Issi),
+        }),
+    }
+}
+
+fn register(test: &mut ComponentTest) {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_mm_msg(sdu));
+    test.run_stack(Some(1));
+}
+
+fn acknowledge(test: &mut ComponentTest, reject: bool) {
+    let pdu = UAttachDetachGroupIdentityAcknowledgement {
+        group_identity_acknowledgement_type: reject,
+        group_identity_uplink: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_mm_msg(sdu));
+    test.run_stack(Some(1));
+}
+
+/// MM PDUs sent towards the MLE, and subscriber updates sent to CMCE as (action, groups)
+fn take_output(test: &mut ComponentTest) -> (Vec<BitBuffer>, Vec<(BrewSubscriberAction, Vec<u32>)>) {
+    let mut sdus = Vec::new();
+    let mut updates = Vec::new();
+    for msg in test.dump_sinks() {
+        match msg.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => sdus.push(prim.sdu),
+            SapMsgInner::MmSubscriberUpdate(update) => updates.push((update.action, update.groups)),
+            _ => {}
+        }
+    }
+    (sdus, updates)
+}
+
+fn assigned_gssis(sdu: &mut BitBuffer) -> (Vec<u32>, Vec<u32>) {
+    let pdu = DAttachDetachGroupIdentity::from_bitbuf(sdu).expect("Failed parsing D-ATTACH/DETACH GROUP IDENTITY");
+    assert!(pdu.group_identity_acknowledgement_request);
+    let gids = pdu.group_identity_downlink.unwrap();
+    let attach = gids
+        .iter()
+        .filter(|g| g.group_identity_attachment.is_some())
+        .filter_map(|g| g.gssi)
+        .collect();
+    let detach = gids
+        .iter()
+        .filter(|g| g.group_identity_detachment_uplink.is_some())
+        .filter_map(|g| g.gssi)
+        .collect();
+    (attach, detach)
+}
+
+fn attached_groups(test: &ComponentTest) -> Vec<u32> {
+    let state = test.config.state_read();
+    let mut groups: Vec<u32> = state
+        .subscribers
+        .get_subscriber(ISSI)
+        .unwrap()
+        .attached_groups
+        .iter()
+        .copied()
+        .collect();
+    groups.sort_unstable();
+    groups
+}
+
+#[test]
+fn test_static_groups_on_registration() {
+    let mut test = setup(Some(CfgGroupAssignment {
+        static_gssis: vec![91],
+        subscribers: HashMap::from([(ISSI, vec![92])]),
+        dgna_authorised_issis: vec![],
+    }));
+    register(&mut test);
+
+    // D-LOCATION UPDATE ACCEPT, followed by the group assignment
+    let (mut sdus, _) = take_output(&mut test);
+    assert_eq!(sdus.len(), 2);
+    DLocationUpdateAccept::from_bitbuf(&mut sdus[0]).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    assert_eq!(assigned_gssis(&mut sdus[1]), (vec![91, 92], vec![]));
+    assert!(attached_groups(&test).is_empty());
+
+    // The registry follows once the MS acknowledges
+    acknowledge(&mut test, false);
+    let (sdus, updates) = take_output(&mut test);
+    assert!(sdus.is_empty());
+    assert_eq!(updates, vec![(BrewSubscriberAction::Affiliate, vec![91, 92])]);
+    assert_eq!(attached_groups(&test), vec![91, 92]);
+}
+
+#[test]
+fn test_group_assignment_retries() {
+    let mut test = setup(None);
+    register(&mut test);
+    test.dump_sinks();
+
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mgmt,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::MmControl(MmControl::AssignGroups {
+            issi: ISSI,
+            attach: vec![93],
+            detach: Vec::new(),
+        }),
+    });
+    test.run_stack(Some(1));
+    let (mut sdus, _) = take_output(&mut test);
+    assert_eq!(sdus.len(), 1);
+    assert_eq!(assigned_gssis(&mut sdus[0]), (vec![93], vec![]));
+
+    // Repeated twice without acknowledgement, then abandoned
+    for _ in 0..2 {
+        test.run_stack(Some(multiframes!(5) as usize + 4));
+        let (mut sdus, _) = take_output(&mut test);
+        assert_eq!(sdus.len(), 1);
+        assert_eq!(assigned_gssis(&mut sdus[0]), (vec![93], vec![]));
+    }
+    test.run_stack(Some(multiframes!(5) as usize + 4));
+    let (sdus, _) = take_output(&mut test);
+    assert!(sdus.is_empty());
+
+    // A late acknowledgement is ignored
+    acknowledge(&mut test, false);
+    assert!(attached_groups(&test).is_empty());
+
+    // A rejected assignment leaves the registry untouched
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mgmt,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::MmControl(MmControl::AssignGroups {
+            issi: ISSI,
+            attach: vec![93],
+            detach: Vec::new(),
+        }),
+    });
+    test.run_stack(Some(1));
+    test.dump_sinks();
+    acknowledge(&mut test, true);
+    let (_, updates) = take_output(&mut test);
+    assert!(updates.is_empty());
+    assert!(attached_groups(&test).is_empty());
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use std::collections::HashMap;
+
+use tetra_config::bluestation::{CfgAccessPolicy, CfgSubscriberPolicy, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::location_update_reject_cause::LocationUpdateRejectCause;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+/// Allowed, restricted to group 91 and subscriber class 2
+const ISSI_RESTRICTED: u32 = 1000001;
+/// Allowed, subscriber class the cell does not serve
+const ISSI_WRONG_CLASS: u32 = 1000002;
+/// Outside the allowed ranges
+const ISSI_UNKNOWN: u32 = 2040814;
+/// Within the group range
+const ISSI_GROUP: u32 = 92;
+
+fn setup() -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.subscriber_class = 0x0003;
+    config.access_policy = Some(CfgAccessPolicy {
+        allowed_issis: [ISSI_GROUP].into(),
+        allowed_issi_ranges: vec![1000000..=1000999],
+        group_ranges: vec![1..=9999],
+        max_groups_per_ms: Some(2),
+        subscribers: HashMap::from([
+            (
+                ISSI_RESTRICTED,
+                CfgSubscriberPolicy {
+                    allowed_gssis: Some([91].into()),
+                    subscriber_class: Some(0x0002),
+                },
+            ),
+            (
+                ISSI_WRONG_CLASS,
+                CfgSubscriberPolicy {
+                    allowed_gssis: None,
+                    subscriber_class: Some(0x0004),
+                },
+            ),
+        ]),
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn build_mm_msg(issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(issi, SsiType::Issi),
+        }),
+    }
+}
+
+fn build_location_update_demand(issi: u32) -> SapMsg {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_mm_msg(issi, sdu)
+}
+
+fn build_attach(issi: u32, gssis: &[u32]) -> SapMsg {
+    let pdu = UAttachDetachGroupIdentity {
+        group_identity_report: false,
+        group_identity_attach_detach_mode: false,
+        group_report_response: None,
+        group_identity_uplink: Some(
+            gssis
+                .iter()
+                .map(|&gssi| GroupIdentityUplink {
+                    class_of_usage: Some(4),
+                    group_identity_detachment_uplink: None,
+                    gssi: Some(gssi),
+                    address_extension: None,
+                    vgssi: None,
+                })
+                .collect(),
+        ),
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_mm_msg(issi, sdu)
+}
+
+/// Single MM PDU sent towards the MLE
+fn take_mm_sdu(test: &mut ComponentTest) -> BitBuffer {
+    let mut sdus: Vec<BitBuffer> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(prim.sdu),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(sdus.len(), 1);
+    sdus.remove(0)
+}
+
+fn expect_reject(test: &mut ComponentTest, issi: u32, cause: LocationUpdateRejectCause) {
+    test.submit_message(build_location_update_demand(issi));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(test);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE REJECT");
+    assert_eq!(pdu.reject_cause, cause.into_raw() as u8);
+    assert!(test.config.state_read().subscribers.get_subscriber(issi).is_none());
+}
+
+#[test]
+fn test_registration_rejected_by_policy() {
+    let mut test = setup();
+    expect_reject(&mut test, ISSI_UNKNOWN, LocationUpdateRejectCause::ItsiUnknown);
+    expect_reject(&mut test, ISSI_GROUP, LocationUpdateRejectCause::IllegalMs);
+    expect_reject(&mut test, ISSI_WRONG_CLASS, LocationUpdateRejectCause::LaNotAllowed);
+
+    // An unknown MS cannot register through a group attachment either
+    test.submit_message(build_attach(ISSI_UNKNOWN, &[91]));
+    test.run_stack(Some(1));
+    assert!(test.config.state_read().subscribers.get_subscriber(ISSI_UNKNOWN).is_none());
+}
+
+#[test]
+fn test_group_attach_restricted_by_policy() {
+    let mut test = setup();
+    test.submit_message(build_location_update_demand(ISSI_RESTRICTED));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    assert_eq!(pdu.subscriber_class, Some(0x0002));
+
+    // Group 92 is not allowed for this MS and 10000 is no group SSI, so only 91 is attached
+    test.submit_message(build_attach(ISSI_RESTRICTED, &[91, 92, 10000]));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut sdu).expect("Failed parsing D-ATTACH/DETACH GROUP IDENTITY ACK");
+    assert_eq!(pdu.group_identity_accept_reject, 1);
+    let gssis: Vec<Option<u32>> = pdu.group_identity_downlink.unwrap().iter().map(|gid| gid.gssi).collect();
+    assert_eq!(gssis, vec![Some(91)]);
+    let state = test.config.state_read();
+    let subscriber = state.subscribers.get_subscriber(ISSI_RESTRICTED).unwrap();
+    assert_eq!(subscriber.attached_groups.iter().copied().collect::<Vec<u32>>(), vec![91]);
+}
+
+#[test]
+fn test_max_groups_per_ms() {
+    let mut test = setup();
+    let issi = 1000003;
+    test.submit_message(build_location_update_demand(issi));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).expect("Failed parsing D-LOCATION UPDATE ACCEPT");
+    assert_eq!(pdu.subscriber_class, None);
+
+    test.submit_message(build_attach(issi, &[91, 92, 93]));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut sdu).expect("Failed parsing D-ATTACH/DETACH GROUP IDENTITY ACK");
+    assert_eq!(pdu.group_identity_accept_reject, 1);
+    assert_eq!(pdu.group_identity_downlink.unwrap().len(), 2);
+
+    // Re-attaching a group the MS is already attached to is fine
+    test.submit_message(build_attach(issi, &[92]));
+    test.run_stack(Some(1));
+    let mut sdu = take_mm_sdu(&mut test);
+    let pdu = DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut sdu).expect("Failed parsing D-ATTACH/DETACH GROUP IDENTITY ACK");
+    assert_eq!(pdu.group_identity_accept_reject, 0);
+}
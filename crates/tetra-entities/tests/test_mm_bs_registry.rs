@@ -0,0 +1,186 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tetra_config::bluestation::{CfgRegistry, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug, multiframes};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::brew::BrewSubscriberAction;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const ISSI_A: u32 = 1000001;
+const ISSI_B: u32 = 1000002;
+const GSSI: u32 = 91;
+
+fn journal_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bluestation-registry-{}-{}.journal", std::process::id(), name))
+}
+
+fn setup(name: &str) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.registry = Some(CfgRegistry {
+        journal_path: journal_path(name).to_string_lossy().into_owned(),
+        ttl: Duration::from_secs(3600),
+        disabled_path: None,
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    test
+}
+
+fn build_location_update_demand(issi: u32, location_update_type: LocationUpdateType) -> SapMsg {
+    let pdu = ULocationUpdateDemand {
+        location_update_type,
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: None,
+        address_extension: None,
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(issi, SsiType::Issi),
+        }),
+    }
+}
+
+/// Subscriber updates sent to CMCE, as (ISSI, action, groups)
+fn subscriber_updates(msgs: &[SapMsg]) -> Vec<(u32, BrewSubscriberAction, Vec<u32>)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::MmSubscriberUpdate(update) if m.dest == TetraEntity::Cmce => {
+                Some((update.issi, update.action, update.groups.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn mm_pdu_types(msgs: &[SapMsg]) -> Vec<MmPduTypeDl> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(MmPduTypeDl::try_from(prim.sdu.peek_bits(4).unwrap()).unwrap()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_registry_survives_restart() {
+    let name = "restart";
+    let _ = std::fs::remove_file(journal_path(name));
+
+    // First run: two terminals register, one of them is affiliated to a group
+    let mut test = setup(name);
+    test.run_stack(Some(1));
+    assert!(subscriber_updates(&test.dump_sinks()).is_empty());
+    for issi in [ISSI_A, ISSI_B] {
+        test.submit_message(build_location_update_demand(issi, LocationUpdateType::ItsiAttach));
+    }
+    test.run_stack(Some(1));
+    test.config.state_write().subscribers.affiliate(ISSI_A, GSSI);
+    test.run_stack(Some(multiframes!(1) as usize));
+    drop(test);
+
+    // Second run: registrations are restored and announced without the terminals registering again
+    let mut test = setup(name);
+    test.run_stack(Some(1));
+    let mut updates = subscriber_updates(&test.dump_sinks());
+    updates.sort_by_key(|(issi, action, _)| (*issi, *action as u8));
+    assert_eq!(
+        updates,
+        vec![
+            (ISSI_A, BrewSubscriberAction::Register, vec![]),
+            (ISSI_A, BrewSubscriberAction::Affiliate, vec![GSSI]),
+            (ISSI_B, BrewSubscriberAction::Register, vec![]),
+        ]
+    );
+    {
+        let state = test.config.state_read();
+        assert!(state.subscribers.is_registered(ISSI_A));
+        assert!(state.subscribers.is_registered(ISSI_B));
+        assert!(state.subscribers.has_group_members(GSSI));
+    }
+
+    // A restored terminal updating its location is known, so no D-LOCATION UPDATE COMMAND is needed
+    test.submit_message(build_location_update_demand(ISSI_B, LocationUpdateType::RoamingLocationUpdating));
+    test.run_stack(Some(1));
+    assert_eq!(mm_pdu_types(&test.dump_sinks()), vec![MmPduTypeDl::DLocationUpdateAccept]);
+    drop(test);
+    let _ = std::fs::remove_file(journal_path(name));
+}
+
+#[test]
+fn test_registry_expired_entries() {
+    let name = "expired";
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let journal = format!("{} {} clear {}\n{} {} clear -\n", ISSI_A, now - 60, GSSI, ISSI_B, now - 7200);
+    std::fs::write(journal_path(name), journal).unwrap();
+
+    let mut test = setup(name);
+    test.run_stack(Some(1));
+    assert_eq!(
+        subscriber_updates(&test.dump_sinks()),
+        vec![
+            (ISSI_A, BrewSubscriberAction::Register, vec![]),
+            (ISSI_A, BrewSubscriberAction::Affiliate, vec![GSSI]),
+        ]
+    );
+    {
+        let state = test.config.state_read();
+        assert!(state.subscribers.is_registered(ISSI_A));
+        assert!(!state.subscribers.is_registered(ISSI_B));
+    }
+    drop(test);
+    let _ = std::fs::remove_file(journal_path(name));
+}
+
+#[test]
+fn test_registry_corrupt_journal_kept() {
+    let name = "corrupt";
+    let mut bad_path = journal_path(name).into_os_string();
+    bad_path.push(".bad");
+    let _ = std::fs::remove_file(&bad_path);
+    std::fs::write(journal_path(name), "not a journal\n").unwrap();
+
+    // Nothing is restored, and the unreadable journal is kept for inspection instead of being overwritten
+    let mut test = setup(name);
+    test.run_stack(Some(1));
+    assert!(subscriber_updates(&test.dump_sinks()).is_empty());
+    assert_eq!(std::fs::read_to_string(&bad_path).unwrap(), "not a journal\n");
+
+    test.submit_message(build_location_update_demand(ISSI_A, LocationUpdateType::ItsiAttach));
+    test.run_stack(Some(1));
+    drop(test);
+    let journal = std::fs::read_to_string(journal_path(name)).unwrap();
+    assert!(journal.contains(&ISSI_A.to_string()));
+    assert_eq!(std::fs::read_to_string(&bad_path).unwrap(), "not a journal\n");
+
+    let _ = std::fs::remove_file(journal_path(name));
+    let _ = std::fs::remove_file(bad_path);
+}
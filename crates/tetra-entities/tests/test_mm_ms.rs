@@ -0,0 +1,115 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::lmm::{LmmMleActivateConf, LmmMleUnitdataInd};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+fn build_activate_conf() -> SapMsg {
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleActivateConf(LmmMleActivateConf {
+            registration_required: true,
+            la: 1,
+            cell_type: 0,
+        }),
+    }
+}
+
+fn build_accept(issi: u32) -> SapMsg {
+    let pdu = DLocationUpdateAccept {
+        location_update_accept_type: LocationUpdateType::ItsiAttach,
+        ssi: Some(issi as u64),
+        address_extension: None,
+        subscriber_class: None,
+        energy_saving_information: None,
+        scch_information_and_distribution_on_18th_frame: None,
+        new_registered_area: None,
+        security_downlink: None,
+        group_identity_location_accept: None,
+        default_group_attachment_lifetime: None,
+        authentication_downlink: None,
+        group_identity_security_related_information: None,
+        cell_type_control: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress::new(issi, SsiType::Issi),
+        }),
+    }
+}
+
+/// Parses all U-LOCATION UPDATE DEMANDs sent to the MLE sink
+fn collect_demands(msgs: Vec<SapMsg>) -> Vec<(TetraAddress, ULocationUpdateDemand)> {
+    msgs.into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::LmmMleUnitdataReq(mut prim) => {
+                let pdu = ULocationUpdateDemand::from_bitbuf(&mut prim.sdu).expect("valid U-LOCATION UPDATE DEMAND");
+                Some((prim.address, pdu))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_registration_on_camping() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    let ms = test.config.config().ms.clone().unwrap();
+
+    test.submit_message(build_activate_conf());
+    test.run_stack(Some(1));
+
+    let demands = collect_demands(test.dump_sinks());
+    assert_eq!(demands.len(), 1);
+    let (address, pdu) = &demands[0];
+    assert_eq!(address.ssi, ms.issi);
+    assert_eq!(pdu.location_update_type, LocationUpdateType::ItsiAttach);
+    let groups: Vec<u32> = pdu
+        .group_identity_location_demand
+        .as_ref()
+        .and_then(|gild| gild.group_identity_uplink.as_ref())
+        .map(|giu| giu.iter().filter_map(|g| g.gssi).collect())
+        .unwrap_or_default();
+    assert_eq!(groups, ms.groups);
+}
+
+#[test]
+fn test_registration_retry() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    let issi = test.config.config().ms.as_ref().unwrap().issi;
+
+    // Without a response, registration is repeated after a timeout
+    test.submit_message(build_activate_conf());
+    test.run_stack(Some(4 * 18 * 30 + 2));
+    assert_eq!(collect_demands(test.dump_sinks()).len(), 2);
+
+    // Once accepted, no further attempts are made
+    test.submit_message(build_accept(issi));
+    test.run_stack(Some(2 * 4 * 18 * 30));
+    assert_eq!(collect_demands(test.dump_sinks()).len(), 0);
+}
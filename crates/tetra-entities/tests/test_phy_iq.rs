@@ -0,0 +1,199 @@
+mod common;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tetra_config::bluestation::{PhyBackend, StackMode};
+use tetra_core::debug;
+use tetra_core::freqs::FreqInfo;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_entities::mm::mm_ms::MmMs;
+use tetra_entities::phy::components::file_io::RxTxDevFile;
+use tetra_entities::phy::components::loopback_io::LoopbackIo;
+use tetra_entities::phy::components::soapy_dev::RxTxDevSdr;
+use tetra_entities::phy::{phy_bs::PhyBs, phy_mon::PhyMon, phy_ms::PhyMs};
+use tetra_saps::sapmsg::SapMsgInner;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+use crate::common::ComponentTest;
+
+/// Upper bound on the time it takes an MS to sync, camp and register.
+const MAX_REGISTRATION_SLOTS: usize = 20 * 4 * 18;
+
+/// Runs a BS and an MS stack, each with its full modem DSP chain, over an in-process radio channel
+/// and checks that the MS registers to the BS.
+#[test]
+fn test_bs_ms_loopback_registration() {
+    debug::setup_logging_verbose();
+
+    let cell = ComponentTest::get_default_test_config(StackMode::Bs).cell;
+    let freq_info = FreqInfo::from_components(
+        cell.freq_band,
+        cell.main_carrier,
+        cell.freq_offset_hz,
+        cell.reverse_operation,
+        cell.duplex_spacing_id,
+        cell.custom_duplex_spacing,
+    )
+    .unwrap();
+    let (dl_freq, ul_freq) = freq_info.get_freqs();
+    let (dl_freq, ul_freq) = (dl_freq as f64, ul_freq as f64);
+    let (bs_io, ms_io) = LoopbackIo::pair(512e3, dl_freq, ul_freq);
+
+    let running = Arc::new(AtomicBool::new(true));
+    let bs_running = running.clone();
+    let bs_thread = std::thread::spawn(move || {
+        let mut test = ComponentTest::new(StackMode::Bs, None);
+        let rxtxdev = RxTxDevSdr::with_io(StackMode::Bs, bs_io, dl_freq, ul_freq);
+        test.register_entity(PhyBs::new(test.get_shared_config(), rxtxdev));
+        test.populate_entities(
+            vec![
+                TetraEntity::Lmac,
+                TetraEntity::Umac,
+                TetraEntity::Llc,
+                TetraEntity::Mle,
+                TetraEntity::Mm,
+                TetraEntity::Cmce,
+            ],
+            vec![],
+        );
+        test.router.run_stack(None, Some(bs_running));
+        test.get_shared_config()
+    });
+
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    let issi = test.config.config().ms.as_ref().unwrap().issi;
+    let rxtxdev = RxTxDevSdr::with_io(StackMode::Ms, ms_io, dl_freq, ul_freq);
+    test.register_entity(PhyMs::new(test.get_shared_config(), rxtxdev));
+    test.populate_entities(
+        vec![
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            TetraEntity::Mle,
+            TetraEntity::Mm,
+            TetraEntity::Cmce,
+        ],
+        vec![],
+    );
+
+    let mut registered = false;
+    for _ in 0..MAX_REGISTRATION_SLOTS {
+        test.run_stack(Some(1));
+        let mm = test.router.get_entity(TetraEntity::Mm).unwrap();
+        if mm.as_any_mut().downcast_mut::<MmMs>().unwrap().is_registered() {
+            registered = true;
+            break;
+        }
+    }
+
+    // Stopping the MS makes the BS receive silence until it notices it should stop too
+    running.store(false, Ordering::Relaxed);
+    drop(test);
+    let bs_config = bs_thread.join().expect("BS stack panicked");
+
+    assert!(registered, "MS did not register within {} slots", MAX_REGISTRATION_SLOTS);
+    assert!(bs_config.state_read().subscribers.is_registered(issi));
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    let mut path = std::env::temp_dir();
+    path.push(format!(
+        "phy_iq_test_{}_{}.cf32",
+        name,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    path
+}
+
+/// Records the downlink of a BS to a file and decodes it with a monitor reading that file
+#[test]
+fn test_file_backend_bs_to_mon() {
+    debug::setup_logging_verbose();
+
+    let path = temp_path("dl");
+    let path_str = path.to_str().unwrap().to_string();
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.phy_io.backend = PhyBackend::File;
+    config.phy_io.dl_tx_file = Some(path_str.clone());
+    let mut test = ComponentTest::from_config(config, None);
+    let rxtxdev = RxTxDevFile::new(&test.get_shared_config());
+    test.register_entity(PhyBs::new(test.get_shared_config(), rxtxdev));
+    test.populate_entities(
+        vec![
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            TetraEntity::Mle,
+            TetraEntity::Mm,
+            TetraEntity::Cmce,
+        ],
+        vec![],
+    );
+    test.run_stack(Some(2 * 4 * 18));
+    drop(test);
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Mon);
+    config.phy_io.backend = PhyBackend::File;
+    config.phy_io.dl_input_file = Some(path_str);
+    let mut test = ComponentTest::from_config(config, None);
+    let rxtxdev = RxTxDevFile::new(&test.get_shared_config());
+    test.register_entity(PhyMon::new(test.get_shared_config(), rxtxdev));
+    test.populate_entities(vec![TetraEntity::Lmac], vec![TetraEntity::Umac]);
+    // Stops by itself at the end of the recording
+    test.run_stack(None);
+
+    let syncs = test
+        .dump_sinks()
+        .into_iter()
+        .filter(|m| matches!(&m.msg, SapMsgInner::TmvUnitdataInd(prim) if prim.logical_channel == LogicalChannel::Bsch && prim.crc_pass))
+        .count();
+    assert!(syncs > 0, "No SYNC decoded from recorded downlink");
+
+    let _ = std::fs::remove_file(&path);
+}
+
+/// A BS reading its uplink from a file stops once the file and the silence tail have been received
+#[test]
+fn test_file_backend_bs_stops_at_end_of_input() {
+    debug::setup_logging_verbose();
+
+    // 0.1 s of uplink silence at the default 512 kHz sample rate
+    let ul_path = temp_path("ul");
+    std::fs::write(&ul_path, vec![0u8; 51200 * 8]).unwrap();
+    let dl_path = temp_path("dl_stop");
+
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.phy_io.backend = PhyBackend::File;
+    config.phy_io.ul_input_file = Some(ul_path.to_str().unwrap().to_string());
+    config.phy_io.dl_tx_file = Some(dl_path.to_str().unwrap().to_string());
+    config.phy_io.rx_silence_tail_secs = Some(0.1);
+    let mut test = ComponentTest::from_config(config, None);
+    let rxtxdev = RxTxDevFile::new(&test.get_shared_config());
+    test.register_entity(PhyBs::new(test.get_shared_config(), rxtxdev));
+    test.populate_entities(
+        vec![
+            TetraEntity::Lmac,
+            TetraEntity::Umac,
+            TetraEntity::Llc,
+            TetraEntity::Mle,
+            TetraEntity::Mm,
+            TetraEntity::Cmce,
+        ],
+        vec![],
+    );
+    // Would run for about 5 seconds of signal if the end of the input went unnoticed
+    test.run_stack(Some(5 * 4 * 18));
+    drop(test);
+
+    // Roughly 0.2 s of downlink was produced, allowing for blocks transmitted ahead
+    let dl_samples = std::fs::metadata(&dl_path).unwrap().len() / 8;
+    assert!(dl_samples > 90_000 && dl_samples < 160_000, "{} downlink samples", dl_samples);
+
+    let _ = std::fs::remove_file(&ul_path);
+    let _ = std::fs::remove_file(&dl_path);
+}
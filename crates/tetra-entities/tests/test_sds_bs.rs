@@ -123,6 +123,8 @@ fn test_sds_brew_forward() {
         reconnect_delay: Duration::from_secs(1),
         jitter_initial_latency_frames: 0,
         feature_sds_enabled: true,
+        feature_dtmf_enabled: true,
+        feature_packet_data_enabled: false,
         whitelisted_ssis: None,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
@@ -328,6 +330,8 @@ fn test_u_status_brew_forward() {
         reconnect_delay: Duration::from_secs(1),
         jitter_initial_latency_frames: 0,
         feature_sds_enabled: true,
+        feature_dtmf_enabled: true,
+        feature_packet_data_enabled: false,
         whitelisted_ssis: None,
     });
     let mut test = ComponentTest::from_config(config, Some(dltime));
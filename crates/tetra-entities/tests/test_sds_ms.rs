@@ -0,0 +1,85 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_saps::control::enums::sds_user_data::SdsUserData;
+use tetra_saps::control::sds::CmceSdsData;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+#[test]
+fn test_sds_send() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle]);
+    let issi = test.config.config().ms.as_ref().unwrap().issi;
+
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::User,
+        dest: TetraEntity::Cmce,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::CmceSdsData(CmceSdsData {
+            source_issi: 0,
+            dest_issi: 1000,
+            user_defined_data: SdsUserData::Type1(0x1234),
+        }),
+    });
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    assert_eq!(msgs.len(), 1);
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msgs.into_iter().next().unwrap().msg else {
+        panic!("Expected LcmcMleUnitdataReq");
+    };
+    assert_eq!(prim.main_address.ssi, issi);
+    let pdu = USdsData::from_bitbuf(&mut prim.sdu).expect("valid U-SDS-DATA");
+    assert_eq!(pdu.called_party_ssi, Some(1000));
+    assert!(matches!(pdu.user_defined_data, SdsUserData::Type1(0x1234)));
+}
+
+#[test]
+fn test_sds_receive() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle]);
+    let issi = test.config.config().ms.as_ref().unwrap().issi;
+
+    let pdu = DSdsData {
+        calling_party_type_identifier: PartyTypeIdentifier::Ssi,
+        calling_party_address_ssi: Some(1000),
+        calling_party_extension: None,
+        user_defined_data: SdsUserData::Type1(0x1234),
+        external_subscriber_number: None,
+        dm_ms_address: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+
+    test.submit_message(SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 0,
+            endpoint_id: 0,
+            link_id: 0,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    });
+    test.run_stack(Some(1));
+
+    // Received SDS is only reported, nothing is sent in response
+    assert_eq!(test.dump_sinks().len(), 0);
+}
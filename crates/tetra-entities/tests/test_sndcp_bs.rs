@@ -0,0 +1,302 @@
+mod common;
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use tetra_config::bluestation::{CfgBrew, CfgSndcp, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, TimeslotOwner, debug, multiframes};
+use tetra_entities::sndcp::packet_io::MemoryPacketSink;
+use tetra_entities::sndcp::sndcp_bs::Sndcp;
+use tetra_pdus::sndcp::enums::activation_reject_cause::ActivationRejectCause;
+use tetra_pdus::sndcp::enums::address_type_identifier::AddressTypeIdentifier;
+use tetra_pdus::sndcp::enums::deactivation_type::DeactivationType;
+use tetra_pdus::sndcp::enums::sn_pdu_type_dl::SnPduTypeDl;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_accept::SnActivatePdpContextAccept;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_demand::SnActivatePdpContextDemand;
+use tetra_pdus::sndcp::pdus::sn_activate_pdp_context_reject::SnActivatePdpContextReject;
+use tetra_pdus::sndcp::pdus::sn_data::SnData;
+use tetra_pdus::sndcp::pdus::sn_deactivate_pdp_context_demand::SnDeactivatePdpContextDemand;
+use tetra_saps::control::packet_data::SndcpPacketData;
+use tetra_saps::control::pdch::PdchControl;
+use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
+use tetra_saps::ltpd::{LtpdMleUnitdataInd, LtpdMleUnitdataReq};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const TEST_ISSI: u32 = 1000001;
+
+fn setup(dltime: TdmaTime, brew: Option<CfgBrew>) -> (ComponentTest, MemoryPacketSink) {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.sndcp_service = true;
+    config.sndcp = Some(CfgSndcp {
+        tun_name: "tetra0".to_string(),
+        network: Ipv4Addr::new(10, 200, 0, 0),
+        prefix_len: 29,
+        mtu: 1500,
+        pdch: true,
+    });
+    config.brew = brew;
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+
+    let packets = MemoryPacketSink::new();
+    let mut sndcp = Sndcp::new(test.get_shared_config());
+    sndcp.set_packet_io(Box::new(packets.clone()));
+    test.register_entity(sndcp);
+    test.populate_entities(vec![], vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew]);
+    (test, packets)
+}
+
+fn build_ltpd_ind(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::TlpdSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Sndcp,
+        dltime,
+        msg: SapMsgInner::LtpdMleUnitdataInd(LtpdMleUnitdataInd {
+            sdu,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+fn build_activate_demand(dltime: TdmaTime, issi: u32, address_type_identifier: AddressTypeIdentifier, ip: Option<Ipv4Addr>) -> SapMsg {
+    let pdu = SnActivatePdpContextDemand {
+        sndcp_version: 1,
+        nsapi: 1,
+        address_type_identifier,
+        ip_address: ip.map(u32::from),
+        packet_data_ms_type: 0,
+        pcomp_negotiation: 0,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_ltpd_ind(dltime, issi, sdu)
+}
+
+/// Minimal IPv4 header followed by a payload
+fn build_ipv4_packet(src: Ipv4Addr, dst: Ipv4Addr, payload: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0];
+    packet[2..4].copy_from_slice(&((20 + payload.len()) as u16).to_be_bytes());
+    packet.extend_from_slice(&src.octets());
+    packet.extend_from_slice(&dst.octets());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+fn build_sn_data(dltime: TdmaTime, issi: u32, n_pdu: Vec<u8>) -> SapMsg {
+    let mut sdu = BitBuffer::new_autoexpand(256);
+    SnData {
+        nsapi: 1,
+        pcomp: 0,
+        dcomp: 0,
+        n_pdu,
+    }
+    .to_bitbuf(&mut sdu)
+    .unwrap();
+    sdu.seek(0);
+    build_ltpd_ind(dltime, issi, sdu)
+}
+
+/// Collects the SNDCP PDUs sent towards the MLE
+fn extract_ltpd_reqs(msgs: &[SapMsg]) -> Vec<LtpdMleUnitdataReq> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LtpdMleUnitdataReq(prim) => Some(prim.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn extract_pdch_control(msgs: &[SapMsg]) -> Vec<PdchControl> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::PdchControl(prim) => Some(prim.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_pdp_context_activation_and_data() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, packets) = setup(dltime, None);
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    let ms_ip = Ipv4Addr::new(10, 200, 0, 2);
+
+    // Activation hands out the first pool address and moves the MS onto a PDCH
+    test.submit_message(build_activate_demand(dltime, TEST_ISSI, AddressTypeIdentifier::Ipv4Dynamic, None));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let reqs = extract_ltpd_reqs(&msgs);
+    assert_eq!(reqs.len(), 1);
+    let mut sdu = reqs[0].sdu.clone();
+    let accept = SnActivatePdpContextAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(accept.nsapi, 1);
+    assert_eq!(accept.ip_address, Some(u32::from(ms_ip)));
+    assert_eq!(reqs[0].main_address.ssi, TEST_ISSI);
+
+    let pdch = extract_pdch_control(&msgs);
+    assert_eq!(pdch.len(), 1);
+    let PdchControl::Open { ts } = pdch[0] else {
+        panic!("expected PDCH open, got {:?}", pdch[0]);
+    };
+    assert_eq!(test.config.state_read().timeslot_alloc.owner(ts), Some(TimeslotOwner::Sndcp));
+    let chan_alloc = reqs[0].chan_alloc.as_ref().expect("accept should assign the PDCH");
+    assert_eq!(chan_alloc.alloc_type, ChanAllocType::Replace);
+    assert!(chan_alloc.timeslots[(ts - 1) as usize]);
+
+    // Uplink packets with the assigned source address are forwarded, spoofed ones are not
+    let packet = build_ipv4_packet(ms_ip, Ipv4Addr::new(192, 0, 2, 1), b"avl");
+    let spoofed = build_ipv4_packet(Ipv4Addr::new(10, 200, 0, 3), Ipv4Addr::new(192, 0, 2, 1), b"avl");
+    for n_pdu in [packet.clone(), spoofed] {
+        test.submit_message(build_sn_data(dltime, TEST_ISSI, n_pdu));
+    }
+    test.run_stack(Some(1));
+    assert_eq!(packets.take_sent(), vec![packet]);
+
+    // Downlink packets are routed to the MS by destination address
+    let packet = build_ipv4_packet(Ipv4Addr::new(192, 0, 2, 1), ms_ip, b"telemetry");
+    packets.inject(packet.clone());
+    packets.inject(build_ipv4_packet(
+        Ipv4Addr::new(192, 0, 2, 1),
+        Ipv4Addr::new(10, 200, 0, 4),
+        b"nobody",
+    ));
+    test.run_stack(Some(1));
+    let reqs = extract_ltpd_reqs(&test.dump_sinks());
+    assert_eq!(reqs.len(), 1);
+    let mut sdu = reqs[0].sdu.clone();
+    let data = SnData::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(data.n_pdu, packet);
+    assert_eq!(reqs[0].main_address.ssi, TEST_ISSI);
+
+    // Deactivation sends the MS back to the MCCH, and the PDCH is closed after a while
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    SnDeactivatePdpContextDemand {
+        deactivation_type: DeactivationType::AllNsapis,
+        nsapi: None,
+    }
+    .to_bitbuf(&mut sdu, Direction::Ul)
+    .unwrap();
+    sdu.seek(0);
+    test.submit_message(build_ltpd_ind(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+    let reqs = extract_ltpd_reqs(&test.dump_sinks());
+    assert_eq!(reqs.len(), 1);
+    assert_eq!(reqs[0].sdu.peek_bits(4), Some(SnPduTypeDl::SnDeactivatePdpContextAccept.into_raw()));
+    assert_eq!(reqs[0].chan_alloc.as_ref().unwrap().alloc_type, ChanAllocType::QuitAndGo);
+
+    test.run_stack(Some(multiframes!(6) as usize));
+    let pdch = extract_pdch_control(&test.dump_sinks());
+    assert!(matches!(pdch.as_slice(), [PdchControl::Close { ts: closed }] if *closed == ts));
+    assert!(test.config.state_read().timeslot_alloc.is_free(ts));
+}
+
+#[test]
+fn test_pdp_context_rejects() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, _packets) = setup(dltime, None);
+
+    // Unregistered MS
+    test.submit_message(build_activate_demand(dltime, TEST_ISSI, AddressTypeIdentifier::Ipv4Dynamic, None));
+    // Registered MS asking for a static address outside the pool
+    test.config.state_write().subscribers.register(TEST_ISSI + 1);
+    test.submit_message(build_activate_demand(
+        dltime,
+        TEST_ISSI + 1,
+        AddressTypeIdentifier::Ipv4Static,
+        Some(Ipv4Addr::new(192, 0, 2, 10)),
+    ));
+    // IPv6 is not supported
+    test.submit_message(build_activate_demand(dltime, TEST_ISSI + 1, AddressTypeIdentifier::Ipv6, None));
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let causes: Vec<(u32, ActivationRejectCause)> = extract_ltpd_reqs(&msgs)
+        .iter()
+        .map(|req| {
+            let mut sdu = req.sdu.clone();
+            let reject = SnActivatePdpContextReject::from_bitbuf(&mut sdu).unwrap();
+            (req.main_address.ssi, reject.activation_reject_cause)
+        })
+        .collect();
+    assert_eq!(
+        causes,
+        vec![
+            (TEST_ISSI, ActivationRejectCause::MsNotProvisionedForPacketData),
+            (TEST_ISSI + 1, ActivationRejectCause::Ipv4StaticAddressNotCorrect),
+            (TEST_ISSI + 1, ActivationRejectCause::Ipv6NotSupported),
+        ]
+    );
+    assert!(extract_pdch_control(&msgs).is_empty());
+}
+
+#[test]
+fn test_packet_data_bridged_to_brew() {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let brew = CfgBrew {
+        host: "test.local".into(),
+        port: 3000,
+        tls: false,
+        username: None,
+        password: None,
+        reconnect_delay: Duration::from_secs(1),
+        jitter_initial_latency_frames: 0,
+        feature_sds_enabled: true,
+        feature_dtmf_enabled: true,
+        feature_packet_data_enabled: true,
+        whitelisted_ssis: None,
+    };
+    let (mut test, packets) = setup(dltime, Some(brew));
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    let ms_ip = Ipv4Addr::new(10, 200, 0, 2);
+    test.submit_message(build_activate_demand(dltime, TEST_ISSI, AddressTypeIdentifier::Ipv4Dynamic, None));
+    test.run_stack(Some(1));
+    test.dump_sinks();
+
+    // Uplink packets go to Brew instead of the packet interface
+    let packet = build_ipv4_packet(ms_ip, Ipv4Addr::new(192, 0, 2, 1), b"avl");
+    test.submit_message(build_sn_data(dltime, TEST_ISSI, packet.clone()));
+    test.run_stack(Some(1));
+    let bridged: Vec<SndcpPacketData> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::SndcpPacketData(data) if m.dest == TetraEntity::Brew => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(bridged.len(), 1);
+    assert_eq!(bridged[0].issi, Some(TEST_ISSI));
+    assert_eq!(bridged[0].packet, packet);
+    assert!(packets.take_sent().is_empty());
+
+    // Packets from Brew are delivered by destination address, unless meant for another MS
+    let packet = build_ipv4_packet(Ipv4Addr::new(192, 0, 2, 1), ms_ip, b"telemetry");
+    for issi in [None, Some(TEST_ISSI + 1)] {
+        test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Brew,
+            dest: TetraEntity::Sndcp,
+            dltime,
+            msg: SapMsgInner::SndcpPacketData(SndcpPacketData {
+                issi,
+                packet: packet.clone(),
+            }),
+        });
+    }
+    test.run_stack(Some(1));
+    let reqs = extract_ltpd_reqs(&test.dump_sinks());
+    assert_eq!(reqs.len(), 1);
+    let mut sdu = reqs[0].sdu.clone();
+    assert_eq!(SnData::from_bitbuf(&mut sdu).unwrap().n_pdu, packet);
+    assert_eq!(reqs[0].main_address.ssi, TEST_ISSI);
+}
@@ -0,0 +1,321 @@
+mod common;
+
+use tetra_config::bluestation::{CallForwardingCondition, CfgGroupAssignment, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::cmce::enums::cf_condition::CfCondition;
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::enums::party_type_identifier::PartyTypeIdentifier;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::fields::ss_cf::SsCfPdu;
+use tetra_pdus::cmce::fields::ss_dgna::SsDgnaPdu;
+use tetra_pdus::cmce::fields::ss_pdu::SsPdu;
+use tetra_pdus::cmce::fields::ss_tpi::SsTpiPdu;
+use tetra_pdus::cmce::pdus::cmce_function_not_supported::CmceFunctionNotSupported;
+use tetra_pdus::cmce::pdus::d_facility::DFacility;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::u_facility::UFacility;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_saps::control::brew::{BrewSubscriberAction, MmSubscriberUpdate};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::control::enums::communication_type::CommunicationType;
+use tetra_saps::control::mm::MmControl;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+
+use crate::common::ComponentTest;
+
+const TEST_ISSI: u32 = 1000001;
+const TEST_GSSI: u32 = 91;
+
+fn setup(dltime: TdmaTime) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    test.populate_entities(
+        vec![TetraEntity::Cmce],
+        vec![TetraEntity::Mle, TetraEntity::Umac, TetraEntity::Brew],
+    );
+    test.config.state_write().subscribers.register(TEST_ISSI);
+    test
+}
+
+fn build_lcmc_ind(dltime: TdmaTime, issi: u32, sdu: BitBuffer) -> SapMsg {
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 1,
+            endpoint_id: 1,
+            link_id: 1,
+            received_tetra_address: TetraAddress::new(issi, SsiType::Issi),
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+        }),
+    }
+}
+
+fn build_u_facility_msg(dltime: TdmaTime, issi: u32, ss_pdu: SsPdu) -> SapMsg {
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    UFacility { ss_pdu }.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    build_lcmc_ind(dltime, issi, sdu)
+}
+
+/// Collects the SS-PDUs sent in D-FACILITY, with the ISSI they were addressed to
+fn extract_d_facilities(msgs: &[SapMsg]) -> Vec<(u32, SsPdu)> {
+    msgs.iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DFacility.into_raw()) => {
+                let mut sdu = prim.sdu.clone();
+                Some((prim.main_address.ssi, DFacility::from_bitbuf(&mut sdu).unwrap().ss_pdu))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// FUNCTION NOT SUPPORTED PDUs sent to MLE
+fn count_function_not_supported(msgs: &[SapMsg]) -> usize {
+    msgs.iter()
+        .filter(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) => prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::CmceFunctionNotSupported.into_raw()),
+            _ => false,
+        })
+        .count()
+}
+
+#[test]
+fn test_cf_activate_interrogate_deactivate() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+
+    let activate = SsCfPdu::Activate {
+        condition: CfCondition::NotReachable,
+        forwarded_to_ssi: 1000002,
+    };
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, SsPdu::Cf(activate)));
+    test.run_stack(Some(1));
+    let expected = SsPdu::Cf(SsCfPdu::Result {
+        condition: CfCondition::NotReachable,
+        accepted: true,
+        forwarded_to_ssi: Some(1000002),
+    });
+    assert_eq!(extract_d_facilities(&test.dump_sinks()), vec![(TEST_ISSI, expected.clone())]);
+    assert_eq!(
+        test.config
+            .state_read()
+            .subscribers
+            .call_forwarding(TEST_ISSI, CallForwardingCondition::NotReachable),
+        Some(1000002)
+    );
+
+    let interrogate = SsCfPdu::Interrogate {
+        condition: CfCondition::NotReachable,
+    };
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, SsPdu::Cf(interrogate)));
+    test.run_stack(Some(1));
+    assert_eq!(extract_d_facilities(&test.dump_sinks()), vec![(TEST_ISSI, expected)]);
+
+    let deactivate = SsCfPdu::Deactivate {
+        condition: CfCondition::NotReachable,
+    };
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, SsPdu::Cf(deactivate)));
+    test.run_stack(Some(1));
+    let expected = SsPdu::Cf(SsCfPdu::Result {
+        condition: CfCondition::NotReachable,
+        accepted: true,
+        forwarded_to_ssi: None,
+    });
+    assert_eq!(extract_d_facilities(&test.dump_sinks()), vec![(TEST_ISSI, expected)]);
+}
+
+#[test]
+fn test_cf_to_self_rejected() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+
+    let activate = SsCfPdu::Activate {
+        condition: CfCondition::Unconditional,
+        forwarded_to_ssi: TEST_ISSI,
+    };
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, SsPdu::Cf(activate)));
+    test.run_stack(Some(1));
+    let expected = SsPdu::Cf(SsCfPdu::Result {
+        condition: CfCondition::Unconditional,
+        accepted: false,
+        forwarded_to_ssi: None,
+    });
+    assert_eq!(extract_d_facilities(&test.dump_sinks()), vec![(TEST_ISSI, expected)]);
+}
+
+#[test]
+fn test_dgna_request_handed_to_mm() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let dispatcher_issi = 1000009;
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.group_assignment = Some(CfgGroupAssignment {
+        dgna_authorised_issis: vec![dispatcher_issi],
+        ..Default::default()
+    });
+    let mut test = ComponentTest::from_config(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Mm]);
+    test.config.state_write().subscribers.register(TEST_ISSI);
+
+    // The authorised user's request is accepted and handed to MM, which attaches the served user
+    let request = SsPdu::Dgna(SsDgnaPdu::AssignRequest {
+        gssi: TEST_GSSI,
+        issi: TEST_ISSI,
+    });
+    test.submit_message(build_u_facility_msg(dltime, dispatcher_issi, request.clone()));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    let result = |accepted| {
+        SsPdu::Dgna(SsDgnaPdu::RequestResult {
+            gssi: TEST_GSSI,
+            issi: TEST_ISSI,
+            accepted,
+        })
+    };
+    assert_eq!(extract_d_facilities(&msgs), vec![(dispatcher_issi, result(true))]);
+    let controls: Vec<&MmControl> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::MmControl(control) if m.dest == TetraEntity::Mm => Some(control),
+            _ => None,
+        })
+        .collect();
+    assert!(matches!(
+        controls[..],
+        [MmControl::AssignGroups { issi: TEST_ISSI, attach, detach }] if attach == &vec![TEST_GSSI] && detach.is_empty()
+    ));
+    // CMCE leaves the registry to MM
+    assert!(!test.config.state_read().subscribers.has_group_members(TEST_GSSI));
+
+    // Anyone else is refused
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, request));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert_eq!(extract_d_facilities(&msgs), vec![(TEST_ISSI, result(false))]);
+    assert!(!msgs.iter().any(|m| m.dest == TetraEntity::Mm));
+
+    // Acknowledgements of SwMI-sent assignments are not expected
+    let ack = SsPdu::Dgna(SsDgnaPdu::AssignAck {
+        gssi: TEST_GSSI,
+        accepted: true,
+    });
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, ack));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(extract_d_facilities(&msgs).is_empty());
+    assert_eq!(count_function_not_supported(&msgs), 0);
+}
+
+#[test]
+fn test_tpi_interrogate() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+    let listener_issi = 1000002;
+    test.config.state_write().subscribers.register(listener_issi);
+    test.config.state_write().subscribers.affiliate(listener_issi, TEST_GSSI);
+
+    // Make call control aware of a listener so it accepts the group call
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::MmSubscriberUpdate(MmSubscriberUpdate {
+            issi: listener_issi,
+            groups: vec![TEST_GSSI],
+            action: BrewSubscriberAction::Affiliate,
+        }),
+    });
+    let u_setup = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type: CommunicationType::P2Mp,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: false,
+        call_priority: 0,
+        clir_control: 0,
+        called_party_type_identifier: PartyTypeIdentifier::Ssi,
+        called_party_ssi: Some(TEST_GSSI as u64),
+        called_party_short_number_address: None,
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    u_setup.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(build_lcmc_ind(dltime, TEST_ISSI, sdu));
+    test.run_stack(Some(1));
+
+    let call_identifier = test
+        .dump_sinks()
+        .into_iter()
+        .find_map(|m| match m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(mut prim) if prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::DSetup.into_raw()) => {
+                DSetup::from_bitbuf(&mut prim.sdu).ok().map(|pdu| pdu.call_identifier)
+            }
+            _ => None,
+        })
+        .expect("D-SETUP sent");
+
+    let interrogate = SsPdu::Tpi(SsTpiPdu::Interrogate { call_identifier });
+    test.submit_message(build_u_facility_msg(dltime, listener_issi, interrogate.clone()));
+    test.run_stack(Some(1));
+    let expected = SsPdu::Tpi(SsTpiPdu::Result {
+        call_identifier,
+        talking_party_ssi: Some(TEST_ISSI),
+    });
+    assert_eq!(extract_d_facilities(&test.dump_sinks()), vec![(listener_issi, expected)]);
+
+    // Not revealed to MSs outside the group
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, interrogate));
+    test.run_stack(Some(1));
+    assert!(extract_d_facilities(&test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_unsupported_ss_type_not_supported() {
+    let dltime = TdmaTime { h: 0, m: 1, f: 1, t: 1 };
+    let mut test = setup(dltime);
+
+    let ss_pdu = SsPdu::Unsupported {
+        ss_type: 1,
+        ss_pdu_type: 0,
+    };
+    test.submit_message(build_u_facility_msg(dltime, TEST_ISSI, ss_pdu));
+    test.run_stack(Some(1));
+    let msgs = test.dump_sinks();
+    assert!(extract_d_facilities(&msgs).is_empty());
+
+    // The MS is told the U-FACILITY is not supported
+    let replies: Vec<CmceFunctionNotSupported> = msgs
+        .iter()
+        .filter_map(|m| match &m.msg {
+            SapMsgInner::LcmcMleUnitdataReq(prim) if prim.sdu.peek_bits(5) == Some(CmcePduTypeDl::CmceFunctionNotSupported.into_raw()) => {
+                assert_eq!(prim.main_address.ssi, TEST_ISSI);
+                CmceFunctionNotSupported::from_bitbuf(&mut prim.sdu.clone()).ok()
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].not_supported_pdu_type as u64, CmcePduTypeUl::UFacility.into_raw());
+    assert_eq!(replies[0].function_not_supported_pointer, 0);
+}
@@ -2,7 +2,7 @@ mod common;
 
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Layer2Service, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::{BitBuffer, Direction, Layer2Service, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
@@ -24,6 +24,8 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
+        carrier: 0,
     };
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
@@ -38,6 +40,8 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         logical_channel: LogicalChannel::SchF,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
+        carrier: 0,
     };
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
@@ -84,6 +88,8 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
+        carrier: 0,
     };
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
@@ -98,6 +104,8 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
         scrambling_code: 864282631,
+        direction: Direction::Ul,
+        carrier: 0,
     };
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
@@ -172,3 +180,47 @@ fn test_out_fragmented_resource() {
 
     tracing::info!("Validation of result not implemented");
 }
+
+#[test]
+fn test_in_malformed_sch_f_dropped() {
+    // Malformed or unsupported MAC PDUs are logged and dropped, never crashing the stack
+    debug::setup_logging_verbose();
+    let ssi = format!("{:024b}", 2065022);
+    let vecs = [
+        // MAC-DATA with reserved length indication 0b000001
+        format!("000000{}0000001", ssi),
+        // MAC-DATA with a length indication shorter than its own header
+        format!("000000{}0000010", ssi),
+        // MAC-U-BLCK
+        "110000000000011001".to_string(),
+    ];
+
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(StackMode::Bs, Some(dltime));
+    let components = vec![TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle];
+    let sinks: Vec<TetraEntity> = vec![TetraEntity::Mm, TetraEntity::Cmce, TetraEntity::Sndcp];
+    test.populate_entities(components, sinks);
+
+    for vec in vecs {
+        // BL-UDATA carrying an MM PDU, then padding to a full slot
+        let mut bits = format!("{}0010001{}", vec, "0110".repeat(8));
+        bits.push_str(&"0".repeat(268 - bits.len()));
+        test.submit_message(SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime: TdmaTime::default(),
+            msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+                pdu: BitBuffer::from_bitstr(&bits),
+                block_num: PhyBlockNum::Both,
+                logical_channel: LogicalChannel::SchF,
+                crc_pass: true,
+                scrambling_code: 864282631,
+                direction: Direction::Ul,
+                carrier: 0,
+            }),
+        });
+        test.run_stack(Some(1));
+        assert!(test.dump_sinks().is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tetra_config::bluestation::{AieKeyClass, CfgSecurity, SecurityClass, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_entities::umac::subcomp::aie::{AieCipher, ENCRYPTION_MODE, TestKsg};
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tma::TmaUnitdataReq;
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 2040814;
+const DCK: u128 = 0x0123456789abcdef0123;
+const PLAIN_SDU: &str = "0010110011110000101011011110001101010111";
+
+/// Class 3 cell, with ISSI registered and holding a DCK
+fn setup(components: Vec<TetraEntity>, sinks: Vec<TetraEntity>) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.aie_service = true;
+    config.security = Some(CfgSecurity {
+        keys: HashMap::from([(ISSI, 0)]),
+        security_class: SecurityClass::Class3,
+        ..Default::default()
+    });
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(components, sinks);
+
+    let mut state = test.config.state_write();
+    state.subscribers.register(ISSI);
+    state.subscribers.get_subscriber_mut(ISSI).aie = Some(AieKeyClass::Dck(DCK));
+    drop(state);
+    test
+}
+
+fn build_tma_unitdata_req(air_interface_encryption: Option<i32>) -> SapMsg {
+    SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+            req_handle: 0,
+            pdu: BitBuffer::from_bitstr(PLAIN_SDU),
+            main_address: TetraAddress::new(ISSI, SsiType::Issi),
+            endpoint_id: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            air_interface_encryption,
+            stealing_repeats_flag: None,
+            data_category: None,
+            chan_alloc: None,
+            tx_reporter: None,
+        }),
+    }
+}
+
+/// Finds the MAC-RESOURCE addressed to ISSI among the transmitted blocks.
+/// Returns it with the block, positioned at the TM-SDU, and the time of its slot.
+fn find_resource(msgs: Vec<SapMsg>) -> (MacResource, BitBuffer, TdmaTime) {
+    msgs.into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmvUnitdataReq(slot) => Some((slot.ts, slot.blk1?)),
+            _ => None,
+        })
+        .filter(|(_, blk)| blk.logical_channel == LogicalChannel::SchF)
+        .find_map(|(ts, blk)| {
+            let mut mac_block = blk.mac_block;
+            mac_block.seek(0);
+            let pdu = MacResource::from_bitbuf(&mut mac_block).ok()?;
+            pdu.addr.filter(|addr| addr.ssi == ISSI)?;
+            Some((pdu, mac_block, ts))
+        })
+        .expect("MAC-RESOURCE for ISSI transmitted")
+}
+
+#[test]
+fn test_dl_encrypted_with_dck() {
+    let mut test = setup(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+    test.submit_message(build_tma_unitdata_req(None));
+    test.run_stack(Some(8));
+
+    let (pdu, mut sdu, ts) = find_resource(test.dump_sinks());
+    assert_eq!(pdu.encryption_mode, ENCRYPTION_MODE);
+    let sdu_len = PLAIN_SDU.len();
+    assert_ne!(
+        sdu.peek_bits(sdu_len).unwrap(),
+        BitBuffer::from_bitstr(PLAIN_SDU).read_bits(sdu_len).unwrap()
+    );
+
+    // Keystream for the slot carrying the MAC-RESOURCE recovers the SDU
+    AieCipher::new(Arc::new(TestKsg), DCK).apply(&mut sdu, ts, Direction::Dl);
+    assert_eq!(sdu.read_bits(sdu_len), BitBuffer::from_bitstr(PLAIN_SDU).read_bits(sdu_len));
+}
+
+#[test]
+fn test_dl_clear_on_request() {
+    let mut test = setup(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+    test.submit_message(build_tma_unitdata_req(Some(0)));
+    test.run_stack(Some(8));
+
+    let (pdu, mut sdu, _) = find_resource(test.dump_sinks());
+    assert_eq!(pdu.encryption_mode, 0);
+    let sdu_len = PLAIN_SDU.len();
+    assert_eq!(sdu.read_bits(sdu_len), BitBuffer::from_bitstr(PLAIN_SDU).read_bits(sdu_len));
+}
+
+#[test]
+fn test_ul_mac_access_decrypted() {
+    let plain = "011010011100101100011110000111010110";
+    let ultime = TdmaTime::default();
+
+    // MAC-ACCESS header of 36 bits followed by the encrypted TM-SDU, 9 bytes in total
+    let mut sdu = BitBuffer::from_bitstr(plain);
+    AieCipher::new(Arc::new(TestKsg), DCK).apply(&mut sdu, ultime, Direction::Ul);
+    let header = MacAccess {
+        fill_bits: false,
+        encrypted: true,
+        addr: Some(TetraAddress {
+            encrypted: true,
+            ssi_type: SsiType::Ssi,
+            ssi: ISSI,
+        }),
+        event_label: None,
+        length_ind: Some(9),
+        frag_flag: None,
+        reservation_req: None,
+    };
+    let mut block = BitBuffer::new(92);
+    header.to_bitbuf(&mut block);
+    block.copy_bits(&mut sdu, plain.len());
+    block.seek(0);
+
+    let mut test = setup(vec![TetraEntity::Umac], vec![TetraEntity::Llc]);
+    test.submit_message(SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: ultime,
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: block,
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: true,
+            scrambling_code: 864282631,
+            direction: Direction::Ul,
+            carrier: 0,
+        }),
+    });
+    test.run_stack(Some(1));
+
+    let inds: Vec<_> = test
+        .dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmaUnitdataInd(prim) => Some(prim),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(inds.len(), 1);
+    assert_eq!(inds[0].air_interface_encryption, 1);
+    assert_eq!(inds[0].pdu.as_ref().unwrap().to_bitstr(), plain);
+}
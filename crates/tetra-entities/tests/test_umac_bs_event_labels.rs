@@ -0,0 +1,231 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, TxReporter, debug};
+use tetra_pdus::umac::fields::EventLabel;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tma::TmaUnitdataReq;
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::ComponentTest;
+
+const ISSI: u32 = 2040814;
+const SDU: &str = "0010110011110000101011011110001101010111";
+
+/// Cell using event labels, with ISSI registered
+fn setup(event_labels: bool, sinks: Vec<TetraEntity>) -> ComponentTest {
+    debug::setup_logging_verbose();
+    let mut config = ComponentTest::get_default_test_config(StackMode::Bs);
+    config.cell.event_labels = event_labels;
+    let mut test = ComponentTest::from_config(config, None);
+    test.populate_entities(vec![TetraEntity::Umac], sinks);
+    test.config.state_write().subscribers.register(ISSI);
+    test
+}
+
+/// Sends an SDU for ISSI down to the UMAC and returns the SSI and event label addressing the MAC-RESOURCE carrying it
+fn send_dl(test: &mut ComponentTest, tx_reporter: Option<TxReporter>) -> (Option<u32>, Option<EventLabel>) {
+    let pdu = send_dl_on(test, false, LogicalChannel::SchF, tx_reporter);
+    (pdu.addr.map(|addr| addr.ssi), pdu.event_label)
+}
+
+/// Sends an SDU for ISSI down to the UMAC, stolen from a traffic channel if steal is set, and returns the
+/// MAC-RESOURCE carrying it on lchan
+fn send_dl_on(test: &mut ComponentTest, steal: bool, lchan: LogicalChannel, tx_reporter: Option<TxReporter>) -> MacResource {
+    test.submit_message(SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+            req_handle: 0,
+            pdu: BitBuffer::from_bitstr(SDU),
+            main_address: TetraAddress::new(ISSI, SsiType::Issi),
+            endpoint_id: 0,
+            stealing_permission: steal,
+            subscriber_class: 0,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_category: None,
+            chan_alloc: None,
+            tx_reporter,
+        }),
+    });
+    test.run_stack(Some(8));
+
+    test.dump_sinks()
+        .into_iter()
+        .filter_map(|m| match m.msg {
+            SapMsgInner::TmvUnitdataReq(slot) => slot.blk1,
+            _ => None,
+        })
+        .filter(|blk| blk.logical_channel == lchan)
+        .find_map(|blk| {
+            let mut mac_block = blk.mac_block;
+            mac_block.seek(0);
+            let pdu = MacResource::from_bitbuf(&mut mac_block).ok()?;
+            (pdu.addr.is_some() || pdu.event_label.is_some()).then_some(pdu)
+        })
+        .expect("MAC-RESOURCE transmitted")
+}
+
+/// Sends a MAC-ACCESS addressed by event label up to the UMAC, returning the address passed on to the LLC
+fn send_ul_with_label(test: &mut ComponentTest, label: EventLabel) -> Option<TetraAddress> {
+    let sdu = "011010011100101100011110000111010110";
+    let header = MacAccess {
+        fill_bits: false,
+        encrypted: false,
+        addr: None,
+        event_label: Some(label),
+        length_ind: Some(8),
+        frag_flag: None,
+        reservation_req: None,
+    };
+    let mut block = BitBuffer::new(92);
+    header.to_bitbuf(&mut block);
+    block.copy_bits(&mut BitBuffer::from_bitstr(sdu), sdu.len());
+    block.seek(0);
+
+    test.submit_message(SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: block,
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::SchHu,
+            crc_pass: true,
+            scrambling_code: 864282631,
+            direction: Direction::Ul,
+            carrier: 0,
+        }),
+    });
+    test.run_stack(Some(1));
+
+    test.dump_sinks().into_iter().find_map(|m| match m.msg {
+        SapMsgInner::TmaUnitdataInd(prim) => Some(prim.main_address),
+        _ => None,
+    })
+}
+
+#[test]
+fn test_event_label_confirmed_by_acknowledgement() {
+    let mut test = setup(true, vec![TetraEntity::Lmac]);
+
+    // The label is assigned along with the SSI, until the MS acknowledged a PDU carrying it
+    let reporter = TxReporter::new();
+    let (addr, label) = send_dl(&mut test, Some(reporter.clone()));
+    assert_eq!(addr, Some(ISSI));
+    let label = label.expect("event label assigned");
+    assert!(reporter.is_transmitted());
+    assert_eq!(send_dl(&mut test, None), (addr, Some(label)));
+
+    reporter.mark_acknowledged();
+    assert_eq!(send_dl(&mut test, None), (None, Some(label)));
+}
+
+#[test]
+fn test_event_label_confirmed_by_uplink() {
+    let mut test = setup(true, vec![TetraEntity::Lmac, TetraEntity::Llc]);
+
+    let (_, label) = send_dl(&mut test, None);
+    let label = label.expect("event label assigned");
+
+    // The MS uses the label on the uplink, which resolves to its address
+    let addr = send_ul_with_label(&mut test, label).expect("MAC-ACCESS passed to the LLC");
+    assert_eq!(addr.ssi, ISSI);
+    assert_eq!(send_dl(&mut test, None), (None, Some(label)));
+
+    // An unknown label is dropped
+    assert!(send_ul_with_label(&mut test, label + 1).is_none());
+}
+
+#[test]
+fn test_event_labels_disabled() {
+    let mut test = setup(false, vec![TetraEntity::Lmac]);
+    let (addr, label) = send_dl(&mut test, None);
+    assert_eq!(addr, Some(ISSI));
+    assert!(label.is_none());
+}
+
+/// Sends CMCE call control down to the UMAC
+fn send_call_control(test: &mut ComponentTest, prim: CallControl) {
+    test.submit_message(SapMsg {
+        sap: Sap::Control,
+        src: TetraEntity::Cmce,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::CmceCallControl(prim),
+    });
+    test.run_stack(Some(1));
+}
+
+/// Opens a circuit on timeslot 2 with usage marker 7
+fn open_circuit(test: &mut ComponentTest) {
+    send_call_control(
+        test,
+        CallControl::Open(Circuit {
+            direction: Direction::Both,
+            carrier: 0,
+            ts: 2,
+            usage: 7,
+            circuit_mode: CircuitModeType::TchS,
+            speech_service: Some(0),
+            etee_encrypted: false,
+            peer_ts: None,
+        }),
+    );
+}
+
+#[test]
+fn test_stolen_signalling_carries_usage_marker() {
+    let mut test = setup(false, vec![TetraEntity::Lmac]);
+    open_circuit(&mut test);
+
+    // Signalling stolen from the traffic channel is addressed by SSI along with the usage marker of the call
+    let pdu = send_dl_on(&mut test, true, LogicalChannel::Stch, None);
+    assert_eq!(pdu.addr.map(|addr| addr.ssi), Some(ISSI));
+    assert_eq!(pdu.usage_marker, Some(7));
+    assert!(pdu.event_label.is_none());
+}
+
+#[test]
+fn test_event_label_per_call() {
+    let mut test = setup(true, vec![TetraEntity::Lmac]);
+    let (_, mcch_label) = send_dl(&mut test, None);
+    let mcch_label = mcch_label.expect("event label assigned");
+    open_circuit(&mut test);
+
+    // The MS gets a label of its own for the call, used instead of the usage marker once acknowledged
+    let reporter = TxReporter::new();
+    let pdu = send_dl_on(&mut test, true, LogicalChannel::Stch, Some(reporter.clone()));
+    assert_eq!(pdu.addr.map(|addr| addr.ssi), Some(ISSI));
+    assert!(pdu.usage_marker.is_none());
+    let call_label = pdu.event_label.expect("event label assigned for the call");
+    assert_ne!(call_label, mcch_label);
+    reporter.mark_acknowledged();
+    let pdu = send_dl_on(&mut test, true, LogicalChannel::Stch, None);
+    assert!(pdu.addr.is_none());
+    assert_eq!(pdu.event_label, Some(call_label));
+
+    // The label is released along with the call, while the one on the MCCH remains
+    send_call_control(
+        &mut test,
+        CallControl::CallEnded {
+            call_id: 1,
+            carrier: 0,
+            ts: 2,
+        },
+    );
+    open_circuit(&mut test);
+    let pdu = send_dl_on(&mut test, true, LogicalChannel::Stch, None);
+    assert_eq!(pdu.addr.map(|addr| addr.ssi), Some(ISSI));
+    assert!(pdu.event_label.is_some_and(|label| label != call_label));
+    assert_eq!(send_dl(&mut test, None), (Some(ISSI), Some(mcch_label)));
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use tetra_config::bluestation::StackMode;
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, TdmaTime, debug};
+use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
+use tetra_pdus::umac::pdus::mac_sync::MacSync;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+
+use crate::common::ComponentTest;
+
+fn build_bsch_msg(dltime: TdmaTime, network_time: TdmaTime) -> SapMsg {
+    let mut pdu = BitBuffer::new_autoexpand(60);
+    MacSync {
+        system_code: 3,
+        colour_code: 1,
+        time: network_time,
+        sharing_mode: 0,
+        ts_reserved_frames: 0,
+        u_plane_dtx: false,
+        frame_18_ext: false,
+    }
+    .to_bitbuf(&mut pdu);
+    DMleSync {
+        mcc: 204,
+        mnc: 1337,
+        neighbor_cell_broadcast: 2,
+        cell_load_ca: 0,
+        late_entry_supported: false,
+    }
+    .to_bitbuf(&mut pdu);
+    pdu.seek(0);
+
+    SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime,
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu,
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::Bsch,
+            crc_pass: true,
+            scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
+        }),
+    }
+}
+
+#[test]
+/// A monitoring Umac receiving its first SYNC adopts the network time and scrambling code,
+/// and configures the Lmac accordingly. A subsequent SYNC that matches causes no reconfiguration.
+fn test_umac_mon_sync() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Mon, None);
+    let components = vec![TetraEntity::Umac];
+    let sinks: Vec<TetraEntity> = vec![TetraEntity::Lmac];
+    test.populate_entities(components, sinks);
+
+    let network_time = TdmaTime { h: 0, m: 12, f: 7, t: 3 };
+    test.submit_message(build_bsch_msg(TdmaTime::default(), network_time));
+    test.deliver_all_messages();
+    let sink_msgs = test.dump_sinks();
+
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::TmvConfigureReq(prim) = &sink_msgs[0].msg else {
+        panic!("expected TmvConfigureReq, got {:?}", sink_msgs[0].msg);
+    };
+    assert_eq!(prim.time, Some(network_time));
+    assert_eq!(prim.scrambling_code, Some(((1 | (1337 << 6) | (204 << 20)) << 2) | 3));
+
+    // Now in sync
+    test.submit_message(build_bsch_msg(network_time, network_time));
+    test.deliver_all_messages();
+    assert_eq!(test.dump_sinks().len(), 0);
+}
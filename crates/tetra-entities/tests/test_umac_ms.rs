@@ -2,8 +2,11 @@ mod common;
 
 use tetra_config::bluestation::StackMode;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, debug};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_pdus::umac::pdus::access_assign::{AccessAssign, AccessField};
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tma::TmaUnitdataReq;
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
 
 use crate::common::ComponentTest;
@@ -30,6 +33,8 @@ fn test_umac_ms() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
 
@@ -69,6 +74,8 @@ fn test_umac_frag() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
     test.submit_message(m);
@@ -87,6 +94,8 @@ fn test_umac_frag() {
             logical_channel: LogicalChannel::SchHd,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
 
@@ -127,6 +136,8 @@ fn test_sysinfo() {
             logical_channel: LogicalChannel::Bnch,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
     test.submit_message(m);
@@ -162,6 +173,8 @@ fn test_sync() {
             logical_channel: LogicalChannel::Bsch,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
     test.submit_message(m);
@@ -196,6 +209,8 @@ fn test_resource() {
             logical_channel: LogicalChannel::SchF,
             crc_pass: true,
             scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
         }),
     };
     test.submit_message(m);
@@ -207,3 +222,102 @@ fn test_resource() {
 
     tracing::warn!("Validation of result not implemented");
 }
+
+#[test]
+/// After syncing to a cell, a queued TMA-UNITDATA request is sent as MAC-ACCESS on the
+/// first common uplink slot announced by an ACCESS-ASSIGN
+fn test_random_access() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(StackMode::Ms, None);
+    let components = vec![TetraEntity::Umac];
+    let sinks = vec![TetraEntity::Lmac, TetraEntity::Mle];
+    test.populate_entities(components, sinks);
+    let issi = test.config.config().ms.as_ref().unwrap().issi;
+
+    // SYNC at 09/11/4, see test_sync
+    test.submit_message(SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: BitBuffer::from_bitstr("000100000111010110010010000000001101001000000100010101110011"),
+            block_num: PhyBlockNum::Block1,
+            logical_channel: LogicalChannel::Bsch,
+            crc_pass: true,
+            scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
+        }),
+    });
+    test.deliver_all_messages();
+    test.dump_sinks();
+
+    // Short uplink PDU from the LLC
+    test.submit_message(SapMsg {
+        sap: Sap::TmaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+            req_handle: 0,
+            pdu: BitBuffer::from_bitstr("0000111100001111"),
+            main_address: TetraAddress::new(issi, SsiType::Issi),
+            endpoint_id: 0,
+            stealing_permission: false,
+            subscriber_class: 0,
+            air_interface_encryption: None,
+            stealing_repeats_flag: None,
+            data_category: None,
+            chan_alloc: None,
+            tx_reporter: None,
+        }),
+    });
+
+    // ACCESS-ASSIGN on the MCCH, opening the uplink for random access
+    let mut aach = BitBuffer::new(14);
+    let af = AccessField {
+        access_code: 0,
+        base_frame_len: 0,
+    };
+    AccessAssign {
+        f1_af1: Some(af),
+        f2_af2: Some(af),
+        ..Default::default()
+    }
+    .to_bitbuf(&mut aach);
+    aach.seek(0);
+    let ul_time = TdmaTime { h: 0, m: 9, f: 12, t: 1 };
+    test.submit_message(SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: ul_time,
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: aach,
+            block_num: PhyBlockNum::Undefined,
+            logical_channel: LogicalChannel::Aach,
+            crc_pass: true,
+            scrambling_code: 0,
+            direction: Direction::Dl,
+            carrier: 0,
+        }),
+    });
+    test.run_stack(Some(1));
+
+    let msgs = test.dump_sinks();
+    let Some(SapMsgInner::TmvUnitdataReq(req)) = msgs
+        .into_iter()
+        .map(|m| m.msg)
+        .find(|m| matches!(m, SapMsgInner::TmvUnitdataReq(_)))
+    else {
+        panic!("No uplink block sent");
+    };
+    assert_eq!(req.ts, ul_time);
+    let mut blk = req.blk1.expect("MAC-ACCESS in first block");
+    assert_eq!(blk.logical_channel, LogicalChannel::SchHu);
+    blk.mac_block.seek(0);
+    let pdu = MacAccess::from_bitbuf(&mut blk.mac_block).expect("valid MAC-ACCESS");
+    assert_eq!(pdu.addr.map(|a| a.ssi), Some(issi));
+    assert_eq!(pdu.frag_flag, None);
+}
@@ -0,0 +1,56 @@
+/// Call forwarding condition, selects which of the forwarding services an SS-CF PDU refers to
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum CfCondition {
+    /// Call Forwarding Unconditional (CFU)
+    Unconditional = 0,
+    /// Call Forwarding on Busy (CFB)
+    Busy = 1,
+    /// Call Forwarding on No Reply (CFNRy)
+    NoReply = 2,
+    /// Call Forwarding on Not Reachable (CFNRc)
+    NotReachable = 3,
+}
+
+impl std::convert::TryFrom<u64> for CfCondition {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(CfCondition::Unconditional),
+            1 => Ok(CfCondition::Busy),
+            2 => Ok(CfCondition::NoReply),
+            3 => Ok(CfCondition::NotReachable),
+            _ => Err(()),
+        }
+    }
+}
+
+impl CfCondition {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            CfCondition::Unconditional => 0,
+            CfCondition::Busy => 1,
+            CfCondition::NoReply => 2,
+            CfCondition::NotReachable => 3,
+        }
+    }
+}
+
+impl From<CfCondition> for u64 {
+    fn from(e: CfCondition) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for CfCondition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CfCondition::Unconditional => write!(f, "Unconditional"),
+            CfCondition::Busy => write!(f, "Busy"),
+            CfCondition::NoReply => write!(f, "NoReply"),
+            CfCondition::NotReachable => write!(f, "NotReachable"),
+        }
+    }
+}
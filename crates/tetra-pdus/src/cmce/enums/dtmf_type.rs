@@ -0,0 +1,52 @@
+/// 14.8.17b DTMF type
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DtmfType {
+    DtmfToneStart = 0,
+    DtmfToneEnd = 1,
+    DtmfNotSupported = 2,
+    DtmfNotSubscribed = 3,
+}
+
+impl std::convert::TryFrom<u64> for DtmfType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(DtmfType::DtmfToneStart),
+            1 => Ok(DtmfType::DtmfToneEnd),
+            2 => Ok(DtmfType::DtmfNotSupported),
+            3 => Ok(DtmfType::DtmfNotSubscribed),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DtmfType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DtmfType::DtmfToneStart => 0,
+            DtmfType::DtmfToneEnd => 1,
+            DtmfType::DtmfNotSupported => 2,
+            DtmfType::DtmfNotSubscribed => 3,
+        }
+    }
+}
+
+impl From<DtmfType> for u64 {
+    fn from(e: DtmfType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for DtmfType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DtmfType::DtmfToneStart => write!(f, "DtmfToneStart"),
+            DtmfType::DtmfToneEnd => write!(f, "DtmfToneEnd"),
+            DtmfType::DtmfNotSupported => write!(f, "DtmfNotSupported"),
+            DtmfType::DtmfNotSubscribed => write!(f, "DtmfNotSubscribed"),
+        }
+    }
+}
@@ -1,12 +1,15 @@
 pub mod call_status;
 pub mod call_timeout;
 pub mod call_timeout_setup_phase;
+pub mod cf_condition;
 pub mod cmce_pdu_type_dl;
 pub mod cmce_pdu_type_ul;
 pub mod disconnect_cause;
+pub mod dtmf_type;
 pub mod party_type_identifier;
 pub mod pre_coded_status;
 pub mod sds_protocol_id;
 pub mod short_report_type;
+pub mod ss_type;
 pub mod transmission_grant;
 pub mod type3_elem_id;
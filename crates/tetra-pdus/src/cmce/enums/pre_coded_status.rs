@@ -17,8 +17,8 @@ impl From<u16> for PreCodedStatus {
     fn from(x: u16) -> Self {
         match x {
             0 => PreCodedStatus::Emergency,
-            1..=31742 => PreCodedStatus::Reserved(x),
-            31743..=32767 => PreCodedStatus::SdsTl(SdsShortReport::from_u16(x).unwrap()),
+            1..=31743 => PreCodedStatus::Reserved(x),
+            31744..=32767 => PreCodedStatus::SdsTl(SdsShortReport::from_u16(x).unwrap()), // Never fails
             32768..=65535 => PreCodedStatus::NetworkUserSpecific(x),
         }
     }
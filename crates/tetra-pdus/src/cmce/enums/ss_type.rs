@@ -0,0 +1,53 @@
+/// SS type, first element of every SS-PDU (EN 300 392-9)
+/// Bits: 6
+/// Only the supplementary services implemented by the SS sub-entity are listed. Values are
+/// numbered after the part of EN 300 392-12 specifying the service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum SsType {
+    /// Talking Party Identification
+    Tpi = 3,
+    /// Call Forwarding
+    Cf = 4,
+    /// Dynamic Group Number Assignment
+    Dgna = 22,
+}
+
+impl std::convert::TryFrom<u64> for SsType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            3 => Ok(SsType::Tpi),
+            4 => Ok(SsType::Cf),
+            22 => Ok(SsType::Dgna),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SsType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SsType::Tpi => 3,
+            SsType::Cf => 4,
+            SsType::Dgna => 22,
+        }
+    }
+}
+
+impl From<SsType> for u64 {
+    fn from(e: SsType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SsType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SsType::Tpi => write!(f, "Tpi"),
+            SsType::Cf => write!(f, "Cf"),
+            SsType::Dgna => write!(f, "Dgna"),
+        }
+    }
+}
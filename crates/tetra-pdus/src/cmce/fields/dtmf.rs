@@ -0,0 +1,103 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::Type3FieldGeneric;
+use tetra_core::{PduParseErr, expect_failed};
+
+use crate::cmce::enums::dtmf_type::DtmfType;
+use crate::cmce::enums::type3_elem_id::CmceType3ElemId;
+
+/// DTMF digits in digit order, as coded 0..=15 in Clause 14.8.17c
+pub const DTMF_DIGITS: &[u8; 16] = b"0123456789*#ABCD";
+
+/// Most digits fitting into a single DTMF element, as the generic type 3 element holds up to 64 bits
+pub const DTMF_MAX_DIGITS: usize = (64 - 3) / 4;
+
+/// Clause 14.8.17a DTMF
+/// Carried as type 3 element in U-INFO and D-INFO, among others
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dtmf {
+    /// 3 bits
+    pub dtmf_type: DtmfType,
+    /// 4 bits per digit, present for DtmfToneStart only. Characters out of "0123456789*#ABCD".
+    pub digits: String,
+}
+
+impl Dtmf {
+    /// DTMF tone start element carrying the given digits.
+    /// Returns None if any digit is invalid or there are more than DTMF_MAX_DIGITS.
+    pub fn tone_start(digits: &str) -> Option<Self> {
+        if digits.len() > DTMF_MAX_DIGITS || !digits.bytes().all(|d| DTMF_DIGITS.contains(&d)) {
+            return None;
+        }
+        Some(Dtmf {
+            dtmf_type: DtmfType::DtmfToneStart,
+            digits: digits.to_string(),
+        })
+    }
+
+    pub fn from_type3(elem: &Type3FieldGeneric) -> Result<Self, PduParseErr> {
+        if elem.len < 3 || elem.len > 64 {
+            return expect_failed!(elem.len as u64, "dtmf length");
+        }
+        let raw_type = (elem.data >> (elem.len - 3)) & 0x7;
+        let Ok(dtmf_type) = DtmfType::try_from(raw_type) else {
+            return expect_failed!(raw_type, "dtmf_type");
+        };
+
+        let num_digits = (elem.len - 3) / 4;
+        let digits = (0..num_digits)
+            .map(|i| {
+                let shift = elem.len - 3 - 4 * (i + 1);
+                DTMF_DIGITS[((elem.data >> shift) & 0xf) as usize] as char
+            })
+            .collect();
+        Ok(Dtmf { dtmf_type, digits })
+    }
+
+    pub fn to_type3(&self) -> Type3FieldGeneric {
+        let mut data = self.dtmf_type.into_raw();
+        let mut len = 3;
+        for d in self.digits.bytes() {
+            let code = DTMF_DIGITS.iter().position(|&c| c == d).expect("invalid DTMF digit");
+            data = (data << 4) | code as u64;
+            len += 4;
+        }
+        Type3FieldGeneric {
+            field_id: CmceType3ElemId::Dtmf.into_raw(),
+            len,
+            data,
+        }
+    }
+}
+
+impl fmt::Display for Dtmf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Dtmf {{ dtmf_type: {}, digits: {:?} }}", self.dtmf_type, self.digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dtmf_roundtrip() {
+        let dtmf = Dtmf::tone_start("91*#D").unwrap();
+        let elem = dtmf.to_type3();
+        assert_eq!(elem.len, 23);
+        assert_eq!(elem.data, 0b000_1001_0001_1010_1011_1111);
+        assert_eq!(Dtmf::from_type3(&elem).unwrap(), dtmf);
+
+        let end = Dtmf::from_type3(&Type3FieldGeneric {
+            field_id: CmceType3ElemId::Dtmf.into_raw(),
+            len: 3,
+            data: 1,
+        })
+        .unwrap();
+        assert_eq!(end.dtmf_type, DtmfType::DtmfToneEnd);
+        assert!(end.digits.is_empty());
+
+        assert!(Dtmf::tone_start("12X").is_none());
+        assert!(Dtmf::tone_start(&"1".repeat(DTMF_MAX_DIGITS + 1)).is_none());
+    }
+}
@@ -1,2 +1,7 @@
 pub mod basic_service_information;
+pub mod dtmf;
 pub mod sds_short_report;
+pub mod ss_cf;
+pub mod ss_dgna;
+pub mod ss_pdu;
+pub mod ss_tpi;
@@ -0,0 +1,99 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, expect_failed, pdu_parse_error::PduParseErr};
+
+use crate::cmce::enums::cf_condition::CfCondition;
+
+/// SS-PDUs of Call Forwarding (SS-CF), carried in U-FACILITY / D-FACILITY.
+/// Each PDU starts with a 5-bit SS-PDU type, followed by the 2-bit forwarding condition.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsCfPdu {
+    /// MS -> SwMI: activate forwarding of calls matching condition to forwarded_to_ssi (24 bits)
+    Activate { condition: CfCondition, forwarded_to_ssi: u32 },
+    /// MS -> SwMI: deactivate forwarding for condition
+    Deactivate { condition: CfCondition },
+    /// MS -> SwMI: query the forwarding state for condition
+    Interrogate { condition: CfCondition },
+    /// SwMI -> MS: response to any of the above. forwarded_to_ssi is present (1-bit flag
+    /// followed by 24 bits) while forwarding for condition is active.
+    Result {
+        condition: CfCondition,
+        accepted: bool,
+        forwarded_to_ssi: Option<u32>,
+    },
+}
+
+impl SsCfPdu {
+    pub fn ss_pdu_type(&self) -> u8 {
+        match self {
+            SsCfPdu::Activate { .. } => 0,
+            SsCfPdu::Deactivate { .. } => 1,
+            SsCfPdu::Interrogate { .. } => 2,
+            SsCfPdu::Result { .. } => 3,
+        }
+    }
+
+    /// Parse the PDU contents following the SS-PDU type
+    pub fn from_bitbuf(ss_pdu_type: u8, buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        if ss_pdu_type > 3 {
+            return expect_failed!(ss_pdu_type as u64, "ss_pdu_type");
+        }
+        let condition = CfCondition::try_from(buffer.read_field(2, "condition")?).unwrap(); // Never fails
+        match ss_pdu_type {
+            0 => Ok(SsCfPdu::Activate {
+                condition,
+                forwarded_to_ssi: buffer.read_field(24, "forwarded_to_ssi")? as u32,
+            }),
+            1 => Ok(SsCfPdu::Deactivate { condition }),
+            2 => Ok(SsCfPdu::Interrogate { condition }),
+            _ => {
+                let accepted = buffer.read_field(1, "accepted")? == 1;
+                let forwarded_to_ssi = if buffer.read_field(1, "forwarded_to_ssi_present")? == 1 {
+                    Some(buffer.read_field(24, "forwarded_to_ssi")? as u32)
+                } else {
+                    None
+                };
+                Ok(SsCfPdu::Result {
+                    condition,
+                    accepted,
+                    forwarded_to_ssi,
+                })
+            }
+        }
+    }
+
+    /// Serialize SS-PDU type and contents
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.ss_pdu_type() as u64, 5);
+        match self {
+            SsCfPdu::Activate {
+                condition,
+                forwarded_to_ssi,
+            } => {
+                buffer.write_bits(condition.into_raw(), 2);
+                buffer.write_bits(*forwarded_to_ssi as u64, 24);
+            }
+            SsCfPdu::Deactivate { condition } | SsCfPdu::Interrogate { condition } => {
+                buffer.write_bits(condition.into_raw(), 2);
+            }
+            SsCfPdu::Result {
+                condition,
+                accepted,
+                forwarded_to_ssi,
+            } => {
+                buffer.write_bits(condition.into_raw(), 2);
+                buffer.write_bit(*accepted as u8);
+                buffer.write_bit(forwarded_to_ssi.is_some() as u8);
+                if let Some(ssi) = forwarded_to_ssi {
+                    buffer.write_bits(*ssi as u64, 24);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for SsCfPdu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SsCfPdu::{:?}", self)
+    }
+}
@@ -0,0 +1,97 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, expect_failed, pdu_parse_error::PduParseErr};
+
+/// SS-PDUs of Dynamic Group Number Assignment (SS-DGNA), carried in D-FACILITY / U-FACILITY.
+/// Each PDU starts with a 5-bit SS-PDU type, followed by the 24-bit GSSI it refers to.
+/// Requests of an authorised user also carry the 24-bit ISSI of the served user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsDgnaPdu {
+    /// SwMI -> MS: assign gssi to the MS, which attaches to it on acceptance
+    Assign { gssi: u32 },
+    /// SwMI -> MS: withdraw a previously assigned gssi
+    Deassign { gssi: u32 },
+    /// MS -> SwMI: response to Assign, 1 bit accepted flag
+    AssignAck { gssi: u32, accepted: bool },
+    /// MS -> SwMI: response to Deassign
+    DeassignAck { gssi: u32 },
+    /// Authorised user -> SwMI: assign gssi to the served user issi
+    AssignRequest { gssi: u32, issi: u32 },
+    /// Authorised user -> SwMI: withdraw gssi from the served user issi
+    DeassignRequest { gssi: u32, issi: u32 },
+    /// SwMI -> authorised user: response to AssignRequest or DeassignRequest, 1 bit accepted flag
+    RequestResult { gssi: u32, issi: u32, accepted: bool },
+}
+
+impl SsDgnaPdu {
+    pub fn ss_pdu_type(&self) -> u8 {
+        match self {
+            SsDgnaPdu::Assign { .. } => 0,
+            SsDgnaPdu::Deassign { .. } => 1,
+            SsDgnaPdu::AssignAck { .. } => 2,
+            SsDgnaPdu::DeassignAck { .. } => 3,
+            SsDgnaPdu::AssignRequest { .. } => 4,
+            SsDgnaPdu::DeassignRequest { .. } => 5,
+            SsDgnaPdu::RequestResult { .. } => 6,
+        }
+    }
+
+    /// Parse the PDU contents following the SS-PDU type
+    pub fn from_bitbuf(ss_pdu_type: u8, buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        if ss_pdu_type > 6 {
+            return expect_failed!(ss_pdu_type as u64, "ss_pdu_type");
+        }
+        let gssi = buffer.read_field(24, "gssi")? as u32;
+        match ss_pdu_type {
+            0 => Ok(SsDgnaPdu::Assign { gssi }),
+            1 => Ok(SsDgnaPdu::Deassign { gssi }),
+            2 => Ok(SsDgnaPdu::AssignAck {
+                gssi,
+                accepted: buffer.read_field(1, "accepted")? == 1,
+            }),
+            3 => Ok(SsDgnaPdu::DeassignAck { gssi }),
+            4 => Ok(SsDgnaPdu::AssignRequest {
+                gssi,
+                issi: buffer.read_field(24, "issi")? as u32,
+            }),
+            5 => Ok(SsDgnaPdu::DeassignRequest {
+                gssi,
+                issi: buffer.read_field(24, "issi")? as u32,
+            }),
+            _ => Ok(SsDgnaPdu::RequestResult {
+                gssi,
+                issi: buffer.read_field(24, "issi")? as u32,
+                accepted: buffer.read_field(1, "accepted")? == 1,
+            }),
+        }
+    }
+
+    /// Serialize SS-PDU type and contents
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.ss_pdu_type() as u64, 5);
+        match self {
+            SsDgnaPdu::Assign { gssi } | SsDgnaPdu::Deassign { gssi } | SsDgnaPdu::DeassignAck { gssi } => {
+                buffer.write_bits(*gssi as u64, 24);
+            }
+            SsDgnaPdu::AssignAck { gssi, accepted } => {
+                buffer.write_bits(*gssi as u64, 24);
+                buffer.write_bit(*accepted as u8);
+            }
+            SsDgnaPdu::AssignRequest { gssi, issi } | SsDgnaPdu::DeassignRequest { gssi, issi } => {
+                buffer.write_bits(*gssi as u64, 24);
+                buffer.write_bits(*issi as u64, 24);
+            }
+            SsDgnaPdu::RequestResult { gssi, issi, accepted } => {
+                buffer.write_bits(*gssi as u64, 24);
+                buffer.write_bits(*issi as u64, 24);
+                buffer.write_bit(*accepted as u8);
+            }
+        }
+    }
+}
+
+impl fmt::Display for SsDgnaPdu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SsDgnaPdu::{:?}", self)
+    }
+}
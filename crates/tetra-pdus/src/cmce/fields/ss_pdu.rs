@@ -0,0 +1,142 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::cmce::enums::ss_type::SsType;
+use crate::cmce::fields::{ss_cf::SsCfPdu, ss_dgna::SsDgnaPdu, ss_tpi::SsTpiPdu};
+
+/// SS-PDU as carried in U-FACILITY and D-FACILITY (EN 300 392-9).
+/// Starts with the 6-bit SS type and 5-bit SS-PDU type; the remainder is defined per service.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsPdu {
+    Tpi(SsTpiPdu),
+    Cf(SsCfPdu),
+    Dgna(SsDgnaPdu),
+    /// Service we have no parser for. Its contents are skipped.
+    Unsupported {
+        ss_type: u8,
+        ss_pdu_type: u8,
+    },
+}
+
+impl SsPdu {
+    /// SS type of a supported service, None for Unsupported
+    pub fn ss_type(&self) -> Option<SsType> {
+        match self {
+            SsPdu::Tpi(_) => Some(SsType::Tpi),
+            SsPdu::Cf(_) => Some(SsType::Cf),
+            SsPdu::Dgna(_) => Some(SsType::Dgna),
+            SsPdu::Unsupported { .. } => None,
+        }
+    }
+
+    /// Parse an SS-PDU, consuming the remainder of the buffer for unsupported services
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let ss_type = buffer.read_field(6, "ss_type")?;
+        let ss_pdu_type = buffer.read_field(5, "ss_pdu_type")? as u8;
+        match SsType::try_from(ss_type) {
+            Ok(SsType::Tpi) => Ok(SsPdu::Tpi(SsTpiPdu::from_bitbuf(ss_pdu_type, buffer)?)),
+            Ok(SsType::Cf) => Ok(SsPdu::Cf(SsCfPdu::from_bitbuf(ss_pdu_type, buffer)?)),
+            Ok(SsType::Dgna) => Ok(SsPdu::Dgna(SsDgnaPdu::from_bitbuf(ss_pdu_type, buffer)?)),
+            Err(_) => {
+                buffer.seek_rel(buffer.get_len_remaining() as isize);
+                Ok(SsPdu::Unsupported {
+                    ss_type: ss_type as u8,
+                    ss_pdu_type,
+                })
+            }
+        }
+    }
+
+    /// Serialize this SS-PDU. For Unsupported, only the header is written.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        match self {
+            SsPdu::Tpi(pdu) => {
+                buffer.write_bits(SsType::Tpi.into_raw(), 6);
+                pdu.to_bitbuf(buffer);
+            }
+            SsPdu::Cf(pdu) => {
+                buffer.write_bits(SsType::Cf.into_raw(), 6);
+                pdu.to_bitbuf(buffer);
+            }
+            SsPdu::Dgna(pdu) => {
+                buffer.write_bits(SsType::Dgna.into_raw(), 6);
+                pdu.to_bitbuf(buffer);
+            }
+            SsPdu::Unsupported { ss_type, ss_pdu_type } => {
+                buffer.write_bits(*ss_type as u64, 6);
+                buffer.write_bits(*ss_pdu_type as u64, 5);
+            }
+        }
+    }
+}
+
+impl fmt::Display for SsPdu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SsPdu::Tpi(pdu) => write!(f, "{}", pdu),
+            SsPdu::Cf(pdu) => write!(f, "{}", pdu),
+            SsPdu::Dgna(pdu) => write!(f, "{}", pdu),
+            SsPdu::Unsupported { ss_type, ss_pdu_type } => {
+                write!(f, "SsPdu::Unsupported {{ ss_type: {} ss_pdu_type: {} }}", ss_type, ss_pdu_type)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmce::enums::cf_condition::CfCondition;
+
+    fn roundtrip(pdu: SsPdu) {
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        let parsed = SsPdu::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(parsed, pdu);
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+
+    #[test]
+    fn test_ss_pdu_roundtrip() {
+        roundtrip(SsPdu::Cf(SsCfPdu::Activate {
+            condition: CfCondition::Busy,
+            forwarded_to_ssi: 2040815,
+        }));
+        roundtrip(SsPdu::Cf(SsCfPdu::Result {
+            condition: CfCondition::Unconditional,
+            accepted: true,
+            forwarded_to_ssi: None,
+        }));
+        roundtrip(SsPdu::Dgna(SsDgnaPdu::AssignAck { gssi: 91, accepted: true }));
+        roundtrip(SsPdu::Dgna(SsDgnaPdu::AssignRequest { gssi: 91, issi: 1000001 }));
+        roundtrip(SsPdu::Dgna(SsDgnaPdu::RequestResult {
+            gssi: 91,
+            issi: 1000001,
+            accepted: false,
+        }));
+        roundtrip(SsPdu::Tpi(SsTpiPdu::Result {
+            call_identifier: 0x3fff,
+            talking_party_ssi: Some(1000001),
+        }));
+    }
+
+    #[test]
+    fn test_ss_pdu_unsupported() {
+        let mut buf = BitBuffer::from_bitstr("000001000101011");
+        let parsed = SsPdu::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(
+            parsed,
+            SsPdu::Unsupported {
+                ss_type: 1,
+                ss_pdu_type: 2
+            }
+        );
+        assert_eq!(buf.get_len_remaining(), 0);
+
+        // Unknown SS-PDU type of a supported service is an error
+        let mut buf = BitBuffer::from_bitstr("000100111110000000000000000000000000");
+        assert!(SsPdu::from_bitbuf(&mut buf).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, expect_failed, pdu_parse_error::PduParseErr};
+
+/// SS-PDUs of Talking Party Identification (SS-TPI), carried in U-FACILITY / D-FACILITY.
+/// Each PDU starts with a 5-bit SS-PDU type, followed by the 14-bit call identifier.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SsTpiPdu {
+    /// MS -> SwMI: request the identity of the party currently talking in a call
+    Interrogate { call_identifier: u16 },
+    /// SwMI -> MS: talking party of the call. talking_party_ssi is present (1-bit flag
+    /// followed by 24 bits) while someone holds the floor.
+    Result {
+        call_identifier: u16,
+        talking_party_ssi: Option<u32>,
+    },
+}
+
+impl SsTpiPdu {
+    pub fn ss_pdu_type(&self) -> u8 {
+        match self {
+            SsTpiPdu::Interrogate { .. } => 0,
+            SsTpiPdu::Result { .. } => 1,
+        }
+    }
+
+    /// Parse the PDU contents following the SS-PDU type
+    pub fn from_bitbuf(ss_pdu_type: u8, buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        match ss_pdu_type {
+            0 => Ok(SsTpiPdu::Interrogate {
+                call_identifier: buffer.read_field(14, "call_identifier")? as u16,
+            }),
+            1 => {
+                let call_identifier = buffer.read_field(14, "call_identifier")? as u16;
+                let talking_party_ssi = if buffer.read_field(1, "talking_party_present")? == 1 {
+                    Some(buffer.read_field(24, "talking_party_ssi")? as u32)
+                } else {
+                    None
+                };
+                Ok(SsTpiPdu::Result {
+                    call_identifier,
+                    talking_party_ssi,
+                })
+            }
+            _ => expect_failed!(ss_pdu_type as u64, "ss_pdu_type"),
+        }
+    }
+
+    /// Serialize SS-PDU type and contents
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        buffer.write_bits(self.ss_pdu_type() as u64, 5);
+        match self {
+            SsTpiPdu::Interrogate { call_identifier } => {
+                buffer.write_bits(*call_identifier as u64, 14);
+            }
+            SsTpiPdu::Result {
+                call_identifier,
+                talking_party_ssi,
+            } => {
+                buffer.write_bits(*call_identifier as u64, 14);
+                buffer.write_bit(talking_party_ssi.is_some() as u8);
+                if let Some(ssi) = talking_party_ssi {
+                    buffer.write_bits(*ssi as u64, 24);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for SsTpiPdu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SsTpiPdu::{:?}", self)
+    }
+}
@@ -58,7 +58,9 @@ impl CmceFunctionNotSupported {
         };
         // Conditional
         let received_pdu_extract = if function_not_supported_pointer != 0 {
-            unimplemented!();
+            return Err(PduParseErr::NotImplemented {
+                field: Some("received_pdu_extract"),
+            });
             Some(buffer.read_field(999, "received_pdu_extract")?)
         } else {
             None
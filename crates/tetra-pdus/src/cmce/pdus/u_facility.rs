@@ -1,7 +1,7 @@
 use core::fmt;
 
 use crate::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
-use tetra_core::typed_pdu_fields::*;
+use crate::cmce::fields::ss_pdu::SsPdu;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 /// Representation of the U-FACILITY PDU (Clause 14.7.2.5).
@@ -11,39 +11,34 @@ use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 // note 1: Contents of this PDU shall be defined by SS protocols.
 #[derive(Debug)]
-pub struct UFacility {}
+pub struct UFacility {
+    /// SS-PDU, see note 1
+    pub ss_pdu: SsPdu,
+}
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl UFacility {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(5, "pdu_type")?;
         expect_pdu_type!(pdu_type, CmcePduTypeUl::UFacility)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // The SS-PDU makes up the remainder of the PDU
+        let ss_pdu = SsPdu::from_bitbuf(buffer)?;
 
-        Ok(UFacility {})
+        Ok(UFacility { ss_pdu })
     }
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UFacility.into_raw(), 5);
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
+        self.ss_pdu.to_bitbuf(buffer);
         Ok(())
     }
 }
 
 impl fmt::Display for UFacility {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "UFacility {{ }}",)
+        write!(f, "UFacility {{ ss_pdu: {} }}", self.ss_pdu)
     }
 }
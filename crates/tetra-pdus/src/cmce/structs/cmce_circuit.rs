@@ -28,6 +28,9 @@ pub struct CmceCircuit {
     /// Direction
     pub direction: Direction,
 
+    /// Carrier index on which this circuit exists, 0 for the main carrier
+    pub carrier: u8,
+
     /// Timeslot in which this circuit exists
     pub ts: u8,
 
@@ -50,6 +53,8 @@ pub struct CmceCircuit {
     pub speech_service: Option<u8>,
     /// Whether end-to-end encryption is enabled on this circuit
     pub etee_encrypted: bool,
+    /// Duplex calls only: timeslot of the other party's circuit on the same carrier, whose DL carries our UL traffic
+    pub peer_ts: Option<u8>,
 }
 
 // impl CmceCircuit {
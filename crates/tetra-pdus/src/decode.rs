@@ -0,0 +1,863 @@
+//! Stateless decoding of MAC blocks and TM-SDUs through the UMAC, LLC, MLE, MM, CMCE and SNDCP layers.
+//!
+//! Unlike the stack entities, nothing here keeps link state or generates responses. This makes it
+//! suitable for passively inspecting traffic, e.g. in monitor mode or in offline tooling.
+
+use tetra_core::{BitBuffer, Direction, pdu_parse_error::PduParseErr};
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+
+use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, cmce_pdu_type_ul::CmcePduTypeUl};
+use crate::cmce::pdus::*;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+use crate::llc::pdus::{bl_ack::BlAck, bl_adata::BlAdata, bl_data::BlData, bl_udata::BlUdata};
+use crate::mle::enums::{
+    mle_pdu_type_dl::MlePduTypeDl, mle_pdu_type_ul::MlePduTypeUl, mle_protocol_discriminator::MleProtocolDiscriminator,
+};
+use crate::mle::pdus::*;
+use crate::mm::enums::{authentication_sub_type::AuthenticationSubType, mm_pdu_type_dl::MmPduTypeDl, mm_pdu_type_ul::MmPduTypeUl};
+use crate::mm::pdus::*;
+use crate::sndcp::enums::{sn_pdu_type_dl::SnPduTypeDl, sn_pdu_type_ul::SnPduTypeUl};
+use crate::sndcp::pdus::*;
+use crate::umac::enums::{broadcast_type::BroadcastType, mac_pdu_type::MacPduType};
+use crate::umac::pdus::*;
+
+/// Protocol layer a decoded PDU belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodedLayer {
+    Umac,
+    Llc,
+    Mle,
+    Mm,
+    Cmce,
+    Sndcp,
+}
+
+/// A single PDU extracted from a TM-SDU
+#[derive(Debug, Clone)]
+pub struct DecodedPdu {
+    pub layer: DecodedLayer,
+    /// PDU type name, e.g. "DLocationUpdateAccept". TM-SDU fragments, which can't be decoded by themselves, are
+    /// named "TmSduFragment" and hold the fragment bits.
+    pub name: String,
+    /// Debug representation of the parsed PDU, or a description of why parsing failed
+    pub contents: Result<String, String>,
+}
+
+impl DecodedPdu {
+    fn ok(layer: DecodedLayer, name: impl Into<String>, contents: String) -> Self {
+        Self {
+            layer,
+            name: name.into(),
+            contents: Ok(contents),
+        }
+    }
+
+    fn err(layer: DecodedLayer, name: impl Into<String>, contents: String) -> Self {
+        Self {
+            layer,
+            name: name.into(),
+            contents: Err(contents),
+        }
+    }
+}
+
+/// Parses a PDU with its from_bitbuf function and wraps the outcome in a DecodedPdu
+macro_rules! decode_pdu {
+    ($layer:expr, $ty:ty, $buf:expr $(, $arg:expr)*) => {{
+        // Strip the module path from the type name
+        let name = stringify!($ty).rsplit(':').next().unwrap_or_default().trim();
+        let r: Result<$ty, PduParseErr> = <$ty>::from_bitbuf($buf $(, $arg)*);
+        match r {
+            Ok(pdu) => DecodedPdu::ok($layer, name, format!("{:?}", pdu)),
+            Err(e) => DecodedPdu::err($layer, name, format!("{:?} {}", e, $buf.dump_bin())),
+        }
+    }};
+}
+
+/// Parses a MAC PDU header with parse, recording the outcome in ret. Unlike decode_pdu, returns the parsed PDU,
+/// as its fields determine what follows the header.
+fn parse_mac_header<T: std::fmt::Debug>(
+    ret: &mut Vec<DecodedPdu>,
+    name: &str,
+    buf: &mut BitBuffer,
+    parse: fn(&mut BitBuffer) -> Result<T, PduParseErr>,
+) -> Option<T> {
+    match parse(buf) {
+        Ok(pdu) => {
+            ret.push(DecodedPdu::ok(DecodedLayer::Umac, name, format!("{:?}", pdu)));
+            Some(pdu)
+        }
+        Err(e) => {
+            ret.push(DecodedPdu::err(DecodedLayer::Umac, name, format!("{:?} {}", e, buf.dump_bin())));
+            None
+        }
+    }
+}
+
+/// What follows a MAC PDU header
+enum MacSdu {
+    /// Nothing, as for a null PDU
+    None,
+    /// A TM-SDU we can't decode without the key
+    Encrypted,
+    /// A complete TM-SDU
+    Complete,
+    /// Part of a TM-SDU, which can only be decoded once reassembled
+    Fragment,
+}
+
+/// Decodes a MAC block received on the given logical channel. Returns all MAC PDUs in the block, each followed
+/// by the PDUs of the TM-SDU it carries. Fragmented TM-SDUs are not reassembled; their fragments are returned as is.
+pub fn decode_mac_block(block: &BitBuffer, lchan: LogicalChannel, direction: Direction) -> Vec<DecodedPdu> {
+    const L: DecodedLayer = DecodedLayer::Umac;
+    let mut buf = BitBuffer::from_bitbuffer_pos(block);
+
+    match lchan {
+        // Frame 18 carries ACCESS-ASSIGN-FR18 instead, which we can't tell apart without knowing the frame number
+        LogicalChannel::Aach => vec![decode_pdu!(L, access_assign::AccessAssign, &mut buf)],
+        LogicalChannel::Bsch => {
+            let mac_sync = decode_pdu!(L, mac_sync::MacSync, &mut buf);
+            if mac_sync.contents.is_err() {
+                return vec![mac_sync];
+            }
+            vec![mac_sync, decode_pdu!(DecodedLayer::Mle, d_mle_sync::DMleSync, &mut buf)]
+        }
+        LogicalChannel::Bnch | LogicalChannel::SchF | LogicalChannel::SchHd | LogicalChannel::SchHu | LogicalChannel::Stch => {
+            let mut ret = Vec::new();
+
+            // Iterate until no more MAC PDUs left in the block
+            loop {
+                let orig_start = buf.get_raw_start();
+                if !decode_mac_pdu(&mut ret, &mut buf, lchan, direction) {
+                    break;
+                }
+                // If start was not updated, or less bits remain than a null PDU takes, the block is done
+                if buf.get_raw_start() == orig_start || buf.get_len() < 16 {
+                    break;
+                }
+            }
+            ret
+        }
+        _ => vec![DecodedPdu::err(L, format!("{:?}", lchan), "not supported".to_string())],
+    }
+}
+
+/// Decodes the MAC PDU at the start of the window and what it carries, then moves the window past it.
+/// Returns false if the rest of the block can't be decoded.
+fn decode_mac_pdu(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer, lchan: LogicalChannel, direction: Direction) -> bool {
+    const L: DecodedLayer = DecodedLayer::Umac;
+    let Some(bits) = buf.peek_bits(3) else {
+        ret.push(DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin())));
+        return false;
+    };
+
+    // Clause 21.4.1; SCH/HU needs only 1 bit for a single subtype distinction
+    if lchan == LogicalChannel::SchHu {
+        return if (bits >> 2) & 1 == 0 {
+            decode_mac_access(ret, buf)
+        } else {
+            decode_mac_end_hu(ret, buf)
+        };
+    }
+
+    let Ok(pdu_type) = MacPduType::try_from(bits >> 1) else {
+        ret.push(DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits >> 1)));
+        return false;
+    };
+    let is_dl = direction == Direction::Dl;
+    match pdu_type {
+        MacPduType::MacResourceMacData if is_dl => decode_mac_resource(ret, buf),
+        MacPduType::MacResourceMacData => decode_mac_data(ret, buf),
+        // Third bit designates MAC-FRAG versus MAC-END
+        // Third bit designates MAC-FRAG versus MAC-END
+        MacPduType::MacFragMacEnd if bits & 1 == 0 => decode_mac_frag(ret, buf, direction),
+        MacPduType::MacFragMacEnd if is_dl => decode_mac_end_dl(ret, buf),
+        MacPduType::MacFragMacEnd => decode_mac_end_ul(ret, buf),
+        MacPduType::Broadcast if is_dl => decode_broadcast(ret, buf),
+        MacPduType::SuppMacUSignal if lchan == LogicalChannel::Stch => decode_mac_u_signal(ret, buf, direction),
+        MacPduType::SuppMacUSignal if bits & 1 == 0 => decode_mac_blck(ret, buf, direction),
+        _ => {
+            ret.push(DecodedPdu::err(
+                L,
+                pdu_type.to_string(),
+                format!("not supported on {:?} {:?}", direction, lchan),
+            ));
+            false
+        }
+    }
+}
+
+/// Returns the number of fill bits at the end of the first pdu_len_bits of the window, not counting into the header
+fn count_fill_bits(buf: &BitBuffer, pdu_len_bits: usize) -> usize {
+    (buf.get_pos()..pdu_len_bits)
+        .rev()
+        .find(|&i| buf.peek_bits_startoffset(i, 1) == Some(1))
+        .map_or(0, |i| pdu_len_bits - i)
+}
+
+/// Restricts the window to the MAC PDU of pdu_len_bits minus its fill bits, and decodes what follows the
+/// already parsed header. Then moves the window past the MAC PDU. Returns false if the length is invalid.
+fn decode_mac_sdu(
+    ret: &mut Vec<DecodedPdu>,
+    buf: &mut BitBuffer,
+    pdu_len_bits: usize,
+    fill_bits: bool,
+    sdu: MacSdu,
+    direction: Direction,
+) -> bool {
+    let pdu_len_bits = pdu_len_bits.min(buf.get_len());
+    if pdu_len_bits < buf.get_pos() {
+        ret.push(DecodedPdu::err(
+            DecodedLayer::Umac,
+            "?",
+            format!("pdu length {} shorter than header {}", pdu_len_bits, buf.get_pos()),
+        ));
+        return false;
+    }
+    let num_fill_bits = if fill_bits { count_fill_bits(buf, pdu_len_bits) } else { 0 };
+
+    let orig_end = buf.get_raw_end();
+    buf.set_raw_end(buf.get_raw_start() + pdu_len_bits - num_fill_bits);
+    if buf.get_len_remaining() > 0 {
+        match sdu {
+            MacSdu::None => {}
+            MacSdu::Encrypted => ret.push(DecodedPdu::err(DecodedLayer::Llc, "?", "encrypted".to_string())),
+            MacSdu::Complete => ret.extend(decode_tm_sdu(buf, direction)),
+            MacSdu::Fragment => ret.push(DecodedPdu::ok(
+                DecodedLayer::Umac,
+                "TmSduFragment",
+                BitBuffer::from_bitbuffer_pos(buf).to_bitstr(),
+            )),
+        }
+    }
+
+    buf.set_raw_end(orig_end);
+    buf.set_raw_pos(buf.get_raw_start() + pdu_len_bits);
+    buf.set_raw_start(buf.get_raw_pos());
+    true
+}
+
+fn decode_mac_resource(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacResource", buf, mac_resource::MacResource::from_bitbuf) else {
+        return false;
+    };
+    let pdu_len_bits = match pdu.length_ind {
+        0b000010..=0b111001 => pdu.length_ind as usize * 8,
+        // Second half slot stolen, or start of fragmentation
+        0b111110 | 0b111111 => buf.get_len(),
+        _ => {
+            ret.push(DecodedPdu::err(
+                DecodedLayer::Umac,
+                "MacResource",
+                format!("invalid length_ind {}", pdu.length_ind),
+            ));
+            return false;
+        }
+    };
+    let sdu = if pdu.is_null_pdu() {
+        MacSdu::None
+    } else if pdu.encryption_mode > 0 {
+        MacSdu::Encrypted
+    } else if pdu.length_ind == 0b111111 {
+        MacSdu::Fragment
+    } else {
+        MacSdu::Complete
+    };
+    decode_mac_sdu(ret, buf, pdu_len_bits, pdu.fill_bits, sdu, Direction::Dl)
+}
+
+fn decode_mac_data(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacData", buf, mac_data::MacData::from_bitbuf) else {
+        return false;
+    };
+    let (pdu_len_bits, is_frag_start, is_null_pdu) = match pdu.length_ind {
+        Some(0b000000) => (37, false, true),
+        Some(len_ind @ 0b000010..0b111000) => (len_ind as usize * 8, false, false),
+        Some(0b111110) => (buf.get_len(), false, false),
+        Some(0b111111) => (buf.get_len(), true, false),
+        Some(len_ind) => {
+            ret.push(DecodedPdu::err(
+                DecodedLayer::Umac,
+                "MacData",
+                format!("invalid length_ind {}", len_ind),
+            ));
+            return false;
+        }
+        // Capacity request
+        None => (buf.get_len(), pdu.frag_flag.unwrap_or(false), false),
+    };
+    let sdu = if is_null_pdu {
+        MacSdu::None
+    } else if pdu.encrypted {
+        MacSdu::Encrypted
+    } else if is_frag_start {
+        MacSdu::Fragment
+    } else {
+        MacSdu::Complete
+    };
+    decode_mac_sdu(ret, buf, pdu_len_bits, pdu.fill_bits, sdu, Direction::Ul)
+}
+
+fn decode_mac_access(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacAccess", buf, mac_access::MacAccess::from_bitbuf) else {
+        return false;
+    };
+    let pdu_len_bits = match pdu.length_ind {
+        Some(0) => 36,
+        Some(length_ind) => length_ind as usize * 8,
+        // Capacity request, fills slot
+        None => buf.get_len(),
+    };
+    let sdu = if pdu.is_null_pdu() {
+        MacSdu::None
+    } else if pdu.encrypted {
+        MacSdu::Encrypted
+    } else if pdu.is_frag_start() {
+        MacSdu::Fragment
+    } else {
+        MacSdu::Complete
+    };
+    decode_mac_sdu(ret, buf, pdu_len_bits, pdu.fill_bits, sdu, Direction::Ul)
+}
+
+/// MAC-FRAG, in either direction. Carries a TM-SDU fragment filling the rest of the block.
+fn decode_mac_frag(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer, direction: Direction) -> bool {
+    let fill_bits = if direction == Direction::Dl {
+        let Some(pdu) = parse_mac_header(ret, "MacFragDl", buf, mac_frag_dl::MacFragDl::from_bitbuf) else {
+            return false;
+        };
+        pdu.fill_bits
+    } else {
+        let Some(pdu) = parse_mac_header(ret, "MacFragUl", buf, mac_frag_ul::MacFragUl::from_bitbuf) else {
+            return false;
+        };
+        pdu.fill_bits
+    };
+    let pdu_len_bits = buf.get_len();
+    decode_mac_sdu(ret, buf, pdu_len_bits, fill_bits, MacSdu::Fragment, direction)
+}
+
+fn decode_mac_end_dl(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacEndDl", buf, mac_end_dl::MacEndDl::from_bitbuf) else {
+        return false;
+    };
+    if pdu.length_ind == 0 {
+        ret.push(DecodedPdu::err(DecodedLayer::Umac, "MacEndDl", "reserved length_ind 0".to_string()));
+        return false;
+    }
+    decode_mac_sdu(
+        ret,
+        buf,
+        pdu.length_ind as usize * 8,
+        pdu.fill_bits,
+        MacSdu::Fragment,
+        Direction::Dl,
+    )
+}
+
+fn decode_mac_end_ul(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacEndUl", buf, mac_end_ul::MacEndUl::from_bitbuf) else {
+        return false;
+    };
+    let pdu_len_bits = pdu.length_ind.map_or(buf.get_len(), |l| l as usize * 8);
+    decode_mac_sdu(ret, buf, pdu_len_bits, pdu.fill_bits, MacSdu::Fragment, Direction::Ul)
+}
+
+fn decode_mac_end_hu(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    let Some(pdu) = parse_mac_header(ret, "MacEndHu", buf, mac_end_hu::MacEndHu::from_bitbuf) else {
+        return false;
+    };
+    if pdu.length_ind == Some(0) {
+        // Table 21.44: length indication 0 is reserved
+        ret.push(DecodedPdu::err(DecodedLayer::Umac, "MacEndHu", "reserved length_ind 0".to_string()));
+        return false;
+    }
+    let pdu_len_bits = pdu.length_ind.map_or(buf.get_len(), |l| l as usize * 8);
+    decode_mac_sdu(ret, buf, pdu_len_bits, pdu.fill_bits, MacSdu::Fragment, Direction::Ul)
+}
+
+/// MAC-U-SIGNAL on STCH. Carries a TM-SDU filling the rest of the block, in either direction.
+fn decode_mac_u_signal(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer, direction: Direction) -> bool {
+    if parse_mac_header(ret, "MacUSignal", buf, mac_u_signal::MacUSignal::from_bitbuf).is_none() {
+        return false;
+    }
+    let pdu_len_bits = buf.get_len();
+    decode_mac_sdu(ret, buf, pdu_len_bits, false, MacSdu::Complete, direction)
+}
+
+/// MAC-D-BLCK or MAC-U-BLCK. Carries a TM-SDU filling the rest of the block.
+fn decode_mac_blck(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer, direction: Direction) -> bool {
+    let (fill_bits, encrypted) = if direction == Direction::Dl {
+        let Some(pdu) = parse_mac_header(ret, "MacDBlck", buf, mac_d_blck::MacDBlck::from_bitbuf) else {
+            return false;
+        };
+        (pdu.fill_bits, pdu.encryption_mode > 0)
+    } else {
+        let Some(pdu) = parse_mac_header(ret, "MacUBlck", buf, mac_u_blck::MacUBlck::from_bitbuf) else {
+            return false;
+        };
+        (pdu.fill_bits, pdu.encrypted)
+    };
+    let sdu = if encrypted { MacSdu::Encrypted } else { MacSdu::Complete };
+    let pdu_len_bits = buf.get_len();
+    decode_mac_sdu(ret, buf, pdu_len_bits, fill_bits, sdu, direction)
+}
+
+fn decode_broadcast(ret: &mut Vec<DecodedPdu>, buf: &mut BitBuffer) -> bool {
+    const L: DecodedLayer = DecodedLayer::Umac;
+    let Some(bits) = buf.peek_bits_posoffset(2, 2) else {
+        ret.push(DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin())));
+        return false;
+    };
+    match BroadcastType::try_from(bits) {
+        Ok(BroadcastType::Sysinfo) => {
+            if parse_mac_header(ret, "MacSysinfo", buf, mac_sysinfo::MacSysinfo::from_bitbuf).is_none() {
+                return false;
+            }
+            // The remainder of the block is a D-MLE-SYSINFO TM-SDU
+            ret.push(decode_pdu!(DecodedLayer::Mle, d_mle_sysinfo::DMleSysinfo, buf));
+            let end = buf.get_raw_end();
+            buf.set_raw_pos(end);
+            buf.set_raw_start(end);
+            true
+        }
+        Ok(BroadcastType::AccessDefine) => {
+            if parse_mac_header(ret, "AccessDefine", buf, access_define::AccessDefine::from_bitbuf).is_none() {
+                return false;
+            }
+            let pos = buf.get_raw_pos();
+            buf.set_raw_start(pos);
+            true
+        }
+        Ok(bcast_type) => {
+            ret.push(DecodedPdu::err(L, bcast_type.to_string(), "not supported".to_string()));
+            false
+        }
+        Err(_) => {
+            ret.push(DecodedPdu::err(L, "?", format!("invalid broadcast type: {}", bits)));
+            false
+        }
+    }
+}
+
+/// Decodes a TM-SDU (the LLC PDU carried in a MAC block) into its constituent layers.
+/// Returns the PDUs found, from the LLC downwards. Decoding stops at the first layer that can't be parsed.
+pub fn decode_tm_sdu(sdu: &BitBuffer, direction: Direction) -> Vec<DecodedPdu> {
+    let mut ret = Vec::new();
+    let mut buf = BitBuffer::from_bitbuffer_pos(sdu);
+
+    let Some(bits) = buf.peek_bits(4) else {
+        ret.push(DecodedPdu::err(
+            DecodedLayer::Llc,
+            "?",
+            format!("insufficient bits: {}", buf.dump_bin()),
+        ));
+        return ret;
+    };
+    let Ok(pdu_type) = LlcPduType::try_from(bits) else {
+        ret.push(DecodedPdu::err(DecodedLayer::Llc, "?", format!("invalid pdu type: {}", bits)));
+        return ret;
+    };
+
+    let (decoded, has_fcs) = match pdu_type {
+        LlcPduType::BlAdata | LlcPduType::BlAdataFcs => (
+            decode_pdu!(DecodedLayer::Llc, BlAdata, &mut buf),
+            pdu_type == LlcPduType::BlAdataFcs,
+        ),
+        LlcPduType::BlData | LlcPduType::BlDataFcs => (decode_pdu!(DecodedLayer::Llc, BlData, &mut buf), pdu_type == LlcPduType::BlDataFcs),
+        LlcPduType::BlUdata | LlcPduType::BlUdataFcs => (
+            decode_pdu!(DecodedLayer::Llc, BlUdata, &mut buf),
+            pdu_type == LlcPduType::BlUdataFcs,
+        ),
+        LlcPduType::BlAck | LlcPduType::BlAckFcs => (decode_pdu!(DecodedLayer::Llc, BlAck, &mut buf), pdu_type == LlcPduType::BlAckFcs),
+        _ => {
+            ret.push(DecodedPdu::err(
+                DecodedLayer::Llc,
+                pdu_type.to_string(),
+                "not supported".to_string(),
+            ));
+            return ret;
+        }
+    };
+    let ok = decoded.contents.is_ok();
+    ret.push(decoded);
+    if !ok || matches!(pdu_type, LlcPduType::BlAck | LlcPduType::BlAckFcs) {
+        return ret;
+    }
+
+    // Exclude trailing FCS from the TL-SDU
+    if has_fcs {
+        if buf.get_len_remaining() < 32 {
+            return ret;
+        }
+        buf.set_raw_end(buf.get_raw_end() - 32);
+    }
+    if buf.get_len_remaining() == 0 {
+        return ret;
+    }
+
+    buf.set_raw_start(buf.get_raw_pos());
+    ret.extend(decode_tl_sdu(&mut buf, direction));
+    ret
+}
+
+/// Decodes a TL-SDU, starting at the MLE protocol discriminator
+pub fn decode_tl_sdu(buf: &mut BitBuffer, direction: Direction) -> Vec<DecodedPdu> {
+    let Some(bits) = buf.read_bits(3) else {
+        return vec![DecodedPdu::err(
+            DecodedLayer::Mle,
+            "?",
+            format!("insufficient bits: {}", buf.dump_bin()),
+        )];
+    };
+    let Ok(pd) = MleProtocolDiscriminator::try_from(bits) else {
+        return vec![DecodedPdu::err(
+            DecodedLayer::Mle,
+            "?",
+            format!("invalid protocol discriminator: {}", bits),
+        )];
+    };
+
+    let decoded = match pd {
+        MleProtocolDiscriminator::Mm => decode_mm(buf, direction),
+        MleProtocolDiscriminator::Cmce => decode_cmce(buf, direction),
+        MleProtocolDiscriminator::Mle => decode_mle(buf, direction),
+        MleProtocolDiscriminator::Sndcp => decode_sndcp(buf, direction),
+        MleProtocolDiscriminator::TetraManagementEntity => DecodedPdu::err(DecodedLayer::Mle, "Tme", "not supported".to_string()),
+    };
+    vec![decoded]
+}
+
+fn decode_mle(buf: &mut BitBuffer, direction: Direction) -> DecodedPdu {
+    const L: DecodedLayer = DecodedLayer::Mle;
+    let Some(bits) = buf.peek_bits(3) else {
+        return DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin()));
+    };
+
+    if direction == Direction::Dl {
+        let Ok(pdu_type) = MlePduTypeDl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            MlePduTypeDl::DNewCell => decode_pdu!(L, d_new_cell::DNewCell, buf),
+            MlePduTypeDl::DPrepareFail => decode_pdu!(L, d_prepare_fail::DPrepareFail, buf),
+            MlePduTypeDl::DNwrkBroadcast => decode_pdu!(L, d_nwrk_broadcast::DNwrkBroadcast, buf),
+            MlePduTypeDl::DRestoreAck => decode_pdu!(L, d_restore_ack::DRestoreAck, buf),
+            MlePduTypeDl::DRestoreFail => decode_pdu!(L, d_restore_fail::DRestoreFail, buf),
+            MlePduTypeDl::DChannelResponse => decode_pdu!(L, d_channel_response::DChannelResponse, buf),
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    } else {
+        let Ok(pdu_type) = MlePduTypeUl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            MlePduTypeUl::UPrepare => decode_pdu!(L, u_prepare::UPrepare, buf),
+            MlePduTypeUl::URestore => decode_pdu!(L, u_restore::URestore, buf),
+            MlePduTypeUl::UChannelClassAdvice => decode_pdu!(L, u_channel_class_advice::UChannelClassAdvice, buf),
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    }
+}
+
+/// Authentication PDUs share one MM PDU type and are told apart by the 2-bit sub-type following it
+fn peek_authentication_sub_type(buf: &BitBuffer) -> Option<AuthenticationSubType> {
+    let bits = buf.peek_bits(6)?;
+    AuthenticationSubType::try_from(bits & 0x3).ok()
+}
+
+fn decode_mm(buf: &mut BitBuffer, direction: Direction) -> DecodedPdu {
+    const L: DecodedLayer = DecodedLayer::Mm;
+    let Some(bits) = buf.peek_bits(4) else {
+        return DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin()));
+    };
+
+    if direction == Direction::Dl {
+        let Ok(pdu_type) = MmPduTypeDl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            MmPduTypeDl::DAuthentication => match peek_authentication_sub_type(buf) {
+                Some(AuthenticationSubType::Demand) => decode_pdu!(L, d_authentication_demand::DAuthenticationDemand, buf),
+                Some(AuthenticationSubType::Response) => decode_pdu!(L, d_authentication_response::DAuthenticationResponse, buf),
+                Some(AuthenticationSubType::Result) => decode_pdu!(L, d_authentication_result::DAuthenticationResult, buf),
+                Some(AuthenticationSubType::Reject) => decode_pdu!(L, d_authentication_reject::DAuthenticationReject, buf),
+                None => DecodedPdu::err(L, pdu_type.to_string(), format!("insufficient bits: {}", buf.dump_bin())),
+            },
+            MmPduTypeDl::DLocationUpdateAccept => decode_pdu!(L, d_location_update_accept::DLocationUpdateAccept, buf),
+            MmPduTypeDl::DLocationUpdateCommand => decode_pdu!(L, d_location_update_command::DLocationUpdateCommand, buf),
+            MmPduTypeDl::DLocationUpdateReject => decode_pdu!(L, d_location_update_reject::DLocationUpdateReject, buf),
+            MmPduTypeDl::DLocationUpdateProceeding => decode_pdu!(L, d_location_update_proceeding::DLocationUpdateProceeding, buf),
+            MmPduTypeDl::DAttachDetachGroupIdentity => {
+                decode_pdu!(L, d_attach_detach_group_identity::DAttachDetachGroupIdentity, buf)
+            }
+            MmPduTypeDl::DAttachDetachGroupIdentityAcknowledgement => decode_pdu!(
+                L,
+                d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement,
+                buf
+            ),
+            MmPduTypeDl::DMmStatus => decode_pdu!(L, d_mm_status::DMmStatus, buf),
+            MmPduTypeDl::MmPduFunctionNotSupported => {
+                decode_pdu!(L, mm_pdu_function_not_supported::MmPduFunctionNotSupported, buf)
+            }
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    } else {
+        let Ok(pdu_type) = MmPduTypeUl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            MmPduTypeUl::UAuthentication => match peek_authentication_sub_type(buf) {
+                Some(AuthenticationSubType::Demand) => decode_pdu!(L, u_authentication_demand::UAuthenticationDemand, buf),
+                Some(AuthenticationSubType::Response) => decode_pdu!(L, u_authentication_response::UAuthenticationResponse, buf),
+                Some(AuthenticationSubType::Result) => decode_pdu!(L, u_authentication_result::UAuthenticationResult, buf),
+                Some(AuthenticationSubType::Reject) => decode_pdu!(L, u_authentication_reject::UAuthenticationReject, buf),
+                None => DecodedPdu::err(L, pdu_type.to_string(), format!("insufficient bits: {}", buf.dump_bin())),
+            },
+            MmPduTypeUl::ULocationUpdateDemand => decode_pdu!(L, u_location_update_demand::ULocationUpdateDemand, buf),
+            MmPduTypeUl::UItsiDetach => decode_pdu!(L, u_itsi_detach::UItsiDetach, buf),
+            MmPduTypeUl::UMmStatus => decode_pdu!(L, u_mm_status::UMmStatus, buf),
+            MmPduTypeUl::UAttachDetachGroupIdentity => {
+                decode_pdu!(L, u_attach_detach_group_identity::UAttachDetachGroupIdentity, buf)
+            }
+            MmPduTypeUl::UAttachDetachGroupIdentityAcknowledgement => decode_pdu!(
+                L,
+                u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement,
+                buf
+            ),
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    }
+}
+
+fn decode_cmce(buf: &mut BitBuffer, direction: Direction) -> DecodedPdu {
+    const L: DecodedLayer = DecodedLayer::Cmce;
+    let Some(bits) = buf.peek_bits(5) else {
+        return DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin()));
+    };
+
+    if direction == Direction::Dl {
+        let Ok(pdu_type) = CmcePduTypeDl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            CmcePduTypeDl::DAlert => decode_pdu!(L, d_alert::DAlert, buf),
+            CmcePduTypeDl::DCallProceeding => decode_pdu!(L, d_call_proceeding::DCallProceeding, buf),
+            CmcePduTypeDl::DConnect => decode_pdu!(L, d_connect::DConnect, buf),
+            CmcePduTypeDl::DConnectAcknowledge => decode_pdu!(L, d_connect_acknowledge::DConnectAcknowledge, buf),
+            CmcePduTypeDl::DDisconnect => decode_pdu!(L, d_disconnect::DDisconnect, buf),
+            CmcePduTypeDl::DInfo => decode_pdu!(L, d_info::DInfo, buf),
+            CmcePduTypeDl::DRelease => decode_pdu!(L, d_release::DRelease, buf),
+            CmcePduTypeDl::DSetup => decode_pdu!(L, d_setup::DSetup, buf),
+            CmcePduTypeDl::DStatus => decode_pdu!(L, d_status::DStatus, buf),
+            CmcePduTypeDl::DTxCeased => decode_pdu!(L, d_tx_ceased::DTxCeased, buf),
+            CmcePduTypeDl::DTxContinue => decode_pdu!(L, d_tx_continue::DTxContinue, buf),
+            CmcePduTypeDl::DTxGranted => decode_pdu!(L, d_tx_granted::DTxGranted, buf),
+            CmcePduTypeDl::DTxWait => decode_pdu!(L, d_tx_wait::DTxWait, buf),
+            CmcePduTypeDl::DTxInterrupt => decode_pdu!(L, d_tx_interrupt::DTxInterrupt, buf),
+            CmcePduTypeDl::DCallRestore => decode_pdu!(L, d_call_restore::DCallRestore, buf),
+            CmcePduTypeDl::DSdsData => decode_pdu!(L, d_sds_data::DSdsData, buf),
+            CmcePduTypeDl::DFacility => decode_pdu!(L, d_facility::DFacility, buf),
+            CmcePduTypeDl::CmceFunctionNotSupported => {
+                decode_pdu!(L, cmce_function_not_supported::CmceFunctionNotSupported, buf)
+            }
+        }
+    } else {
+        let Ok(pdu_type) = CmcePduTypeUl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            CmcePduTypeUl::UAlert => decode_pdu!(L, u_alert::UAlert, buf),
+            CmcePduTypeUl::UConnect => decode_pdu!(L, u_connect::UConnect, buf),
+            CmcePduTypeUl::UDisconnect => decode_pdu!(L, u_disconnect::UDisconnect, buf),
+            CmcePduTypeUl::UInfo => decode_pdu!(L, u_info::UInfo, buf),
+            CmcePduTypeUl::URelease => decode_pdu!(L, u_release::URelease, buf),
+            CmcePduTypeUl::USetup => decode_pdu!(L, u_setup::USetup, buf),
+            CmcePduTypeUl::UStatus => decode_pdu!(L, u_status::UStatus, buf),
+            CmcePduTypeUl::UTxCeased => decode_pdu!(L, u_tx_ceased::UTxCeased, buf),
+            CmcePduTypeUl::UTxDemand => decode_pdu!(L, u_tx_demand::UTxDemand, buf),
+            CmcePduTypeUl::UCallRestore => decode_pdu!(L, u_call_restore::UCallRestore, buf),
+            CmcePduTypeUl::USdsData => decode_pdu!(L, u_sds_data::USdsData, buf),
+            CmcePduTypeUl::UFacility => decode_pdu!(L, u_facility::UFacility, buf),
+            CmcePduTypeUl::CmceFunctionNotSupported => {
+                decode_pdu!(L, cmce_function_not_supported::CmceFunctionNotSupported, buf)
+            }
+        }
+    }
+}
+
+fn decode_sndcp(buf: &mut BitBuffer, direction: Direction) -> DecodedPdu {
+    const L: DecodedLayer = DecodedLayer::Sndcp;
+    let Some(bits) = buf.peek_bits(4) else {
+        return DecodedPdu::err(L, "?", format!("insufficient bits: {}", buf.dump_bin()));
+    };
+
+    if direction == Direction::Dl {
+        let Ok(pdu_type) = SnPduTypeDl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            SnPduTypeDl::SnActivatePdpContextAccept => {
+                decode_pdu!(L, sn_activate_pdp_context_accept::SnActivatePdpContextAccept, buf)
+            }
+            SnPduTypeDl::SnActivatePdpContextReject => {
+                decode_pdu!(L, sn_activate_pdp_context_reject::SnActivatePdpContextReject, buf)
+            }
+            SnPduTypeDl::SnDeactivatePdpContextAccept => {
+                decode_pdu!(L, sn_deactivate_pdp_context_accept::SnDeactivatePdpContextAccept, buf, direction)
+            }
+            SnPduTypeDl::SnDeactivatePdpContextDemand => {
+                decode_pdu!(L, sn_deactivate_pdp_context_demand::SnDeactivatePdpContextDemand, buf, direction)
+            }
+            SnPduTypeDl::SnUnitdata => decode_pdu!(L, sn_unitdata::SnUnitdata, buf),
+            SnPduTypeDl::SnData => decode_pdu!(L, sn_data::SnData, buf),
+            SnPduTypeDl::SnNotSupported => decode_pdu!(L, sn_not_supported::SnNotSupported, buf),
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    } else {
+        let Ok(pdu_type) = SnPduTypeUl::try_from(bits) else {
+            return DecodedPdu::err(L, "?", format!("invalid pdu type: {}", bits));
+        };
+        match pdu_type {
+            SnPduTypeUl::SnActivatePdpContextDemand => {
+                decode_pdu!(L, sn_activate_pdp_context_demand::SnActivatePdpContextDemand, buf)
+            }
+            SnPduTypeUl::SnDeactivatePdpContextAccept => {
+                decode_pdu!(L, sn_deactivate_pdp_context_accept::SnDeactivatePdpContextAccept, buf, direction)
+            }
+            SnPduTypeUl::SnDeactivatePdpContextDemand => {
+                decode_pdu!(L, sn_deactivate_pdp_context_demand::SnDeactivatePdpContextDemand, buf, direction)
+            }
+            SnPduTypeUl::SnUnitdata => decode_pdu!(L, sn_unitdata::SnUnitdata, buf),
+            SnPduTypeUl::SnData => decode_pdu!(L, sn_data::SnData, buf),
+            SnPduTypeUl::SnNotSupported => decode_pdu!(L, sn_not_supported::SnNotSupported, buf),
+            _ => DecodedPdu::err(L, pdu_type.to_string(), "not supported".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::{SsiType, TetraAddress};
+
+    use super::*;
+    use crate::umac::pdus::mac_resource::MacResource;
+
+    /// BL-UDATA, CMCE protocol discriminator, D-SETUP
+    const D_SETUP_SDU: &str = "001001000111000000000001000111000000010011000001001010000110111100010101100010";
+
+    #[test]
+    fn test_decode_bl_udata_d_setup() {
+        let sdu = BitBuffer::from_bitstr(D_SETUP_SDU);
+        let decoded = decode_tm_sdu(&sdu, Direction::Dl);
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].layer, DecodedLayer::Llc);
+        assert_eq!(decoded[0].name, "BlUdata");
+        assert_eq!(decoded[1].layer, DecodedLayer::Cmce);
+        assert_eq!(decoded[1].name, "DSetup");
+        assert!(decoded[1].contents.is_ok());
+    }
+
+    #[test]
+    fn test_decode_truncated_and_garbage() {
+        // Malformed input must yield parse errors, never panics
+        let sdu = D_SETUP_SDU;
+        for len in 0..sdu.len() {
+            for direction in [Direction::Dl, Direction::Ul] {
+                decode_tm_sdu(&BitBuffer::from_bitstr(&sdu[..len]), direction);
+            }
+        }
+
+        let mut state: u32 = 0x1234_5678;
+        for _ in 0..2000 {
+            let bits: String = (0..268)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    if state & 1 == 1 { '1' } else { '0' }
+                })
+                .collect();
+            for direction in [Direction::Dl, Direction::Ul] {
+                decode_tm_sdu(&BitBuffer::from_bitstr(&bits), direction);
+                decode_tl_sdu(&mut BitBuffer::from_bitstr(&bits), direction);
+                for lchan in [
+                    LogicalChannel::Aach,
+                    LogicalChannel::Bsch,
+                    LogicalChannel::Bnch,
+                    LogicalChannel::SchF,
+                    LogicalChannel::SchHd,
+                    LogicalChannel::SchHu,
+                    LogicalChannel::Stch,
+                ] {
+                    decode_mac_block(&BitBuffer::from_bitstr(&bits), lchan, direction);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_llc_type() {
+        let sdu = BitBuffer::from_bitstr("1101");
+        let decoded = decode_tm_sdu(&sdu, Direction::Ul);
+        assert_eq!(decoded.len(), 1);
+        assert!(decoded[0].contents.is_err());
+    }
+
+    #[test]
+    fn test_decode_sndcp() {
+        // SNDCP protocol discriminator, SN-ACTIVATE PDP CONTEXT DEMAND
+        let mut tl_sdu = BitBuffer::from_bitstr("1000000000101010010010000000000");
+        let decoded = decode_tl_sdu(&mut tl_sdu, Direction::Ul);
+        assert_eq!(decoded[0].layer, DecodedLayer::Sndcp);
+        assert_eq!(decoded[0].name, "SnActivatePdpContextDemand");
+        assert!(decoded[0].contents.is_ok());
+
+        // The deactivation PDU types differ per direction
+        let tl_sdu = "10000010000000101010";
+        let decoded = decode_tl_sdu(&mut BitBuffer::from_bitstr(tl_sdu), Direction::Dl);
+        assert_eq!(decoded[0].name, "SnDeactivatePdpContextAccept");
+        assert!(decoded[0].contents.is_ok());
+        let decoded = decode_tl_sdu(&mut BitBuffer::from_bitstr(tl_sdu), Direction::Ul);
+        assert_eq!(decoded[0].name, "SnDeactivatePdpContextDemand");
+    }
+
+    #[test]
+    fn test_decode_mac_block() {
+        // MAC-RESOURCE carrying the D-SETUP, followed by fill bits and a null PDU
+        let mut sdu = BitBuffer::from_bitstr(D_SETUP_SDU);
+        let mut hdr = MacResource {
+            addr: Some(TetraAddress::new(2040814, SsiType::Ssi)),
+            ..Default::default()
+        };
+        let num_fill_bits = hdr.update_len_and_fill_ind(sdu.get_len());
+        assert!(num_fill_bits > 0);
+
+        let mut block = BitBuffer::new(hdr.length_ind as usize * 8 + 16);
+        hdr.to_bitbuf(&mut block);
+        block.copy_bits(&mut sdu, D_SETUP_SDU.len());
+        block.write_bit(1);
+        block.write_zeroes(num_fill_bits - 1);
+        MacResource::null_pdu().to_bitbuf(&mut block);
+        block.seek(0);
+
+        let decoded = decode_mac_block(&block, LogicalChannel::SchF, Direction::Dl);
+        let names: Vec<&str> = decoded.iter().map(|pdu| pdu.name.as_str()).collect();
+        assert_eq!(names, ["MacResource", "BlUdata", "DSetup", "MacResource"]);
+        assert!(decoded.iter().all(|pdu| pdu.contents.is_ok()));
+        assert_eq!(decoded[0].layer, DecodedLayer::Umac);
+        assert_eq!(decoded[2].layer, DecodedLayer::Cmce);
+
+        // Start of fragmentation: the fragment is returned undecoded
+        hdr.length_ind = 0b111111;
+        hdr.fill_bits = false;
+        let mut block = BitBuffer::new(268);
+        hdr.to_bitbuf(&mut block);
+        let hdr_len = block.get_pos();
+        block.seek(0);
+        let decoded = decode_mac_block(&block, LogicalChannel::SchF, Direction::Dl);
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].name, "TmSduFragment");
+        assert_eq!(decoded[1].contents.as_ref().unwrap().len(), 268 - hdr_len);
+    }
+}
@@ -1,8 +1,10 @@
 #![allow(dead_code)]
 
 pub mod cmce;
+pub mod decode;
 pub mod llc;
 pub mod mle;
 pub mod mm;
 pub mod phy;
+pub mod sndcp;
 pub mod umac;
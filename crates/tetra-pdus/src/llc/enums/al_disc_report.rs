@@ -0,0 +1,64 @@
+/// Clause 21.2.3.3 Report, as carried in AL-DISC
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlDiscReport {
+    /// Normal release of the advanced link requested by the sender
+    DisconnectRequest = 0,
+    /// Confirmation of a received disconnect request
+    DisconnectConfirm = 1,
+    /// The requested advanced link service is not supported
+    ServiceNotSupported = 2,
+    /// The proposed advanced link parameters are not acceptable
+    SetupRejected = 3,
+    /// The link failed, e.g. retransmissions were exhausted
+    LinkFailure = 4,
+    Reserved5 = 5,
+    Reserved6 = 6,
+    Reserved7 = 7,
+}
+
+impl std::convert::TryFrom<u64> for AlDiscReport {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlDiscReport::DisconnectRequest),
+            1 => Ok(AlDiscReport::DisconnectConfirm),
+            2 => Ok(AlDiscReport::ServiceNotSupported),
+            3 => Ok(AlDiscReport::SetupRejected),
+            4 => Ok(AlDiscReport::LinkFailure),
+            5 => Ok(AlDiscReport::Reserved5),
+            6 => Ok(AlDiscReport::Reserved6),
+            7 => Ok(AlDiscReport::Reserved7),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AlDiscReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl From<AlDiscReport> for u64 {
+    fn from(e: AlDiscReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlDiscReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlDiscReport::DisconnectRequest => write!(f, "DisconnectRequest"),
+            AlDiscReport::DisconnectConfirm => write!(f, "DisconnectConfirm"),
+            AlDiscReport::ServiceNotSupported => write!(f, "ServiceNotSupported"),
+            AlDiscReport::SetupRejected => write!(f, "SetupRejected"),
+            AlDiscReport::LinkFailure => write!(f, "LinkFailure"),
+            AlDiscReport::Reserved5 => write!(f, "Reserved5"),
+            AlDiscReport::Reserved6 => write!(f, "Reserved6"),
+            AlDiscReport::Reserved7 => write!(f, "Reserved7"),
+        }
+    }
+}
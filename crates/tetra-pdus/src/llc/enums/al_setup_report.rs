@@ -0,0 +1,49 @@
+/// Clause 21.2.3.5 Setup report, as carried in AL-SETUP
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AlSetupReport {
+    /// Request to set up the advanced link with the proposed parameters
+    SetupRequest = 0,
+    /// Acceptance of the advanced link, carrying the negotiated parameters
+    SetupConfirm = 1,
+    Reserved2 = 2,
+    Reserved3 = 3,
+}
+
+impl std::convert::TryFrom<u64> for AlSetupReport {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AlSetupReport::SetupRequest),
+            1 => Ok(AlSetupReport::SetupConfirm),
+            2 => Ok(AlSetupReport::Reserved2),
+            3 => Ok(AlSetupReport::Reserved3),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AlSetupReport {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl From<AlSetupReport> for u64 {
+    fn from(e: AlSetupReport) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AlSetupReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AlSetupReport::SetupRequest => write!(f, "SetupRequest"),
+            AlSetupReport::SetupConfirm => write!(f, "SetupConfirm"),
+            AlSetupReport::Reserved2 => write!(f, "Reserved2"),
+            AlSetupReport::Reserved3 => write!(f, "Reserved3"),
+        }
+    }
+}
@@ -1 +1,3 @@
+pub mod al_disc_report;
+pub mod al_setup_report;
 pub mod llc_pdu_type;
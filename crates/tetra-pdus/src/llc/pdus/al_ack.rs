@@ -0,0 +1,126 @@
+use core::fmt;
+
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.1 AL-ACK and AL-RNR
+/// Acknowledges TL-SDU N(R), either completely or selectively per segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlAck {
+    // 1, set for AL-RNR: the receiver is not ready for more data
+    pub not_ready: bool,
+    // 3
+    pub nr: u8,
+    // 6 bits acknowledgement length, followed by the selective acknowledgement if non-zero.
+    // None acknowledges the whole TL-SDU N(R).
+    pub selective: Option<AlSelectiveAck>,
+}
+
+/// Selective acknowledgement of the segments of a partially received TL-SDU
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlSelectiveAck {
+    // 8, first segment not received. All segments before it were received.
+    pub sr: u8,
+    // Up to 62 bits, bit i set if segment sr + 1 + i was received
+    pub mask: Vec<bool>,
+}
+
+impl AlSelectiveAck {
+    /// Longest mask the 6-bit acknowledgement length can describe
+    pub const MAX_MASK_LEN: usize = 62;
+}
+
+impl AlAck {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlAckAlRnr)?;
+        let_field!(buf, not_ready, 1);
+        let_field!(buf, nr, 3);
+        let_field!(buf, ack_length, 6);
+
+        let selective = if ack_length == 0 {
+            None
+        } else {
+            let_field!(buf, sr, 8);
+            let mut mask = Vec::with_capacity(ack_length as usize - 1);
+            for _ in 1..ack_length {
+                let_field!(buf, received, 1);
+                mask.push(received != 0);
+            }
+            Some(AlSelectiveAck { sr: sr as u8, mask })
+        };
+
+        Ok(AlAck {
+            not_ready: not_ready != 0,
+            nr: nr as u8,
+            selective,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlAckAlRnr.into_raw(), 4);
+        buf.write_bits(self.not_ready as u64, 1);
+        buf.write_bits(self.nr as u64, 3);
+        match &self.selective {
+            None => buf.write_bits(0, 6),
+            Some(sel) => {
+                assert!(sel.mask.len() <= AlSelectiveAck::MAX_MASK_LEN);
+                buf.write_bits(sel.mask.len() as u64 + 1, 6);
+                buf.write_bits(sel.sr as u64, 8);
+                for &received in &sel.mask {
+                    buf.write_bits(received as u64, 1);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for AlAck {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = if self.not_ready { "al_rnr" } else { "al_ack" };
+        write!(f, "{} {{ nr: {}", name, self.nr)?;
+        if let Some(sel) = &self.selective {
+            write!(f, ", sr: {}, mask: ", sel.sr)?;
+            for &received in &sel.mask {
+                write!(f, "{}", received as u8)?;
+            }
+        }
+        write!(f, " }}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(pdu: &AlAck, expected_len: usize) {
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.get_len(), expected_len);
+        buf.seek(0);
+        assert_eq!(&AlAck::from_bitbuf(&mut buf).unwrap(), pdu);
+    }
+
+    #[test]
+    fn test_al_ack_roundtrip() {
+        let complete = AlAck {
+            not_ready: false,
+            nr: 6,
+            selective: None,
+        };
+        roundtrip(&complete, 14);
+
+        let selective = AlAck {
+            not_ready: true,
+            nr: 1,
+            selective: Some(AlSelectiveAck {
+                sr: 3,
+                mask: vec![true, false, true],
+            }),
+        };
+        roundtrip(&selective, 14 + 8 + 3);
+    }
+}
@@ -0,0 +1,87 @@
+use core::fmt;
+
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.2 AL-DATA, AL-DATA-AR, AL-FINAL and AL-FINAL-AR
+/// Header of one segment of a TL-SDU; the segment itself follows.
+/// The final segment ends with the FCS computed over the whole TL-SDU.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlData {
+    // 1, set for AL-FINAL(-AR), the last segment of a TL-SDU
+    pub is_final: bool,
+    // 1, set for the -AR variants, requesting an AL-ACK
+    pub ack_request: bool,
+    // 3, TL-SDU sequence number
+    pub ns: u8,
+    // 8, segment sequence number within the TL-SDU
+    pub ss: u8,
+}
+
+impl AlData {
+    /// Header length in bits
+    pub const HEADER_LEN: usize = 17;
+
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlDataAlFinal)?;
+        let_field!(buf, is_final, 1);
+        let_field!(buf, ack_request, 1);
+        let_field!(buf, ns, 3);
+        let_field!(buf, ss, 8);
+
+        Ok(AlData {
+            is_final: is_final != 0,
+            ack_request: ack_request != 0,
+            ns: ns as u8,
+            ss: ss as u8,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlDataAlFinal.into_raw(), 4);
+        buf.write_bits(self.is_final as u64, 1);
+        buf.write_bits(self.ack_request as u64, 1);
+        buf.write_bits(self.ns as u64, 3);
+        buf.write_bits(self.ss as u64, 8);
+    }
+
+    pub fn name(&self) -> &'static str {
+        match (self.is_final, self.ack_request) {
+            (false, false) => "AL-DATA",
+            (false, true) => "AL-DATA-AR",
+            (true, false) => "AL-FINAL",
+            (true, true) => "AL-FINAL-AR",
+        }
+    }
+}
+
+impl fmt::Display for AlData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {{ ns: {}, ss: {} }}", self.name(), self.ns, self.ss)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_data_roundtrip() {
+        let pdu = AlData {
+            is_final: true,
+            ack_request: true,
+            ns: 5,
+            ss: 200,
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.get_len(), AlData::HEADER_LEN);
+        buf.seek(0);
+        assert_eq!(AlData::from_bitbuf(&mut buf).unwrap(), pdu);
+        assert_eq!(pdu.name(), "AL-FINAL-AR");
+    }
+}
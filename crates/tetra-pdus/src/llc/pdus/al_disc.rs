@@ -0,0 +1,39 @@
+use core::fmt;
+
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, let_field};
+
+use crate::llc::enums::al_disc_report::AlDiscReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.3 AL-DISC
+/// Releases an advanced link, confirms a release, or turns down an AL-SETUP
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlDisc {
+    // 3
+    pub report: AlDiscReport,
+}
+
+impl AlDisc {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlDisc)?;
+        let_field!(buf, report, 3);
+
+        Ok(AlDisc {
+            report: AlDiscReport::try_from(report).unwrap(), // Never fails
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlDisc.into_raw(), 4);
+        buf.write_bits(self.report.into_raw(), 3);
+    }
+}
+
+impl fmt::Display for AlDisc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "al_disc {{ report: {} }}", self.report)
+    }
+}
@@ -0,0 +1,111 @@
+use core::fmt;
+
+use tetra_core::BitBuffer;
+use tetra_core::pdu_parse_error::*;
+use tetra_core::{expect_pdu_type, expect_value, let_field};
+
+use crate::llc::enums::al_setup_report::AlSetupReport;
+use crate::llc::enums::llc_pdu_type::LlcPduType;
+
+/// Clause 21.2.3.5 AL-SETUP
+/// Sent by either side to request an advanced link, and by the responder to accept it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlSetup {
+    // 1, set for the unacknowledged service
+    pub unacknowledged: bool,
+    // 2
+    pub setup_report: AlSetupReport,
+    // 3, N.271 coded as 32 << max_tlsdu_len octets
+    pub max_tlsdu_len: u8,
+    // 4, N.272
+    pub window_size: u8,
+    // 3, N.273
+    pub max_tlsdu_retransmissions: u8,
+    // 4, N.274
+    pub max_segment_retransmissions: u8,
+    // 2, N.264 - 1
+    pub num_timeslots: u8,
+}
+
+impl AlSetup {
+    pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let_field!(buf, pdu_type, 4);
+        expect_pdu_type!(pdu_type, LlcPduType::AlSetup)?;
+
+        let_field!(buf, unacknowledged, 1);
+        let_field!(buf, setup_report, 2);
+        let_field!(buf, max_tlsdu_len, 3);
+        let_field!(buf, window_size, 4);
+        // A window of zero TL-SDUs can never carry data
+        if window_size == 0 {
+            expect_value!(window_size, 1)?;
+        }
+        let_field!(buf, max_tlsdu_retransmissions, 3);
+        let_field!(buf, max_segment_retransmissions, 4);
+        let_field!(buf, num_timeslots, 2);
+
+        Ok(AlSetup {
+            unacknowledged: unacknowledged != 0,
+            setup_report: AlSetupReport::try_from(setup_report).unwrap(), // Never fails
+            max_tlsdu_len: max_tlsdu_len as u8,
+            window_size: window_size as u8,
+            max_tlsdu_retransmissions: max_tlsdu_retransmissions as u8,
+            max_segment_retransmissions: max_segment_retransmissions as u8,
+            num_timeslots: num_timeslots as u8,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buf: &mut BitBuffer) {
+        buf.write_bits(LlcPduType::AlSetup.into_raw(), 4);
+        buf.write_bits(self.unacknowledged as u64, 1);
+        buf.write_bits(self.setup_report.into_raw(), 2);
+        buf.write_bits(self.max_tlsdu_len as u64, 3);
+        buf.write_bits(self.window_size as u64, 4);
+        buf.write_bits(self.max_tlsdu_retransmissions as u64, 3);
+        buf.write_bits(self.max_segment_retransmissions as u64, 4);
+        buf.write_bits(self.num_timeslots as u64, 2);
+    }
+
+    /// Maximum TL-SDU length in octets, as coded in max_tlsdu_len
+    pub fn max_tlsdu_octets(&self) -> usize {
+        32 << self.max_tlsdu_len
+    }
+}
+
+impl fmt::Display for AlSetup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "al_setup {{")?;
+        write!(f, "  unacknowledged: {}", self.unacknowledged)?;
+        write!(f, "  setup_report: {}", self.setup_report)?;
+        write!(f, "  max_tlsdu_len: {}", self.max_tlsdu_len)?;
+        write!(f, "  window_size: {}", self.window_size)?;
+        write!(f, "  max_tlsdu_retransmissions: {}", self.max_tlsdu_retransmissions)?;
+        write!(f, "  max_segment_retransmissions: {}", self.max_segment_retransmissions)?;
+        write!(f, "  num_timeslots: {}", self.num_timeslots)?;
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_setup_roundtrip() {
+        let pdu = AlSetup {
+            unacknowledged: false,
+            setup_report: AlSetupReport::SetupRequest,
+            max_tlsdu_len: 7,
+            window_size: 3,
+            max_tlsdu_retransmissions: 3,
+            max_segment_retransmissions: 3,
+            num_timeslots: 0,
+        };
+        let mut buf = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf);
+        assert_eq!(buf.get_len(), 23);
+        buf.seek(0);
+        assert_eq!(AlSetup::from_bitbuf(&mut buf).unwrap(), pdu);
+        assert_eq!(pdu.max_tlsdu_octets(), 4096);
+    }
+}
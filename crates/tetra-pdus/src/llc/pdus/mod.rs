@@ -1,12 +1,12 @@
+pub mod al_ack; // and AL-RNR
+pub mod al_data; // and AL-DATA-AR/AL-FINAL/AL-FINAL-AR
+pub mod al_disc;
+pub mod al_setup;
 pub mod bl_ack;
 pub mod bl_adata;
 pub mod bl_data;
 pub mod bl_udata;
-// mod al_setup;
-// mod al_data; // and possibly AL-DATA-AR/AL-FINAL/AL-FINAL-AR
 // mod al_udata; // and AL-UFINAL
-// mod al_ack // and AL-RNR
 // mod al_reconnect;
 // mod supp_llc_pdu;
 // mod l2_sig_pdu;
-// mod al_disc;
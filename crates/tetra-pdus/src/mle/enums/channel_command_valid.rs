@@ -0,0 +1,49 @@
+/// Clause 18.5.4 Channel command valid
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ChannelCommandValid {
+    FollowMacChannelChange = 0,
+    ChangeChannelImmediately = 1,
+    NoChannelChange = 2,
+    // Reserved = 3,
+}
+
+impl std::convert::TryFrom<u64> for ChannelCommandValid {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(ChannelCommandValid::FollowMacChannelChange),
+            1 => Ok(ChannelCommandValid::ChangeChannelImmediately),
+            2 => Ok(ChannelCommandValid::NoChannelChange),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ChannelCommandValid {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            ChannelCommandValid::FollowMacChannelChange => 0,
+            ChannelCommandValid::ChangeChannelImmediately => 1,
+            ChannelCommandValid::NoChannelChange => 2,
+        }
+    }
+}
+
+impl From<ChannelCommandValid> for u64 {
+    fn from(e: ChannelCommandValid) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for ChannelCommandValid {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ChannelCommandValid::FollowMacChannelChange => write!(f, "FollowMacChannelChange"),
+            ChannelCommandValid::ChangeChannelImmediately => write!(f, "ChangeChannelImmediately"),
+            ChannelCommandValid::NoChannelChange => write!(f, "NoChannelChange"),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+/// Clause 18.5.8 Fail cause
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FailCause {
+    CellReselectionTypeNotSupported = 0,
+    ServiceTemporarilyUnavailable = 1,
+    ServicePermanentlyUnavailable = 2,
+    // Reserved = 3,
+}
+
+impl std::convert::TryFrom<u64> for FailCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(FailCause::CellReselectionTypeNotSupported),
+            1 => Ok(FailCause::ServiceTemporarilyUnavailable),
+            2 => Ok(FailCause::ServicePermanentlyUnavailable),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FailCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            FailCause::CellReselectionTypeNotSupported => 0,
+            FailCause::ServiceTemporarilyUnavailable => 1,
+            FailCause::ServicePermanentlyUnavailable => 2,
+        }
+    }
+}
+
+impl From<FailCause> for u64 {
+    fn from(e: FailCause) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for FailCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FailCause::CellReselectionTypeNotSupported => write!(f, "CellReselectionTypeNotSupported"),
+            FailCause::ServiceTemporarilyUnavailable => write!(f, "ServiceTemporarilyUnavailable"),
+            FailCause::ServicePermanentlyUnavailable => write!(f, "ServicePermanentlyUnavailable"),
+        }
+    }
+}
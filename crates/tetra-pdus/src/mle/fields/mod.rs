@@ -1 +1,3 @@
 pub mod bs_service_details;
+pub mod neighbour_cell_information_for_ca;
+pub mod sdu;
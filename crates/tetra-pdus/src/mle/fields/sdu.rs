@@ -0,0 +1,20 @@
+use tetra_core::BitBuffer;
+
+/// Reads the SDU carried at the end of an MLE PDU. It is not preceded by a P-bit and takes
+/// the remainder of the PDU, which is consumed.
+pub fn read_sdu(buffer: &mut BitBuffer) -> Option<BitBuffer> {
+    if buffer.get_len_remaining() == 0 {
+        return None;
+    }
+    let sdu = BitBuffer::from_bitbuffer_pos(buffer);
+    buffer.seek_rel(buffer.get_len_remaining() as isize);
+    Some(sdu)
+}
+
+/// Appends an SDU to an MLE PDU, from its start
+pub fn write_sdu(buffer: &mut BitBuffer, sdu: &BitBuffer) {
+    let mut sdu = sdu.clone();
+    sdu.seek(0);
+    let len = sdu.get_len();
+    buffer.copy_bits(&mut sdu, len);
+}
@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::sdu::{read_sdu, write_sdu};
 
 /// Representation of the D-NEW-CELL PDU (Clause 18.4.1.4.2).
 /// Upon receipt from the SwMI the message shall inform the MS-MLE that it can select a new cell as previously indicated in the U-PREPARE or U-PREPARE-DA PDU.
@@ -16,11 +17,9 @@ pub struct DNewCell {
     /// Type1, 2 bits, Channel command valid
     pub channel_command_valid: u8,
     /// Conditional SDU
-    pub sdu: Option<u64>,
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DNewCell {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -29,27 +28,12 @@ impl DNewCell {
 
         // Type1
         let channel_command_valid = buffer.read_field(2, "channel_command_valid")? as u8;
-        // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of the SDU
         let obit = delimiters::read_obit(buffer)?;
 
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
-        } else {
-            None
-        };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, takes the remainder of the PDU
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(DNewCell {
             channel_command_valid,
@@ -63,14 +47,14 @@ impl DNewCell {
         buffer.write_bits(MlePduTypeDl::DNewCell.into_raw(), 3);
         // Type1
         buffer.write_bits(self.channel_command_valid as u64, 2);
-        // TODO FIXME: sdu handling
+
+        // Place o-bit for the SDU
+        delimiters::write_obit(buffer, self.sdu.is_some() as u8);
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -84,3 +68,23 @@ impl fmt::Display for DNewCell {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_new_cell_roundtrip() {
+        let pdu = DNewCell {
+            channel_command_valid: 1,
+            sdu: None,
+        };
+        let mut buffer = BitBuffer::new_autoexpand(8);
+        pdu.to_bitbuf(&mut buffer).unwrap();
+        assert_eq!(buffer.to_bitstr(), "000010");
+        buffer.seek(0);
+        let parsed = DNewCell::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(parsed.channel_command_valid, 1);
+        assert!(parsed.sdu.is_none());
+    }
+}
@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
 
 /// Representation of the D-NWRK-BROADCAST PDU (Clause 18.4.1.4.1).
 /// Upon receipt from the SwMI, the message shall inform the MS-MLE about parameters for the CA serving cell and parameters for one or more CA neighbour cells.
@@ -24,10 +25,9 @@ pub struct DNwrkBroadcast {
     /// Type2, 3 bits, See note 2,
     pub number_of_ca_neighbour_cells: Option<u64>,
     /// Conditional See note 3, condition: number_of_ca_neighbour_cells > Some(0)
-    pub neighbour_cell_information_for_ca: Option<u64>,
+    pub neighbour_cell_information_for_ca: Vec<NeighbourCellInformationForCa>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DNwrkBroadcast {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -48,12 +48,10 @@ impl DNwrkBroadcast {
         let number_of_ca_neighbour_cells = typed::parse_type2_generic(obit, buffer, 3, "number_of_ca_neighbour_cells")?;
 
         // Conditional
-        let neighbour_cell_information_for_ca = if obit && number_of_ca_neighbour_cells > Some(0) {
-            unimplemented!();
-            Some(buffer.read_field(999, "neighbour_cell_information_for_ca")?)
-        } else {
-            None
-        };
+        let mut neighbour_cell_information_for_ca = Vec::new();
+        for _ in 0..number_of_ca_neighbour_cells.unwrap_or(0) {
+            neighbour_cell_information_for_ca.push(NeighbourCellInformationForCa::from_bitbuf(buffer)?);
+        }
 
         // MLE PDUs do not use M-bits (Annex E.2.1) — no trailing delimiter to read
 
@@ -68,6 +66,13 @@ impl DNwrkBroadcast {
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.number_of_ca_neighbour_cells.unwrap_or(0) != self.neighbour_cell_information_for_ca.len() as u64 {
+            return Err(PduParseErr::Inconsistency {
+                field: "number_of_ca_neighbour_cells",
+                reason: "does not match the number of neighbour cell information elements",
+            });
+        }
+
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DNwrkBroadcast.into_raw(), 3);
         // Type1
@@ -89,9 +94,8 @@ impl DNwrkBroadcast {
         typed::write_type2_generic(obit, buffer, self.number_of_ca_neighbour_cells, 3);
 
         // Conditional
-        if let Some(ref _value) = self.neighbour_cell_information_for_ca {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        for neighbour in &self.neighbour_cell_information_for_ca {
+            neighbour.to_bitbuf(buffer)?;
         }
         // MLE PDUs do not use M-bits (Annex E.2.1) — PDU ends after last Type 2 element
         Ok(())
@@ -111,3 +115,59 @@ impl fmt::Display for DNwrkBroadcast {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_nwrk_broadcast_neighbours_roundtrip() {
+        let neighbour = |cell_identifier_ca: u8, main_carrier_number: u16| NeighbourCellInformationForCa {
+            cell_identifier_ca,
+            cell_reselection_types_supported: 1,
+            neighbour_cell_synchronized: false,
+            cell_load_ca: 0,
+            main_carrier_number,
+            main_carrier_number_extension: None,
+            mcc: None,
+            mnc: None,
+            location_area: Some(2),
+            maximum_ms_transmit_power: None,
+            minimum_rx_access_level: Some(3),
+            subscriber_class: None,
+            bs_service_details: None,
+            timeshare_cell_information_or_security_parameters: None,
+            tdma_frame_offset: None,
+        };
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: Some(0x123456789ABC),
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![neighbour(1, 1521), neighbour(2, 1523)],
+        };
+
+        let mut buffer = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buffer).unwrap();
+        let len = buffer.get_pos();
+        buffer.seek(0);
+        let parsed = DNwrkBroadcast::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(buffer.get_pos(), len);
+        assert_eq!(parsed.tetra_network_time, pdu.tetra_network_time);
+        assert_eq!(parsed.number_of_ca_neighbour_cells, Some(2));
+        assert_eq!(parsed.neighbour_cell_information_for_ca, pdu.neighbour_cell_information_for_ca);
+    }
+
+    #[test]
+    fn test_d_nwrk_broadcast_inconsistent_count() {
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(1),
+            neighbour_cell_information_for_ca: Vec::new(),
+        };
+        let mut buffer = BitBuffer::new_autoexpand(32);
+        assert!(pdu.to_bitbuf(&mut buffer).is_err());
+    }
+}
@@ -57,15 +57,21 @@ impl DNwrkBroadcastRemove {
         // Type2
         let number_of_ca_cells_for_removal = typed::parse_type2_generic(obit, buffer, 5, "number_of_ca_cells_for_removal")?;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("removal_data_for_ca_cell"),
+        });
         let removal_data_for_ca_cell = if obit { Some(0) } else { None };
         // Type2
         let number_of_da_cells_for_removal = typed::parse_type2_generic(obit, buffer, 8, "number_of_da_cells_for_removal")?;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("removal_data_for_da_cell"),
+        });
         let removal_data_for_da_cell = if obit { Some(0) } else { None };
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("removal_data_for_serving_cell"),
+        });
         let removal_data_for_serving_cell = if obit { Some(0) } else { None };
         // Type2
         let reserved1 = typed::parse_type2_generic(obit, buffer, 8, "reserved1")?;
@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::sdu::{read_sdu, write_sdu};
 
 /// Representation of the D-PREPARE-FAIL PDU (Clause 18.4.1.4.3).
 /// Upon receipt from the SwMI the message shall be used by the MS-MLE as a preparation failure, while announcing cell reselection to the old cell.
@@ -16,11 +17,9 @@ pub struct DPrepareFail {
     /// Type1, 2 bits, Fail cause
     pub fail_cause: u8,
     /// Conditional See note,
-    pub sdu: Option<u64>,
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DPrepareFail {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -29,27 +28,12 @@ impl DPrepareFail {
 
         // Type1
         let fail_cause = buffer.read_field(2, "fail_cause")? as u8;
-        // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of the SDU
         let obit = delimiters::read_obit(buffer)?;
 
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
-        } else {
-            None
-        };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, takes the remainder of the PDU
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(DPrepareFail { fail_cause, sdu })
     }
@@ -60,14 +44,14 @@ impl DPrepareFail {
         buffer.write_bits(MlePduTypeDl::DPrepareFail.into_raw(), 3);
         // Type1
         buffer.write_bits(self.fail_cause as u64, 2);
-        // TODO FIXME: sdu handling
+
+        // Place o-bit for the SDU
+        delimiters::write_obit(buffer, self.sdu.is_some() as u8);
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::sdu::{read_sdu, write_sdu};
 
 /// Representation of the D-RESTORE-ACK PDU (Clause 18.4.1.4.4).
 /// Upon receipt from the SwMI, the message shall indicate to the MS-MLE an acknowledgement of the C-Plane restoration on the new selected cell.
@@ -14,38 +15,20 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 #[derive(Debug)]
 pub struct DRestoreAck {
     /// Conditional See note,
-    pub sdu: Option<u64>,
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DRestoreAck {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeDl::DRestoreAck)?;
 
-        // Exceptional case: obit required for SDU field.
-        // SDU takes rest of slot, but still ends with 0-bit (closing obit)
-
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of the SDU
         let obit = delimiters::read_obit(buffer)?;
 
-        let sdu = if buffer.get_len_remaining() > 0 {
-            Some(buffer.read_field(buffer.get_len_remaining() - 1, "sdu")?)
-        } else {
-            None
-        };
-        unimplemented!(); // read closing obit
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, takes the remainder of the PDU
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(DRestoreAck { sdu })
     }
@@ -54,14 +37,14 @@ impl DRestoreAck {
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DRestoreAck.into_raw(), 3);
-        // TODO FIXME: sdu handling
+
+        // Place o-bit for the SDU
+        delimiters::write_obit(buffer, self.sdu.is_some() as u8);
+
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
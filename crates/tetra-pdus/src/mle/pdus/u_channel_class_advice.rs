@@ -43,12 +43,16 @@ impl UChannelClassAdvice {
         // Type1
         let number_of_channel_class_identifiers = buffer.read_field(2, "number_of_channel_class_identifiers")? as u8;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("channel_class_identifier"),
+        });
         let channel_class_identifier = if true { Some(0) } else { None };
         // Type1
         let discriminator_for_sdu_protocol_present = buffer.read_field(1, "discriminator_for_sdu_protocol_present")? != 0;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("protocol_discriminator"),
+        });
         let protocol_discriminator = if true { Some(0) } else { None };
 
         // obit designates presence of any further type2, type3 or type4 fields
@@ -57,7 +61,7 @@ impl UChannelClassAdvice {
         // Type2
         let data_priority = typed::parse_type2_generic(obit, buffer, 3, "data_priority")?;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented { field: Some("sdu") });
         let sdu = if obit { Some(0) } else { None };
 
         // Read trailing obit (if not previously encountered)
@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
+use crate::mle::fields::sdu::{read_sdu, write_sdu};
 
 /// Representation of the U-PREPARE PDU (Clause 18.4.1.4.6).
 /// The message shall be sent on the serving cell to the SwMI by the MS-MLE, when preparation of cell reselection to a neighbour cell is in progress.
@@ -16,32 +17,23 @@ pub struct UPrepare {
     /// Type2, 5 bits, Cell identifier CA
     pub cell_identifier_ca: Option<u64>,
     /// Conditional See note,
-    pub sdu: Option<u64>,
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl UPrepare {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeUl::UPrepare)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of any further type2 fields or the SDU
         let obit = delimiters::read_obit(buffer)?;
 
         // Type2
         let cell_identifier_ca = typed::parse_type2_generic(obit, buffer, 5, "cell_identifier_ca")?;
 
-        // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, takes the remainder of the PDU
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(UPrepare { cell_identifier_ca, sdu })
     }
@@ -52,7 +44,7 @@ impl UPrepare {
         buffer.write_bits(MlePduTypeUl::UPrepare.into_raw(), 3);
 
         // Check if any optional field present and place o-bit
-        let obit = self.cell_identifier_ca.is_some();
+        let obit = self.cell_identifier_ca.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -62,12 +54,9 @@ impl UPrepare {
         typed::write_type2_generic(obit, buffer, self.cell_identifier_ca, 5);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
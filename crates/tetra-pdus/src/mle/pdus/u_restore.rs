@@ -4,6 +4,7 @@ use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 
 use crate::mle::enums::mle_pdu_type_ul::MlePduTypeUl;
+use crate::mle::fields::sdu::{read_sdu, write_sdu};
 
 /// Representation of the U-RESTORE PDU (Clause 18.4.1.4.7).
 /// The message shall be sent by the MS-MLE, when restoration of the C-Plane towards a new cell is in progress.
@@ -22,18 +23,16 @@ pub struct URestore {
     /// Type2, 14 bits, See notes 1 and 2,
     pub la: Option<u64>,
     /// Conditional This PDU shall carry a CMCE U-CALL RESTORE PDU which shall be used to restore a call after cell reselection. The SDU is coded according to the CMCE protocol. There shall be no P-bit in the PDU coding preceding the "SDU" information element.,
-    pub sdu: Option<u64>,
+    pub sdu: Option<BitBuffer>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl URestore {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         let pdu_type = buffer.read_field(3, "pdu_type")?;
         expect_pdu_type!(pdu_type, MlePduTypeUl::URestore)?;
 
-        // obit designates presence of any further type2, type3 or type4 fields
+        // obit designates presence of any further type2 fields or the SDU
         let obit = delimiters::read_obit(buffer)?;
 
         // Type2
@@ -42,15 +41,8 @@ impl URestore {
         let mnc = typed::parse_type2_generic(obit, buffer, 14, "mnc")?;
         // Type2
         let la = typed::parse_type2_generic(obit, buffer, 14, "la")?;
-        // Conditional
-        unimplemented!();
-        let sdu = if obit { Some(0) } else { None };
-
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // Conditional, takes the remainder of the PDU
+        let sdu = if obit { read_sdu(buffer) } else { None };
 
         Ok(URestore { mcc, mnc, la, sdu })
     }
@@ -61,7 +53,7 @@ impl URestore {
         buffer.write_bits(MlePduTypeUl::URestore.into_raw(), 3);
 
         // Check if any optional field present and place o-bit
-        let obit = self.mcc.is_some() || self.mnc.is_some() || self.la.is_some();
+        let obit = self.mcc.is_some() || self.mnc.is_some() || self.la.is_some() || self.sdu.is_some();
         delimiters::write_obit(buffer, obit as u8);
         if !obit {
             return Ok(());
@@ -77,12 +69,9 @@ impl URestore {
         typed::write_type2_generic(obit, buffer, self.la, 14);
 
         // Conditional
-        if let Some(ref value) = self.sdu {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(ref sdu) = self.sdu {
+            write_sdu(buffer, sdu);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -96,3 +85,35 @@ impl fmt::Display for URestore {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u_restore_sdu_roundtrip() {
+        let sdu = BitBuffer::from_bitstr("01011000000000011110000100000000000000000001100100");
+        let pdu = URestore {
+            mcc: None,
+            mnc: None,
+            la: Some(2),
+            sdu: Some(sdu.clone()),
+        };
+
+        let mut buffer = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buffer).unwrap();
+        buffer.seek(0);
+        let parsed = URestore::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(buffer.get_len_remaining(), 0);
+        assert_eq!(parsed.la, Some(2));
+        assert_eq!(parsed.sdu.unwrap().to_bitstr(), sdu.to_bitstr());
+    }
+
+    #[test]
+    fn test_u_restore_without_elements() {
+        let mut buffer = BitBuffer::from_bitstr("1000");
+        let parsed = URestore::from_bitbuf(&mut buffer).unwrap();
+        assert!(parsed.mcc.is_none() && parsed.mnc.is_none() && parsed.la.is_none());
+        assert!(parsed.sdu.is_none());
+    }
+}
@@ -0,0 +1,112 @@
+/// Clause 16.10.42 Reject cause
+/// Bits: 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LocationUpdateRejectCause {
+    ItsiUnknown = 1,
+    IllegalMs = 2,
+    LaNotAllowed = 3,
+    LaUnknown = 4,
+    NetworkFailure = 5,
+    Congestion = 6,
+    ForwardRegistrationFailure = 7,
+    ServiceNotSubscribed = 8,
+    MandatoryElementError = 9,
+    MessageConsistencyError = 10,
+    RoamingNotSupported = 11,
+    MigrationNotSupported = 12,
+    NoCipherKsg = 13,
+    IdentifiedCipherKsgNotSupported = 14,
+    RequestedCipherKeyTypeNotAvailable = 15,
+    IdentifiedCipherKeyNotAvailable = 16,
+    IncompatibleService = 17,
+    CipheringRequired = 18,
+    AuthenticationFailure = 19,
+}
+
+impl std::convert::TryFrom<u64> for LocationUpdateRejectCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            1 => Ok(LocationUpdateRejectCause::ItsiUnknown),
+            2 => Ok(LocationUpdateRejectCause::IllegalMs),
+            3 => Ok(LocationUpdateRejectCause::LaNotAllowed),
+            4 => Ok(LocationUpdateRejectCause::LaUnknown),
+            5 => Ok(LocationUpdateRejectCause::NetworkFailure),
+            6 => Ok(LocationUpdateRejectCause::Congestion),
+            7 => Ok(LocationUpdateRejectCause::ForwardRegistrationFailure),
+            8 => Ok(LocationUpdateRejectCause::ServiceNotSubscribed),
+            9 => Ok(LocationUpdateRejectCause::MandatoryElementError),
+            10 => Ok(LocationUpdateRejectCause::MessageConsistencyError),
+            11 => Ok(LocationUpdateRejectCause::RoamingNotSupported),
+            12 => Ok(LocationUpdateRejectCause::MigrationNotSupported),
+            13 => Ok(LocationUpdateRejectCause::NoCipherKsg),
+            14 => Ok(LocationUpdateRejectCause::IdentifiedCipherKsgNotSupported),
+            15 => Ok(LocationUpdateRejectCause::RequestedCipherKeyTypeNotAvailable),
+            16 => Ok(LocationUpdateRejectCause::IdentifiedCipherKeyNotAvailable),
+            17 => Ok(LocationUpdateRejectCause::IncompatibleService),
+            18 => Ok(LocationUpdateRejectCause::CipheringRequired),
+            19 => Ok(LocationUpdateRejectCause::AuthenticationFailure),
+            _ => Err(()),
+        }
+    }
+}
+
+impl LocationUpdateRejectCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            LocationUpdateRejectCause::ItsiUnknown => 1,
+            LocationUpdateRejectCause::IllegalMs => 2,
+            LocationUpdateRejectCause::LaNotAllowed => 3,
+            LocationUpdateRejectCause::LaUnknown => 4,
+            LocationUpdateRejectCause::NetworkFailure => 5,
+            LocationUpdateRejectCause::Congestion => 6,
+            LocationUpdateRejectCause::ForwardRegistrationFailure => 7,
+            LocationUpdateRejectCause::ServiceNotSubscribed => 8,
+            LocationUpdateRejectCause::MandatoryElementError => 9,
+            LocationUpdateRejectCause::MessageConsistencyError => 10,
+            LocationUpdateRejectCause::RoamingNotSupported => 11,
+            LocationUpdateRejectCause::MigrationNotSupported => 12,
+            LocationUpdateRejectCause::NoCipherKsg => 13,
+            LocationUpdateRejectCause::IdentifiedCipherKsgNotSupported => 14,
+            LocationUpdateRejectCause::RequestedCipherKeyTypeNotAvailable => 15,
+            LocationUpdateRejectCause::IdentifiedCipherKeyNotAvailable => 16,
+            LocationUpdateRejectCause::IncompatibleService => 17,
+            LocationUpdateRejectCause::CipheringRequired => 18,
+            LocationUpdateRejectCause::AuthenticationFailure => 19,
+        }
+    }
+}
+
+impl From<LocationUpdateRejectCause> for u64 {
+    fn from(e: LocationUpdateRejectCause) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for LocationUpdateRejectCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LocationUpdateRejectCause::ItsiUnknown => write!(f, "ItsiUnknown"),
+            LocationUpdateRejectCause::IllegalMs => write!(f, "IllegalMs"),
+            LocationUpdateRejectCause::LaNotAllowed => write!(f, "LaNotAllowed"),
+            LocationUpdateRejectCause::LaUnknown => write!(f, "LaUnknown"),
+            LocationUpdateRejectCause::NetworkFailure => write!(f, "NetworkFailure"),
+            LocationUpdateRejectCause::Congestion => write!(f, "Congestion"),
+            LocationUpdateRejectCause::ForwardRegistrationFailure => write!(f, "ForwardRegistrationFailure"),
+            LocationUpdateRejectCause::ServiceNotSubscribed => write!(f, "ServiceNotSubscribed"),
+            LocationUpdateRejectCause::MandatoryElementError => write!(f, "MandatoryElementError"),
+            LocationUpdateRejectCause::MessageConsistencyError => write!(f, "MessageConsistencyError"),
+            LocationUpdateRejectCause::RoamingNotSupported => write!(f, "RoamingNotSupported"),
+            LocationUpdateRejectCause::MigrationNotSupported => write!(f, "MigrationNotSupported"),
+            LocationUpdateRejectCause::NoCipherKsg => write!(f, "NoCipherKsg"),
+            LocationUpdateRejectCause::IdentifiedCipherKsgNotSupported => write!(f, "IdentifiedCipherKsgNotSupported"),
+            LocationUpdateRejectCause::RequestedCipherKeyTypeNotAvailable => write!(f, "RequestedCipherKeyTypeNotAvailable"),
+            LocationUpdateRejectCause::IdentifiedCipherKeyNotAvailable => write!(f, "IdentifiedCipherKeyNotAvailable"),
+            LocationUpdateRejectCause::IncompatibleService => write!(f, "IncompatibleService"),
+            LocationUpdateRejectCause::CipheringRequired => write!(f, "CipheringRequired"),
+            LocationUpdateRejectCause::AuthenticationFailure => write!(f, "AuthenticationFailure"),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+/// Random challenges (RAND1, RAND2) and the random seed (RS) are 80 bits,
+/// more than fits in a single field read. They are kept in the low bits of a u128.
+pub const RANDOM_VALUE_BITS: usize = 80;
+
+/// Read an 80-bit random challenge or random seed
+pub fn read_random_value(buffer: &mut BitBuffer, field: &'static str) -> Result<u128, PduParseErr> {
+    let high = buffer.read_field(RANDOM_VALUE_BITS - 64, field)? as u128;
+    let low = buffer.read_field(64, field)? as u128;
+    Ok((high << 64) | low)
+}
+
+/// Write an 80-bit random challenge or random seed
+pub fn write_random_value(buffer: &mut BitBuffer, value: u128) {
+    buffer.write_bits((value >> 64) as u64, RANDOM_VALUE_BITS - 64);
+    buffer.write_bits(value as u64, 64);
+}
+
+/// Authentication challenge of authenticated enable/disable, RAND1 followed by RS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationChallenge {
+    /// 80 bits, RAND1
+    pub random_challenge: u128,
+    /// 80 bits, RS
+    pub random_seed: u128,
+}
+
+impl AuthenticationChallenge {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        Ok(Self {
+            random_challenge: read_random_value(buffer, "random_challenge")?,
+            random_seed: read_random_value(buffer, "random_seed")?,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        write_random_value(buffer, self.random_challenge);
+        write_random_value(buffer, self.random_seed);
+        Ok(())
+    }
+}
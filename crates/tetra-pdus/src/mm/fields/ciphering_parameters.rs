@@ -0,0 +1,56 @@
+use core::fmt;
+
+/// Ciphering parameters element, see ETSI EN 300 392-7.
+/// Carried as a 10-bit value in U-LOCATION UPDATE DEMAND and in the
+/// security downlink element of D-LOCATION UPDATE ACCEPT.
+pub const CIPHERING_PARAMETERS_BITS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CipheringParameters {
+    // 4, keystream generator, TEA1 is 0
+    pub ksg_number: u8,
+    // 1, false for class 2 (SCK), true for class 3 (DCK)
+    pub security_class_3: bool,
+    // 5, SCK number 1-32, coded as 0-31. Only meaningful for class 2
+    pub sck_number: u8,
+}
+
+impl CipheringParameters {
+    pub fn from_raw(value: u64) -> Self {
+        CipheringParameters {
+            ksg_number: ((value >> 6) & 0xf) as u8,
+            security_class_3: (value >> 5) & 1 != 0,
+            sck_number: (value & 0x1f) as u8 + 1,
+        }
+    }
+
+    pub fn into_raw(self) -> u64 {
+        ((self.ksg_number as u64 & 0xf) << 6) | ((self.security_class_3 as u64) << 5) | (self.sck_number.wrapping_sub(1) as u64 & 0x1f)
+    }
+}
+
+impl fmt::Display for CipheringParameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CipheringParameters {{ ksg_number: {} security_class_3: {} sck_number: {} }}",
+            self.ksg_number, self.security_class_3, self.sck_number,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ciphering_parameters_raw() {
+        let params = CipheringParameters {
+            ksg_number: 1,
+            security_class_3: false,
+            sck_number: 32,
+        };
+        assert_eq!(params.into_raw(), 0b0001_0_11111);
+        assert_eq!(CipheringParameters::from_raw(params.into_raw()), params);
+    }
+}
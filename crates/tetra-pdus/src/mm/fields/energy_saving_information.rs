@@ -4,15 +4,17 @@ use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 
 use crate::mm::enums::energy_saving_mode::EnergySavingMode;
 
-/// 16.10.10 Energy saving information
+/// Length of the energy saving information element
+pub const ENERGY_SAVING_INFORMATION_BITS: usize = 14;
 
-#[derive(Debug, Clone)]
+/// 16.10.10 Energy saving information
+#[derive(Debug, Clone, PartialEq)]
 pub struct EnergySavingInformation {
     // 3
     pub energy_saving_mode: EnergySavingMode,
-    // 2, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
+    // 5, starting point frame number, 1 to 18. None for "Stay alive", where the field has no meaning and is set to 0
     pub frame_number: Option<u8>,
-    // 2, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
+    // 6, starting point multiframe number, 1 to 60. None for "Stay alive", where the field has no meaning and is set to 0
     pub multiframe_number: Option<u8>,
 }
 
@@ -21,26 +23,26 @@ impl EnergySavingInformation {
         let val = buffer.read_field(3, "energy_saving_mode")? as u8;
         let energy_saving_mode = EnergySavingMode::try_from(val as u64).unwrap(); // Never fails
 
-        let fn_val = buffer.read_field(2, "frame_number")? as u8;
-        let mn_val = buffer.read_field(2, "multiframe_number")? as u8;
+        let fn_val = buffer.read_field(5, "frame_number")? as u8;
+        let mn_val = buffer.read_field(6, "multiframe_number")? as u8;
 
         // Sanity check
         let (f, m) = if energy_saving_mode == EnergySavingMode::StayAlive {
-            if fn_val != 0 {
+            (None, None)
+        } else {
+            if !(1..=18).contains(&fn_val) {
                 return Err(PduParseErr::InvalidValue {
                     field: "frame_number",
                     value: fn_val as u64,
                 });
             }
-            if mn_val != 0 {
+            if !(1..=60).contains(&mn_val) {
                 return Err(PduParseErr::InvalidValue {
                     field: "multiframe_number",
                     value: mn_val as u64,
                 });
             }
             (Some(fn_val), Some(mn_val))
-        } else {
-            (None, None)
         };
 
         let s = EnergySavingInformation {
@@ -69,17 +71,17 @@ impl EnergySavingInformation {
                     value: f as u64,
                 });
             }
-            buf.write_bits(0, 2 + 2);
+            buf.write_bits(0, 5 + 6);
         } else {
             if let Some(f) = self.frame_number {
-                buf.write_bits(f as u64, 2);
+                buf.write_bits(f as u64, 5);
             } else {
                 return Err(PduParseErr::FieldNotPresent {
                     field: Some("frame_number"),
                 });
             }
             if let Some(f) = self.multiframe_number {
-                buf.write_bits(f as u64, 2);
+                buf.write_bits(f as u64, 6);
             } else {
                 return Err(PduParseErr::FieldNotPresent {
                     field: Some("multiframe_number"),
@@ -100,3 +102,36 @@ impl fmt::Display for EnergySavingInformation {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_energy_saving_information() {
+        let esi = EnergySavingInformation {
+            energy_saving_mode: EnergySavingMode::Eg3,
+            frame_number: Some(4),
+            multiframe_number: Some(10),
+        };
+        let mut buf = BitBuffer::new_autoexpand(14);
+        esi.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.to_bitstr(), "01100100001010");
+
+        buf.seek(0);
+        assert_eq!(EnergySavingInformation::from_bitbuf(&mut buf).unwrap(), esi);
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+
+    #[test]
+    fn test_energy_saving_information_stay_alive() {
+        let mut buf = BitBuffer::from_bitstr("00000000000000");
+        let esi = EnergySavingInformation::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(esi.energy_saving_mode, EnergySavingMode::StayAlive);
+        assert_eq!((esi.frame_number, esi.multiframe_number), (None, None));
+
+        // A starting point is required outside of stay alive
+        let mut buf = BitBuffer::from_bitstr("00100000000001");
+        assert!(EnergySavingInformation::from_bitbuf(&mut buf).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod authentication_values;
+pub mod ciphering_parameters;
 pub mod energy_saving_information;
 pub mod group_identity_attachment;
 pub mod group_identity_downlink;
@@ -0,0 +1,119 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::{read_random_value, write_random_value};
+
+/// Representation of the D-AUTHENTICATION DEMAND PDU (EN 300 392-7 Clause 4.4.1.1).
+/// The infrastructure sends this message to the MS to initiate authentication of the MS.
+/// Response expected: U-AUTHENTICATION RESPONSE/U-AUTHENTICATION REJECT
+/// Response to: -
+#[derive(Debug)]
+pub struct DAuthenticationDemand {
+    /// Type1, 80 bits, RAND1
+    pub random_challenge: u128,
+    /// Type1, 80 bits, RS
+    pub random_seed: u128,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DAuthenticationDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Demand)?;
+
+        // Type1
+        let random_challenge = read_random_value(buffer, "random_challenge")?;
+        // Type1
+        let random_seed = read_random_value(buffer, "random_seed")?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DAuthenticationDemand {
+            random_challenge,
+            random_seed,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Demand.into_raw(), 2);
+        // Type1
+        write_random_value(buffer, self.random_challenge);
+        // Type1
+        write_random_value(buffer, self.random_seed);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DAuthenticationDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DAuthenticationDemand {{ random_challenge: {:?} random_seed: {:?} proprietary: {:?} }}",
+            self.random_challenge, self.random_seed, self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_authentication_demand() {
+        debug::setup_logging_verbose();
+        let test_vec = "00010000010001001000100011001101000100010101010110011001110111100010001010101010111011111111111110111011011101110011001011101110101010100110011000100001110111011001100";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DAuthenticationDemand::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        tracing::info!("Buf at end: {}", buf_in.dump_bin());
+
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.random_challenge, 0x1122334455667788AABB);
+        assert_eq!(pdu.random_seed, 0xFFEEDDCCBBAA99887766);
+
+        let mut buf_out = BitBuffer::new_autoexpand(167);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        tracing::info!("Serialized: {}", buf_out.dump_bin());
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
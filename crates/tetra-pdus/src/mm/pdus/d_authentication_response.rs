@@ -0,0 +1,120 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::{read_random_value, write_random_value};
+
+/// Representation of the D-AUTHENTICATION RESPONSE PDU (EN 300 392-7 Clause 4.4.1.2).
+/// The infrastructure sends this message to the MS in response to an MS-initiated authentication.
+/// Response expected: U-AUTHENTICATION RESULT
+/// Response to: U-AUTHENTICATION DEMAND
+
+// note: The conditional element is present if and only if the Mutual authentication flag is set.
+#[derive(Debug)]
+pub struct DAuthenticationResponse {
+    /// Type1, 80 bits, RS
+    pub random_seed: u128,
+    /// Type1, 32 bits, Response value RES2
+    pub response_value: u32,
+    /// Type1, 1 bits, Mutual authentication flag
+    pub mutual_authentication_flag: bool,
+    /// Conditional 80 bits, RAND1, see note
+    pub random_challenge: Option<u128>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DAuthenticationResponse {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Response)?;
+
+        // Type1
+        let random_seed = read_random_value(buffer, "random_seed")?;
+        // Type1
+        let response_value = buffer.read_field(32, "response_value")? as u32;
+        // Type1
+        let mutual_authentication_flag = buffer.read_field(1, "mutual_authentication_flag")? != 0;
+        // Conditional
+        let random_challenge = if mutual_authentication_flag {
+            Some(read_random_value(buffer, "random_challenge")?)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DAuthenticationResponse {
+            random_seed,
+            response_value,
+            mutual_authentication_flag,
+            random_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Response.into_raw(), 2);
+        // Type1
+        write_random_value(buffer, self.random_seed);
+        // Type1
+        buffer.write_bits(self.response_value as u64, 32);
+        // Type1
+        buffer.write_bits(self.mutual_authentication_flag as u64, 1);
+        // Conditional
+        if self.mutual_authentication_flag != self.random_challenge.is_some() {
+            return Err(PduParseErr::Inconsistency {
+                field: "random_challenge",
+                reason: "must be present if and only if mutual_authentication_flag is set",
+            });
+        }
+        if let Some(value) = self.random_challenge {
+            write_random_value(buffer, value);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DAuthenticationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DAuthenticationResponse {{ random_seed: {:?} response_value: {:?} mutual_authentication_flag: {:?} random_challenge: {:?} proprietary: {:?} }}",
+            self.random_seed, self.response_value, self.mutual_authentication_flag, self.random_challenge, self.proprietary,
+        )
+    }
+}
@@ -0,0 +1,190 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::AuthenticationChallenge;
+
+/// Representation of the D-DISABLE PDU (EN 300 392-7 Clause 5).
+/// The infrastructure sends this message to the MS to disable its subscription and/or equipment, temporarily or permanently.
+/// Response expected: U-DISABLE STATUS
+/// Response to: -
+
+// note 1: "TETRA equipment identity" is present if "Equipment disable" is set.
+// note 2: "Address extension" and "SSI" are present if "Subscription disable" is set.
+#[derive(Debug, PartialEq)]
+pub struct DDisable {
+    /// Type1, 1 bits, Intent/confirm, 0 for intent, 1 for confirm
+    pub intent_confirm: bool,
+    /// Type1, 1 bits, Disabling type, 0 for temporary, 1 for permanent
+    pub disabling_type: bool,
+    /// Type1, 1 bits, Equipment disable
+    pub equipment_disable: bool,
+    /// Conditional 60 bits, See note 1,
+    pub tetra_equipment_identity: Option<u64>,
+    /// Type1, 1 bits, Subscription disable
+    pub subscription_disable: bool,
+    /// Conditional 24 bits, MNI of the MS, See note 2,
+    pub address_extension: Option<u64>,
+    /// Conditional 24 bits, See note 2,
+    pub ssi: Option<u64>,
+    /// Type2, 160 bits, Authentication challenge
+    pub authentication_challenge: Option<AuthenticationChallenge>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DDisable {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DDisable)?;
+
+        // Type1
+        let intent_confirm = buffer.read_field(1, "intent_confirm")? != 0;
+        // Type1
+        let disabling_type = buffer.read_field(1, "disabling_type")? != 0;
+        // Type1
+        let equipment_disable = buffer.read_field(1, "equipment_disable")? != 0;
+        // Conditional
+        let tetra_equipment_identity = if equipment_disable {
+            Some(buffer.read_field(60, "tetra_equipment_identity")?)
+        } else {
+            None
+        };
+        // Type1
+        let subscription_disable = buffer.read_field(1, "subscription_disable")? != 0;
+        // Conditional
+        let (address_extension, ssi) = if subscription_disable {
+            (
+                Some(buffer.read_field(24, "address_extension")?),
+                Some(buffer.read_field(24, "ssi")?),
+            )
+        } else {
+            (None, None)
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type2
+        let authentication_challenge = typed::parse_type2_struct(obit, buffer, AuthenticationChallenge::from_bitbuf)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DDisable {
+            intent_confirm,
+            disabling_type,
+            equipment_disable,
+            tetra_equipment_identity,
+            subscription_disable,
+            address_extension,
+            ssi,
+            authentication_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DDisable.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.intent_confirm as u64, 1);
+        // Type1
+        buffer.write_bits(self.disabling_type as u64, 1);
+        // Type1
+        buffer.write_bits(self.equipment_disable as u64, 1);
+        // Conditional
+        if self.equipment_disable {
+            let Some(tei) = self.tetra_equipment_identity else {
+                return Err(PduParseErr::FieldNotPresent {
+                    field: Some("tetra_equipment_identity"),
+                });
+            };
+            buffer.write_bits(tei, 60);
+        }
+        // Type1
+        buffer.write_bits(self.subscription_disable as u64, 1);
+        // Conditional
+        if self.subscription_disable {
+            let (Some(address_extension), Some(ssi)) = (self.address_extension, self.ssi) else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("ssi") });
+            };
+            buffer.write_bits(address_extension, 24);
+            buffer.write_bits(ssi, 24);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.authentication_challenge.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type2
+        typed::write_type2_struct(obit, buffer, &self.authentication_challenge, AuthenticationChallenge::to_bitbuf)?;
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DDisable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DDisable {{ intent_confirm: {:?} disabling_type: {:?} equipment_disable: {:?} tetra_equipment_identity: {:?} subscription_disable: {:?} address_extension: {:?} ssi: {:?} authentication_challenge: {:?} proprietary: {:?} }}",
+            self.intent_confirm,
+            self.disabling_type,
+            self.equipment_disable,
+            self.tetra_equipment_identity,
+            self.subscription_disable,
+            self.address_extension,
+            self.ssi,
+            self.authentication_challenge,
+            self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_disable_roundtrip() {
+        // Confirmed temporary subscription disable of ISSI 2040814 on MCC 204 MNC 1337
+        let pdu = DDisable {
+            intent_confirm: true,
+            disabling_type: false,
+            equipment_disable: false,
+            tetra_equipment_identity: None,
+            subscription_disable: true,
+            address_extension: Some((204 << 14) | 1337),
+            ssi: Some(2040814),
+            authentication_challenge: None,
+            proprietary: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.get_len(), 4 + 4 + 48 + 1);
+        buf.seek(0);
+        assert_eq!(DDisable::from_bitbuf(&mut buf).unwrap(), pdu);
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+}
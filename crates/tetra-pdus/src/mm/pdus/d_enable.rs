@@ -0,0 +1,155 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::authentication_values::AuthenticationChallenge;
+
+/// Representation of the D-ENABLE PDU (EN 300 392-7 Clause 5).
+/// The infrastructure sends this message to the MS to enable a temporarily disabled subscription and/or equipment.
+/// Response expected: U-DISABLE STATUS
+/// Response to: -
+
+// note 1: "TETRA equipment identity" is present if "Equipment enable" is set.
+// note 2: "Address extension" and "SSI" are present if "Subscription enable" is set.
+#[derive(Debug, PartialEq)]
+pub struct DEnable {
+    /// Type1, 1 bits, Intent/confirm, 0 for intent, 1 for confirm
+    pub intent_confirm: bool,
+    /// Type1, 1 bits, Equipment enable
+    pub equipment_enable: bool,
+    /// Conditional 60 bits, See note 1,
+    pub tetra_equipment_identity: Option<u64>,
+    /// Type1, 1 bits, Subscription enable
+    pub subscription_enable: bool,
+    /// Conditional 24 bits, MNI of the MS, See note 2,
+    pub address_extension: Option<u64>,
+    /// Conditional 24 bits, See note 2,
+    pub ssi: Option<u64>,
+    /// Type2, 160 bits, Authentication challenge
+    pub authentication_challenge: Option<AuthenticationChallenge>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DEnable {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DEnable)?;
+
+        // Type1
+        let intent_confirm = buffer.read_field(1, "intent_confirm")? != 0;
+        // Type1
+        let equipment_enable = buffer.read_field(1, "equipment_enable")? != 0;
+        // Conditional
+        let tetra_equipment_identity = if equipment_enable {
+            Some(buffer.read_field(60, "tetra_equipment_identity")?)
+        } else {
+            None
+        };
+        // Type1
+        let subscription_enable = buffer.read_field(1, "subscription_enable")? != 0;
+        // Conditional
+        let (address_extension, ssi) = if subscription_enable {
+            (
+                Some(buffer.read_field(24, "address_extension")?),
+                Some(buffer.read_field(24, "ssi")?),
+            )
+        } else {
+            (None, None)
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type2
+        let authentication_challenge = typed::parse_type2_struct(obit, buffer, AuthenticationChallenge::from_bitbuf)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DEnable {
+            intent_confirm,
+            equipment_enable,
+            tetra_equipment_identity,
+            subscription_enable,
+            address_extension,
+            ssi,
+            authentication_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DEnable.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.intent_confirm as u64, 1);
+        // Type1
+        buffer.write_bits(self.equipment_enable as u64, 1);
+        // Conditional
+        if self.equipment_enable {
+            let Some(tei) = self.tetra_equipment_identity else {
+                return Err(PduParseErr::FieldNotPresent {
+                    field: Some("tetra_equipment_identity"),
+                });
+            };
+            buffer.write_bits(tei, 60);
+        }
+        // Type1
+        buffer.write_bits(self.subscription_enable as u64, 1);
+        // Conditional
+        if self.subscription_enable {
+            let (Some(address_extension), Some(ssi)) = (self.address_extension, self.ssi) else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("ssi") });
+            };
+            buffer.write_bits(address_extension, 24);
+            buffer.write_bits(ssi, 24);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.authentication_challenge.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type2
+        typed::write_type2_struct(obit, buffer, &self.authentication_challenge, AuthenticationChallenge::to_bitbuf)?;
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DEnable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DEnable {{ intent_confirm: {:?} equipment_enable: {:?} tetra_equipment_identity: {:?} subscription_enable: {:?} address_extension: {:?} ssi: {:?} authentication_challenge: {:?} proprietary: {:?} }}",
+            self.intent_confirm,
+            self.equipment_enable,
+            self.tetra_equipment_identity,
+            self.subscription_enable,
+            self.address_extension,
+            self.ssi,
+            self.authentication_challenge,
+            self.proprietary,
+        )
+    }
+}
@@ -41,7 +41,9 @@ impl DLocationUpdateCommand {
         // Type1
         let cipher_control = buffer.read_field(1, "cipher_control")? != 0;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("ciphering_parameters"),
+        });
         let ciphering_parameters = if true { Some(0) } else { None };
 
         // obit designates presence of any further type2, type3 or type4 fields
@@ -50,10 +52,14 @@ impl DLocationUpdateCommand {
         // Type2
         let address_extension = typed::parse_type2_generic(obit, buffer, 24, "address_extension")?;
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("cell_type_control"),
+        });
         let cell_type_control = if obit { Some(0) } else { None };
         // Conditional
-        unimplemented!();
+        return Err(PduParseErr::NotImplemented {
+            field: Some("proprietary"),
+        });
         let proprietary = if obit { Some(0) } else { None };
 
         // Read trailing obit (if not previously encountered)
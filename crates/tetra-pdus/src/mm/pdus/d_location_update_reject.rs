@@ -32,8 +32,6 @@ pub struct DLocationUpdateReject {
     pub proprietary: Option<Type3FieldGeneric>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DLocationUpdateReject {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -47,8 +45,11 @@ impl DLocationUpdateReject {
         // Type1
         let cipher_control = buffer.read_field(1, "cipher_control")? != 0;
         // Conditional
-        unimplemented!();
-        let ciphering_parameters = if true { Some(0) } else { None };
+        let ciphering_parameters = if cipher_control {
+            Some(buffer.read_field(10, "ciphering_parameters")?)
+        } else {
+            None
+        };
 
         // obit designates presence of any further type2, type3 or type4 fields
         let mut obit = delimiters::read_obit(buffer)?;
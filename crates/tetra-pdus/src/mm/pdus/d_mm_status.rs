@@ -1,10 +1,10 @@
 use core::fmt;
 
 use tetra_core::expect_pdu_type;
-use tetra_core::typed_pdu_fields::*;
 use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 
 use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::status_downlink::StatusDownlink;
 
 /// Representation of the D-MM STATUS PDU (Clause 16.9.2.5.1).
 /// The infrastructure sends this message to the MS to request or indicate/reject a change of an operation mode.
@@ -17,13 +17,12 @@ use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 #[derive(Debug)]
 pub struct DMmStatus {
     /// Type1, 6 bits, See notes 1 and 3,
-    pub status_downlink: u8,
+    pub status_downlink: StatusDownlink,
     /// Conditional See note 2,
     pub status_downlink_dependent_information: Option<u64>,
+    pub status_downlink_dependent_information_len: Option<usize>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DMmStatus {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -31,23 +30,24 @@ impl DMmStatus {
         expect_pdu_type!(pdu_type, MmPduTypeDl::DMmStatus)?;
 
         // Type1
-        let status_downlink = buffer.read_field(6, "status_downlink")? as u8;
-        // Conditional
-        unimplemented!();
-        let status_downlink_dependent_information = if true { Some(0) } else { None };
-
-        // obit designates presence of any further type2, type3 or type4 fields
-        let mut obit = delimiters::read_obit(buffer)?;
+        let val = buffer.read_field(6, "status_downlink")?;
+        let status_downlink = StatusDownlink::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            field: "status_downlink",
+            value: val,
+        })?;
 
-        // Read trailing obit (if not previously encountered)
-        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
-        if obit {
-            return Err(PduParseErr::InvalidTrailingMbitValue);
-        }
+        // The sub-PDU takes the remainder of the PDU
+        let bits_left = buffer.get_len_remaining();
+        let status_downlink_dependent_information = if bits_left > 0 {
+            Some(buffer.read_field(bits_left, "status_downlink_dependent_information")?)
+        } else {
+            None
+        };
 
         Ok(DMmStatus {
             status_downlink,
             status_downlink_dependent_information,
+            status_downlink_dependent_information_len: if bits_left > 0 { Some(bits_left) } else { None },
         })
     }
 
@@ -56,14 +56,16 @@ impl DMmStatus {
         // PDU Type
         buffer.write_bits(MmPduTypeDl::DMmStatus.into_raw(), 4);
         // Type1
-        buffer.write_bits(self.status_downlink as u64, 6);
+        buffer.write_bits(self.status_downlink.into_raw(), 6);
         // Conditional
-        if let Some(ref value) = self.status_downlink_dependent_information {
-            unimplemented!();
-            buffer.write_bits(*value, 999);
+        if let Some(value) = self.status_downlink_dependent_information {
+            let Some(len) = self.status_downlink_dependent_information_len else {
+                return Err(PduParseErr::FieldNotPresent {
+                    field: Some("status_downlink_dependent_information_len"),
+                });
+            };
+            buffer.write_bits(value, len);
         }
-        // Write terminating m-bit
-        delimiters::write_mbit(buffer, 0);
         Ok(())
     }
 }
@@ -77,3 +79,22 @@ impl fmt::Display for DMmStatus {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_mm_status_energy_saving_response() {
+        // D-CHANGE OF ENERGY SAVING MODE RESPONSE granting EG1 from frame 2 of multiframe 7, no optional elements
+        let test_vec = "1100000010001000100001110";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DMmStatus::from_bitbuf(&mut buf_in).expect("Failed parsing");
+        assert_eq!(pdu.status_downlink, StatusDownlink::ChangeOfEnergySavingModeResponse);
+        assert_eq!(pdu.status_downlink_dependent_information_len, Some(15));
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
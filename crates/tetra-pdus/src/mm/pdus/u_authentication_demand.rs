@@ -0,0 +1,85 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+use crate::mm::fields::authentication_values::{read_random_value, write_random_value};
+
+/// Representation of the U-AUTHENTICATION DEMAND PDU (EN 300 392-7 Clause 4.4.1.5).
+/// The MS sends this message to the infrastructure to initiate authentication of the infrastructure.
+/// Response expected: D-AUTHENTICATION RESPONSE/D-AUTHENTICATION REJECT
+/// Response to: -
+#[derive(Debug)]
+pub struct UAuthenticationDemand {
+    /// Type1, 80 bits, RAND2
+    pub random_challenge: u128,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UAuthenticationDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Demand)?;
+
+        // Type1
+        let random_challenge = read_random_value(buffer, "random_challenge")?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UAuthenticationDemand {
+            random_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Demand.into_raw(), 2);
+        // Type1
+        write_random_value(buffer, self.random_challenge);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UAuthenticationDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UAuthenticationDemand {{ random_challenge: {:?} proprietary: {:?} }}",
+            self.random_challenge, self.proprietary,
+        )
+    }
+}
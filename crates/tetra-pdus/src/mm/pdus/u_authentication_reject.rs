@@ -0,0 +1,84 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+
+/// Representation of the U-AUTHENTICATION REJECT PDU (EN 300 392-7 Clause 4.4.1.8).
+/// The MS sends this message to the infrastructure to reject a SwMI-initiated authentication.
+/// Response expected: -
+/// Response to: D-AUTHENTICATION DEMAND
+#[derive(Debug)]
+pub struct UAuthenticationReject {
+    /// Type1, 3 bits, Authentication reject reason
+    pub authentication_reject_reason: u8,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UAuthenticationReject {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Reject)?;
+
+        // Type1
+        let authentication_reject_reason = buffer.read_field(3, "authentication_reject_reason")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UAuthenticationReject {
+            authentication_reject_reason,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Reject.into_raw(), 2);
+        // Type1
+        buffer.write_bits(self.authentication_reject_reason as u64, 3);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UAuthenticationReject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UAuthenticationReject {{ authentication_reject_reason: {:?} proprietary: {:?} }}",
+            self.authentication_reject_reason, self.proprietary,
+        )
+    }
+}
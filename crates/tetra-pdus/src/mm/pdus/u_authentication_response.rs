@@ -0,0 +1,141 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+use crate::mm::fields::authentication_values::{read_random_value, write_random_value};
+
+/// Representation of the U-AUTHENTICATION RESPONSE PDU (EN 300 392-7 Clause 4.4.1.6).
+/// The MS sends this message to the infrastructure in response to a SwMI-initiated authentication.
+/// Response expected: D-AUTHENTICATION RESULT
+/// Response to: D-AUTHENTICATION DEMAND
+
+// note: The conditional element is present if and only if the Mutual authentication flag is set.
+#[derive(Debug)]
+pub struct UAuthenticationResponse {
+    /// Type1, 32 bits, Response value RES1
+    pub response_value: u32,
+    /// Type1, 1 bits, Mutual authentication flag
+    pub mutual_authentication_flag: bool,
+    /// Conditional 80 bits, RAND2, see note
+    pub random_challenge: Option<u128>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UAuthenticationResponse {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Response)?;
+
+        // Type1
+        let response_value = buffer.read_field(32, "response_value")? as u32;
+        // Type1
+        let mutual_authentication_flag = buffer.read_field(1, "mutual_authentication_flag")? != 0;
+        // Conditional
+        let random_challenge = if mutual_authentication_flag {
+            Some(read_random_value(buffer, "random_challenge")?)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UAuthenticationResponse {
+            response_value,
+            mutual_authentication_flag,
+            random_challenge,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Response.into_raw(), 2);
+        // Type1
+        buffer.write_bits(self.response_value as u64, 32);
+        // Type1
+        buffer.write_bits(self.mutual_authentication_flag as u64, 1);
+        // Conditional
+        if self.mutual_authentication_flag != self.random_challenge.is_some() {
+            return Err(PduParseErr::Inconsistency {
+                field: "random_challenge",
+                reason: "must be present if and only if mutual_authentication_flag is set",
+            });
+        }
+        if let Some(value) = self.random_challenge {
+            write_random_value(buffer, value);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UAuthenticationResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UAuthenticationResponse {{ response_value: {:?} mutual_authentication_flag: {:?} random_challenge: {:?} proprietary: {:?} }}",
+            self.response_value, self.mutual_authentication_flag, self.random_challenge, self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_u_authentication_response() {
+        debug::setup_logging_verbose();
+        let test_vec =
+            "000001110111101010110110111110111011111000000010010001101000101011001111000100110101011110011011110111100000001001000110";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = UAuthenticationResponse::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        tracing::info!("Buf at end: {}", buf_in.dump_bin());
+
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.response_value, 0xDEADBEEF);
+        assert_eq!(pdu.random_challenge, Some(0x0123456789ABCDEF0123));
+
+        let mut buf_out = BitBuffer::new_autoexpand(120);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        tracing::info!("Serialized: {}", buf_out.dump_bin());
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
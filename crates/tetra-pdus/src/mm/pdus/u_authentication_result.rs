@@ -0,0 +1,112 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::authentication_sub_type::AuthenticationSubType;
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+
+/// Representation of the U-AUTHENTICATION RESULT PDU (EN 300 392-7 Clause 4.4.1.7).
+/// The MS sends this message to the infrastructure to report the result of authenticating the infrastructure.
+/// Response expected: -/D-AUTHENTICATION RESULT
+/// Response to: D-AUTHENTICATION RESPONSE/D-AUTHENTICATION RESULT
+
+// note: The conditional element is present if and only if the Mutual authentication flag is set.
+#[derive(Debug)]
+pub struct UAuthenticationResult {
+    /// Type1, 1 bits, Authentication result R2
+    pub authentication_result: bool,
+    /// Type1, 1 bits, Mutual authentication flag
+    pub mutual_authentication_flag: bool,
+    /// Conditional 32 bits, Response value RES1, see note
+    pub response_value: Option<u32>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UAuthenticationResult {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UAuthentication)?;
+        let sub_type = buffer.read_field(2, "authentication_sub_type")?;
+        expect_pdu_type!(sub_type, AuthenticationSubType::Result)?;
+
+        // Type1
+        let authentication_result = buffer.read_field(1, "authentication_result")? != 0;
+        // Type1
+        let mutual_authentication_flag = buffer.read_field(1, "mutual_authentication_flag")? != 0;
+        // Conditional
+        let response_value = if mutual_authentication_flag {
+            Some(buffer.read_field(32, "response_value")? as u32)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UAuthenticationResult {
+            authentication_result,
+            mutual_authentication_flag,
+            response_value,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UAuthentication.into_raw(), 4);
+        buffer.write_bits(AuthenticationSubType::Result.into_raw(), 2);
+        // Type1
+        buffer.write_bits(self.authentication_result as u64, 1);
+        // Type1
+        buffer.write_bits(self.mutual_authentication_flag as u64, 1);
+        // Conditional
+        if self.mutual_authentication_flag != self.response_value.is_some() {
+            return Err(PduParseErr::Inconsistency {
+                field: "response_value",
+                reason: "must be present if and only if mutual_authentication_flag is set",
+            });
+        }
+        if let Some(value) = self.response_value {
+            buffer.write_bits(value as u64, 32);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UAuthenticationResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UAuthenticationResult {{ authentication_result: {:?} mutual_authentication_flag: {:?} response_value: {:?} proprietary: {:?} }}",
+            self.authentication_result, self.mutual_authentication_flag, self.response_value, self.proprietary,
+        )
+    }
+}
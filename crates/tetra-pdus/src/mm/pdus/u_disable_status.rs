@@ -0,0 +1,132 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+
+/// Subscription or equipment enabled
+pub const DISABLE_STATUS_ENABLED: u8 = 0;
+/// Subscription or equipment temporarily disabled
+pub const DISABLE_STATUS_TEMPORARY: u8 = 1;
+/// Subscription or equipment permanently disabled
+pub const DISABLE_STATUS_PERMANENT: u8 = 2;
+
+/// Representation of the U-DISABLE STATUS PDU (EN 300 392-7 Clause 5).
+/// The MS sends this message to the infrastructure to report its enable/disable state after D-DISABLE or D-ENABLE.
+/// Response expected: -
+/// Response to: D-DISABLE/D-ENABLE
+
+#[derive(Debug, PartialEq)]
+pub struct UDisableStatus {
+    /// Type1, 2 bits, Equipment status
+    pub equipment_status: u8,
+    /// Type1, 2 bits, Subscription status
+    pub subscription_status: u8,
+    /// Type1, 3 bits, Enable/disable result, 0 if the request was carried out
+    pub enable_disable_result: u8,
+    /// Type2, 32 bits, Authentication response (RES1)
+    pub authentication_response: Option<u64>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UDisableStatus {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UDisableStatus)?;
+
+        // Type1
+        let equipment_status = buffer.read_field(2, "equipment_status")? as u8;
+        // Type1
+        let subscription_status = buffer.read_field(2, "subscription_status")? as u8;
+        // Type1
+        let enable_disable_result = buffer.read_field(3, "enable_disable_result")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type2
+        let authentication_response = typed::parse_type2_generic(obit, buffer, 32, "authentication_response")?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UDisableStatus {
+            equipment_status,
+            subscription_status,
+            enable_disable_result,
+            authentication_response,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UDisableStatus.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.equipment_status as u64, 2);
+        // Type1
+        buffer.write_bits(self.subscription_status as u64, 2);
+        // Type1
+        buffer.write_bits(self.enable_disable_result as u64, 3);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.authentication_response.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit {
+            return Ok(());
+        }
+
+        // Type2
+        typed::write_type2_generic(obit, buffer, self.authentication_response, 32);
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UDisableStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UDisableStatus {{ equipment_status: {:?} subscription_status: {:?} enable_disable_result: {:?} authentication_response: {:?} proprietary: {:?} }}",
+            self.equipment_status, self.subscription_status, self.enable_disable_result, self.authentication_response, self.proprietary,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_u_disable_status() {
+        // Subscription temporarily disabled, request carried out, no optional elements
+        let test_vec = "101100010000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = UDisableStatus::from_bitbuf(&mut buf_in).expect("Failed parsing");
+        assert_eq!(pdu.equipment_status, DISABLE_STATUS_ENABLED);
+        assert_eq!(pdu.subscription_status, DISABLE_STATUS_TEMPORARY);
+        assert_eq!(pdu.enable_disable_result, 0);
+        assert_eq!(buf_in.get_len_remaining(), 0);
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
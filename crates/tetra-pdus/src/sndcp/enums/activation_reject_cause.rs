@@ -0,0 +1,72 @@
+/// Clause 28.4.4.1 Activation reject cause
+/// Bits: 8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ActivationRejectCause {
+    MsNotProvisionedForPacketData = 0,
+    Ipv4NotSupported = 1,
+    Ipv6NotSupported = 2,
+    Ipv4DynamicAddressNegotiationNotSupported = 3,
+    Ipv4StaticAddressNotSupported = 7,
+    Ipv4StaticAddressNotCorrect = 8,
+    MobileIpv4NotSupported = 9,
+    InsufficientResources = 12,
+    NsapiAlreadyInUse = 19,
+}
+
+impl std::convert::TryFrom<u64> for ActivationRejectCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(ActivationRejectCause::MsNotProvisionedForPacketData),
+            1 => Ok(ActivationRejectCause::Ipv4NotSupported),
+            2 => Ok(ActivationRejectCause::Ipv6NotSupported),
+            3 => Ok(ActivationRejectCause::Ipv4DynamicAddressNegotiationNotSupported),
+            7 => Ok(ActivationRejectCause::Ipv4StaticAddressNotSupported),
+            8 => Ok(ActivationRejectCause::Ipv4StaticAddressNotCorrect),
+            9 => Ok(ActivationRejectCause::MobileIpv4NotSupported),
+            12 => Ok(ActivationRejectCause::InsufficientResources),
+            19 => Ok(ActivationRejectCause::NsapiAlreadyInUse),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ActivationRejectCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            ActivationRejectCause::MsNotProvisionedForPacketData => 0,
+            ActivationRejectCause::Ipv4NotSupported => 1,
+            ActivationRejectCause::Ipv6NotSupported => 2,
+            ActivationRejectCause::Ipv4DynamicAddressNegotiationNotSupported => 3,
+            ActivationRejectCause::Ipv4StaticAddressNotSupported => 7,
+            ActivationRejectCause::Ipv4StaticAddressNotCorrect => 8,
+            ActivationRejectCause::MobileIpv4NotSupported => 9,
+            ActivationRejectCause::InsufficientResources => 12,
+            ActivationRejectCause::NsapiAlreadyInUse => 19,
+        }
+    }
+}
+
+impl From<ActivationRejectCause> for u64 {
+    fn from(e: ActivationRejectCause) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for ActivationRejectCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ActivationRejectCause::MsNotProvisionedForPacketData => write!(f, "MsNotProvisionedForPacketData"),
+            ActivationRejectCause::Ipv4NotSupported => write!(f, "Ipv4NotSupported"),
+            ActivationRejectCause::Ipv6NotSupported => write!(f, "Ipv6NotSupported"),
+            ActivationRejectCause::Ipv4DynamicAddressNegotiationNotSupported => write!(f, "Ipv4DynamicAddressNegotiationNotSupported"),
+            ActivationRejectCause::Ipv4StaticAddressNotSupported => write!(f, "Ipv4StaticAddressNotSupported"),
+            ActivationRejectCause::Ipv4StaticAddressNotCorrect => write!(f, "Ipv4StaticAddressNotCorrect"),
+            ActivationRejectCause::MobileIpv4NotSupported => write!(f, "MobileIpv4NotSupported"),
+            ActivationRejectCause::InsufficientResources => write!(f, "InsufficientResources"),
+            ActivationRejectCause::NsapiAlreadyInUse => write!(f, "NsapiAlreadyInUse"),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/// Clause 28.4.4.3 Address type identifier in demand / in accept
+/// Bits: 3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum AddressTypeIdentifier {
+    Ipv4Static = 0,
+    Ipv4Dynamic = 1,
+    /// Mobile IPv4, foreign agent care-of address requested
+    Ipv4MobileForeignAgent = 2,
+    /// Mobile IPv4, co-located care-of address requested
+    Ipv4MobileCoLocated = 3,
+    Ipv6 = 4,
+    /// Accept only: no address assigned, such as for a secondary context
+    NoAddress = 7,
+}
+
+impl std::convert::TryFrom<u64> for AddressTypeIdentifier {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(AddressTypeIdentifier::Ipv4Static),
+            1 => Ok(AddressTypeIdentifier::Ipv4Dynamic),
+            2 => Ok(AddressTypeIdentifier::Ipv4MobileForeignAgent),
+            3 => Ok(AddressTypeIdentifier::Ipv4MobileCoLocated),
+            4 => Ok(AddressTypeIdentifier::Ipv6),
+            7 => Ok(AddressTypeIdentifier::NoAddress),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AddressTypeIdentifier {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            AddressTypeIdentifier::Ipv4Static => 0,
+            AddressTypeIdentifier::Ipv4Dynamic => 1,
+            AddressTypeIdentifier::Ipv4MobileForeignAgent => 2,
+            AddressTypeIdentifier::Ipv4MobileCoLocated => 3,
+            AddressTypeIdentifier::Ipv6 => 4,
+            AddressTypeIdentifier::NoAddress => 7,
+        }
+    }
+}
+
+impl From<AddressTypeIdentifier> for u64 {
+    fn from(e: AddressTypeIdentifier) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for AddressTypeIdentifier {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AddressTypeIdentifier::Ipv4Static => write!(f, "Ipv4Static"),
+            AddressTypeIdentifier::Ipv4Dynamic => write!(f, "Ipv4Dynamic"),
+            AddressTypeIdentifier::Ipv4MobileForeignAgent => write!(f, "Ipv4MobileForeignAgent"),
+            AddressTypeIdentifier::Ipv4MobileCoLocated => write!(f, "Ipv4MobileCoLocated"),
+            AddressTypeIdentifier::Ipv6 => write!(f, "Ipv6"),
+            AddressTypeIdentifier::NoAddress => write!(f, "NoAddress"),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/// Clause 28.4.4.16 Deactivation type
+/// Bits: 8
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum DeactivationType {
+    /// Deactivate all PDP contexts of the MS
+    AllNsapis = 0,
+    /// Deactivate the PDP context of the NSAPI given in the PDU
+    GivenNsapi = 1,
+}
+
+impl std::convert::TryFrom<u64> for DeactivationType {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(DeactivationType::AllNsapis),
+            1 => Ok(DeactivationType::GivenNsapi),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DeactivationType {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DeactivationType::AllNsapis => 0,
+            DeactivationType::GivenNsapi => 1,
+        }
+    }
+}
+
+impl From<DeactivationType> for u64 {
+    fn from(e: DeactivationType) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for DeactivationType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeactivationType::AllNsapis => write!(f, "AllNsapis"),
+            DeactivationType::GivenNsapi => write!(f, "GivenNsapi"),
+        }
+    }
+}
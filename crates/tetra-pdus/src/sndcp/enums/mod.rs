@@ -0,0 +1,6 @@
+pub mod sn_pdu_type_dl;
+pub mod sn_pdu_type_ul;
+
+pub mod activation_reject_cause;
+pub mod address_type_identifier;
+pub mod deactivation_type;
@@ -0,0 +1,88 @@
+/// Clause 28.4.4.51 SN PDU type, downlink
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SnPduTypeDl {
+    SnActivatePdpContextAccept = 0,
+    SnDeactivatePdpContextAccept = 1,
+    SnDeactivatePdpContextDemand = 2,
+    SnActivatePdpContextReject = 3,
+    SnUnitdata = 4,
+    SnData = 5,
+    SnDataTransmitResponse = 7,
+    SnEndOfData = 8,
+    SnReconnect = 9,
+    SnPageRequest = 10,
+    SnNotSupported = 11,
+    SnDataPriority = 12,
+    SnModify = 13,
+}
+
+impl std::convert::TryFrom<u64> for SnPduTypeDl {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SnPduTypeDl::SnActivatePdpContextAccept),
+            1 => Ok(SnPduTypeDl::SnDeactivatePdpContextAccept),
+            2 => Ok(SnPduTypeDl::SnDeactivatePdpContextDemand),
+            3 => Ok(SnPduTypeDl::SnActivatePdpContextReject),
+            4 => Ok(SnPduTypeDl::SnUnitdata),
+            5 => Ok(SnPduTypeDl::SnData),
+            7 => Ok(SnPduTypeDl::SnDataTransmitResponse),
+            8 => Ok(SnPduTypeDl::SnEndOfData),
+            9 => Ok(SnPduTypeDl::SnReconnect),
+            10 => Ok(SnPduTypeDl::SnPageRequest),
+            11 => Ok(SnPduTypeDl::SnNotSupported),
+            12 => Ok(SnPduTypeDl::SnDataPriority),
+            13 => Ok(SnPduTypeDl::SnModify),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SnPduTypeDl {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SnPduTypeDl::SnActivatePdpContextAccept => 0,
+            SnPduTypeDl::SnDeactivatePdpContextAccept => 1,
+            SnPduTypeDl::SnDeactivatePdpContextDemand => 2,
+            SnPduTypeDl::SnActivatePdpContextReject => 3,
+            SnPduTypeDl::SnUnitdata => 4,
+            SnPduTypeDl::SnData => 5,
+            SnPduTypeDl::SnDataTransmitResponse => 7,
+            SnPduTypeDl::SnEndOfData => 8,
+            SnPduTypeDl::SnReconnect => 9,
+            SnPduTypeDl::SnPageRequest => 10,
+            SnPduTypeDl::SnNotSupported => 11,
+            SnPduTypeDl::SnDataPriority => 12,
+            SnPduTypeDl::SnModify => 13,
+        }
+    }
+}
+
+impl From<SnPduTypeDl> for u64 {
+    fn from(e: SnPduTypeDl) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SnPduTypeDl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SnPduTypeDl::SnActivatePdpContextAccept => write!(f, "SnActivatePdpContextAccept"),
+            SnPduTypeDl::SnDeactivatePdpContextAccept => write!(f, "SnDeactivatePdpContextAccept"),
+            SnPduTypeDl::SnDeactivatePdpContextDemand => write!(f, "SnDeactivatePdpContextDemand"),
+            SnPduTypeDl::SnActivatePdpContextReject => write!(f, "SnActivatePdpContextReject"),
+            SnPduTypeDl::SnUnitdata => write!(f, "SnUnitdata"),
+            SnPduTypeDl::SnData => write!(f, "SnData"),
+            SnPduTypeDl::SnDataTransmitResponse => write!(f, "SnDataTransmitResponse"),
+            SnPduTypeDl::SnEndOfData => write!(f, "SnEndOfData"),
+            SnPduTypeDl::SnReconnect => write!(f, "SnReconnect"),
+            SnPduTypeDl::SnPageRequest => write!(f, "SnPageRequest"),
+            SnPduTypeDl::SnNotSupported => write!(f, "SnNotSupported"),
+            SnPduTypeDl::SnDataPriority => write!(f, "SnDataPriority"),
+            SnPduTypeDl::SnModify => write!(f, "SnModify"),
+        }
+    }
+}
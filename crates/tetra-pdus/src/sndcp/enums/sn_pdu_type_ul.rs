@@ -0,0 +1,84 @@
+/// Clause 28.4.4.51 SN PDU type, uplink
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SnPduTypeUl {
+    SnActivatePdpContextDemand = 0,
+    SnDeactivatePdpContextDemand = 1,
+    SnDeactivatePdpContextAccept = 2,
+    SnUnitdata = 4,
+    SnData = 5,
+    SnDataTransmitRequest = 6,
+    SnEndOfData = 8,
+    SnReconnect = 9,
+    SnPageResponse = 10,
+    SnNotSupported = 11,
+    SnDataPriority = 12,
+    SnModify = 13,
+}
+
+impl std::convert::TryFrom<u64> for SnPduTypeUl {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(SnPduTypeUl::SnActivatePdpContextDemand),
+            1 => Ok(SnPduTypeUl::SnDeactivatePdpContextDemand),
+            2 => Ok(SnPduTypeUl::SnDeactivatePdpContextAccept),
+            4 => Ok(SnPduTypeUl::SnUnitdata),
+            5 => Ok(SnPduTypeUl::SnData),
+            6 => Ok(SnPduTypeUl::SnDataTransmitRequest),
+            8 => Ok(SnPduTypeUl::SnEndOfData),
+            9 => Ok(SnPduTypeUl::SnReconnect),
+            10 => Ok(SnPduTypeUl::SnPageResponse),
+            11 => Ok(SnPduTypeUl::SnNotSupported),
+            12 => Ok(SnPduTypeUl::SnDataPriority),
+            13 => Ok(SnPduTypeUl::SnModify),
+            _ => Err(()),
+        }
+    }
+}
+
+impl SnPduTypeUl {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            SnPduTypeUl::SnActivatePdpContextDemand => 0,
+            SnPduTypeUl::SnDeactivatePdpContextDemand => 1,
+            SnPduTypeUl::SnDeactivatePdpContextAccept => 2,
+            SnPduTypeUl::SnUnitdata => 4,
+            SnPduTypeUl::SnData => 5,
+            SnPduTypeUl::SnDataTransmitRequest => 6,
+            SnPduTypeUl::SnEndOfData => 8,
+            SnPduTypeUl::SnReconnect => 9,
+            SnPduTypeUl::SnPageResponse => 10,
+            SnPduTypeUl::SnNotSupported => 11,
+            SnPduTypeUl::SnDataPriority => 12,
+            SnPduTypeUl::SnModify => 13,
+        }
+    }
+}
+
+impl From<SnPduTypeUl> for u64 {
+    fn from(e: SnPduTypeUl) -> Self {
+        e.into_raw()
+    }
+}
+
+impl core::fmt::Display for SnPduTypeUl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SnPduTypeUl::SnActivatePdpContextDemand => write!(f, "SnActivatePdpContextDemand"),
+            SnPduTypeUl::SnDeactivatePdpContextDemand => write!(f, "SnDeactivatePdpContextDemand"),
+            SnPduTypeUl::SnDeactivatePdpContextAccept => write!(f, "SnDeactivatePdpContextAccept"),
+            SnPduTypeUl::SnUnitdata => write!(f, "SnUnitdata"),
+            SnPduTypeUl::SnData => write!(f, "SnData"),
+            SnPduTypeUl::SnDataTransmitRequest => write!(f, "SnDataTransmitRequest"),
+            SnPduTypeUl::SnEndOfData => write!(f, "SnEndOfData"),
+            SnPduTypeUl::SnReconnect => write!(f, "SnReconnect"),
+            SnPduTypeUl::SnPageResponse => write!(f, "SnPageResponse"),
+            SnPduTypeUl::SnNotSupported => write!(f, "SnNotSupported"),
+            SnPduTypeUl::SnDataPriority => write!(f, "SnDataPriority"),
+            SnPduTypeUl::SnModify => write!(f, "SnModify"),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod enums;
+pub mod pdus;
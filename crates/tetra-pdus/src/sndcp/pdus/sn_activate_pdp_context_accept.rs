@@ -0,0 +1,175 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::address_type_identifier::AddressTypeIdentifier;
+use crate::sndcp::enums::sn_pdu_type_dl::SnPduTypeDl;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT ACCEPT PDU (Clause 28.3.3.2).
+/// The SwMI sends this PDU to the MS to confirm activation of a PDP context.
+/// Response expected: -
+/// Response to: SN-ACTIVATE PDP CONTEXT DEMAND
+
+// note 1: The IP address is present for static and dynamic IPv4 addresses.
+#[derive(Debug)]
+pub struct SnActivatePdpContextAccept {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 3 bits, PDU priority max
+    pub pdu_priority_max: u8,
+    /// Type1, 4 bits, READY timer
+    pub ready_timer: u8,
+    /// Type1, 4 bits, STANDBY timer
+    pub standby_timer: u8,
+    /// Type1, 4 bits, Response wait time
+    pub response_wait_time: u8,
+    /// Type1, 3 bits, Address type identifier in accept
+    pub address_type_identifier: AddressTypeIdentifier,
+    /// Conditional 32 bits, See note 1,
+    pub ip_address: Option<u32>,
+    /// Type1, 8 bits, PCOMP negotiation
+    pub pcomp_negotiation: u8,
+}
+
+impl SnActivatePdpContextAccept {
+    fn has_ipv4_address(address_type_identifier: AddressTypeIdentifier) -> bool {
+        matches!(
+            address_type_identifier,
+            AddressTypeIdentifier::Ipv4Static | AddressTypeIdentifier::Ipv4Dynamic
+        )
+    }
+
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SnPduTypeDl::SnActivatePdpContextAccept)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let pdu_priority_max = buffer.read_field(3, "pdu_priority_max")? as u8;
+        // Type1
+        let ready_timer = buffer.read_field(4, "ready_timer")? as u8;
+        // Type1
+        let standby_timer = buffer.read_field(4, "standby_timer")? as u8;
+        // Type1
+        let response_wait_time = buffer.read_field(4, "response_wait_time")? as u8;
+        // Type1
+        let val = buffer.read_field(3, "address_type_identifier")?;
+        let address_type_identifier = AddressTypeIdentifier::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            field: "address_type_identifier",
+            value: val,
+        })?;
+        // Conditional
+        let ip_address = if Self::has_ipv4_address(address_type_identifier) {
+            Some(buffer.read_field(32, "ip_address")? as u32)
+        } else {
+            None
+        };
+        // Type1
+        let pcomp_negotiation = buffer.read_field(8, "pcomp_negotiation")? as u8;
+
+        // obit designates presence of any further type3 fields, which we skip
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            buffer.seek_rel(buffer.get_len_remaining() as isize);
+        }
+
+        Ok(SnActivatePdpContextAccept {
+            nsapi,
+            pdu_priority_max,
+            ready_timer,
+            standby_timer,
+            response_wait_time,
+            address_type_identifier,
+            ip_address,
+            pcomp_negotiation,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SnPduTypeDl::SnActivatePdpContextAccept.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.pdu_priority_max as u64, 3);
+        // Type1
+        buffer.write_bits(self.ready_timer as u64, 4);
+        // Type1
+        buffer.write_bits(self.standby_timer as u64, 4);
+        // Type1
+        buffer.write_bits(self.response_wait_time as u64, 4);
+        // Type1
+        buffer.write_bits(self.address_type_identifier.into_raw(), 3);
+        // Conditional
+        if Self::has_ipv4_address(self.address_type_identifier) {
+            let Some(ip_address) = self.ip_address else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("ip_address") });
+            };
+            buffer.write_bits(ip_address as u64, 32);
+        }
+        // Type1
+        buffer.write_bits(self.pcomp_negotiation as u64, 8);
+
+        // No type3 fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextAccept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextAccept {{ nsapi: {} pdu_priority_max: {} ready_timer: {} standby_timer: {} response_wait_time: {} address_type_identifier: {} ip_address: {:?} pcomp_negotiation: {} }}",
+            self.nsapi,
+            self.pdu_priority_max,
+            self.ready_timer,
+            self.standby_timer,
+            self.response_wait_time,
+            self.address_type_identifier,
+            self.ip_address,
+            self.pcomp_negotiation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_activate_pdp_context_accept() {
+        debug::setup_logging_verbose();
+        let pdu = SnActivatePdpContextAccept {
+            nsapi: 5,
+            pdu_priority_max: 3,
+            ready_timer: 4,
+            standby_timer: 6,
+            response_wait_time: 2,
+            address_type_identifier: AddressTypeIdentifier::Ipv4Dynamic,
+            ip_address: Some(0x0ac80002),
+            pcomp_negotiation: 0,
+        };
+        let mut buf = BitBuffer::new_autoexpand(80);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+        assert_eq!(
+            buf.to_bitstr(),
+            "0000010101101000110001000100001010110010000000000000000010000000000"
+        );
+
+        buf.seek(0);
+        let parsed = SnActivatePdpContextAccept::from_bitbuf(&mut buf).expect("Failed parsing");
+        tracing::info!("Parsed: {:?}", parsed);
+        assert_eq!(buf.get_len_remaining(), 0);
+        assert_eq!(parsed.nsapi, 5);
+        assert_eq!(parsed.ready_timer, 4);
+        assert_eq!(parsed.ip_address, Some(0x0ac80002));
+    }
+}
@@ -0,0 +1,158 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::address_type_identifier::AddressTypeIdentifier;
+use crate::sndcp::enums::sn_pdu_type_ul::SnPduTypeUl;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT DEMAND PDU (Clause 28.3.3.1).
+/// The MS sends this PDU to the SwMI to activate a PDP context for the given NSAPI.
+/// Response expected: SN-ACTIVATE PDP CONTEXT ACCEPT/SN-ACTIVATE PDP CONTEXT REJECT
+/// Response to: -
+
+// note 1: The IP address is present only when a static IPv4 address is requested.
+// note 2: Type 3 elements (protocol configuration options, DCOMP negotiation) are skipped, not interpreted.
+#[derive(Debug)]
+pub struct SnActivatePdpContextDemand {
+    /// Type1, 4 bits, SNDCP version
+    pub sndcp_version: u8,
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 3 bits, Address type identifier in demand
+    pub address_type_identifier: AddressTypeIdentifier,
+    /// Conditional 32 bits, See note 1,
+    pub ip_address: Option<u32>,
+    /// Type1, 4 bits, Packet data MS type
+    pub packet_data_ms_type: u8,
+    /// Type1, 8 bits, PCOMP negotiation
+    pub pcomp_negotiation: u8,
+}
+
+impl SnActivatePdpContextDemand {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SnPduTypeUl::SnActivatePdpContextDemand)?;
+
+        // Type1
+        let sndcp_version = buffer.read_field(4, "sndcp_version")? as u8;
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let val = buffer.read_field(3, "address_type_identifier")?;
+        let address_type_identifier = AddressTypeIdentifier::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            field: "address_type_identifier",
+            value: val,
+        })?;
+        // Conditional
+        let ip_address = if address_type_identifier == AddressTypeIdentifier::Ipv4Static {
+            Some(buffer.read_field(32, "ip_address")? as u32)
+        } else {
+            None
+        };
+        // Type1
+        let packet_data_ms_type = buffer.read_field(4, "packet_data_ms_type")? as u8;
+        // Type1
+        let pcomp_negotiation = buffer.read_field(8, "pcomp_negotiation")? as u8;
+
+        // obit designates presence of any further type3 fields, which we skip
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            buffer.seek_rel(buffer.get_len_remaining() as isize);
+        }
+
+        Ok(SnActivatePdpContextDemand {
+            sndcp_version,
+            nsapi,
+            address_type_identifier,
+            ip_address,
+            packet_data_ms_type,
+            pcomp_negotiation,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SnPduTypeUl::SnActivatePdpContextDemand.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.sndcp_version as u64, 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.address_type_identifier.into_raw(), 3);
+        // Conditional
+        if self.address_type_identifier == AddressTypeIdentifier::Ipv4Static {
+            let Some(ip_address) = self.ip_address else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("ip_address") });
+            };
+            buffer.write_bits(ip_address as u64, 32);
+        }
+        // Type1
+        buffer.write_bits(self.packet_data_ms_type as u64, 4);
+        // Type1
+        buffer.write_bits(self.pcomp_negotiation as u64, 8);
+
+        // No type3 fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextDemand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextDemand {{ sndcp_version: {} nsapi: {} address_type_identifier: {} ip_address: {:?} packet_data_ms_type: {} pcomp_negotiation: {} }}",
+            self.sndcp_version, self.nsapi, self.address_type_identifier, self.ip_address, self.packet_data_ms_type, self.pcomp_negotiation,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_activate_pdp_context_demand_dynamic() {
+        debug::setup_logging_verbose();
+        // Version 1, NSAPI 5, dynamic IPv4, MS type 2, no PCOMP
+        let test_vec = "0000000101010010010000000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnActivatePdpContextDemand::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, 5);
+        assert_eq!(pdu.address_type_identifier, AddressTypeIdentifier::Ipv4Dynamic);
+        assert_eq!(pdu.ip_address, None);
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        tracing::info!("Serialized: {}", buf_out.dump_bin());
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_sn_activate_pdp_context_demand_static() {
+        let pdu = SnActivatePdpContextDemand {
+            sndcp_version: 1,
+            nsapi: 1,
+            address_type_identifier: AddressTypeIdentifier::Ipv4Static,
+            ip_address: Some(0x0ac80005),
+            packet_data_ms_type: 0,
+            pcomp_negotiation: 0,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.get_len(), 4 + 4 + 4 + 3 + 32 + 4 + 8 + 1);
+
+        buf.seek(0);
+        let parsed = SnActivatePdpContextDemand::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert_eq!(parsed.ip_address, Some(0x0ac80005));
+        assert_eq!(buf.get_len_remaining(), 0);
+    }
+}
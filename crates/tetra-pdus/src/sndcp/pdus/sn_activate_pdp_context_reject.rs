@@ -0,0 +1,95 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::activation_reject_cause::ActivationRejectCause;
+use crate::sndcp::enums::sn_pdu_type_dl::SnPduTypeDl;
+
+/// Representation of the SN-ACTIVATE PDP CONTEXT REJECT PDU (Clause 28.3.3.3).
+/// The SwMI sends this PDU to the MS to refuse activation of a PDP context.
+/// Response expected: -
+/// Response to: SN-ACTIVATE PDP CONTEXT DEMAND
+#[derive(Debug)]
+pub struct SnActivatePdpContextReject {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 8 bits, Activation reject cause
+    pub activation_reject_cause: ActivationRejectCause,
+}
+
+impl SnActivatePdpContextReject {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SnPduTypeDl::SnActivatePdpContextReject)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let val = buffer.read_field(8, "activation_reject_cause")?;
+        let activation_reject_cause = ActivationRejectCause::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            field: "activation_reject_cause",
+            value: val,
+        })?;
+
+        // obit designates presence of any further type3 fields, which we skip
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            buffer.seek_rel(buffer.get_len_remaining() as isize);
+        }
+
+        Ok(SnActivatePdpContextReject {
+            nsapi,
+            activation_reject_cause,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SnPduTypeDl::SnActivatePdpContextReject.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.activation_reject_cause.into_raw(), 8);
+
+        // No type3 fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnActivatePdpContextReject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnActivatePdpContextReject {{ nsapi: {} activation_reject_cause: {} }}",
+            self.nsapi, self.activation_reject_cause,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_activate_pdp_context_reject() {
+        debug::setup_logging_verbose();
+        let test_vec = "00110101000011000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnActivatePdpContextReject::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, 5);
+        assert_eq!(pdu.activation_reject_cause, ActivationRejectCause::InsufficientResources);
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+}
@@ -0,0 +1,120 @@
+use core::fmt;
+
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::{BitBuffer, Direction, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::deactivation_type::DeactivationType;
+use crate::sndcp::enums::sn_pdu_type_dl::SnPduTypeDl;
+use crate::sndcp::enums::sn_pdu_type_ul::SnPduTypeUl;
+
+/// Representation of the SN-DEACTIVATE PDP CONTEXT ACCEPT PDU (Clause 28.3.3.4).
+/// Sent by either side to confirm deactivation of one or all PDP contexts of the MS.
+/// Response expected: -
+/// Response to: SN-DEACTIVATE PDP CONTEXT DEMAND
+
+// note 1: This PDU is used in both directions with a different SN PDU type, so parsing and serializing
+// take the direction of the PDU.
+// note 2: The NSAPI is present only if the deactivation type is for a given NSAPI.
+#[derive(Debug)]
+pub struct SnDeactivatePdpContextAccept {
+    /// Type1, 8 bits, Deactivation type
+    pub deactivation_type: DeactivationType,
+    /// Conditional 4 bits, See note 2,
+    pub nsapi: Option<u8>,
+}
+
+impl SnDeactivatePdpContextAccept {
+    fn pdu_type(dir: Direction) -> u64 {
+        match dir {
+            Direction::Ul => SnPduTypeUl::SnDeactivatePdpContextAccept.into_raw(),
+            _ => SnPduTypeDl::SnDeactivatePdpContextAccept.into_raw(),
+        }
+    }
+
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer, dir: Direction) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        let expected = Self::pdu_type(dir);
+        if pdu_type != expected {
+            return Err(PduParseErr::InvalidPduType { expected, found: pdu_type });
+        }
+
+        // Type1
+        let val = buffer.read_field(8, "deactivation_type")?;
+        let deactivation_type = DeactivationType::try_from(val).map_err(|_| PduParseErr::InvalidValue {
+            field: "deactivation_type",
+            value: val,
+        })?;
+        // Conditional
+        let nsapi = if deactivation_type == DeactivationType::GivenNsapi {
+            Some(buffer.read_field(4, "nsapi")? as u8)
+        } else {
+            None
+        };
+
+        // obit designates presence of any further type3 fields, which we skip
+        let obit = delimiters::read_obit(buffer)?;
+        if obit {
+            buffer.seek_rel(buffer.get_len_remaining() as isize);
+        }
+
+        Ok(SnDeactivatePdpContextAccept { deactivation_type, nsapi })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer, dir: Direction) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(Self::pdu_type(dir), 4);
+        // Type1
+        buffer.write_bits(self.deactivation_type.into_raw(), 8);
+        // Conditional
+        if self.deactivation_type == DeactivationType::GivenNsapi {
+            let Some(nsapi) = self.nsapi else {
+                return Err(PduParseErr::FieldNotPresent { field: Some("nsapi") });
+            };
+            buffer.write_bits(nsapi as u64, 4);
+        }
+
+        // No type3 fields
+        delimiters::write_obit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnDeactivatePdpContextAccept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnDeactivatePdpContextAccept {{ deactivation_type: {} nsapi: {:?} }}",
+            self.deactivation_type, self.nsapi,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_deactivate_pdp_context_accept() {
+        debug::setup_logging_verbose();
+        let test_vec = "00010000000101010";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnDeactivatePdpContextAccept::from_bitbuf(&mut buf_in, Direction::Dl).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.deactivation_type, DeactivationType::GivenNsapi);
+        assert_eq!(pdu.nsapi, Some(5));
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out, Direction::Dl).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+
+        // The same bits carry the other PDU type in the opposite direction
+        buf_in.seek(0);
+        assert!(SnDeactivatePdpContextAccept::from_bitbuf(&mut buf_in, Direction::Ul).is_err());
+    }
+}
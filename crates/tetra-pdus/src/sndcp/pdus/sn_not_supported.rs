@@ -0,0 +1,49 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sn_pdu_type_ul::SnPduTypeUl;
+
+/// Representation of the SN-NOT SUPPORTED PDU (Clause 28.3.3.13).
+/// Sent by either side in response to an SN PDU type it does not support.
+/// Response expected: -
+/// Response to: any unsupported SN PDU
+
+// note 1: The SN PDU type is the same in both directions.
+#[derive(Debug)]
+pub struct SnNotSupported {
+    /// Type1, 4 bits, Not supported SN PDU type
+    pub not_supported_sn_pdu_type: u8,
+}
+
+impl SnNotSupported {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SnPduTypeUl::SnNotSupported)?;
+
+        // Type1
+        let not_supported_sn_pdu_type = buffer.read_field(4, "not_supported_sn_pdu_type")? as u8;
+
+        Ok(SnNotSupported { not_supported_sn_pdu_type })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SnPduTypeUl::SnNotSupported.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.not_supported_sn_pdu_type as u64, 4);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnNotSupported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnNotSupported {{ not_supported_sn_pdu_type: {} }}",
+            self.not_supported_sn_pdu_type
+        )
+    }
+}
@@ -0,0 +1,116 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+
+use crate::sndcp::enums::sn_pdu_type_ul::SnPduTypeUl;
+
+/// Representation of the SN-UNITDATA PDU (Clause 28.3.3.14).
+/// Carries an N-PDU over the unacknowledged basic link service.
+/// Response expected: -
+/// Response to: -
+
+// note 1: The SN PDU type is the same in both directions.
+#[derive(Debug)]
+pub struct SnUnitdata {
+    /// Type1, 4 bits, NSAPI
+    pub nsapi: u8,
+    /// Type1, 4 bits, PCOMP, 0 for no protocol header compression
+    pub pcomp: u8,
+    /// Type1, 4 bits, DCOMP, 0 for no data compression
+    pub dcomp: u8,
+    /// N-PDU, the remainder of the PDU: an IP packet
+    pub n_pdu: Vec<u8>,
+}
+
+impl SnUnitdata {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, SnPduTypeUl::SnUnitdata)?;
+
+        // Type1
+        let nsapi = buffer.read_field(4, "nsapi")? as u8;
+        // Type1
+        let pcomp = buffer.read_field(4, "pcomp")? as u8;
+        // Type1
+        let dcomp = buffer.read_field(4, "dcomp")? as u8;
+
+        // N-PDU
+        let n_pdu_bits = buffer.get_len_remaining();
+        if !n_pdu_bits.is_multiple_of(8) {
+            return Err(PduParseErr::InconsistentLength {
+                expected: n_pdu_bits / 8 * 8,
+                found: n_pdu_bits,
+            });
+        }
+        let mut n_pdu = vec![0u8; n_pdu_bits / 8];
+        buffer
+            .read_bits_into_slice(n_pdu_bits, &mut n_pdu)
+            .ok_or(PduParseErr::BufferEnded { field: Some("n_pdu") })?;
+
+        Ok(SnUnitdata {
+            nsapi,
+            pcomp,
+            dcomp,
+            n_pdu,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(SnPduTypeUl::SnUnitdata.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.nsapi as u64, 4);
+        // Type1
+        buffer.write_bits(self.pcomp as u64, 4);
+        // Type1
+        buffer.write_bits(self.dcomp as u64, 4);
+        // N-PDU
+        for byte in &self.n_pdu {
+            buffer.write_bits(*byte as u64, 8);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SnUnitdata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SnUnitdata {{ nsapi: {} pcomp: {} dcomp: {} n_pdu: {} bytes }}",
+            self.nsapi,
+            self.pcomp,
+            self.dcomp,
+            self.n_pdu.len(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_sn_unitdata() {
+        debug::setup_logging_verbose();
+        let test_vec = "01000101000000000100010100000000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = SnUnitdata::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.nsapi, 5);
+        assert_eq!(pdu.n_pdu, vec![0x45, 0x00]);
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+
+        // N-PDU must be a whole number of octets
+        let mut buf_in = BitBuffer::from_bitstr(&test_vec[..test_vec.len() - 1]);
+        assert!(SnUnitdata::from_bitbuf(&mut buf_in).is_err());
+    }
+}
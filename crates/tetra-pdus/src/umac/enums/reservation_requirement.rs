@@ -102,13 +102,10 @@ impl ReservationRequirement {
     }
 
     /// Returns 0 when just a single subslot is required
-    /// Returns 99 when over 69 subslots are required
+    /// Returns 68, the largest capacity that can be granted at once, when over 68 slots are required
     pub fn to_req_slotcount(&self) -> usize {
         match self {
-            ReservationRequirement::Req1Subslot => {
-                unimplemented!();
-                // 0
-            }
+            ReservationRequirement::Req1Subslot => 0,
             ReservationRequirement::Req1Slot => 1,
             ReservationRequirement::Req2Slots => 2,
             ReservationRequirement::Req3Slots => 3,
@@ -123,10 +120,7 @@ impl ReservationRequirement {
             ReservationRequirement::Req34Slots => 34,
             ReservationRequirement::Req51Slots => 51,
             ReservationRequirement::Req68Slots => 68,
-            ReservationRequirement::ReqOver68 => {
-                unimplemented!();
-                // 99
-            }
+            ReservationRequirement::ReqOver68 => 68,
         }
     }
 }
@@ -2,9 +2,36 @@
 
 use core::fmt;
 
-use tetra_core::{BitBuffer, Todo, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 use tetra_saps::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
 
+/// Extended carrier numbering, for a carrier outside the band and offset of the main carrier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtCarrierNum {
+    // 4
+    pub freq_band: u8,
+    // 2
+    pub offset: u8,
+    // 3
+    pub duplex_spacing: u8,
+    // 1
+    pub reverse_operation: bool,
+}
+
+impl ExtCarrierNum {
+    pub const LEN: usize = 4 + 2 + 3 + 1;
+}
+
+impl fmt::Display for ExtCarrierNum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{{ freq_band: {} offset: {} duplex_spacing: {} reverse_operation: {} }}",
+            self.freq_band, self.offset, self.duplex_spacing, self.reverse_operation
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ChanAllocElement {
     // 2
@@ -19,17 +46,8 @@ pub struct ChanAllocElement {
     pub cell_change_flag: bool,
     // 12
     pub carrier_num: u16,
-    // 1
-    // pub ext_carrier_num_flag: bool,
-    // 4 opt
-    // pub ext_freq_band: Option<u8>,
-    // 2 opt
-    // pub ext_offset: Option<u8>,
-    // 3 opt
-    // pub ext_duplex_spacing: Option<u8>,
-    // 1 opt
-    // pub ext_reverse_operation: Option<bool>,
-    pub ext: Option<Todo>,
+    // 1 + 10 opt
+    pub ext: Option<ExtCarrierNum>,
     // 2
     pub mon_pattern: u8,
     // 2 opt
@@ -91,17 +109,12 @@ impl ChanAllocElement {
 
         let ext_carrier_num_flag = buf.read_field(1, "ext_carrier_num_flag")? == 1;
         let ext = if ext_carrier_num_flag {
-            unimplemented!("Extended channel allocation");
-            // let (ext_freq_band, ext_offset, ext_duplex_spacing, ext_reverse_operation) = match ext_carrier_num_flag {
-            //     false => (None, None, None, None),
-            //     true => {
-            //         let ext_freq_band = buf.read_field(4, "ext_freq_band")? as u8;
-            //         let ext_offset = buf.read_field(2, "ext_offset")? as u8;
-            //         let ext_duplex_spacing = buf.read_field(3, "ext_duplex_spacing")? as u8;
-            //         let ext_reverse_operation = buf.read_field(1, "ext_reverse_operation")? != 0;
-            //         (Some(ext_freq_band), Some(ext_offset), Some(ext_duplex_spacing), Some(ext_reverse_operation))
-            //     },
-            // };
+            Some(ExtCarrierNum {
+                freq_band: buf.read_field(4, "ext_freq_band")? as u8,
+                offset: buf.read_field(2, "ext_offset")? as u8,
+                duplex_spacing: buf.read_field(3, "ext_duplex_spacing")? as u8,
+                reverse_operation: buf.read_field(1, "ext_reverse_operation")? != 0,
+            })
         } else {
             None
         };
@@ -113,7 +126,9 @@ impl ChanAllocElement {
         };
 
         if ul_dl_assigned == UlDlAssignment::Augmented {
-            unimplemented!("Augmented channel allocation is not implemented");
+            return Err(PduParseErr::NotImplemented {
+                field: Some("augmented channel allocation"),
+            });
         }
 
         Ok(ChanAllocElement {
@@ -124,10 +139,6 @@ impl ChanAllocElement {
             cell_change_flag,
             carrier_num,
             ext,
-            // ext_freq_band,
-            // ext_offset,
-            // ext_duplex_spacing,
-            // ext_reverse_operation,
             mon_pattern,
             frame18_mon_pattern,
         })
@@ -143,14 +154,12 @@ impl ChanAllocElement {
         buf.write_bits(self.cell_change_flag as u8 as u64, 1);
         buf.write_bits(self.carrier_num as u64, 12);
 
-        // If ext_freq_band supplied, we assume all four fields are there
-        if let Some(_ext) = self.ext {
+        if let Some(ext) = self.ext {
             buf.write_bits(1, 1); // Extended carrier number flag
-            unimplemented!("Extended channel allocation");
-            // buf.write_bits(ext_freq_band as u64, 4);
-            // buf.write_bits(self.ext_offset.unwrap() as u64, 2);
-            // buf.write_bits(self.ext_duplex_spacing.unwrap() as u64, 3);
-            // buf.write_bits(self.ext_reverse_operation.unwrap() as u8 as u64, 1);
+            buf.write_bits(ext.freq_band as u64, 4);
+            buf.write_bits(ext.offset as u64, 2);
+            buf.write_bits(ext.duplex_spacing as u64, 3);
+            buf.write_bits(ext.reverse_operation as u8 as u64, 1);
         } else {
             buf.write_bits(0, 1); // Extended carrier number flag
         }
@@ -199,8 +208,8 @@ impl ChanAllocElement {
         // Until and including ext carrier numbering flag
         let mut len = 2 + 4 + 2 + 1 + 1 + 12 + 1;
 
-        if let Some(_ext) = &self.ext {
-            unimplemented!("Extended carrier numbering");
+        if self.ext.is_some() {
+            len += ExtCarrierNum::LEN;
         }
 
         len += 2;
@@ -316,4 +325,34 @@ mod tests {
         assert_eq!(bitstr, buffer_out.to_bitstr());
         assert_eq!(bitstr.len(), result.compute_len());
     }
+
+    #[test]
+    fn test_chanalloc_ext_carrier_roundtrip() {
+        debug::setup_logging_verbose();
+        let elem = ChanAllocElement {
+            alloc_type: ChanAllocType::Replace,
+            ts_assigned: [false, true, false, false],
+            ul_dl_assigned: UlDlAssignment::Both,
+            clch_permission: false,
+            cell_change_flag: false,
+            carrier_num: 1525,
+            ext: Some(ExtCarrierNum {
+                freq_band: 4,
+                offset: 2,
+                duplex_spacing: 3,
+                reverse_operation: true,
+            }),
+            mon_pattern: 0,
+            frame18_mon_pattern: Some(0),
+        };
+
+        let mut buffer = BitBuffer::new_autoexpand(40);
+        elem.to_bitbuf(&mut buffer);
+        assert_eq!(buffer.get_pos(), elem.compute_len());
+        buffer.seek(0);
+        let parsed = ChanAllocElement::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(parsed.carrier_num, 1525);
+        assert_eq!(parsed.ext, elem.ext);
+        assert_eq!(parsed.ts_assigned, elem.ts_assigned);
+    }
 }
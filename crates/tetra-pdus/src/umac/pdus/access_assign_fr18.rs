@@ -94,11 +94,10 @@ impl AccessAssignFr18 {
             3 => {
                 // UL usage counts as CommonAndAssigned, but with traffic marker
                 let ul_usage = AccessAssignUlUsage::from_usage_marker(field1);
-                s.ul_usage = ul_usage.ok_or(PduParseErr::InvalidValue {
+                s.ul_usage = ul_usage.filter(|u| u.is_traffic()).ok_or(PduParseErr::InvalidValue {
                     field: "ul_usage",
                     value: field1 as u64,
                 })?;
-                assert!(ul_usage.unwrap().is_traffic());
 
                 s.f2_af = Some(AccessField {
                     access_code: (field2 >> 4) & 0x3,
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, expect_value, pdu_parse_error::PduParseErr};
 
 /// Clause 21.4.4.3 ACCESS-DEFINE
 #[derive(Debug, Clone)]
@@ -46,9 +46,9 @@ impl AccessDefine {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 2);
+        expect_value!(buf.read_field(2, "mac_pdu_type")?, 2, "mac_pdu_type")?;
         // required constant broadcast_type
-        assert!(buf.read_field(2, "broadcast_type")? == 1);
+        expect_value!(buf.read_field(2, "broadcast_type")?, 1, "broadcast_type")?;
         s.common_or_assigned_control = buf.read_field(1, "common_or_assigned_control")? != 0;
         s.access_code = buf.read_field(2, "access_code")? as u8;
         s.imm = buf.read_field(4, "imm")? as u8;
@@ -67,7 +67,7 @@ impl AccessDefine {
             s.gssi = Some(buf.read_field(24, "gssi")? as u32);
         }
         // required constant FILLER
-        assert!(buf.read_field(3, "filler")? == 4);
+        expect_value!(buf.read_field(3, "filler")?, 4, "filler")?;
 
         Ok(s)
     }
@@ -1,6 +1,6 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, SsiType, TetraAddress, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_value, pdu_parse_error::PduParseErr};
 
 use crate::umac::{enums::reservation_requirement::ReservationRequirement, fields::EventLabel};
 
@@ -35,7 +35,7 @@ impl MacAccess {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
         let mac_pdu_type = buf.read_field(1, "mac_pdu_type")?;
-        assert!(mac_pdu_type == 0);
+        expect_value!(mac_pdu_type, 0)?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
         let encrypted = buf.read_field(1, "encrypted")? != 0;
 
@@ -111,7 +111,7 @@ impl MacAccess {
         if let Some(addr) = self.addr {
             assert!(addr.encrypted == self.encrypted, "pdu and addr need same encryption status");
             match addr.ssi_type {
-                SsiType::Ssi => {
+                SsiType::Ssi | SsiType::Issi | SsiType::Gssi => {
                     buf.write_bits(0, 2);
                     buf.write_bits(addr.ssi as u64, 24);
                 }
@@ -1,7 +1,7 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, expect_value};
 
 use crate::umac::fields::basic_slotgrant::BasicSlotgrant;
 
@@ -34,9 +34,9 @@ impl MacDBlck {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 3);
+        expect_value!(buf.read_field(2, "mac_pdu_type")?, 3, "mac_pdu_type")?;
         // required constant pdu_subtype
-        assert!(buf.read_field(1, "pdu_subtype")? == 0);
+        expect_value!(buf.read_field(1, "pdu_subtype")?, 0, "pdu_subtype")?;
 
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
         s.encryption_mode = buf.read_field(2, "encryption_mode")? as u8;
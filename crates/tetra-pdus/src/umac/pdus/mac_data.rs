@@ -1,7 +1,7 @@
 use core::fmt;
 
 use tetra_core::pdu_parse_error::PduParseErr;
-use tetra_core::{BitBuffer, SsiType, TetraAddress};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_value};
 
 use crate::umac::enums::reservation_requirement::ReservationRequirement;
 
@@ -30,7 +30,7 @@ pub struct MacData {
 impl MacData {
     pub fn from_bitbuf(buf: &mut BitBuffer) -> Result<Self, PduParseErr> {
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 0);
+        expect_value!(buf.read_field(2, "mac_pdu_type")?, 0, "mac_pdu_type")?;
         let fill_bits = buf.read_field(1, "fill_bits")? != 0;
         let encrypted = buf.read_field(1, "encrypted")? != 0;
         let addr_type = buf.read_field(2, "addr_type")? as u8;
@@ -1,7 +1,7 @@
 use core::fmt;
 
-use tetra_core::BitBuffer;
 use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, expect_value};
 
 use crate::umac::fields::basic_slotgrant::BasicSlotgrant;
 use crate::umac::fields::channel_allocation::ChanAllocElement;
@@ -36,9 +36,9 @@ impl MacEndDl {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 1);
+        expect_value!(buf.read_field(2, "mac_pdu_type")?, 1, "mac_pdu_type")?;
         // required constant pdu_subtype
-        assert!(buf.read_field(1, "pdu_subtype")? == 1);
+        expect_value!(buf.read_field(1, "pdu_subtype")?, 1, "pdu_subtype")?;
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
         s.pos_of_grant = buf.read_field(1, "pos_of_grant")? as u8;
         s.length_ind = buf.read_field(6, "length_ind")? as u8;
@@ -1,7 +1,7 @@
 use core::fmt;
 use std::panic;
 
-use tetra_core::{BitBuffer, SsiType, TetraAddress, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_value, pdu_parse_error::PduParseErr};
 
 use crate::umac::{
     enums::mac_resource_addr_type::MacResourceAddrType,
@@ -85,7 +85,7 @@ impl MacResource {
         };
 
         // required constant mac_pdu_type
-        assert!(buf.read_field(2, "mac_pdu_type")? == 0);
+        expect_value!(buf.read_field(2, "mac_pdu_type")?, 0, "mac_pdu_type")?;
         s.fill_bits = buf.read_field(1, "fill_bits")? != 0;
         s.pos_of_grant = buf.read_field(1, "pos_of_grant")? as u8;
         s.encryption_mode = buf.read_field(2, "encryption_mode")? as u8;
@@ -98,13 +98,11 @@ impl MacSysinfo {
         match s.option_field {
             SysinfoOptFieldFlag::EvenMfDefForTsMode => {
                 tracing::trace!("Sysinfo: Even multiframe definition for TS mode");
-                buf.seek_rel(20);
-                unimplemented_log!("Even multiframe definition for TS mode");
+                s.ts_common_frames = Some(TsCommonFrames::from_bitbuf(buf)?);
             }
             SysinfoOptFieldFlag::OddMfDefForTsMode => {
                 tracing::trace!("Sysinfo: Odd multiframe definition for TS mode");
-                buf.seek_rel(20);
-                unimplemented_log!("Odd multiframe definition for TS mode");
+                s.ts_common_frames = Some(TsCommonFrames::from_bitbuf(buf)?);
             }
             SysinfoOptFieldFlag::DefaultDefForAccCodeA => {
                 tracing::trace!("Sysinfo: Default definition for access code A");
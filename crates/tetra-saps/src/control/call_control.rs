@@ -7,6 +7,9 @@ pub struct Circuit {
     /// Direction
     pub direction: Direction,
 
+    /// Carrier on which this circuit exists, 0 for the main carrier and 1.. for the secondary carriers
+    pub carrier: u8,
+
     /// Timeslot in which this circuit exists
     pub ts: u8,
 
@@ -25,6 +28,29 @@ pub struct Circuit {
     pub speech_service: Option<u8>,
     /// Whether end-to-end encryption is enabled on this circuit
     pub etee_encrypted: bool,
+    /// Duplex calls only: timeslot on the same carrier whose DL carries the UL traffic of this circuit.
+    /// None loops UL traffic back onto the DL of the same timeslot.
+    pub peer_ts: Option<u8>,
+}
+
+/// Snapshot of an active call, as reported to the management API
+#[derive(Debug, Clone)]
+pub struct CallInfo {
+    pub call_id: u16,
+    /// True for an individual call between two local ISSIs, false for a group call
+    pub individual: bool,
+    /// Calling party of an individual call, current or last speaker of a group call
+    pub source_ssi: u32,
+    /// Called ISSI or GSSI
+    pub dest_ssi: u32,
+    /// Carrier of the traffic timeslots, 0 for the main carrier
+    pub carrier: u8,
+    /// Timeslots carrying traffic for this call, empty while still being set up
+    pub timeslots: Vec<u8>,
+    /// Group calls only: started by the network (Brew) rather than by a local MS
+    pub network: bool,
+    /// Someone currently holds the floor
+    pub tx_active: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -36,22 +62,25 @@ pub enum CallControl {
     /// Signals to release a circuit
     /// Created by CMCE, sent to Umac
     /// Umac forwards to Lmac
-    /// Contains (Direction, timeslot) of associated circuit
-    Close(Direction, u8),
+    /// Contains (Direction, carrier, timeslot) of associated circuit
+    Close(Direction, u8, u8),
     /// Floor granted: a speaker has been given transmission permission.
     /// Sent to UMAC to exit hangtime (resume traffic mode) and to Brew to start forwarding voice.
     FloorGranted {
         call_id: u16,
         source_issi: u32,
         dest_gssi: u32,
+        carrier: u8,
         ts: u8,
+        /// Call priority, 15 for emergency calls
+        priority: u8,
     },
     /// Floor released: speaker stopped transmitting (entering hangtime).
     /// Sent to UMAC to enter hangtime signalling mode and to Brew to stop forwarding audio.
-    FloorReleased { call_id: u16, ts: u8 },
+    FloorReleased { call_id: u16, carrier: u8, ts: u8 },
     /// Call ended: the call is being torn down.
     /// Sent to UMAC to clear hangtime state and to Brew to clean up call tracking.
-    CallEnded { call_id: u16, ts: u8 },
+    CallEnded { call_id: u16, carrier: u8, ts: u8 },
     /// Request CMCE to start a network-initiated group call
     /// Sent by Brew when TetraPack sends GROUP_TX
     NetworkCallStart {
@@ -65,6 +94,7 @@ pub enum CallControl {
     NetworkCallReady {
         brew_uuid: uuid::Uuid, // Matches request
         call_id: u16,          // CMCE-allocated call identifier
+        carrier: u8,           // Carrier of the allocated timeslot
         ts: u8,                // Allocated timeslot
         usage: u8,             // Usage number
     },
@@ -75,5 +105,16 @@ pub enum CallControl {
     },
     /// UL inactivity detected on a traffic timeslot — no voice frames received
     /// for the timeout period. Sent by UMAC to CMCE.
-    UlInactivityTimeout { ts: u8 },
+    UlInactivityTimeout { carrier: u8, ts: u8 },
+    /// Request a list of the active calls.
+    /// Sent by the management API to CMCE, which answers with CallList
+    ListCalls { req_id: u64 },
+    /// Active calls, in response to ListCalls
+    CallList { req_id: u64, calls: Vec<CallInfo> },
+    /// Request CMCE to release a call, as if ordered by the SwMI.
+    /// Sent by the management API
+    ReleaseCall { call_id: u16 },
+    /// DTMF digits sent within a group call, out of "0123456789*#ABCD".
+    /// Sent by CMCE to Brew for U-INFO from a local MS, and by Brew to CMCE for delivery to the group in D-INFO
+    Dtmf { call_id: u16, digits: String },
 }
@@ -0,0 +1,38 @@
+/// Requests to MM from other entities or the operator
+#[derive(Debug, Clone)]
+pub enum MmControl {
+    /// Deregister an MS, dropping its group attachments. The MS itself is not signalled.
+    Deregister { issi: u32 },
+    /// Attach an MS to groups and detach it from others with D-ATTACH/DETACH GROUP IDENTITY (DGNA).
+    /// The registry follows once the MS acknowledges.
+    AssignGroups { issi: u32, attach: Vec<u32>, detach: Vec<u32> },
+    /// Disable a lost or stolen MS with D-DISABLE and deregister it. A disabled subscription stays refused,
+    /// also when registering again, until enabled.
+    Disable { issi: u32, permanent: bool, target: DisableTarget },
+    /// Lift the disabling of an MS and send it D-ENABLE
+    Enable { issi: u32, target: DisableTarget },
+}
+
+/// What D-DISABLE and D-ENABLE act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisableTarget {
+    /// The subscription of the ISSI
+    Subscription,
+    /// The terminal with the given TETRA equipment identity, whichever subscription it holds
+    Equipment { tei: u64 },
+    /// Both the subscription and the terminal
+    Both { tei: u64 },
+}
+
+impl DisableTarget {
+    pub fn subscription(&self) -> bool {
+        matches!(self, DisableTarget::Subscription | DisableTarget::Both { .. })
+    }
+
+    pub fn tei(&self) -> Option<u64> {
+        match self {
+            DisableTarget::Subscription => None,
+            DisableTarget::Equipment { tei } | DisableTarget::Both { tei } => Some(*tei),
+        }
+    }
+}
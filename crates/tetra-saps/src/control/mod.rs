@@ -1,4 +1,7 @@
 pub mod brew;
 pub mod call_control;
 pub mod enums;
+pub mod mm;
+pub mod packet_data;
+pub mod pdch;
 pub mod sds;
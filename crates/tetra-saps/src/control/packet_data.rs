@@ -0,0 +1,9 @@
+/// IP packet routing between the SNDCP entity and Brew entity
+#[derive(Debug, Clone)]
+pub struct SndcpPacketData {
+    /// ISSI of the sending MS for packets towards Brew. For packets from Brew, the MS the
+    /// packet is addressed to if known, otherwise None and SNDCP routes by destination IP.
+    pub issi: Option<u32>,
+    /// Complete IP packet (N-PDU)
+    pub packet: Vec<u8>,
+}
@@ -0,0 +1,9 @@
+/// Packet data channel control, created by SNDCP and sent to UMAC
+#[derive(Debug, Clone)]
+pub enum PdchControl {
+    /// Operate the given timeslot as an assigned control channel for packet data.
+    /// UMAC keeps it in signalling mode and advertises it as assigned in the AACH.
+    Open { ts: u8 },
+    /// Return the given timeslot to the pool of traffic timeslots
+    Close { ts: u8 },
+}
@@ -1,13 +1,11 @@
-use tetra_core::Todo;
-
 use crate::lcmc::enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment};
 
 #[derive(Debug, Clone)]
 pub struct CmceChanAllocReq {
     /// Set for new allocation, None for QuitAndGo
     pub usage: Option<u8>,
-    /// Carrier frequency; by default, uses self
-    pub carrier: Option<Todo>,
+    /// Carrier index to allocate on, 0 for the main carrier; by default, uses the current carrier
+    pub carrier: Option<u8>,
     /// Bitmap of slots to use.
     pub timeslots: [bool; 4],
     /// Alloc type.
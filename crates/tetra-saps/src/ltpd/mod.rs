@@ -3,6 +3,8 @@
 #![allow(unused)]
 use tetra_core::{BitBuffer, EndpointId, Layer2Service, LinkId, TetraAddress, Todo};
 
+use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+
 #[derive(Debug, Clone)]
 pub struct LtpdMleActivityReq {
     pub sleep_mode: bool,
@@ -195,25 +197,23 @@ pub struct LtpdMleResumeInd {
     pub mnc: Todo, // Current network
 }
 
+/// MLE-UNITDATA request: this primitive shall be used by the SNDCP entity to send an SN-PDU to a peer entity.
 #[derive(Debug, Clone)]
 pub struct LtpdMleUnitdataReq {
-    pub sdu: Todo,
+    pub sdu: BitBuffer,
     pub handle: Todo,
     pub layer2service: Layer2Service,
-    pub unacked_bl_repetitions: Todo,
     pub pdu_prio: Todo,
     pub endpoint_id: EndpointId,
     pub link_id: LinkId,
     pub stealing_permission: bool,
-    pub stealing_repeats_flag: bool,
-    pub channel_advice_flag: bool,
-    pub data_class_info: Todo,
-    pub data_prio: Todo,
-    pub mle_data_prio_flag: bool,
     pub packet_data_flag: bool,
-    pub scheduled_data_status: Todo,
-    pub max_schedule_interval: Todo,
     pub fcs_flag: bool,
+
+    /// Custom field to address the MS, as the BS has no endpoints per MS
+    pub main_address: TetraAddress,
+    /// Custom field to move the MS onto a packet data channel
+    pub chan_alloc: Option<CmceChanAllocReq>,
 }
 
 #[derive(Debug, Clone)]
@@ -6,6 +6,9 @@ use tetra_core::tetra_entities::TetraEntity;
 
 use crate::control::brew::MmSubscriberUpdate;
 use crate::control::call_control::CallControl;
+use crate::control::mm::MmControl;
+use crate::control::packet_data::SndcpPacketData;
+use crate::control::pdch::PdchControl;
 use crate::control::sds::CmceSdsData;
 use crate::tmd::TmdCircuitDataInd;
 use crate::tmd::TmdCircuitDataReq;
@@ -70,6 +73,7 @@ pub enum SapMsgInner {
     // LMM-SAP (MLE-MM)
     LmmMleUnitdataInd(LmmMleUnitdataInd),
     LmmMleUnitdataReq(LmmMleUnitdataReq),
+    LmmMleActivateConf(LmmMleActivateConf),
 
     // LCMC-SAP (MLE-CMCE)
     LcmcMleUnitdataInd(LcmcMleUnitdataInd),
@@ -84,8 +88,18 @@ pub enum SapMsgInner {
     // CMCE SDS <-> Brew SDS routing
     CmceSdsData(CmceSdsData),
 
-    // LTPD-SAP (MLE-LTPD)
+    // LTPD-SAP (MLE-SNDCP)
     LtpdMleUnitdataInd(LtpdMleUnitdataInd),
+    LtpdMleUnitdataReq(LtpdMleUnitdataReq),
+
+    // SNDCP -> UMAC packet data channel control
+    PdchControl(PdchControl),
+
+    // SNDCP <-> Brew packet data routing
+    SndcpPacketData(SndcpPacketData),
+
+    // Requests to MM
+    MmControl(MmControl),
 
     // TNMM-SAP (MM-User)
     TnmmTestDemand(TnmmTestDemand),
@@ -96,7 +110,8 @@ impl Display for SapMsgInner {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             // TP-SAP
-            // SapMsgInner::TpUnitdataInd(_) => write!(f, "TpUnitdataInd"),
+            SapMsgInner::TpUnitdataInd(_) => write!(f, "TpUnitdataInd"),
+            SapMsgInner::TpUnitdataReq(_) => write!(f, "TpUnitdataReq"),
 
             // TMV-SAP
             SapMsgInner::TmvUnitdataReq(_) => write!(f, "TmvUnitdataReq"),
@@ -107,18 +122,50 @@ impl Display for SapMsgInner {
             // TMA-SAP
             SapMsgInner::TmaUnitdataInd(_) => write!(f, "TmaUnitdataInd"),
             SapMsgInner::TmaUnitdataReq(_) => write!(f, "TmaUnitdataReq"),
+            SapMsgInner::TmaReportInd(_) => write!(f, "TmaReportInd"),
 
             // TMB-SAP
             SapMsgInner::TlmbSyncInd(_) => write!(f, "TmbSyncInd"),
             SapMsgInner::TlmbSysinfoInd(_) => write!(f, "TmbSysinfoInd"),
 
+            // TMC-SAP
+            SapMsgInner::TlmcConfigureReq(_) => write!(f, "TlmcConfigureReq"),
+
+            // TMD-SAP
+            SapMsgInner::TmdCircuitDataReq(_) => write!(f, "TmdCircuitDataReq"),
+            SapMsgInner::TmdCircuitDataInd(_) => write!(f, "TmdCircuitDataInd"),
+
+            // TLA-SAP
+            SapMsgInner::TlaTlDataIndBl(_) => write!(f, "TlaTlDataIndBl"),
+            SapMsgInner::TlaTlDataReqBl(_) => write!(f, "TlaTlDataReqBl"),
+            SapMsgInner::TlaTlReportInd(_) => write!(f, "TlaTlReportInd"),
+            SapMsgInner::TlaTlUnitdataIndBl(_) => write!(f, "TlaTlUnitdataIndBl"),
+            SapMsgInner::TlaTlUnitdataReqBl(_) => write!(f, "TlaTlUnitdataReqBl"),
+
+            // LMM-SAP
+            SapMsgInner::LmmMleUnitdataInd(_) => write!(f, "LmmMleUnitdataInd"),
+            SapMsgInner::LmmMleUnitdataReq(_) => write!(f, "LmmMleUnitdataReq"),
+            SapMsgInner::LmmMleActivateConf(_) => write!(f, "LmmMleActivateConf"),
+
+            // LCMC-SAP
+            SapMsgInner::LcmcMleUnitdataInd(_) => write!(f, "LcmcMleUnitdataInd"),
+            SapMsgInner::LcmcMleUnitdataReq(_) => write!(f, "LcmcMleUnitdataReq"),
+
+            // LTPD-SAP
+            SapMsgInner::LtpdMleUnitdataInd(_) => write!(f, "LtpdMleUnitdataInd"),
+            SapMsgInner::LtpdMleUnitdataReq(_) => write!(f, "LtpdMleUnitdataReq"),
+
+            // TNMM-SAP
+            SapMsgInner::TnmmTestDemand(_) => write!(f, "TnmmTestDemand"),
+            SapMsgInner::TnmmTestResponse(_) => write!(f, "TnmmTestResponse"),
+
             // Control/Brew
+            SapMsgInner::CmceCallControl(_) => write!(f, "CmceCallControl"),
             SapMsgInner::MmSubscriberUpdate(_) => write!(f, "MmSubscriberUpdate"),
-
-            // TLB-SAP
-            // SapMsgInner::TlbTlSyncInd(_) => write!(f, "TlbTlSyncInd"),
-            // SapMsgInner::TlbTlSysinfoInd(_) => write!(f, "TlbTlSysinfoInd"),
-            _ => panic!("Unknown SapMsgInner type"),
+            SapMsgInner::CmceSdsData(_) => write!(f, "CmceSdsData"),
+            SapMsgInner::PdchControl(_) => write!(f, "PdchControl"),
+            SapMsgInner::SndcpPacketData(_) => write!(f, "SndcpPacketData"),
+            SapMsgInner::MmControl(_) => write!(f, "MmControl"),
         }
     }
 }
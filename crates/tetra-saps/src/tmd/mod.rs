@@ -2,6 +2,8 @@
 #[derive(Debug, Clone)]
 pub struct TmdCircuitDataReq {
     // call_id: CallId,
+    /// Carrier index, 0 for the main carrier
+    pub carrier: u8,
     pub ts: u8,
     pub data: Vec<u8>,
 }
@@ -10,6 +12,8 @@ pub struct TmdCircuitDataReq {
 #[derive(Debug, Clone)]
 pub struct TmdCircuitDataInd {
     // call_id: CallId,
+    /// Carrier index, 0 for the main carrier
+    pub carrier: u8,
     pub ts: u8,
     pub data: Vec<u8>,
 }
@@ -1,6 +1,6 @@
 pub mod enums;
 
-use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
 
 use crate::tmv::enums::logical_chans::LogicalChannel;
 
@@ -16,6 +16,8 @@ pub struct TmvUnitdataReq {
 pub struct TmvUnitdataReqSlot {
     /// Timeslot at which this block is to be transmitted
     pub ts: TdmaTime,
+    /// Carrier to transmit on, 0 for the main carrier. Secondary carriers only exist on a BS.
+    pub carrier: u8,
     pub ul_phy_chan: PhysicalChannel,
 
     /// First MAC block in this timeslot. May be received from LLC
@@ -46,6 +48,14 @@ pub struct TmvUnitdataInd {
     /// If no CRC is present on this message type (for example, for AACH), crc_pass is set to True
     pub crc_pass: bool,
     pub scrambling_code: u32,
+
+    /// While not in the spec, a monitoring Umac receives blocks from both directions and needs to
+    /// know which one this is, since SCH/F and STCH exist on both the downlink and the uplink.
+    pub direction: Direction,
+
+    /// While not in the spec, a BS Umac needs to know on which carrier an uplink block was received,
+    /// so that responses go out on the same carrier. 0 for the main carrier.
+    pub carrier: u8,
 }
 
 /// Clause 23.2.1
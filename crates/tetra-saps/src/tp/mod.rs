@@ -2,6 +2,8 @@ use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, TrainingSequen
 
 #[derive(Debug, Clone)]
 pub struct TpUnitdataInd {
+    /// Carrier the burst was received on, 0 for the main carrier
+    pub carrier: u8,
     pub train_type: TrainingSequence,
     pub burst_type: BurstType,
     pub block_type: PhyBlockType,
@@ -12,6 +14,8 @@ pub struct TpUnitdataInd {
 
 #[derive(Debug, Clone)]
 pub struct TpUnitdataReqSlot {
+    /// Carrier to transmit on, 0 for the main carrier
+    pub carrier: u8,
     pub train_type: TrainingSequence,
     pub burst_type: BurstType,
     pub bbk: Option<BitBuffer>,
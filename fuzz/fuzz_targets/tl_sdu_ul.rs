@@ -0,0 +1,12 @@
+#![no_main]
+
+//! Uplink TL-SDU, starting at the MLE protocol discriminator
+
+use libfuzzer_sys::fuzz_target;
+use tetra_core::{BitBuffer, Direction};
+use tetra_pdus::decode::decode_tl_sdu;
+
+fuzz_target!(|data: &[u8]| {
+    let mut sdu = BitBuffer::from_bytes(data);
+    let _ = decode_tl_sdu(&mut sdu, Direction::Ul);
+});
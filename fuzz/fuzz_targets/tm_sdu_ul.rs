@@ -0,0 +1,12 @@
+#![no_main]
+
+//! Full uplink TM-SDU: LLC PDU and the MLE, MM, CMCE or SNDCP PDU it carries
+
+use libfuzzer_sys::fuzz_target;
+use tetra_core::{BitBuffer, Direction};
+use tetra_pdus::decode::decode_tm_sdu;
+
+fuzz_target!(|data: &[u8]| {
+    let sdu = BitBuffer::from_bytes(data);
+    let _ = decode_tm_sdu(&sdu, Direction::Ul);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tetra_core::BitBuffer;
+use tetra_pdus::umac::pdus::mac_access::MacAccess;
+
+fuzz_target!(|data: &[u8]| {
+    let mut buf = BitBuffer::from_bytes(data);
+    if let Ok(pdu) = MacAccess::from_bitbuf(&mut buf) {
+        let _ = format!("{:?}", pdu);
+    }
+});